@@ -0,0 +1,180 @@
+// Copyright 2015-2017 Parity Technologies (UK) Ltd.
+// This file is part of Parity.
+
+// Parity is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// Parity is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with Parity.  If not, see <http://www.gnu.org/licenses/>.
+
+//! Benchmarks of transaction execution, state and crypto primitives.
+//! should be started with:
+//! ```bash
+//! rustup run nightly cargo bench -p ethcore --bench execution
+//! ```
+//!
+//! All fixtures are derived from fixed seeds, so runs are comparable across revisions.
+
+#![feature(test)]
+
+extern crate test;
+extern crate ethcore;
+extern crate ethcore_crypto;
+extern crate ethcore_transaction as transaction;
+extern crate ethereum_types;
+extern crate ethkey;
+extern crate rustc_hex;
+extern crate vm;
+
+use test::{Bencher, black_box};
+
+use ethcore::ethereum::new_byzantium_test_machine;
+use ethcore::executive::{Executive, TransactOptions};
+use ethcore::state::{CleanupMode, State};
+use ethcore::state_db::StateDB;
+use ethcore::test_helpers::get_temp_state;
+use ethcore_crypto::aes_gcm::{Encryptor, Decryptor};
+use ethereum_types::{Address, H256, U256};
+use ethkey::{Brain, Generator, KeyPair};
+use rustc_hex::FromHex;
+use transaction::{Action, SignedTransaction, Transaction};
+use vm::EnvInfo;
+
+/// Number of accounts used by state fixtures.
+const ACCOUNTS: usize = 1000;
+
+fn keypair(seed: usize) -> KeyPair {
+	Brain::new(format!("execution bench {}", seed)).generate().expect("brain wallet generation is infallible; qed")
+}
+
+fn address(seed: usize) -> Address {
+	Address::from(H256::from(seed as u64 + 0x1000))
+}
+
+fn env_info() -> EnvInfo {
+	let mut info = EnvInfo::default();
+	info.gas_limit = U256::from(100_000_000);
+	info
+}
+
+/// Value transfers, contract creations and storage-writing calls in 2:1:1 proportion.
+fn transaction_mix(sender: &KeyPair, contract: Address) -> Vec<SignedTransaction> {
+	// store calldata word at slot 0: PUSH1 0 CALLDATALOAD PUSH1 0 SSTORE
+	let init = "600035600055".from_hex().unwrap();
+	(0..100).map(|i| {
+		let (action, data) = match i % 4 {
+			0 | 1 => (Action::Call(address(i)), vec![]),
+			2 => (Action::Create, init.clone()),
+			_ => (Action::Call(contract), H256::from(i as u64).to_vec()),
+		};
+		Transaction {
+			nonce: U256::from(i),
+			gas_price: U256::one(),
+			gas: U256::from(100_000),
+			action: action,
+			value: U256::from(1),
+			data: data,
+		}.sign(sender.secret(), None)
+	}).collect()
+}
+
+#[bench]
+fn transact_mix(b: &mut Bencher) {
+	let machine = new_byzantium_test_machine();
+	let info = env_info();
+	let sender = keypair(0);
+	let contract = address(ACCOUNTS + 1);
+	let transactions = transaction_mix(&sender, contract);
+
+	let mut state = get_temp_state();
+	state.add_balance(&sender.address(), &U256::from(1_000_000_000_000u64), CleanupMode::NoEmpty).unwrap();
+	state.init_code(&contract, "600035600055".from_hex().unwrap()).unwrap();
+	state.commit().unwrap();
+
+	b.iter(|| {
+		state.checkpoint();
+		for t in &transactions {
+			let mut ex = Executive::new(&mut state, &info, &machine);
+			black_box(ex.transact(t, TransactOptions::with_no_tracing()).unwrap());
+		}
+		state.revert_to_checkpoint();
+	});
+}
+
+fn populated_state() -> (State<StateDB>, H256) {
+	let mut state = get_temp_state();
+	for i in 0..ACCOUNTS {
+		state.add_balance(&address(i), &U256::from(i + 1), CleanupMode::NoEmpty).unwrap();
+		state.set_storage(&address(i), H256::from(i as u64), H256::from(i as u64 + 1)).unwrap();
+	}
+	state.commit().unwrap();
+	let root = *state.root();
+	(state, root)
+}
+
+#[bench]
+fn state_commit_1k_accounts(b: &mut Bencher) {
+	b.iter(|| {
+		let mut state = get_temp_state();
+		for i in 0..ACCOUNTS {
+			state.add_balance(&address(i), &U256::from(i + 1), CleanupMode::NoEmpty).unwrap();
+		}
+		state.commit().unwrap();
+		black_box(*state.root())
+	});
+}
+
+#[bench]
+fn state_reads_cached(b: &mut Bencher) {
+	let (state, _) = populated_state();
+	b.iter(|| {
+		for i in 0..ACCOUNTS {
+			black_box(state.balance(&address(i)).unwrap());
+			black_box(state.storage_at(&address(i), &H256::from(i as u64)).unwrap());
+		}
+	});
+}
+
+#[bench]
+fn state_reads_uncached(b: &mut Bencher) {
+	let (state, root) = populated_state();
+	let db = state.drop().1;
+	b.iter(|| {
+		let state = State::from_existing(db.boxed_clone(), root, U256::zero(), Default::default()).unwrap();
+		for i in 0..ACCOUNTS {
+			black_box(state.balance(&address(i)).unwrap());
+			black_box(state.storage_at(&address(i), &H256::from(i as u64)).unwrap());
+		}
+	});
+}
+
+const AES_PAYLOAD: usize = 64 * 1024;
+
+#[bench]
+fn aes_256_gcm_encrypt_64k(b: &mut Bencher) {
+	let key = [0x42u8; 32];
+	let nonce = [0x07u8; 12];
+	let data = vec![0xaau8; AES_PAYLOAD];
+	b.bytes = AES_PAYLOAD as u64;
+	b.iter(|| {
+		black_box(Encryptor::aes_256_gcm(&key).unwrap().encrypt(&nonce, data.clone()).unwrap())
+	});
+}
+
+#[bench]
+fn aes_256_gcm_decrypt_64k(b: &mut Bencher) {
+	let key = [0x42u8; 32];
+	let nonce = [0x07u8; 12];
+	let sealed = Encryptor::aes_256_gcm(&key).unwrap().encrypt(&nonce, vec![0xaau8; AES_PAYLOAD]).unwrap();
+	b.bytes = AES_PAYLOAD as u64;
+	b.iter(|| {
+		black_box(Decryptor::aes_256_gcm(&key).unwrap().decrypt(&nonce, sealed.clone()).unwrap())
+	});
+}
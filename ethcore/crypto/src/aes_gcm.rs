@@ -19,6 +19,89 @@ use ring;
 
 enum Mode { Aes128Gcm, Aes256Gcm }
 
+impl Mode {
+	fn name(&self) -> &'static str {
+		match *self {
+			Mode::Aes128Gcm => "AES-128-GCM",
+			Mode::Aes256Gcm => "AES-256-GCM",
+		}
+	}
+}
+
+/// 96-bit AEAD nonce.
+///
+/// Intentionally neither `Clone` nor `Copy`: `Encryptor::seal` consumes it, so a nonce
+/// built once can't accidentally be used for two messages.
+#[derive(Debug, PartialEq)]
+pub struct Nonce([u8; 12]);
+
+impl Nonce {
+	/// Explicitly wraps raw nonce bytes. The caller is responsible for uniqueness.
+	pub fn from_raw(raw: [u8; 12]) -> Self {
+		Nonce(raw)
+	}
+
+	/// Builds nonce as a 32-bit salt followed by a big-endian 64-bit sequence number (RFC 5288).
+	pub fn from_salt_and_sequence(salt: &[u8; 4], sequence: u64) -> Self {
+		let mut nonce = [0u8; 12];
+		nonce[..4].copy_from_slice(salt);
+		nonce[4..].copy_from_slice(&sequence_bytes(sequence));
+		Nonce(nonce)
+	}
+
+	/// Builds nonce by XOR-ing a 96-bit IV with a big-endian, left-padded 64-bit sequence number (RFC 8446).
+	pub fn from_iv_and_sequence(iv: &[u8; 12], sequence: u64) -> Self {
+		let mut nonce = *iv;
+		for (n, s) in nonce[4..].iter_mut().zip(sequence_bytes(sequence).iter()) {
+			*n ^= *s;
+		}
+		Nonce(nonce)
+	}
+
+	/// Nonce bytes.
+	pub fn as_bytes(&self) -> &[u8; 12] {
+		&self.0
+	}
+}
+
+fn sequence_bytes(sequence: u64) -> [u8; 8] {
+	let mut bytes = [0u8; 8];
+	for (i, b) in bytes.iter_mut().enumerate() {
+		*b = (sequence >> (8 * (7 - i))) as u8;
+	}
+	bytes
+}
+
+enum NonceBase { Salt([u8; 4]), Iv([u8; 12]) }
+
+/// Generator of unique nonces for a single key, as used by TLS-style record framing.
+pub struct NonceSequence {
+	base: NonceBase,
+	next: Option<u64>,
+}
+
+impl NonceSequence {
+	/// Sequence of `salt || sequence` nonces starting at 0.
+	pub fn salted(salt: [u8; 4]) -> Self {
+		NonceSequence { base: NonceBase::Salt(salt), next: Some(0) }
+	}
+
+	/// Sequence of `iv ^ sequence` nonces starting at 0.
+	pub fn xored(iv: [u8; 12]) -> Self {
+		NonceSequence { base: NonceBase::Iv(iv), next: Some(0) }
+	}
+
+	/// Returns the next nonce. Fails once the 64-bit sequence number space is used up.
+	pub fn next(&mut self) -> Result<Nonce, SymmError> {
+		let sequence = self.next.ok_or_else(SymmError::nonce_exhausted)?;
+		self.next = sequence.checked_add(1);
+		Ok(match self.base {
+			NonceBase::Salt(ref salt) => Nonce::from_salt_and_sequence(salt, sequence),
+			NonceBase::Iv(ref iv) => Nonce::from_iv_and_sequence(iv, sequence),
+		})
+	}
+}
+
 /// AES GCM encryptor.
 pub struct Encryptor<'a> {
 	mode: Mode,
@@ -65,22 +148,30 @@ impl<'a> Encryptor<'a> {
 	///
 	/// [1]: https://nvlpubs.nist.gov/nistpubs/Legacy/SP/nistspecialpublication800-38d.pdf
 	pub fn encrypt(&self, nonce: &[u8; 12], mut data: Vec<u8>) -> Result<Vec<u8>, SymmError> {
-		if self.offset > data.len() {
-			return Err(SymmError::offset_error(self.offset))
+		let (len, offset) = (data.len(), self.offset);
+		if offset > len {
+			return Err(SymmError::invalid_offset("encrypt", self.mode.name(), len, offset))
 		}
 		let tag_len = match self.mode {
 			Mode::Aes128Gcm => ring::aead::AES_128_GCM.tag_len(),
 			Mode::Aes256Gcm => ring::aead::AES_256_GCM.tag_len(),
 		};
 		data.extend(::std::iter::repeat(0).take(tag_len));
-		let len = ring::aead::seal_in_place(&self.key, nonce, self.ad, &mut data[self.offset ..], tag_len)?;
-		data.truncate(self.offset + len);
+		let sealed = ring::aead::seal_in_place(&self.key, nonce, self.ad, &mut data[offset ..], tag_len)
+			.map_err(|_| SymmError::aead("encrypt", self.mode.name(), len, offset))?;
+		data.truncate(offset + sealed);
 		Ok(data)
 	}
+
+	/// Same as `encrypt`, but consumes a typed `Nonce`.
+	pub fn seal(&self, nonce: Nonce, data: Vec<u8>) -> Result<Vec<u8>, SymmError> {
+		self.encrypt(nonce.as_bytes(), data)
+	}
 }
 
 /// AES GCM decryptor.
 pub struct Decryptor<'a> {
+	mode: Mode,
 	key: ring::aead::OpeningKey,
 	ad: &'a [u8],
 	offset: usize,
@@ -90,6 +181,7 @@ impl<'a> Decryptor<'a> {
 	pub fn aes_128_gcm(key: &[u8; 16]) -> Result<Decryptor<'a>, SymmError> {
 		let ok = ring::aead::OpeningKey::new(&ring::aead::AES_128_GCM, key)?;
 		Ok(Decryptor {
+			mode: Mode::Aes128Gcm,
 			key: ok,
 			ad: &[],
 			offset: 0,
@@ -99,6 +191,7 @@ impl<'a> Decryptor<'a> {
 	pub fn aes_256_gcm(key: &[u8; 32]) -> Result<Decryptor<'a>, SymmError> {
 		let ok = ring::aead::OpeningKey::new(&ring::aead::AES_256_GCM, key)?;
 		Ok(Decryptor {
+			mode: Mode::Aes256Gcm,
 			key: ok,
 			ad: &[],
 			offset: 0,
@@ -118,18 +211,26 @@ impl<'a> Decryptor<'a> {
 	}
 
 	pub fn decrypt(&self, nonce: &[u8; 12], mut data: Vec<u8>) -> Result<Vec<u8>, SymmError> {
-		if self.offset > data.len() {
-			return Err(SymmError::offset_error(self.offset))
+		let (len, offset) = (data.len(), self.offset);
+		if offset > len {
+			return Err(SymmError::invalid_offset("decrypt", self.mode.name(), len, offset))
 		}
-		let len = ring::aead::open_in_place(&self.key, nonce, self.ad, 0, &mut data[self.offset ..])?.len();
-		data.truncate(self.offset + len);
+		let opened = ring::aead::open_in_place(&self.key, nonce, self.ad, 0, &mut data[offset ..])
+			.map_err(|_| SymmError::aead("decrypt", self.mode.name(), len, offset))?
+			.len();
+		data.truncate(offset + opened);
 		Ok(data)
 	}
+
+	/// Same as `decrypt`, but takes a typed `Nonce`.
+	pub fn open(&self, nonce: Nonce, data: Vec<u8>) -> Result<Vec<u8>, SymmError> {
+		self.decrypt(nonce.as_bytes(), data)
+	}
 }
 
 #[cfg(test)]
 mod tests {
-	use super::{Encryptor, Decryptor};
+	use super::{Encryptor, Decryptor, Nonce, NonceSequence};
 
 	#[test]
 	fn aes_gcm_128() {
@@ -195,5 +296,71 @@ mod tests {
 
 		assert_eq!(plaintext, &message[..])
 	}
-}
 
+	#[test]
+	fn aes_gcm_offset_past_data_fails() {
+		let secret = b"12345678901234567890123456789012";
+		let nonce = b"123456789012";
+
+		let err = Encryptor::aes_256_gcm(secret)
+			.unwrap()
+			.offset(5)
+			.encrypt(nonce, vec![0; 4])
+			.unwrap_err();
+		assert!(err.is_invalid_offset());
+		assert_eq!(err.to_string(), "encrypt with AES-256-GCM: offset 5 greater than data length 4");
+
+		let err = Decryptor::aes_128_gcm(b"1234567890123456")
+			.unwrap()
+			.offset(5)
+			.decrypt(nonce, vec![0; 4])
+			.unwrap_err();
+		assert!(err.is_invalid_offset());
+	}
+
+	#[test]
+	fn aes_gcm_decrypt_failure_has_context() {
+		let err = Decryptor::aes_128_gcm(b"1234567890123456")
+			.unwrap()
+			.decrypt(b"123456789012", vec![0; 20])
+			.unwrap_err();
+		assert!(!err.is_invalid_offset());
+		assert_eq!(err.to_string(), "decrypt with AES-128-GCM failed (data length 20, offset 0)");
+	}
+
+	#[test]
+	fn nonce_from_salt_and_sequence() {
+		let nonce = Nonce::from_salt_and_sequence(&[1, 2, 3, 4], 0x0102);
+		assert_eq!(nonce.as_bytes(), &[1, 2, 3, 4, 0, 0, 0, 0, 0, 0, 1, 2]);
+	}
+
+	#[test]
+	fn nonce_from_iv_and_sequence() {
+		let iv = [0xff; 12];
+		let nonce = Nonce::from_iv_and_sequence(&iv, 0x0102);
+		assert_eq!(nonce.as_bytes(), &[0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xfe, 0xfd]);
+	}
+
+	#[test]
+	fn nonce_sequence_is_unique_and_seals() {
+		let secret = b"12345678901234567890123456789012";
+		let message = b"So many books, so little time";
+		let mut sequence = NonceSequence::salted([9, 9, 9, 9]);
+
+		let first = sequence.next().unwrap();
+		let second = sequence.next().unwrap();
+		assert!(first != second);
+
+		let ciphertext = Encryptor::aes_256_gcm(secret)
+			.unwrap()
+			.seal(first, message.to_vec())
+			.unwrap();
+
+		let plaintext = Decryptor::aes_256_gcm(secret)
+			.unwrap()
+			.open(Nonce::from_salt_and_sequence(&[9, 9, 9, 9], 0), ciphertext)
+			.unwrap();
+
+		assert_eq!(plaintext, message)
+	}
+}
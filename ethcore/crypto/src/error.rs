@@ -57,15 +57,37 @@ quick_error! {
 			cause(e)
 			from()
 		}
-		Offset(x: usize) {
-			display("offset {} greater than slice length", x)
+		InvalidOffset(operation: &'static str, algorithm: &'static str, len: usize, offset: usize) {
+			display("{} with {}: offset {} greater than data length {}", operation, algorithm, offset, len)
+		}
+		Aead(operation: &'static str, algorithm: &'static str, len: usize, offset: usize) {
+			display("{} with {} failed (data length {}, offset {})", operation, algorithm, len, offset)
+		}
+		NonceExhausted {
+			display("nonce sequence exhausted")
 		}
 	}
 }
 
 impl SymmError {
-	pub(crate) fn offset_error(x: usize) -> SymmError {
-		SymmError(PrivSymmErr::Offset(x))
+	pub(crate) fn invalid_offset(operation: &'static str, algorithm: &'static str, len: usize, offset: usize) -> SymmError {
+		SymmError(PrivSymmErr::InvalidOffset(operation, algorithm, len, offset))
+	}
+
+	pub(crate) fn aead(operation: &'static str, algorithm: &'static str, len: usize, offset: usize) -> SymmError {
+		SymmError(PrivSymmErr::Aead(operation, algorithm, len, offset))
+	}
+
+	/// Whether the error was caused by an offset past the end of the data.
+	pub fn is_invalid_offset(&self) -> bool {
+		match self.0 {
+			PrivSymmErr::InvalidOffset(..) => true,
+			_ => false,
+		}
+	}
+
+	pub(crate) fn nonce_exhausted() -> SymmError {
+		SymmError(PrivSymmErr::NonceExhausted)
 	}
 }
 
@@ -29,6 +29,7 @@ pub mod error;
 pub mod scrypt;
 pub mod digest;
 pub mod hmac;
+pub mod passwd;
 pub mod pbkdf2;
 
 pub use error::Error;
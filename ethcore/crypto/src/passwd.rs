@@ -0,0 +1,95 @@
+// Copyright 2018 Parity Technologies (UK) Ltd.
+// This file is part of Parity.
+
+// Parity is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// Parity is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with Parity.  If not, see <http://www.gnu.org/licenses/>.
+
+//! Work factor of password-based key derivation, calibrated to the machine.
+//!
+//! Keys are PBKDF2-SHA256 derivations. The number of iterations is measured once so that
+//! a derivation takes about a target duration, and persisted for the following runs.
+
+use std::cmp;
+use std::fs::File;
+use std::io::{self, Read, Write};
+use std::path::Path;
+use std::time::{Duration, Instant};
+
+use {pbkdf2, KEY_ITERATIONS, KEY_LENGTH};
+
+/// Lowest number of iterations, whatever the calibration says. Matches the former fixed count.
+pub const MIN_ITERATIONS: u32 = KEY_ITERATIONS as u32;
+/// Highest number of iterations, whatever the calibration says.
+pub const MAX_ITERATIONS: u32 = 1 << 24;
+/// Default duration of a single derivation, in milliseconds.
+pub const DEFAULT_TARGET_MS: u64 = 250;
+
+// probes shorter than this are dominated by noise.
+const MIN_PROBE_MS: u64 = 20;
+
+fn as_nanos(d: Duration) -> u64 {
+	d.as_secs() * 1_000_000_000 + d.subsec_nanos() as u64
+}
+
+/// Number of iterations for a derivation to take about `target` on this machine.
+pub fn calibrate(target: Duration) -> u32 {
+	let salt = [0u8; 32];
+	let mut out = [0u8; KEY_LENGTH];
+	let mut probe = MIN_ITERATIONS;
+	let elapsed = loop {
+		let start = Instant::now();
+		pbkdf2::sha256(probe, pbkdf2::Salt(&salt), pbkdf2::Secret(b"calibration"), &mut out);
+		let elapsed = start.elapsed();
+		if elapsed >= Duration::from_millis(MIN_PROBE_MS) || probe >= MAX_ITERATIONS {
+			break elapsed;
+		}
+		probe *= 2;
+	};
+
+	let iterations = probe as u64 * as_nanos(target) / cmp::max(as_nanos(elapsed), 1);
+	cmp::min(cmp::max(iterations, MIN_ITERATIONS as u64), MAX_ITERATIONS as u64) as u32
+}
+
+/// Number of iterations persisted in the file at `path`.
+/// If there is no such file, calibrates to `target` and creates it.
+pub fn persisted_iterations(path: &Path, target: Duration) -> io::Result<u32> {
+	match File::open(path) {
+		Ok(mut file) => {
+			let mut content = String::new();
+			file.read_to_string(&mut content)?;
+			content.trim().parse::<u32>()
+				.map(|i| cmp::max(i, MIN_ITERATIONS))
+				.map_err(|e| io::Error::new(io::ErrorKind::InvalidData, e))
+		},
+		Err(ref e) if e.kind() == io::ErrorKind::NotFound => {
+			let iterations = calibrate(target);
+			let mut file = File::create(path)?;
+			writeln!(file, "{}", iterations)?;
+			Ok(iterations)
+		},
+		Err(e) => Err(e),
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use std::time::Duration;
+	use super::{calibrate, MIN_ITERATIONS, MAX_ITERATIONS};
+
+	#[test]
+	fn calibrates_within_bounds() {
+		assert_eq!(calibrate(Duration::from_millis(0)), MIN_ITERATIONS);
+		let iterations = calibrate(Duration::from_millis(50));
+		assert!(iterations >= MIN_ITERATIONS && iterations <= MAX_ITERATIONS);
+	}
+}
@@ -17,19 +17,21 @@
 //! benchmarking for EVM
 //! should be started with:
 //! ```bash
-//! multirust run nightly cargo bench
+//! multirust run nightly cargo bench --features benches
 //! ```
 
 extern crate test;
 
 use self::test::{Bencher, black_box};
 
-use bigint::prelude::U256;
-use bigint::hash::H256;
-use util::*;
-use vm::ActionParams;
-use evm::{self, Factory, VMType};
-use evm::tests::FakeExt;
+use std::str::FromStr;
+use std::sync::Arc;
+use ethereum_types::{U256, Address};
+use rustc_hex::FromHex;
+use vm::{ActionParams, GasLeft, Result};
+use vm::tests::FakeExt;
+use factory::Factory;
+use vmtype::VMType;
 
 #[bench]
 fn simple_loop_log0_usize(b: &mut Bencher) {
@@ -42,7 +44,6 @@ fn simple_loop_log0_u256(b: &mut Bencher) {
 }
 
 fn simple_loop_log0(gas: U256, b: &mut Bencher) {
-	let mut vm = Factory::new(VMType::Interpreter).create(gas);
 	let mut ext = FakeExt::new();
 
 	let address = Address::from_str("0f572e5295c57f15886f9b263e2f6d2d6c7b5ec6").unwrap();
@@ -54,9 +55,9 @@ fn simple_loop_log0(gas: U256, b: &mut Bencher) {
 		let mut params = ActionParams::default();
 		params.address = address.clone();
 		params.gas = gas;
-		params.code = Some(code.clone());
+		params.code = Some(Arc::new(code.clone()));
 
-		result(vm.exec(params, &mut ext))
+		result(Factory::new(VMType::Interpreter, 1).create(&gas).exec(params, &mut ext))
 	});
 }
 
@@ -71,7 +72,6 @@ fn mem_gas_calculation_same_u256(b: &mut Bencher) {
 }
 
 fn mem_gas_calculation_same(gas: U256, b: &mut Bencher) {
-	let mut vm = Factory::new(VMType::Interpreter).create(gas);
 	let mut ext = FakeExt::new();
 
 	let address = Address::from_str("0f572e5295c57f15886f9b263e2f6d2d6c7b5ec6").unwrap();
@@ -84,9 +84,9 @@ fn mem_gas_calculation_same(gas: U256, b: &mut Bencher) {
 		let mut params = ActionParams::default();
 		params.address = address.clone();
 		params.gas = gas;
-		params.code = Some(code.clone());
+		params.code = Some(Arc::new(code.clone()));
 
-		result(vm.exec(params, &mut ext))
+		result(Factory::new(VMType::Interpreter, 1).create(&gas).exec(params, &mut ext))
 	});
 }
 
@@ -101,7 +101,6 @@ fn mem_gas_calculation_increasing_u256(b: &mut Bencher) {
 }
 
 fn mem_gas_calculation_increasing(gas: U256, b: &mut Bencher) {
-	let mut vm = Factory::new(VMType::Interpreter).create(gas);
 	let mut ext = FakeExt::new();
 
 	let address = Address::from_str("0f572e5295c57f15886f9b263e2f6d2d6c7b5ec6").unwrap();
@@ -114,16 +113,49 @@ fn mem_gas_calculation_increasing(gas: U256, b: &mut Bencher) {
 		let mut params = ActionParams::default();
 		params.address = address.clone();
 		params.gas = gas;
-		params.code = Some(code.clone());
+		params.code = Some(Arc::new(code.clone()));
 
-		result(vm.exec(params, &mut ext))
+		result(Factory::new(VMType::Interpreter, 1).create(&gas).exec(params, &mut ext))
 	});
 }
 
-fn result(r: evm::Result<evm::GasLeft>) -> U256 {
+#[bench]
+fn arithmetic_small_operands(b: &mut Bencher) {
+	// PUSH1 7
+	arithmetic_loop("6007", b)
+}
+
+#[bench]
+fn arithmetic_large_operands(b: &mut Bencher) {
+	// PUSH32 0xff..ff
+	arithmetic_loop("7fffffffffffffffffffffffffffffffffffffffffffffffffffffffffffffffff", b)
+}
+
+/// Loops 1023 times over ADD, MUL, DIV, MOD, LT and GT of the loop counter and `operand`.
+fn arithmetic_loop(operand: &str, b: &mut Bencher) {
+	let gas = U256::from(100_000_000);
+	let mut ext = FakeExt::new();
+
+	let mut code = "620003ff5b6001900380".to_owned();
+	for op in &["01", "02", "04", "06", "10", "11"] {
+		code.push_str(&format!("{}{}5080", operand, op));
+	}
+	code.push_str("600457");
+	let code = black_box(code.from_hex().unwrap());
+
+	b.iter(|| {
+		let mut params = ActionParams::default();
+		params.gas = gas;
+		params.code = Some(Arc::new(code.clone()));
+
+		result(Factory::new(VMType::Interpreter, 1).create(&gas).exec(params, &mut ext))
+	});
+}
+
+fn result(r: Result<GasLeft>) -> U256 {
 	match r {
-		Ok(evm::GasLeft::Known(v)) => v,
-		Ok(evm::GasLeft::NeedsReturn(v, _)) => v,
+		Ok(GasLeft::Known(v)) => v,
+		Ok(GasLeft::NeedsReturn { gas_left: v, .. }) => v,
 		_ => U256::zero(),
 	}
 }
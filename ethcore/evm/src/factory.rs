@@ -27,6 +27,7 @@ use super::vmtype::VMType;
 pub struct Factory {
 	evm: VMType,
 	evm_cache: Arc<SharedCache>,
+	memory_limit: usize,
 }
 
 impl Factory {
@@ -35,9 +36,9 @@ impl Factory {
 	pub fn create(&self, gas: &U256) -> Box<Vm> {
 		match self.evm {
 			VMType::Interpreter => if Self::can_fit_in_usize(gas) {
-				Box::new(super::interpreter::Interpreter::<usize>::new(self.evm_cache.clone()))
+				Box::new(super::interpreter::Interpreter::<usize>::new(self.evm_cache.clone()).with_memory_limit(self.memory_limit))
 			} else {
-				Box::new(super::interpreter::Interpreter::<U256>::new(self.evm_cache.clone()))
+				Box::new(super::interpreter::Interpreter::<U256>::new(self.evm_cache.clone()).with_memory_limit(self.memory_limit))
 			}
 		}
 	}
@@ -48,9 +49,22 @@ impl Factory {
 		Factory {
 			evm: evm,
 			evm_cache: Arc::new(SharedCache::new(cache_size)),
+			memory_limit: usize::max_value(),
 		}
 	}
 
+	/// Limit memory, in bytes, a single call frame may expand to. Unlimited by default.
+	/// Execution expanding memory past it fails with `OutOfMemory`.
+	pub fn with_memory_limit(mut self, limit: usize) -> Self {
+		self.memory_limit = limit;
+		self
+	}
+
+	/// Jump destinations cache shared by created interpreters.
+	pub fn cache(&self) -> &SharedCache {
+		&self.evm_cache
+	}
+
 	fn can_fit_in_usize(gas: &U256) -> bool {
 		gas == &U256::from(gas.low_u64() as usize)
 	}
@@ -62,6 +76,7 @@ impl Default for Factory {
 		Factory {
 			evm: VMType::Interpreter,
 			evm_cache: Arc::new(SharedCache::default()),
+			memory_limit: usize::max_value(),
 		}
 	}
 }
@@ -0,0 +1,480 @@
+// Copyright 2015-2017 Parity Technologies (UK) Ltd.
+// This file is part of Parity.
+
+// Parity is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// Parity is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with Parity.  If not, see <http://www.gnu.org/licenses/>.
+
+//! Precomputed opcode dispatch table.
+//!
+//! Instructions which only operate on the stack are executed through a function pointer
+//! looked up by opcode, bypassing the general instruction match.
+
+use ethereum_types::{U256, U512};
+use instructions::{self, Instruction, InstructionInfo, INSTRUCTIONS};
+use super::stack::Stack;
+
+const ONE: U256 = U256([1, 0, 0, 0]);
+const TWO: U256 = U256([2, 0, 0, 0]);
+const TWO_POW_5: U256 = U256([0x20, 0, 0, 0]);
+const TWO_POW_8: U256 = U256([0x100, 0, 0, 0]);
+const TWO_POW_16: U256 = U256([0x10000, 0, 0, 0]);
+const TWO_POW_24: U256 = U256([0x1000000, 0, 0, 0]);
+const TWO_POW_64: U256 = U256([0, 0x1, 0, 0]); // 0x1 00000000 00000000
+const TWO_POW_96: U256 = U256([0, 0x100000000, 0, 0]); //0x1 00000000 00000000 00000000
+const TWO_POW_224: U256 = U256([0, 0, 0, 0x100000000]); //0x1 00000000 00000000 00000000 00000000 00000000 00000000 00000000
+const TWO_POW_248: U256 = U256([0, 0, 0, 0x100000000000000]); //0x1 00000000 00000000 00000000 00000000 00000000 00000000 00000000 000000
+const CONST_256: U256 = U256([256, 0, 0, 0]);
+const CONST_HIBIT: U256 = U256([0, 0, 0, 0x8000000000000000]);
+
+/// Executes a stack-only instruction.
+pub type StackHandler = fn(Instruction, &mut Stack<U256>);
+
+/// Dispatch table entry.
+#[derive(Copy, Clone)]
+pub struct Op {
+	/// Static instruction information.
+	pub info: InstructionInfo,
+	/// Index of the instruction's gas tier in the schedule.
+	pub tier_idx: usize,
+	/// Handler of stack-only instructions, `None` for instructions requiring the interpreter.
+	pub handler: Option<StackHandler>,
+}
+
+lazy_static! {
+	/// Dispatch table indexed by opcode.
+	pub static ref OPS: [Op; 0x100] = {
+		let mut arr = [Op { info: InstructionInfo::default(), tier_idx: 0, handler: None }; 0x100];
+		for (op, info) in arr.iter_mut().zip(INSTRUCTIONS.iter()) {
+			op.info = *info;
+			op.tier_idx = instructions::get_tier_idx(info.tier);
+		}
+
+		{
+			let mut set = |instruction: Instruction, handler: StackHandler| arr[instruction as usize].handler = Some(handler);
+			for instruction in instructions::DUP1..(instructions::DUP16 + 1) {
+				set(instruction, dup);
+			}
+			for instruction in instructions::SWAP1..(instructions::SWAP16 + 1) {
+				set(instruction, swap);
+			}
+			set(instructions::POP, pop);
+			set(instructions::ADD, add);
+			set(instructions::MUL, mul);
+			set(instructions::SUB, sub);
+			set(instructions::DIV, div);
+			set(instructions::MOD, modulo);
+			set(instructions::SDIV, sdiv);
+			set(instructions::SMOD, smod);
+			set(instructions::EXP, exp);
+			set(instructions::NOT, not);
+			set(instructions::LT, lt);
+			set(instructions::SLT, slt);
+			set(instructions::GT, gt);
+			set(instructions::SGT, sgt);
+			set(instructions::EQ, eq);
+			set(instructions::ISZERO, iszero);
+			set(instructions::AND, and);
+			set(instructions::OR, or);
+			set(instructions::XOR, xor);
+			set(instructions::BYTE, byte);
+			set(instructions::ADDMOD, addmod);
+			set(instructions::MULMOD, mulmod);
+			set(instructions::SIGNEXTEND, signextend);
+			set(instructions::SHL, shl);
+			set(instructions::SHR, shr);
+			set(instructions::SAR, sar);
+		}
+		arr
+	};
+}
+
+/// Returns the value if it fits in a single limb; most stack values in practice do,
+/// and native arithmetic on them is much cheaper than the full 4-limb routines.
+#[inline]
+fn small(value: &U256) -> Option<u64> {
+	let U256(ref limbs) = *value;
+	if limbs[1] == 0 && limbs[2] == 0 && limbs[3] == 0 {
+		Some(limbs[0])
+	} else {
+		None
+	}
+}
+
+fn bool_to_u256(val: bool) -> U256 {
+	if val {
+		U256::one()
+	} else {
+		U256::zero()
+	}
+}
+
+fn get_and_reset_sign(value: U256) -> (U256, bool) {
+	let U256(arr) = value;
+	let sign = arr[3].leading_zeros() == 0;
+	(set_sign(value, sign), sign)
+}
+
+fn set_sign(value: U256, sign: bool) -> U256 {
+	if sign {
+		(!U256::zero() ^ value).overflowing_add(U256::one()).0
+	} else {
+		value
+	}
+}
+
+fn dup(instruction: Instruction, stack: &mut Stack<U256>) {
+	let position = instructions::get_dup_position(instruction);
+	let val = stack.peek(position).clone();
+	stack.push(val);
+}
+
+fn swap(instruction: Instruction, stack: &mut Stack<U256>) {
+	let position = instructions::get_swap_position(instruction);
+	stack.swap_with_top(position)
+}
+
+fn pop(_: Instruction, stack: &mut Stack<U256>) {
+	stack.pop_back();
+}
+
+fn add(_: Instruction, stack: &mut Stack<U256>) {
+	let a = stack.pop_back();
+	let b = stack.pop_back();
+	stack.push(match (small(&a), small(&b)) {
+		(Some(x), Some(y)) => {
+			let (sum, carry) = x.overflowing_add(y);
+			U256([sum, carry as u64, 0, 0])
+		},
+		_ => a.overflowing_add(b).0,
+	});
+}
+
+fn mul(_: Instruction, stack: &mut Stack<U256>) {
+	let a = stack.pop_back();
+	let b = stack.pop_back();
+	let product = match (small(&a), small(&b)) {
+		(Some(x), Some(y)) => x.checked_mul(y).map(U256::from),
+		_ => None,
+	};
+	stack.push(product.unwrap_or_else(|| a.overflowing_mul(b).0));
+}
+
+fn sub(_: Instruction, stack: &mut Stack<U256>) {
+	let a = stack.pop_back();
+	let b = stack.pop_back();
+	stack.push(a.overflowing_sub(b).0);
+}
+
+fn div(_: Instruction, stack: &mut Stack<U256>) {
+	let a = stack.pop_back();
+	let b = stack.pop_back();
+	stack.push(if b.is_zero() {
+		U256::zero()
+	} else if let (Some(x), Some(y)) = (small(&a), small(&b)) {
+		U256::from(x / y)
+	} else {
+		match b {
+			ONE => a,
+			TWO => a >> 1,
+			TWO_POW_5 => a >> 5,
+			TWO_POW_8 => a >> 8,
+			TWO_POW_16 => a >> 16,
+			TWO_POW_24 => a >> 24,
+			TWO_POW_64 => a >> 64,
+			TWO_POW_96 => a >> 96,
+			TWO_POW_224 => a >> 224,
+			TWO_POW_248 => a >> 248,
+			_ => a.overflowing_div(b).0,
+		}
+	});
+}
+
+fn modulo(_: Instruction, stack: &mut Stack<U256>) {
+	let a = stack.pop_back();
+	let b = stack.pop_back();
+	stack.push(if b.is_zero() {
+		U256::zero()
+	} else if let (Some(x), Some(y)) = (small(&a), small(&b)) {
+		U256::from(x % y)
+	} else {
+		a.overflowing_rem(b).0
+	});
+}
+
+fn sdiv(_: Instruction, stack: &mut Stack<U256>) {
+	let (a, sign_a) = get_and_reset_sign(stack.pop_back());
+	let (b, sign_b) = get_and_reset_sign(stack.pop_back());
+
+	// -2^255
+	let min = (U256::one() << 255) - U256::one();
+	stack.push(if b.is_zero() {
+		U256::zero()
+	} else if a == min && b == !U256::zero() {
+		min
+	} else {
+		let c = a.overflowing_div(b).0;
+		set_sign(c, sign_a ^ sign_b)
+	});
+}
+
+fn smod(_: Instruction, stack: &mut Stack<U256>) {
+	let ua = stack.pop_back();
+	let ub = stack.pop_back();
+	let (a, sign_a) = get_and_reset_sign(ua);
+	let b = get_and_reset_sign(ub).0;
+
+	stack.push(if !b.is_zero() {
+		let c = a.overflowing_rem(b).0;
+		set_sign(c, sign_a)
+	} else {
+		U256::zero()
+	});
+}
+
+fn exp(_: Instruction, stack: &mut Stack<U256>) {
+	let base = stack.pop_back();
+	let expon = stack.pop_back();
+	let res = base.overflowing_pow(expon).0;
+	stack.push(res);
+}
+
+fn not(_: Instruction, stack: &mut Stack<U256>) {
+	let a = stack.pop_back();
+	stack.push(!a);
+}
+
+fn lt(_: Instruction, stack: &mut Stack<U256>) {
+	let a = stack.pop_back();
+	let b = stack.pop_back();
+	stack.push(bool_to_u256(match (small(&a), small(&b)) {
+		(Some(x), Some(y)) => x < y,
+		_ => a < b,
+	}));
+}
+
+fn slt(_: Instruction, stack: &mut Stack<U256>) {
+	let (a, neg_a) = get_and_reset_sign(stack.pop_back());
+	let (b, neg_b) = get_and_reset_sign(stack.pop_back());
+
+	let is_positive_lt = a < b && !(neg_a | neg_b);
+	let is_negative_lt = a > b && (neg_a & neg_b);
+	let has_different_signs = neg_a && !neg_b;
+
+	stack.push(bool_to_u256(is_positive_lt | is_negative_lt | has_different_signs));
+}
+
+fn gt(_: Instruction, stack: &mut Stack<U256>) {
+	let a = stack.pop_back();
+	let b = stack.pop_back();
+	stack.push(bool_to_u256(match (small(&a), small(&b)) {
+		(Some(x), Some(y)) => x > y,
+		_ => a > b,
+	}));
+}
+
+fn sgt(_: Instruction, stack: &mut Stack<U256>) {
+	let (a, neg_a) = get_and_reset_sign(stack.pop_back());
+	let (b, neg_b) = get_and_reset_sign(stack.pop_back());
+
+	let is_positive_gt = a > b && !(neg_a | neg_b);
+	let is_negative_gt = a < b && (neg_a & neg_b);
+	let has_different_signs = !neg_a && neg_b;
+
+	stack.push(bool_to_u256(is_positive_gt | is_negative_gt | has_different_signs));
+}
+
+fn eq(_: Instruction, stack: &mut Stack<U256>) {
+	let a = stack.pop_back();
+	let b = stack.pop_back();
+	stack.push(bool_to_u256(a == b));
+}
+
+fn iszero(_: Instruction, stack: &mut Stack<U256>) {
+	let a = stack.pop_back();
+	stack.push(bool_to_u256(a.is_zero()));
+}
+
+fn and(_: Instruction, stack: &mut Stack<U256>) {
+	let a = stack.pop_back();
+	let b = stack.pop_back();
+	stack.push(a & b);
+}
+
+fn or(_: Instruction, stack: &mut Stack<U256>) {
+	let a = stack.pop_back();
+	let b = stack.pop_back();
+	stack.push(a | b);
+}
+
+fn xor(_: Instruction, stack: &mut Stack<U256>) {
+	let a = stack.pop_back();
+	let b = stack.pop_back();
+	stack.push(a ^ b);
+}
+
+fn byte(_: Instruction, stack: &mut Stack<U256>) {
+	let word = stack.pop_back();
+	let val = stack.pop_back();
+	let byte = match word < U256::from(32) {
+		true => (val >> (8 * (31 - word.low_u64() as usize))) & U256::from(0xff),
+		false => U256::zero()
+	};
+	stack.push(byte);
+}
+
+fn addmod(_: Instruction, stack: &mut Stack<U256>) {
+	let a = stack.pop_back();
+	let b = stack.pop_back();
+	let c = stack.pop_back();
+
+	stack.push(if !c.is_zero() {
+		// upcast to 512
+		let a5 = U512::from(a);
+		let res = a5.overflowing_add(U512::from(b)).0;
+		let x = res.overflowing_rem(U512::from(c)).0;
+		U256::from(x)
+	} else {
+		U256::zero()
+	});
+}
+
+fn mulmod(_: Instruction, stack: &mut Stack<U256>) {
+	let a = stack.pop_back();
+	let b = stack.pop_back();
+	let c = stack.pop_back();
+
+	stack.push(if !c.is_zero() {
+		let a5 = U512::from(a);
+		let res = a5.overflowing_mul(U512::from(b)).0;
+		let x = res.overflowing_rem(U512::from(c)).0;
+		U256::from(x)
+	} else {
+		U256::zero()
+	});
+}
+
+fn signextend(_: Instruction, stack: &mut Stack<U256>) {
+	let bit = stack.pop_back();
+	if bit < U256::from(32) {
+		let number = stack.pop_back();
+		let bit_position = (bit.low_u64() * 8 + 7) as usize;
+
+		let bit = number.bit(bit_position);
+		let mask = (U256::one() << bit_position) - U256::one();
+		stack.push(if bit {
+			number | !mask
+		} else {
+			number & mask
+		});
+	}
+}
+
+fn shl(_: Instruction, stack: &mut Stack<U256>) {
+	let shift = stack.pop_back();
+	let value = stack.pop_back();
+
+	let result = if shift >= CONST_256 {
+		U256::zero()
+	} else {
+		value << (shift.as_u32() as usize)
+	};
+	stack.push(result);
+}
+
+fn shr(_: Instruction, stack: &mut Stack<U256>) {
+	let shift = stack.pop_back();
+	let value = stack.pop_back();
+
+	let result = if shift >= CONST_256 {
+		U256::zero()
+	} else {
+		value >> (shift.as_u32() as usize)
+	};
+	stack.push(result);
+}
+
+fn sar(_: Instruction, stack: &mut Stack<U256>) {
+	// We cannot use get_and_reset_sign/set_sign here, because the rounding looks different.
+	let shift = stack.pop_back();
+	let value = stack.pop_back();
+	let sign = value & CONST_HIBIT != U256::zero();
+
+	let result = if shift >= CONST_256 {
+		if sign {
+			U256::max_value()
+		} else {
+			U256::zero()
+		}
+	} else {
+		let shift = shift.as_u32() as usize;
+		let mut shifted = value >> shift;
+		if sign {
+			shifted = shifted | (U256::max_value() << (256 - shift));
+		}
+		shifted
+	};
+	stack.push(result);
+}
+
+#[cfg(test)]
+mod tests {
+	use ethereum_types::U256;
+	use instructions;
+	use interpreter::stack::{Stack, VecStack};
+	use super::OPS;
+
+	#[test]
+	fn should_dispatch_only_stack_instructions() {
+		assert!(OPS[instructions::ADD as usize].handler.is_some());
+		assert!(OPS[instructions::SWAP16 as usize].handler.is_some());
+		assert!(OPS[instructions::SSTORE as usize].handler.is_none());
+		assert!(OPS[instructions::PUSH1 as usize].handler.is_none());
+		assert_eq!(OPS[instructions::ADD as usize].info.name, "ADD");
+		assert_eq!(OPS[instructions::ADD as usize].tier_idx, instructions::get_tier_idx(instructions::GasPriceTier::VeryLow));
+	}
+
+	#[test]
+	fn small_operand_fast_paths_should_match_full_arithmetic() {
+		let max = U256::from(::std::u64::MAX);
+		let values = [U256::zero(), U256::one(), U256::from(7), max, max + U256::one(), !U256::zero()];
+		let binary = |instruction, a: U256, b: U256| {
+			let mut stack = VecStack::with_capacity(16, U256::zero());
+			stack.push(b);
+			stack.push(a);
+			(OPS[instruction as usize].handler.unwrap())(instruction, &mut stack);
+			stack.pop_back()
+		};
+
+		for &a in &values {
+			for &b in &values {
+				assert_eq!(binary(instructions::ADD, a, b), a.overflowing_add(b).0);
+				assert_eq!(binary(instructions::MUL, a, b), a.overflowing_mul(b).0);
+				assert_eq!(binary(instructions::DIV, a, b), if b.is_zero() { U256::zero() } else { a / b });
+				assert_eq!(binary(instructions::MOD, a, b), if b.is_zero() { U256::zero() } else { a % b });
+				assert_eq!(binary(instructions::LT, a, b), if a < b { U256::one() } else { U256::zero() });
+				assert_eq!(binary(instructions::GT, a, b), if a > b { U256::one() } else { U256::zero() });
+			}
+		}
+	}
+
+	#[test]
+	fn should_execute_through_table() {
+		let mut stack = VecStack::with_capacity(16, U256::zero());
+		stack.push(3.into());
+		stack.push(4.into());
+		let op = &OPS[instructions::SUB as usize];
+		(op.handler.unwrap())(instructions::SUB, &mut stack);
+		assert_eq!(stack.size(), 1);
+		assert_eq!(stack.pop_back(), U256::from(1));
+	}
+}
@@ -15,11 +15,11 @@
 // along with Parity.  If not, see <http://www.gnu.org/licenses/>.
 
 use std::cmp;
-use ethereum_types::{U256, H256};
+use ethereum_types::{U256, H256, Address};
 use super::u256_to_address;
 
 use {evm, vm};
-use instructions::{self, Instruction, InstructionInfo};
+use instructions::{self, Instruction};
 use interpreter::stack::Stack;
 use vm::Schedule;
 
@@ -40,11 +40,46 @@ enum Request<Cost: ::evm::CostType> {
 
 pub struct InstructionRequirements<Cost> {
 	pub gas_cost: Cost,
+	/// Part of `gas_cost` which depends on the size of the operands (`EXP`, `SHA3` and copies).
+	pub dynamic_gas: Cost,
 	pub provide_gas: Option<Cost>,
 	pub memory_total_gas: Cost,
 	pub memory_required_size: usize,
 }
 
+/// Operand size dependent gas costs of `EXP`, `SHA3` and the copy instructions.
+pub struct DynamicGas<'a> {
+	schedule: &'a Schedule,
+}
+
+impl<'a> DynamicGas<'a> {
+	pub fn new(schedule: &'a Schedule) -> Self {
+		DynamicGas { schedule }
+	}
+
+	/// Cost of the bytes of the exponent of `EXP`.
+	pub fn exp<Gas: evm::CostType>(&self, exponent: &U256) -> vm::Result<Gas> {
+		let bytes = (exponent.bits() + 7) / 8;
+		Ok(overflowing!(Gas::from(self.schedule.exp_byte_gas).overflow_mul(Gas::from(bytes))))
+	}
+
+	/// Cost of hashing `size` bytes with `SHA3`.
+	pub fn sha3<Gas: evm::CostType>(&self, size: Gas) -> vm::Result<Gas> {
+		words_gas(self.schedule.sha3_word_gas, size)
+	}
+
+	/// Cost of copying `size` bytes to memory.
+	pub fn copy<Gas: evm::CostType>(&self, size: Gas) -> vm::Result<Gas> {
+		words_gas(self.schedule.copy_gas, size)
+	}
+}
+
+#[inline]
+fn words_gas<Gas: evm::CostType>(word_gas: usize, size: Gas) -> vm::Result<Gas> {
+	let words = overflowing!(add_gas_usize(size, 31)) >> 5;
+	Ok(overflowing!(Gas::from(word_gas).overflow_mul(words)))
+}
+
 pub struct Gasometer<Gas> {
 	pub current_gas: Gas,
 	pub current_mem_gas: Gas,
@@ -108,46 +143,67 @@ impl<Gas: evm::CostType> Gasometer<Gas> {
 		&mut self,
 		ext: &vm::Ext,
 		instruction: Instruction,
-		info: &InstructionInfo,
+		tier_idx: usize,
 		stack: &Stack<U256>,
 		current_mem_size: usize,
 	) -> vm::Result<InstructionRequirements<Gas>> {
 		let schedule = ext.schedule();
-		let tier = instructions::get_tier_idx(info.tier);
-		let default_gas = Gas::from(schedule.tier_step_gas[tier]);
+		let default_gas = Gas::from(schedule.tier_step_gas[tier_idx]);
+		let dynamic = DynamicGas::new(schedule);
+		let mut dynamic_gas = Gas::from(0);
 
 		let cost = match instruction {
 			instructions::JUMPDEST => {
 				Request::Gas(Gas::from(1))
 			},
 			instructions::SSTORE => {
+				if schedule.eip1706 && self.current_gas <= Gas::from(schedule.call_stipend) {
+					return Err(vm::Error::OutOfGas);
+				}
+
 				let address = H256::from(stack.peek(0));
 				let newval = stack.peek(1);
 				let val = U256::from(&*ext.storage_at(&address)?);
 
-				let gas = if val.is_zero() && !newval.is_zero() {
-					schedule.sstore_set_gas
-				} else {
-					// Refund for below case is added when actually executing sstore
-					// !is_zero(&val) && is_zero(newval)
-					schedule.sstore_reset_gas
+				let gas = match schedule.sstore_dirty_gas {
+					Some(dirty_gas) => {
+						let orig = U256::from(&*ext.initial_storage_at(&address)?);
+						calculate_net_sstore_gas(schedule, dirty_gas, &orig, &val, &newval)
+					},
+					None => if val.is_zero() && !newval.is_zero() {
+						schedule.sstore_set_gas
+					} else {
+						// Refund for below case is added when actually executing sstore
+						// !is_zero(&val) && is_zero(newval)
+						schedule.sstore_reset_gas
+					},
 				};
-				Request::Gas(Gas::from(gas))
+				let cold_gas = match schedule.eip2929 && !ext.is_warm_storage_key(&address) {
+					true => schedule.cold_sload_gas,
+					false => 0,
+				};
+				Request::Gas(Gas::from(gas + cold_gas))
 			},
 			instructions::SLOAD => {
-				Request::Gas(Gas::from(schedule.sload_gas))
+				let key = H256::from(stack.peek(0));
+				Request::Gas(Gas::from(schedule.sload_gas + cold_storage_surcharge(ext, &key)))
 			},
 			instructions::BALANCE => {
-				Request::Gas(Gas::from(schedule.balance_gas))
+				let address = u256_to_address(stack.peek(0));
+				Request::Gas(Gas::from(schedule.balance_gas + cold_account_surcharge(ext, &address)))
 			},
 			instructions::EXTCODESIZE => {
-				Request::Gas(Gas::from(schedule.extcodesize_gas))
+				let address = u256_to_address(stack.peek(0));
+				Request::Gas(Gas::from(schedule.extcodesize_gas + cold_account_surcharge(ext, &address)))
 			},
 			instructions::SUICIDE => {
 				let mut gas = Gas::from(schedule.suicide_gas);
 
 				let is_value_transfer = !ext.origin_balance()?.is_zero();
 				let address = u256_to_address(stack.peek(0));
+				if schedule.eip2929 && !ext.is_warm_address(&address) {
+					gas = overflowing!(gas.overflow_add(schedule.cold_account_access_gas.into()));
+				}
 				if (
 					!schedule.no_empty && !ext.exists(&address)?
 				) || (
@@ -168,16 +224,17 @@ impl<Gas: evm::CostType> Gasometer<Gas> {
 				Request::GasMem(default_gas, mem_needed(stack.peek(0), stack.peek(1))?)
 			},
 			instructions::SHA3 => {
-				let w = overflowing!(add_gas_usize(Gas::from_u256(*stack.peek(1))?, 31));
-				let words = w >> 5;
-				let gas = Gas::from(schedule.sha3_gas) + (Gas::from(schedule.sha3_word_gas) * words);
+				dynamic_gas = dynamic.sha3(Gas::from_u256(*stack.peek(1))?)?;
+				let gas = overflowing!(Gas::from(schedule.sha3_gas).overflow_add(dynamic_gas));
 				Request::GasMem(gas, mem_needed(stack.peek(0), stack.peek(1))?)
 			},
 			instructions::CALLDATACOPY | instructions::CODECOPY | instructions::RETURNDATACOPY => {
 				Request::GasMemCopy(default_gas, mem_needed(stack.peek(0), stack.peek(2))?, Gas::from_u256(*stack.peek(2))?)
 			},
 			instructions::EXTCODECOPY => {
-				Request::GasMemCopy(schedule.extcodecopy_base_gas.into(), mem_needed(stack.peek(1), stack.peek(3))?, Gas::from_u256(*stack.peek(3))?)
+				let address = u256_to_address(stack.peek(0));
+				let gas = Gas::from(schedule.extcodecopy_base_gas + cold_account_surcharge(ext, &address));
+				Request::GasMemCopy(gas, mem_needed(stack.peek(1), stack.peek(3))?, Gas::from_u256(*stack.peek(3))?)
 			},
 			instructions::LOG0...instructions::LOG4 => {
 				let no_of_topics = instructions::get_log_topics(instruction);
@@ -188,13 +245,13 @@ impl<Gas: evm::CostType> Gasometer<Gas> {
 				Request::GasMem(gas, mem_needed(stack.peek(0), stack.peek(1))?)
 			},
 			instructions::CALL | instructions::CALLCODE => {
-				let mut gas = Gas::from(schedule.call_gas);
+				let address = u256_to_address(stack.peek(1));
+				let mut gas = Gas::from(schedule.call_gas + cold_account_surcharge(ext, &address));
 				let mem = cmp::max(
 					mem_needed(stack.peek(5), stack.peek(6))?,
 					mem_needed(stack.peek(3), stack.peek(4))?
 				);
 
-				let address = u256_to_address(stack.peek(1));
 				let is_value_transfer = !stack.peek(2).is_zero();
 
 				if instruction == instructions::CALL && (
@@ -214,7 +271,8 @@ impl<Gas: evm::CostType> Gasometer<Gas> {
 				Request::GasMemProvide(gas, mem, Some(requested))
 			},
 			instructions::DELEGATECALL | instructions::STATICCALL => {
-				let gas = Gas::from(schedule.call_gas);
+				let address = u256_to_address(stack.peek(1));
+				let gas = Gas::from(schedule.call_gas + cold_account_surcharge(ext, &address));
 				let mem = cmp::max(
 					mem_needed(stack.peek(4), stack.peek(5))?,
 					mem_needed(stack.peek(2), stack.peek(3))?
@@ -230,9 +288,8 @@ impl<Gas: evm::CostType> Gasometer<Gas> {
 				Request::GasMemProvide(gas, mem, None)
 			},
 			instructions::EXP => {
-				let expon = stack.peek(1);
-				let bytes = ((expon.bits() + 7) / 8) as usize;
-				let gas = Gas::from(schedule.exp_gas + schedule.exp_byte_gas * bytes);
+				dynamic_gas = dynamic.exp(stack.peek(1))?;
+				let gas = overflowing!(Gas::from(schedule.exp_gas).overflow_add(dynamic_gas));
 				Request::Gas(gas)
 			},
 			instructions::BLOCKHASH => {
@@ -245,6 +302,7 @@ impl<Gas: evm::CostType> Gasometer<Gas> {
 			Request::Gas(gas) => {
 				InstructionRequirements {
 					gas_cost: gas,
+					dynamic_gas: dynamic_gas,
 					provide_gas: None,
 					memory_required_size: 0,
 					memory_total_gas: self.current_mem_gas,
@@ -255,6 +313,7 @@ impl<Gas: evm::CostType> Gasometer<Gas> {
 				let gas = overflowing!(gas.overflow_add(mem_gas_cost));
 				InstructionRequirements {
 					gas_cost: gas,
+					dynamic_gas: dynamic_gas,
 					provide_gas: None,
 					memory_required_size: new_mem_size,
 					memory_total_gas: new_mem_gas,
@@ -268,6 +327,7 @@ impl<Gas: evm::CostType> Gasometer<Gas> {
 
 				InstructionRequirements {
 					gas_cost: total_gas,
+					dynamic_gas: dynamic_gas,
 					provide_gas: Some(provided),
 					memory_required_size: new_mem_size,
 					memory_total_gas: new_mem_gas,
@@ -275,13 +335,13 @@ impl<Gas: evm::CostType> Gasometer<Gas> {
 			},
 			Request::GasMemCopy(gas, mem_size, copy) => {
 				let (mem_gas_cost, new_mem_gas, new_mem_size) = self.mem_gas_cost(schedule, current_mem_size, &mem_size)?;
-				let copy = overflowing!(add_gas_usize(copy, 31)) >> 5;
-				let copy_gas = Gas::from(schedule.copy_gas) * copy;
-				let gas = overflowing!(gas.overflow_add(copy_gas));
+				dynamic_gas = dynamic.copy(copy)?;
+				let gas = overflowing!(gas.overflow_add(dynamic_gas));
 				let gas = overflowing!(gas.overflow_add(mem_gas_cost));
 
 				InstructionRequirements {
 					gas_cost: gas,
+					dynamic_gas: dynamic_gas,
 					provide_gas: None,
 					memory_required_size: new_mem_size,
 					memory_total_gas: new_mem_gas,
@@ -336,6 +396,81 @@ fn add_gas_usize<Gas: evm::CostType>(value: Gas, num: usize) -> (Gas, bool) {
 	value.overflow_add(Gas::from(num))
 }
 
+// extra cost of the first access of a storage key in the transaction (EIP-2929).
+fn cold_storage_surcharge(ext: &vm::Ext, key: &H256) -> usize {
+	let schedule = ext.schedule();
+	match schedule.eip2929 && !ext.is_warm_storage_key(key) {
+		true => schedule.cold_sload_gas - schedule.warm_storage_read_gas,
+		false => 0,
+	}
+}
+
+// extra cost of the first access of an account in the transaction (EIP-2929).
+fn cold_account_surcharge(ext: &vm::Ext, address: &Address) -> usize {
+	let schedule = ext.schedule();
+	match schedule.eip2929 && !ext.is_warm_address(address) {
+		true => schedule.cold_account_access_gas - schedule.warm_storage_read_gas,
+		false => 0,
+	}
+}
+
+/// Gas cost of `SSTORE` under net gas metering (EIP-1283), given the value at the start of
+/// the transaction (`original`), the current value and the new value.
+fn calculate_net_sstore_gas(schedule: &Schedule, dirty_gas: usize, original: &U256, current: &U256, new: &U256) -> usize {
+	if current == new {
+		// no-op
+		dirty_gas
+	} else if original == current {
+		// first change of the slot in this transaction
+		if original.is_zero() {
+			schedule.sstore_set_gas
+		} else {
+			schedule.sstore_reset_gas
+		}
+	} else {
+		// slot already dirty
+		dirty_gas
+	}
+}
+
+/// Adjusts `SSTORE` refund under net gas metering (EIP-1283).
+pub fn handle_net_sstore_refund(ext: &mut vm::Ext, dirty_gas: usize, original: &U256, current: &U256, new: &U256) {
+	let (clear_refund, set_gas, reset_gas) = {
+		let schedule = ext.schedule();
+		(schedule.sstore_refund_gas, schedule.sstore_set_gas, schedule.sstore_reset_gas)
+	};
+
+	if current == new {
+		return;
+	}
+
+	if original == current {
+		if !original.is_zero() && new.is_zero() {
+			ext.add_sstore_refund(clear_refund);
+		}
+		return;
+	}
+
+	if !original.is_zero() {
+		if current.is_zero() {
+			// slot was cleared before, but is not anymore
+			ext.sub_sstore_refund(clear_refund);
+		} else if new.is_zero() {
+			ext.add_sstore_refund(clear_refund);
+		}
+	}
+
+	if original == new {
+		// slot is reset to its original value. Nothing is refunded if the spec prices dirty
+		// writes above the set or reset cost.
+		if original.is_zero() {
+			ext.add_sstore_refund(set_gas.saturating_sub(dirty_gas));
+		} else {
+			ext.add_sstore_refund(reset_gas.saturating_sub(dirty_gas));
+		}
+	}
+}
+
 #[test]
 fn test_mem_gas_cost() {
 	// given
@@ -370,3 +505,46 @@ fn test_calculate_mem_cost() {
 	assert_eq!(mem_size, 32);
 }
 
+
+#[test]
+fn test_dynamic_gas() {
+	let schedule = Schedule::new_post_eip150(usize::max_value(), true, true, true);
+	let dynamic = DynamicGas::new(&schedule);
+
+	assert_eq!(dynamic.exp::<usize>(&U256::zero()).unwrap(), 0);
+	assert_eq!(dynamic.exp::<usize>(&U256::from(0xff)).unwrap(), 50);
+	assert_eq!(dynamic.exp::<usize>(&U256::from(0x100)).unwrap(), 100);
+	assert_eq!(dynamic.exp::<U256>(&!U256::zero()).unwrap(), U256::from(32 * 50));
+
+	assert_eq!(dynamic.sha3::<usize>(0).unwrap(), 0);
+	assert_eq!(dynamic.sha3::<usize>(1).unwrap(), 6);
+	assert_eq!(dynamic.sha3::<usize>(33).unwrap(), 12);
+
+	assert_eq!(dynamic.copy::<usize>(32).unwrap(), 3);
+	assert_eq!(dynamic.copy::<usize>(65).unwrap(), 9);
+}
+
+#[test]
+fn test_dynamic_gas_overflow() {
+	let schedule = Schedule::default();
+	let dynamic = DynamicGas::new(&schedule);
+
+	assert!(dynamic.sha3(!U256::zero()).is_err());
+	assert!(dynamic.copy(usize::max_value() - 16).is_err());
+	assert!(dynamic.copy(U256::from(u64::max_value()) << 192).is_err());
+}
+
+#[test]
+fn test_net_sstore_refund_with_expensive_dirty_writes() {
+	use vm::tests::FakeExt;
+
+	let mut ext = FakeExt::new();
+	let dirty_gas = ext.schedule.sstore_set_gas + 1;
+
+	// original 0, changed to 1 and reset to 0
+	handle_net_sstore_refund(&mut ext, dirty_gas, &U256::zero(), &U256::one(), &U256::zero());
+	// original 1, changed to 2 and reset to 1
+	handle_net_sstore_refund(&mut ext, dirty_gas, &U256::one(), &2.into(), &U256::one());
+
+	assert_eq!(ext.sstore_clears, 0);
+}
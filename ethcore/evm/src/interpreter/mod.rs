@@ -22,12 +22,13 @@ mod gasometer;
 mod stack;
 mod memory;
 mod shared_cache;
+mod dispatch;
 
 use std::marker::PhantomData;
 use std::{cmp, mem};
 use std::sync::Arc;
 use hash::keccak;
-use ethereum_types::{U256, U512, H256, Address};
+use ethereum_types::{U256, H256, Address};
 
 use vm::{
 	self, ActionParams, ActionValue, CallType, MessageCallResult,
@@ -46,16 +47,11 @@ use bit_set::BitSet;
 
 type ProgramCounter = usize;
 
-const ONE: U256 = U256([1, 0, 0, 0]);
-const TWO: U256 = U256([2, 0, 0, 0]);
-const TWO_POW_5: U256 = U256([0x20, 0, 0, 0]);
-const TWO_POW_8: U256 = U256([0x100, 0, 0, 0]);
-const TWO_POW_16: U256 = U256([0x10000, 0, 0, 0]);
-const TWO_POW_24: U256 = U256([0x1000000, 0, 0, 0]);
-const TWO_POW_64: U256 = U256([0, 0x1, 0, 0]); // 0x1 00000000 00000000
-const TWO_POW_96: U256 = U256([0, 0x100000000, 0, 0]); //0x1 00000000 00000000 00000000
-const TWO_POW_224: U256 = U256([0, 0, 0, 0x100000000]); //0x1 00000000 00000000 00000000 00000000 00000000 00000000 00000000
-const TWO_POW_248: U256 = U256([0, 0, 0, 0x100000000000000]); //0x1 00000000 00000000 00000000 00000000 00000000 00000000 00000000 000000
+/// Default limit of memory, in bytes, a single call frame of a virtual call may expand to.
+///
+/// Reaching it costs far more gas than any block allows, so it is only ever applied to calls
+/// executed with artificially high gas, like `eth_call`. Imported blocks execute unlimited.
+pub const DEFAULT_MEMORY_LIMIT: usize = 256 * 1024 * 1024;
 
 /// Abstraction over raw vector of Bytes. Easier state management of PC.
 struct CodeReader<'a> {
@@ -107,6 +103,7 @@ enum InstructionResult<Gas> {
 /// Intepreter EVM implementation
 pub struct Interpreter<Cost: CostType> {
 	mem: Vec<u8>,
+	memory_limit: usize,
 	cache: Arc<SharedCache>,
 	return_data: ReturnData,
 	_type: PhantomData<Cost>,
@@ -125,7 +122,7 @@ impl<Cost: CostType> vm::Vm for Interpreter<Cost> {
 		let mut gasometer = Gasometer::<Cost>::new(Cost::from_u256(params.gas)?);
 		let mut stack = VecStack::with_capacity(ext.schedule().stack_limit, U256::zero());
 		let mut reader = CodeReader::new(code);
-		let infos = &*instructions::INSTRUCTIONS;
+		let ops = &*dispatch::OPS;
 
 		while reader.position < code.len() {
 			let instruction = code[reader.position];
@@ -136,19 +133,31 @@ impl<Cost: CostType> vm::Vm for Interpreter<Cost> {
 				reader.position - 1, instruction, gasometer.current_gas.as_u256(),
 			);
 
-			let info = &infos[instruction as usize];
+			let op = &ops[instruction as usize];
+			let info = &op.info;
 			self.verify_instruction(ext, instruction, info, &stack)?;
 
 			// Calculate gas cost
-			let requirements = gasometer.requirements(ext, instruction, info, &stack, self.mem.size())?;
+			let requirements = gasometer.requirements(ext, instruction, op.tier_idx, &stack, self.mem.size())?;
 			if do_trace {
-				ext.trace_prepare_execute(reader.position - 1, instruction, requirements.gas_cost.as_u256());
+				ext.trace_prepare_execute(
+					reader.position - 1, instruction, requirements.gas_cost.as_u256(), requirements.dynamic_gas.as_u256(),
+				);
 			}
 
 			gasometer.verify_gas(&requirements.gas_cost)?;
+			if requirements.memory_required_size > self.memory_limit {
+				return Err(vm::Error::OutOfMemory {
+					required: requirements.memory_required_size,
+					limit: self.memory_limit,
+				});
+			}
 			self.mem.expand(requirements.memory_required_size);
 			gasometer.current_mem_gas = requirements.memory_total_gas;
 			gasometer.current_gas = gasometer.current_gas - requirements.gas_cost;
+			if ext.schedule().eip2929 {
+				Self::warm_accessed(ext, instruction, &stack);
+			}
 
 			evm_debug!({ informant.before_instruction(reader.position, instruction, info, &gasometer.current_gas, &stack) });
 
@@ -158,9 +167,15 @@ impl<Cost: CostType> vm::Vm for Interpreter<Cost> {
 			};
 
 			// Execute instruction
-			let result = self.exec_instruction(
-				gasometer.current_gas, &params, ext, instruction, &mut reader, &mut stack, requirements.provide_gas
-			)?;
+			let result = match op.handler {
+				Some(handler) => {
+					handler(instruction, &mut stack);
+					InstructionResult::Ok
+				},
+				None => self.exec_instruction(
+					gasometer.current_gas, &params, ext, instruction, &mut reader, &mut stack, requirements.provide_gas
+				)?,
+			};
 
 			evm_debug!({ informant.after_instruction(instruction) });
 
@@ -211,12 +226,19 @@ impl<Cost: CostType> Interpreter<Cost> {
 	pub fn new(cache: Arc<SharedCache>) -> Interpreter<Cost> {
 		Interpreter {
 			mem: Vec::new(),
+			memory_limit: usize::max_value(),
 			cache: cache,
 			return_data: ReturnData::empty(),
 			_type: PhantomData::default(),
 		}
 	}
 
+	/// Limit memory, in bytes, a single call frame may expand to. Unlimited by default.
+	pub fn with_memory_limit(mut self, limit: usize) -> Self {
+		self.memory_limit = limit;
+		self
+	}
+
 	fn verify_instruction(&self, ext: &vm::Ext, instruction: Instruction, info: &InstructionInfo, stack: &Stack<U256>) -> vm::Result<()> {
 		let schedule = ext.schedule();
 
@@ -225,6 +247,7 @@ impl<Cost: CostType> Interpreter<Cost> {
 			(instruction == instructions::STATICCALL && !schedule.have_static_call) ||
 			((instruction == instructions::RETURNDATACOPY || instruction == instructions::RETURNDATASIZE) && !schedule.have_return_data) ||
 			(instruction == instructions::REVERT && !schedule.have_revert) ||
+			(instruction == instructions::SUICIDE && !schedule.have_suicide) ||
 			((instruction == instructions::SHL || instruction == instructions::SHR || instruction == instructions::SAR) && !schedule.have_bitwise_shifting) {
 
 			return Err(vm::Error::BadInstruction {
@@ -276,6 +299,24 @@ impl<Cost: CostType> Interpreter<Cost> {
 		}
 	}
 
+	// marks the account or storage key accessed by the instruction as accessed in the transaction.
+	fn warm_accessed(
+		ext: &mut vm::Ext,
+		instruction: Instruction,
+		stack: &Stack<U256>
+	) {
+		match instruction {
+			instructions::SLOAD | instructions::SSTORE => ext.warm_storage_key(&H256::from(stack.peek(0))),
+			instructions::BALANCE | instructions::EXTCODESIZE | instructions::EXTCODECOPY | instructions::SUICIDE => {
+				ext.warm_address(&u256_to_address(stack.peek(0)))
+			},
+			instructions::CALL | instructions::CALLCODE | instructions::DELEGATECALL | instructions::STATICCALL => {
+				ext.warm_address(&u256_to_address(stack.peek(1)))
+			},
+			_ => {},
+		}
+	}
+
 	fn store_written(
 		instruction: Instruction,
 		stack: &Stack<U256>
@@ -503,9 +544,18 @@ impl<Cost: CostType> Interpreter<Cost> {
 				let val = stack.pop_back();
 
 				let current_val = U256::from(&*ext.storage_at(&address)?);
-				// Increase refund for clear
-				if !self.is_zero(&current_val) && self.is_zero(&val) {
-					ext.inc_sstore_clears();
+				match ext.schedule().sstore_dirty_gas {
+					Some(dirty_gas) => {
+						let original_val = U256::from(&*ext.initial_storage_at(&address)?);
+						gasometer::handle_net_sstore_refund(ext, dirty_gas, &original_val, &current_val, &val);
+					},
+					None => {
+						// Increase refund for clear
+						if !self.is_zero(&current_val) && self.is_zero(&val) {
+							let sstore_clears_schedule = ext.schedule().sstore_refund_gas;
+							ext.add_sstore_refund(sstore_clears_schedule);
+						}
+					},
 				}
 				ext.set_storage(address, H256::from(&val))?;
 			},
@@ -611,7 +661,9 @@ impl<Cost: CostType> Interpreter<Cost> {
 				stack.push(ext.env_info().gas_limit.clone());
 			},
 			_ => {
-				self.exec_stack_instruction(instruction, stack)?;
+				return Err(vm::Error::BadInstruction {
+					instruction: instruction
+				});
 			}
 		};
 		Ok(InstructionResult::Ok)
@@ -660,293 +712,6 @@ impl<Cost: CostType> Interpreter<Cost> {
 		val.is_zero()
 	}
 
-	fn bool_to_u256(&self, val: bool) -> U256 {
-		if val {
-			U256::one()
-		} else {
-			U256::zero()
-		}
-	}
-
-	fn exec_stack_instruction(&self, instruction: Instruction, stack: &mut Stack<U256>) -> vm::Result<()> {
-		match instruction {
-			instructions::DUP1...instructions::DUP16 => {
-				let position = instructions::get_dup_position(instruction);
-				let val = stack.peek(position).clone();
-				stack.push(val);
-			},
-			instructions::SWAP1...instructions::SWAP16 => {
-				let position = instructions::get_swap_position(instruction);
-				stack.swap_with_top(position)
-			},
-			instructions::POP => {
-				stack.pop_back();
-			},
-			instructions::ADD => {
-				let a = stack.pop_back();
-				let b = stack.pop_back();
-				stack.push(a.overflowing_add(b).0);
-			},
-			instructions::MUL => {
-				let a = stack.pop_back();
-				let b = stack.pop_back();
-				stack.push(a.overflowing_mul(b).0);
-			},
-			instructions::SUB => {
-				let a = stack.pop_back();
-				let b = stack.pop_back();
-				stack.push(a.overflowing_sub(b).0);
-			},
-			instructions::DIV => {
-				let a = stack.pop_back();
-				let b = stack.pop_back();
-				stack.push(if !self.is_zero(&b) {
-					match b {
-						ONE => a,
-						TWO => a >> 1,
-						TWO_POW_5 => a >> 5,
-						TWO_POW_8 => a >> 8,
-						TWO_POW_16 => a >> 16,
-						TWO_POW_24 => a >> 24,
-						TWO_POW_64 => a >> 64,
-						TWO_POW_96 => a >> 96,
-						TWO_POW_224 => a >> 224,
-						TWO_POW_248 => a >> 248,
-						_ => a.overflowing_div(b).0,
-					}
-				} else {
-					U256::zero()
-				});
-			},
-			instructions::MOD => {
-				let a = stack.pop_back();
-				let b = stack.pop_back();
-				stack.push(if !self.is_zero(&b) {
-					a.overflowing_rem(b).0
-				} else {
-					U256::zero()
-				});
-			},
-			instructions::SDIV => {
-				let (a, sign_a) = get_and_reset_sign(stack.pop_back());
-				let (b, sign_b) = get_and_reset_sign(stack.pop_back());
-
-				// -2^255
-				let min = (U256::one() << 255) - U256::one();
-				stack.push(if self.is_zero(&b) {
-					U256::zero()
-				} else if a == min && b == !U256::zero() {
-					min
-				} else {
-					let c = a.overflowing_div(b).0;
-					set_sign(c, sign_a ^ sign_b)
-				});
-			},
-			instructions::SMOD => {
-				let ua = stack.pop_back();
-				let ub = stack.pop_back();
-				let (a, sign_a) = get_and_reset_sign(ua);
-				let b = get_and_reset_sign(ub).0;
-
-				stack.push(if !self.is_zero(&b) {
-					let c = a.overflowing_rem(b).0;
-					set_sign(c, sign_a)
-				} else {
-					U256::zero()
-				});
-			},
-			instructions::EXP => {
-				let base = stack.pop_back();
-				let expon = stack.pop_back();
-				let res = base.overflowing_pow(expon).0;
-				stack.push(res);
-			},
-			instructions::NOT => {
-				let a = stack.pop_back();
-				stack.push(!a);
-			},
-			instructions::LT => {
-				let a = stack.pop_back();
-				let b = stack.pop_back();
-				stack.push(self.bool_to_u256(a < b));
-			},
-			instructions::SLT => {
-				let (a, neg_a) = get_and_reset_sign(stack.pop_back());
-				let (b, neg_b) = get_and_reset_sign(stack.pop_back());
-
-				let is_positive_lt = a < b && !(neg_a | neg_b);
-				let is_negative_lt = a > b && (neg_a & neg_b);
-				let has_different_signs = neg_a && !neg_b;
-
-				stack.push(self.bool_to_u256(is_positive_lt | is_negative_lt | has_different_signs));
-			},
-			instructions::GT => {
-				let a = stack.pop_back();
-				let b = stack.pop_back();
-				stack.push(self.bool_to_u256(a > b));
-			},
-			instructions::SGT => {
-				let (a, neg_a) = get_and_reset_sign(stack.pop_back());
-				let (b, neg_b) = get_and_reset_sign(stack.pop_back());
-
-				let is_positive_gt = a > b && !(neg_a | neg_b);
-				let is_negative_gt = a < b && (neg_a & neg_b);
-				let has_different_signs = !neg_a && neg_b;
-
-				stack.push(self.bool_to_u256(is_positive_gt | is_negative_gt | has_different_signs));
-			},
-			instructions::EQ => {
-				let a = stack.pop_back();
-				let b = stack.pop_back();
-				stack.push(self.bool_to_u256(a == b));
-			},
-			instructions::ISZERO => {
-				let a = stack.pop_back();
-				stack.push(self.bool_to_u256(self.is_zero(&a)));
-			},
-			instructions::AND => {
-				let a = stack.pop_back();
-				let b = stack.pop_back();
-				stack.push(a & b);
-			},
-			instructions::OR => {
-				let a = stack.pop_back();
-				let b = stack.pop_back();
-				stack.push(a | b);
-			},
-			instructions::XOR => {
-				let a = stack.pop_back();
-				let b = stack.pop_back();
-				stack.push(a ^ b);
-			},
-			instructions::BYTE => {
-				let word = stack.pop_back();
-				let val = stack.pop_back();
-				let byte = match word < U256::from(32) {
-					true => (val >> (8 * (31 - word.low_u64() as usize))) & U256::from(0xff),
-					false => U256::zero()
-				};
-				stack.push(byte);
-			},
-			instructions::ADDMOD => {
-				let a = stack.pop_back();
-				let b = stack.pop_back();
-				let c = stack.pop_back();
-
-				stack.push(if !self.is_zero(&c) {
-					// upcast to 512
-					let a5 = U512::from(a);
-					let res = a5.overflowing_add(U512::from(b)).0;
-					let x = res.overflowing_rem(U512::from(c)).0;
-					U256::from(x)
-				} else {
-					U256::zero()
-				});
-			},
-			instructions::MULMOD => {
-				let a = stack.pop_back();
-				let b = stack.pop_back();
-				let c = stack.pop_back();
-
-				stack.push(if !self.is_zero(&c) {
-					let a5 = U512::from(a);
-					let res = a5.overflowing_mul(U512::from(b)).0;
-					let x = res.overflowing_rem(U512::from(c)).0;
-					U256::from(x)
-				} else {
-					U256::zero()
-				});
-			},
-			instructions::SIGNEXTEND => {
-				let bit = stack.pop_back();
-				if bit < U256::from(32) {
-					let number = stack.pop_back();
-					let bit_position = (bit.low_u64() * 8 + 7) as usize;
-
-					let bit = number.bit(bit_position);
-					let mask = (U256::one() << bit_position) - U256::one();
-					stack.push(if bit {
-						number | !mask
-					} else {
-						number & mask
-					});
-				}
-			},
-			instructions::SHL => {
-				const CONST_256: U256 = U256([256, 0, 0, 0]);
-
-				let shift = stack.pop_back();
-				let value = stack.pop_back();
-
-				let result = if shift >= CONST_256 {
-					U256::zero()
-				} else {
-					value << (shift.as_u32() as usize)
-				};
-				stack.push(result);
-			},
-			instructions::SHR => {
-				const CONST_256: U256 = U256([256, 0, 0, 0]);
-
-				let shift = stack.pop_back();
-				let value = stack.pop_back();
-
-				let result = if shift >= CONST_256 {
-					U256::zero()
-				} else {
-					value >> (shift.as_u32() as usize)
-				};
-				stack.push(result);
-			},
-			instructions::SAR => {
-				// We cannot use get_and_reset_sign/set_sign here, because the rounding looks different.
-
-				const CONST_256: U256 = U256([256, 0, 0, 0]);
-				const CONST_HIBIT: U256 = U256([0, 0, 0, 0x8000000000000000]);
-
-				let shift = stack.pop_back();
-				let value = stack.pop_back();
-				let sign = value & CONST_HIBIT != U256::zero();
-
-				let result = if shift >= CONST_256 {
-					if sign {
-						U256::max_value()
-					} else {
-						U256::zero()
-					}
-				} else {
-					let shift = shift.as_u32() as usize;
-					let mut shifted = value >> shift;
-					if sign {
-						shifted = shifted | (U256::max_value() << (256 - shift));
-					}
-					shifted
-				};
-				stack.push(result);
-			},
-			_ => {
-				return Err(vm::Error::BadInstruction {
-					instruction: instruction
-				});
-			}
-		}
-		Ok(())
-	}
-
-}
-
-fn get_and_reset_sign(value: U256) -> (U256, bool) {
-	let U256(arr) = value;
-	let sign = arr[3].leading_zeros() == 0;
-	(set_sign(value, sign), sign)
-}
-
-fn set_sign(value: U256, sign: bool) -> U256 {
-	if sign {
-		(!U256::zero() ^ value).overflowing_add(U256::one()).0
-	} else {
-		value
-	}
 }
 
 #[inline]
@@ -1017,4 +782,24 @@ mod tests {
 
 		assert_eq!(err, ::vm::Error::OutOfBounds);
 	}
+
+	#[test]
+	fn should_not_expand_memory_past_limit() {
+		// MSTORE at offset 0x10000
+		let code = "60016201000052".from_hex().unwrap();
+
+		let mut params = ActionParams::default();
+		params.address = 5.into();
+		params.gas = 300_000.into();
+		params.gas_price = 1.into();
+		params.code = Some(Arc::new(code));
+		let mut ext = FakeExt::new();
+
+		let err = {
+			let mut vm = Factory::new(VMType::Interpreter, 1).with_memory_limit(1024).create(&params.gas);
+			test_finalize(vm.exec(params, &mut ext)).err().unwrap()
+		};
+
+		assert_eq!(err, ::vm::Error::OutOfMemory { required: 0x10020, limit: 1024 });
+	}
 }
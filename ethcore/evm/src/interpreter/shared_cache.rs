@@ -19,7 +19,8 @@ use hash::KECCAK_EMPTY;
 use heapsize::HeapSizeOf;
 use ethereum_types::H256;
 use parking_lot::Mutex;
-use memory_cache::MemoryLruCache;
+use memory_cache::{MemoryLruCache, CacheStats};
+use memory_cache::pressure::{self, Shed};
 use bit_set::BitSet;
 use super::super::instructions;
 
@@ -37,15 +38,17 @@ impl HeapSizeOf for Bits {
 
 /// Global cache for EVM interpreter
 pub struct SharedCache {
-	jump_destinations: Mutex<MemoryLruCache<H256, Bits>>,
+	jump_destinations: Arc<Mutex<MemoryLruCache<H256, Bits>>>,
 }
 
 impl SharedCache {
 	/// Create a jump destinations cache with a maximum size in bytes
 	/// to cache.
 	pub fn new(max_size: usize) -> Self {
+		let jump_destinations = Arc::new(Mutex::new(MemoryLruCache::new(max_size)));
+		pressure::global().register(&(jump_destinations.clone() as Arc<Shed>));
 		SharedCache {
-			jump_destinations: Mutex::new(MemoryLruCache::new(max_size)),
+			jump_destinations: jump_destinations,
 		}
 	}
 
@@ -65,6 +68,16 @@ impl SharedCache {
 		d
 	}
 
+	/// Change the maximum size in bytes of cached jump destinations.
+	pub fn set_max_size(&self, max_size: usize) {
+		self.jump_destinations.lock().set_max_size(max_size);
+	}
+
+	/// Returns lookup statistics since last called.
+	pub fn take_stats(&self) -> CacheStats {
+		self.jump_destinations.lock().take_stats()
+	}
+
 	fn find_jump_destinations(code: &[u8]) -> Arc<BitSet> {
 		let mut jump_dests = BitSet::with_capacity(code.len());
 		let mut position = 0;
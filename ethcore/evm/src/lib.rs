@@ -16,6 +16,8 @@
 
 //! Ethereum virtual machine.
 
+#![cfg_attr(all(feature = "benches", test), feature(test))]
+
 extern crate bit_set;
 extern crate ethereum_types;
 extern crate parking_lot;
@@ -55,3 +57,4 @@ pub use self::evm::{Finalize, FinalizationResult, CostType};
 pub use self::instructions::{InstructionInfo, INSTRUCTIONS, push_bytes};
 pub use self::vmtype::VMType;
 pub use self::factory::Factory;
+pub use self::interpreter::DEFAULT_MEMORY_LIMIT;
@@ -716,12 +716,40 @@ fn test_jumps(factory: super::Factory) {
 		test_finalize(vm.exec(params, &mut ext)).unwrap()
 	};
 
-	assert_eq!(ext.sstore_clears, 1);
+	assert_eq!(ext.sstore_clears, ext.schedule.sstore_refund_gas as i128);
 	assert_store(&ext, 0, "0000000000000000000000000000000000000000000000000000000000000000"); // 5!
 	assert_store(&ext, 1, "0000000000000000000000000000000000000000000000000000000000000078"); // 5!
 	assert_eq!(gas_left, U256::from(54_117));
 }
 
+evm_test!{test_net_sstore_gas_metering: test_net_sstore_gas_metering_int}
+fn test_net_sstore_gas_metering(factory: super::Factory) {
+	// (code, gas used, refund) for original value 0, see EIP-1283
+	let cases = [
+		("60006000556000600055", 412, 0),
+		("60006000556001600055", 20_212, 0),
+		("60016000556000600055", 20_212, 19_800),
+		("60016000556002600055", 20_212, 0),
+		("60016000556001600055", 20_212, 0),
+	];
+
+	for &(code, gas_used, refund) in &cases {
+		let mut params = ActionParams::default();
+		params.gas = U256::from(100_000);
+		params.code = Some(Arc::new(code.from_hex().unwrap()));
+		let mut ext = FakeExt::new();
+		ext.schedule.sstore_dirty_gas = Some(200);
+
+		let gas_left = {
+			let mut vm = factory.create(&params.gas);
+			test_finalize(vm.exec(params, &mut ext)).unwrap()
+		};
+
+		assert_eq!(gas_left, U256::from(100_000 - gas_used), "gas used by {}", code);
+		assert_eq!(ext.sstore_clears, refund, "refund of {}", code);
+	}
+}
+
 evm_test!{test_calls: test_calls_int}
 fn test_calls(factory: super::Factory) {
 	let code = "600054602d57600160005560006000600060006050610998610100f160006000600060006050610998610100f25b".from_hex().unwrap();
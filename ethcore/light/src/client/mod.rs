@@ -20,7 +20,7 @@ use std::sync::{Weak, Arc};
 
 use ethcore::block_status::BlockStatus;
 use ethcore::client::{ClientReport, EnvInfo, ClientIoMessage};
-use ethcore::engines::{epoch, EthEngine, EpochChange, EpochTransition, Proof};
+use ethcore::engines::{epoch, EthEngine, EpochChange, EpochTransition, LivenessAlert, Proof};
 use ethcore::machine::EthereumMachine;
 use ethcore::error::{Error, BlockImportError};
 use ethcore::ids::BlockId;
@@ -31,7 +31,7 @@ use ethcore::spec::{Spec, SpecHardcodedSync};
 use ethcore::encoded;
 use io::IoChannel;
 use parking_lot::{Mutex, RwLock};
-use ethereum_types::{H256, U256};
+use ethereum_types::{H256, H512, U256};
 use futures::{IntoFuture, Future};
 
 use kvdb::KeyValueDB;
@@ -379,10 +379,14 @@ impl<T: ChainDataFetcher> Client<T> {
 			Some(hdr) => hdr,
 			None => return None,
 		};
+		let author = match header.decode() {
+			Ok(decoded) => self.engine.executive_author(&decoded),
+			Err(_) => header.author(),
+		};
 
 		Some(EnvInfo {
 			number: header.number(),
-			author: header.author(),
+			author: author,
 			timestamp: header.timestamp(),
 			difficulty: header.difficulty(),
 			last_hashes: self.build_last_hashes(header.parent_hash()),
@@ -606,6 +610,10 @@ impl<T: ChainDataFetcher> ::ethcore::client::EngineClient for Client<T> {
 	fn submit_seal(&self, _block_hash: H256, _seal: Vec<Vec<u8>>) { }
 	fn broadcast_consensus_message(&self, _message: Vec<u8>) { }
 
+	fn send_consensus_message(&self, _message: Vec<u8>, _node_id: H512) { }
+
+	fn notify_liveness_alert(&self, _alert: LivenessAlert) { }
+
 	fn epoch_transition_for(&self, parent_hash: H256) -> Option<EpochTransition> {
 		self.chain.epoch_transition_for(parent_hash).map(|(hdr, proof)| EpochTransition {
 			block_hash: hdr.hash(),
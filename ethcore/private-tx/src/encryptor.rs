@@ -23,7 +23,7 @@ use std::time::{Instant, Duration};
 use std::collections::HashMap;
 use std::collections::hash_map::Entry;
 use parking_lot::Mutex;
-use ethcore::account_provider::AccountProvider;
+use ethcore::account_provider::{AccountProvider, KeyOperation};
 use ethereum_types::{H128, H256, Address};
 use ethjson;
 use ethkey::{Signature, Public};
@@ -33,7 +33,7 @@ use fetch::{Fetch, Client as FetchClient, Method, BodyReader, Request};
 use bytes::{Bytes, ToPretty};
 use error::{Error, ErrorKind};
 use url::Url;
-use super::find_account_password;
+use super::{find_account_password, USAGE_ORIGIN};
 
 /// Initialization vector length.
 const INIT_VEC_LEN: usize = 16;
@@ -151,7 +151,7 @@ impl SecretStoreEncryptor {
 		let password = find_account_password(&self.config.passwords, &*accounts, &requester);
 
 		// decrypt Public
-		let decrypted_bytes = accounts.decrypt(requester, password, &crypto::DEFAULT_MAC, &encrypted_bytes)?;
+		let decrypted_bytes = accounts.decrypt(requester, password, &crypto::DEFAULT_MAC, &encrypted_bytes, USAGE_ORIGIN)?;
 		let decrypted_key = Public::from_slice(&decrypted_bytes);
 
 		// and now take x coordinate of Public as a key
@@ -192,7 +192,7 @@ impl SecretStoreEncryptor {
 		let contract_address_extended: H256 = contract_address.into();
 		let key_server_account = self.config.key_server_account.ok_or_else(|| ErrorKind::KeyServerAccountNotSet)?;
 		let password = find_account_password(&self.config.passwords, accounts, &key_server_account);
-		Ok(accounts.sign(key_server_account, password, H256::from_slice(&contract_address_extended))?)
+		Ok(accounts.sign(key_server_account, password, H256::from_slice(&contract_address_extended), KeyOperation::SignMessage, USAGE_ORIGIN)?)
 	}
 }
 
@@ -74,14 +74,14 @@ use parking_lot::{Mutex, RwLock};
 use bytes::Bytes;
 use ethkey::{Signature, recover, public_to_address};
 use io::IoChannel;
-use ethcore::executive::{Executive, TransactOptions};
+use ethcore::executive::{BlockExecutor, TransactOptions};
 use ethcore::executed::{Executed};
 use transaction::{SignedTransaction, Transaction, Action, UnverifiedTransaction};
 use ethcore::{contract_address as ethcore_contract_address};
 use ethcore::client::{
 	Client, ChainNotify, ChainRoute, ChainMessageType, ClientIoMessage, BlockId, CallContract
 };
-use ethcore::account_provider::AccountProvider;
+use ethcore::account_provider::{AccountProvider, KeyOperation};
 use ethcore::miner::{self, Miner, MinerService};
 use ethcore::trace::{Tracer, VMTracer};
 use rustc_hex::FromHex;
@@ -94,6 +94,9 @@ use_contract!(private, "PrivateContract", "res/private.json");
 /// Initialization vector length.
 const INIT_VEC_LEN: usize = 16;
 
+/// Origin recorded in the key usage log for operations performed by private transactions.
+const USAGE_ORIGIN: &'static str = "Private Transactions";
+
 /// Configurtion for private transaction provider
 #[derive(Default, PartialEq, Debug, Clone)]
 pub struct ProviderConfig {
@@ -281,7 +284,7 @@ impl Provider where {
 						let private_state_hash = self.calculate_state_hash(&private_state, contract_nonce);
 						trace!("Hashed effective private state for validator: {:?}", private_state_hash);
 						let password = find_account_password(&self.passwords, &*self.accounts, &account);
-						let signed_state = self.accounts.sign(account, password, private_state_hash)?;
+						let signed_state = self.accounts.sign(account, password, private_state_hash, KeyOperation::SignMessage, USAGE_ORIGIN)?;
 						let signed_private_transaction = SignedPrivateTransaction::new(desc.private_hash, signed_state, None);
 						trace!("Sending signature for private transaction: {:?}", signed_private_transaction);
 						self.broadcast_signed_private_transaction(signed_private_transaction.rlp_bytes().into_vec());
@@ -430,7 +433,7 @@ impl Provider where {
 			let (new_address, _) = ethcore_contract_address(engine.create_address_scheme(env_info.number), &sender, &nonce, &transaction.data);
 			Some(new_address)
 		});
-		let result = Executive::new(&mut state, &env_info, engine.machine()).transact_virtual(transaction, options)?;
+		let result = BlockExecutor::new(env_info).transact_virtual(&mut state, engine.machine(), transaction, options)?;
 		let (encrypted_code, encrypted_storage) = match contract_address {
 			None => bail!(ErrorKind::ContractDoesNotExist),
 			Some(address) => {
@@ -638,7 +641,7 @@ impl Importer for Arc<Provider> {
 			let hash = public_tx.hash(chain_id);
 			let signer_account = self.signer_account.ok_or_else(|| ErrorKind::SignerAccountNotSet)?;
 			let password = find_account_password(&self.passwords, &*self.accounts, &signer_account);
-			let signature = self.accounts.sign(signer_account, password, hash)?;
+			let signature = self.accounts.sign(signer_account, password, hash, KeyOperation::SignTransaction, USAGE_ORIGIN)?;
 			let signed = SignedTransaction::new(public_tx.with_signature(signature, chain_id))?;
 			match self.miner.import_own_transaction(&*self.client, signed.into()) {
 				Ok(_) => trace!("Public transaction added to queue"),
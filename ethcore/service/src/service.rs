@@ -225,11 +225,14 @@ impl IoHandler<ClientIoMessage> for ClientIoHandler {
 			ClientIoMessage::FeedBlockChunk(ref hash, ref chunk) => {
 				self.snapshot.feed_block_chunk(*hash, chunk)
 			}
-			ClientIoMessage::TakeSnapshot(num) => {
+			ClientIoMessage::TakeSnapshot(num, ref pin) => {
 				let client = self.client.clone();
 				let snapshot = self.snapshot.clone();
+				let pin = pin.clone();
 
 				let res = thread::Builder::new().name("Periodic Snapshot".into()).spawn(move || {
+					// the state stays pinned until the snapshot is taken.
+					let _pin = pin;
 					if let Err(e) = snapshot.take_snapshot(&*client, num) {
 						warn!("Failed to take snapshot at block #{}: {}", num, e);
 					}
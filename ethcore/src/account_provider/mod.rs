@@ -16,14 +16,19 @@
 
 //! Account management.
 
+mod remote;
 mod stores;
+mod usage;
 
 use self::stores::{AddressBook, DappsSettingsStore, NewDappsPolicy};
+use self::usage::KeyUsageLog;
+pub use self::remote::{RemoteSigner, RemoteSignerError, HttpRemoteSigner, HttpRemoteSignerConfig};
+pub use self::usage::{KeyUsage, KeyOperation};
 
 use std::fmt;
 use std::collections::{HashMap, HashSet};
 use std::time::{Instant, Duration};
-use parking_lot::RwLock;
+use parking_lot::{Mutex, RwLock};
 use ethstore::{
 	SimpleSecretStore, SecretStore, Error as SSError, EthStore, EthMultiStore,
 	random_string, SecretVaultRef, StoreAccountRef, OpaqueSecret,
@@ -64,6 +69,8 @@ pub enum SignError {
 	NotFound,
 	/// Low-level hardware device error.
 	Hardware(HardwareError),
+	/// Remote signer error.
+	Remote(RemoteSignerError),
 	/// Low-level error from store
 	SStore(SSError),
 }
@@ -74,6 +81,7 @@ impl fmt::Display for SignError {
 			SignError::NotUnlocked => write!(f, "Account is locked"),
 			SignError::NotFound => write!(f, "Account does not exist"),
 			SignError::Hardware(ref e) => write!(f, "{}", e),
+			SignError::Remote(ref e) => write!(f, "{}", e),
 			SignError::SStore(ref e) => write!(f, "{}", e),
 		}
 	}
@@ -85,6 +93,12 @@ impl From<HardwareError> for SignError {
 	}
 }
 
+impl From<RemoteSignerError> for SignError {
+	fn from(e: RemoteSignerError) -> Self {
+		SignError::Remote(e)
+	}
+}
+
 impl From<SSError> for SignError {
 	fn from(e: SSError) -> Self {
 		SignError::SStore(e)
@@ -125,12 +139,16 @@ pub struct AccountProvider {
 	address_book: RwLock<AddressBook>,
 	/// Dapps settings.
 	dapps_settings: RwLock<DappsSettingsStore>,
+	/// Log of signing and decryption operations.
+	usage_log: Mutex<KeyUsageLog>,
 	/// Accounts on disk
 	sstore: Box<SecretStore>,
 	/// Accounts unlocked with rolling tokens
 	transient_sstore: EthMultiStore,
 	/// Accounts in hardware wallets.
 	hardware_store: Option<HardwareWalletManager>,
+	/// Accounts held by a remote signer.
+	remote_signer: Option<Box<RemoteSigner>>,
 	/// When unlocking account permanently we additionally keep a raw secret in memory
 	/// to increase the performance of transaction signing.
 	unlock_keep_secret: bool,
@@ -193,9 +211,15 @@ impl AccountProvider {
 			unlocked: RwLock::new(HashMap::new()),
 			address_book: RwLock::new(address_book),
 			dapps_settings: RwLock::new(DappsSettingsStore::new(&sstore.local_path())),
+			usage_log: Mutex::new(match sstore.local_path() {
+				// in-memory key stores don't have a directory to keep the log in.
+				ref path if path.as_os_str().is_empty() => KeyUsageLog::transient(),
+				ref path => KeyUsageLog::new(path),
+			}),
 			sstore: sstore,
 			transient_sstore: transient_sstore(),
 			hardware_store: hardware_store,
+			remote_signer: None,
 			unlock_keep_secret: settings.unlock_keep_secret,
 			blacklisted_accounts: settings.blacklisted_accounts,
 		}
@@ -208,14 +232,22 @@ impl AccountProvider {
 			unlocked: RwLock::new(HashMap::new()),
 			address_book: RwLock::new(AddressBook::transient()),
 			dapps_settings: RwLock::new(DappsSettingsStore::transient()),
+			usage_log: Mutex::new(KeyUsageLog::transient()),
 			sstore: Box::new(EthStore::open(Box::new(MemoryDirectory::default())).expect("MemoryDirectory load always succeeds; qed")),
 			transient_sstore: transient_sstore(),
 			hardware_store: None,
+			remote_signer: None,
 			unlock_keep_secret: false,
 			blacklisted_accounts: vec![],
 		}
 	}
 
+	/// Uses given remote signer for accounts it manages.
+	pub fn with_remote_signer(mut self, signer: Box<RemoteSigner>) -> Self {
+		self.remote_signer = Some(signer);
+		self
+	}
+
 	/// Creates new random account.
 	pub fn new_account(&self, password: &str) -> Result<Address, Error> {
 		self.new_account_and_public(password).map(|d| d.0)
@@ -293,6 +325,16 @@ impl AccountProvider {
 		Ok(accounts.into_iter().map(|a| a.address).collect())
 	}
 
+	/// Returns addresses of accounts held by the remote signer.
+	pub fn remote_accounts(&self) -> Vec<Address> {
+		self.remote_signer.as_ref().map_or_else(Vec::new, |s| s.accounts())
+	}
+
+	/// Checks whether given address is held by the remote signer.
+	pub fn is_remote_address(&self, address: &Address) -> bool {
+		self.remote_signer.as_ref().map_or(false, |s| s.accounts().contains(address))
+	}
+
 	/// Get a list of paths to locked hardware wallets
 	pub fn locked_hardware_accounts(&self) -> Result<Vec<String>, SignError> {
 		match self.hardware_store.as_ref().map(|h| h.list_locked_wallets()) {
@@ -660,7 +702,21 @@ impl AccountProvider {
 	}
 
 	/// Signs the message. If password is not provided the account must be unlocked.
-	pub fn sign(&self, address: Address, password: Option<String>, message: Message) -> Result<Signature, SignError> {
+	///
+	/// The operation is recorded in the key usage log of the account, along with the `origin` of the request.
+	pub fn sign(&self, address: Address, password: Option<String>, message: Message, operation: KeyOperation, origin: &str) -> Result<Signature, SignError> {
+		let result = self.sign_message(address, password, message);
+		self.note_key_usage(address, operation, origin, &result);
+		result
+	}
+
+	fn sign_message(&self, address: Address, password: Option<String>, message: Message) -> Result<Signature, SignError> {
+		if let Some(ref signer) = self.remote_signer {
+			if self.sstore.account_ref(&address).is_err() && signer.accounts().contains(&address) {
+				return Ok(signer.sign(&address, &message)?);
+			}
+		}
+
 		let account = self.sstore.account_ref(&address)?;
 		match self.unlocked_secrets.read().get(&account) {
 			Some(secret) => {
@@ -674,16 +730,33 @@ impl AccountProvider {
 	}
 
 	/// Signs message using the derived secret. If password is not provided the account must be unlocked.
-	pub fn sign_derived(&self, address: &Address, password: Option<String>, derivation: Derivation, message: Message)
+	///
+	/// The operation is recorded in the key usage log of the base account.
+	pub fn sign_derived(&self, address: &Address, password: Option<String>, derivation: Derivation, message: Message, operation: KeyOperation, origin: &str)
 		-> Result<Signature, SignError>
 	{
-		let account = self.sstore.account_ref(address)?;
-		let password = password.map(Ok).unwrap_or_else(|| self.password(&account))?;
-		Ok(self.sstore.sign_derived(&account, &password, derivation, &message)?)
+		let result = self.sstore.account_ref(address)
+			.map_err(Into::into)
+			.and_then(|account| {
+				let password = password.map(Ok).unwrap_or_else(|| self.password(&account))?;
+				Ok(self.sstore.sign_derived(&account, &password, derivation, &message)?)
+			});
+		self.note_key_usage(*address, operation, origin, &result);
+		result
 	}
 
 	/// Signs given message with supplied token. Returns a token to use in next signing within this session.
-	pub fn sign_with_token(&self, address: Address, token: AccountToken, message: Message) -> Result<(Signature, AccountToken), SignError> {
+	///
+	/// The operation is recorded in the key usage log of the account.
+	pub fn sign_with_token(&self, address: Address, token: AccountToken, message: Message, operation: KeyOperation, origin: &str)
+		-> Result<(Signature, AccountToken), SignError>
+	{
+		let result = self.sign_message_with_token(address, token, message);
+		self.note_key_usage(address, operation, origin, &result);
+		result
+	}
+
+	fn sign_message_with_token(&self, address: Address, token: AccountToken, message: Message) -> Result<(Signature, AccountToken), SignError> {
 		let account = self.sstore.account_ref(&address)?;
 		let is_std_password = self.sstore.test_password(&account, &token)?;
 
@@ -704,7 +777,17 @@ impl AccountProvider {
 	}
 
 	/// Decrypts a message with given token. Returns a token to use in next operation for this account.
-	pub fn decrypt_with_token(&self, address: Address, token: AccountToken, shared_mac: &[u8], message: &[u8])
+	///
+	/// The operation is recorded in the key usage log of the account.
+	pub fn decrypt_with_token(&self, address: Address, token: AccountToken, shared_mac: &[u8], message: &[u8], origin: &str)
+		-> Result<(Vec<u8>, AccountToken), SignError>
+	{
+		let result = self.decrypt_message_with_token(address, token, shared_mac, message);
+		self.note_key_usage(address, KeyOperation::Decrypt, origin, &result);
+		result
+	}
+
+	fn decrypt_message_with_token(&self, address: Address, token: AccountToken, shared_mac: &[u8], message: &[u8])
 		-> Result<(Vec<u8>, AccountToken), SignError>
 	{
 		let account = self.sstore.account_ref(&address)?;
@@ -727,17 +810,40 @@ impl AccountProvider {
 	}
 
 	/// Decrypts a message. If password is not provided the account must be unlocked.
-	pub fn decrypt(&self, address: Address, password: Option<String>, shared_mac: &[u8], message: &[u8]) -> Result<Vec<u8>, SignError> {
-		let account = self.sstore.account_ref(&address)?;
-		let password = password.map(Ok).unwrap_or_else(|| self.password(&account))?;
-		Ok(self.sstore.decrypt(&account, &password, shared_mac, message)?)
+	///
+	/// The operation is recorded in the key usage log of the account.
+	pub fn decrypt(&self, address: Address, password: Option<String>, shared_mac: &[u8], message: &[u8], origin: &str) -> Result<Vec<u8>, SignError> {
+		let result = self.sstore.account_ref(&address)
+			.map_err(Into::into)
+			.and_then(|account| {
+				let password = password.map(Ok).unwrap_or_else(|| self.password(&account))?;
+				Ok(self.sstore.decrypt(&account, &password, shared_mac, message)?)
+			});
+		self.note_key_usage(address, KeyOperation::Decrypt, origin, &result);
+		result
 	}
 
 	/// Agree on shared key.
-	pub fn agree(&self, address: Address, password: Option<String>, other_public: &Public) -> Result<Secret, SignError> {
-		let account = self.sstore.account_ref(&address)?;
-		let password = password.map(Ok).unwrap_or_else(|| self.password(&account))?;
-		Ok(self.sstore.agree(&account, &password, other_public)?)
+	///
+	/// The operation is recorded in the key usage log of the account.
+	pub fn agree(&self, address: Address, password: Option<String>, other_public: &Public, origin: &str) -> Result<Secret, SignError> {
+		let result = self.sstore.account_ref(&address)
+			.map_err(Into::into)
+			.and_then(|account| {
+				let password = password.map(Ok).unwrap_or_else(|| self.password(&account))?;
+				Ok(self.sstore.agree(&account, &password, other_public)?)
+			});
+		self.note_key_usage(address, KeyOperation::Agree, origin, &result);
+		result
+	}
+
+	fn note_key_usage<T, E>(&self, address: Address, operation: KeyOperation, origin: &str, result: &Result<T, E>) {
+		self.usage_log.lock().note(address, operation, result.is_ok(), origin.into());
+	}
+
+	/// Returns recorded key usage, either of all accounts or of the given one only.
+	pub fn key_usage(&self, address: Option<&Address>) -> Vec<KeyUsage> {
+		self.usage_log.lock().entries(address)
 	}
 
 	/// Returns the underlying `SecretStore` reference if one exists.
@@ -810,7 +916,11 @@ impl AccountProvider {
 	}
 
 	/// Sign transaction with hardware wallet.
-	pub fn sign_with_hardware(&self, address: Address, transaction: &Transaction, chain_id: Option<u64>, rlp_encoded_transaction: &[u8]) -> Result<Signature, SignError> {
+	///
+	/// The operation is recorded in the key usage log of the account.
+	pub fn sign_with_hardware(&self, address: Address, transaction: &Transaction, chain_id: Option<u64>, rlp_encoded_transaction: &[u8], origin: &str)
+		-> Result<Signature, SignError>
+	{
 		let t_info = TransactionInfo {
 			nonce: transaction.nonce,
 			gas_price: transaction.gas_price,
@@ -823,22 +933,46 @@ impl AccountProvider {
 			data: transaction.data.to_vec(),
 			chain_id: chain_id,
 		};
-		match self.hardware_store.as_ref().map(|s| s.sign_transaction(&address, &t_info, rlp_encoded_transaction)) {
+		let result = match self.hardware_store.as_ref().map(|s| s.sign_transaction(&address, &t_info, rlp_encoded_transaction)) {
 			None | Some(Err(HardwareError::KeyNotFound)) => Err(SignError::NotFound),
 			Some(Err(e)) => Err(From::from(e)),
 			Some(Ok(s)) => Ok(s),
-		}
+		};
+		self.note_key_usage(address, KeyOperation::SignTransaction, origin, &result);
+		result
 	}
 }
 
 #[cfg(test)]
 mod tests {
-	use super::{AccountProvider, Unlock, DappId};
+	use super::{AccountProvider, Unlock, DappId, RemoteSigner, RemoteSignerError, Signature, KeyOperation};
 	use std::time::{Duration, Instant};
-	use ethstore::ethkey::{Generator, Random, Address};
+	use ethstore::ethkey::{Generator, Random, Address, Message};
 	use ethstore::{StoreAccountRef, Derivation};
 	use ethereum_types::H256;
 
+	struct StaticRemoteSigner(Address);
+
+	impl RemoteSigner for StaticRemoteSigner {
+		fn accounts(&self) -> Vec<Address> {
+			vec![self.0]
+		}
+
+		fn sign(&self, address: &Address, _message: &Message) -> Result<Signature, RemoteSignerError> {
+			if *address == self.0 { Ok(Signature::from([1u8; 65])) } else { Err(RemoteSignerError::UnknownAccount(*address)) }
+		}
+	}
+
+	#[test]
+	fn sign_with_remote_signer() {
+		let remote = Address::from(0x42);
+		let ap = AccountProvider::transient_provider().with_remote_signer(Box::new(StaticRemoteSigner(remote)));
+		assert_eq!(ap.remote_accounts(), vec![remote]);
+		assert!(ap.is_remote_address(&remote));
+		assert_eq!(ap.sign(remote, None, Default::default(), KeyOperation::SignMessage, "test").unwrap(), Signature::from([1u8; 65]));
+		assert!(ap.sign(Address::from(0x43), None, Default::default(), KeyOperation::SignMessage, "test").is_err());
+	}
+
 	#[test]
 	fn unlock_account_temp() {
 		let kp = Random.generate().unwrap();
@@ -846,8 +980,8 @@ mod tests {
 		assert!(ap.insert_account(kp.secret().clone(), "test").is_ok());
 		assert!(ap.unlock_account_temporarily(kp.address(), "test1".into()).is_err());
 		assert!(ap.unlock_account_temporarily(kp.address(), "test".into()).is_ok());
-		assert!(ap.sign(kp.address(), None, Default::default()).is_ok());
-		assert!(ap.sign(kp.address(), None, Default::default()).is_err());
+		assert!(ap.sign(kp.address(), None, Default::default(), KeyOperation::SignMessage, "test").is_ok());
+		assert!(ap.sign(kp.address(), None, Default::default(), KeyOperation::SignMessage, "test").is_err());
 	}
 
 	#[test]
@@ -906,13 +1040,15 @@ mod tests {
 			.expect("Should be ok because account is saved and password is valid");
 
 		let msg = Default::default();
-		let signed_msg1 = ap.sign(derived_addr, None, msg)
+		let signed_msg1 = ap.sign(derived_addr, None, msg, KeyOperation::SignMessage, "test")
 			.expect("Signing with existing unlocked account should not fail");
 		let signed_msg2 = ap.sign_derived(
 			&kp.address(),
 			None,
 			Derivation::SoftHash(H256::from(1999)),
 			msg,
+			KeyOperation::SignMessage,
+			"test",
 		).expect("Derived signing with existing unlocked account should not fail");
 
 		assert_eq!(signed_msg1, signed_msg2,
@@ -926,11 +1062,11 @@ mod tests {
 		assert!(ap.insert_account(kp.secret().clone(), "test").is_ok());
 		assert!(ap.unlock_account_permanently(kp.address(), "test1".into()).is_err());
 		assert!(ap.unlock_account_permanently(kp.address(), "test".into()).is_ok());
-		assert!(ap.sign(kp.address(), None, Default::default()).is_ok());
-		assert!(ap.sign(kp.address(), None, Default::default()).is_ok());
+		assert!(ap.sign(kp.address(), None, Default::default(), KeyOperation::SignMessage, "test").is_ok());
+		assert!(ap.sign(kp.address(), None, Default::default(), KeyOperation::SignMessage, "test").is_ok());
 		assert!(ap.unlock_account_temporarily(kp.address(), "test".into()).is_ok());
-		assert!(ap.sign(kp.address(), None, Default::default()).is_ok());
-		assert!(ap.sign(kp.address(), None, Default::default()).is_ok());
+		assert!(ap.sign(kp.address(), None, Default::default(), KeyOperation::SignMessage, "test").is_ok());
+		assert!(ap.sign(kp.address(), None, Default::default(), KeyOperation::SignMessage, "test").is_ok());
 	}
 
 	#[test]
@@ -940,9 +1076,9 @@ mod tests {
 		assert!(ap.insert_account(kp.secret().clone(), "test").is_ok());
 		assert!(ap.unlock_account_timed(kp.address(), "test1".into(), Duration::from_secs(60)).is_err());
 		assert!(ap.unlock_account_timed(kp.address(), "test".into(), Duration::from_secs(60)).is_ok());
-		assert!(ap.sign(kp.address(), None, Default::default()).is_ok());
+		assert!(ap.sign(kp.address(), None, Default::default(), KeyOperation::SignMessage, "test").is_ok());
 		ap.unlocked.write().get_mut(&StoreAccountRef::root(kp.address())).unwrap().unlock = Unlock::Timed(Instant::now());
-		assert!(ap.sign(kp.address(), None, Default::default()).is_err());
+		assert!(ap.sign(kp.address(), None, Default::default(), KeyOperation::SignMessage, "test").is_err());
 	}
 
 	#[test]
@@ -953,12 +1089,33 @@ mod tests {
 		assert!(ap.insert_account(kp.secret().clone(), "test").is_ok());
 
 		// when
-		let (_signature, token) = ap.sign_with_token(kp.address(), "test".into(), Default::default()).unwrap();
+		let (_signature, token) = ap.sign_with_token(kp.address(), "test".into(), Default::default(), KeyOperation::SignMessage, "test").unwrap();
 
 		// then
-		ap.sign_with_token(kp.address(), token.clone(), Default::default())
+		ap.sign_with_token(kp.address(), token.clone(), Default::default(), KeyOperation::SignMessage, "test")
 			.expect("First usage of token should be correct.");
-		assert!(ap.sign_with_token(kp.address(), token, Default::default()).is_err(), "Second usage of the same token should fail.");
+		assert!(ap.sign_with_token(kp.address(), token, Default::default(), KeyOperation::SignMessage, "test").is_err(), "Second usage of the same token should fail.");
+	}
+
+	#[test]
+	fn should_record_key_usage() {
+		// given
+		let kp = Random.generate().unwrap();
+		let ap = AccountProvider::transient_provider();
+		assert!(ap.insert_account(kp.secret().clone(), "test").is_ok());
+
+		// when
+		let (_signature, token) = ap.sign_with_token(kp.address(), "test".into(), Default::default(), KeyOperation::SignTransaction, "IPC").unwrap();
+		assert!(ap.decrypt_with_token(kp.address(), token, &[], &[], "IPC").is_err());
+		assert!(ap.sign_derived(&kp.address(), Some("test".into()), Derivation::SoftHash(H256::from(1)), Default::default(), KeyOperation::SignMessage, "RPC").is_ok());
+
+		// then
+		let usage = ap.key_usage(Some(&kp.address()));
+		assert_eq!(usage.iter().map(|u| (u.operation, u.success, &*u.origin)).collect::<Vec<_>>(), vec![
+			(KeyOperation::SignTransaction, true, "IPC"),
+			(KeyOperation::Decrypt, false, "IPC"),
+			(KeyOperation::SignMessage, true, "RPC"),
+		]);
 	}
 
 	#[test]
@@ -0,0 +1,122 @@
+// Copyright 2015-2017 Parity Technologies (UK) Ltd.
+// This file is part of Parity.
+
+// Parity is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// Parity is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with Parity.  If not, see <http://www.gnu.org/licenses/>.
+
+//! Remote keystore backends: signing without holding private keys locally.
+
+use std::fmt;
+use std::io::Read;
+use std::str::FromStr;
+use futures::Future;
+use fetch::{self, Fetch, Client as FetchClient, BodyReader, Request, Url};
+use ethstore::ethkey::{Address, Message, Signature};
+
+/// Remote signer error.
+#[derive(Debug)]
+pub enum RemoteSignerError {
+	/// Remote signer does not manage given account.
+	UnknownAccount(Address),
+	/// Failed to reach the remote signer.
+	Transport(String),
+	/// Remote signer refused the request.
+	Rejected(String),
+	/// Remote signer returned malformed response.
+	InvalidResponse(String),
+}
+
+impl fmt::Display for RemoteSignerError {
+	fn fmt(&self, f: &mut fmt::Formatter) -> Result<(), fmt::Error> {
+		match *self {
+			RemoteSignerError::UnknownAccount(ref address) => write!(f, "Account {} is not managed by the remote signer", address),
+			RemoteSignerError::Transport(ref e) => write!(f, "Remote signer unreachable: {}", e),
+			RemoteSignerError::Rejected(ref e) => write!(f, "Remote signer rejected the request: {}", e),
+			RemoteSignerError::InvalidResponse(ref e) => write!(f, "Invalid remote signer response: {}", e),
+		}
+	}
+}
+
+impl From<fetch::Error> for RemoteSignerError {
+	fn from(e: fetch::Error) -> Self {
+		RemoteSignerError::Transport(format!("{:?}", e))
+	}
+}
+
+/// Keystore backend producing signatures for accounts whose keys are held elsewhere.
+pub trait RemoteSigner: Send + Sync {
+	/// Accounts this signer can sign for.
+	fn accounts(&self) -> Vec<Address>;
+
+	/// Signs the message with the key of given account.
+	fn sign(&self, address: &Address, message: &Message) -> Result<Signature, RemoteSignerError>;
+}
+
+/// Configuration of `HttpRemoteSigner`.
+#[derive(Debug, Clone, PartialEq)]
+pub struct HttpRemoteSignerConfig {
+	/// Base URL of the signer service.
+	pub base_url: String,
+	/// Token sent as `Authorization: Bearer <token>` with every request.
+	pub auth_token: String,
+	/// Accounts managed by the signer service.
+	pub accounts: Vec<Address>,
+}
+
+/// Remote signer talking to a signing service over token-authenticated HTTP(S).
+///
+/// Signing is a `POST <base_url>/sign/<address>/<message>` request
+/// answered with a hex-encoded (optionally quoted) 65-byte signature.
+pub struct HttpRemoteSigner {
+	config: HttpRemoteSignerConfig,
+	client: FetchClient,
+}
+
+impl HttpRemoteSigner {
+	/// Creates new remote signer.
+	pub fn new(config: HttpRemoteSignerConfig, client: FetchClient) -> Self {
+		HttpRemoteSigner {
+			config: config,
+			client: client,
+		}
+	}
+}
+
+impl RemoteSigner for HttpRemoteSigner {
+	fn accounts(&self) -> Vec<Address> {
+		self.config.accounts.clone()
+	}
+
+	fn sign(&self, address: &Address, message: &Message) -> Result<Signature, RemoteSignerError> {
+		if !self.config.accounts.contains(address) {
+			return Err(RemoteSignerError::UnknownAccount(*address));
+		}
+
+		let url = format!("{}/sign/{:x}/{:x}", self.config.base_url.trim_right_matches('/'), address, message);
+		let url = Url::from_str(&url).map_err(|e| RemoteSignerError::Transport(e.to_string()))?;
+		let mut request = Request::post(url);
+		request.headers_mut().set_raw("Authorization", format!("Bearer {}", self.config.auth_token));
+
+		let response = self.client.fetch(request, Default::default()).wait()?;
+		if !response.is_success() {
+			return Err(RemoteSignerError::Rejected(response.status().canonical_reason().unwrap_or("unknown").into()));
+		}
+
+		let mut result = String::new();
+		BodyReader::new(response).read_to_string(&mut result)
+			.map_err(|e| RemoteSignerError::Transport(e.to_string()))?;
+
+		result.trim().trim_matches('\"').trim_left_matches("0x").parse()
+			.map_err(|e| RemoteSignerError::InvalidResponse(format!("{}", e)))
+	}
+}
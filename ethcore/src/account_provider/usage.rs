@@ -0,0 +1,222 @@
+// Copyright 2015-2018 Parity Technologies (UK) Ltd.
+// This file is part of Parity.
+
+// Parity is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// Parity is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with Parity.  If not, see <http://www.gnu.org/licenses/>.
+
+//! Append-only log of key usage.
+//!
+//! Every line of the log records a single operation:
+//! `<unix timestamp>\t<address>\t<operation>\t<ok|failed>\t<origin>`.
+
+use std::{fmt, fs};
+use std::io::{BufRead, BufReader, Write};
+use std::path::{Path, PathBuf};
+use std::str::FromStr;
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use ethstore::ethkey::Address;
+
+const LOG_FILE: &'static str = "key_usage.log";
+
+/// Kind of operation performed with an account key.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum KeyOperation {
+	/// Signing of a transaction.
+	SignTransaction,
+	/// Signing of a message or raw hash.
+	SignMessage,
+	/// Decryption of a message.
+	Decrypt,
+	/// Key agreement with another public key.
+	Agree,
+}
+
+impl fmt::Display for KeyOperation {
+	fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+		match *self {
+			KeyOperation::SignTransaction => write!(f, "sign_transaction"),
+			KeyOperation::SignMessage => write!(f, "sign_message"),
+			KeyOperation::Decrypt => write!(f, "decrypt"),
+			KeyOperation::Agree => write!(f, "agree"),
+		}
+	}
+}
+
+impl FromStr for KeyOperation {
+	type Err = String;
+
+	fn from_str(s: &str) -> Result<Self, Self::Err> {
+		match s {
+			"sign_transaction" => Ok(KeyOperation::SignTransaction),
+			"sign_message" => Ok(KeyOperation::SignMessage),
+			"decrypt" => Ok(KeyOperation::Decrypt),
+			"agree" => Ok(KeyOperation::Agree),
+			other => Err(format!("Unknown key operation: {}", other)),
+		}
+	}
+}
+
+/// Single entry of the key usage log.
+#[derive(Debug, Clone, PartialEq)]
+pub struct KeyUsage {
+	/// Unix timestamp (in seconds) of the operation.
+	pub timestamp: u64,
+	/// Account whose key was used.
+	pub address: Address,
+	/// Operation performed.
+	pub operation: KeyOperation,
+	/// Whether the operation succeeded.
+	pub success: bool,
+	/// Transport and session the request originated from.
+	pub origin: String,
+}
+
+impl KeyUsage {
+	fn to_line(&self) -> String {
+		// keep every entry on a single line with a fixed number of columns.
+		let origin: String = self.origin.chars().map(|c| if c == '\t' || c == '\n' || c == '\r' { ' ' } else { c }).collect();
+		format!("{}\t{:x}\t{}\t{}\t{}\n", self.timestamp, self.address, self.operation, if self.success { "ok" } else { "failed" }, origin)
+	}
+
+	fn from_line(line: &str) -> Option<KeyUsage> {
+		let mut columns = line.splitn(5, '\t');
+		let timestamp = columns.next()?.parse().ok()?;
+		let address = columns.next()?.parse().ok()?;
+		let operation = columns.next()?.parse().ok()?;
+		let success = match columns.next()? {
+			"ok" => true,
+			"failed" => false,
+			_ => return None,
+		};
+		let origin = columns.next()?.to_owned();
+
+		Some(KeyUsage { timestamp, address, operation, success, origin })
+	}
+}
+
+/// Append-only key usage log. Uses a plain text file in the keys directory.
+pub struct KeyUsageLog {
+	path: Option<PathBuf>,
+	transient: Vec<KeyUsage>,
+}
+
+impl KeyUsageLog {
+	/// Opens the log in given directory.
+	pub fn new(path: &Path) -> Self {
+		KeyUsageLog {
+			path: Some(path.join(LOG_FILE)),
+			transient: Vec::new(),
+		}
+	}
+
+	/// Creates transient log (entries are kept in memory only).
+	pub fn transient() -> Self {
+		KeyUsageLog {
+			path: None,
+			transient: Vec::new(),
+		}
+	}
+
+	/// Records an operation performed now.
+	pub fn note(&mut self, address: Address, operation: KeyOperation, success: bool, origin: String) {
+		let timestamp = SystemTime::now().duration_since(UNIX_EPOCH).map(|d| d.as_secs()).unwrap_or(0);
+		self.append(KeyUsage { timestamp, address, operation, success, origin });
+	}
+
+	fn append(&mut self, entry: KeyUsage) {
+		let path = match self.path {
+			Some(ref path) => path,
+			None => {
+				self.transient.push(entry);
+				return;
+			},
+		};
+
+		let res = fs::OpenOptions::new()
+			.create(true)
+			.append(true)
+			.open(path)
+			.and_then(|mut file| file.write_all(entry.to_line().as_bytes()));
+		if let Err(e) = res {
+			warn!(target: "account_provider", "Could not write key usage log {}: {}", path.display(), e);
+		}
+	}
+
+	/// Returns all recorded entries, optionally only those of given account.
+	pub fn entries(&self, address: Option<&Address>) -> Vec<KeyUsage> {
+		let matches = |entry: &KeyUsage| address.map_or(true, |a| entry.address == *a);
+		let path = match self.path {
+			Some(ref path) => path,
+			None => return self.transient.iter().filter(|e| matches(e)).cloned().collect(),
+		};
+
+		let file = match fs::File::open(path) {
+			Ok(file) => file,
+			Err(_) => return Vec::new(),
+		};
+
+		BufReader::new(file).lines()
+			.filter_map(|line| line.ok())
+			.filter_map(|line| {
+				let entry = KeyUsage::from_line(&line);
+				if entry.is_none() {
+					warn!(target: "account_provider", "Skipping malformed key usage log entry: {}", line);
+				}
+				entry
+			})
+			.filter(|e| matches(e))
+			.collect()
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use tempdir::TempDir;
+	use ethstore::ethkey::Address;
+	use super::{KeyUsageLog, KeyOperation};
+
+	#[test]
+	fn appends_and_reads_entries() {
+		let tempdir = TempDir::new("").unwrap();
+		let a = Address::from(1);
+		let b = Address::from(2);
+		{
+			let mut log = KeyUsageLog::new(tempdir.path());
+			log.note(a, KeyOperation::SignTransaction, true, "Dapp\twallet".into());
+			log.note(b, KeyOperation::Decrypt, false, "IPC (session: 0x0)".into());
+		}
+
+		let mut log = KeyUsageLog::new(tempdir.path());
+		log.note(a, KeyOperation::SignMessage, false, "unknown origin".into());
+
+		let entries = log.entries(Some(&a));
+		assert_eq!(entries.len(), 2);
+		assert_eq!(entries[0].operation, KeyOperation::SignTransaction);
+		assert!(entries[0].success);
+		assert_eq!(entries[0].origin, "Dapp wallet");
+		assert_eq!(entries[1].operation, KeyOperation::SignMessage);
+		assert!(!entries[1].success);
+
+		assert_eq!(log.entries(None).len(), 3);
+	}
+
+	#[test]
+	fn transient_log_keeps_entries_in_memory() {
+		let mut log = KeyUsageLog::transient();
+		log.note(Address::from(1), KeyOperation::Decrypt, true, "C API".into());
+
+		assert_eq!(log.entries(None).len(), 1);
+		assert!(log.entries(Some(&Address::from(2))).is_empty());
+	}
+}
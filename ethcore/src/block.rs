@@ -94,6 +94,8 @@ pub struct ExecutedBlock {
 	state: State<StateDB>,
 	traces: Tracing,
 	last_hashes: Arc<LastHashes>,
+	// address credited as the block author during execution.
+	executive_author: Address,
 	is_finalized: bool,
 	metadata: Option<Vec<u8>>,
 }
@@ -114,6 +116,7 @@ impl ExecutedBlock {
 				Tracing::Disabled
 			},
 			last_hashes: last_hashes,
+			executive_author: Default::default(),
 			is_finalized: false,
 			metadata: None,
 		}
@@ -124,7 +127,7 @@ impl ExecutedBlock {
 		// TODO: memoise.
 		EnvInfo {
 			number: self.header.number(),
-			author: self.header.author().clone(),
+			author: self.executive_author,
 			timestamp: self.header.timestamp(),
 			difficulty: self.header.difficulty().clone(),
 			last_hashes: self.last_hashes.clone(),
@@ -302,6 +305,7 @@ impl<'x> OpenBlock<'x> {
 
 		engine.machine().populate_from_parent(&mut r.block.header, parent, gas_floor_target, gas_ceil_target);
 		engine.populate_from_parent(&mut r.block.header, parent);
+		r.block.executive_author = engine.executive_author(&r.block.header);
 
 		engine.machine().on_new_block(&mut r.block)?;
 		engine.on_new_block(&mut r.block, is_epoch_begin, ancestry)?;
@@ -407,6 +411,7 @@ impl<'x> OpenBlock<'x> {
 		} else {
 			self.block.header.set_extra_data(header.extra_data().clone());
 		}
+		self.block.executive_author = self.engine.executive_author(header);
 	}
 
 	/// Turn this into a `ClosedBlock`.
@@ -19,10 +19,11 @@
 use std::collections::{HashMap, HashSet, hash_map};
 use std::sync::Arc;
 use std::mem;
+use std::ops::Deref;
 use itertools::Itertools;
 use bloomchain as bc;
 use heapsize::HeapSizeOf;
-use ethereum_types::{H256, Bloom, U256};
+use ethereum_types::{H256, Bloom, U256, Address};
 use parking_lot::{Mutex, RwLock};
 use bytes::Bytes;
 use rlp::RlpStream;
@@ -35,7 +36,7 @@ use receipt::Receipt;
 use blooms::{BloomGroup, GroupPosition};
 use blockchain::best_block::{BestBlock, BestAncientBlock};
 use blockchain::block_info::{BlockInfo, BlockLocation, BranchBecomingCanonChainData};
-use blockchain::extras::{BlockReceipts, BlockDetails, TransactionAddress, EPOCH_KEY_PREFIX, EpochTransitions};
+use blockchain::extras::{BlockReceipts, BlockDetails, TransactionAddress, EPOCH_KEY_PREFIX, EpochTransitions, SenderNonce, SenderTransactions, BlockSenders};
 use types::blockchain_info::BlockChainInfo;
 use types::tree_route::TreeRoute;
 use blockchain::update::{ExtrasUpdate, ExtrasInsert};
@@ -48,6 +49,8 @@ use engines::epoch::{Transition as EpochTransition, PendingTransition as Pending
 use rayon::prelude::*;
 use ansi_term::Colour;
 use kvdb::{DBTransaction, KeyValueDB};
+use memory_cache::{AtomicCacheStats, CacheStats};
+use memory_cache::pressure::Shed;
 
 const LOG_BLOOMS_LEVELS: usize = 3;
 const LOG_BLOOMS_ELEMENTS_PER_INDEX: usize = 16;
@@ -173,7 +176,7 @@ enum CacheId {
 impl bc::group::BloomGroupDatabase for BlockChain {
 	fn blooms_at(&self, position: &bc::group::GroupPosition) -> Option<bc::group::BloomGroup> {
 		let position = GroupPosition::from(position.clone());
-		let result = self.db.read_with_cache(db::COL_EXTRA, &self.blocks_blooms, &position).map(Into::into);
+		let result = self.db.read_with_cache_stats(db::COL_EXTRA, &self.blocks_blooms, &position, &self.cache_stats).map(Into::into);
 		self.cache_man.lock().note_used(CacheId::BlocksBlooms(position));
 		result
 	}
@@ -185,6 +188,8 @@ impl bc::group::BloomGroupDatabase for BlockChain {
 pub struct BlockChain {
 	// All locks must be captured in the order declared here.
 	blooms_config: bc::Config,
+	// number of recent blocks whose transactions are indexed by sender and nonce.
+	sender_index_history: u64,
 
 	best_block: RwLock<BestBlock>,
 	// Stores best block of the first uninterrupted sequence of blocks. `None` if there are no gaps.
@@ -213,6 +218,17 @@ pub struct BlockChain {
 	pending_block_hashes: RwLock<HashMap<BlockNumber, H256>>,
 	pending_block_details: RwLock<HashMap<H256, BlockDetails>>,
 	pending_transaction_addresses: RwLock<HashMap<H256, Option<TransactionAddress>>>,
+
+	// lookups of all caches since the stats were last taken.
+	cache_stats: AtomicCacheStats,
+}
+
+impl Shed for BlockChain {
+	fn shed(&self, percent: usize) -> usize {
+		let before = self.cache_size().total();
+		self.evict_cached(|cache_man, evict| cache_man.collect_oldest(percent, evict));
+		before.saturating_sub(self.cache_size().total())
+	}
 }
 
 impl BlockProvider for BlockChain {
@@ -247,9 +263,11 @@ impl BlockProvider for BlockChain {
 		{
 			let read = self.block_headers.read();
 			if let Some(v) = read.get(hash) {
+				self.cache_stats.note_hit();
 				return Some(v.clone());
 			}
 		}
+		self.cache_stats.note_miss();
 
 		// Check if it's the best block
 		{
@@ -277,9 +295,11 @@ impl BlockProvider for BlockChain {
 		{
 			let read = self.block_bodies.read();
 			if let Some(v) = read.get(hash) {
+				self.cache_stats.note_hit();
 				return Some(v.clone());
 			}
 		}
+		self.cache_stats.note_miss();
 
 		// Check if it's the best block
 		{
@@ -303,28 +323,28 @@ impl BlockProvider for BlockChain {
 
 	/// Get the familial details concerning a block.
 	fn block_details(&self, hash: &H256) -> Option<BlockDetails> {
-		let result = self.db.read_with_cache(db::COL_EXTRA, &self.block_details, hash)?;
+		let result = self.db.read_with_cache_stats(db::COL_EXTRA, &self.block_details, hash, &self.cache_stats)?;
 		self.cache_man.lock().note_used(CacheId::BlockDetails(*hash));
 		Some(result)
 	}
 
 	/// Get the hash of given block's number.
 	fn block_hash(&self, index: BlockNumber) -> Option<H256> {
-		let result = self.db.read_with_cache(db::COL_EXTRA, &self.block_hashes, &index)?;
+		let result = self.db.read_with_cache_stats(db::COL_EXTRA, &self.block_hashes, &index, &self.cache_stats)?;
 		self.cache_man.lock().note_used(CacheId::BlockHashes(index));
 		Some(result)
 	}
 
 	/// Get the address of transaction with given hash.
 	fn transaction_address(&self, hash: &H256) -> Option<TransactionAddress> {
-		let result = self.db.read_with_cache(db::COL_EXTRA, &self.transaction_addresses, hash)?;
+		let result = self.db.read_with_cache_stats(db::COL_EXTRA, &self.transaction_addresses, hash, &self.cache_stats)?;
 		self.cache_man.lock().note_used(CacheId::TransactionAddresses(*hash));
 		Some(result)
 	}
 
 	/// Get receipts of block with given hash.
 	fn block_receipts(&self, hash: &H256) -> Option<BlockReceipts> {
-		let result = self.db.read_with_cache(db::COL_EXTRA, &self.block_receipts, hash)?;
+		let result = self.db.read_with_cache_stats(db::COL_EXTRA, &self.block_receipts, hash, &self.cache_stats)?;
 		self.cache_man.lock().note_used(CacheId::BlockReceipts(*hash));
 		Some(result)
 	}
@@ -496,6 +516,121 @@ impl<'a> Iterator for EpochTransitionIter<'a> {
 	}
 }
 
+/// Position of a walk over the canonical chain: the last block visited.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct CanonCursor {
+	/// Number of the block.
+	pub number: BlockNumber,
+	/// Hash of the block.
+	pub hash: H256,
+}
+
+/// Item of a walk over the canonical chain.
+#[derive(Debug, Clone)]
+pub enum CanonItem {
+	/// Next canonical block.
+	Block {
+		/// Header of the block.
+		header: encoded::Header,
+		/// Body of the block.
+		body: encoded::Body,
+		/// Receipts of the block.
+		receipts: BlockReceipts,
+	},
+	/// The blocks visited after `ancestor` are not canonical anymore.
+	/// The walk continues with the new canonical block following it.
+	Reorg {
+		/// Latest visited block which is still canonical.
+		ancestor: CanonCursor,
+	},
+}
+
+/// An iterator which walks the canonical chain up to some block, checking before every step
+/// that the blocks already visited are still canonical.
+///
+/// Ends at the last block or at the first block whose data is missing, e.g. in the gap
+/// left by warp sync.
+pub struct CanonIter<C> {
+	chain: C,
+	cursor: Option<CanonCursor>,
+	next: BlockNumber,
+	last: BlockNumber,
+}
+
+impl<C> CanonIter<C> where C: Deref<Target = BlockChain> {
+	/// Iterator over the canonical blocks of `chain` from `first` to `last`, inclusive.
+	pub fn new(chain: C, first: BlockNumber, last: BlockNumber) -> Self {
+		CanonIter {
+			chain,
+			cursor: None,
+			next: first,
+			last,
+		}
+	}
+
+	/// Iterator over the canonical blocks of `chain` following `cursor`, up to `last`.
+	/// Starts with a `CanonItem::Reorg` if the block at `cursor` isn't canonical anymore.
+	pub fn after(chain: C, cursor: CanonCursor, last: BlockNumber) -> Self {
+		CanonIter {
+			chain,
+			cursor: Some(cursor),
+			next: cursor.number + 1,
+			last,
+		}
+	}
+
+	/// Last visited block, to resume the walk from with `CanonIter::after`.
+	pub fn cursor(&self) -> Option<CanonCursor> {
+		self.cursor
+	}
+
+	// latest ancestor of the cursor which is still canonical.
+	fn common_ancestor(&self, cursor: &CanonCursor) -> Option<CanonCursor> {
+		let mut current = cursor.hash;
+		loop {
+			let details = self.chain.block_details(&current)?;
+			if self.chain.block_hash(details.number) == Some(current) {
+				return Some(CanonCursor { number: details.number, hash: current });
+			}
+			current = details.parent;
+		}
+	}
+}
+
+impl<C> Iterator for CanonIter<C> where C: Deref<Target = BlockChain> {
+	type Item = CanonItem;
+
+	fn next(&mut self) -> Option<CanonItem> {
+		if let Some(cursor) = self.cursor {
+			if self.chain.block_hash(cursor.number) != Some(cursor.hash) {
+				let ancestor = match self.common_ancestor(&cursor) {
+					Some(ancestor) => ancestor,
+					None => {
+						self.next = self.last + 1;
+						return None;
+					},
+				};
+				self.cursor = Some(ancestor);
+				self.next = ancestor.number + 1;
+				return Some(CanonItem::Reorg { ancestor });
+			}
+		}
+
+		if self.next > self.last {
+			return None;
+		}
+
+		let hash = self.chain.block_hash(self.next)?;
+		let header = self.chain.block_header_data(&hash)?;
+		let body = self.chain.block_body(&hash)?;
+		let receipts = self.chain.block_receipts(&hash)?;
+
+		self.cursor = Some(CanonCursor { number: self.next, hash });
+		self.next += 1;
+		Some(CanonItem::Block { header, body, receipts })
+	}
+}
+
 impl BlockChain {
 	/// Create new instance of blockchain from given Genesis.
 	pub fn new(config: Config, genesis: &[u8], db: Arc<KeyValueDB>) -> BlockChain {
@@ -507,6 +642,7 @@ impl BlockChain {
 				levels: LOG_BLOOMS_LEVELS,
 				elements_per_index: LOG_BLOOMS_ELEMENTS_PER_INDEX,
 			},
+			sender_index_history: config.sender_index_history,
 			first_block: None,
 			best_block: RwLock::new(BestBlock {
 				// BestBlock will be overwritten anyway.
@@ -528,6 +664,7 @@ impl BlockChain {
 			pending_block_hashes: RwLock::new(HashMap::new()),
 			pending_block_details: RwLock::new(HashMap::new()),
 			pending_transaction_addresses: RwLock::new(HashMap::new()),
+			cache_stats: AtomicCacheStats::default(),
 		};
 
 		// load best block
@@ -763,6 +900,16 @@ impl BlockChain {
 		batch.put(db::COL_HEADERS, &hash, &compressed_header);
 		batch.put(db::COL_BODIES, &hash, &compressed_body);
 
+		// ancient and restored blocks are indexed only if they are within the indexed history.
+		// those are pruned once newer blocks are imported.
+		if header.number() + self.sender_index_history > self.best_block_number() {
+			let mut index = HashMap::new();
+			self.add_transaction_senders(batch, &mut index, &hash, block.transactions());
+			for (key, value) in index {
+				batch.write(db::COL_EXTRA, &key, &value);
+			}
+		}
+
 		let maybe_parent = self.block_details(&header.parent_hash());
 
 		if let Some(parent_details) = maybe_parent {
@@ -989,6 +1136,8 @@ impl BlockChain {
 			);
 		}
 
+		self.prepare_transaction_senders_update(batch, bytes, &info);
+
 		self.prepare_update(batch, ExtrasUpdate {
 			block_hashes: self.prepare_block_hashes_update(bytes, &info),
 			block_details: self.prepare_block_details_update(bytes, &info, extras.is_finalized, extras.metadata),
@@ -1046,6 +1195,89 @@ impl BlockChain {
 		Some(())
 	}
 
+	/// Updates the sender and nonce index for a block inserted at given location.
+	///
+	/// Only canonical blocks are indexed: transactions of retracted blocks are removed from the index
+	/// and those of enacted blocks are added. Transactions of the canonical block falling out of the
+	/// indexed history are removed as well.
+	fn prepare_transaction_senders_update(&self, batch: &mut DBTransaction, bytes: &[u8], info: &BlockInfo) {
+		// reads don't see the batch, so all changes to the index are gathered before writing.
+		let mut index = HashMap::new();
+
+		match info.location {
+			BlockLocation::Branch => return,
+			BlockLocation::CanonChain => {},
+			BlockLocation::BranchBecomingCanonChain(ref data) => {
+				for hash in &data.retracted {
+					self.remove_transaction_senders(batch, &mut index, hash);
+				}
+				for hash in &data.enacted {
+					let transactions = self.block_body(hash).map_or_else(Vec::new, |body| body.transactions());
+					self.add_transaction_senders(batch, &mut index, hash, transactions);
+				}
+			},
+		}
+
+		self.add_transaction_senders(batch, &mut index, &info.hash, view!(BlockView, bytes).transactions());
+
+		if info.number > self.sender_index_history {
+			if let Some(hash) = self.block_hash(info.number - self.sender_index_history) {
+				self.remove_transaction_senders(batch, &mut index, &hash);
+			}
+		}
+
+		for (key, value) in index {
+			if value.hashes.is_empty() {
+				batch.delete(db::COL_EXTRA, &key);
+			} else {
+				batch.write(db::COL_EXTRA, &key, &value);
+			}
+		}
+	}
+
+	// add transactions of the block with given hash to the sender and nonce index.
+	fn add_transaction_senders(&self, batch: &mut DBTransaction, index: &mut HashMap<SenderNonce, SenderTransactions>, hash: &H256, transactions: Vec<UnverifiedTransaction>) {
+		let transactions: Vec<_> = transactions.into_par_iter()
+			.filter_map(|t| SignedTransaction::new(t).ok())
+			.collect();
+
+		let mut senders = Vec::with_capacity(transactions.len());
+		for t in transactions {
+			let key = SenderNonce { sender: t.sender(), nonce: t.nonce };
+			let value = index.entry(key).or_insert_with(|| self.db.read(db::COL_EXTRA, &key).unwrap_or_default());
+			if !value.hashes.contains(&t.hash()) {
+				value.hashes.push(t.hash());
+			}
+			senders.push(key);
+		}
+		batch.write(db::COL_EXTRA, hash, &BlockSenders { senders: senders });
+	}
+
+	// remove transactions of the block with given hash from the sender and nonce index.
+	fn remove_transaction_senders(&self, batch: &mut DBTransaction, index: &mut HashMap<SenderNonce, SenderTransactions>, hash: &H256) {
+		let block_senders: BlockSenders = match self.db.read(db::COL_EXTRA, hash) {
+			Some(block_senders) => block_senders,
+			None => return,
+		};
+		let removed: HashSet<_> = self.block_body(hash)
+			.map(|body| body.transaction_hashes().into_iter().collect())
+			.unwrap_or_default();
+
+		for key in block_senders.senders {
+			let value = index.entry(key).or_insert_with(|| self.db.read(db::COL_EXTRA, &key).unwrap_or_default());
+			value.hashes.retain(|hash| !removed.contains(hash));
+		}
+		batch.delete::<BlockSenders, _>(db::COL_EXTRA, hash);
+	}
+
+	/// Hash of the canonical transaction with given sender and nonce.
+	pub fn transaction_hash_by_sender_and_nonce(&self, sender: &Address, nonce: &U256) -> Option<H256> {
+		let key = SenderNonce { sender: *sender, nonce: *nonce };
+		let value: SenderTransactions = self.db.read(db::COL_EXTRA, &key)?;
+		// transactions of retracted blocks have no address.
+		value.hashes.into_iter().find(|hash| self.transaction_address(hash).is_some())
+	}
+
 	/// Prepares extras block detail update.
 	fn update_block_details(&self, batch: &mut DBTransaction, block_hash: H256, block_details: BlockDetails) {
 		let mut details_map = HashMap::new();
@@ -1188,6 +1420,17 @@ impl BlockChain {
 		}
 	}
 
+	/// Iterator over the canonical blocks from `first` to `last`, inclusive.
+	pub fn canon_iter(&self, first: BlockNumber, last: BlockNumber) -> CanonIter<&BlockChain> {
+		CanonIter::new(self, first, last)
+	}
+
+	/// Iterator over the canonical blocks following `cursor`, up to `last`.
+	/// Starts with a `CanonItem::Reorg` if the block at `cursor` isn't canonical anymore.
+	pub fn canon_iter_after(&self, cursor: CanonCursor, last: BlockNumber) -> CanonIter<&BlockChain> {
+		CanonIter::after(self, cursor, last)
+	}
+
 	/// Given a block's `parent`, find every block header which represents a valid possible uncle.
 	pub fn find_uncle_headers(&self, parent: &H256, uncle_generations: usize) -> Option<Vec<encoded::Header>> {
 		self.find_uncle_hashes(parent, uncle_generations)
@@ -1428,10 +1671,24 @@ impl BlockChain {
 		}
 	}
 
+	/// Change the preferred and maximal size of the cache, in bytes.
+	pub fn set_cache_size(&self, pref_cache_size: usize, max_cache_size: usize) {
+		self.cache_man.lock().set_cache_size(pref_cache_size, max_cache_size);
+	}
+
+	/// Cache lookups since the last call.
+	pub fn take_cache_stats(&self) -> CacheStats {
+		self.cache_stats.take()
+	}
+
 	/// Ticks our cache system and throws out any old data.
 	pub fn collect_garbage(&self) {
 		let current_size = self.cache_size().total();
+		self.evict_cached(|cache_man, evict| cache_man.collect_garbage(current_size, evict));
+	}
 
+	// evict the cached entries chosen by the cache manager, locking the caches before the manager.
+	fn evict_cached<F>(&self, choose: F) where F: FnOnce(&mut CacheManager<CacheId>, &mut FnMut(HashSet<CacheId>) -> usize) {
 		let mut block_headers = self.block_headers.write();
 		let mut block_bodies = self.block_bodies.write();
 		let mut block_details = self.block_details.write();
@@ -1441,7 +1698,7 @@ impl BlockChain {
 		let mut block_receipts = self.block_receipts.write();
 
 		let mut cache_man = self.cache_man.lock();
-		cache_man.collect_garbage(current_size, | ids | {
+		choose(&mut *cache_man, &mut |ids| {
 			for id in &ids {
 				match *id {
 					CacheId::BlockHeader(ref h) => { block_headers.remove(h); },
@@ -1512,12 +1769,14 @@ mod tests {
 	use ethereum_types::*;
 	use receipt::{Receipt, TransactionOutcome};
 	use blockchain::{BlockProvider, BlockChain, Config, ImportRoute};
+	use super::{CanonCursor, CanonItem, CanonIter};
 	use test_helpers::{
 		generate_dummy_blockchain, generate_dummy_blockchain_with_extra,
 		generate_dummy_empty_blockchain
 	};
 	use blockchain::generator::{BlockGenerator, BlockBuilder, BlockOptions};
-	use blockchain::extras::TransactionAddress;
+	use blockchain::extras::{TransactionAddress, SenderNonce, SenderTransactions, BlockSenders};
+	use db::Readable;
 	use transaction::{Transaction, Action};
 	use log_entry::{LogEntry, LocalizedLogEntry};
 	use ethkey::Secret;
@@ -1685,6 +1944,44 @@ mod tests {
 		keccak("").into()
 	}
 
+	#[test]
+	fn test_canon_iter_reports_reorg() {
+		let genesis = BlockBuilder::genesis();
+		let b1 = genesis.add_block();
+		let b2a = b1.add_block();
+		let b3a = b2a.add_block();
+		let b2b = b1.add_block_with_difficulty(30);
+		let b3b = b2b.add_block();
+
+		let db = new_db();
+		let bc = new_chain(&genesis.last().encoded(), db.clone());
+		for block in &[&b1, &b2a, &b3a] {
+			insert_block(&db, &bc, &block.last().encoded(), vec![]);
+		}
+
+		let hashes = |iter: &mut CanonIter<&BlockChain>| iter.by_ref().take(2).map(|item| match item {
+			CanonItem::Block { header, .. } => header.hash(),
+			CanonItem::Reorg { .. } => panic!("unexpected reorg"),
+		}).collect::<Vec<_>>();
+
+		let mut iter = bc.canon_iter(1, 3);
+		assert_eq!(hashes(&mut iter), vec![b1.last().hash(), b2a.last().hash()]);
+		let cursor = iter.cursor().unwrap();
+		assert_eq!(cursor, CanonCursor { number: 2, hash: b2a.last().hash() });
+
+		insert_block(&db, &bc, &b2b.last().encoded(), vec![]);
+		insert_block(&db, &bc, &b3b.last().encoded(), vec![]);
+		assert_eq!(bc.best_block_hash(), b3b.last().hash());
+
+		let mut iter = bc.canon_iter_after(cursor, 3);
+		match iter.next() {
+			Some(CanonItem::Reorg { ancestor }) => assert_eq!(ancestor, CanonCursor { number: 1, hash: b1.last().hash() }),
+			_ => panic!("expected reorg"),
+		}
+		assert_eq!(hashes(&mut iter), vec![b2b.last().hash(), b3b.last().hash()]);
+		assert!(iter.next().is_none());
+	}
+
 	#[test]
 	fn test_fork_transaction_addresses() {
 		let t1 = Transaction {
@@ -1822,6 +2119,119 @@ mod tests {
 		}));
 	}
 
+	#[test]
+	fn test_transaction_by_sender_and_nonce() {
+		let tx = |value: u64| Transaction {
+			nonce: 0.into(),
+			gas_price: 0.into(),
+			gas: 100_000.into(),
+			action: Action::Create,
+			value: value.into(),
+			data: vec![],
+		}.sign(&secret(), None);
+		let t1 = tx(100);
+		let t2 = tx(200);
+		let sender = t1.sender();
+
+		let genesis = BlockBuilder::genesis();
+		let b1a = genesis.add_block_with_transactions(iter::once(t1.clone()));
+		let b1b = genesis.add_block_with_difficulty(9);
+		let b2 = b1b.add_block_with_transactions(iter::once(t2.clone()));
+
+		let db = new_db();
+		let bc = new_chain(&genesis.last().encoded(), db.clone());
+		assert_eq!(bc.transaction_hash_by_sender_and_nonce(&sender, &0.into()), None);
+
+		let mut batch = db.transaction();
+		let _ = insert_block_batch(&mut batch, &bc, &b1a.last().encoded(), vec![]);
+		bc.commit();
+		let _ = insert_block_batch(&mut batch, &bc, &b1b.last().encoded(), vec![]);
+		bc.commit();
+		db.write(batch).unwrap();
+
+		assert_eq!(bc.transaction_hash_by_sender_and_nonce(&sender, &0.into()), Some(t1.hash()));
+
+		// now let's make forked chain the canon chain
+		let mut batch = db.transaction();
+		let _ = insert_block_batch(&mut batch, &bc, &b2.last().encoded(), vec![]);
+		bc.commit();
+		db.write(batch).unwrap();
+
+		assert_eq!(bc.transaction_hash_by_sender_and_nonce(&sender, &0.into()), Some(t2.hash()));
+		assert_eq!(bc.transaction_hash_by_sender_and_nonce(&sender, &1.into()), None);
+
+		// the retracted block is no longer indexed.
+		let indexed: SenderTransactions = db.read(::db::COL_EXTRA, &SenderNonce { sender: sender, nonce: 0.into() }).unwrap();
+		assert_eq!(indexed.hashes, vec![t2.hash()]);
+		assert!(db.read::<BlockSenders, _>(::db::COL_EXTRA, &b1a.last().hash()).is_none());
+	}
+
+	#[test]
+	fn test_transaction_by_sender_and_nonce_of_unordered_blocks() {
+		let t1 = Transaction {
+			nonce: 0.into(),
+			gas_price: 0.into(),
+			gas: 100_000.into(),
+			action: Action::Create,
+			value: 0.into(),
+			data: vec![],
+		}.sign(&secret(), None);
+		let sender = t1.sender();
+
+		let genesis = BlockBuilder::genesis();
+		let b1 = genesis.add_block_with_transactions(iter::once(t1.clone()));
+		let b2 = b1.add_block();
+
+		let db = new_db();
+		let bc = new_chain(&genesis.last().encoded(), db.clone());
+
+		let b1_total_difficulty = genesis.last().difficulty() + b1.last().difficulty();
+
+		let mut batch = db.transaction();
+		bc.insert_unordered_block(&mut batch, &b2.last().encoded(), vec![], Some(b1_total_difficulty), true, false);
+		bc.commit();
+		bc.insert_unordered_block(&mut batch, &b1.last().encoded(), vec![], None, false, false);
+		bc.commit();
+		db.write(batch).unwrap();
+
+		assert_eq!(bc.transaction_hash_by_sender_and_nonce(&sender, &0.into()), Some(t1.hash()));
+	}
+
+	#[test]
+	fn test_transaction_by_sender_and_nonce_pruning() {
+		let tx = |nonce: u64| Transaction {
+			nonce: nonce.into(),
+			gas_price: 0.into(),
+			gas: 100_000.into(),
+			action: Action::Create,
+			value: 0.into(),
+			data: vec![],
+		}.sign(&secret(), None);
+		let t1 = tx(0);
+		let t2 = tx(1);
+		let sender = t1.sender();
+
+		let genesis = BlockBuilder::genesis();
+		let b1 = genesis.add_block_with_transactions(iter::once(t1.clone()));
+		let b2 = b1.add_block_with_transactions(iter::once(t2.clone()));
+		let b3 = b2.add_block();
+
+		let db = new_db();
+		let config = Config { sender_index_history: 2, ..Config::default() };
+		let bc = BlockChain::new(config, &genesis.last().encoded(), db.clone());
+
+		for block in vec![&b1, &b2, &b3] {
+			let mut batch = db.transaction();
+			let _ = insert_block_batch(&mut batch, &bc, &block.last().encoded(), vec![]);
+			bc.commit();
+			db.write(batch).unwrap();
+		}
+
+		// block 1 fell out of the indexed history.
+		assert_eq!(bc.transaction_hash_by_sender_and_nonce(&sender, &0.into()), None);
+		assert_eq!(bc.transaction_hash_by_sender_and_nonce(&sender, &1.into()), Some(t2.hash()));
+	}
+
 	#[test]
 	fn test_small_fork() {
 		let genesis = BlockBuilder::genesis();
@@ -1995,6 +2405,22 @@ mod tests {
 		assert!(bc.cache_size().blocks < 1024 * 1024);
 	}
 
+	#[test]
+	fn sheds_caches_under_memory_pressure() {
+		use memory_cache::pressure::Shed;
+
+		let bc = generate_dummy_blockchain(100);
+		let mut block_header = bc.block_header_data(&bc.best_block_hash());
+		while !block_header.is_none() {
+			block_header = bc.block_header_data(&block_header.unwrap().parent_hash());
+		}
+		let size = bc.cache_size().total();
+		assert!(bc.cache_size().blocks > 0);
+
+		assert_eq!(bc.shed(100), size - bc.cache_size().total());
+		assert_eq!(bc.cache_size().blocks, 0);
+	}
+
 	#[test]
 	fn can_contain_arbitrary_block_sequence_with_extra() {
 		let bc = generate_dummy_blockchain_with_extra(25);
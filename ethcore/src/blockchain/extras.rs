@@ -26,7 +26,7 @@ use receipt::Receipt;
 use rlp;
 
 use heapsize::HeapSizeOf;
-use ethereum_types::{H256, H264, U256};
+use ethereum_types::{H256, H264, U256, Address};
 use kvdb::PREFIX_LEN as DB_PREFIX_LEN;
 
 /// Represents index of extra data in database
@@ -46,6 +46,10 @@ pub enum ExtrasIndex {
 	EpochTransitions = 5,
 	/// Pending epoch transition data index.
 	PendingEpochTransition = 6,
+	/// Transactions by sender and nonce index.
+	SenderNonce = 7,
+	/// Senders and nonces of block transactions index.
+	BlockSenders = 8,
 }
 
 fn with_index(hash: &H256, i: ExtrasIndex) -> H264 {
@@ -136,6 +140,45 @@ impl Key<::engines::epoch::PendingTransition> for H256 {
 	}
 }
 
+/// Sender and nonce of a transaction.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, RlpEncodable, RlpDecodable)]
+pub struct SenderNonce {
+	/// Sender of the transaction
+	pub sender: Address,
+	/// Nonce of the transaction
+	pub nonce: U256,
+}
+
+pub struct SenderNonceKey([u8; 53]);
+
+impl ops::Deref for SenderNonceKey {
+	type Target = [u8];
+
+	fn deref(&self) -> &Self::Target {
+		&self.0
+	}
+}
+
+impl Key<SenderTransactions> for SenderNonce {
+	type Target = SenderNonceKey;
+
+	fn key(&self) -> Self::Target {
+		let mut result = [0u8; 53];
+		result[0] = ExtrasIndex::SenderNonce as u8;
+		result[1..21].copy_from_slice(&self.sender);
+		self.nonce.to_big_endian(&mut result[21..]);
+		SenderNonceKey(result)
+	}
+}
+
+impl Key<BlockSenders> for H256 {
+	type Target = H264;
+
+	fn key(&self) -> H264 {
+		with_index(self, ExtrasIndex::BlockSenders)
+	}
+}
+
 /// length of epoch keys.
 pub const EPOCH_KEY_LEN: usize = DB_PREFIX_LEN + 16;
 
@@ -250,6 +293,19 @@ impl HeapSizeOf for TransactionAddress {
 	fn heap_size_of_children(&self) -> usize { 0 }
 }
 
+/// Hashes of all imported transactions with the same sender and nonce,
+/// including those which were replaced in forks.
+#[derive(Debug, Default, Clone, PartialEq, RlpEncodableWrapper, RlpDecodableWrapper)]
+pub struct SenderTransactions {
+	pub hashes: Vec<H256>,
+}
+
+/// Senders and nonces of the transactions of a block, used to prune them from the index.
+#[derive(Debug, Default, Clone, PartialEq, RlpEncodableWrapper, RlpDecodableWrapper)]
+pub struct BlockSenders {
+	pub senders: Vec<SenderNonce>,
+}
+
 /// Contains all block receipts.
 #[derive(Clone, RlpEncodableWrapper, RlpDecodableWrapper)]
 pub struct BlockReceipts {
@@ -280,7 +336,9 @@ pub struct EpochTransitions {
 #[cfg(test)]
 mod tests {
 	use rlp::*;
-	use super::BlockReceipts;
+	use db::Key;
+	use ethereum_types::{Address, U256};
+	use super::{BlockReceipts, SenderNonce, SenderTransactions, ExtrasIndex};
 
 	#[test]
 	fn encode_block_receipts() {
@@ -293,4 +351,16 @@ mod tests {
 		assert!(s.is_finished(), "List should be finished now");
 		s.out();
 	}
+
+	#[test]
+	fn sender_nonce_key_and_value() {
+		let key = SenderNonce { sender: Address::from(0x10), nonce: U256::from(0x0102) }.key();
+		assert_eq!(key.len(), 53);
+		assert_eq!(key[0], ExtrasIndex::SenderNonce as u8);
+		assert_eq!(key[20], 0x10);
+		assert_eq!(&key[51..], &[0x01, 0x02]);
+
+		let value = SenderTransactions { hashes: vec![1.into(), 2.into()] };
+		assert_eq!(decode::<SenderTransactions>(&encode(&value)).unwrap(), value);
+	}
 }
@@ -15,6 +15,7 @@
 // along with Parity.  If not, see <http://www.gnu.org/licenses/>.
 
 use std::cmp::{max, min};
+use std::collections::BTreeMap;
 use std::io::{self, Read};
 
 use byteorder::{ByteOrder, BigEndian};
@@ -155,13 +156,21 @@ impl ModexpPricer {
 /// Unless `is_active` is true,
 pub struct Builtin {
 	pricer: Box<Pricer>,
+	repricing: BTreeMap<u64, Box<Pricer>>,
 	native: Box<Impl>,
 	activate_at: u64,
 }
 
 impl Builtin {
-	/// Simple forwarder for cost.
-	pub fn cost(&self, input: &[u8]) -> U256 { self.pricer.cost(input) }
+	/// Cost of running the builtin on given input at given block number.
+	/// Uses the latest repricing activated at that block, or the original pricer.
+	pub fn cost(&self, input: &[u8], at: u64) -> U256 {
+		self.repricing.iter()
+			.rev()
+			.find(|&(&block, _)| block <= at)
+			.map_or(&self.pricer, |(_, pricer)| pricer)
+			.cost(input)
+	}
 
 	/// Simple forwarder for execute.
 	pub fn execute(&self, input: &[u8], output: &mut BytesRef) -> Result<(), Error> {
@@ -172,9 +181,9 @@ impl Builtin {
 	pub fn is_active(&self, at: u64) -> bool { at >= self.activate_at }
 }
 
-impl From<ethjson::spec::Builtin> for Builtin {
-	fn from(b: ethjson::spec::Builtin) -> Self {
-		let pricer: Box<Pricer> = match b.pricing {
+impl From<ethjson::spec::Pricing> for Box<Pricer> {
+	fn from(pricing: ethjson::spec::Pricing) -> Self {
+		match pricing {
 			ethjson::spec::Pricing::Linear(linear) => {
 				Box::new(Linear {
 					base: linear.base,
@@ -197,10 +206,19 @@ impl From<ethjson::spec::Builtin> for Builtin {
 					pair: pricer.pair,
 				})
 			}
-		};
+		}
+	}
+}
+
+impl From<ethjson::spec::Builtin> for Builtin {
+	fn from(b: ethjson::spec::Builtin) -> Self {
+		let repricing = b.repricing.unwrap_or_default().into_iter()
+			.map(|r| (r.activate_at.into(), r.pricing.into()))
+			.collect();
 
 		Builtin {
-			pricer: pricer,
+			pricer: b.pricing.into(),
+			repricing: repricing,
 			native: ethereum_builtin(&b.name),
 			activate_at: b.activate_at.map(Into::into).unwrap_or(0),
 		}
@@ -535,6 +553,7 @@ impl Bn128PairingImpl {
 
 #[cfg(test)]
 mod tests {
+	use std::collections::BTreeMap;
 	use super::{Builtin, Linear, ethereum_builtin, Pricer, ModexpPricer, modexp as me};
 	use ethjson;
 	use ethereum_types::U256;
@@ -692,6 +711,7 @@ mod tests {
 
 		let f = Builtin {
 			pricer: Box::new(ModexpPricer { divisor: 20 }),
+			repricing: BTreeMap::new(),
 			native: ethereum_builtin("modexp"),
 			activate_at: 0,
 		};
@@ -700,7 +720,7 @@ mod tests {
 		{
 			let input = FromHex::from_hex("0000000000000000000000000000000000000000000000000000000000000001000000000000000000000000000000000000000000000000000000003b27bafd00000000000000000000000000000000000000000000000000000000503c8ac3").unwrap();
 			let expected_cost = U256::max_value();
-			assert_eq!(f.cost(&input[..]), expected_cost.into());
+			assert_eq!(f.cost(&input[..], 0), expected_cost.into());
 		}
 
 
@@ -718,7 +738,7 @@ mod tests {
 
 			f.execute(&input[..], &mut BytesRef::Fixed(&mut output[..])).expect("Builtin should fail");
 			assert_eq!(output, expected);
-			assert_eq!(f.cost(&input[..]), expected_cost.into());
+			assert_eq!(f.cost(&input[..], 0), expected_cost.into());
 		}
 
 		// fermat's little theorem example.
@@ -738,7 +758,7 @@ mod tests {
 
 			f.execute(&input[..], &mut BytesRef::Fixed(&mut output[..])).expect("Builtin should not fail");
 			assert_eq!(output, expected);
-			assert_eq!(f.cost(&input[..]), expected_cost.into());
+			assert_eq!(f.cost(&input[..], 0), expected_cost.into());
 		}
 
 		// second example from EIP: zero base.
@@ -757,7 +777,7 @@ mod tests {
 
 			f.execute(&input[..], &mut BytesRef::Fixed(&mut output[..])).expect("Builtin should not fail");
 			assert_eq!(output, expected);
-			assert_eq!(f.cost(&input[..]), expected_cost.into());
+			assert_eq!(f.cost(&input[..], 0), expected_cost.into());
 		}
 
 		// another example from EIP: zero-padding
@@ -777,7 +797,7 @@ mod tests {
 
 			f.execute(&input[..], &mut BytesRef::Fixed(&mut output[..])).expect("Builtin should not fail");
 			assert_eq!(output, expected);
-			assert_eq!(f.cost(&input[..]), expected_cost.into());
+			assert_eq!(f.cost(&input[..], 0), expected_cost.into());
 		}
 
 		// zero-length modulus.
@@ -795,7 +815,7 @@ mod tests {
 
 			f.execute(&input[..], &mut BytesRef::Flexible(&mut output)).expect("Builtin should not fail");
 			assert_eq!(output.len(), 0); // shouldn't have written any output.
-			assert_eq!(f.cost(&input[..]), expected_cost.into());
+			assert_eq!(f.cost(&input[..], 0), expected_cost.into());
 		}
 	}
 
@@ -804,6 +824,7 @@ mod tests {
 
 		let f = Builtin {
 			pricer: Box::new(Linear { base: 0, word: 0 }),
+			repricing: BTreeMap::new(),
 			native: ethereum_builtin("alt_bn128_add"),
 			activate_at: 0,
 		};
@@ -865,6 +886,7 @@ mod tests {
 
 		let f = Builtin {
 			pricer: Box::new(Linear { base: 0, word: 0 }),
+			repricing: BTreeMap::new(),
 			native: ethereum_builtin("alt_bn128_mul"),
 			activate_at: 0,
 		};
@@ -905,6 +927,7 @@ mod tests {
 	fn builtin_pairing() -> Builtin {
 		Builtin {
 			pricer: Box::new(Linear { base: 0, word: 0 }),
+			repricing: BTreeMap::new(),
 			native: ethereum_builtin("alt_bn128_pairing"),
 			activate_at: 0,
 		}
@@ -989,6 +1012,7 @@ mod tests {
 		let pricer = Box::new(Linear { base: 10, word: 20} );
 		let b = Builtin {
 			pricer: pricer as Box<Pricer>,
+			repricing: BTreeMap::new(),
 			native: ethereum_builtin("identity"),
 			activate_at: 100_000,
 		};
@@ -1003,14 +1027,15 @@ mod tests {
 		let pricer = Box::new(Linear { base: 10, word: 20 });
 		let b = Builtin {
 			pricer: pricer as Box<Pricer>,
+			repricing: BTreeMap::new(),
 			native: ethereum_builtin("identity"),
 			activate_at: 1,
 		};
 
-		assert_eq!(b.cost(&[0; 0]), U256::from(10));
-		assert_eq!(b.cost(&[0; 1]), U256::from(30));
-		assert_eq!(b.cost(&[0; 32]), U256::from(30));
-		assert_eq!(b.cost(&[0; 33]), U256::from(50));
+		assert_eq!(b.cost(&[0; 0], 0), U256::from(10));
+		assert_eq!(b.cost(&[0; 1], 0), U256::from(30));
+		assert_eq!(b.cost(&[0; 32], 0), U256::from(30));
+		assert_eq!(b.cost(&[0; 33], 0), U256::from(50));
 
 		let i = [0u8, 1, 2, 3];
 		let mut o = [255u8; 4];
@@ -1027,16 +1052,41 @@ mod tests {
 				word: 20,
 			}),
 			activate_at: None,
+			repricing: None,
 		});
 
-		assert_eq!(b.cost(&[0; 0]), U256::from(10));
-		assert_eq!(b.cost(&[0; 1]), U256::from(30));
-		assert_eq!(b.cost(&[0; 32]), U256::from(30));
-		assert_eq!(b.cost(&[0; 33]), U256::from(50));
+		assert_eq!(b.cost(&[0; 0], 0), U256::from(10));
+		assert_eq!(b.cost(&[0; 1], 0), U256::from(30));
+		assert_eq!(b.cost(&[0; 32], 0), U256::from(30));
+		assert_eq!(b.cost(&[0; 33], 0), U256::from(50));
 
 		let i = [0u8, 1, 2, 3];
 		let mut o = [255u8; 4];
 		b.execute(&i[..], &mut BytesRef::Fixed(&mut o[..])).expect("Builtin should not fail");
 		assert_eq!(i, o);
 	}
+
+	#[test]
+	fn repricing() {
+		let b = Builtin::from(ethjson::spec::Builtin {
+			name: "identity".to_owned(),
+			pricing: ethjson::spec::Pricing::Linear(ethjson::spec::Linear {
+				base: 10,
+				word: 20,
+			}),
+			activate_at: None,
+			repricing: Some(vec![ethjson::spec::Repricing {
+				activate_at: ethjson::uint::Uint(100.into()),
+				pricing: ethjson::spec::Pricing::Linear(ethjson::spec::Linear {
+					base: 5,
+					word: 1,
+				}),
+			}]),
+		});
+
+		assert_eq!(b.cost(&[0; 33], 0), U256::from(50));
+		assert_eq!(b.cost(&[0; 33], 99), U256::from(50));
+		assert_eq!(b.cost(&[0; 33], 100), U256::from(7));
+		assert_eq!(b.cost(&[0; 33], 1_000), U256::from(7));
+	}
 }
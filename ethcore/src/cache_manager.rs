@@ -14,6 +14,7 @@
 // You should have received a copy of the GNU General Public License
 // along with Parity.  If not, see <http://www.gnu.org/licenses/>.
 
+use std::cmp;
 use std::collections::{VecDeque, HashSet};
 use std::hash::Hash;
 
@@ -45,6 +46,12 @@ impl<T> CacheManager<T> where T: Eq + Hash {
 		}
 	}
 
+	/// Change the preferred and maximal size of the cache. Takes effect on next garbage collection.
+	pub fn set_cache_size(&mut self, pref_cache_size: usize, max_cache_size: usize) {
+		self.pref_cache_size = pref_cache_size;
+		self.max_cache_size = max_cache_size;
+	}
+
 	/// Collects unused objects from cache.
 	/// First params is the current size of the cache.
 	/// Second one is an with objects to remove. It should also return new size of the cache.
@@ -65,6 +72,17 @@ impl<T> CacheManager<T> where T: Eq + Hash {
 		}
 	}
 
+	/// Collects the least recently used `percent`% of the tracked objects, whatever the size of the cache.
+	pub fn collect_oldest<F>(&mut self, percent: usize, mut notify_unused: F) where F: FnMut(HashSet<T>) -> usize {
+		let generations = (COLLECTION_QUEUE_SIZE * cmp::min(percent, 100) + 99) / 100;
+		for _ in 0..generations {
+			if let Some(back) = self.cache_usage.pop_back() {
+				notify_unused(back);
+				self.cache_usage.push_front(Default::default());
+			}
+		}
+	}
+
 	fn rotate_cache_if_needed(&mut self) {
 		if self.cache_usage.is_empty() { return }
 
@@ -0,0 +1,275 @@
+// Copyright 2015-2018 Parity Technologies (UK) Ltd.
+// This file is part of Parity.
+
+// Parity is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// Parity is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with Parity.  If not, see <http://www.gnu.org/licenses/>.
+
+//! Chain database integrity audit.
+//!
+//! Walks the canonical chain and cross-checks the columns a block is spread over:
+//! the canonical index, headers, bodies, receipts, block details (total difficulty)
+//! and the state database.
+
+use std::fmt;
+
+use ethereum_types::{H256, U256, Bloom};
+use hash::{keccak, KECCAK_NULL_RLP};
+use receipt::Receipt;
+use rlp::{self, Encodable};
+use triehash::ordered_trie_root;
+
+use client::{BlockChainClient, BlockId};
+use header::BlockNumber;
+
+/// A single inconsistency found while auditing a block.
+#[derive(Debug, PartialEq, Clone)]
+pub enum Inconsistency {
+	/// The canonical index has no entry for the block number.
+	MissingCanonicalHash,
+	/// The header referenced by the canonical index is missing.
+	MissingHeader,
+	/// The stored header hashes to a different value than it is indexed by.
+	HeaderHashMismatch(H256),
+	/// The stored header carries a different number than it is indexed by.
+	NumberMismatch(BlockNumber),
+	/// The parent hash does not point at the canonical block before it.
+	ParentMismatch {
+		/// Canonical hash of the previous block.
+		expected: H256,
+		/// Parent hash found in the header.
+		found: H256,
+	},
+	/// The block body is missing.
+	MissingBody,
+	/// Transactions in the body don't match the header's transactions root.
+	TransactionsRootMismatch(H256),
+	/// Uncles in the body don't match the header's uncles hash.
+	UnclesHashMismatch(H256),
+	/// The block receipts are missing.
+	MissingReceipts,
+	/// The receipts could not be decoded.
+	CorruptReceipts,
+	/// The number of receipts differs from the number of transactions.
+	ReceiptsCountMismatch {
+		/// Number of transactions in the body.
+		transactions: usize,
+		/// Number of stored receipts.
+		receipts: usize,
+	},
+	/// Receipts don't match the header's receipts root.
+	ReceiptsRootMismatch(H256),
+	/// Cumulative gas of the receipts differs from the header's gas used.
+	GasUsedMismatch(U256),
+	/// Receipt blooms don't combine to the header's log bloom.
+	LogBloomMismatch,
+	/// Block details with the total difficulty are missing.
+	MissingTotalDifficulty,
+	/// Total difficulty is not the parent's total difficulty plus the block difficulty.
+	TotalDifficultyMismatch {
+		/// Total difficulty derived from the parent.
+		expected: U256,
+		/// Stored total difficulty.
+		found: U256,
+	},
+	/// The state root is missing from the state database although the block is not pruned.
+	MissingState(H256),
+}
+
+impl fmt::Display for Inconsistency {
+	fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+		use self::Inconsistency::*;
+
+		match *self {
+			MissingCanonicalHash => write!(f, "no canonical hash for block number"),
+			MissingHeader => write!(f, "header missing"),
+			HeaderHashMismatch(ref found) => write!(f, "header hashes to {:#x}", found),
+			NumberMismatch(found) => write!(f, "header has number {}", found),
+			ParentMismatch { ref expected, ref found } =>
+				write!(f, "parent hash {:#x} does not match canonical parent {:#x}", found, expected),
+			MissingBody => write!(f, "body missing"),
+			TransactionsRootMismatch(ref found) => write!(f, "body transactions root is {:#x}", found),
+			UnclesHashMismatch(ref found) => write!(f, "body uncles hash is {:#x}", found),
+			MissingReceipts => write!(f, "receipts missing"),
+			CorruptReceipts => write!(f, "receipts can't be decoded"),
+			ReceiptsCountMismatch { transactions, receipts } =>
+				write!(f, "{} receipts stored for {} transactions", receipts, transactions),
+			ReceiptsRootMismatch(ref found) => write!(f, "receipts root is {:#x}", found),
+			GasUsedMismatch(ref found) => write!(f, "receipts use {} gas", found),
+			LogBloomMismatch => write!(f, "receipts log bloom does not match header"),
+			MissingTotalDifficulty => write!(f, "total difficulty missing"),
+			TotalDifficultyMismatch { ref expected, ref found } =>
+				write!(f, "total difficulty is {}, expected {}", found, expected),
+			MissingState(ref root) => write!(f, "state root {:#x} missing", root),
+		}
+	}
+}
+
+/// Audit result of a single block.
+#[derive(Debug, PartialEq, Clone)]
+pub struct BlockAudit {
+	/// Number of the audited block.
+	pub number: BlockNumber,
+	/// Canonical hash of the block, if indexed.
+	pub hash: Option<H256>,
+	/// Inconsistencies found. Empty if the block is intact.
+	pub inconsistencies: Vec<Inconsistency>,
+}
+
+impl BlockAudit {
+	/// Whether no inconsistencies were found.
+	pub fn is_ok(&self) -> bool {
+		self.inconsistencies.is_empty()
+	}
+}
+
+/// Audit a single canonical block.
+///
+/// State is only checked for blocks the client does not consider pruned.
+pub fn audit_block<C: BlockChainClient + ?Sized>(client: &C, number: BlockNumber) -> BlockAudit {
+	let hash = client.block_hash(BlockId::Number(number));
+	let mut inconsistencies = Vec::new();
+	match hash {
+		Some(hash) => check_block(client, number, hash, &mut inconsistencies),
+		None => inconsistencies.push(Inconsistency::MissingCanonicalHash),
+	}
+
+	BlockAudit {
+		number: number,
+		hash: hash,
+		inconsistencies: inconsistencies,
+	}
+}
+
+fn check_block<C: BlockChainClient + ?Sized>(client: &C, number: BlockNumber, hash: H256, report: &mut Vec<Inconsistency>) {
+	let header = match client.block_header(BlockId::Hash(hash)) {
+		Some(header) => header,
+		None => {
+			report.push(Inconsistency::MissingHeader);
+			return;
+		}
+	};
+
+	if header.hash() != hash {
+		report.push(Inconsistency::HeaderHashMismatch(header.hash()));
+	}
+	if header.number() != number {
+		report.push(Inconsistency::NumberMismatch(header.number()));
+	}
+
+	// the canonical parent, if indexed, must be the one the header points at.
+	let parent = if number == 0 { None } else { client.block_hash(BlockId::Number(number - 1)) };
+	if let Some(parent) = parent {
+		if parent != header.parent_hash() {
+			report.push(Inconsistency::ParentMismatch { expected: parent, found: header.parent_hash() });
+		}
+	}
+
+	let mut transactions = None;
+	match client.block_body(BlockId::Hash(hash)) {
+		Some(body) => {
+			let transactions_root = ordered_trie_root(body.transactions_rlp().iter().map(|r| r.as_raw()));
+			if transactions_root != header.transactions_root() {
+				report.push(Inconsistency::TransactionsRootMismatch(transactions_root));
+			}
+			let uncles_hash = keccak(body.uncles_rlp().as_raw());
+			if uncles_hash != header.uncles_hash() {
+				report.push(Inconsistency::UnclesHashMismatch(uncles_hash));
+			}
+			transactions = Some(body.transactions_count());
+		},
+		None => report.push(Inconsistency::MissingBody),
+	}
+
+	match client.block_receipts(&hash) {
+		Some(bytes) => match rlp::Rlp::new(&bytes).as_list::<Receipt>() {
+			Ok(receipts) => {
+				if let Some(transactions) = transactions {
+					if transactions != receipts.len() {
+						report.push(Inconsistency::ReceiptsCountMismatch { transactions, receipts: receipts.len() });
+					}
+				}
+				let receipts_root = ordered_trie_root(receipts.iter().map(|r| r.rlp_bytes()));
+				if receipts_root != header.receipts_root() {
+					report.push(Inconsistency::ReceiptsRootMismatch(receipts_root));
+				}
+				let gas_used = receipts.last().map_or_else(U256::zero, |r| r.gas_used);
+				if gas_used != header.gas_used() {
+					report.push(Inconsistency::GasUsedMismatch(gas_used));
+				}
+				let log_bloom = receipts.iter().fold(Bloom::default(), |b, r| b | r.log_bloom);
+				if log_bloom != header.log_bloom() {
+					report.push(Inconsistency::LogBloomMismatch);
+				}
+			},
+			Err(_) => report.push(Inconsistency::CorruptReceipts),
+		},
+		None => report.push(Inconsistency::MissingReceipts),
+	}
+
+	match client.block_total_difficulty(BlockId::Hash(hash)) {
+		Some(total_difficulty) => {
+			let parent_total_difficulty = if number == 0 {
+				Some(U256::zero())
+			} else {
+				client.block_total_difficulty(BlockId::Hash(header.parent_hash()))
+			};
+			// a missing parent is reported when auditing the parent itself.
+			if let Some(parent_total_difficulty) = parent_total_difficulty {
+				let expected = parent_total_difficulty + header.difficulty();
+				if expected != total_difficulty {
+					report.push(Inconsistency::TotalDifficultyMismatch { expected, found: total_difficulty });
+				}
+			}
+		},
+		None => report.push(Inconsistency::MissingTotalDifficulty),
+	}
+
+	let state_root = header.state_root();
+	let state_pruned = number < client.pruning_info().earliest_state;
+	if !state_pruned && state_root != KECCAK_NULL_RLP && client.state_data(&state_root).is_none() {
+		report.push(Inconsistency::MissingState(state_root));
+	}
+}
+
+/// Audit a range of canonical blocks, inclusive on both ends.
+pub fn audit_chain<C: BlockChainClient + ?Sized>(client: &C, from: BlockNumber, to: BlockNumber) -> Vec<BlockAudit> {
+	(from..to + 1).map(|number| audit_block(client, number)).collect()
+}
+
+#[cfg(test)]
+mod tests {
+	use ethereum_types::U256;
+	use test_helpers::generate_dummy_client_with_data;
+	use super::audit_chain;
+
+	#[test]
+	fn intact_chain_passes_audit() {
+		let client = generate_dummy_client_with_data(4, 2, &[U256::from(10), U256::from(20)]);
+		let audits = audit_chain(&*client, 0, 4);
+
+		assert_eq!(audits.len(), 5);
+		for audit in audits {
+			assert!(audit.is_ok(), "block #{} failed audit: {:?}", audit.number, audit.inconsistencies);
+		}
+	}
+
+	#[test]
+	fn reports_blocks_past_the_best_block() {
+		let client = generate_dummy_client_with_data(2, 0, &[]);
+		let audits = audit_chain(&*client, 2, 3);
+
+		assert!(audits[0].is_ok());
+		assert_eq!(audits[1].hash, None);
+		assert_eq!(audits[1].inconsistencies, vec![super::Inconsistency::MissingCanonicalHash]);
+	}
+}
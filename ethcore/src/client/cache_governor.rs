@@ -0,0 +1,183 @@
+// Copyright 2015-2018 Parity Technologies (UK) Ltd.
+// This file is part of Parity.
+
+// Parity is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// Parity is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with Parity.  If not, see <http://www.gnu.org/licenses/>.
+
+//! Balancing of the cache budget among client subsystems.
+//!
+//! The governor redistributes a fixed budget among the state, blockchain, traces and
+//! jump destinations caches. Every cache gets a share weighted by the workload phase,
+//! and caches reporting lookup statistics get more room the more often they miss.
+
+use memory_cache::CacheStats;
+use parking_lot::Mutex;
+
+/// Share of the budget no cache goes below, in percent.
+const MIN_SHARE_PERCENT: usize = 5;
+
+/// Workload phase of the client.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Phase {
+	/// Blocks are being imported from the queue.
+	Syncing,
+	/// The client is up to date and mostly serving requests.
+	Serving,
+}
+
+/// Cache budget of every subsystem, in bytes.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub struct CacheAllocation {
+	/// State (accounts and code) cache.
+	pub state: usize,
+	/// Blockchain cache.
+	pub blockchain: usize,
+	/// Traces cache.
+	pub traces: usize,
+	/// EVM jump destinations cache.
+	pub jump_destinations: usize,
+}
+
+impl CacheAllocation {
+	/// Total budget.
+	pub fn total(&self) -> usize {
+		self.state + self.blockchain + self.traces + self.jump_destinations
+	}
+}
+
+/// Lookup statistics observed since the last rebalancing, `None` for caches not tracking lookups.
+#[derive(Debug, Default, Clone, Copy, PartialEq)]
+pub struct CacheUsage {
+	/// State cache lookups.
+	pub state: Option<CacheStats>,
+	/// Blockchain cache lookups.
+	pub blockchain: Option<CacheStats>,
+	/// Traces cache lookups.
+	pub traces: Option<CacheStats>,
+	/// Jump destinations cache lookups.
+	pub jump_destinations: Option<CacheStats>,
+}
+
+// relative weights of state, blockchain, traces and jump destinations caches.
+fn phase_weights(phase: Phase) -> [u64; 4] {
+	match phase {
+		Phase::Syncing => [60, 15, 10, 15],
+		Phase::Serving => [40, 35, 15, 10],
+	}
+}
+
+// weight multiplier in permille: 1000 for caches which always hit, up to 2000 for caches which always miss.
+fn pressure(stats: Option<CacheStats>) -> u64 {
+	let miss_rate = stats.and_then(|s| s.hit_rate()).map_or(0.5, |rate| 1.0 - rate);
+	1000 + (miss_rate * 1000.0) as u64
+}
+
+/// Redistributes a fixed cache budget among client subsystems.
+pub struct CacheGovernor {
+	allocation: Mutex<CacheAllocation>,
+}
+
+impl CacheGovernor {
+	/// Create a new governor redistributing the total of the initial allocation.
+	pub fn new(initial: CacheAllocation) -> Self {
+		CacheGovernor {
+			allocation: Mutex::new(initial),
+		}
+	}
+
+	/// Current allocation.
+	pub fn allocation(&self) -> CacheAllocation {
+		*self.allocation.lock()
+	}
+
+	/// Compute and store a new allocation given the workload phase and the lookups observed
+	/// since the last call. Every cache moves halfway towards its target share, so single
+	/// bursts of lookups don't make the caches oscillate.
+	pub fn rebalance(&self, phase: Phase, usage: &CacheUsage) -> CacheAllocation {
+		let mut allocation = self.allocation.lock();
+		let total = allocation.total();
+
+		let stats = [usage.state, usage.blockchain, usage.traces, usage.jump_destinations];
+		let mut weights = phase_weights(phase);
+		for (weight, stats) in weights.iter_mut().zip(stats.iter()) {
+			*weight *= pressure(*stats);
+		}
+		let weights_sum: u64 = weights.iter().sum();
+
+		let min = total * MIN_SHARE_PERCENT / 100;
+		let distributable = (total - min * weights.len()) as u64;
+		let current = [allocation.state, allocation.blockchain, allocation.traces, allocation.jump_destinations];
+		let mut next = [0usize; 4];
+		for i in 0..next.len() {
+			let target = min + (distributable * weights[i] / weights_sum) as usize;
+			next[i] = (current[i] + target) / 2;
+		}
+
+		*allocation = CacheAllocation {
+			// rounding leftovers go to the state cache.
+			state: total - next[1] - next[2] - next[3],
+			blockchain: next[1],
+			traces: next[2],
+			jump_destinations: next[3],
+		};
+		*allocation
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use memory_cache::CacheStats;
+	use super::{CacheGovernor, CacheAllocation, CacheUsage, Phase};
+
+	const MB: usize = 1024 * 1024;
+
+	fn governor() -> CacheGovernor {
+		CacheGovernor::new(CacheAllocation {
+			state: 25 * MB,
+			blockchain: 25 * MB,
+			traces: 25 * MB,
+			jump_destinations: 25 * MB,
+		})
+	}
+
+	#[test]
+	fn keeps_total_and_minimal_shares() {
+		let governor = governor();
+		for _ in 0..32 {
+			let allocation = governor.rebalance(Phase::Syncing, &Default::default());
+			assert_eq!(allocation.total(), 100 * MB);
+		}
+
+		let allocation = governor.allocation();
+		assert!(allocation.state > 50 * MB);
+		assert!(allocation.traces >= 5 * MB);
+		assert!(allocation.blockchain < allocation.state);
+	}
+
+	#[test]
+	fn favours_missing_caches() {
+		let governor = governor();
+		let usage = CacheUsage {
+			state: Some(CacheStats { hits: 100, misses: 0 }),
+			jump_destinations: Some(CacheStats { hits: 0, misses: 100 }),
+			..Default::default()
+		};
+		let unknown = governor.rebalance(Phase::Serving, &Default::default());
+
+		let governor = self::governor();
+		let observed = governor.rebalance(Phase::Serving, &usage);
+
+		assert!(observed.state < unknown.state);
+		assert!(observed.jump_destinations > unknown.jump_destinations);
+	}
+}
@@ -15,9 +15,10 @@
 // along with Parity.  If not, see <http://www.gnu.org/licenses/>.
 
 use bytes::Bytes;
-use ethereum_types::H256;
+use ethereum_types::{H256, H512};
 use transaction::UnverifiedTransaction;
 use blockchain::ImportRoute;
+use engines::LivenessAlert;
 use std::time::Duration;
 use std::collections::HashMap;
 
@@ -143,6 +144,9 @@ pub trait ChainNotify : Send + Sync {
 	/// fires when chain broadcasts a message
 	fn broadcast(&self, _message_type: ChainMessageType) {}
 
+	/// fires when chain sends a message to a single peer
+	fn send(&self, _message_type: ChainMessageType, _node_id: &H512) {}
+
 	/// fires when new transactions are received from a peer
 	fn transactions_received(&self,
 		_txs: &[UnverifiedTransaction],
@@ -150,4 +154,9 @@ pub trait ChainNotify : Send + Sync {
 	) {
 		// does nothing by default
 	}
+
+	/// fires when a validator misses the configured number of consecutive steps
+	fn liveness_alert(&self, _alert: &LivenessAlert) {
+		// does nothing by default
+	}
 }
@@ -31,10 +31,12 @@ use kvdb::{DBValue, KeyValueDB, DBTransaction};
 use util_error::UtilError;
 
 // other
-use ethereum_types::{H256, Address, U256};
+use ethereum_types::{H256, H512, Address, U256};
 use block::{IsBlock, LockedBlock, Drain, ClosedBlock, OpenBlock, enact_verified, SealedBlock};
-use blockchain::{BlockChain, BlockProvider, TreeRoute, ImportRoute, TransactionAddress, ExtrasInsert};
+use blockchain::{BlockChain, BlockProvider, CanonCursor, CanonIter, TreeRoute, ImportRoute, TransactionAddress, ExtrasInsert};
 use client::ancient_import::AncientVerifier;
+use client::cache_governor::{CacheGovernor, CacheAllocation, CacheUsage, Phase as CachePhase};
+use client::state_pin::{PinnedStates, StatePin};
 use client::Error as ClientError;
 use client::{
 	Nonce, Balance, ChainInfo, BlockInfo, CallContract, TransactionInfo,
@@ -50,16 +52,17 @@ use client::{
 	IoClient,
 };
 use encoded;
-use engines::{EthEngine, EpochTransition, ForkChoice};
+use engines::{EthEngine, EpochTransition, ForkChoice, LivenessAlert};
 use error::{ImportErrorKind, BlockImportErrorKind, ExecutionError, CallError, BlockError, ImportResult, Error as EthcoreError};
 use vm::{EnvInfo, LastHashes};
 use evm::Schedule;
-use executive::{Executive, Executed, TransactOptions, contract_address};
+use executive::{Executed, TransactOptions, BlockExecutor, contract_address, intrinsic_gas};
 use factory::{Factories, VmFactory};
 use header::{BlockNumber, Header, ExtendedHeader};
 use io::{IoChannel, IoError};
 use log_entry::LocalizedLogEntry;
 use miner::{Miner, MinerService};
+use memory_cache::pressure::{self, Shed};
 use ethcore_miner::pool::VerifiedTransaction;
 use parking_lot::{Mutex, RwLock};
 use rand::OsRng;
@@ -67,9 +70,11 @@ use receipt::{Receipt, LocalizedReceipt};
 use snapshot::{self, io as snapshot_io};
 use spec::Spec;
 use state_db::StateDB;
-use state::{self, State};
+use state_healing::{StateHealer, StateHealingStatus};
+use state::{self, State, StateSizeDelta, Backend as StateBackend};
+use state::backend::{NodeCache, Overlay};
 use trace;
-use trace::{TraceDB, ImportRequest as TraceImportRequest, LocalizedTrace, Database as TraceDatabase};
+use trace::{TraceDB, ImportRequest as TraceImportRequest, LocalizedTrace, Database as TraceDatabase, StorageUsage as TraceStorageUsage};
 use transaction::{self, LocalizedTransaction, UnverifiedTransaction, SignedTransaction, Transaction, Action};
 use types::filter::Filter;
 use types::mode::Mode as IpcMode;
@@ -92,6 +97,15 @@ const MAX_TX_QUEUE_SIZE: usize = 4096;
 const MAX_ANCIENT_BLOCKS_QUEUE_SIZE: usize = 4096;
 const MAX_QUEUE_SIZE_TO_SLEEP_ON: usize = 2;
 const MIN_HISTORY_SIZE: u64 = 8;
+// number of state nodes checked per healing request.
+const MAX_HEALING_CHECKS: usize = 4096;
+// number of healed state nodes between progress reports.
+const HEALING_REPORT_INTERVAL: u64 = 1000;
+// state healing moves to the best block's state once the healed one is this many blocks old,
+// so that the journal pinned for it doesn't grow without bound.
+const HEALING_REANCHOR_BLOCKS: BlockNumber = 512;
+// size of the trie node cache shared by state views, in bytes.
+const STATE_VIEW_CACHE_SIZE: usize = 32 * 1024 * 1024;
 
 /// Report on the status of a client.
 #[derive(Default, Clone, Debug, Eq, PartialEq)]
@@ -104,14 +118,19 @@ pub struct ClientReport {
 	pub gas_processed: U256,
 	/// Memory used by state DB
 	pub state_db_mem: usize,
+	/// Bytes written to the state DB by imported blocks so far.
+	pub state_bytes_added: u64,
+	/// Bytes freed from the state DB by pruning so far.
+	pub state_bytes_freed: u64,
 }
 
 impl ClientReport {
 	/// Alter internal reporting to reflect the additional `block` has been processed.
-	pub fn accrue_block(&mut self, header: &Header, transactions: usize) {
+	pub fn accrue_block(&mut self, header: &Header, transactions: usize, size_delta: &StateSizeDelta) {
 		self.blocks_imported += 1;
 		self.transactions_applied += transactions;
 		self.gas_processed = self.gas_processed + *header.gas_used();
+		self.state_bytes_added += size_delta.bytes_added;
 	}
 }
 
@@ -126,11 +145,20 @@ impl<'a> ::std::ops::Sub<&'a ClientReport> for ClientReport {
 		self.transactions_applied -= other.transactions_applied;
 		self.gas_processed = self.gas_processed - other.gas_processed;
 		self.state_db_mem = higher_mem - lower_mem;
+		self.state_bytes_added -= other.state_bytes_added;
+		self.state_bytes_freed -= other.state_bytes_freed;
 
 		self
 	}
 }
 
+// state healing in progress. The healed state is pinned so it isn't pruned while walked.
+struct StateHealing {
+	healer: StateHealer,
+	block: BlockNumber,
+	_pin: StatePin,
+}
+
 struct SleepState {
 	last_activity: Option<Instant>,
 	last_autosleep: Option<Instant>,
@@ -194,6 +222,9 @@ pub struct Client {
 
 	state_db: RwLock<StateDB>,
 
+	/// Trie nodes read by state views.
+	state_view_nodes: Arc<NodeCache>,
+
 	/// Report on the status of client
 	report: RwLock<ClientReport>,
 
@@ -214,9 +245,13 @@ pub struct Client {
 	pending_ancient_blocks: RwLock<HashSet<H256>>,
 	/// Consensus messages import queue
 	queue_consensus_message: IoChannelQueue,
+	/// Trace database pruning queue
+	queue_trace_pruning: IoChannelQueue,
 
 	last_hashes: RwLock<VecDeque<H256>>,
 	factories: Factories,
+	/// VM factory of virtual calls, limiting memory they may use.
+	virtual_vm_factory: VmFactory,
 
 	/// Number of eras kept in a journal before they are pruned
 	history: u64,
@@ -231,6 +266,15 @@ pub struct Client {
 	/// A closure to call when we want to restart the client
 	exit_handler: Mutex<Option<Box<Fn(String) + 'static + Send>>>,
 
+	/// Redistributes cache sizes if cache balancing is enabled.
+	cache_governor: Option<CacheGovernor>,
+
+	/// Blocks whose state is kept from being pruned, e.g. to take a snapshot of it.
+	pinned_states: PinnedStates,
+
+	/// Healing of the state left incomplete by warp sync, if ongoing.
+	state_healer: Mutex<Option<StateHealing>>,
+
 	importer: Importer,
 }
 
@@ -295,11 +339,13 @@ impl Importer {
 						imported_blocks.push(hash);
 
 						let transactions_len = closed_block.transactions().len();
+						let size_delta = closed_block.state().size_delta();
+						trace!(target: "client", "Block #{} state size delta: +{} bytes, -{} entries", header.number(), size_delta.bytes_added, size_delta.nodes_freed);
 
 						let route = self.commit_block(closed_block, &header, &bytes, client);
 						import_results.push(route);
 
-						client.report.write().accrue_block(&header, transactions_len);
+						client.report.write().accrue_block(&header, transactions_len, &size_delta);
 					}
 				} else {
 					invalid_blocks.insert(header.hash());
@@ -556,6 +602,7 @@ impl Importer {
 		chain.commit();
 
 		self.check_epoch_end(&header, &chain, client);
+		self.engine.on_block_committed(&header, &(|hash| client.block_header_decoded(BlockId::Hash(hash))));
 
 		client.update_last_hashes(&parent, hash);
 
@@ -596,7 +643,7 @@ impl Importer {
 					Proof::WithState(with_state) => {
 						let env_info = EnvInfo {
 							number: header.number(),
-							author: header.author().clone(),
+							author: self.engine.executive_author(header),
 							timestamp: header.timestamp(),
 							difficulty: header.difficulty().clone(),
 							last_hashes: client.build_last_hashes(header.parent_hash()),
@@ -619,8 +666,8 @@ impl Importer {
 							).expect("state known to be available for just-imported block; qed");
 
 							let options = TransactOptions::with_no_tracing().dont_check_nonce();
-							let res = Executive::new(&mut state, &env_info, self.engine.machine())
-								.transact(&transaction, options);
+							let res = BlockExecutor::new(env_info)
+								.transact(&mut state, self.engine.machine(), &transaction, options);
 
 							let res = match res {
 								Err(ExecutionError::Internal(e)) =>
@@ -701,8 +748,11 @@ impl Client {
 		};
 
 		let trie_factory = TrieFactory::new(trie_spec);
+		let vm_factory = VmFactory::new(config.vm_type.clone(), config.jump_table_size);
+		let virtual_vm_factory = vm_factory.clone()
+			.with_memory_limit(config.vm_memory_limit.unwrap_or(::evm::DEFAULT_MEMORY_LIMIT));
 		let factories = Factories {
-			vm: VmFactory::new(config.vm_type.clone(), config.jump_table_size),
+			vm: vm_factory,
 			trie: trie_factory,
 			accountdb: Default::default(),
 		};
@@ -719,6 +769,7 @@ impl Client {
 
 		let gb = spec.genesis_block();
 		let chain = Arc::new(BlockChain::new(config.blockchain.clone(), &gb, db.clone()));
+		pressure::global().register(&(chain.clone() as Arc<Shed>));
 		let tracedb = RwLock::new(TraceDB::new(config.tracing.clone(), db.clone(), chain.clone()));
 
 		trace!("Cleanup journal: DB Earliest = {:?}, Latest = {:?}", state_db.journal_db().earliest_era(), state_db.journal_db().latest_era());
@@ -738,6 +789,10 @@ impl Client {
 
 		let engine = spec.engine.clone();
 
+		if let Some(certifier) = engine.machine().sender_certifier() {
+			certifier.attach_db(db.clone());
+		}
+
 		let awake = match config.mode { Mode::Dark(..) | Mode::Off => false, _ => true };
 
 		let importer = Importer::new(&config, engine.clone(), message_channel.clone(), miner)?;
@@ -747,6 +802,16 @@ impl Client {
 			trace!(target: "client", "Found registrar at {}", addr);
 		}
 
+		let cache_governor = match config.balance_caches {
+			true => Some(CacheGovernor::new(CacheAllocation {
+				state: config.state_cache_size,
+				blockchain: config.blockchain.max_cache_size,
+				traces: config.tracing.max_cache_size,
+				jump_destinations: config.jump_table_size,
+			})),
+			false => None,
+		};
+
 		let client = Arc::new(Client {
 			enabled: AtomicBool::new(true),
 			sleep_state: Mutex::new(SleepState::new(awake)),
@@ -759,6 +824,7 @@ impl Client {
 			config: config,
 			db: RwLock::new(db),
 			state_db: RwLock::new(state_db),
+			state_view_nodes: Arc::new(NodeCache::new(STATE_VIEW_CACHE_SIZE)),
 			report: RwLock::new(Default::default()),
 			io_channel: Mutex::new(message_channel),
 			notify: RwLock::new(Vec::new()),
@@ -766,13 +832,18 @@ impl Client {
 			queue_ancient_blocks: IoChannelQueue::new(MAX_ANCIENT_BLOCKS_QUEUE_SIZE),
 			pending_ancient_blocks: RwLock::new(HashSet::new()),
 			queue_consensus_message: IoChannelQueue::new(usize::max_value()),
+			queue_trace_pruning: IoChannelQueue::new(1),
 			last_hashes: RwLock::new(VecDeque::new()),
 			factories: factories,
+			virtual_vm_factory: virtual_vm_factory,
 			history: history,
 			on_user_defaults_change: Mutex::new(None),
 			registrar: registry::Registry::default(),
 			registrar_address,
 			exit_handler: Mutex::new(None),
+			cache_governor,
+			pinned_states: PinnedStates::default(),
+			state_healer: Mutex::new(None),
 			importer,
 		});
 
@@ -837,6 +908,15 @@ impl Client {
 		self.notify.write().push(Arc::downgrade(&target));
 	}
 
+	/// Removes an actor added with `add_notify`, along with any actors which were dropped.
+	pub fn remove_notify(&self, target: &Arc<ChainNotify>) {
+		let target = &**target as *const ChainNotify as *const ();
+		self.notify.write().retain(|np| match np.upgrade() {
+			Some(n) => &*n as *const ChainNotify as *const () != target,
+			None => false,
+		});
+	}
+
 	/// Set a closure to call when the client wants to be restarted.
 	///
 	/// The parameter passed to the callback is the name of the new chain spec to use after
@@ -879,15 +959,15 @@ impl Client {
 	/// The env info as of a given block.
 	/// returns `None` if the block unknown.
 	pub fn env_info(&self, id: BlockId) -> Option<EnvInfo> {
-		self.block_header(id).map(|header| {
+		self.block_header_decoded(id).map(|header| {
 			EnvInfo {
 				number: header.number(),
-				author: header.author(),
+				author: self.engine.executive_author(&header),
 				timestamp: header.timestamp(),
-				difficulty: header.difficulty(),
-				last_hashes: self.build_last_hashes(&header.parent_hash()),
+				difficulty: *header.difficulty(),
+				last_hashes: self.build_last_hashes(header.parent_hash()),
 				gas_used: U256::default(),
-				gas_limit: header.gas_limit(),
+				gas_limit: *header.gas_limit(),
 			}
 		})
 	}
@@ -954,7 +1034,13 @@ impl Client {
 				state_db.journal_db().journal_size() >= self.config.history_mem;
 
 			if !needs_pruning { break }
+			let pinned = self.pinned_states.earliest();
 			match state_db.journal_db().earliest_era() {
+				// pruning an era drops the state of blocks before it.
+				Some(era) if pinned.map_or(false, |pinned| era > pinned) => {
+					trace!(target: "client", "Not pruning era {}, state at {:?} is pinned", era, pinned);
+					break;
+				}
 				Some(era) if era + self.history <= number => {
 					trace!(target: "client", "Pruning state for ancient era {}", era);
 					match chain.block_hash(era) {
@@ -1038,6 +1124,30 @@ impl Client {
 		})
 	}
 
+	/// Attempt to get a read-only view of a specific block's final state.
+	///
+	/// Views share a cache of trie nodes which block import doesn't touch, so they are
+	/// suited for serving calls on historical blocks. Changes made to a view are dropped with it.
+	pub fn state_view(&self, id: BlockId) -> Option<State<Overlay<Arc<StateDB>>>> {
+		let block_number = match self.block_number(id) {
+			Some(num) => num,
+			None => return None,
+		};
+
+		self.block_header(id).and_then(|header| {
+			let db = self.state_db.read().boxed_clone();
+
+			// early exit for pruned blocks
+			if db.is_pruned() && self.pruning_info().earliest_state > block_number {
+				return None;
+			}
+
+			let root = header.state_root();
+			let view = Overlay::with_node_cache(Arc::new(db), self.state_view_nodes.clone());
+			State::from_existing(view, root, self.engine.account_start_nonce(block_number), self.factories.clone()).ok()
+		})
+	}
+
 	/// Attempt to get a copy of a specific block's beginning state.
 	///
 	/// This will not fail if given BlockId::Latest.
@@ -1062,23 +1172,62 @@ impl Client {
 	/// Get the report.
 	pub fn report(&self) -> ClientReport {
 		let mut report = self.report.read().clone();
-		report.state_db_mem = self.state_db.read().mem_used();
+		{
+			let state_db = self.state_db.read();
+			report.state_db_mem = state_db.mem_used();
+			report.state_bytes_freed = state_db.journal_db().freed_bytes();
+		}
 		report
 	}
 
 	/// Tick the client.
 	// TODO: manage by real events.
 	pub fn tick(&self, prevent_sleep: bool) {
+		self.balance_caches();
 		self.check_garbage();
 		if !prevent_sleep {
 			self.check_snooze();
 		}
 	}
 
+	fn balance_caches(&self) {
+		let governor = match self.cache_governor {
+			Some(ref governor) => governor,
+			None => return,
+		};
+
+		let phase = match self.importer.block_queue.queue_info().is_empty() {
+			true => CachePhase::Serving,
+			false => CachePhase::Syncing,
+		};
+		let usage = CacheUsage {
+			state: Some(self.state_db.read().take_cache_stats()),
+			blockchain: Some(self.chain.read().take_cache_stats()),
+			traces: Some(self.tracedb.read().take_cache_stats()),
+			jump_destinations: Some(self.factories.vm.evm_cache().take_stats()),
+		};
+
+		let allocation = governor.rebalance(phase, &usage);
+		trace!(target: "client", "Cache sizes rebalanced ({:?}, {:?}): {:?}", phase, usage, allocation);
+
+		self.state_db.write().set_cache_size(allocation.state);
+		self.chain.read().set_cache_size(allocation.blockchain * 3 / 4, allocation.blockchain);
+		self.tracedb.read().set_cache_size(allocation.traces * 3 / 4, allocation.traces);
+		self.factories.vm.evm_cache().set_max_size(allocation.jump_destinations);
+	}
+
 	fn check_garbage(&self) {
 		self.chain.read().collect_garbage();
 		self.importer.block_queue.collect_garbage();
 		self.tracedb.read().collect_garbage();
+
+		// traces are pruned on an IO worker, at most one pass is queued at a time.
+		let best_block = self.chain.read().best_block_number();
+		self.queue_trace_pruning.queue(&mut self.io_channel.lock(), move |client| {
+			client.tracedb.read().prune(best_block);
+		}).unwrap_or_else(|e| {
+			trace!(target: "client", "Skipping trace pruning: {}", e);
+		});
 	}
 
 	fn check_snooze(&self) {
@@ -1115,8 +1264,27 @@ impl Client {
 		}
 	}
 
+	/// Keep the state at given block from being pruned until the returned pin and all its clones are dropped.
+	/// Pruning of later states is delayed as well.
+	pub fn pin_state(&self, number: BlockNumber) -> StatePin {
+		self.pinned_states.pin(number)
+	}
+
+	/// Iterator over the canonical blocks from `first` to `last`, inclusive.
+	/// See `CanonIter` for how reorganisations during the walk are reported.
+	pub fn canon_iter(&self, first: BlockNumber, last: BlockNumber) -> CanonIter<Arc<BlockChain>> {
+		CanonIter::new(self.chain.read().clone(), first, last)
+	}
+
+	/// Iterator over the canonical blocks following `cursor`, up to `last`.
+	pub fn canon_iter_after(&self, cursor: CanonCursor, last: BlockNumber) -> CanonIter<Arc<BlockChain>> {
+		CanonIter::after(self.chain.read().clone(), cursor, last)
+	}
+
 	/// Take a snapshot at the given block.
 	/// If the ID given is "latest", this will default to 1000 blocks behind.
+	///
+	/// The state of the block is pinned while the snapshot is taken.
 	pub fn take_snapshot<W: snapshot_io::SnapshotWriter + Send>(&self, writer: W, at: BlockId, p: &snapshot::Progress) -> Result<(), EthcoreError> {
 		let db = self.state_db.read().journal_db().boxed_clone();
 		let best_block_number = self.chain_info().best_block_number;
@@ -1128,26 +1296,28 @@ impl Client {
 
 		let history = ::std::cmp::min(self.history, 1000);
 
-		let start_hash = match at {
+		let (start_num, start_id) = match at {
 			BlockId::Latest => {
 				let start_num = match db.earliest_era() {
 					Some(era) => ::std::cmp::max(era, best_block_number.saturating_sub(history)),
 					None => best_block_number.saturating_sub(history),
 				};
-
-				match self.block_hash(BlockId::Number(start_num)) {
-					Some(h) => h,
-					None => return Err(snapshot::Error::InvalidStartingBlock(at).into()),
-				}
-			}
-			_ => match self.block_hash(at) {
-				Some(hash) => hash,
-				None => return Err(snapshot::Error::InvalidStartingBlock(at).into()),
+				(start_num, BlockId::Number(start_num))
 			},
+			_ => (block_number, at),
+		};
+		let start_hash = match self.block_hash(start_id) {
+			Some(hash) => hash,
+			None => return Err(snapshot::Error::InvalidStartingBlock(at).into()),
 		};
 
-		snapshot::take_snapshot(&*self.engine, &self.chain.read(), start_hash, db.as_hashdb(), writer, p)?;
+		let _pin = self.pin_state(start_num);
+		// the state might have been pruned before it got pinned.
+		if db.is_pruned() && self.pruning_info().earliest_state > start_num {
+			return Err(snapshot::Error::OldBlockPrunedDB.into());
+		}
 
+		snapshot::take_snapshot(&*self.engine, &self.chain.read(), start_hash, db.as_hashdb(), writer, p)?;
 		Ok(())
 	}
 
@@ -1212,45 +1382,59 @@ impl Client {
 		}.fake_sign(from)
 	}
 
-	fn do_virtual_call(
+	// environment of virtual calls on top of the block with given header.
+	fn virtual_env_info(&self, header: &Header) -> EnvInfo {
+		EnvInfo {
+			number: header.number(),
+			author: self.engine.executive_author(header),
+			timestamp: header.timestamp(),
+			difficulty: header.difficulty().clone(),
+			last_hashes: self.build_last_hashes(header.parent_hash()),
+			gas_used: U256::default(),
+			gas_limit: U256::max_value(),
+		}
+	}
+
+	fn do_virtual_call<B: StateBackend>(
 		machine: &::machine::EthereumMachine,
-		env_info: &EnvInfo,
-		state: &mut State<StateDB>,
+		vm_factory: &VmFactory,
+		executor: &mut BlockExecutor,
+		state: &mut State<B>,
 		t: &SignedTransaction,
 		analytics: CallAnalytics,
 	) -> Result<Executed, CallError> {
-		fn call<V, T>(
-			state: &mut State<StateDB>,
-			env_info: &EnvInfo,
+		fn call<B, V, T>(
+			state: &mut State<B>,
+			executor: &mut BlockExecutor,
 			machine: &::machine::EthereumMachine,
 			state_diff: bool,
 			transaction: &SignedTransaction,
 			options: TransactOptions<T, V>,
 		) -> Result<Executed<T::Output, V::Output>, CallError> where
+			B: StateBackend,
 			T: trace::Tracer,
 			V: trace::VMTracer,
 		{
 			let options = options
 				.dont_check_nonce()
 				.save_output_from_contract();
-			let original_state = if state_diff { Some(state.clone()) } else { None };
+			let options = if state_diff { options.with_state_diff() } else { options };
 
-			let mut ret = Executive::new(state, env_info, machine).transact_virtual(transaction, options)?;
-
-			if let Some(original) = original_state {
-				ret.state_diff = Some(state.diff_from(original).map_err(ExecutionError::from)?);
-			}
-			Ok(ret)
+			Ok(executor.transact_virtual(state, machine, transaction, options)?)
 		}
 
 		let state_diff = analytics.state_diffing;
 
-		match (analytics.transaction_tracing, analytics.vm_tracing) {
-			(true, true) => call(state, env_info, machine, state_diff, t, TransactOptions::with_tracing_and_vm_tracing()),
-			(true, false) => call(state, env_info, machine, state_diff, t, TransactOptions::with_tracing()),
-			(false, true) => call(state, env_info, machine, state_diff, t, TransactOptions::with_vm_tracing()),
-			(false, false) => call(state, env_info, machine, state_diff, t, TransactOptions::with_no_tracing()),
-		}
+		// virtual calls run with the memory limited VM, the state keeps its own factory afterwards.
+		let previous_factory = state.set_vm_factory(vm_factory.clone());
+		let result = match (analytics.transaction_tracing, analytics.vm_tracing) {
+			(true, true) => call(state, executor, machine, state_diff, t, TransactOptions::with_tracing_and_vm_tracing()),
+			(true, false) => call(state, executor, machine, state_diff, t, TransactOptions::with_tracing()),
+			(false, true) => call(state, executor, machine, state_diff, t, TransactOptions::with_vm_tracing()),
+			(false, false) => call(state, executor, machine, state_diff, t, TransactOptions::with_no_tracing()),
+		};
+		state.set_vm_factory(previous_factory);
+		result
 	}
 
 	fn block_number_ref(&self, id: &BlockId) -> Option<BlockNumber> {
@@ -1295,6 +1479,7 @@ impl snapshot::DatabaseRestore for Client {
 		let cache_size = state_db.cache_size();
 		*state_db = StateDB::new(journaldb::new(db.clone(), self.pruning, ::db::COL_STATE), cache_size);
 		*chain = Arc::new(BlockChain::new(self.config.blockchain.clone(), &[], db.clone()));
+		pressure::global().register(&(chain.clone() as Arc<Shed>));
 		*tracedb = TraceDB::new(self.config.tracing.clone(), db.clone(), chain.clone());
 		Ok(())
 	}
@@ -1422,43 +1607,40 @@ impl Call for Client {
 	type State = State<::state_db::StateDB>;
 
 	fn call(&self, transaction: &SignedTransaction, analytics: CallAnalytics, state: &mut Self::State, header: &Header) -> Result<Executed, CallError> {
-		let env_info = EnvInfo {
-			number: header.number(),
-			author: header.author().clone(),
-			timestamp: header.timestamp(),
-			difficulty: header.difficulty().clone(),
-			last_hashes: self.build_last_hashes(header.parent_hash()),
-			gas_used: U256::default(),
-			gas_limit: U256::max_value(),
-		};
+		let env_info = self.virtual_env_info(header);
 		let machine = self.engine.machine();
 
-		Self::do_virtual_call(&machine, &env_info, state, transaction, analytics)
+		Self::do_virtual_call(&machine, &self.virtual_vm_factory, &mut BlockExecutor::new(env_info), state, transaction, analytics)
 	}
 
 	fn call_many(&self, transactions: &[(SignedTransaction, CallAnalytics)], state: &mut Self::State, header: &Header) -> Result<Vec<Executed>, CallError> {
-		let mut env_info = EnvInfo {
-			number: header.number(),
-			author: header.author().clone(),
-			timestamp: header.timestamp(),
-			difficulty: header.difficulty().clone(),
-			last_hashes: self.build_last_hashes(header.parent_hash()),
-			gas_used: U256::default(),
-			gas_limit: U256::max_value(),
-		};
+		let env_info = self.virtual_env_info(header);
 
 		let mut results = Vec::with_capacity(transactions.len());
+		let mut executor = BlockExecutor::new(env_info);
 		let machine = self.engine.machine();
 
 		for &(ref t, analytics) in transactions {
-			let ret = Self::do_virtual_call(machine, &env_info, state, t, analytics)?;
-			env_info.gas_used = ret.cumulative_gas_used;
-			results.push(ret);
+			results.push(Self::do_virtual_call(machine, &self.virtual_vm_factory, &mut executor, state, t, analytics)?);
 		}
 
 		Ok(results)
 	}
 
+	fn call_at(&self, transaction: &SignedTransaction, analytics: CallAnalytics, id: BlockId) -> Result<Executed, CallError> {
+		let header = self.block_header_decoded(id).ok_or(CallError::StatePruned)?;
+		let mut executor = BlockExecutor::new(self.virtual_env_info(&header));
+		let machine = self.engine.machine();
+
+		match id {
+			BlockId::Latest => Self::do_virtual_call(machine, &self.virtual_vm_factory, &mut executor, &mut self.latest_state(), transaction, analytics),
+			_ => {
+				let mut state = self.state_view(id).ok_or(CallError::StatePruned)?;
+				Self::do_virtual_call(machine, &self.virtual_vm_factory, &mut executor, &mut state, transaction, analytics)
+			},
+		}
+	}
+
 	fn estimate_gas(&self, t: &SignedTransaction, state: &Self::State, header: &Header) -> Result<U256, CallError> {
 		let (mut upper, max_upper, env_info) = {
 			let init = *header.gas_limit();
@@ -1466,7 +1648,7 @@ impl Call for Client {
 
 			let env_info = EnvInfo {
 				number: header.number(),
-				author: header.author().clone(),
+				author: self.engine.executive_author(header),
 				timestamp: header.timestamp(),
 				difficulty: header.difficulty().clone(),
 				last_hashes: self.build_last_hashes(header.parent_hash()),
@@ -1483,11 +1665,18 @@ impl Call for Client {
 		let cond = |gas| {
 			let mut tx = t.as_unsigned().clone();
 			tx.gas = gas;
-			let tx = tx.fake_sign(sender);
+			let tx = match t.access_list() {
+				Some(access_list) => {
+					let chain_id = t.chain_id().expect("access-list transactions always carry a chain id; qed");
+					tx.fake_sign_with_access_list(sender, chain_id, access_list.clone())
+				},
+				None => tx.fake_sign(sender),
+			};
 
-			let mut clone = state.clone();
-			Ok(Executive::new(&mut clone, &env_info, self.engine.machine())
-				.transact_virtual(&tx, options())
+			let mut overlay = state.overlay();
+			overlay.set_vm_factory(self.virtual_vm_factory.clone());
+			Ok(BlockExecutor::new(env_info.clone())
+				.transact_virtual(&mut overlay, self.engine.machine(), &tx, options())
 				.map(|r| r.exception.is_none())
 				.unwrap_or(false))
 		};
@@ -1500,7 +1689,7 @@ impl Call for Client {
 				return Err(err.into())
 			}
 		}
-		let lower = t.gas_required(&self.engine.schedule(env_info.number)).into();
+		let lower = intrinsic_gas(t, &self.engine.schedule(env_info.number));
 		if cond(lower)? {
 			trace!(target: "estimate_gas", "estimate_gas succeeded with {}", lower);
 			return Ok(lower)
@@ -1529,6 +1718,47 @@ impl Call for Client {
 		trace!(target: "estimate_gas", "estimate_gas chopping {} .. {}", lower, upper);
 		binary_chop(lower, upper, cond)
 	}
+
+	fn create_access_list(&self, t: &SignedTransaction, state: &Self::State, header: &Header) -> Result<(state::AccessList, U256), CallError> {
+		let mut tracked = state.overlay();
+		tracked.track_accesses();
+		let env_info = self.virtual_env_info(header);
+		Self::do_virtual_call(self.engine.machine(), &self.virtual_vm_factory, &mut BlockExecutor::new(env_info.clone()), &mut tracked, t, Default::default())?;
+
+		let mut accessed = tracked.accessed().expect("access tracking enabled above; qed");
+		// the sender, the recipient and the precompiles are accessed by every such transaction,
+		// declaring them only adds to its cost.
+		accessed.remove(&t.sender());
+		if let Action::Call(ref to) = t.action {
+			accessed.remove(to);
+		}
+		let precompiles: Vec<_> = accessed.keys()
+			.filter(|address| self.engine.builtin(address, env_info.number).is_some())
+			.cloned()
+			.collect();
+		for address in &precompiles {
+			accessed.remove(address);
+		}
+		// the block author is touched by the execution itself
+		let author = env_info.author;
+		if accessed.get(&author).map_or(false, |keys| keys.is_empty()) {
+			accessed.remove(&author);
+		}
+
+		// estimate the gas of the transaction declaring the list, where the chain supports it
+		let gas = if self.engine.schedule(env_info.number).have_access_lists {
+			// access-list transactions carry the chain id even before replay protection is enabled
+			let chain_id = self.engine.signing_chain_id(&env_info).unwrap_or(self.engine.params().chain_id);
+			let access_list = accessed.iter()
+				.map(|(address, keys)| (*address, keys.iter().cloned().collect()))
+				.collect();
+			let with_access_list = t.as_unsigned().clone().fake_sign_with_access_list(t.sender(), chain_id, access_list);
+			self.estimate_gas(&with_access_list, state, header)?
+		} else {
+			self.estimate_gas(t, state, header)?
+		};
+		Ok((accessed, gas))
+	}
 }
 
 impl EngineInfo for Client {
@@ -1547,11 +1777,12 @@ impl BlockChainClient for Client {
 	}
 
 	fn replay_block_transactions(&self, block: BlockId, analytics: CallAnalytics) -> Result<Box<Iterator<Item = Executed>>, CallError> {
-		let mut env_info = self.env_info(block).ok_or(CallError::StatePruned)?;
+		let mut executor = BlockExecutor::new(self.env_info(block).ok_or(CallError::StatePruned)?);
 		let body = self.block_body(block).ok_or(CallError::StatePruned)?;
 		let mut state = self.state_at_beginning(block).ok_or(CallError::StatePruned)?;
 		let txs = body.transactions();
 		let engine = self.engine.clone();
+		let vm_factory = self.virtual_vm_factory.clone();
 
 		const PROOF: &'static str = "Transactions fetched from blockchain; blockchain transactions are valid; qed";
 		const EXECUTE_PROOF: &'static str = "Transaction replayed; qed";
@@ -1560,9 +1791,7 @@ impl BlockChainClient for Client {
 			.map(move |t| {
 				let t = SignedTransaction::new(t).expect(PROOF);
 				let machine = engine.machine();
-				let x = Self::do_virtual_call(machine, &env_info, &mut state, &t, analytics).expect(EXECUTE_PROOF);
-				env_info.gas_used = env_info.gas_used + x.gas_used;
-				x
+				Self::do_virtual_call(machine, &vm_factory, &mut executor, &mut state, &t, analytics).expect(EXECUTE_PROOF)
 			})))
 	}
 
@@ -1753,6 +1982,11 @@ impl BlockChainClient for Client {
 		self.transaction_address(id).and_then(|address| self.chain.read().transaction(&address))
 	}
 
+	fn transaction_by_sender_and_nonce(&self, sender: &Address, nonce: &U256) -> Option<LocalizedTransaction> {
+		let hash = self.chain.read().transaction_hash_by_sender_and_nonce(sender, nonce)?;
+		self.transaction(TransactionId::Hash(hash))
+	}
+
 	fn uncle(&self, id: UncleId) -> Option<encoded::Header> {
 		let index = id.position;
 		self.block_body(id.block).and_then(|body| body.view().uncle_rlp_at(index))
@@ -1950,6 +2184,15 @@ impl BlockChainClient for Client {
 			.and_then(|number| self.tracedb.read().block_traces(number))
 	}
 
+	fn trace_storage_usage(&self) -> Option<TraceStorageUsage> {
+		let tracedb = self.tracedb.read();
+		if !tracedb.tracing_enabled() {
+			return None;
+		}
+
+		Some(tracedb.storage_usage())
+	}
+
 	fn last_hashes(&self) -> LastHashes {
 		(*self.build_last_hashes(&self.chain.read().best_block_hash())).clone()
 	}
@@ -1958,6 +2201,24 @@ impl BlockChainClient for Client {
 		self.importer.miner.ready_transactions(self)
 	}
 
+	fn queued_transaction(&self, hash: &H256) -> Option<Arc<VerifiedTransaction>> {
+		self.importer.miner.transaction(hash)
+	}
+
+	fn transactions_to_propagate(&self) -> Vec<Arc<VerifiedTransaction>> {
+		let miner = &self.importer.miner;
+		self.ready_transactions()
+			.into_iter()
+			.filter(|tx| !tx.pending().local_only && miner.should_propagate(tx))
+			.collect()
+	}
+
+	fn transaction_to_propagate(&self, hash: &H256) -> Option<Arc<VerifiedTransaction>> {
+		let miner = &self.importer.miner;
+		self.queued_transaction(hash)
+			.and_then(|tx| if !tx.pending().local_only && miner.should_propagate(&tx) { Some(tx) } else { None })
+	}
+
 	fn signing_chain_id(&self) -> Option<u64> {
 		self.engine.signing_chain_id(&self.latest_env_info())
 	}
@@ -2007,6 +2268,80 @@ impl BlockChainClient for Client {
 	fn eip86_transition(&self) -> u64 {
 		self.engine().params().eip86_transition
 	}
+
+	fn start_state_healing(&self) {
+		let header = self.best_block_header();
+		let root = *header.state_root();
+		info!(target: "client", "Healing state {} of block #{}", root, header.number());
+		*self.state_healer.lock() = Some(StateHealing {
+			healer: StateHealer::new(root, self.factories.accountdb.clone()),
+			block: header.number(),
+			_pin: self.pin_state(header.number()),
+		});
+	}
+
+	fn state_healing_request(&self, max: usize) -> Vec<H256> {
+		let mut healer = self.state_healer.lock();
+		if let Some(ref mut healing) = *healer {
+			let header = self.best_block_header();
+			if header.number() >= healing.block + HEALING_REANCHOR_BLOCKS {
+				info!(target: "client", "Healing state {} of block #{} instead of #{}", header.state_root(), header.number(), healing.block);
+				healing.healer.reanchor(*header.state_root());
+				healing.block = header.number();
+				healing._pin = self.pin_state(header.number());
+			}
+		}
+		let request = match *healer {
+			Some(ref mut healing) => healing.healer.request(self.state_db.read().as_hashdb(), max, MAX_HEALING_CHECKS),
+			None => return Vec::new(),
+		};
+
+		let status = healer.as_ref().map(|healing| healing.healer.status());
+		match status {
+			Some(ref status) if status.is_done() => {
+				info!(target: "client", "State {} healed: {} nodes checked, {} retrieved, {} missing",
+					status.root, status.checked, status.healed, status.failed);
+				*healer = None;
+			},
+			_ => {},
+		}
+
+		request
+	}
+
+	fn import_state_nodes(&self, nodes: Vec<Bytes>) {
+		let mut healer = self.state_healer.lock();
+		let healer = match *healer {
+			Some(ref mut healing) => &mut healing.healer,
+			None => return,
+		};
+
+		let healed_before = healer.status().healed;
+		let mut db = self.state_db.read().journal_db().boxed_clone();
+		if healer.import(db.as_hashdb_mut(), &nodes) == 0 {
+			return;
+		}
+
+		let mut batch = DBTransaction::new();
+		if let Err(e) = db.inject(&mut batch) {
+			warn!(target: "client", "Failed to write healed state nodes: {}", e);
+			return;
+		}
+		if let Err(e) = db.backing().write(batch) {
+			warn!(target: "client", "Failed to write healed state nodes: {}", e);
+			return;
+		}
+
+		let status = healer.status();
+		if status.healed / HEALING_REPORT_INTERVAL != healed_before / HEALING_REPORT_INTERVAL {
+			info!(target: "client", "Healing state {}: {} nodes checked, {} retrieved, {} pending",
+				status.root, status.checked, status.healed, status.pending);
+		}
+	}
+
+	fn state_healing_status(&self) -> Option<StateHealingStatus> {
+		self.state_healer.lock().as_ref().map(|healing| healing.healer.status())
+	}
 }
 
 impl IoClient for Client {
@@ -2070,9 +2405,13 @@ impl IoClient for Client {
 		}
 	}
 
-	fn queue_consensus_message(&self, message: Bytes) {
+	fn queue_consensus_message(&self, message: Bytes, node_id: Option<H512>) {
 		match self.queue_consensus_message.queue(&mut self.io_channel.lock(), move |client| {
-			if let Err(e) = client.engine().handle_message(&message) {
+			let result = match node_id {
+				Some(ref node_id) => client.engine().handle_message_from(&message, node_id),
+				None => client.engine().handle_message(&message),
+			};
+			if let Err(e) = result {
 				debug!(target: "poa", "Invalid message received: {}", e);
 			}
 		}) {
@@ -2234,6 +2573,14 @@ impl super::traits::EngineClient for Client {
 		self.notify(|notify| notify.broadcast(ChainMessageType::Consensus(message.clone())));
 	}
 
+	fn send_consensus_message(&self, message: Bytes, node_id: H512) {
+		self.notify(|notify| notify.send(ChainMessageType::Consensus(message.clone()), &node_id));
+	}
+
+	fn notify_liveness_alert(&self, alert: LivenessAlert) {
+		self.notify(|notify| notify.liveness_alert(&alert));
+	}
+
 	fn epoch_transition_for(&self, parent_hash: H256) -> Option<::engines::EpochTransition> {
 		self.chain.read().epoch_transition_for(parent_hash)
 	}
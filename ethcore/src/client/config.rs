@@ -144,6 +144,11 @@ pub struct ClientConfig {
 	pub state_cache_size: usize,
 	/// EVM jump-tables cache size.
 	pub jump_table_size: usize,
+	/// Maximal memory, in bytes, a single EVM call frame of a virtual call (`eth_call`, gas estimation
+	/// or tracing) may use. `None` for the default limit. Imported blocks are never limited.
+	pub vm_memory_limit: Option<usize>,
+	/// Redistribute the state, blockchain, traces and jump-tables cache sizes based on their usage.
+	pub balance_caches: bool,
 	/// Minimum state pruning history size.
 	pub history: u64,
 	/// Ideal memory usage for state pruning history.
@@ -164,7 +164,7 @@ impl<'a> EvmTestClient<'a> {
 			spec.engine.account_start_nonce(0),
 			factories.clone(),
 		);
-		state.populate_from(pod_state);
+		state.populate_from(pod_state)?;
 		state.commit()?;
 		Ok(state)
 	}
@@ -238,6 +238,7 @@ impl<'a> EvmTestClient<'a> {
 					output: result.output,
 					trace: result.trace,
 					vm_trace: result.vm_trace,
+					exception: result.exception,
 					logs: result.receipt.logs,
 					contract_address: if let transaction::Action::Create = transaction.action {
 						Some(executive::contract_address(scheme, &transaction.sender(), &transaction.nonce, &transaction.data).0)
@@ -269,6 +270,8 @@ pub enum TransactResult<T, V> {
 		trace: Vec<T>,
 		/// VM Traces
 		vm_trace: Option<V>,
+		/// VM error the transaction failed with (if any)
+		exception: Option<vm::Error>,
 		/// Created contract address (if any)
 		contract_address: Option<H160>,
 		/// Generated logs
@@ -16,7 +16,7 @@
 
 use std::fmt;
 use bytes::Bytes;
-use client::Client;
+use client::{Client, StatePin};
 use ethereum_types::H256;
 use snapshot::ManifestData;
 
@@ -33,8 +33,8 @@ pub enum ClientIoMessage {
 	FeedStateChunk(H256, Bytes),
 	/// Feed a block chunk to the snapshot service
 	FeedBlockChunk(H256, Bytes),
-	/// Take a snapshot for the block with given number.
-	TakeSnapshot(u64),
+	/// Take a snapshot for the block with given number, keeping its state pinned if a pin is given.
+	TakeSnapshot(u64, Option<StatePin>),
 	/// Execute wrapped closure
 	Execute(Callback),
 }
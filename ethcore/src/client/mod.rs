@@ -17,19 +17,26 @@
 //! Blockchain database client.
 
 mod ancient_import;
+mod audit;
+mod cache_governor;
 mod client;
 mod config;
 mod error;
 mod evm_test_client;
 mod io_message;
+mod state_pin;
 mod test_client;
 mod trace;
 
+pub use self::audit::{audit_block, audit_chain, BlockAudit, Inconsistency};
+pub use self::cache_governor::{CacheGovernor, CacheAllocation, CacheUsage, Phase as CachePhase};
 pub use self::client::*;
 pub use self::config::{Mode, ClientConfig, DatabaseCompactionProfile, BlockChainConfig, VMType};
+pub use blockchain::{CanonCursor, CanonItem, CanonIter};
 pub use self::error::Error;
 pub use self::evm_test_client::{EvmTestClient, EvmTestError, TransactResult};
 pub use self::io_message::ClientIoMessage;
+pub use self::state_pin::{PinnedStates, StatePin};
 pub use self::test_client::{TestBlockChainClient, EachBlockWith};
 pub use self::chain_notify::{ChainNotify, ChainRoute, ChainRouteType, ChainMessageType};
 pub use self::traits::{
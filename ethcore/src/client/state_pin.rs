@@ -0,0 +1,117 @@
+// Copyright 2015-2018 Parity Technologies (UK) Ltd.
+// This file is part of Parity.
+
+// Parity is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// Parity is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with Parity.  If not, see <http://www.gnu.org/licenses/>.
+
+//! States kept from being pruned.
+//!
+//! Every user of a state which must survive pruning holds a `StatePin` of its block.
+//! The state stays pinned until all pins of the block are dropped.
+
+use std::collections::BTreeMap;
+use std::fmt;
+use std::sync::Arc;
+use parking_lot::Mutex;
+use header::BlockNumber;
+
+/// Blocks whose state is pinned, with the number of pins held for each.
+#[derive(Default, Clone)]
+pub struct PinnedStates {
+	pins: Arc<Mutex<BTreeMap<BlockNumber, usize>>>,
+}
+
+impl PinnedStates {
+	/// Pin the state at given block until the returned guard is dropped.
+	pub fn pin(&self, number: BlockNumber) -> StatePin {
+		*self.pins.lock().entry(number).or_insert(0) += 1;
+		StatePin {
+			pins: self.clone(),
+			number: number,
+		}
+	}
+
+	/// Earliest block whose state is pinned.
+	pub fn earliest(&self) -> Option<BlockNumber> {
+		self.pins.lock().keys().next().cloned()
+	}
+
+	fn unpin(&self, number: BlockNumber) {
+		let mut pins = self.pins.lock();
+		let remove = match pins.get_mut(&number) {
+			Some(count) => {
+				*count -= 1;
+				*count == 0
+			},
+			None => false,
+		};
+		if remove {
+			pins.remove(&number);
+		}
+	}
+}
+
+/// Keeps the state at a block from being pruned while alive.
+/// Cloning the pin pins the state once more.
+pub struct StatePin {
+	pins: PinnedStates,
+	number: BlockNumber,
+}
+
+impl StatePin {
+	/// Block whose state is pinned.
+	pub fn number(&self) -> BlockNumber {
+		self.number
+	}
+}
+
+impl Clone for StatePin {
+	fn clone(&self) -> Self {
+		self.pins.pin(self.number)
+	}
+}
+
+impl Drop for StatePin {
+	fn drop(&mut self) {
+		self.pins.unpin(self.number);
+	}
+}
+
+impl fmt::Debug for StatePin {
+	fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+		write!(f, "StatePin({})", self.number)
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::PinnedStates;
+
+	#[test]
+	fn counts_pins_of_each_block() {
+		let pinned = PinnedStates::default();
+		assert_eq!(pinned.earliest(), None);
+
+		let a = pinned.pin(10);
+		let b = pinned.pin(5);
+		let c = b.clone();
+		assert_eq!(pinned.earliest(), Some(5));
+
+		drop(b);
+		assert_eq!(pinned.earliest(), Some(5));
+		drop(c);
+		assert_eq!(pinned.earliest(), Some(10));
+		drop(a);
+		assert_eq!(pinned.earliest(), None);
+	}
+}
@@ -23,7 +23,7 @@ use std::mem;
 use itertools::Itertools;
 use rustc_hex::FromHex;
 use hash::keccak;
-use ethereum_types::{H256, U256, Address};
+use ethereum_types::{H256, H512, U256, Address};
 use parking_lot::RwLock;
 use journaldb;
 use kvdb::DBValue;
@@ -58,11 +58,13 @@ use verification::queue::QueueInfo;
 use block::{OpenBlock, SealedBlock, ClosedBlock};
 use executive::Executed;
 use error::CallError;
-use trace::LocalizedTrace;
+use trace::{LocalizedTrace, StorageUsage as TraceStorageUsage};
 use state_db::StateDB;
+use state::AccessList;
+use state_healing::StateHealingStatus;
 use header::Header;
 use encoded;
-use engines::EthEngine;
+use engines::{EthEngine, LivenessAlert};
 use trie;
 use state::StateInfo;
 use views::BlockView;
@@ -328,6 +330,29 @@ impl TestBlockChainClient {
 
 	/// Inserts a transaction with given gas price to miners transactions queue.
 	pub fn insert_transaction_with_gas_price_to_queue(&self, gas_price: U256) -> H256 {
+		let signed_tx = self.funded_transaction(gas_price);
+		let hash = signed_tx.hash();
+		let res = self.miner.import_external_transactions(self, vec![signed_tx.into()]);
+		let res = res.into_iter().next().unwrap();
+		assert!(res.is_ok());
+		hash
+	}
+
+	/// Inserts a transaction to miners transactions queue.
+	pub fn insert_transaction_to_queue(&self) -> H256 {
+		self.insert_transaction_with_gas_price_to_queue(U256::from(20_000_000_000u64))
+	}
+
+	/// Inserts a local transaction to miners transactions queue.
+	pub fn insert_local_transaction_to_queue(&self) -> H256 {
+		let signed_tx = self.funded_transaction(U256::from(20_000_000_000u64));
+		let hash = signed_tx.hash();
+		self.miner.import_own_transaction(self, signed_tx.into()).unwrap();
+		hash
+	}
+
+	// transaction from a new account with enough balance to pay for it.
+	fn funded_transaction(&self, gas_price: U256) -> SignedTransaction {
 		let keypair = Random.generate().unwrap();
 		let tx = Transaction {
 			action: Action::Create,
@@ -339,16 +364,7 @@ impl TestBlockChainClient {
 		};
 		let signed_tx = tx.sign(keypair.secret(), None);
 		self.set_balance(signed_tx.sender(), 10_000_000_000_000_000_000u64.into());
-		let hash = signed_tx.hash();
-		let res = self.miner.import_external_transactions(self, vec![signed_tx.into()]);
-		let res = res.into_iter().next().unwrap();
-		assert!(res.is_ok());
-		hash
-	}
-
-	/// Inserts a transaction to miners transactions queue.
-	pub fn insert_transaction_to_queue(&self) -> H256 {
-		self.insert_transaction_with_gas_price_to_queue(U256::from(20_000_000_000u64))
+		signed_tx
 	}
 
 	/// Set reported history size.
@@ -576,9 +592,17 @@ impl Call for TestBlockChainClient {
 		Ok(res)
 	}
 
+	fn call_at(&self, _t: &SignedTransaction, _analytics: CallAnalytics, _id: BlockId) -> Result<Executed, CallError> {
+		self.execution_result.read().clone().unwrap()
+	}
+
 	fn estimate_gas(&self, _t: &SignedTransaction, _state: &Self::State, _header: &Header) -> Result<U256, CallError> {
 		Ok(21000.into())
 	}
+
+	fn create_access_list(&self, t: &SignedTransaction, state: &Self::State, header: &Header) -> Result<(AccessList, U256), CallError> {
+		Ok((AccessList::new(), self.estimate_gas(t, state, header)?))
+	}
 }
 
 impl StateInfo for () {
@@ -653,6 +677,10 @@ impl BlockChainClient for TestBlockChainClient {
 		None	// Simple default.
 	}
 
+	fn transaction_by_sender_and_nonce(&self, _sender: &Address, _nonce: &U256) -> Option<LocalizedTransaction> {
+		None	// Simple default.
+	}
+
 	fn uncle(&self, _id: UncleId) -> Option<encoded::Header> {
 		None	// Simple default.
 	}
@@ -808,10 +836,18 @@ impl BlockChainClient for TestBlockChainClient {
 		self.traces.read().clone()
 	}
 
+	fn trace_storage_usage(&self) -> Option<TraceStorageUsage> {
+		None
+	}
+
 	fn ready_transactions(&self) -> Vec<Arc<VerifiedTransaction>> {
 		self.miner.ready_transactions(self)
 	}
 
+	fn queued_transaction(&self, hash: &H256) -> Option<Arc<VerifiedTransaction>> {
+		self.miner.transaction(hash)
+	}
+
 	fn signing_chain_id(&self) -> Option<u64> { None }
 
 	fn mode(&self) -> Mode { Mode::Active }
@@ -850,6 +886,14 @@ impl BlockChainClient for TestBlockChainClient {
 	fn registrar_address(&self) -> Option<Address> { None }
 
 	fn eip86_transition(&self) -> u64 { u64::max_value() }
+
+	fn start_state_healing(&self) {}
+
+	fn state_healing_request(&self, _max: usize) -> Vec<H256> { Vec::new() }
+
+	fn import_state_nodes(&self, _nodes: Vec<Bytes>) {}
+
+	fn state_healing_status(&self) -> Option<StateHealingStatus> { None }
 }
 
 impl IoClient for TestBlockChainClient {
@@ -863,8 +907,11 @@ impl IoClient for TestBlockChainClient {
 		self.import_block(b)
 	}
 
-	fn queue_consensus_message(&self, message: Bytes) {
-		self.spec.engine.handle_message(&message).unwrap();
+	fn queue_consensus_message(&self, message: Bytes, node_id: Option<H512>) {
+		match node_id {
+			Some(ref node_id) => self.spec.engine.handle_message_from(&message, node_id).unwrap(),
+			None => self.spec.engine.handle_message(&message).unwrap(),
+		}
 	}
 }
 
@@ -900,6 +947,10 @@ impl super::traits::EngineClient for TestBlockChainClient {
 
 	fn broadcast_consensus_message(&self, _message: Bytes) {}
 
+	fn send_consensus_message(&self, _message: Bytes, _node_id: H512) {}
+
+	fn notify_liveness_alert(&self, _alert: LivenessAlert) {}
+
 	fn epoch_transition_for(&self, _block_hash: H256) -> Option<::engines::EpochTransition> {
 		None
 	}
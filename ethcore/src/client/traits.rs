@@ -30,14 +30,15 @@ use filter::Filter;
 use header::{BlockNumber};
 use log_entry::LocalizedLogEntry;
 use receipt::LocalizedReceipt;
-use trace::LocalizedTrace;
+use trace::{LocalizedTrace, StorageUsage as TraceStorageUsage};
 use transaction::{self, LocalizedTransaction, SignedTransaction};
 use verification::queue::QueueInfo as BlockQueueInfo;
-use state::StateInfo;
+use state::{StateInfo, AccessList};
+use state_healing::StateHealingStatus;
 use header::Header;
-use engines::EthEngine;
+use engines::{EthEngine, LivenessAlert};
 
-use ethereum_types::{H256, U256, Address};
+use ethereum_types::{H256, H512, U256, Address};
 use ethcore_miner::pool::VerifiedTransaction;
 use bytes::Bytes;
 use hashdb::DBValue;
@@ -188,8 +189,17 @@ pub trait Call {
 	/// Returns a vector of successes or a failure if any of the transaction fails.
 	fn call_many(&self, txs: &[(SignedTransaction, CallAnalytics)], state: &mut Self::State, header: &Header) -> Result<Vec<Executed>, CallError>;
 
+	/// Makes a non-persistent transaction call on top of the state of given block.
+	/// Calls on historical blocks don't contend with block import.
+	fn call_at(&self, tx: &SignedTransaction, analytics: CallAnalytics, id: BlockId) -> Result<Executed, CallError>;
+
 	/// Estimates how much gas will be necessary for a call.
 	fn estimate_gas(&self, t: &SignedTransaction, state: &Self::State, header: &Header) -> Result<U256, CallError>;
+
+	/// Executes a call recording accessed accounts and storage keys.
+	/// Returns the access list, leaving out the sender, the recipient and the precompiles,
+	/// together with the gas estimate for the call declaring it.
+	fn create_access_list(&self, t: &SignedTransaction, state: &Self::State, header: &Header) -> Result<(AccessList, U256), CallError>;
 }
 
 /// Provides `engine` method
@@ -207,7 +217,7 @@ pub trait IoClient: Sync + Send {
 	fn queue_ancient_block(&self, block_bytes: Bytes, receipts_bytes: Bytes) -> Result<H256, BlockImportError>;
 
 	/// Queue conensus engine message.
-	fn queue_consensus_message(&self, message: Bytes);
+	fn queue_consensus_message(&self, message: Bytes, node_id: Option<H512>);
 }
 
 /// Blockchain database client. Owns and manages a blockchain and a block queue.
@@ -268,6 +278,9 @@ pub trait BlockChainClient : Sync + Send + AccountData + BlockChain + CallContra
 	/// Get transaction with given hash.
 	fn transaction(&self, id: TransactionId) -> Option<LocalizedTransaction>;
 
+	/// Get the canonical transaction with given sender and nonce.
+	fn transaction_by_sender_and_nonce(&self, sender: &Address, nonce: &U256) -> Option<LocalizedTransaction>;
+
 	/// Get uncle with given id.
 	fn uncle(&self, id: UncleId) -> Option<encoded::Header>;
 
@@ -317,12 +330,31 @@ pub trait BlockChainClient : Sync + Send + AccountData + BlockChain + CallContra
 	/// Returns traces created by transaction from block.
 	fn block_traces(&self, trace: BlockId) -> Option<Vec<LocalizedTrace>>;
 
+	/// Returns storage used by the traces database, `None` if tracing is disabled.
+	fn trace_storage_usage(&self) -> Option<TraceStorageUsage>;
+
 	/// Get last hashes starting from best block.
 	fn last_hashes(&self) -> LastHashes;
 
 	/// List all transactions that are allowed into the next block.
 	fn ready_transactions(&self) -> Vec<Arc<VerifiedTransaction>>;
 
+	/// Get transaction with given hash from the queue.
+	fn queued_transaction(&self, hash: &H256) -> Option<Arc<VerifiedTransaction>>;
+
+	/// List ready transactions which may be propagated to peers, i.e. all but the local-only ones.
+	fn transactions_to_propagate(&self) -> Vec<Arc<VerifiedTransaction>> {
+		self.ready_transactions()
+			.into_iter()
+			.filter(|tx| !tx.pending().local_only)
+			.collect()
+	}
+
+	/// Get queued transaction with given hash if it may be propagated to peers.
+	fn transaction_to_propagate(&self, hash: &H256) -> Option<Arc<VerifiedTransaction>> {
+		self.queued_transaction(hash).and_then(|tx| if tx.pending().local_only { None } else { Some(tx) })
+	}
+
 	/// Sorted list of transaction gas prices from at least last sample_size blocks.
 	fn gas_price_corpus(&self, sample_size: usize) -> ::stats::Corpus<U256> {
 		let mut h = self.chain_info().best_block_hash;
@@ -380,6 +412,19 @@ pub trait BlockChainClient : Sync + Send + AccountData + BlockChain + CallContra
 
 	/// Get the EIP-86 transition block number.
 	fn eip86_transition(&self) -> u64;
+
+	/// Start healing the state of the best block, e.g. after an interrupted warp sync.
+	/// Restarts the healing if it's already ongoing.
+	fn start_state_healing(&self);
+
+	/// Hashes of at most `max` missing state nodes to request from peers.
+	fn state_healing_request(&self, max: usize) -> Vec<H256>;
+
+	/// Import state nodes retrieved from peers.
+	fn import_state_nodes(&self, nodes: Vec<Bytes>);
+
+	/// Progress of the state healing, if any.
+	fn state_healing_status(&self) -> Option<StateHealingStatus>;
 }
 
 /// Provides `reopen_block` method
@@ -433,6 +478,12 @@ pub trait EngineClient: Sync + Send + ChainInfo {
 	/// Broadcast a consensus message to the network.
 	fn broadcast_consensus_message(&self, message: Bytes);
 
+	/// Send a consensus message to the peer with given node id only.
+	fn send_consensus_message(&self, message: Bytes, node_id: H512);
+
+	/// Notify listeners that a validator has stopped sealing.
+	fn notify_liveness_alert(&self, alert: LivenessAlert);
+
 	/// Get the transition to the epoch the given parent hash is part of
 	/// or transitions to.
 	/// This will give the epoch that any children of this parent belong to.
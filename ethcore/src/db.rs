@@ -20,6 +20,7 @@ use std::ops::Deref;
 use std::hash::Hash;
 use std::collections::HashMap;
 use parking_lot::RwLock;
+use memory_cache::AtomicCacheStats;
 use kvdb::{DBTransaction, KeyValueDB};
 
 use rlp;
@@ -188,6 +189,28 @@ pub trait Readable {
 		})
 	}
 
+	/// Returns value for given key either in cache or in database, noting in `stats`
+	/// whether it was found in the cache.
+	fn read_with_cache_stats<K, T, C>(&self, col: Option<u32>, cache: &RwLock<C>, key: &K, stats: &AtomicCacheStats) -> Option<T> where
+		K: Key<T> + Eq + Hash + Clone,
+		T: Clone + rlp::Decodable,
+		C: Cache<K, T> {
+		{
+			let read = cache.read();
+			if let Some(v) = read.get(key) {
+				stats.note_hit();
+				return Some(v.clone());
+			}
+		}
+
+		stats.note_miss();
+		self.read(col, key).map(|value: T|{
+			let mut write = cache.write();
+			write.insert(key.clone(), value.clone());
+			value
+		})
+	}
+
 	/// Returns true if given value exists.
 	fn exists<T, R>(&self, col: Option<u32>, key: &Key<T, Target = R>) -> bool where R: Deref<Target= [u8]>;
 
@@ -29,13 +29,14 @@ use client::EngineClient;
 use engines::{Engine, Seal, EngineError, ConstructedVerifier};
 use engines::block_reward;
 use engines::block_reward::{BlockRewardContract, RewardKind};
+use engines::liveness::{LivenessTracker, LivenessAlert, ValidatorLiveness};
 use error::{Error, BlockError};
 use ethjson;
 use machine::{AuxiliaryData, Call, EthereumMachine};
 use hash::keccak;
 use header::{Header, BlockNumber, ExtendedHeader};
 
-use super::signer::EngineSigner;
+use super::signer::{EngineSigner, SignerTimeoutPolicy, TimedSigner};
 use super::validator_set::{ValidatorSet, SimpleList, new_validator_set};
 
 use self::finality::RollingFinality;
@@ -82,10 +83,15 @@ pub struct AuthorityRoundParams {
 	pub empty_steps_transition: u64,
 	/// Number of accepted empty steps.
 	pub maximum_empty_steps: usize,
+	/// Number of consecutive missed steps after which a validator is reported as offline.
+	pub missed_steps_alert_threshold: Option<u64>,
 }
 
 const U16_MAX: usize = ::std::u16::MAX as usize;
 
+// maximal number of skipped steps between two blocks accounted for in validators' liveness.
+const MAX_TRACKED_STEP_GAP: usize = 1024;
+
 impl From<ethjson::spec::AuthorityRoundParams> for AuthorityRoundParams {
 	fn from(p: ethjson::spec::AuthorityRoundParams) -> Self {
 		let mut step_duration_usize: usize = p.step_duration.into();
@@ -107,6 +113,7 @@ impl From<ethjson::spec::AuthorityRoundParams> for AuthorityRoundParams {
 			maximum_uncle_count: p.maximum_uncle_count.map_or(0, Into::into),
 			empty_steps_transition: p.empty_steps_transition.map_or(u64::max_value(), |n| ::std::cmp::max(n.into(), 1)),
 			maximum_empty_steps: p.maximum_empty_steps.map_or(0, Into::into),
+			missed_steps_alert_threshold: p.missed_steps_alert_threshold.map(Into::into),
 		}
 	}
 }
@@ -389,6 +396,7 @@ pub struct AuthorityRound {
 	can_propose: AtomicBool,
 	client: RwLock<Option<Weak<EngineClient>>>,
 	signer: RwLock<EngineSigner>,
+	seal_signer: TimedSigner,
 	validators: Box<ValidatorSet>,
 	validate_score_transition: u64,
 	validate_step_transition: u64,
@@ -402,6 +410,7 @@ pub struct AuthorityRound {
 	maximum_uncle_count: usize,
 	empty_steps_transition: u64,
 	maximum_empty_steps: usize,
+	liveness: LivenessTracker,
 	machine: EthereumMachine,
 }
 
@@ -623,6 +632,7 @@ impl AuthorityRound {
 				can_propose: AtomicBool::new(true),
 				client: RwLock::new(None),
 				signer: Default::default(),
+				seal_signer: TimedSigner::default(),
 				validators: our_params.validators,
 				validate_score_transition: our_params.validate_score_transition,
 				validate_step_transition: our_params.validate_step_transition,
@@ -636,6 +646,7 @@ impl AuthorityRound {
 				maximum_uncle_count: our_params.maximum_uncle_count,
 				empty_steps_transition: our_params.empty_steps_transition,
 				maximum_empty_steps: our_params.maximum_empty_steps,
+				liveness: LivenessTracker::new(our_params.missed_steps_alert_threshold),
 				machine: machine,
 			});
 
@@ -692,6 +703,41 @@ impl AuthorityRound {
 			}
 		}
 	}
+
+	fn notify_liveness_alert(&self, alert: LivenessAlert) {
+		match self.client.read().as_ref().and_then(|weak| weak.upgrade()) {
+			Some(client) => client.notify_liveness_alert(alert),
+			None => warn!(target: "engine", "Validator {} missed {} consecutive steps before block #{}",
+				alert.validator, alert.consecutive_missed, alert.block),
+		}
+	}
+
+	// note the author of a committed block as alive and the primaries of the skipped steps as missing.
+	fn note_liveness(&self, header: &Header, parent: &Header, step: usize, parent_step: usize) {
+		self.liveness.note_sealed(header.author(), header.number());
+		if step <= parent_step + 1 {
+			return;
+		}
+
+		let empty_steps = if header.number() >= self.empty_steps_transition {
+			header_empty_steps(header).unwrap_or_default()
+		} else {
+			Vec::new()
+		};
+		// only alert about blocks at the tip of the chain, not the ones being synced.
+		let is_recent = step + 1 >= self.step.load();
+
+		for s in (parent_step + 1..step).take(MAX_TRACKED_STEP_GAP) {
+			let primary = step_proposer(&*self.validators, &parent.hash(), s);
+			if empty_steps.iter().any(|e| e.step == s) {
+				self.liveness.note_empty_step(&primary);
+			} else if let Some(alert) = self.liveness.note_missed(&primary, header.number()) {
+				if is_recent {
+					self.notify_liveness_alert(alert);
+				}
+			}
+		}
+	}
 }
 
 fn unix_now() -> Duration {
@@ -780,6 +826,10 @@ impl Engine<EthereumMachine> for AuthorityRound {
 		info
 	}
 
+	fn validator_liveness(&self) -> BTreeMap<Address, ValidatorLiveness> {
+		self.liveness.report()
+	}
+
 	fn maximum_uncle_count(&self, block: BlockNumber) -> usize {
 		if block >= self.maximum_uncle_count_transition {
 			self.maximum_uncle_count
@@ -919,7 +969,15 @@ impl Engine<EthereumMachine> for AuthorityRound {
 				None
 			};
 
-			if let Ok(signature) = self.sign(header_seal_hash(header, empty_steps_rlp.as_ref().map(|e| &**e))) {
+			let seal_hash = header_seal_hash(header, empty_steps_rlp.as_ref().map(|e| &**e));
+			if let Ok(signature) = self.seal_signer.sign(&*self.signer.read(), seal_hash) {
+				let signature = match signature {
+					Some(signature) => signature,
+					None => {
+						debug!(target: "engine", "generate_seal: Signer timed out for step {}.", step);
+						return Seal::None;
+					},
+				};
 				trace!(target: "engine", "generate_seal: Issuing a block for step {}.", step);
 
 				// only issue the seal if we were the first to reach the compare_and_swap.
@@ -1180,6 +1238,18 @@ impl Engine<EthereumMachine> for AuthorityRound {
 		self.validators.signals_epoch_end(first, header, aux)
 	}
 
+	fn on_block_committed(&self, header: &Header, chain: &super::Headers<Header>) {
+		let parent = match chain(*header.parent_hash()) {
+			Some(parent) => parent,
+			None => return,
+		};
+		let steps = header_step(header, self.empty_steps_transition)
+			.and_then(|step| header_step(&parent, self.empty_steps_transition).map(|parent_step| (step, parent_step)));
+		if let Ok((step, parent_step)) = steps {
+			self.note_liveness(header, &parent, step, parent_step);
+		}
+	}
+
 	fn is_epoch_end(
 		&self,
 		chain_head: &Header,
@@ -1338,6 +1408,10 @@ impl Engine<EthereumMachine> for AuthorityRound {
 		self.signer.write().set(ap, address, password);
 	}
 
+	fn set_signer_timeout(&self, timeout: Option<Duration>, policy: SignerTimeoutPolicy) {
+		self.seal_signer.set_timeout(timeout, policy);
+	}
+
 	fn sign(&self, hash: H256) -> Result<Signature, Error> {
 		Ok(self.signer.read().sign(hash)?)
 	}
@@ -1368,13 +1442,13 @@ mod tests {
 		generate_dummy_client_with_spec_and_accounts, get_temp_state_db, generate_dummy_client,
 		TestNotify
 	};
-	use account_provider::AccountProvider;
+	use account_provider::{AccountProvider, KeyOperation};
 	use spec::Spec;
 	use transaction::{Action, Transaction};
-	use engines::{Seal, Engine, EngineError, EthEngine};
+	use engines::{Seal, Engine, EngineError, EthEngine, LivenessAlert};
 	use engines::validator_set::TestSet;
 	use error::{Error, ErrorKind};
-	use super::{AuthorityRoundParams, AuthorityRound, EmptyStep, SealedEmptyStep};
+	use super::{AuthorityRoundParams, AuthorityRound, EmptyStep, SealedEmptyStep, step_proposer};
 
 	#[test]
 	fn has_valid_metadata() {
@@ -1453,13 +1527,13 @@ mod tests {
 
 		engine.set_signer(tap.clone(), addr1, "1".into());
 		match engine.generate_seal(b1.block(), &genesis_header) {
-			Seal::None | Seal::Proposal(_) => panic!("wrong seal"),
+			Seal::None | Seal::Proposal(_) | Seal::External => panic!("wrong seal"),
 			Seal::Regular(_) => {
 				engine.step();
 
 				engine.set_signer(tap.clone(), addr2, "0".into());
 				match engine.generate_seal(b2.block(), &genesis_header) {
-					Seal::Regular(_) | Seal::Proposal(_) => panic!("sealed despite wrong difficulty"),
+					Seal::Regular(_) | Seal::Proposal(_) | Seal::External => panic!("sealed despite wrong difficulty"),
 					Seal::None => {}
 				}
 			}
@@ -1480,7 +1554,7 @@ mod tests {
 
 		let engine = Spec::new_test_round().engine;
 
-		let signature = tap.sign(addr, Some("0".into()), header.bare_hash()).unwrap();
+		let signature = tap.sign(addr, Some("0".into()), header.bare_hash(), KeyOperation::SignMessage, "test").unwrap();
 		// Two validators.
 		// Spec starts with step 2.
 		header.set_seal(vec![encode(&2usize).into_vec(), encode(&(&*signature as &[u8])).into_vec()]);
@@ -1506,7 +1580,7 @@ mod tests {
 
 		let engine = Spec::new_test_round().engine;
 
-		let signature = tap.sign(addr, Some("0".into()), header.bare_hash()).unwrap();
+		let signature = tap.sign(addr, Some("0".into()), header.bare_hash(), KeyOperation::SignMessage, "test").unwrap();
 		// Two validators.
 		// Spec starts with step 2.
 		header.set_seal(vec![encode(&1usize).into_vec(), encode(&(&*signature as &[u8])).into_vec()]);
@@ -1531,7 +1605,7 @@ mod tests {
 
 		let engine = Spec::new_test_round().engine;
 
-		let signature = tap.sign(addr, Some("0".into()), header.bare_hash()).unwrap();
+		let signature = tap.sign(addr, Some("0".into()), header.bare_hash(), KeyOperation::SignMessage, "test").unwrap();
 		// Two validators.
 		// Spec starts with step 2.
 		header.set_seal(vec![encode(&5usize).into_vec(), encode(&(&*signature as &[u8])).into_vec()]);
@@ -1554,6 +1628,7 @@ mod tests {
 			maximum_uncle_count: 0,
 			empty_steps_transition: u64::max_value(),
 			maximum_empty_steps: 0,
+			missed_steps_alert_threshold: None,
 			block_reward: Default::default(),
 			block_reward_contract_transition: 0,
 			block_reward_contract: Default::default(),
@@ -1584,6 +1659,58 @@ mod tests {
 		assert_eq!(last_benign.load(AtomicOrdering::SeqCst), 1);
 	}
 
+	#[test]
+	fn notes_liveness_of_committed_blocks_only() {
+		let params = AuthorityRoundParams {
+			step_duration: 1,
+			start_step: Some(1),
+			validators: Box::new(TestSet::new(Default::default(), Default::default())),
+			validate_score_transition: 0,
+			validate_step_transition: 0,
+			immediate_transitions: true,
+			maximum_uncle_count_transition: 0,
+			maximum_uncle_count: 0,
+			empty_steps_transition: u64::max_value(),
+			maximum_empty_steps: 0,
+			missed_steps_alert_threshold: Some(1),
+			block_reward: Default::default(),
+			block_reward_contract_transition: 0,
+			block_reward_contract: Default::default(),
+		};
+
+		let aura = {
+			let mut c_params = ::spec::CommonParams::default();
+			c_params.gas_limit_bound_divisor = 5.into();
+			let machine = ::machine::EthereumMachine::regular(c_params, Default::default());
+			AuthorityRound::new(params, machine).unwrap()
+		};
+		let client = generate_dummy_client(0);
+		let notify = Arc::new(TestNotify::default());
+		client.add_notify(notify.clone());
+		aura.register_client(Arc::downgrade(&client) as _);
+
+		let mut parent_header: Header = Header::default();
+		parent_header.set_seal(vec![encode(&1usize).into_vec()]);
+		parent_header.set_gas_limit("222222".parse::<U256>().unwrap());
+		let mut header: Header = Header::default();
+		header.set_number(1);
+		header.set_parent_hash(parent_header.hash());
+		header.set_gas_limit("222222".parse::<U256>().unwrap());
+		header.set_seal(vec![encode(&3usize).into_vec()]);
+
+		// verification alone does not count the block.
+		assert!(aura.verify_block_family(&header, &parent_header).is_ok());
+		assert!(aura.validator_liveness().is_empty());
+		assert!(notify.liveness_alerts.read().is_empty());
+
+		aura.on_block_committed(&header, &|hash| if hash == parent_header.hash() { Some(parent_header.clone()) } else { None });
+		let liveness = aura.validator_liveness();
+		assert_eq!(liveness[header.author()].sealed, 1);
+		let primary = step_proposer(&*aura.validators, &parent_header.hash(), 2);
+		assert_eq!(liveness[&primary].missed, 1);
+		assert_eq!(*notify.liveness_alerts.read(), vec![LivenessAlert { validator: primary, consecutive_missed: 1, block: 1 }]);
+	}
+
 	#[test]
 	fn test_uncles_transition() {
 		let last_benign = Arc::new(AtomicUsize::new(0));
@@ -1598,6 +1725,7 @@ mod tests {
 			maximum_uncle_count: 0,
 			empty_steps_transition: u64::max_value(),
 			maximum_empty_steps: 0,
+			missed_steps_alert_threshold: None,
 			block_reward: Default::default(),
 			block_reward_contract_transition: 0,
 			block_reward_contract: Default::default(),
@@ -1654,6 +1782,7 @@ mod tests {
 			maximum_uncle_count: 0,
 			empty_steps_transition: u64::max_value(),
 			maximum_empty_steps: 0,
+			missed_steps_alert_threshold: None,
 			block_reward: Default::default(),
 			block_reward_contract_transition: 0,
 			block_reward_contract: Default::default(),
@@ -1871,7 +2000,7 @@ mod tests {
 		header.set_gas_limit("222222".parse::<U256>().unwrap());
 		header.set_author(addr1);
 
-		let signature = tap.sign(addr1, Some("1".into()), header.bare_hash()).unwrap();
+		let signature = tap.sign(addr1, Some("1".into()), header.bare_hash(), KeyOperation::SignMessage, "test").unwrap();
 
 		// empty step with invalid step
 		let empty_steps = vec![SealedEmptyStep { signature: 0.into(), step: 2 }];
@@ -0,0 +1,213 @@
+// Copyright 2015-2018 Parity Technologies (UK) Ltd.
+// This file is part of Parity.
+
+// Parity is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// Parity is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with Parity.  If not, see <http://www.gnu.org/licenses/>.
+
+//! Clique signer schedule.
+//!
+//! Keeps the set of authorized signers, the signers of recent blocks and the pending votes
+//! as of some block, and advances them by applying the headers of the following blocks.
+
+use std::collections::{BTreeMap, BTreeSet, VecDeque};
+
+use engines::EngineError;
+use error::{BlockError, Error};
+use ethereum_types::{Address, U256};
+use header::{BlockNumber, Header};
+use unexpected::Mismatch;
+
+use super::{recover_signer, vote};
+
+/// Difficulty of a block sealed by the in-turn signer.
+pub const DIFF_INTURN: u64 = 2;
+/// Difficulty of a block sealed by an out-of-turn signer.
+pub const DIFF_NOTURN: u64 = 1;
+
+/// Kind of a signer vote.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum VoteType {
+	/// Vote to authorize the beneficiary.
+	Add,
+	/// Vote to deauthorize the beneficiary.
+	Remove,
+}
+
+/// Authorized signers, recent signers and pending votes as of a block.
+#[derive(Debug, Clone, PartialEq)]
+pub struct CliqueBlockState {
+	signers: BTreeSet<Address>,
+	// signers of the most recent blocks, the most recent first.
+	recent_signers: VecDeque<Address>,
+	// voters of every pending vote, by beneficiary and kind of the vote.
+	votes: BTreeMap<(Address, VoteType), BTreeSet<Address>>,
+}
+
+impl CliqueBlockState {
+	/// Create the schedule of a checkpoint block authorizing `signers`.
+	pub fn new(signers: BTreeSet<Address>) -> Self {
+		CliqueBlockState {
+			signers,
+			recent_signers: VecDeque::new(),
+			votes: BTreeMap::new(),
+		}
+	}
+
+	/// Authorized signers, in the order of their turns.
+	pub fn signers(&self) -> &BTreeSet<Address> {
+		&self.signers
+	}
+
+	/// Whether it is `signer`'s turn to seal block `number`.
+	pub fn is_inturn(&self, number: BlockNumber, signer: &Address) -> bool {
+		if self.signers.is_empty() {
+			return false;
+		}
+		let turn = (number % self.signers.len() as u64) as usize;
+		self.signers.iter().nth(turn) == Some(signer)
+	}
+
+	// number of consecutive blocks among which every signer may seal only one.
+	fn signer_limit(&self) -> usize {
+		self.signers.len() / 2 + 1
+	}
+
+	/// Verify `header` of the next block against the schedule and apply it. Returns the signer.
+	///
+	/// Votes are discarded at checkpoints, whose signer lists are verified by the caller.
+	pub fn apply(&mut self, header: &Header, is_checkpoint: bool) -> Result<Address, Error> {
+		let signer = recover_signer(header)?;
+		if !self.signers.contains(&signer) {
+			return Err(EngineError::NotAuthorized(signer).into());
+		}
+
+		let limit = self.signer_limit();
+		self.recent_signers.truncate(limit - 1);
+		if self.recent_signers.contains(&signer) {
+			return Err(EngineError::RecentlySigned(signer).into());
+		}
+
+		let expected_difficulty = if self.is_inturn(header.number(), &signer) { DIFF_INTURN } else { DIFF_NOTURN };
+		if *header.difficulty() != U256::from(expected_difficulty) {
+			return Err(BlockError::InvalidDifficulty(Mismatch {
+				expected: expected_difficulty.into(),
+				found: *header.difficulty(),
+			}).into());
+		}
+
+		self.recent_signers.push_front(signer);
+
+		if is_checkpoint {
+			self.votes.clear();
+			return Ok(signer);
+		}
+
+		if let Some(kind) = vote(header)? {
+			self.cast_vote(signer, *header.author(), kind);
+		}
+
+		Ok(signer)
+	}
+
+	fn cast_vote(&mut self, voter: Address, beneficiary: Address, kind: VoteType) {
+		// votes which wouldn't change the signer set are ignored.
+		let is_signer = self.signers.contains(&beneficiary);
+		if (kind == VoteType::Add) == is_signer {
+			return;
+		}
+
+		// every signer has a single vote per beneficiary.
+		for other in &[VoteType::Add, VoteType::Remove] {
+			if let Some(voters) = self.votes.get_mut(&(beneficiary, *other)) {
+				voters.remove(&voter);
+			}
+		}
+
+		let tally = {
+			let voters = self.votes.entry((beneficiary, kind)).or_insert_with(BTreeSet::new);
+			voters.insert(voter);
+			voters.len()
+		};
+
+		if tally > self.signers.len() / 2 {
+			trace!(target: "engine", "Clique vote passed: {:?} {}", kind, beneficiary);
+			match kind {
+				VoteType::Add => {
+					self.signers.insert(beneficiary);
+				},
+				VoteType::Remove => {
+					self.signers.remove(&beneficiary);
+					// votes of the removed signer don't count anymore.
+					for voters in self.votes.values_mut() {
+						voters.remove(&beneficiary);
+					}
+				},
+			}
+			self.votes.remove(&(beneficiary, VoteType::Add));
+			self.votes.remove(&(beneficiary, VoteType::Remove));
+			self.votes.retain(|_, voters| !voters.is_empty());
+		}
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use std::collections::BTreeSet;
+	use ethereum_types::Address;
+	use super::{CliqueBlockState, VoteType};
+
+	fn state(signers: &[u64]) -> CliqueBlockState {
+		CliqueBlockState::new(signers.iter().map(|s| Address::from(*s)).collect())
+	}
+
+	#[test]
+	fn rotates_turns() {
+		let state = state(&[3, 1, 2]);
+		assert!(state.is_inturn(0, &Address::from(1)));
+		assert!(state.is_inturn(1, &Address::from(2)));
+		assert!(state.is_inturn(5, &Address::from(3)));
+		assert!(!state.is_inturn(5, &Address::from(1)));
+	}
+
+	#[test]
+	fn applies_votes_on_majority() {
+		let mut state = state(&[1, 2, 3]);
+		let new = Address::from(4);
+
+		state.cast_vote(Address::from(1), new, VoteType::Add);
+		// repeated votes of a single signer count once.
+		state.cast_vote(Address::from(1), new, VoteType::Add);
+		assert!(!state.signers().contains(&new));
+		state.cast_vote(Address::from(2), new, VoteType::Add);
+		assert!(state.signers().contains(&new));
+		assert!(state.votes.is_empty());
+
+		state.cast_vote(Address::from(1), Address::from(3), VoteType::Remove);
+		state.cast_vote(Address::from(2), Address::from(3), VoteType::Remove);
+		state.cast_vote(Address::from(4), Address::from(3), VoteType::Remove);
+		let expected: BTreeSet<_> = [1, 2, 4].iter().map(|s| Address::from(*s)).collect();
+		assert_eq!(state.signers(), &expected);
+	}
+
+	#[test]
+	fn drops_votes_of_removed_signers() {
+		let mut state = state(&[1, 2, 3, 4]);
+		state.cast_vote(Address::from(4), Address::from(5), VoteType::Add);
+		state.cast_vote(Address::from(1), Address::from(4), VoteType::Remove);
+		state.cast_vote(Address::from(2), Address::from(4), VoteType::Remove);
+		state.cast_vote(Address::from(3), Address::from(4), VoteType::Remove);
+
+		assert!(!state.signers().contains(&Address::from(4)));
+		assert!(state.votes.is_empty());
+	}
+}
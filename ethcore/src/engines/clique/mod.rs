@@ -0,0 +1,383 @@
+// Copyright 2015-2018 Parity Technologies (UK) Ltd.
+// This file is part of Parity.
+
+// Parity is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// Parity is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with Parity.  If not, see <http://www.gnu.org/licenses/>.
+
+//! Clique proof-of-authority consensus (EIP-225), as used by the Rinkeby and Görli testnets.
+//!
+//! Blocks are signed by one of the authorized signers. The signature is stored in the last
+//! 65 bytes of the extra data and covers the whole header except for the signature itself.
+//! Signers vote to authorize (nonce `0xff..ff`) or deauthorize (nonce `0x00..00`) the block's
+//! beneficiary; a vote passes once the majority of signers agrees. Every `epoch` blocks
+//! pending votes are discarded and the checkpoint block lists all authorized signers in its
+//! extra data, between the 32 bytes of vanity and the signature.
+//!
+//! Checkpoint blocks carry more extra data than most chains permit, so Clique chain specs
+//! need to raise `maximumExtraDataSize` accordingly.
+//!
+//! This engine only verifies blocks on full clients; sealing and light clients are not supported.
+//! Signer schedules are kept in memory only and rebuilt from the closest checkpoint when missing,
+//! e.g. after a restart.
+
+mod block_state;
+
+use std::collections::BTreeSet;
+use std::sync::Weak;
+use std::time;
+
+use client::EngineClient;
+use engines::{ConstructedVerifier, Engine, EngineError};
+use error::{BlockError, Error};
+use ethereum_types::{Address, H64, H160, H256, H520};
+use ethjson;
+use ethkey::{self, Signature};
+use header::{ExtendedHeader, Header};
+use ids::BlockId;
+use lru_cache::LruCache;
+use machine::EthereumMachine;
+use parking_lot::{Mutex, RwLock};
+use rlp::Rlp;
+use unexpected::{Mismatch, OutOfBounds};
+
+use self::block_state::CliqueBlockState;
+
+pub use self::block_state::VoteType;
+
+/// Length of the vanity prefix of the extra data.
+pub const VANITY_LENGTH: usize = 32;
+/// Length of the signature suffix of the extra data.
+pub const SIGNATURE_LENGTH: usize = 65;
+/// Nonce of a block voting to authorize its beneficiary.
+pub const NONCE_AUTH_VOTE: [u8; 8] = [0xff; 8];
+/// Nonce of a block voting to deauthorize its beneficiary.
+pub const NONCE_DROP_VOTE: [u8; 8] = [0x00; 8];
+
+const DEFAULT_EPOCH_LENGTH: u64 = 30_000;
+// number of schedules of recent blocks kept in memory.
+const STATE_CACHE_SIZE: usize = 128;
+
+/// `Clique` params.
+#[derive(Debug, PartialEq)]
+pub struct CliqueParams {
+	/// Minimal number of seconds between two blocks.
+	pub period: u64,
+	/// Number of blocks between two checkpoints.
+	pub epoch: u64,
+}
+
+impl From<ethjson::spec::CliqueParams> for CliqueParams {
+	fn from(p: ethjson::spec::CliqueParams) -> Self {
+		CliqueParams {
+			period: p.period.map_or(0, Into::into),
+			epoch: p.epoch.map_or(DEFAULT_EPOCH_LENGTH, |e| ::std::cmp::max(e.into(), 1)),
+		}
+	}
+}
+
+// the hash signed by the signer of the block: hash of the header without the signature.
+fn seal_hash(header: &Header) -> H256 {
+	let mut unsigned = header.clone();
+	let extra_data = header.extra_data();
+	let unsigned_length = extra_data.len().saturating_sub(SIGNATURE_LENGTH);
+	unsigned.set_extra_data(extra_data[..unsigned_length].to_vec());
+	unsigned.hash()
+}
+
+// recover the signer of the block from the signature in the extra data.
+fn recover_signer(header: &Header) -> Result<Address, Error> {
+	let extra_data = header.extra_data();
+	if extra_data.len() < VANITY_LENGTH + SIGNATURE_LENGTH {
+		return Err(BlockError::ExtraDataOutOfBounds(OutOfBounds {
+			min: Some(VANITY_LENGTH + SIGNATURE_LENGTH),
+			max: None,
+			found: extra_data.len(),
+		}).into());
+	}
+
+	let signature = H520::from_slice(&extra_data[extra_data.len() - SIGNATURE_LENGTH..]);
+	let public = ethkey::recover(&Signature::from(signature), &seal_hash(header))?;
+	Ok(ethkey::public_to_address(&public))
+}
+
+// signers listed in the extra data of a checkpoint block.
+fn checkpoint_signers(header: &Header) -> Result<BTreeSet<Address>, Error> {
+	let extra_data = header.extra_data();
+	if extra_data.len() < VANITY_LENGTH + SIGNATURE_LENGTH {
+		return Err(BlockError::ExtraDataOutOfBounds(OutOfBounds {
+			min: Some(VANITY_LENGTH + SIGNATURE_LENGTH),
+			max: None,
+			found: extra_data.len(),
+		}).into());
+	}
+
+	let signers = &extra_data[VANITY_LENGTH..extra_data.len() - SIGNATURE_LENGTH];
+	if signers.len() % 20 != 0 {
+		return Err(BlockError::InvalidSeal.into());
+	}
+	Ok(signers.chunks(20).map(H160::from_slice).collect())
+}
+
+// vote cast by the signer of the block.
+fn vote(header: &Header) -> Result<Option<VoteType>, Error> {
+	let nonce = Rlp::new(&header.seal()[1]).as_val::<H64>()?;
+	let kind = if nonce == H64::from(NONCE_AUTH_VOTE) {
+		VoteType::Add
+	} else if nonce == H64::from(NONCE_DROP_VOTE) {
+		VoteType::Remove
+	} else {
+		return Err(BlockError::InvalidSeal.into());
+	};
+
+	match header.author().is_zero() {
+		true => Ok(None),
+		false => Ok(Some(kind)),
+	}
+}
+
+/// Engine using `Clique` proof-of-authority consensus.
+pub struct Clique {
+	period: u64,
+	epoch_length: u64,
+	machine: EthereumMachine,
+	client: RwLock<Option<Weak<EngineClient>>>,
+	// signer schedules as of recent blocks, by block hash.
+	block_states: Mutex<LruCache<H256, CliqueBlockState>>,
+}
+
+impl Clique {
+	/// Create a new instance of Clique engine.
+	pub fn new(our_params: CliqueParams, machine: EthereumMachine) -> Self {
+		Clique {
+			period: our_params.period,
+			epoch_length: our_params.epoch,
+			machine,
+			client: RwLock::new(None),
+			block_states: Mutex::new(LruCache::new(STATE_CACHE_SIZE)),
+		}
+	}
+
+	fn is_checkpoint(&self, header: &Header) -> bool {
+		header.number() % self.epoch_length == 0
+	}
+
+	// signer schedule as of given block. Rebuilt from the closest checkpoint if not cached.
+	fn state(&self, header: &Header) -> Result<CliqueBlockState, Error> {
+		if let Some(state) = self.block_states.lock().get_mut(&header.hash()) {
+			return Ok(state.clone());
+		}
+
+		let client = self.client.read().as_ref().and_then(Weak::upgrade).ok_or(EngineError::RequiresClient)?;
+		let mut headers = Vec::new();
+		let mut current = header.clone();
+		let mut state = loop {
+			if let Some(state) = self.block_states.lock().get_mut(&current.hash()) {
+				break state.clone();
+			}
+
+			if self.is_checkpoint(&current) {
+				let mut state = CliqueBlockState::new(checkpoint_signers(&current)?);
+				if current.number() != 0 {
+					state.apply(&current, true)?;
+				}
+				self.block_states.lock().insert(current.hash(), state.clone());
+				break state;
+			}
+
+			let parent_hash = *current.parent_hash();
+			let parent = client.block_header(BlockId::Hash(parent_hash))
+				.ok_or(BlockError::UnknownParent(parent_hash))?
+				.decode()?;
+			headers.push(current);
+			current = parent;
+		};
+
+		trace!(target: "engine", "Rebuilding Clique signer schedule from block #{} over {} blocks", current.number(), headers.len());
+		for header in headers.into_iter().rev() {
+			state.apply(&header, false)?;
+			self.block_states.lock().insert(header.hash(), state.clone());
+		}
+
+		Ok(state)
+	}
+}
+
+impl Engine<EthereumMachine> for Clique {
+	fn name(&self) -> &str { "Clique" }
+
+	fn machine(&self) -> &EthereumMachine { &self.machine }
+
+	// Two fields - mix hash (always zero) and nonce (vote)
+	fn seal_fields(&self, _header: &Header) -> usize { 2 }
+
+	fn seals_internally(&self) -> Option<bool> {
+		Some(false)
+	}
+
+	// the beneficiary field holds the vote, so blocks are executed on behalf of their signer.
+	fn executive_author(&self, header: &Header) -> Address {
+		recover_signer(header).unwrap_or_else(|_| *header.author())
+	}
+
+	fn verify_local_seal(&self, _header: &Header) -> Result<(), Error> {
+		Ok(())
+	}
+
+	fn verify_block_basic(&self, header: &Header) -> Result<(), Error> {
+		if header.number() == 0 {
+			return Ok(());
+		}
+
+		if header.seal().len() != 2 {
+			return Err(BlockError::InvalidSealArity(Mismatch { expected: 2, found: header.seal().len() }).into());
+		}
+
+		let mix_hash = Rlp::new(&header.seal()[0]).as_val::<H256>()?;
+		if !mix_hash.is_zero() {
+			return Err(BlockError::MismatchedH256SealElement(Mismatch { expected: H256::zero(), found: mix_hash }).into());
+		}
+
+		let signers_length = header.extra_data().len().saturating_sub(VANITY_LENGTH + SIGNATURE_LENGTH);
+		if self.is_checkpoint(header) {
+			// checkpoints carry no votes.
+			let nonce = Rlp::new(&header.seal()[1]).as_val::<H64>()?;
+			if !header.author().is_zero() || nonce != H64::from(NONCE_DROP_VOTE) {
+				return Err(BlockError::InvalidSeal.into());
+			}
+			checkpoint_signers(header)?;
+		} else {
+			if signers_length != 0 {
+				return Err(BlockError::InvalidSeal.into());
+			}
+			vote(header)?;
+		}
+
+		recover_signer(header).map(|_| ())
+	}
+
+	fn verify_block_family(&self, header: &Header, parent: &Header) -> Result<(), Error> {
+		let mut state = self.state(parent)?;
+		let is_checkpoint = self.is_checkpoint(header);
+		if is_checkpoint && checkpoint_signers(header)? != *state.signers() {
+			return Err(EngineError::SignerListMismatch.into());
+		}
+
+		state.apply(header, is_checkpoint)?;
+		self.block_states.lock().insert(header.hash(), state);
+		Ok(())
+	}
+
+	// verifying a header requires the signer schedule, which light clients can't rebuild.
+	fn epoch_verifier<'a>(&self, _header: &Header, _proof: &'a [u8]) -> ConstructedVerifier<'a, EthereumMachine> {
+		ConstructedVerifier::Err(EngineError::InsufficientProof("Clique blocks can't be verified without their ancestry".into()).into())
+	}
+
+	fn register_client(&self, client: Weak<EngineClient>) {
+		*self.client.write() = Some(client);
+	}
+
+	fn open_block_header_timestamp(&self, parent_timestamp: u64) -> u64 {
+		let now = time::SystemTime::now().duration_since(time::UNIX_EPOCH).unwrap_or_default();
+		::std::cmp::max(now.as_secs(), parent_timestamp + self.period)
+	}
+
+	fn is_timestamp_valid(&self, header_timestamp: u64, parent_timestamp: u64) -> bool {
+		header_timestamp >= parent_timestamp.saturating_add(self.period)
+	}
+
+	fn fork_choice(&self, new: &ExtendedHeader, current: &ExtendedHeader) -> super::ForkChoice {
+		super::total_difficulty_fork_choice(new, current)
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use ethereum_types::{Address, H64, H256, H520};
+	use ethkey::{self, Generator, Random};
+	use engines::Engine;
+	use header::Header;
+	use rlp;
+	use super::{Clique, CliqueParams, seal_hash, recover_signer, checkpoint_signers, vote, VoteType,
+		NONCE_AUTH_VOTE, NONCE_DROP_VOTE, VANITY_LENGTH, SIGNATURE_LENGTH};
+
+	fn sealed_header_by(author: Address, nonce: [u8; 8], signers: &[Address]) -> (Header, Address) {
+		let keypair = Random.generate().unwrap();
+		let mut header = Header::default();
+		header.set_number(3);
+		header.set_author(author);
+		header.set_seal(vec![rlp::encode(&H256::zero()).into_vec(), rlp::encode(&H64::from(nonce)).into_vec()]);
+
+		let mut extra_data = vec![0u8; VANITY_LENGTH];
+		for signer in signers {
+			extra_data.extend_from_slice(&signer);
+		}
+		extra_data.extend_from_slice(&[0u8; SIGNATURE_LENGTH]);
+		header.set_extra_data(extra_data);
+
+		let signature = ethkey::sign(keypair.secret(), &seal_hash(&header)).unwrap();
+		let mut extra_data = header.extra_data().clone();
+		let signature_start = extra_data.len() - SIGNATURE_LENGTH;
+		extra_data[signature_start..].copy_from_slice(&H520::from(signature));
+		header.set_extra_data(extra_data);
+
+		(header, keypair.address())
+	}
+
+	fn sealed_header(nonce: [u8; 8], signers: &[Address]) -> (Header, Address) {
+		sealed_header_by(Address::from(7), nonce, signers)
+	}
+
+	fn engine(epoch: u64) -> Clique {
+		let machine = ::machine::EthereumMachine::regular(Default::default(), Default::default());
+		Clique::new(CliqueParams { period: 0, epoch: epoch }, machine)
+	}
+
+	#[test]
+	fn recovers_signer() {
+		let (header, signer) = sealed_header(NONCE_AUTH_VOTE, &[]);
+		assert_eq!(recover_signer(&header).unwrap(), signer);
+		assert_eq!(vote(&header).unwrap(), Some(VoteType::Add));
+
+		let mut tampered = header.clone();
+		tampered.set_gas_limit(1.into());
+		assert!(recover_signer(&tampered).unwrap() != signer);
+	}
+
+	#[test]
+	fn reads_checkpoint_signers() {
+		let signers = vec![Address::from(2), Address::from(1)];
+		let (header, _) = sealed_header([0; 8], &signers);
+		assert_eq!(checkpoint_signers(&header).unwrap(), signers.into_iter().collect());
+
+		let (mut header, _) = sealed_header([1; 8], &[]);
+		assert!(vote(&header).is_err());
+		header.set_extra_data(vec![0u8; VANITY_LENGTH]);
+		assert!(recover_signer(&header).is_err());
+	}
+
+	#[test]
+	fn executes_blocks_on_behalf_of_signer() {
+		let (header, signer) = sealed_header(NONCE_AUTH_VOTE, &[]);
+		assert_eq!(engine(30_000).executive_author(&header), signer);
+	}
+
+	#[test]
+	fn requires_zero_nonce_on_checkpoints() {
+		let engine = engine(3);
+		let (header, _) = sealed_header_by(Address::zero(), NONCE_DROP_VOTE, &[Address::from(1)]);
+		assert!(engine.verify_block_basic(&header).is_ok());
+
+		let (header, _) = sealed_header_by(Address::zero(), NONCE_AUTH_VOTE, &[Address::from(1)]);
+		assert!(engine.verify_block_basic(&header).is_err());
+	}
+}
@@ -0,0 +1,128 @@
+// Copyright 2015-2018 Parity Technologies (UK) Ltd.
+// This file is part of Parity.
+
+// Parity is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// Parity is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with Parity.  If not, see <http://www.gnu.org/licenses/>.
+
+//! Tracking of validators' sealing performance.
+
+use std::collections::BTreeMap;
+
+use ethereum_types::Address;
+use header::BlockNumber;
+use parking_lot::RwLock;
+
+/// Sealing performance of a single validator.
+#[derive(Debug, Default, Clone, PartialEq, Eq)]
+pub struct ValidatorLiveness {
+	/// Number of imported blocks sealed by the validator.
+	pub sealed: u64,
+	/// Number of steps in which the validator was expected to seal but didn't.
+	pub missed: u64,
+	/// Number of steps missed since the validator was last seen.
+	pub consecutive_missed: u64,
+	/// Number of the last block sealed by the validator.
+	pub last_seen: Option<BlockNumber>,
+}
+
+/// Raised when a validator reaches the threshold of consecutive missed steps.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct LivenessAlert {
+	/// The validator.
+	pub validator: Address,
+	/// Number of consecutive steps missed.
+	pub consecutive_missed: u64,
+	/// Block at which the missed steps were noticed.
+	pub block: BlockNumber,
+}
+
+/// Sealing performance of all validators seen since startup.
+pub struct LivenessTracker {
+	alert_threshold: Option<u64>,
+	validators: RwLock<BTreeMap<Address, ValidatorLiveness>>,
+}
+
+impl LivenessTracker {
+	/// Create a new tracker alerting once a validator misses `alert_threshold` consecutive steps.
+	pub fn new(alert_threshold: Option<u64>) -> Self {
+		LivenessTracker {
+			alert_threshold,
+			validators: RwLock::new(BTreeMap::new()),
+		}
+	}
+
+	/// Note a block sealed by `author`.
+	pub fn note_sealed(&self, author: &Address, block: BlockNumber) {
+		let mut validators = self.validators.write();
+		let liveness = validators.entry(*author).or_insert_with(Default::default);
+		liveness.sealed += 1;
+		liveness.consecutive_missed = 0;
+		liveness.last_seen = Some(::std::cmp::max(block, liveness.last_seen.unwrap_or(0)));
+	}
+
+	/// Note a step in which `validator` had nothing to seal, but proved to be online.
+	pub fn note_empty_step(&self, validator: &Address) {
+		let mut validators = self.validators.write();
+		validators.entry(*validator).or_insert_with(Default::default).consecutive_missed = 0;
+	}
+
+	/// Note a step missed by `validator`. Returns an alert if the validator has just reached
+	/// the threshold of consecutive missed steps.
+	pub fn note_missed(&self, validator: &Address, block: BlockNumber) -> Option<LivenessAlert> {
+		let mut validators = self.validators.write();
+		let liveness = validators.entry(*validator).or_insert_with(Default::default);
+		liveness.missed += 1;
+		liveness.consecutive_missed += 1;
+
+		match self.alert_threshold {
+			Some(threshold) if liveness.consecutive_missed == threshold => Some(LivenessAlert {
+				validator: *validator,
+				consecutive_missed: liveness.consecutive_missed,
+				block,
+			}),
+			_ => None,
+		}
+	}
+
+	/// Sealing performance of all validators.
+	pub fn report(&self) -> BTreeMap<Address, ValidatorLiveness> {
+		self.validators.read().clone()
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use ethereum_types::Address;
+	use super::{LivenessTracker, LivenessAlert};
+
+	#[test]
+	fn alerts_once_on_consecutive_misses() {
+		let tracker = LivenessTracker::new(Some(2));
+		let (a, b) = (Address::from(1), Address::from(2));
+
+		tracker.note_sealed(&a, 1);
+		assert_eq!(tracker.note_missed(&b, 2), None);
+		tracker.note_empty_step(&b);
+		assert_eq!(tracker.note_missed(&b, 3), None);
+		assert_eq!(tracker.note_missed(&b, 3), Some(LivenessAlert { validator: b, consecutive_missed: 2, block: 3 }));
+		assert_eq!(tracker.note_missed(&b, 4), None);
+		tracker.note_sealed(&b, 5);
+
+		let report = tracker.report();
+		assert_eq!(report[&a].sealed, 1);
+		assert_eq!(report[&a].last_seen, Some(1));
+		assert_eq!(report[&b].sealed, 1);
+		assert_eq!(report[&b].missed, 4);
+		assert_eq!(report[&b].consecutive_missed, 0);
+	}
+}
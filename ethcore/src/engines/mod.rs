@@ -18,6 +18,7 @@
 
 mod authority_round;
 mod basic_authority;
+mod clique;
 mod instant_seal;
 mod null_engine;
 mod signer;
@@ -28,15 +29,20 @@ mod vote_collector;
 
 pub mod block_reward;
 pub mod epoch;
+pub mod liveness;
 
 pub use self::authority_round::AuthorityRound;
 pub use self::basic_authority::BasicAuthority;
+pub use self::clique::Clique;
 pub use self::epoch::{EpochVerifier, Transition as EpochTransition};
 pub use self::instant_seal::InstantSeal;
+pub use self::liveness::{ValidatorLiveness, LivenessAlert};
 pub use self::null_engine::NullEngine;
+pub use self::signer::SignerTimeoutPolicy;
 pub use self::tendermint::Tendermint;
 
 use std::sync::{Weak, Arc};
+use std::time::Duration;
 use std::collections::{BTreeMap, HashMap};
 use std::{fmt, error};
 
@@ -44,6 +50,8 @@ use self::epoch::PendingTransition;
 
 use account_provider::AccountProvider;
 use builtin::Builtin;
+use client::CallContract;
+use governance::GovernanceParams;
 use vm::{EnvInfo, Schedule, CreateContractAddress};
 use error::Error;
 use header::{Header, BlockNumber};
@@ -53,7 +61,7 @@ use transaction::{self, UnverifiedTransaction, SignedTransaction};
 
 use ethkey::Signature;
 use parity_machine::{Machine, LocalizedMachine as Localized, TotalScoredHeader};
-use ethereum_types::{H256, U256, Address};
+use ethereum_types::{H256, H512, U256, Address};
 use unexpected::{Mismatch, OutOfBounds};
 use bytes::Bytes;
 use types::ancestry_action::AncestryAction;
@@ -92,6 +100,10 @@ pub enum EngineError {
 	MalformedMessage(String),
 	/// Requires client ref, but none registered.
 	RequiresClient,
+	/// The signer has already sealed one of the recent blocks.
+	RecentlySigned(Address),
+	/// Signers listed in a checkpoint block differ from the authorized ones.
+	SignerListMismatch,
 }
 
 impl fmt::Display for EngineError {
@@ -107,6 +119,8 @@ impl fmt::Display for EngineError {
 			FailedSystemCall(ref msg) => format!("Failed to make system call: {}", msg),
 			MalformedMessage(ref msg) => format!("Received malformed consensus message: {}", msg),
 			RequiresClient => format!("Call requires client but none registered"),
+			RecentlySigned(ref address) => format!("Signer {} has signed a recent block.", address),
+			SignerListMismatch => "Checkpoint signers don't match the authorized signers.".into(),
 		};
 
 		f.write_fmt(format_args!("Engine error ({})", msg))
@@ -126,6 +140,10 @@ pub enum Seal {
 	Proposal(Vec<Bytes>),
 	/// Regular block seal; should be part of the blockchain.
 	Regular(Vec<Bytes>),
+	/// The block is kept as a sealing candidate and its seal is provided later through
+	/// `EngineClient::submit_seal`, e.g. by a remote signer. Candidates built on top of
+	/// a block which is no longer the best one are discarded.
+	External,
 	/// Engine does generate seal for this block right now.
 	None,
 }
@@ -206,6 +224,9 @@ pub trait Engine<M: Machine>: Sync + Send {
 	/// Additional engine-specific information for the user/developer concerning `header`.
 	fn extra_info(&self, _header: &M::Header) -> BTreeMap<String, String> { BTreeMap::new() }
 
+	/// Sealing performance of the validators seen since startup. Empty for engines without validators.
+	fn validator_liveness(&self) -> BTreeMap<Address, ValidatorLiveness> { BTreeMap::new() }
+
 	/// Maximum number of uncles a block is allowed to declare.
 	fn maximum_uncle_count(&self, _block: BlockNumber) -> usize { 0 }
 
@@ -303,6 +324,18 @@ pub trait Engine<M: Machine>: Sync + Send {
 		None
 	}
 
+	/// Address credited as the author of the block while executing it.
+	///
+	/// This is the beneficiary of the block unless the engine keeps it elsewhere in the header.
+	fn executive_author(&self, header: &M::Header) -> Address {
+		header.author().clone()
+	}
+
+	/// Called after a block has been committed to the chain.
+	///
+	/// The `Headers` given are guaranteed to return the parent of the block.
+	fn on_block_committed(&self, _header: &M::Header, _chain: &Headers<M::Header>) {}
+
 	/// Create an epoch verifier from validation proof and a flag indicating
 	/// whether finality is required.
 	fn epoch_verifier<'a>(&self, _header: &M::Header, _proof: &'a [u8]) -> ConstructedVerifier<'a, M> {
@@ -317,6 +350,12 @@ pub trait Engine<M: Machine>: Sync + Send {
 	/// updating consensus state and potentially issuing a new one.
 	fn handle_message(&self, _message: &[u8]) -> Result<(), EngineError> { Err(EngineError::UnexpectedMessage) }
 
+	/// Handle a consensus message received from the peer with given node id.
+	/// Engines which reply to a single peer, e.g. during a handshake, should override this.
+	fn handle_message_from(&self, message: &[u8], _node_id: &H512) -> Result<(), EngineError> {
+		self.handle_message(message)
+	}
+
 	/// Find out if the block is a proposal block and should not be inserted into the DB.
 	/// Takes a header of a fully verified block.
 	fn is_proposal(&self, _verified_header: &M::Header) -> bool { false }
@@ -324,6 +363,9 @@ pub trait Engine<M: Machine>: Sync + Send {
 	/// Register an account which signs consensus messages.
 	fn set_signer(&self, _account_provider: Arc<AccountProvider>, _address: Address, _password: String) {}
 
+	/// Wait at most `timeout` for the signer when sealing, or indefinitely if `None`.
+	fn set_signer_timeout(&self, _timeout: Option<Duration>, _policy: SignerTimeoutPolicy) {}
+
 	/// Sign using the EngineSigner, to be used for consensus tx signing.
 	fn sign(&self, _hash: H256) -> Result<Signature, M::Error> { unimplemented!() }
 
@@ -456,6 +498,11 @@ pub trait EthEngine: Engine<::machine::EthereumMachine> {
 		self.machine().additional_params()
 	}
 
+	/// Runtime parameters read from the chain's governance contract at given block, if any.
+	fn governance_params(&self, header: &Header, client: &CallContract) -> Option<GovernanceParams> {
+		self.machine().governance_params(header, client)
+	}
+
 	/// Performs pre-validation of RLP decoded transaction before other processing
 	fn decode_transaction(&self, transaction: &[u8]) -> Result<UnverifiedTransaction, transaction::Error> {
 		self.machine().decode_transaction(transaction)
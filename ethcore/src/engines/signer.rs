@@ -16,10 +16,18 @@
 
 //! A signer used by Engines which need to sign messages.
 
-use std::sync::Arc;
+use std::sync::{mpsc, Arc};
+use std::thread;
+use std::time::Duration;
 use ethereum_types::{H256, Address};
 use ethkey::Signature;
-use account_provider::{self, AccountProvider};
+use account_provider::{self, AccountProvider, KeyOperation};
+use parking_lot::{Mutex, RwLock};
+
+type SignResult = Result<Signature, account_provider::SignError>;
+type SignJob = Box<FnMut() -> SignResult + Send>;
+
+const USAGE_ORIGIN: &'static str = "Engine";
 
 /// Everything that an Engine needs to sign messages.
 pub struct EngineSigner {
@@ -49,7 +57,15 @@ impl EngineSigner {
 
 	/// Sign a consensus message hash.
 	pub fn sign(&self, hash: H256) -> Result<Signature, account_provider::SignError> {
-		self.account_provider.sign(self.address.unwrap_or_else(Default::default), self.password.clone(), hash)
+		self.account_provider.sign(self.address.unwrap_or_else(Default::default), self.password.clone(), hash, KeyOperation::SignMessage, USAGE_ORIGIN)
+	}
+
+	// signing of a consensus message hash, to be run on another thread.
+	fn sign_job(&self, hash: H256) -> SignJob {
+		let account_provider = self.account_provider.clone();
+		let address = self.address.unwrap_or_else(Default::default);
+		let password = self.password.clone();
+		Box::new(move || account_provider.sign(address, password.clone(), hash, KeyOperation::SignMessage, USAGE_ORIGIN))
 	}
 
 	/// Signing address.
@@ -67,3 +83,173 @@ impl EngineSigner {
 		self.address.is_some()
 	}
 }
+
+/// What to do with a seal signature which didn't arrive in time.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SignerTimeoutPolicy {
+	/// Give up on the signature and skip sealing.
+	Skip,
+	/// Skip sealing, but keep waiting for the signature on the next attempt to seal the same block.
+	Retry,
+}
+
+/// Signs with a bounded wait, so that a slow external signer doesn't hold up sealing.
+///
+/// Signatures which are waited for are made one after another on a single worker thread.
+#[derive(Default)]
+pub struct TimedSigner {
+	settings: RwLock<Option<(Duration, SignerTimeoutPolicy)>>,
+	// requests to the worker thread, started on first use.
+	worker: Mutex<Option<mpsc::Sender<(SignJob, mpsc::Sender<SignResult>)>>>,
+	// hash being signed and the receiver of its signature.
+	pending: Mutex<Option<(H256, mpsc::Receiver<SignResult>)>>,
+}
+
+impl TimedSigner {
+	/// Create a signer waiting at most `timeout` for signatures, or indefinitely if `None`.
+	pub fn new(timeout: Option<Duration>, policy: SignerTimeoutPolicy) -> Self {
+		let signer = TimedSigner::default();
+		signer.set_timeout(timeout, policy);
+		signer
+	}
+
+	/// Wait at most `timeout` for signatures, or indefinitely if `None`.
+	pub fn set_timeout(&self, timeout: Option<Duration>, policy: SignerTimeoutPolicy) {
+		*self.settings.write() = timeout.map(|timeout| (timeout, policy));
+	}
+
+	/// Sign `hash` with `signer`. Returns `None` if the signature didn't arrive in time.
+	pub fn sign(&self, signer: &EngineSigner, hash: H256) -> Result<Option<Signature>, account_provider::SignError> {
+		let settings = *self.settings.read();
+		match settings {
+			Some((timeout, policy)) => self.sign_timed(hash, timeout, policy, || signer.sign_job(hash)),
+			None => signer.sign(hash).map(Some),
+		}
+	}
+
+	fn sign_timed<F>(&self, hash: H256, timeout: Duration, policy: SignerTimeoutPolicy, job: F) -> Result<Option<Signature>, account_provider::SignError>
+		where F: FnOnce() -> SignJob
+	{
+		let mut pending = self.pending.lock();
+		let receiver = match pending.take() {
+			Some((pending_hash, receiver)) if pending_hash == hash => receiver,
+			_ => match self.submit(job()) {
+				Some(receiver) => receiver,
+				None => return Ok(None),
+			},
+		};
+
+		match receiver.recv_timeout(timeout) {
+			Ok(result) => result.map(Some),
+			Err(mpsc::RecvTimeoutError::Timeout) => {
+				debug!(target: "poa", "Signer didn't sign {} within {:?}", hash, timeout);
+				if policy == SignerTimeoutPolicy::Retry {
+					*pending = Some((hash, receiver));
+				}
+				Ok(None)
+			},
+			Err(mpsc::RecvTimeoutError::Disconnected) => {
+				warn!(target: "poa", "Signing of {} was interrupted", hash);
+				Ok(None)
+			},
+		}
+	}
+
+	// queue a job on the worker thread, starting it if needed.
+	fn submit(&self, job: SignJob) -> Option<mpsc::Receiver<SignResult>> {
+		let mut worker = self.worker.lock();
+		if worker.is_none() {
+			let (requests, queue) = mpsc::channel::<(SignJob, mpsc::Sender<SignResult>)>();
+			let spawned = thread::Builder::new().name("engine-signer".into()).spawn(move || {
+				for (mut job, result) in queue {
+					let _ = result.send(job());
+				}
+			});
+			match spawned {
+				Ok(_) => *worker = Some(requests),
+				Err(e) => {
+					warn!(target: "poa", "Unable to spawn signing thread: {}", e);
+					return None;
+				},
+			}
+		}
+
+		let (sender, receiver) = mpsc::channel();
+		let sent = worker.as_ref().map_or(false, |requests| requests.send((job, sender)).is_ok());
+		if !sent {
+			*worker = None;
+			return None;
+		}
+		Some(receiver)
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use std::sync::{mpsc, Arc};
+	use std::sync::atomic::{AtomicBool, Ordering};
+	use std::time::Duration;
+	use parking_lot::Mutex;
+	use account_provider::AccountProvider;
+	use ethkey::{verify_address, Generator, Random, Signature};
+	use hash::keccak;
+	use super::{EngineSigner, SignJob, SignerTimeoutPolicy, TimedSigner};
+
+	// job signing once `release` is sent to.
+	fn blocked_job() -> (SignJob, mpsc::Sender<()>) {
+		let (release, released) = mpsc::channel();
+		let released = Mutex::new(released);
+		let job: SignJob = Box::new(move || {
+			released.lock().recv().expect("test holds the sender; qed");
+			Ok(Signature::default())
+		});
+		(job, release)
+	}
+
+	#[test]
+	fn signs_within_timeout() {
+		let ap = Arc::new(AccountProvider::transient_provider());
+		let keypair = Random.generate().unwrap();
+		let address = ap.insert_account(keypair.secret().clone(), "").unwrap();
+		let mut signer = EngineSigner::default();
+		signer.set(ap, address, "".into());
+
+		let hash = keccak("seal");
+		let timed = TimedSigner::new(Some(Duration::from_secs(10)), SignerTimeoutPolicy::Skip);
+		let signature = timed.sign(&signer, hash).unwrap().unwrap();
+		assert!(verify_address(&address, &signature, &hash).unwrap());
+	}
+
+	#[test]
+	fn skips_signature_after_timeout() {
+		let timed = TimedSigner::default();
+		let hash = keccak("seal");
+		let (job, release) = blocked_job();
+		assert_eq!(timed.sign_timed(hash, Duration::from_millis(10), SignerTimeoutPolicy::Skip, || job).unwrap(), None);
+
+		release.send(()).unwrap();
+		let submitted = AtomicBool::new(false);
+		let (job, release) = blocked_job();
+		release.send(()).unwrap();
+		let signature = timed.sign_timed(hash, Duration::from_secs(10), SignerTimeoutPolicy::Skip, || {
+			submitted.store(true, Ordering::SeqCst);
+			job
+		}).unwrap();
+		assert_eq!(signature, Some(Signature::default()));
+		assert!(submitted.load(Ordering::SeqCst));
+	}
+
+	#[test]
+	fn retries_pending_signature() {
+		let timed = TimedSigner::default();
+		let hash = keccak("seal");
+		let (job, release) = blocked_job();
+		assert_eq!(timed.sign_timed(hash, Duration::from_millis(10), SignerTimeoutPolicy::Retry, || job).unwrap(), None);
+
+		release.send(()).unwrap();
+		let signature = timed.sign_timed(hash, Duration::from_secs(10), SignerTimeoutPolicy::Retry, || -> SignJob {
+			panic!("pending signature should be awaited");
+		}).unwrap();
+		assert_eq!(signature, Some(Signature::default()));
+	}
+}
@@ -204,7 +204,7 @@ mod tests {
 	use std::sync::Arc;
 	use hash::keccak;
 	use rlp::*;
-	use account_provider::AccountProvider;
+	use account_provider::{AccountProvider, KeyOperation};
 	use header::Header;
 	use super::super::Step;
 	use super::*;
@@ -258,7 +258,7 @@ mod tests {
 
 		let mi = message_info_rlp(&VoteStep::new(123, 2, Step::Precommit), Some(H256::default()));
 
-		let raw_rlp = message_full_rlp(&tap.sign(addr, None, keccak(&mi)).unwrap().into(), &mi);
+		let raw_rlp = message_full_rlp(&tap.sign(addr, None, keccak(&mi), KeyOperation::SignMessage, "test").unwrap().into(), &mi);
 
 		let rlp = Rlp::new(&raw_rlp);
 		let message: ConsensusMessage = rlp.as_val().unwrap();
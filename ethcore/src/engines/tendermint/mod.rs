@@ -786,7 +786,7 @@ mod tests {
 		TestNotify, get_temp_state_db, generate_dummy_client,
 		generate_dummy_client_with_spec_and_accounts
 	};
-	use account_provider::AccountProvider;
+	use account_provider::{AccountProvider, KeyOperation};
 	use spec::Spec;
 	use engines::{EthEngine, EngineError, Seal};
 	use engines::epoch::EpochVerifier;
@@ -823,7 +823,7 @@ mod tests {
 	fn proposal_seal(tap: &Arc<AccountProvider>, header: &Header, view: View) -> Vec<Bytes> {
 		let author = header.author();
 		let vote_info = message_info_rlp(&VoteStep::new(header.number() as Height, view, Step::Propose), Some(header.bare_hash()));
-		let signature = tap.sign(*author, None, keccak(vote_info)).unwrap();
+		let signature = tap.sign(*author, None, keccak(vote_info), KeyOperation::SignMessage, "test").unwrap();
 		vec![
 			::rlp::encode(&view).into_vec(),
 			::rlp::encode(&H520::from(signature)).into_vec(),
@@ -927,7 +927,7 @@ mod tests {
 		let mut seal = proposal_seal(&tap, &header, 0);
 
 		let vote_info = message_info_rlp(&VoteStep::new(2, 0, Step::Precommit), Some(header.bare_hash()));
-		let signature1 = tap.sign(proposer, None, keccak(&vote_info)).unwrap();
+		let signature1 = tap.sign(proposer, None, keccak(&vote_info), KeyOperation::SignMessage, "test").unwrap();
 
 		seal[1] = ::rlp::NULL_RLP.to_vec();
 		seal[2] = ::rlp::encode_list(&vec![H520::from(signature1.clone())]).into_vec();
@@ -940,7 +940,7 @@ mod tests {
 		}
 
 		let voter = insert_and_unlock(&tap, "0");
-		let signature0 = tap.sign(voter, None, keccak(&vote_info)).unwrap();
+		let signature0 = tap.sign(voter, None, keccak(&vote_info), KeyOperation::SignMessage, "test").unwrap();
 
 		seal[2] = ::rlp::encode_list(&vec![H520::from(signature1.clone()), H520::from(signature0.clone())]).into_vec();
 		header.set_seal(seal.clone());
@@ -948,7 +948,7 @@ mod tests {
 		assert!(engine.verify_block_external(&header).is_ok());
 
 		let bad_voter = insert_and_unlock(&tap, "101");
-		let bad_signature = tap.sign(bad_voter, None, keccak(vote_info)).unwrap();
+		let bad_signature = tap.sign(bad_voter, None, keccak(vote_info), KeyOperation::SignMessage, "test").unwrap();
 
 		seal[2] = ::rlp::encode_list(&vec![H520::from(signature1), H520::from(bad_signature)]).into_vec();
 		header.set_seal(seal);
@@ -1002,11 +1002,11 @@ mod tests {
 		client.add_notify(notify.clone());
 		engine.register_client(Arc::downgrade(&client) as _);
 
-		let prevote_current = vote(engine.as_ref(), |mh| tap.sign(v0, None, mh).map(H520::from), h, r, Step::Prevote, proposal);
+		let prevote_current = vote(engine.as_ref(), |mh| tap.sign(v0, None, mh, KeyOperation::SignMessage, "test").map(H520::from), h, r, Step::Prevote, proposal);
 
-		let precommit_current = vote(engine.as_ref(), |mh| tap.sign(v0, None, mh).map(H520::from), h, r, Step::Precommit, proposal);
+		let precommit_current = vote(engine.as_ref(), |mh| tap.sign(v0, None, mh, KeyOperation::SignMessage, "test").map(H520::from), h, r, Step::Precommit, proposal);
 
-		let prevote_future = vote(engine.as_ref(), |mh| tap.sign(v0, None, mh).map(H520::from), h + 1, r, Step::Prevote, proposal);
+		let prevote_future = vote(engine.as_ref(), |mh| tap.sign(v0, None, mh, KeyOperation::SignMessage, "test").map(H520::from), h + 1, r, Step::Prevote, proposal);
 
 		// Relays all valid present and future messages.
 		assert!(notify.messages.read().contains(&prevote_current));
@@ -1052,13 +1052,13 @@ mod tests {
 		let r = 0;
 
 		// Prevote.
-		vote(engine, |mh| tap.sign(v1, None, mh).map(H520::from), h, r, Step::Prevote, proposal);
-		vote(engine, |mh| tap.sign(v0, None, mh).map(H520::from), h, r, Step::Prevote, proposal);
-		vote(engine, |mh| tap.sign(v1, None, mh).map(H520::from), h, r, Step::Precommit, proposal);
+		vote(engine, |mh| tap.sign(v1, None, mh, KeyOperation::SignMessage, "test").map(H520::from), h, r, Step::Prevote, proposal);
+		vote(engine, |mh| tap.sign(v0, None, mh, KeyOperation::SignMessage, "test").map(H520::from), h, r, Step::Prevote, proposal);
+		vote(engine, |mh| tap.sign(v1, None, mh, KeyOperation::SignMessage, "test").map(H520::from), h, r, Step::Precommit, proposal);
 
 		assert_eq!(client.chain_info().best_block_number, 0);
 		// Last precommit.
-		vote(engine, |mh| tap.sign(v0, None, mh).map(H520::from), h, r, Step::Precommit, proposal);
+		vote(engine, |mh| tap.sign(v0, None, mh, KeyOperation::SignMessage, "test").map(H520::from), h, r, Step::Precommit, proposal);
 		assert_eq!(client.chain_info().best_block_number, 1);
 	}
 
@@ -1080,10 +1080,10 @@ mod tests {
 		let mut seal = proposal_seal(&tap, &header, 0);
 
 		let vote_info = message_info_rlp(&VoteStep::new(2, 0, Step::Precommit), Some(header.bare_hash()));
-		let signature1 = tap.sign(proposer, None, keccak(&vote_info)).unwrap();
+		let signature1 = tap.sign(proposer, None, keccak(&vote_info), KeyOperation::SignMessage, "test").unwrap();
 
 		let voter = insert_and_unlock(&tap, "0");
-		let signature0 = tap.sign(voter, None, keccak(&vote_info)).unwrap();
+		let signature0 = tap.sign(voter, None, keccak(&vote_info), KeyOperation::SignMessage, "test").unwrap();
 
 		seal[1] = ::rlp::NULL_RLP.to_vec();
 		seal[2] = ::rlp::encode_list(&vec![H520::from(signature1.clone())]).into_vec();
@@ -1120,7 +1120,7 @@ mod tests {
 		assert!(epoch_verifier.verify_light(&header).is_ok());
 
 		let bad_voter = insert_and_unlock(&tap, "101");
-		let bad_signature = tap.sign(bad_voter, None, keccak(&vote_info)).unwrap();
+		let bad_signature = tap.sign(bad_voter, None, keccak(&vote_info), KeyOperation::SignMessage, "test").unwrap();
 
 		seal[2] = ::rlp::encode_list(&vec![H520::from(signature1), H520::from(bad_signature)]).into_vec();
 		header.set_seal(seal);
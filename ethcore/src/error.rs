@@ -99,6 +99,8 @@ pub enum BlockError {
 	UnknownUncleParent(H256),
 	/// No transition to epoch number.
 	UnknownEpochTransition(u64),
+	/// Block at the fork block number isn't the canonical fork block.
+	InvalidForkHash(Mismatch<H256>),
 }
 
 impl fmt::Display for BlockError {
@@ -140,6 +142,7 @@ impl fmt::Display for BlockError {
 			UnknownParent(ref hash) => format!("Unknown parent: {}", hash),
 			UnknownUncleParent(ref hash) => format!("Unknown uncle parent: {}", hash),
 			UnknownEpochTransition(ref num) => format!("Unknown transition to epoch number: {}", num),
+			InvalidForkHash(ref mis) => format!("Block is not on the canonical side of the fork: {}", mis),
 			TooManyTransactions(ref address) => format!("Too many transactions from: {}", address),
 		};
 
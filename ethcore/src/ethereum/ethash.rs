@@ -120,10 +120,17 @@ pub struct EthashParams {
 	pub eip649_delay: u64,
 	/// EIP-649 base reward.
 	pub eip649_reward: Option<U256>,
+	/// Further difficulty bomb delays (e.g. EIP-1234), by transition block. The delays add up
+	/// with each other and with the EIP-649 one.
+	pub difficulty_bomb_delays: BTreeMap<BlockNumber, BlockNumber>,
 	/// EXPIP-2 block height
 	pub expip2_transition: u64,
 	/// EXPIP-2 duration limit
 	pub expip2_duration_limit: u64,
+	/// Maximum number of uncles a block may include.
+	pub maximum_uncle_count: usize,
+	/// Maximum depth of an uncle. Uncles deeper than 7 blocks are not rewarded.
+	pub maximum_uncle_age: usize,
 }
 
 impl From<ethjson::spec::EthashParams> for EthashParams {
@@ -152,8 +159,13 @@ impl From<ethjson::spec::EthashParams> for EthashParams {
 			eip649_transition: p.eip649_transition.map_or(u64::max_value(), Into::into),
 			eip649_delay: p.eip649_delay.map_or(DEFAULT_EIP649_DELAY, Into::into),
 			eip649_reward: p.eip649_reward.map(Into::into),
+			difficulty_bomb_delays: p.difficulty_bomb_delays.unwrap_or_default().into_iter()
+				.map(|(block, delay)| (block.into(), delay.into()))
+				.collect(),
 			expip2_transition: p.expip2_transition.map_or(u64::max_value(), Into::into),
 			expip2_duration_limit: p.expip2_duration_limit.map_or(30, Into::into),
+			maximum_uncle_count: p.maximum_uncle_count.map_or(2, Into::into),
+			maximum_uncle_age: p.maximum_uncle_age.map_or(6, Into::into),
 		}
 	}
 }
@@ -215,7 +227,9 @@ impl Engine<EthereumMachine> for Arc<Ethash> {
 		}
 	}
 
-	fn maximum_uncle_count(&self, _block: BlockNumber) -> usize { 2 }
+	fn maximum_uncle_count(&self, _block: BlockNumber) -> usize { self.ethash_params.maximum_uncle_count }
+
+	fn maximum_uncle_age(&self) -> usize { self.ethash_params.maximum_uncle_age }
 
 	fn populate_from_parent(&self, header: &mut Header, parent: &Header) {
 		let difficulty = self.calculate_difficulty(header, parent);
@@ -269,7 +283,8 @@ impl Engine<EthereumMachine> for Arc<Ethash> {
 		for u in LiveBlock::uncles(&*block) {
 			let uncle_author = u.author();
 			let result_uncle_reward = if eras == 0 {
-				(reward * U256::from(8 + u.number() - number)).shr(3)
+				let depth = number - u.number();
+				(reward * U256::from(8u64.saturating_sub(depth))).shr(3)
 			} else {
 				reward.shr(5)
 			};
@@ -423,6 +438,11 @@ impl Ethash {
 				if number >= self.ethash_params.eip649_transition {
 					number = number.saturating_sub(self.ethash_params.eip649_delay);
 				}
+				for (block, delay) in &self.ethash_params.difficulty_bomb_delays {
+					if header.number() >= *block {
+						number = number.saturating_sub(*delay);
+					}
+				}
 				let period = (number / EXP_DIFF_PERIOD) as usize;
 				if period > 1 {
 					target = cmp::max(min_difficulty, target + (U256::from(1) << (period - 2)));
@@ -487,6 +507,8 @@ mod tests {
 	use header::Header;
 	use spec::Spec;
 	use engines::Engine;
+	use trace::{FlatTrace, RewardType, Tracing};
+	use trace::trace::Action;
 	use super::super::{new_morden, new_mcip3_test, new_homestead_test_machine};
 	use super::{Ethash, EthashParams, ecip1017_eras_block_reward};
 	use rlp;
@@ -522,8 +544,11 @@ mod tests {
 			eip649_transition: u64::max_value(),
 			eip649_delay: 3_000_000,
 			eip649_reward: None,
+			difficulty_bomb_delays: BTreeMap::new(),
 			expip2_transition: u64::max_value(),
 			expip2_duration_limit: 30,
+			maximum_uncle_count: 2,
+			maximum_uncle_age: 6,
 		}
 	}
 
@@ -594,6 +619,36 @@ mod tests {
 		assert_eq!(b.state().balance(&uncle_author).unwrap(), "3cb71f51fc558000".into());
 	}
 
+	#[test]
+	fn on_close_block_traces_rewards() {
+		let spec = test_spec();
+		let engine = &*spec.engine;
+		let genesis_header = spec.genesis_header();
+		let db = spec.ensure_db_good(get_temp_state_db(), &Default::default()).unwrap();
+		let last_hashes = Arc::new(vec![genesis_header.hash()]);
+		let mut b = OpenBlock::new(engine, Default::default(), true, db, &genesis_header, last_hashes, Address::zero(), (3141562.into(), 31415620.into()), vec![], false, &mut Vec::new().into_iter()).unwrap();
+		let mut uncle = Header::new();
+		let uncle_author: Address = "ef2d6d194084c2de36e0dabfce45d046b37d1106".into();
+		uncle.set_author(uncle_author);
+		b.push_uncle(uncle).unwrap();
+
+		let b = b.close();
+		let traces: Vec<FlatTrace> = match *b.traces() {
+			Tracing::Enabled(ref traces) => traces.last().cloned().expect("rewards are traced as the last entry").into(),
+			Tracing::Disabled => panic!("tracing was enabled for the block"),
+		};
+		let rewards: Vec<_> = traces.into_iter().map(|t| match t.action {
+			Action::Reward(reward) => (reward.author, reward.reward_type, reward.value),
+			_ => panic!("only rewards are traced on close"),
+		}).collect();
+
+		let expected: Vec<(Address, RewardType, U256)> = vec![
+			(Address::zero(), RewardType::Block, "478eae0e571ba000".into()),
+			(uncle_author, RewardType::Uncle, "3cb71f51fc558000".into()),
+		];
+		assert_eq!(rewards, expected);
+	}
+
 	#[test]
 	fn has_valid_mcip3_era_block_rewards() {
 		let spec = new_mcip3_test();
@@ -807,6 +862,56 @@ mod tests {
 		assert_eq!(U256::from_str("1fc50f118efe").unwrap(), difficulty);
 	}
 
+	#[test]
+	fn difficulty_bomb_delays() {
+		let machine = new_homestead_test_machine();
+		let ethparams = EthashParams {
+			eip100b_transition: 0,
+			eip649_transition: 4_370_000,
+			difficulty_bomb_delays: vec![(7_280_000, 2_000_000)].into_iter().collect(),
+			..get_default_ethash_params()
+		};
+		let tempdir = TempDir::new("").unwrap();
+		let ethash = Ethash::new(tempdir.path(), ethparams, machine, None);
+
+		let parent_difficulty = U256::from_str("b5e7b3c4d2a1f0").unwrap();
+		let difficulty_at = |number: u64| {
+			let mut parent_header = Header::default();
+			parent_header.set_number(number - 1);
+			parent_header.set_difficulty(parent_difficulty);
+			parent_header.set_timestamp(1_540_000_000);
+			let mut header = Header::default();
+			header.set_number(number);
+			// no adjustment of the parent difficulty, only the bomb remains.
+			header.set_timestamp(parent_header.timestamp() + 9);
+			ethash.calculate_difficulty(&header, &parent_header)
+		};
+
+		// (4_369_999 / 100_000 - 2) periods.
+		assert_eq!(difficulty_at(4_369_999), parent_difficulty + (U256::one() << 41));
+		// (1_370_000 / 100_000 - 2) periods.
+		assert_eq!(difficulty_at(4_370_000), parent_difficulty + (U256::one() << 11));
+		// (4_279_999 / 100_000 - 2) periods.
+		assert_eq!(difficulty_at(7_279_999), parent_difficulty + (U256::one() << 40));
+		// (2_280_000 / 100_000 - 2) periods.
+		assert_eq!(difficulty_at(7_280_000), parent_difficulty + (U256::one() << 20));
+	}
+
+	#[test]
+	fn uses_configured_uncle_limits() {
+		let machine = new_homestead_test_machine();
+		let ethparams = EthashParams {
+			maximum_uncle_count: 4,
+			maximum_uncle_age: 3,
+			..get_default_ethash_params()
+		};
+		let tempdir = TempDir::new("").unwrap();
+		let ethash = Ethash::new(tempdir.path(), ethparams, machine, None);
+
+		assert_eq!(ethash.maximum_uncle_count(100), 4);
+		assert_eq!(ethash.maximum_uncle_age(), 3);
+	}
+
 	#[test]
 	fn difficulty_classic_bomb_delay() {
 		let machine = new_homestead_test_machine();
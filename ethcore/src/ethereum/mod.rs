@@ -30,6 +30,34 @@ pub use self::denominations::*;
 use machine::EthereumMachine;
 use super::spec::*;
 
+/// JSON of the chain specs bundled with the client.
+pub mod bundled {
+	/// Foundation Olympic chain spec.
+	pub const OLYMPIC: &'static [u8] = include_bytes!("../../res/ethereum/olympic.json");
+	/// Foundation Mainnet chain spec.
+	pub const FOUNDATION: &'static [u8] = include_bytes!("../../res/ethereum/foundation.json");
+	/// Classic Mainnet chain spec.
+	pub const CLASSIC: &'static [u8] = include_bytes!("../../res/ethereum/classic.json");
+	/// Expanse mainnet chain spec.
+	pub const EXPANSE: &'static [u8] = include_bytes!("../../res/ethereum/expanse.json");
+	/// Musicoin mainnet chain spec.
+	pub const MUSICOIN: &'static [u8] = include_bytes!("../../res/ethereum/musicoin.json");
+	/// Ellaism mainnet chain spec.
+	pub const ELLAISM: &'static [u8] = include_bytes!("../../res/ethereum/ellaism.json");
+	/// Easthub mainnet chain spec.
+	pub const EASTHUB: &'static [u8] = include_bytes!("../../res/ethereum/easthub.json");
+	/// Ethereum Social mainnet chain spec.
+	pub const SOCIAL: &'static [u8] = include_bytes!("../../res/ethereum/social.json");
+	/// Kovan testnet chain spec.
+	pub const KOVAN: &'static [u8] = include_bytes!("../../res/ethereum/kovan.json");
+	/// Ropsten testnet chain spec.
+	pub const ROPSTEN: &'static [u8] = include_bytes!("../../res/ethereum/ropsten.json");
+	/// Morden testnet chain spec.
+	pub const MORDEN: &'static [u8] = include_bytes!("../../res/ethereum/morden.json");
+	/// Instant seal development chain spec.
+	pub const INSTANT_SEAL: &'static [u8] = include_bytes!("../../res/instant_seal.json");
+}
+
 fn load<'a, T: Into<Option<SpecParams<'a>>>>(params: T, b: &[u8]) -> Spec {
 	match params.into() {
 		Some(params) => Spec::load(params, b),
@@ -43,57 +71,57 @@ fn load_machine(b: &[u8]) -> EthereumMachine {
 
 /// Create a new Foundation Olympic chain spec.
 pub fn new_olympic<'a, T: Into<SpecParams<'a>>>(params: T) -> Spec {
-	load(params.into(), include_bytes!("../../res/ethereum/olympic.json"))
+	load(params.into(), bundled::OLYMPIC)
 }
 
 /// Create a new Foundation Mainnet chain spec.
 pub fn new_foundation<'a, T: Into<SpecParams<'a>>>(params: T) -> Spec {
-	load(params.into(), include_bytes!("../../res/ethereum/foundation.json"))
+	load(params.into(), bundled::FOUNDATION)
 }
 
 /// Create a new Classic Mainnet chain spec without the DAO hardfork.
 pub fn new_classic<'a, T: Into<SpecParams<'a>>>(params: T) -> Spec {
-	load(params.into(), include_bytes!("../../res/ethereum/classic.json"))
+	load(params.into(), bundled::CLASSIC)
 }
 
 /// Create a new Expanse mainnet chain spec.
 pub fn new_expanse<'a, T: Into<SpecParams<'a>>>(params: T) -> Spec {
-	load(params.into(), include_bytes!("../../res/ethereum/expanse.json"))
+	load(params.into(), bundled::EXPANSE)
 }
 
 /// Create a new Musicoin mainnet chain spec.
 pub fn new_musicoin<'a, T: Into<SpecParams<'a>>>(params: T) -> Spec {
-	load(params.into(), include_bytes!("../../res/ethereum/musicoin.json"))
+	load(params.into(), bundled::MUSICOIN)
 }
 
 /// Create a new Ellaism mainnet chain spec.
 pub fn new_ellaism<'a, T: Into<SpecParams<'a>>>(params: T) -> Spec {
-	load(params.into(), include_bytes!("../../res/ethereum/ellaism.json"))
+	load(params.into(), bundled::ELLAISM)
 }
 
 /// Create a new Easthub mainnet chain spec.
 pub fn new_easthub<'a, T: Into<SpecParams<'a>>>(params: T) -> Spec {
-	load(params.into(), include_bytes!("../../res/ethereum/easthub.json"))
+	load(params.into(), bundled::EASTHUB)
 }
 
 /// Create a new Ethereum Social mainnet chain spec ¯\_(ツ)_/¯ .
 pub fn new_social<'a, T: Into<SpecParams<'a>>>(params: T) -> Spec {
-	load(params.into(), include_bytes!("../../res/ethereum/social.json"))
+	load(params.into(), bundled::SOCIAL)
 }
 
 /// Create a new Kovan testnet chain spec.
 pub fn new_kovan<'a, T: Into<SpecParams<'a>>>(params: T) -> Spec {
-	load(params.into(), include_bytes!("../../res/ethereum/kovan.json"))
+	load(params.into(), bundled::KOVAN)
 }
 
 /// Create a new Foundation Ropsten chain spec.
 pub fn new_ropsten<'a, T: Into<SpecParams<'a>>>(params: T) -> Spec {
-	load(params.into(), include_bytes!("../../res/ethereum/ropsten.json"))
+	load(params.into(), bundled::ROPSTEN)
 }
 
 /// Create a new Morden chain spec.
 pub fn new_morden<'a, T: Into<SpecParams<'a>>>(params: T) -> Spec {
-	load(params.into(), include_bytes!("../../res/ethereum/morden.json"))
+	load(params.into(), bundled::MORDEN)
 }
 
 // For tests
@@ -122,6 +150,9 @@ pub fn new_byzantium_test() -> Spec { load(None, include_bytes!("../../res/ether
 /// Create a new Foundation Constantinople era spec.
 pub fn new_constantinople_test() -> Spec { load(None, include_bytes!("../../res/ethereum/constantinople_test.json")) }
 
+/// Create a new Foundation Constantinople era spec with the EIP-1234 bomb delay and reward.
+pub fn new_constantinople_bomb_delay_test() -> Spec { load(None, include_bytes!("../../res/ethereum/constantinople_bomb_delay_test.json")) }
+
 /// Create a new Musicoin-MCIP3-era spec.
 pub fn new_mcip3_test() -> Spec { load(None, include_bytes!("../../res/ethereum/mcip3_test.json")) }
 
@@ -148,6 +179,8 @@ pub fn new_kovan_wasm_test_machine() -> EthereumMachine { load_machine(include_b
 #[cfg(test)]
 mod tests {
 	use ethereum_types::U256;
+	use engines::Engine;
+	use header::Header;
 	use state::*;
 	use super::*;
 	use test_helpers::get_temp_state_db;
@@ -189,4 +222,23 @@ mod tests {
 
 		let _ = frontier.engine;
 	}
+
+	#[test]
+	fn constantinople_bomb_delay() {
+		let spec = new_constantinople_bomb_delay_test();
+
+		let parent_difficulty = U256::from(0x1000000u64);
+		let mut parent = Header::default();
+		parent.set_number(6_999_999);
+		parent.set_difficulty(parent_difficulty);
+		parent.set_timestamp(1_540_000_000);
+		let mut header = Header::default();
+		header.set_number(7_000_000);
+		// no adjustment of the parent difficulty, only the bomb remains.
+		header.set_timestamp(parent.timestamp() + 9);
+		spec.engine.populate_from_parent(&mut header, &parent);
+
+		// both the EIP-649 and the EIP-1234 delays apply: (2_000_000 / 100_000 - 2) periods.
+		assert_eq!(*header.difficulty(), parent_difficulty + (U256::one() << 18));
+	}
 }
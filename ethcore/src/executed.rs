@@ -18,6 +18,7 @@
 
 use ethereum_types::{U256, U512, Address};
 use bytes::Bytes;
+use transaction;
 use trie;
 use vm;
 use trace::{VMTrace, FlatTrace};
@@ -67,6 +68,24 @@ pub struct Executed<T = FlatTrace, V = VMTrace> {
 	pub vm_trace: Option<V>,
 	/// The state diff, if we traced it.
 	pub state_diff: Option<StateDiff>,
+	/// Accounts and storage keys accessed during execution.
+	pub access_counts: AccessCounts,
+}
+
+/// Number of accounts and storage keys accessed during execution of a transaction.
+///
+/// Accesses are warm if declared in the transaction's access list
+/// (the sender and the transaction's target are always warm), cold otherwise.
+#[derive(Debug, Default, PartialEq, Clone, Copy)]
+pub struct AccessCounts {
+	/// Accessed accounts known up front.
+	pub warm_addresses: usize,
+	/// Accessed accounts not declared up front.
+	pub cold_addresses: usize,
+	/// Accessed storage keys declared up front.
+	pub warm_storage_keys: usize,
+	/// Accessed storage keys not declared up front.
+	pub cold_storage_keys: usize,
 }
 
 /// Result of executing the transaction.
@@ -111,6 +130,33 @@ pub enum ExecutionError {
 	MutableCallInStaticContext,
 	/// Returned when transacting from a non-existing account with dust protection enabled.
 	SenderMustExist,
+	/// Returned when transaction gas exceeds the execution gas cap.
+	GasCapExceeded {
+		/// Maximal gas allowed.
+		cap: U256,
+		/// Gas provided.
+		got: U256
+	},
+	/// Returned when transaction is signed for another chain.
+	InvalidChainId {
+		/// Chain ID accepted at this block, `None` if only unprotected transactions are.
+		expected: Option<u64>,
+		/// Chain ID the transaction was signed for.
+		got: u64,
+	},
+	/// Returned when transaction without replay protection is executed
+	/// on a chain requiring it.
+	ReplayProtectionRequired,
+	/// Returned when zero gas price transaction is sent by a sender not certified
+	/// by the service transaction certifier.
+	SenderNotCertified,
+	/// Returned when gas of a service transaction exceeds current block author balance.
+	AuthorNotEnoughCash {
+		/// Minimum required balance.
+		required: U512,
+		/// Actual balance.
+		got: U512
+	},
 	/// Returned when internal evm error occurs.
 	Internal(String),
 	/// Returned when generic transaction occurs
@@ -123,6 +169,16 @@ impl From<Box<trie::TrieError>> for ExecutionError {
 	}
 }
 
+impl From<transaction::Error> for ExecutionError {
+	fn from(err: transaction::Error) -> Self {
+		match err {
+			transaction::Error::InvalidChainId { expected, got } => ExecutionError::InvalidChainId { expected, got },
+			transaction::Error::ReplayProtectionRequired => ExecutionError::ReplayProtectionRequired,
+			err => ExecutionError::TransactionMalformed(format!("{}", err)),
+		}
+	}
+}
+
 impl fmt::Display for ExecutionError {
 	fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
 		use self::ExecutionError::*;
@@ -140,6 +196,17 @@ impl fmt::Display for ExecutionError {
 					but the sender only has {}", required, got),
 			MutableCallInStaticContext => "Mutable Call in static context".to_owned(),
 			SenderMustExist => "Transacting from an empty account".to_owned(),
+			GasCapExceeded { ref cap, ref got } =>
+				format!("Transaction gas {} exceeds the execution gas cap of {}", got, cap),
+			InvalidChainId { expected: Some(expected), got } =>
+				format!("Transaction signed for chain ID {}, expected {}", got, expected),
+			InvalidChainId { expected: None, got } =>
+				format!("Transaction signed for chain ID {}, but replay protection is not enabled", got),
+			ReplayProtectionRequired => "Transaction is not replay-protected".to_owned(),
+			SenderNotCertified => "Zero gas price transaction from a sender not certified for service transactions".to_owned(),
+			AuthorNotEnoughCash { ref required, ref got } =>
+				format!("Gas of the service transaction exceeds block author balance. {} is required \
+					but the author only has {}", required, got),
 			Internal(ref msg) => msg.clone(),
 			TransactionMalformed(ref err) => format!("Malformed transaction: {}", err),
 		};
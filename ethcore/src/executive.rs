@@ -16,11 +16,12 @@
 
 //! Transaction Execution environment.
 use std::cmp;
+use std::collections::BTreeSet;
 use std::sync::Arc;
 use hash::keccak;
 use ethereum_types::{H256, U256, U512, Address};
 use bytes::{Bytes, BytesRef};
-use state::{Backend as StateBackend, State, Substate, CleanupMode};
+use state::{self, Backend as StateBackend, State, Substate, CleanupMode};
 use machine::EthereumMachine as Machine;
 use error::ExecutionError;
 use evm::{CallType, Finalize, FinalizationResult};
@@ -30,9 +31,10 @@ use vm::{
 };
 use externalities::*;
 use trace::{self, Tracer, VMTracer};
-use transaction::{Action, SignedTransaction};
+use transaction::{Action, AccessList, SignedTransaction, UnverifiedTransaction};
 use crossbeam;
 pub use executed::{Executed, ExecutionResult};
+use executed::AccessCounts;
 
 #[cfg(debug_assertions)]
 /// Roughly estimate what stack size each level of evm depth will use. (Debug build)
@@ -50,6 +52,14 @@ const STACK_SIZE_ENTRY_OVERHEAD: usize = 100 * 1024;
 /// Entry stack overhead prior to execution.
 const STACK_SIZE_ENTRY_OVERHEAD: usize = 20 * 1024;
 
+/// Returns the intrinsic gas of a transaction: the base cost of a call or contract creation
+/// plus the cost of its data bytes and of its access list, if declared.
+///
+/// This is charged before any code runs, so it's also the lower bound for gas estimation.
+pub fn intrinsic_gas(t: &UnverifiedTransaction, schedule: &Schedule) -> U256 {
+	t.gas_required(schedule).into()
+}
+
 /// Returns new address created from address, nonce, and code hash
 pub fn contract_address(address_scheme: CreateContractAddress, sender: &Address, nonce: &U256, code: &[u8]) -> (Address, Option<H256>) {
 	use rlp::RlpStream;
@@ -77,6 +87,27 @@ pub fn contract_address(address_scheme: CreateContractAddress, sender: &Address,
 	}
 }
 
+/// Classifies accessed accounts and storage keys as warm (declared up front) or cold.
+fn count_accesses(declared: Option<&AccessList>, always_warm: &[Address], accessed: &state::AccessList) -> AccessCounts {
+	let declared = declared.map_or(&[][..], |list| &list[..]);
+	let mut counts = AccessCounts::default();
+	for (address, keys) in accessed {
+		if always_warm.contains(address) || declared.iter().any(|&(ref a, _)| a == address) {
+			counts.warm_addresses += 1;
+		} else {
+			counts.cold_addresses += 1;
+		}
+		for key in keys {
+			if declared.iter().any(|&(ref a, ref k)| a == address && k.contains(key)) {
+				counts.warm_storage_keys += 1;
+			} else {
+				counts.cold_storage_keys += 1;
+			}
+		}
+	}
+	counts
+}
+
 /// Transaction execution options.
 #[derive(Copy, Clone, PartialEq)]
 pub struct TransactOptions<T, V> {
@@ -86,8 +117,18 @@ pub struct TransactOptions<T, V> {
 	pub vm_tracer: V,
 	/// Check transaction nonce before execution.
 	pub check_nonce: bool,
+	/// Execute without debiting the gas fee from the sender and crediting it to the author.
+	pub no_gas_charge: bool,
 	/// Records the output from init contract calls.
 	pub output_from_init_contract: bool,
+	/// Maximal gas the transaction may provide, independent of the block gas limit.
+	pub gas_cap: Option<U256>,
+	/// Maximal call depth, lowering the one defined by the schedule.
+	pub max_depth: Option<usize>,
+	/// Record the changes made to the state by the transaction.
+	pub state_diff: bool,
+	/// Count cold and warm accesses of the execution.
+	pub count_accesses: bool,
 }
 
 impl<T, V> TransactOptions<T, V> {
@@ -97,7 +138,12 @@ impl<T, V> TransactOptions<T, V> {
 			tracer,
 			vm_tracer,
 			check_nonce: true,
+			no_gas_charge: false,
 			output_from_init_contract: false,
+			gas_cap: None,
+			max_depth: None,
+			state_diff: false,
+			count_accesses: false,
 		}
 	}
 
@@ -107,11 +153,41 @@ impl<T, V> TransactOptions<T, V> {
 		self
 	}
 
+	/// Disables charging the gas fee
+	pub fn dont_charge_gas(mut self) -> Self {
+		self.no_gas_charge = true;
+		self
+	}
+
 	/// Saves the output from contract creation.
 	pub fn save_output_from_contract(mut self) -> Self {
 		self.output_from_init_contract = true;
 		self
 	}
+
+	/// Rejects transactions providing more than `cap` gas.
+	pub fn with_gas_cap(mut self, cap: U256) -> Self {
+		self.gas_cap = Some(cap);
+		self
+	}
+
+	/// Limits the call depth of the execution to `max_depth`.
+	pub fn with_max_depth(mut self, max_depth: usize) -> Self {
+		self.max_depth = Some(max_depth);
+		self
+	}
+
+	/// Records a `StateDiff` of all accounts touched by the transaction.
+	pub fn with_state_diff(mut self) -> Self {
+		self.state_diff = true;
+		self
+	}
+
+	/// Counts cold and warm accesses, also when the transaction declares no access list.
+	pub fn with_access_counts(mut self) -> Self {
+		self.count_accesses = true;
+		self
+	}
 }
 
 impl TransactOptions<trace::ExecutiveTracer, trace::ExecutiveVMTracer> {
@@ -121,7 +197,12 @@ impl TransactOptions<trace::ExecutiveTracer, trace::ExecutiveVMTracer> {
 			tracer: trace::ExecutiveTracer::default(),
 			vm_tracer: trace::ExecutiveVMTracer::toplevel(),
 			check_nonce: true,
+			no_gas_charge: false,
 			output_from_init_contract: false,
+			gas_cap: None,
+			max_depth: None,
+			state_diff: false,
+			count_accesses: true,
 		}
 	}
 }
@@ -133,7 +214,12 @@ impl TransactOptions<trace::ExecutiveTracer, trace::NoopVMTracer> {
 			tracer: trace::ExecutiveTracer::default(),
 			vm_tracer: trace::NoopVMTracer,
 			check_nonce: true,
+			no_gas_charge: false,
 			output_from_init_contract: false,
+			gas_cap: None,
+			max_depth: None,
+			state_diff: false,
+			count_accesses: true,
 		}
 	}
 }
@@ -145,7 +231,29 @@ impl TransactOptions<trace::NoopTracer, trace::ExecutiveVMTracer> {
 			tracer: trace::NoopTracer,
 			vm_tracer: trace::ExecutiveVMTracer::toplevel(),
 			check_nonce: true,
+			no_gas_charge: false,
+			output_from_init_contract: false,
+			gas_cap: None,
+			max_depth: None,
+			state_diff: false,
+			count_accesses: true,
+		}
+	}
+}
+
+impl TransactOptions<trace::NoopTracer, trace::StructLogTracer> {
+	/// Creates new `TransactOptions` with no tracing and step-by-step `structLogs` VM tracing.
+	pub fn with_struct_logs() -> Self {
+		TransactOptions {
+			tracer: trace::NoopTracer,
+			vm_tracer: trace::StructLogTracer::toplevel(),
+			check_nonce: true,
+			no_gas_charge: false,
 			output_from_init_contract: false,
+			gas_cap: None,
+			max_depth: None,
+			state_diff: false,
+			count_accesses: true,
 		}
 	}
 }
@@ -157,7 +265,12 @@ impl TransactOptions<trace::NoopTracer, trace::NoopVMTracer> {
 			tracer: trace::NoopTracer,
 			vm_tracer: trace::NoopVMTracer,
 			check_nonce: true,
+			no_gas_charge: false,
 			output_from_init_contract: false,
+			gas_cap: None,
+			max_depth: None,
+			state_diff: false,
+			count_accesses: false,
 		}
 	}
 }
@@ -169,6 +282,7 @@ pub struct Executive<'a, B: 'a + StateBackend> {
 	machine: &'a Machine,
 	depth: usize,
 	static_flag: bool,
+	max_depth: Option<usize>,
 }
 
 impl<'a, B: 'a + StateBackend> Executive<'a, B> {
@@ -180,6 +294,7 @@ impl<'a, B: 'a + StateBackend> Executive<'a, B> {
 			machine: machine,
 			depth: 0,
 			static_flag: false,
+			max_depth: None,
 		}
 	}
 
@@ -191,9 +306,16 @@ impl<'a, B: 'a + StateBackend> Executive<'a, B> {
 			machine: machine,
 			depth: parent_depth + 1,
 			static_flag: static_flag,
+			max_depth: None,
 		}
 	}
 
+	/// Lowers the maximal call depth of this and all nested executions.
+	pub fn with_max_depth(mut self, max_depth: Option<usize>) -> Self {
+		self.max_depth = max_depth;
+		self
+	}
+
 	/// Creates `Externalities` from `Executive`.
 	pub fn as_externalities<'any, T, V>(
 		&'any mut self,
@@ -206,13 +328,21 @@ impl<'a, B: 'a + StateBackend> Executive<'a, B> {
 	) -> Externalities<'any, T, V, B> where T: Tracer, V: VMTracer {
 		let is_static = self.static_flag || static_call;
 		Externalities::new(self.state, self.info, self.machine, self.depth, origin_info, substate, output, tracer, vm_tracer, is_static)
+			.with_max_depth(self.max_depth)
 	}
 
 	/// This function should be used to execute transaction.
 	pub fn transact<T, V>(&'a mut self, t: &SignedTransaction, options: TransactOptions<T, V>)
 		-> Result<Executed<T::Output, V::Output>, ExecutionError> where T: Tracer, V: VMTracer,
 	{
-		self.transact_with_tracer(t, options.check_nonce, options.output_from_init_contract, options.tracer, options.vm_tracer)
+		if let Some(cap) = options.gas_cap {
+			if t.gas > cap {
+				return Err(ExecutionError::GasCapExceeded { cap: cap, got: t.gas });
+			}
+		}
+
+		self.max_depth = options.max_depth;
+		self.transact_with_tracer(t, options)
 	}
 
 	/// Execute a transaction in a "virtual" context.
@@ -236,16 +366,14 @@ impl<'a, B: 'a + StateBackend> Executive<'a, B> {
 	fn transact_with_tracer<T, V>(
 		&'a mut self,
 		t: &SignedTransaction,
-		check_nonce: bool,
-		output_from_create: bool,
-		mut tracer: T,
-		mut vm_tracer: V
+		options: TransactOptions<T, V>,
 	) -> Result<Executed<T::Output, V::Output>, ExecutionError> where T: Tracer, V: VMTracer {
+		let TransactOptions { check_nonce, no_gas_charge, state_diff, .. } = options;
 		let sender = t.sender();
 		let nonce = self.state.nonce(&sender)?;
 
 		let schedule = self.machine.schedule(self.info.number);
-		let base_gas_required = U256::from(t.gas_required(&schedule));
+		let base_gas_required = intrinsic_gas(t, &schedule);
 
 		if t.gas < base_gas_required {
 			return Err(ExecutionError::NotEnoughBaseGas { required: base_gas_required, got: t.gas });
@@ -255,6 +383,24 @@ impl<'a, B: 'a + StateBackend> Executive<'a, B> {
 			return Err(ExecutionError::SenderMustExist);
 		}
 
+		// virtual calls carry no real signature, like the nonce the chain id isn't checked for them
+		if check_nonce && !t.is_unsigned() {
+			self.machine.verify_replay_protection(t, self.info.number)?;
+		}
+
+		// once the certifier is activated zero gas price transactions are only accepted from
+		// certified senders, the block author pays for their gas. Virtual calls are not restricted.
+		let certifier_active = self.machine.params().service_transaction_certifier.is_some()
+			&& self.info.number >= self.machine.params().service_transaction_certifier_transition;
+		let service_transaction = t.gas_price.is_zero() && certifier_active && check_nonce && !no_gas_charge && !t.is_unsigned();
+		if service_transaction {
+			let certified = self.machine.is_service_transaction(self.state, self.info, t)
+				.map_err(|e| ExecutionError::Internal(format!("{}", e)))?;
+			if !certified {
+				return Err(ExecutionError::SenderNotCertified);
+			}
+		}
+
 		let init_gas = t.gas - base_gas_required;
 
 		// validate transaction nonce
@@ -272,25 +418,98 @@ impl<'a, B: 'a + StateBackend> Executive<'a, B> {
 		}
 
 		// TODO: we might need bigints here, or at least check overflows.
+		let (payer, gas_price) = self.gas_payer(t, service_transaction);
 		let balance = self.state.balance(&sender)?;
-		let gas_cost = t.gas.full_mul(t.gas_price);
-		let total_cost = U512::from(t.value) + gas_cost;
+		let gas_cost = if no_gas_charge { U512::zero() } else { t.gas.full_mul(gas_price) };
+		let total_cost = match payer == sender {
+			true => U512::from(t.value) + gas_cost,
+			false => U512::from(t.value),
+		};
 
 		// avoid unaffordable transactions
 		let balance512 = U512::from(balance);
 		if balance512 < total_cost {
 			return Err(ExecutionError::NotEnoughCash { required: total_cost, got: balance512 });
 		}
+		if payer != sender {
+			let author_balance = U512::from(self.state.balance(&payer)?);
+			if author_balance < gas_cost {
+				return Err(ExecutionError::AuthorNotEnoughCash { required: gas_cost, got: author_balance });
+			}
+		}
 
+		// changes recorded by this checkpoint are used to build the state diff. It's discarded
+		// whether or not the execution succeeds.
+		if state_diff {
+			self.state.checkpoint();
+		}
+		let executed = self.execute_transaction(t, options, nonce, init_gas, gas_cost, service_transaction);
+		if state_diff {
+			self.state.discard_checkpoint();
+		}
+		executed
+	}
+
+	// executes a transaction which passed validation, charging `gas_cost` upfront.
+	fn execute_transaction<T, V>(
+		&mut self,
+		t: &SignedTransaction,
+		options: TransactOptions<T, V>,
+		nonce: U256,
+		init_gas: U256,
+		gas_cost: U512,
+		service_transaction: bool,
+	) -> Result<Executed<T::Output, V::Output>, ExecutionError> where T: Tracer, V: VMTracer {
+		let TransactOptions { mut tracer, mut vm_tracer, no_gas_charge, output_from_init_contract: output_from_create, state_diff, count_accesses: counts_requested, .. } = options;
+		let sender = t.sender();
+		let schedule = self.machine.schedule(self.info.number);
 		let mut substate = Substate::new();
 
 		// NOTE: there can be no invalid transactions from this point.
 		if !schedule.eip86 || !t.is_unsigned() {
 			self.state.inc_nonce(&sender)?;
 		}
-		self.state.sub_balance(&sender, &U256::from(gas_cost), &mut substate.to_cleanup_mode(&schedule))?;
+		let (payer, _) = self.gas_payer(t, service_transaction);
+		self.state.sub_balance(&payer, &U256::from(gas_cost), &mut substate.to_cleanup_mode(&schedule))?;
+
+		// the sender, the recipient, the precompiles and everything declared by the transaction
+		// are cheaper to access
+		if schedule.eip2929 {
+			substate.warm_addresses.insert(sender);
+			if let Action::Call(ref address) = t.action {
+				substate.warm_addresses.insert(*address);
+			}
+			for address in self.machine.builtins().keys() {
+				if self.machine.builtin(address, self.info.number).is_some() {
+					substate.warm_addresses.insert(*address);
+				}
+			}
+			if let Some(access_list) = t.access_list() {
+				for &(ref address, ref keys) in access_list {
+					substate.warm_addresses.insert(*address);
+					substate.warm_storage_keys.extend(keys.iter().map(|key| (*address, *key)));
+				}
+			}
+		}
 
-		let (result, output) = match t.action {
+		// pre-warm accounts and storage declared by the transaction
+		if let Some(access_list) = t.access_list() {
+			for &(ref address, ref keys) in access_list {
+				self.state.exists(address)?;
+				for key in keys {
+					self.state.storage_at(address, key)?;
+				}
+			}
+		}
+
+		// accesses are only tracked when they're counted or an outer execution collects them
+		let outer_accesses = self.state.take_accesses();
+		let track_accesses = counts_requested || t.access_list().is_some() || outer_accesses.is_some();
+		if track_accesses {
+			self.state.track_accesses();
+		}
+
+		let (result, output, target) = match t.action {
 			Action::Create => {
 				let (new_address, code_hash) = contract_address(self.machine.create_address_scheme(self.info.number), &sender, &nonce, &t.data);
 				let params = ActionParams {
@@ -308,7 +527,7 @@ impl<'a, B: 'a + StateBackend> Executive<'a, B> {
 					params_type: vm::ParamsType::Embedded,
 				};
 				let mut out = if output_from_create { Some(vec![]) } else { None };
-				(self.create(params, &mut substate, &mut out, &mut tracer, &mut vm_tracer), out.unwrap_or_else(Vec::new))
+				(self.create(params, &mut substate, &mut out, &mut tracer, &mut vm_tracer), out.unwrap_or_else(Vec::new), new_address)
 			},
 			Action::Call(ref address) => {
 				let params = ActionParams {
@@ -326,12 +545,32 @@ impl<'a, B: 'a + StateBackend> Executive<'a, B> {
 					params_type: vm::ParamsType::Separate,
 				};
 				let mut out = vec![];
-				(self.call(params, &mut substate, BytesRef::Flexible(&mut out), &mut tracer, &mut vm_tracer), out)
+				(self.call(params, &mut substate, BytesRef::Flexible(&mut out), &mut tracer, &mut vm_tracer), out, *address)
 			}
 		};
 
+		let accessed = self.state.take_accesses().unwrap_or_default();
+		let access_counts = match track_accesses {
+			true => count_accesses(t.access_list(), &[sender, target], &accessed),
+			false => AccessCounts::default(),
+		};
+		if let Some(mut outer) = outer_accesses {
+			for (address, keys) in accessed {
+				outer.entry(address).or_insert_with(BTreeSet::new).extend(keys);
+			}
+			self.state.resume_accesses(outer);
+		}
+
 		// finalize here!
-		Ok(self.finalize(t, substate, result, output, tracer.drain(), vm_tracer.drain())?)
+		Ok(self.finalize(t, substate, result, output, tracer.drain(), vm_tracer.drain(), !no_gas_charge, service_transaction, access_counts, state_diff)?)
+	}
+
+	// account paying for the transaction's gas and the gas price it pays.
+	fn gas_payer(&self, t: &SignedTransaction, service_transaction: bool) -> (Address, U256) {
+		match service_transaction {
+			true => (self.info.author, self.machine.params().service_transaction_gas_price),
+			false => (t.sender(), t.gas_price),
+		}
 	}
 
 	fn exec_vm<T, V>(
@@ -386,14 +625,21 @@ impl<'a, B: 'a + StateBackend> Executive<'a, B> {
 				((params.call_type == CallType::Call) &&
 				self.static_flag))
 			&& params.value.value() > 0.into() {
+			let trace_info = tracer.prepare_trace_call(&params);
+			tracer.trace_failed_call(trace_info, vec![], vm::Error::MutableCallInStaticContext.into());
 			return Err(vm::Error::MutableCallInStaticContext);
 		}
 
+		let schedule = self.machine.schedule(self.info.number);
+
+		// call to an account without code is a plain value transfer: no VM, no builtin, only tracing.
+		if params.code.is_none() && self.machine.builtin(&params.code_address, self.info.number).is_none() {
+			return self.transfer(params, substate, &schedule, tracer);
+		}
+
 		// backup used in case of running out of gas
 		self.state.checkpoint();
 
-		let schedule = self.machine.schedule(self.info.number);
-
 		// at first, transfer value to destination
 		if let ActionValue::Transfer(val) = params.value {
 			self.state.transfer_balance(&params.sender, &params.address, &val, substate.to_cleanup_mode(&schedule))?;
@@ -412,7 +658,7 @@ impl<'a, B: 'a + StateBackend> Executive<'a, B> {
 
 			let trace_info = tracer.prepare_trace_call(&params);
 
-			let cost = builtin.cost(data);
+			let cost = builtin.cost(data, self.info.number);
 			if cost <= params.gas {
 				let mut builtin_out_buffer = Vec::new();
 				let result = {
@@ -471,50 +717,68 @@ impl<'a, B: 'a + StateBackend> Executive<'a, B> {
 
 			let gas = params.gas;
 
-			if params.code.is_some() {
-				// part of substate that may be reverted
-				let mut unconfirmed_substate = Substate::new();
+			// part of substate that may be reverted
+			let mut unconfirmed_substate = substate.child();
 
-				// TODO: make ActionParams pass by ref then avoid copy altogether.
-				let mut subvmtracer = vm_tracer.prepare_subtrace(params.code.as_ref().expect("scope is conditional on params.code.is_some(); qed"));
+			// TODO: make ActionParams pass by ref then avoid copy altogether.
+			let mut subvmtracer = vm_tracer.prepare_subtrace(params.code.as_ref().expect("calls without code are plain transfers; qed"));
 
-				let res = {
-					self.exec_vm(schedule, params, &mut unconfirmed_substate, OutputPolicy::Return(output, trace_output.as_mut()), &mut subtracer, &mut subvmtracer)
-				};
+			let res = {
+				self.exec_vm(schedule, params, &mut unconfirmed_substate, OutputPolicy::Return(output, trace_output.as_mut()), &mut subtracer, &mut subvmtracer)
+			};
 
-				vm_tracer.done_subtrace(subvmtracer);
+			vm_tracer.done_subtrace(subvmtracer);
 
-				trace!(target: "executive", "res={:?}", res);
+			trace!(target: "executive", "res={:?}", res);
 
-				let traces = subtracer.drain();
-				match res {
-					Ok(ref res) if res.apply_state => tracer.trace_call(
-						trace_info,
-						gas - res.gas_left,
-						trace_output,
-						traces
-					),
-					Ok(_) => tracer.trace_failed_call(trace_info, traces, vm::Error::Reverted.into()),
-					Err(ref e) => tracer.trace_failed_call(trace_info, traces, e.into()),
-				};
+			let traces = subtracer.drain();
+			match res {
+				Ok(ref res) if res.apply_state => tracer.trace_call(
+					trace_info,
+					gas - res.gas_left,
+					trace_output,
+					traces
+				),
+				Ok(_) => tracer.trace_failed_call(trace_info, traces, vm::Error::Reverted.into()),
+				Err(ref e) => tracer.trace_failed_call(trace_info, traces, e.into()),
+			};
 
-				trace!(target: "executive", "substate={:?}; unconfirmed_substate={:?}\n", substate, unconfirmed_substate);
+			trace!(target: "executive", "substate={:?}; unconfirmed_substate={:?}\n", substate, unconfirmed_substate);
 
-				self.enact_result(&res, substate, unconfirmed_substate);
-				trace!(target: "executive", "enacted: substate={:?}\n", substate);
-				res
-			} else {
-				// otherwise it's just a basic transaction, only do tracing, if necessary.
-				self.state.discard_checkpoint();
+			self.enact_result(&res, substate, unconfirmed_substate);
+			trace!(target: "executive", "enacted: substate={:?}\n", substate);
+			res
+		}
+	}
 
-				tracer.trace_call(trace_info, U256::zero(), trace_output, vec![]);
-				Ok(FinalizationResult {
-					gas_left: params.gas,
-					return_data: ReturnData::empty(),
-					apply_state: true,
-				})
+	/// Performs a simple value transfer for a call to an account without code.
+	fn transfer<T>(
+		&mut self,
+		params: ActionParams,
+		substate: &mut Substate,
+		schedule: &Schedule,
+		tracer: &mut T,
+	) -> vm::Result<FinalizationResult> where T: Tracer {
+		let trace_info = tracer.prepare_trace_call(&params);
+		let trace_output = tracer.prepare_trace_output();
+
+		if let ActionValue::Transfer(val) = params.value {
+			self.state.checkpoint();
+			if let Err(e) = self.state.transfer_balance(&params.sender, &params.address, &val, substate.to_cleanup_mode(schedule)) {
+				self.state.revert_to_checkpoint();
+				let e: vm::Error = e.into();
+				tracer.trace_failed_call(trace_info, vec![], e.clone().into());
+				return Err(e);
 			}
+			self.state.discard_checkpoint();
 		}
+
+		tracer.trace_call(trace_info, U256::zero(), trace_output, vec![]);
+		Ok(FinalizationResult {
+			gas_left: params.gas,
+			return_data: ReturnData::empty(),
+			apply_state: true,
+		})
 	}
 
 	/// Creates contract with given contract params.
@@ -534,8 +798,12 @@ impl<'a, B: 'a + StateBackend> Executive<'a, B> {
 		// nonzero nonce, or nonempty code, then the creation throws immediately, with exactly
 		// the same behavior as would arise if the first byte in the init code were an invalid
 		// opcode. This applies retroactively starting from genesis.
+		// The existing account is left untouched and all gas is consumed; the dedicated error
+		// only makes the cause visible in traces and RPC responses.
 		if self.state.exists_and_has_code_or_nonce(&params.address)? {
-			return Err(vm::Error::OutOfGas);
+			let trace_info = tracer.prepare_trace_create(&params);
+			tracer.trace_failed_create(trace_info, vec![], vm::Error::ContractAddressCollision.into());
+			return Err(vm::Error::ContractAddressCollision);
 		}
 
 		trace!("Executive::create(params={:?}) self.env_info={:?}, static={}", params, self.info, self.static_flag);
@@ -548,11 +816,16 @@ impl<'a, B: 'a + StateBackend> Executive<'a, B> {
 		// backup used in case of running out of gas
 		self.state.checkpoint();
 
-		// part of substate that may be reverted
-		let mut unconfirmed_substate = Substate::new();
-
 		// create contract and transfer value to it if necessary
 		let schedule = self.machine.schedule(self.info.number);
+
+		// the created account stays warm even if the creation fails
+		if schedule.eip2929 {
+			substate.warm_addresses.insert(params.address);
+		}
+
+		// part of substate that may be reverted
+		let mut unconfirmed_substate = substate.child();
 		let nonce_offset = if schedule.no_empty {1} else {0}.into();
 		let prev_bal = self.state.balance(&params.address)?;
 		if let ActionValue::Transfer(val) = params.value {
@@ -605,12 +878,16 @@ impl<'a, B: 'a + StateBackend> Executive<'a, B> {
 		result: vm::Result<FinalizationResult>,
 		output: Bytes,
 		trace: Vec<T>,
-		vm_trace: Option<V>
+		vm_trace: Option<V>,
+		charge_gas: bool,
+		service_transaction: bool,
+		access_counts: AccessCounts,
+		state_diff: bool,
 	) -> Result<Executed<T, V>, ExecutionError> {
 		let schedule = self.machine.schedule(self.info.number);
 
-		// refunds from SSTORE nonzero -> zero
-		let sstore_refunds = U256::from(schedule.sstore_refund_gas) * substate.sstore_clears_count;
+		// refunds from SSTORE; never negative once the whole transaction is executed
+		let sstore_refunds = U256::from(cmp::max(substate.sstore_clears_refund, 0) as u64);
 		// refunds from contract suicides
 		let suicide_refunds = U256::from(schedule.suicide_refund_gas) * U256::from(substate.suicides.len());
 		let refunds_bound = sstore_refunds + suicide_refunds;
@@ -621,28 +898,44 @@ impl<'a, B: 'a + StateBackend> Executive<'a, B> {
 		let gas_left = gas_left_prerefund + refunded;
 
 		let gas_used = t.gas - gas_left;
-		let refund_value = gas_left * t.gas_price;
-		let fees_value = gas_used * t.gas_price;
+		let (payer, gas_price) = self.gas_payer(t, service_transaction);
+		let refund_value = gas_left * gas_price;
+		// the author paying for a service transaction doesn't collect its fee, it's burnt.
+		let fees_value = if service_transaction { U256::zero() } else { gas_used * gas_price };
 
 		trace!("exec::finalize: t.gas={}, sstore_refunds={}, suicide_refunds={}, refunds_bound={}, gas_left_prerefund={}, refunded={}, gas_left={}, gas_used={}, refund_value={}, fees_value={}\n",
 			t.gas, sstore_refunds, suicide_refunds, refunds_bound, gas_left_prerefund, refunded, gas_left, gas_used, refund_value, fees_value);
 
-		let sender = t.sender();
-		trace!("exec::finalize: Refunding refund_value={}, sender={}\n", refund_value, sender);
-		// Below: NoEmpty is safe since the sender must already be non-null to have sent this transaction
-		self.state.add_balance(&sender, &refund_value, CleanupMode::NoEmpty)?;
-		trace!("exec::finalize: Compensating author: fees_value={}, author={}\n", fees_value, &self.info.author);
-		self.state.add_balance(&self.info.author, &fees_value, substate.to_cleanup_mode(&schedule))?;
+		if charge_gas {
+			trace!("exec::finalize: Refunding refund_value={}, payer={}\n", refund_value, payer);
+			// Below: NoEmpty is safe since the payer must already be non-null to have paid for this transaction
+			self.state.add_balance(&payer, &refund_value, CleanupMode::NoEmpty)?;
+			trace!("exec::finalize: Compensating author: fees_value={}, author={}\n", fees_value, &self.info.author);
+			self.state.add_balance(&self.info.author, &fees_value, substate.to_cleanup_mode(&schedule))?;
+		}
 
 		// perform suicides
 		for address in &substate.suicides {
+			let kept = match schedule.suicide_burns_balance {
+				true => U256::zero(),
+				false => self.state.balance(address)?,
+			};
 			self.state.kill_account(address);
+			if !kept.is_zero() {
+				self.state.add_balance(address, &kept, CleanupMode::NoEmpty)?;
+			}
 		}
 
 		// perform garbage-collection
 		let min_balance = if schedule.kill_dust != CleanDustMode::Off { Some(U256::from(schedule.tx_gas) * t.gas_price) } else { None };
 		self.state.kill_garbage(&substate.touched, schedule.kill_empty, &min_balance, schedule.kill_dust == CleanDustMode::WithCodeAndStorage)?;
 
+		let state_diff = if state_diff {
+			Some(self.state.diff_since_checkpoint()?)
+		} else {
+			None
+		};
+
 		match result {
 			Err(vm::Error::Internal(msg)) => Err(ExecutionError::Internal(msg)),
 			Err(exception) => {
@@ -657,7 +950,8 @@ impl<'a, B: 'a + StateBackend> Executive<'a, B> {
 					output: output,
 					trace: trace,
 					vm_trace: vm_trace,
-					state_diff: None,
+					state_diff: state_diff,
+					access_counts: access_counts,
 				})
 			},
 			Ok(r) => {
@@ -672,7 +966,8 @@ impl<'a, B: 'a + StateBackend> Executive<'a, B> {
 					output: output,
 					trace: trace,
 					vm_trace: vm_trace,
-					state_diff: None,
+					state_diff: state_diff,
+					access_counts: access_counts,
 				})
 			},
 		}
@@ -690,6 +985,8 @@ impl<'a, B: 'a + StateBackend> Executive<'a, B> {
 				| Err(vm::Error::MutableCallInStaticContext)
 				| Err(vm::Error::OutOfBounds)
 				| Err(vm::Error::Reverted)
+				| Err(vm::Error::ContractAddressCollision)
+				| Err(vm::Error::OutOfMemory {..})
 				| Ok(FinalizationResult { apply_state: false, .. }) => {
 					self.state.revert_to_checkpoint();
 			},
@@ -701,6 +998,64 @@ impl<'a, B: 'a + StateBackend> Executive<'a, B> {
 	}
 }
 
+/// Executes consecutive transactions of a single block.
+///
+/// Owns the `EnvInfo` of the block and updates its `gas_used` after every transaction, so
+/// transactions not fitting into the rest of the block gas limit are rejected.
+#[derive(Debug, Clone)]
+pub struct BlockExecutor {
+	env_info: EnvInfo,
+}
+
+impl BlockExecutor {
+	/// Create a new executor of transactions following the ones accounted in `env_info`.
+	pub fn new(env_info: EnvInfo) -> Self {
+		BlockExecutor {
+			env_info: env_info,
+		}
+	}
+
+	/// Environment of the next transaction.
+	pub fn env_info(&self) -> &EnvInfo {
+		&self.env_info
+	}
+
+	/// Gas used by the transactions of the block so far.
+	pub fn gas_used(&self) -> U256 {
+		self.env_info.gas_used
+	}
+
+	/// Gas available to the following transactions.
+	pub fn gas_remaining(&self) -> U256 {
+		self.env_info.gas_limit.saturating_sub(self.env_info.gas_used)
+	}
+
+	/// Execute the next transaction of the block.
+	pub fn transact<B, T, V>(&mut self, state: &mut State<B>, machine: &Machine, t: &SignedTransaction, options: TransactOptions<T, V>)
+		-> Result<Executed<T::Output, V::Output>, ExecutionError> where B: StateBackend, T: Tracer, V: VMTracer,
+	{
+		let executed = {
+			let mut e = Executive::new(state, &self.env_info, machine);
+			e.transact(t, options)?
+		};
+		self.env_info.gas_used = executed.cumulative_gas_used;
+		Ok(executed)
+	}
+
+	/// Execute the next transaction of the block in a "virtual" context.
+	/// See `Executive::transact_virtual`.
+	pub fn transact_virtual<B, T, V>(&mut self, state: &mut State<B>, machine: &Machine, t: &SignedTransaction, options: TransactOptions<T, V>)
+		-> Result<Executed<T::Output, V::Output>, ExecutionError> where B: StateBackend, T: Tracer, V: VMTracer,
+	{
+		let executed = {
+			let mut e = Executive::new(state, &self.env_info, machine);
+			e.transact_virtual(t, options)?
+		};
+		self.env_info.gas_used = executed.cumulative_gas_used;
+		Ok(executed)
+	}
+}
+
 #[cfg(test)]
 #[allow(dead_code)]
 mod tests {
@@ -716,6 +1071,7 @@ mod tests {
 	use error::ExecutionError;
 	use machine::EthereumMachine;
 	use state::{Substate, CleanupMode};
+	use spec::CommonParams;
 	use test_helpers::{get_temp_state_with_factory, get_temp_state};
 	use trace::trace;
 	use trace::{FlatTrace, Tracer, NoopTracer, ExecutiveTracer};
@@ -742,6 +1098,54 @@ mod tests {
 	}
 
 	// TODO: replace params with transactions!
+	#[test]
+	fn test_intrinsic_gas() {
+		let schedule = ::vm::Schedule::new_byzantium();
+		let keypair = Random.generate().unwrap();
+		let t = Transaction {
+			action: Action::Create,
+			value: U256::zero(),
+			data: vec![0, 0, 1],
+			gas: U256::from(100_000),
+			gas_price: U256::zero(),
+			nonce: U256::zero()
+		};
+
+		// 53000 creation + 2 * 4 zero bytes + 68 non-zero byte
+		assert_eq!(intrinsic_gas(&t.clone().sign(keypair.secret(), None), &schedule), U256::from(53_076));
+
+		let address = Address::from(0x1234);
+		let t = Transaction { action: Action::Call(address), ..t }
+			.sign_with_access_list(keypair.secret(), 1, vec![(address, vec![H256::from(1), H256::from(2)])]);
+		// 21000 call + 76 data + 2400 per address + 1900 per storage key
+		assert_eq!(intrinsic_gas(&t, &schedule), U256::from(27_276));
+	}
+
+	#[test]
+	fn test_intrinsic_gas_across_eip2028() {
+		let mut params = CommonParams::default();
+		params.eip2028_transition = 10;
+		let keypair = Random.generate().unwrap();
+		let t = Transaction {
+			action: Action::Call(Address::from(0x1234)),
+			value: U256::zero(),
+			data: vec![0, 1, 1],
+			gas: U256::from(100_000),
+			gas_price: U256::zero(),
+			nonce: U256::zero()
+		}.sign(keypair.secret(), None);
+
+		let mut schedule = ::vm::Schedule::new_byzantium();
+		params.update_schedule(9, &mut schedule);
+		// 21000 call + 4 zero byte + 2 * 68 non-zero bytes
+		assert_eq!(intrinsic_gas(&t, &schedule), U256::from(21_140));
+
+		let mut schedule = ::vm::Schedule::new_byzantium();
+		params.update_schedule(10, &mut schedule);
+		// 21000 call + 4 zero byte + 2 * 16 non-zero bytes
+		assert_eq!(intrinsic_gas(&t, &schedule), U256::from(21_036));
+	}
+
 	evm_test!{test_sender_balance: test_sender_balance_int}
 	fn test_sender_balance(factory: Factory) {
 		let sender = Address::from_str("0f572e5295c57f15886f9b263e2f6d2d6c7b5ec6").unwrap();
@@ -895,6 +1299,56 @@ mod tests {
 		}]);
 	}
 
+	#[test]
+	fn test_plain_value_transfer() {
+		let sender: Address = "4444444444444444444444444444444444444444".into();
+		let receiver: Address = "5555555555555555555555555555555555555555".into();
+		let mut params = ActionParams::default();
+		params.sender = sender.clone();
+		params.origin = sender.clone();
+		params.address = receiver.clone();
+		params.code_address = receiver.clone();
+		params.gas = U256::from(21_000);
+		params.value = ActionValue::Transfer(U256::from(100));
+		params.call_type = CallType::Call;
+		let mut state = get_temp_state();
+		state.add_balance(&sender, &U256::from(150), CleanupMode::NoEmpty).unwrap();
+		let info = EnvInfo::default();
+		let machine = make_frontier_machine(0);
+		let mut substate = Substate::new();
+		let mut tracer = ExecutiveTracer::default();
+		let mut vm_tracer = ExecutiveVMTracer::toplevel();
+
+		let FinalizationResult { gas_left, apply_state, .. } = {
+			let mut ex = Executive::new(&mut state, &info, &machine);
+			ex.call(params, &mut substate, BytesRef::Fixed(&mut []), &mut tracer, &mut vm_tracer).unwrap()
+		};
+
+		assert_eq!(gas_left, U256::from(21_000));
+		assert!(apply_state);
+		assert_eq!(state.balance(&sender).unwrap(), U256::from(50));
+		assert_eq!(state.balance(&receiver).unwrap(), U256::from(100));
+		assert_eq!(tracer.drain(), vec![FlatTrace {
+			action: trace::Action::Call(trace::Call {
+				from: sender,
+				to: receiver,
+				value: 100.into(),
+				gas: 21_000.into(),
+				input: vec![],
+				call_type: CallType::Call
+			}),
+			result: trace::Res::Call(trace::CallResult {
+				gas_used: 0.into(),
+				output: vec![]
+			}),
+			subtraces: 0,
+			trace_address: Default::default()
+		}]);
+		assert!(vm_tracer.drain().is_none());
+		// no checkpoint is left behind
+		state.to_pod();
+	}
+
 	#[test]
 	// Tracing is not suported in JIT
 	fn test_call_to_create() {
@@ -988,28 +1442,28 @@ mod tests {
 			parent_step: 0,
 			code: vec![124, 96, 16, 128, 96, 12, 96, 0, 57, 96, 0, 243, 0, 96, 0, 53, 84, 21, 96, 9, 87, 0, 91, 96, 32, 53, 96, 0, 53, 85, 96, 0, 82, 96, 29, 96, 3, 96, 23, 240, 96, 0, 85],
 			operations: vec![
-				VMOperation { pc: 0, instruction: 124, gas_cost: 3.into(), executed: Some(VMExecutedOperation { gas_used: 99997.into(), stack_push: vec_into![U256::from_dec_str("2589892687202724018173567190521546555304938078595079151649957320078677").unwrap()], mem_diff: None, store_diff: None }) },
-				VMOperation { pc: 30, instruction: 96, gas_cost: 3.into(), executed: Some(VMExecutedOperation { gas_used: 99994.into(), stack_push: vec_into![0], mem_diff: None, store_diff: None }) },
-				VMOperation { pc: 32, instruction: 82, gas_cost: 6.into(), executed: Some(VMExecutedOperation { gas_used: 99988.into(), stack_push: vec_into![], mem_diff: Some(MemoryDiff { offset: 0, data: vec![0, 0, 0, 96, 16, 128, 96, 12, 96, 0, 57, 96, 0, 243, 0, 96, 0, 53, 84, 21, 96, 9, 87, 0, 91, 96, 32, 53, 96, 0, 53, 85] }), store_diff: None }) },
-				VMOperation { pc: 33, instruction: 96, gas_cost: 3.into(), executed: Some(VMExecutedOperation { gas_used: 99985.into(), stack_push: vec_into![29], mem_diff: None, store_diff: None }) },
-				VMOperation { pc: 35, instruction: 96, gas_cost: 3.into(), executed: Some(VMExecutedOperation { gas_used: 99982.into(), stack_push: vec_into![3], mem_diff: None, store_diff: None }) },
-				VMOperation { pc: 37, instruction: 96, gas_cost: 3.into(), executed: Some(VMExecutedOperation { gas_used: 99979.into(), stack_push: vec_into![23], mem_diff: None, store_diff: None }) },
-				VMOperation { pc: 39, instruction: 240, gas_cost: 99979.into(), executed: Some(VMExecutedOperation { gas_used: 64755.into(), stack_push: vec_into![U256::from_dec_str("1135198453258042933984631383966629874710669425204").unwrap()], mem_diff: None, store_diff: None }) },
-				VMOperation { pc: 40, instruction: 96, gas_cost: 3.into(), executed: Some(VMExecutedOperation { gas_used: 64752.into(), stack_push: vec_into![0], mem_diff: None, store_diff: None }) },
-				VMOperation { pc: 42, instruction: 85, gas_cost: 20000.into(), executed: Some(VMExecutedOperation { gas_used: 44752.into(), stack_push: vec_into![], mem_diff: None, store_diff: Some(StorageDiff { location: 0.into(), value: U256::from_dec_str("1135198453258042933984631383966629874710669425204").unwrap() }) }) }
+				VMOperation { pc: 0, instruction: 124, gas_cost: 3.into(), dynamic_gas: 0.into(), executed: Some(VMExecutedOperation { gas_used: 99997.into(), stack_push: vec_into![U256::from_dec_str("2589892687202724018173567190521546555304938078595079151649957320078677").unwrap()], mem_diff: None, store_diff: None }) },
+				VMOperation { pc: 30, instruction: 96, gas_cost: 3.into(), dynamic_gas: 0.into(), executed: Some(VMExecutedOperation { gas_used: 99994.into(), stack_push: vec_into![0], mem_diff: None, store_diff: None }) },
+				VMOperation { pc: 32, instruction: 82, gas_cost: 6.into(), dynamic_gas: 0.into(), executed: Some(VMExecutedOperation { gas_used: 99988.into(), stack_push: vec_into![], mem_diff: Some(MemoryDiff { offset: 0, data: vec![0, 0, 0, 96, 16, 128, 96, 12, 96, 0, 57, 96, 0, 243, 0, 96, 0, 53, 84, 21, 96, 9, 87, 0, 91, 96, 32, 53, 96, 0, 53, 85] }), store_diff: None }) },
+				VMOperation { pc: 33, instruction: 96, gas_cost: 3.into(), dynamic_gas: 0.into(), executed: Some(VMExecutedOperation { gas_used: 99985.into(), stack_push: vec_into![29], mem_diff: None, store_diff: None }) },
+				VMOperation { pc: 35, instruction: 96, gas_cost: 3.into(), dynamic_gas: 0.into(), executed: Some(VMExecutedOperation { gas_used: 99982.into(), stack_push: vec_into![3], mem_diff: None, store_diff: None }) },
+				VMOperation { pc: 37, instruction: 96, gas_cost: 3.into(), dynamic_gas: 0.into(), executed: Some(VMExecutedOperation { gas_used: 99979.into(), stack_push: vec_into![23], mem_diff: None, store_diff: None }) },
+				VMOperation { pc: 39, instruction: 240, gas_cost: 99979.into(), dynamic_gas: 0.into(), executed: Some(VMExecutedOperation { gas_used: 64755.into(), stack_push: vec_into![U256::from_dec_str("1135198453258042933984631383966629874710669425204").unwrap()], mem_diff: None, store_diff: None }) },
+				VMOperation { pc: 40, instruction: 96, gas_cost: 3.into(), dynamic_gas: 0.into(), executed: Some(VMExecutedOperation { gas_used: 64752.into(), stack_push: vec_into![0], mem_diff: None, store_diff: None }) },
+				VMOperation { pc: 42, instruction: 85, gas_cost: 20000.into(), dynamic_gas: 0.into(), executed: Some(VMExecutedOperation { gas_used: 44752.into(), stack_push: vec_into![], mem_diff: None, store_diff: Some(StorageDiff { location: 0.into(), value: U256::from_dec_str("1135198453258042933984631383966629874710669425204").unwrap() }) }) }
 			],
 			subs: vec![
 				VMTrace {
 					parent_step: 6,
 					code: vec![96, 16, 128, 96, 12, 96, 0, 57, 96, 0, 243, 0, 96, 0, 53, 84, 21, 96, 9, 87, 0, 91, 96, 32, 53, 96, 0, 53, 85],
 					operations: vec![
-						VMOperation { pc: 0, instruction: 96, gas_cost: 3.into(), executed: Some(VMExecutedOperation { gas_used: 67976.into(), stack_push: vec_into![16], mem_diff: None, store_diff: None }) },
-						VMOperation { pc: 2, instruction: 128, gas_cost: 3.into(), executed: Some(VMExecutedOperation { gas_used: 67973.into(), stack_push: vec_into![16, 16], mem_diff: None, store_diff: None }) },
-						VMOperation { pc: 3, instruction: 96, gas_cost: 3.into(), executed: Some(VMExecutedOperation { gas_used: 67970.into(), stack_push: vec_into![12], mem_diff: None, store_diff: None }) },
-						VMOperation { pc: 5, instruction: 96, gas_cost: 3.into(), executed: Some(VMExecutedOperation { gas_used: 67967.into(), stack_push: vec_into![0], mem_diff: None, store_diff: None }) },
-						VMOperation { pc: 7, instruction: 57, gas_cost: 9.into(), executed: Some(VMExecutedOperation { gas_used: 67958.into(), stack_push: vec_into![], mem_diff: Some(MemoryDiff { offset: 0, data: vec![96, 0, 53, 84, 21, 96, 9, 87, 0, 91, 96, 32, 53, 96, 0, 53] }), store_diff: None }) },
-						VMOperation { pc: 8, instruction: 96, gas_cost: 3.into(), executed: Some(VMExecutedOperation { gas_used: 67955.into(), stack_push: vec_into![0], mem_diff: None, store_diff: None }) },
-						VMOperation { pc: 10, instruction: 243, gas_cost: 0.into(), executed: Some(VMExecutedOperation { gas_used: 67955.into(), stack_push: vec_into![], mem_diff: None, store_diff: None }) }
+						VMOperation { pc: 0, instruction: 96, gas_cost: 3.into(), dynamic_gas: 0.into(), executed: Some(VMExecutedOperation { gas_used: 67976.into(), stack_push: vec_into![16], mem_diff: None, store_diff: None }) },
+						VMOperation { pc: 2, instruction: 128, gas_cost: 3.into(), dynamic_gas: 0.into(), executed: Some(VMExecutedOperation { gas_used: 67973.into(), stack_push: vec_into![16, 16], mem_diff: None, store_diff: None }) },
+						VMOperation { pc: 3, instruction: 96, gas_cost: 3.into(), dynamic_gas: 0.into(), executed: Some(VMExecutedOperation { gas_used: 67970.into(), stack_push: vec_into![12], mem_diff: None, store_diff: None }) },
+						VMOperation { pc: 5, instruction: 96, gas_cost: 3.into(), dynamic_gas: 0.into(), executed: Some(VMExecutedOperation { gas_used: 67967.into(), stack_push: vec_into![0], mem_diff: None, store_diff: None }) },
+						VMOperation { pc: 7, instruction: 57, gas_cost: 9.into(), dynamic_gas: 3.into(), executed: Some(VMExecutedOperation { gas_used: 67958.into(), stack_push: vec_into![], mem_diff: Some(MemoryDiff { offset: 0, data: vec![96, 0, 53, 84, 21, 96, 9, 87, 0, 91, 96, 32, 53, 96, 0, 53] }), store_diff: None }) },
+						VMOperation { pc: 8, instruction: 96, gas_cost: 3.into(), dynamic_gas: 0.into(), executed: Some(VMExecutedOperation { gas_used: 67955.into(), stack_push: vec_into![0], mem_diff: None, store_diff: None }) },
+						VMOperation { pc: 10, instruction: 243, gas_cost: 0.into(), dynamic_gas: 0.into(), executed: Some(VMExecutedOperation { gas_used: 67955.into(), stack_push: vec_into![], mem_diff: None, store_diff: None }) }
 					],
 					subs: vec![]
 				}
@@ -1097,6 +1551,55 @@ mod tests {
 		assert_eq!(tracer.drain(), expected_trace);
 	}
 
+	#[test]
+	fn test_trace_create_address_collision() {
+		let code = "601080600c6000396000f3006000355415600957005b60203560003555".from_hex().unwrap();
+
+		let sender = Address::from_str("cd1722f3947def4cf144679da39c4c32bdc35681").unwrap();
+		let address = contract_address(CreateContractAddress::FromSenderAndNonce, &sender, &U256::zero(), &[]).0;
+		let mut params = ActionParams::default();
+		params.address = address.clone();
+		params.sender = sender.clone();
+		params.origin = sender.clone();
+		params.gas = U256::from(100_000);
+		params.code = Some(Arc::new(code.clone()));
+		params.value = ActionValue::Transfer(100.into());
+		let mut state = get_temp_state();
+		state.add_balance(&sender, &U256::from(100), CleanupMode::NoEmpty).unwrap();
+		state.inc_nonce(&address).unwrap();
+		let info = EnvInfo::default();
+		let machine = make_frontier_machine(5);
+		let mut substate = Substate::new();
+		let mut tracer = ExecutiveTracer::default();
+
+		let result = {
+			let mut ex = Executive::new(&mut state, &info, &machine);
+			ex.create(params.clone(), &mut substate, &mut None, &mut tracer, &mut NoopVMTracer)
+		};
+
+		match result {
+			Err(vm::Error::ContractAddressCollision) => {},
+			_ => panic!("Expected ContractAddressCollision"),
+		}
+
+		let expected_trace = vec![FlatTrace {
+			trace_address: Default::default(),
+			subtraces: 0,
+			action: trace::Action::Create(trace::Create {
+				from: params.sender,
+				value: 100.into(),
+				gas: params.gas,
+				init: code,
+			}),
+			result: trace::Res::FailedCreate(vm::Error::ContractAddressCollision.into()),
+		}];
+
+		assert_eq!(tracer.drain(), expected_trace);
+		// the colliding account is not overwritten and no value is moved
+		assert_eq!(state.nonce(&address).unwrap(), U256::one());
+		assert_eq!(state.balance(&sender).unwrap(), U256::from(100));
+	}
+
 	#[test]
 	fn test_create_contract() {
 		// Tracing is not supported in JIT
@@ -1160,13 +1663,13 @@ mod tests {
 			parent_step: 0,
 			code: vec![96, 16, 128, 96, 12, 96, 0, 57, 96, 0, 243, 0, 96, 0, 53, 84, 21, 96, 9, 87, 0, 91, 96, 32, 53, 96, 0, 53, 85],
 			operations: vec![
-				VMOperation { pc: 0, instruction: 96, gas_cost: 3.into(), executed: Some(VMExecutedOperation { gas_used: 99997.into(), stack_push: vec_into![16], mem_diff: None, store_diff: None }) },
-				VMOperation { pc: 2, instruction: 128, gas_cost: 3.into(), executed: Some(VMExecutedOperation { gas_used: 99994.into(), stack_push: vec_into![16, 16], mem_diff: None, store_diff: None }) },
-				VMOperation { pc: 3, instruction: 96, gas_cost: 3.into(), executed: Some(VMExecutedOperation { gas_used: 99991.into(), stack_push: vec_into![12], mem_diff: None, store_diff: None }) },
-				VMOperation { pc: 5, instruction: 96, gas_cost: 3.into(), executed: Some(VMExecutedOperation { gas_used: 99988.into(), stack_push: vec_into![0], mem_diff: None, store_diff: None }) },
-				VMOperation { pc: 7, instruction: 57, gas_cost: 9.into(), executed: Some(VMExecutedOperation { gas_used: 99979.into(), stack_push: vec_into![], mem_diff: Some(MemoryDiff { offset: 0, data: vec![96, 0, 53, 84, 21, 96, 9, 87, 0, 91, 96, 32, 53, 96, 0, 53] }), store_diff: None }) },
-				VMOperation { pc: 8, instruction: 96, gas_cost: 3.into(), executed: Some(VMExecutedOperation { gas_used: 99976.into(), stack_push: vec_into![0], mem_diff: None, store_diff: None }) },
-				VMOperation { pc: 10, instruction: 243, gas_cost: 0.into(), executed: Some(VMExecutedOperation { gas_used: 99976.into(), stack_push: vec_into![], mem_diff: None, store_diff: None }) }
+				VMOperation { pc: 0, instruction: 96, gas_cost: 3.into(), dynamic_gas: 0.into(), executed: Some(VMExecutedOperation { gas_used: 99997.into(), stack_push: vec_into![16], mem_diff: None, store_diff: None }) },
+				VMOperation { pc: 2, instruction: 128, gas_cost: 3.into(), dynamic_gas: 0.into(), executed: Some(VMExecutedOperation { gas_used: 99994.into(), stack_push: vec_into![16, 16], mem_diff: None, store_diff: None }) },
+				VMOperation { pc: 3, instruction: 96, gas_cost: 3.into(), dynamic_gas: 0.into(), executed: Some(VMExecutedOperation { gas_used: 99991.into(), stack_push: vec_into![12], mem_diff: None, store_diff: None }) },
+				VMOperation { pc: 5, instruction: 96, gas_cost: 3.into(), dynamic_gas: 0.into(), executed: Some(VMExecutedOperation { gas_used: 99988.into(), stack_push: vec_into![0], mem_diff: None, store_diff: None }) },
+				VMOperation { pc: 7, instruction: 57, gas_cost: 9.into(), dynamic_gas: 3.into(), executed: Some(VMExecutedOperation { gas_used: 99979.into(), stack_push: vec_into![], mem_diff: Some(MemoryDiff { offset: 0, data: vec![96, 0, 53, 84, 21, 96, 9, 87, 0, 91, 96, 32, 53, 96, 0, 53] }), store_diff: None }) },
+				VMOperation { pc: 8, instruction: 96, gas_cost: 3.into(), dynamic_gas: 0.into(), executed: Some(VMExecutedOperation { gas_used: 99976.into(), stack_push: vec_into![0], mem_diff: None, store_diff: None }) },
+				VMOperation { pc: 10, instruction: 243, gas_cost: 0.into(), dynamic_gas: 0.into(), executed: Some(VMExecutedOperation { gas_used: 99976.into(), stack_push: vec_into![], mem_diff: None, store_diff: None }) }
 			],
 			subs: vec![]
 		};
@@ -1421,6 +1924,262 @@ mod tests {
 		assert_eq!(state.storage_at(&contract, &H256::new()).unwrap(), H256::from(&U256::from(1)));
 	}
 
+	evm_test!{test_transact_suicide_to_self: test_transact_suicide_to_self_int}
+	fn test_transact_suicide_to_self(factory: Factory) {
+		// ADDRESS SUICIDE
+		let code = "30ff".from_hex().unwrap();
+		let keypair = Random.generate().unwrap();
+		let contract = Address::from(0x10);
+		let t = Transaction {
+			action: Action::Call(contract),
+			value: U256::from(17),
+			data: vec![],
+			gas: U256::from(100_000),
+			gas_price: U256::zero(),
+			nonce: U256::zero()
+		}.sign(keypair.secret(), None);
+		let sender = t.sender();
+
+		let mut state = get_temp_state_with_factory(factory);
+		state.add_balance(&sender, &U256::from(18), CleanupMode::NoEmpty).unwrap();
+		state.add_balance(&contract, &U256::from(5), CleanupMode::NoEmpty).unwrap();
+		state.init_code(&contract, code).unwrap();
+		let mut info = EnvInfo::default();
+		info.gas_limit = U256::from(100_000);
+		let machine = make_frontier_machine(0);
+
+		let executed = {
+			let mut ex = Executive::new(&mut state, &info, &machine);
+			ex.transact(&t, TransactOptions::with_no_tracing()).unwrap()
+		};
+
+		assert!(executed.exception.is_none());
+		// both the transferred value and the previous balance are burnt
+		assert_eq!(state.balance(&sender).unwrap(), U256::from(1));
+		assert_eq!(state.balance(&contract).unwrap(), U256::zero());
+		assert!(!state.exists(&contract).unwrap());
+	}
+
+	evm_test!{test_transact_suicide_to_self_keeping_balance: test_transact_suicide_to_self_keeping_balance_int}
+	fn test_transact_suicide_to_self_keeping_balance(factory: Factory) {
+		// ADDRESS SUICIDE
+		let code = "30ff".from_hex().unwrap();
+		let keypair = Random.generate().unwrap();
+		let contract = Address::from(0x10);
+		let t = Transaction {
+			action: Action::Call(contract),
+			value: U256::from(17),
+			data: vec![],
+			gas: U256::from(100_000),
+			gas_price: U256::zero(),
+			nonce: U256::zero()
+		}.sign(keypair.secret(), None);
+		let sender = t.sender();
+
+		let mut state = get_temp_state_with_factory(factory);
+		state.add_balance(&sender, &U256::from(18), CleanupMode::NoEmpty).unwrap();
+		state.add_balance(&contract, &U256::from(5), CleanupMode::NoEmpty).unwrap();
+		state.init_code(&contract, code).unwrap();
+		let mut info = EnvInfo::default();
+		info.gas_limit = U256::from(100_000);
+		let mut params = ::ethereum::new_byzantium_test().params().clone();
+		params.selfdestruct_keeps_balance_transition = 0;
+		let machine = EthereumMachine::regular(params, Default::default());
+
+		let executed = {
+			let mut ex = Executive::new(&mut state, &info, &machine);
+			ex.transact(&t, TransactOptions::with_no_tracing()).unwrap()
+		};
+
+		assert!(executed.exception.is_none());
+		// the code is gone, the transferred value and the previous balance are kept
+		assert_eq!(state.balance(&sender).unwrap(), U256::from(1));
+		assert_eq!(state.balance(&contract).unwrap(), U256::from(22));
+		assert_eq!(state.code(&contract).unwrap(), None);
+	}
+
+	evm_test!{test_transact_suicide_disabled: test_transact_suicide_disabled_int}
+	fn test_transact_suicide_disabled(factory: Factory) {
+		// ADDRESS SUICIDE
+		let code = "30ff".from_hex().unwrap();
+		let keypair = Random.generate().unwrap();
+		let contract = Address::from(0x10);
+		let t = Transaction {
+			action: Action::Call(contract),
+			value: U256::zero(),
+			data: vec![],
+			gas: U256::from(100_000),
+			gas_price: U256::zero(),
+			nonce: U256::zero()
+		}.sign(keypair.secret(), None);
+
+		let mut state = get_temp_state_with_factory(factory);
+		state.add_balance(&contract, &U256::from(5), CleanupMode::NoEmpty).unwrap();
+		state.init_code(&contract, code.clone()).unwrap();
+		let mut info = EnvInfo::default();
+		info.gas_limit = U256::from(100_000);
+		let mut params = ::ethereum::new_byzantium_test().params().clone();
+		params.selfdestruct_disable_transition = 0;
+		let machine = EthereumMachine::regular(params, Default::default());
+
+		let executed = {
+			let mut ex = Executive::new(&mut state, &info, &machine);
+			ex.transact(&t, TransactOptions::with_no_tracing()).unwrap()
+		};
+
+		assert_eq!(executed.exception, Some(vm::Error::BadInstruction { instruction: 0xff }));
+		assert_eq!(state.balance(&contract).unwrap(), U256::from(5));
+		assert_eq!(state.code(&contract).unwrap().map(|c| (*c).clone()), Some(code));
+	}
+
+	evm_test!{test_transact_suicide_refund: test_transact_suicide_refund_int}
+	fn test_transact_suicide_refund(factory: Factory) {
+		// ADDRESS SUICIDE
+		let code = "30ff".from_hex().unwrap();
+		let keypair = Random.generate().unwrap();
+		let contract = Address::from(0x10);
+		let t = Transaction {
+			action: Action::Call(contract),
+			value: U256::zero(),
+			data: vec![],
+			gas: U256::from(100_000),
+			gas_price: U256::zero(),
+			nonce: U256::zero()
+		}.sign(keypair.secret(), None);
+		let mut info = EnvInfo::default();
+		info.gas_limit = U256::from(100_000);
+
+		let gas_used = |refund: Option<usize>| {
+			let mut state = get_temp_state_with_factory(factory.clone());
+			state.init_code(&contract, code.clone()).unwrap();
+			let mut params = ::ethereum::new_byzantium_test().params().clone();
+			params.selfdestruct_refund = refund;
+			let machine = EthereumMachine::regular(params, Default::default());
+
+			let executed = {
+				let mut ex = Executive::new(&mut state, &info, &machine);
+				ex.transact(&t, TransactOptions::with_no_tracing()).unwrap()
+			};
+			assert!(executed.exception.is_none());
+			executed.gas_used
+		};
+
+		// 21000 + 2 + 5000, minus the refund capped at half
+		assert_eq!(gas_used(None), U256::from(13_001));
+		assert_eq!(gas_used(Some(1_000)), U256::from(25_002));
+		assert_eq!(gas_used(Some(0)), U256::from(26_002));
+	}
+
+	evm_test!{test_transact_suicide_to_new_account: test_transact_suicide_to_new_account_int}
+	fn test_transact_suicide_to_new_account(factory: Factory) {
+		let beneficiary = Address::from(0x20);
+		// PUSH20 beneficiary SUICIDE
+		let code = format!("73{:x}ff", beneficiary).from_hex().unwrap();
+		let keypair = Random.generate().unwrap();
+		let contract = Address::from(0x10);
+		let t = Transaction {
+			action: Action::Call(contract),
+			value: U256::zero(),
+			data: vec![],
+			gas: U256::from(100_000),
+			gas_price: U256::zero(),
+			nonce: U256::zero()
+		}.sign(keypair.secret(), None);
+		let mut info = EnvInfo::default();
+		info.gas_limit = U256::from(100_000);
+		let machine = make_byzantium_machine(0);
+
+		let gas_used = |beneficiary_exists: bool| {
+			let mut state = get_temp_state_with_factory(factory.clone());
+			state.add_balance(&contract, &U256::from(5), CleanupMode::NoEmpty).unwrap();
+			state.init_code(&contract, code.clone()).unwrap();
+			if beneficiary_exists {
+				state.add_balance(&beneficiary, &U256::from(1), CleanupMode::NoEmpty).unwrap();
+			}
+
+			let executed = {
+				let mut ex = Executive::new(&mut state, &info, &machine);
+				ex.transact(&t, TransactOptions::with_no_tracing()).unwrap()
+			};
+			assert!(executed.exception.is_none());
+			assert_eq!(state.balance(&beneficiary).unwrap(), U256::from(if beneficiary_exists { 6 } else { 5 }));
+			executed.gas_used
+		};
+
+		// 21000 + 3 + 5000 (+ 25000 for the new account), minus the refund of 24000 capped at half
+		assert_eq!(gas_used(true), U256::from(13_002));
+		assert_eq!(gas_used(false), U256::from(27_003));
+	}
+
+	evm_test!{test_transact_service_transaction: test_transact_service_transaction_int}
+	fn test_transact_service_transaction(factory: Factory) {
+		let certifier: Address = "0000000000000000000000000000000000000cef".into();
+		let mut params = ::ethereum::new_byzantium_test().params().clone();
+		params.service_transaction_certifier = Some(certifier);
+		params.service_transaction_certifier_transition = 10;
+		params.service_transaction_gas_price = 2.into();
+		let machine = EthereumMachine::regular(params, Default::default());
+		let author: Address = "00000000000000000000000000000000000000a0".into();
+
+		let keypair = Random.generate().unwrap();
+		let sign = |gas_price: u64| Transaction {
+			action: Action::Call(5.into()),
+			value: U256::zero(),
+			data: vec![],
+			gas: U256::from(100_000),
+			gas_price: gas_price.into(),
+			nonce: U256::zero(),
+		}.sign(keypair.secret(), None);
+
+		let mut info = EnvInfo::default();
+		info.number = 10;
+		info.author = author;
+		info.gas_limit = U256::from(100_000);
+
+		// returns 32 zero bytes, i.e. `false` for everyone.
+		let mut state = get_temp_state_with_factory(factory.clone());
+		state.init_code(&certifier, "60206000f3".from_hex().unwrap()).unwrap();
+		let res = Executive::new(&mut state, &info, &machine).transact(&sign(0), TransactOptions::with_no_tracing());
+		match res {
+			Err(ExecutionError::SenderNotCertified) => (),
+			_ => panic!("Expected SenderNotCertified error, got {:?}", res),
+		}
+
+		// senders are not checked before the transition
+		{
+			let mut info = info.clone();
+			info.number = 9;
+			let mut state = get_temp_state_with_factory(factory.clone());
+			state.init_code(&certifier, "60206000f3".from_hex().unwrap()).unwrap();
+			Executive::new(&mut state, &info, &machine).transact(&sign(0), TransactOptions::with_no_tracing()).unwrap();
+		}
+
+		// regular transactions are not affected
+		state.add_balance(&keypair.address(), &U256::from(100_000), CleanupMode::NoEmpty).unwrap();
+		Executive::new(&mut state, &info, &machine).transact(&sign(1), TransactOptions::with_no_tracing()).unwrap();
+
+		// returns `true` for everyone.
+		let mut state = get_temp_state_with_factory(factory);
+		state.init_code(&certifier, "600160005260206000f3".from_hex().unwrap()).unwrap();
+
+		// the author can't pay for the whole gas of the transaction
+		state.add_balance(&author, &U256::from(199_999), CleanupMode::NoEmpty).unwrap();
+		let res = Executive::new(&mut state, &info, &machine).transact(&sign(0), TransactOptions::with_no_tracing());
+		match res {
+			Err(ExecutionError::AuthorNotEnoughCash { required, got })
+				if required == U512::from(200_000) && got == U512::from(199_999) => (),
+			_ => panic!("Expected AuthorNotEnoughCash error, got {:?}", res),
+		}
+
+		state.add_balance(&author, &U256::from(1), CleanupMode::NoEmpty).unwrap();
+		let executed = Executive::new(&mut state, &info, &machine).transact(&sign(0), TransactOptions::with_no_tracing()).unwrap();
+		assert_eq!(executed.gas_used, U256::from(21_000));
+		assert_eq!(state.balance(&keypair.address()).unwrap(), U256::zero());
+		assert_eq!(state.nonce(&keypair.address()).unwrap(), U256::one());
+		// the author paid for the used gas at the service transaction gas price
+		assert_eq!(state.balance(&author).unwrap(), U256::from(200_000 - 2 * 21_000));
+	}
+
 	evm_test!{test_transact_invalid_nonce: test_transact_invalid_nonce_int}
 	fn test_transact_invalid_nonce(factory: Factory) {
 		let keypair = Random.generate().unwrap();
@@ -1486,6 +2245,336 @@ mod tests {
 		}
 	}
 
+	evm_test!{test_transact_gas_cap_exceeded: test_transact_gas_cap_exceeded_int}
+	fn test_transact_gas_cap_exceeded(factory: Factory) {
+		let keypair = Random.generate().unwrap();
+		let t = Transaction {
+			action: Action::Create,
+			value: U256::from(17),
+			data: "3331600055".from_hex().unwrap(),
+			gas: U256::from(80_001),
+			gas_price: U256::zero(),
+			nonce: U256::zero()
+		}.sign(keypair.secret(), None);
+		let sender = t.sender();
+
+		let mut state = get_temp_state_with_factory(factory);
+		state.add_balance(&sender, &U256::from(17), CleanupMode::NoEmpty).unwrap();
+		let mut info = EnvInfo::default();
+		info.gas_limit = U256::from(100_000);
+		let machine = make_frontier_machine(0);
+
+		let res = {
+			let mut ex = Executive::new(&mut state, &info, &machine);
+			let opts = TransactOptions::with_no_tracing().with_gas_cap(80_000.into());
+			ex.transact(&t, opts)
+		};
+
+		match res {
+			Err(ExecutionError::GasCapExceeded { cap, got })
+				if cap == U256::from(80_000) && got == U256::from(80_001) => (),
+			_ => assert!(false, "Expected gas cap error.")
+		}
+	}
+
+	evm_test!{test_transact_max_depth: test_transact_max_depth_int}
+	fn test_transact_max_depth(factory: Factory) {
+		// PUSH1 0 PUSH1 0 PUSH1 0 PUSH1 0 PUSH1 0 PUSH20 callee PUSH1 0 CALL PUSH1 0 SSTORE
+		let code = "600060006000600060007300000000000000000000000000000000000056786000f1600055".from_hex().unwrap();
+		let address = Address::from(0x1234);
+		let keypair = Random.generate().unwrap();
+		let t = Transaction {
+			action: Action::Call(address),
+			value: U256::zero(),
+			data: vec![],
+			gas: U256::from(100_000),
+			gas_price: U256::zero(),
+			nonce: U256::zero()
+		}.sign(keypair.secret(), None);
+		let mut info = EnvInfo::default();
+		info.gas_limit = U256::from(100_000);
+		let machine = make_frontier_machine(1024);
+
+		let call_result = |max_depth: Option<usize>| {
+			let mut state = get_temp_state_with_factory(factory.clone());
+			state.init_code(&address, code.clone()).unwrap();
+			{
+				let mut ex = Executive::new(&mut state, &info, &machine);
+				let opts = TransactOptions::with_no_tracing();
+				let opts = match max_depth {
+					Some(max_depth) => opts.with_max_depth(max_depth),
+					None => opts,
+				};
+				ex.transact(&t, opts).unwrap();
+			}
+			state.storage_at(&address, &H256::new()).unwrap()
+		};
+
+		// the nested call succeeds with the schedule's depth and fails once it's lowered to zero
+		assert_eq!(call_result(None), H256::from(1));
+		assert_eq!(call_result(Some(0)), H256::new());
+	}
+
+	evm_test!{test_transact_without_gas_charge: test_transact_without_gas_charge_int}
+	fn test_transact_without_gas_charge(factory: Factory) {
+		let keypair = Random.generate().unwrap();
+		let t = Transaction {
+			action: Action::Create,
+			value: U256::from(17),
+			data: "3331600055".from_hex().unwrap(),
+			gas: U256::from(100_000),
+			gas_price: U256::one(),
+			nonce: U256::one()
+		}.sign(keypair.secret(), None);
+		let sender = t.sender();
+
+		let mut state = get_temp_state_with_factory(factory);
+		state.add_balance(&sender, &U256::from(17), CleanupMode::NoEmpty).unwrap();
+		let mut info = EnvInfo::default();
+		info.gas_limit = U256::from(100_000);
+		let machine = make_frontier_machine(0);
+
+		let executed = {
+			let mut ex = Executive::new(&mut state, &info, &machine);
+			let opts = TransactOptions::with_no_tracing().dont_check_nonce().dont_charge_gas();
+			ex.transact(&t, opts).unwrap()
+		};
+
+		assert_eq!(executed.gas_used, U256::from(41_301));
+		assert_eq!(state.balance(&sender).unwrap(), U256::zero());
+		assert_eq!(state.balance(&info.author).unwrap(), U256::zero());
+		assert_eq!(state.nonce(&sender).unwrap(), U256::from(1));
+	}
+
+	evm_test!{test_transact_with_access_list: test_transact_with_access_list_int}
+	fn test_transact_with_access_list(factory: Factory) {
+		// PUSH1 1 SLOAD POP PUSH1 2 SLOAD POP STOP
+		let code = "600154506002545000".from_hex().unwrap();
+		let address = Address::from(0x1234);
+		let keypair = Random.generate().unwrap();
+		let t = Transaction {
+			action: Action::Call(address),
+			value: U256::zero(),
+			data: vec![],
+			gas: U256::from(100_000),
+			gas_price: U256::zero(),
+			nonce: U256::zero()
+		}.sign_with_access_list(keypair.secret(), 1, vec![(address, vec![H256::from(1)])]);
+
+		let mut state = get_temp_state_with_factory(factory);
+		state.init_code(&address, code).unwrap();
+		let mut info = EnvInfo::default();
+		info.gas_limit = U256::from(100_000);
+		let machine = make_frontier_machine(0);
+
+		let executed = {
+			let mut ex = Executive::new(&mut state, &info, &machine);
+			ex.transact(&t, TransactOptions::with_no_tracing()).unwrap()
+		};
+
+		// 21000 base + 2400 per address + 1900 per storage key + 110 execution
+		assert_eq!(executed.gas_used, U256::from(25_410));
+		assert_eq!(executed.access_counts.cold_addresses, 0);
+		assert_eq!(executed.access_counts.warm_storage_keys, 1);
+		assert_eq!(executed.access_counts.cold_storage_keys, 1);
+		assert_eq!(state.accessed(), None);
+	}
+
+	evm_test!{test_access_list_makes_accesses_warm: test_access_list_makes_accesses_warm_int}
+	fn test_access_list_makes_accesses_warm(factory: Factory) {
+		// PUSH20 0x5678 BALANCE POP STOP
+		let code = "730000000000000000000000000000000000005678315000".from_hex().unwrap();
+		let address = Address::from(0x1234);
+		let other = Address::from(0x5678);
+		let keypair = Random.generate().unwrap();
+
+		let mut params = ::ethereum::new_byzantium_test_machine().params().clone();
+		params.eip2930_transition = 0;
+		let machine = EthereumMachine::regular(params, Default::default());
+		let chain_id = machine.params().chain_id;
+		let mut info = EnvInfo::default();
+		info.gas_limit = U256::from(100_000);
+
+		let gas_used = |access_list: Option<Vec<(Address, Vec<H256>)>>| {
+			let t = Transaction {
+				action: Action::Call(address),
+				value: U256::zero(),
+				data: vec![],
+				gas: U256::from(100_000),
+				gas_price: U256::zero(),
+				nonce: U256::zero()
+			};
+			let t = match access_list {
+				Some(access_list) => t.sign_with_access_list(keypair.secret(), chain_id, access_list),
+				None => t.sign(keypair.secret(), Some(chain_id)),
+			};
+
+			let mut state = get_temp_state_with_factory(factory.clone());
+			state.init_code(&address, code.clone()).unwrap();
+			let mut ex = Executive::new(&mut state, &info, &machine);
+			ex.transact(&t, TransactOptions::with_no_tracing()).unwrap().gas_used
+		};
+
+		// 21000 base + 3 + 2600 cold account access + 2
+		assert_eq!(gas_used(None), U256::from(23_605));
+		// 21000 base + 2400 declared address + 3 + 100 warm account access + 2
+		assert_eq!(gas_used(Some(vec![(other, vec![])])), U256::from(23_505));
+	}
+
+	evm_test!{test_return_data_of_subcall: test_return_data_of_subcall_int}
+	fn test_return_data_of_subcall(factory: Factory) {
+		// PUSH1 0x2a PUSH1 0 MSTORE PUSH1 0x20 PUSH1 0 RETURN
+		let callee_code = "602a60005260206000f3".from_hex().unwrap();
+		let callee = Address::from(0xb);
+		// CALL callee without output buffer, then
+		// RETURNDATASIZE PUSH1 0 SSTORE
+		// PUSH1 0x20 PUSH1 0 PUSH1 0 RETURNDATACOPY PUSH1 0 MLOAD PUSH1 1 SSTORE
+		let caller_code = ("60006000600060006000".to_owned() + "73000000000000000000000000000000000000000b" + "5af150" +
+			"3d600055" + "6020600060003e600051600155").from_hex().unwrap();
+		let caller = Address::from(0xa);
+
+		let keypair = Random.generate().unwrap();
+		let t = Transaction {
+			action: Action::Call(caller),
+			value: U256::zero(),
+			data: vec![],
+			gas: U256::from(100_000),
+			gas_price: U256::zero(),
+			nonce: U256::zero()
+		}.sign(keypair.secret(), None);
+
+		let mut state = get_temp_state_with_factory(factory);
+		state.init_code(&callee, callee_code).unwrap();
+		state.init_code(&caller, caller_code).unwrap();
+		let mut info = EnvInfo::default();
+		info.gas_limit = U256::from(100_000);
+		let machine = make_byzantium_machine(1024);
+
+		let executed = {
+			let mut ex = Executive::new(&mut state, &info, &machine);
+			ex.transact(&t, TransactOptions::with_no_tracing()).unwrap()
+		};
+
+		assert_eq!(executed.exception, None);
+		assert_eq!(state.storage_at(&caller, &H256::from(0)).unwrap(), H256::from(0x20));
+		assert_eq!(state.storage_at(&caller, &H256::from(1)).unwrap(), H256::from(0x2a));
+	}
+
+	evm_test!{test_transact_with_state_diff: test_transact_with_state_diff_int}
+	fn test_transact_with_state_diff(factory: Factory) {
+		use types::account_diff::Diff;
+
+		// PUSH1 1 PUSH1 0 SSTORE
+		let code = "6001600055".from_hex().unwrap();
+		let address = Address::from(0x1234);
+		let keypair = Random.generate().unwrap();
+		let t = Transaction {
+			action: Action::Call(address),
+			value: U256::from(17),
+			data: vec![],
+			gas: U256::from(100_000),
+			gas_price: U256::zero(),
+			nonce: U256::zero()
+		}.sign(keypair.secret(), None);
+		let sender = t.sender();
+
+		let mut state = get_temp_state_with_factory(factory);
+		state.add_balance(&sender, &U256::from(100), CleanupMode::NoEmpty).unwrap();
+		state.init_code(&address, code).unwrap();
+		state.commit().unwrap();
+		let mut info = EnvInfo::default();
+		info.gas_limit = U256::from(100_000);
+		let machine = make_frontier_machine(0);
+
+		let executed = {
+			let mut ex = Executive::new(&mut state, &info, &machine);
+			ex.transact(&t, TransactOptions::with_no_tracing().with_state_diff()).unwrap()
+		};
+
+		let diff = executed.state_diff.unwrap();
+		let sender_diff = &diff.get()[&sender];
+		assert_eq!(sender_diff.balance, Diff::Changed(U256::from(100), U256::from(83)));
+		assert_eq!(sender_diff.nonce, Diff::Changed(U256::zero(), U256::one()));
+		let contract_diff = &diff.get()[&address];
+		assert_eq!(contract_diff.balance, Diff::Changed(U256::zero(), U256::from(17)));
+		assert_eq!(contract_diff.code, Diff::Same);
+		assert_eq!(contract_diff.storage[&H256::zero()], Diff::Changed(H256::zero(), H256::from(1)));
+
+		// the checkpoint taken for the diff has been discarded.
+		state.to_pod();
+	}
+
+	evm_test!{test_block_executor_tracks_gas_used: test_block_executor_tracks_gas_used_int}
+	fn test_block_executor_tracks_gas_used(factory: Factory) {
+		let keypair = Random.generate().unwrap();
+		let transfer = |nonce: u64| Transaction {
+			action: Action::Call(Address::from(0x1234)),
+			value: U256::from(1),
+			data: vec![],
+			gas: U256::from(21_000),
+			gas_price: U256::zero(),
+			nonce: U256::from(nonce),
+		}.sign(keypair.secret(), None);
+
+		let mut state = get_temp_state_with_factory(factory);
+		state.add_balance(&keypair.address(), &U256::from(100), CleanupMode::NoEmpty).unwrap();
+		let mut info = EnvInfo::default();
+		info.gas_limit = U256::from(50_000);
+		let machine = make_frontier_machine(0);
+		let mut executor = BlockExecutor::new(info);
+
+		executor.transact(&mut state, &machine, &transfer(0), TransactOptions::with_no_tracing()).unwrap();
+		assert_eq!(executor.gas_used(), U256::from(21_000));
+		let executed = executor.transact(&mut state, &machine, &transfer(1), TransactOptions::with_no_tracing()).unwrap();
+		assert_eq!(executed.cumulative_gas_used, U256::from(42_000));
+		assert_eq!(executor.gas_remaining(), U256::from(8_000));
+
+		match executor.transact(&mut state, &machine, &transfer(2), TransactOptions::with_no_tracing()) {
+			Err(ExecutionError::BlockGasLimitReached { gas_used, .. }) if gas_used == U256::from(42_000) => (),
+			_ => panic!("Expected block gas limit error."),
+		}
+		assert_eq!(executor.env_info().gas_used, U256::from(42_000));
+	}
+
+	evm_test!{test_transact_exceptions: test_transact_exceptions_int}
+	fn test_transact_exceptions(factory: Factory) {
+		let keypair = Random.generate().unwrap();
+		let mut info = EnvInfo::default();
+		info.gas_limit = U256::from(100_000);
+		let machine = make_frontier_machine(0);
+		let mut state = get_temp_state_with_factory(factory);
+
+		let cases = vec![
+			// JUMPDEST PUSH1 0 JUMP
+			("5b600056", vm::Error::OutOfGas),
+			// PUSH1 3 JUMP
+			("600356", vm::Error::BadJumpDestination { destination: 3 }),
+			// ADD
+			("01", vm::Error::StackUnderflow { instruction: "ADD", wanted: 2, on_stack: 0 }),
+		];
+
+		for (nonce, (code, exception)) in cases.into_iter().enumerate() {
+			let address = Address::from(0x1000 + nonce as u64);
+			state.init_code(&address, code.from_hex().unwrap()).unwrap();
+			let t = Transaction {
+				action: Action::Call(address),
+				value: U256::zero(),
+				data: vec![],
+				gas: U256::from(100_000),
+				gas_price: U256::zero(),
+				nonce: U256::from(nonce),
+			}.sign(keypair.secret(), None);
+
+			let executed = {
+				let mut ex = Executive::new(&mut state, &info, &machine);
+				ex.transact(&t, TransactOptions::with_no_tracing()).unwrap()
+			};
+
+			assert_eq!(executed.gas_used, U256::from(100_000));
+			assert_eq!(executed.exception, Some(exception));
+		}
+	}
+
 	evm_test!{test_not_enough_cash: test_not_enough_cash_int}
 	fn test_not_enough_cash(factory: Factory) {
 
@@ -77,6 +77,7 @@ pub struct Externalities<'a, T: 'a, V: 'a, B: 'a>
 	tracer: &'a mut T,
 	vm_tracer: &'a mut V,
 	static_flag: bool,
+	max_depth: Option<usize>,
 }
 
 impl<'a, T: 'a, V: 'a, B: 'a> Externalities<'a, T, V, B>
@@ -106,8 +107,18 @@ impl<'a, T: 'a, V: 'a, B: 'a> Externalities<'a, T, V, B>
 			tracer: tracer,
 			vm_tracer: vm_tracer,
 			static_flag: static_flag,
+			max_depth: None,
 		}
 	}
+
+	/// Lowers the maximal call depth of the schedule, if `max_depth` is given.
+	pub fn with_max_depth(mut self, max_depth: Option<usize>) -> Self {
+		if let Some(max_depth) = max_depth {
+			self.schedule.max_depth = cmp::min(self.schedule.max_depth, max_depth);
+		}
+		self.max_depth = max_depth;
+		self
+	}
 }
 
 impl<'a, T: 'a, V: 'a, B: 'a> Ext for Externalities<'a, T, V, B>
@@ -117,6 +128,10 @@ impl<'a, T: 'a, V: 'a, B: 'a> Ext for Externalities<'a, T, V, B>
 		self.state.storage_at(&self.origin_info.address, key).map_err(Into::into)
 	}
 
+	fn initial_storage_at(&self, key: &H256) -> vm::Result<H256> {
+		self.state.original_storage_at(&self.origin_info.address, key).map_err(Into::into)
+	}
+
 	fn set_storage(&mut self, key: H256, value: H256) -> vm::Result<()> {
 		if self.static_flag {
 			Err(vm::Error::MutableCallInStaticContext)
@@ -228,7 +243,8 @@ impl<'a, T: 'a, V: 'a, B: 'a> Ext for Externalities<'a, T, V, B>
 				}
 			}
 		}
-		let mut ex = Executive::from_parent(self.state, self.env_info, self.machine, self.depth, self.static_flag);
+		let mut ex = Executive::from_parent(self.state, self.env_info, self.machine, self.depth, self.static_flag)
+			.with_max_depth(self.max_depth);
 
 		// TODO: handle internal error separately
 		match ex.create(params, self.substate, &mut None, self.tracer, self.vm_tracer) {
@@ -282,7 +298,8 @@ impl<'a, T: 'a, V: 'a, B: 'a> Ext for Externalities<'a, T, V, B>
 			params.value = ActionValue::Transfer(value);
 		}
 
-		let mut ex = Executive::from_parent(self.state, self.env_info, self.machine, self.depth, self.static_flag);
+		let mut ex = Executive::from_parent(self.state, self.env_info, self.machine, self.depth, self.static_flag)
+			.with_max_depth(self.max_depth);
 
 		match ex.call(params, self.substate, BytesRef::Fixed(output), self.tracer, self.vm_tracer) {
 			Ok(FinalizationResult{ gas_left, return_data, apply_state: true }) => MessageCallResult::Success(gas_left, return_data),
@@ -362,8 +379,14 @@ impl<'a, T: 'a, V: 'a, B: 'a> Ext for Externalities<'a, T, V, B>
 		let address = self.origin_info.address.clone();
 		let balance = self.balance(&address)?;
 		if &address == refund_address {
-			// TODO [todr] To be consistent with CPP client we set balance to 0 in that case.
-			self.state.sub_balance(&address, &balance, &mut CleanupMode::NoEmpty)?;
+			// Self-destructing to itself does not move the value; depending on the schedule
+			// it is burnt or kept once the account is removed when the transaction is finalized.
+			if self.schedule.suicide_burns_balance {
+				trace!(target: "ext", "Suiciding {} to itself (burnt: {})", address, balance);
+				self.state.sub_balance(&address, &balance, &mut CleanupMode::NoEmpty)?;
+			} else {
+				trace!(target: "ext", "Suiciding {} to itself (kept: {})", address, balance);
+			}
 		} else {
 			trace!(target: "ext", "Suiciding {} -> {} (xfer: {})", address, refund_address, balance);
 			self.state.transfer_balance(
@@ -392,16 +415,36 @@ impl<'a, T: 'a, V: 'a, B: 'a> Ext for Externalities<'a, T, V, B>
 		self.depth
 	}
 
-	fn inc_sstore_clears(&mut self) {
-		self.substate.sstore_clears_count = self.substate.sstore_clears_count + U256::one();
+	fn add_sstore_refund(&mut self, value: usize) {
+		self.substate.sstore_clears_refund += value as i128;
+	}
+
+	fn sub_sstore_refund(&mut self, value: usize) {
+		self.substate.sstore_clears_refund -= value as i128;
+	}
+
+	fn is_warm_address(&self, address: &Address) -> bool {
+		self.substate.warm_addresses.contains(address)
+	}
+
+	fn warm_address(&mut self, address: &Address) {
+		self.substate.warm_addresses.insert(*address);
+	}
+
+	fn is_warm_storage_key(&self, key: &H256) -> bool {
+		self.substate.warm_storage_keys.contains(&(self.origin_info.address, *key))
+	}
+
+	fn warm_storage_key(&mut self, key: &H256) {
+		self.substate.warm_storage_keys.insert((self.origin_info.address, *key));
 	}
 
 	fn trace_next_instruction(&mut self, pc: usize, instruction: u8, current_gas: U256) -> bool {
 		self.vm_tracer.trace_next_instruction(pc, instruction, current_gas)
 	}
 
-	fn trace_prepare_execute(&mut self, pc: usize, instruction: u8, gas_cost: U256) {
-		self.vm_tracer.trace_prepare_execute(pc, instruction, gas_cost)
+	fn trace_prepare_execute(&mut self, pc: usize, instruction: u8, gas_cost: U256, dynamic_gas: U256) {
+		self.vm_tracer.trace_prepare_execute(pc, instruction, gas_cost, dynamic_gas)
 	}
 
 	fn trace_executed(&mut self, gas_used: U256, stack_push: &[U256], mem_diff: Option<(usize, &[u8])>, store_diff: Option<(U256, U256)>) {
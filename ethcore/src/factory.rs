@@ -17,6 +17,7 @@
 use trie::TrieFactory;
 use account_db::Factory as AccountFactory;
 use evm::{Factory as EvmFactory, VMType};
+use evm::interpreter::SharedCache;
 use vm::{Vm, ActionParams, Schedule};
 use wasm::WasmInterpreter;
 
@@ -29,6 +30,10 @@ pub struct VmFactory {
 }
 
 impl VmFactory {
+	/// Create a VM for given action.
+	///
+	/// Code starting with the wasm magic number runs on the metered WASM interpreter once
+	/// wasm is activated in the schedule (see `wasmActivationTransition`); anything else runs on the EVM.
 	pub fn create(&self, params: &ActionParams, schedule: &Schedule) -> Box<Vm> {
 		if schedule.wasm.is_some() && params.code.as_ref().map_or(false, |code| code.len() > 4 && &code[0..4] == WASM_MAGIC_NUMBER) {
 			Box::new(WasmInterpreter)
@@ -40,6 +45,16 @@ impl VmFactory {
 	pub fn new(evm: VMType, cache_size: usize) -> Self {
 		VmFactory { evm: EvmFactory::new(evm, cache_size) }
 	}
+
+	/// Jump destinations cache shared by created EVM interpreters.
+	pub fn evm_cache(&self) -> &SharedCache {
+		self.evm.cache()
+	}
+
+	/// Limit memory, in bytes, a single EVM call frame may expand to.
+	pub fn with_memory_limit(self, limit: usize) -> Self {
+		VmFactory { evm: self.evm.with_memory_limit(limit) }
+	}
 }
 
 impl From<EvmFactory> for VmFactory {
@@ -0,0 +1,176 @@
+// Copyright 2015-2018 Parity Technologies (UK) Ltd.
+// This file is part of Parity.
+
+// Parity is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// Parity is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with Parity.  If not, see <http://www.gnu.org/licenses/>.
+
+//! Runtime parameters read from an on-chain governance contract.
+
+use ethereum_types::{H256, U256, Address};
+use lru_cache::LruCache;
+use parking_lot::Mutex;
+
+use client::{BlockId, CallContract};
+use header::Header;
+use spec::CommonParams;
+
+use_contract!(governance, "Governance", "res/contracts/governance.json");
+
+const MAX_CACHE_SIZE: usize = 128;
+
+/// Parameters read from the governance contract at a block.
+///
+/// Every parameter is `None` when it could not be read, in which case the locally
+/// configured value should be used.
+#[derive(Debug, Default, Clone, PartialEq)]
+pub struct GovernanceParams {
+	/// Target gas limit of authored blocks.
+	pub gas_limit_target: Option<U256>,
+	/// Minimal gas price of transactions accepted to the queue.
+	pub min_gas_price: Option<U256>,
+	/// Validators of the chain.
+	pub validators: Option<Vec<Address>>,
+}
+
+/// Governance contract reader caching parameters per block.
+pub struct Governance {
+	contract: governance::Governance,
+	contract_address: Address,
+	cache: Mutex<LruCache<H256, GovernanceParams>>,
+}
+
+impl Governance {
+	/// Create a new instance if address is specified in params.
+	pub fn from_params(params: &CommonParams) -> Option<Governance> {
+		params.governance_contract.map(|address|
+			Governance {
+				contract: governance::Governance::default(),
+				contract_address: address,
+				cache: Mutex::new(LruCache::new(MAX_CACHE_SIZE)),
+			}
+		)
+	}
+
+	/// Read parameters at the state of given block.
+	///
+	/// Parameters differing from the ones at the parent block are logged, once per block.
+	pub fn params_at(&self, header: &Header, client: &CallContract) -> GovernanceParams {
+		let block_hash = header.hash();
+		if let Some(params) = self.cache.lock().get_mut(&block_hash) {
+			return params.clone();
+		}
+
+		let params = self.read(&block_hash, client);
+		let parent_params = self.cache.lock().get_mut(header.parent_hash()).cloned();
+		let parent_params = parent_params.unwrap_or_else(|| self.read(header.parent_hash(), client));
+		note_changes(header, &parent_params, &params);
+
+		let mut cache = self.cache.lock();
+		cache.insert(*header.parent_hash(), parent_params);
+		cache.insert(block_hash, params.clone());
+		params
+	}
+
+	fn read(&self, block_hash: &H256, client: &CallContract) -> GovernanceParams {
+		let contract_address = self.contract_address;
+		let call = |data| client.call_contract(BlockId::Hash(*block_hash), contract_address, data);
+		let functions = self.contract.functions();
+
+		let params = GovernanceParams {
+			gas_limit_target: functions.gas_limit_target().call(&call)
+				.map_err(|e| debug!(target: "governance", "Error reading gas limit target: {:?}", e))
+				.ok(),
+			min_gas_price: functions.min_gas_price().call(&call)
+				.map_err(|e| debug!(target: "governance", "Error reading minimal gas price: {:?}", e))
+				.ok(),
+			validators: functions.get_validators().call(&call)
+				.map_err(|e| debug!(target: "governance", "Error reading validators: {:?}", e))
+				.ok(),
+		};
+
+		params
+	}
+}
+
+fn note_changes(header: &Header, parent: &GovernanceParams, params: &GovernanceParams) {
+	if params.gas_limit_target != parent.gas_limit_target {
+		info!(target: "governance", "Gas limit target changed to {:?} at block #{} ({})", params.gas_limit_target, header.number(), header.hash());
+	}
+	if params.min_gas_price != parent.min_gas_price {
+		info!(target: "governance", "Minimal gas price changed to {:?} at block #{} ({})", params.min_gas_price, header.number(), header.hash());
+	}
+	if params.validators != parent.validators {
+		info!(target: "governance", "Validators changed to {:?} at block #{} ({})", params.validators, header.number(), header.hash());
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use ethabi::{self, Token};
+	use ethereum_types::Address;
+	use hash::keccak;
+	use client::{BlockId, CallContract};
+	use header::Header;
+	use spec::CommonParams;
+	use super::{Governance, GovernanceParams};
+
+	struct FailingClient;
+
+	impl CallContract for FailingClient {
+		fn call_contract(&self, _id: BlockId, _address: Address, _data: Vec<u8>) -> Result<Vec<u8>, String> {
+			Err("no state".into())
+		}
+	}
+
+	// only knows the validators.
+	struct ValidatorsClient(Vec<Address>);
+
+	impl CallContract for ValidatorsClient {
+		fn call_contract(&self, _id: BlockId, _address: Address, data: Vec<u8>) -> Result<Vec<u8>, String> {
+			if data[..4] != keccak("getValidators()")[..4] {
+				return Err("not implemented".into());
+			}
+			Ok(ethabi::encode(&[Token::Array(self.0.iter().map(|a| Token::Address(*a)).collect())]))
+		}
+	}
+
+	#[test]
+	fn only_created_with_contract() {
+		let mut params = CommonParams::default();
+		assert!(Governance::from_params(&params).is_none());
+		params.governance_contract = Some(Address::from(0x42));
+		assert!(Governance::from_params(&params).is_some());
+	}
+
+	#[test]
+	fn falls_back_when_reads_fail() {
+		let mut params = CommonParams::default();
+		params.governance_contract = Some(Address::from(0x42));
+		let governance = Governance::from_params(&params).unwrap();
+
+		assert_eq!(governance.params_at(&Header::default(), &FailingClient), GovernanceParams::default());
+	}
+
+	#[test]
+	fn reads_validators() {
+		let mut params = CommonParams::default();
+		params.governance_contract = Some(Address::from(0x42));
+		let governance = Governance::from_params(&params).unwrap();
+		let validators = vec![Address::from(1), Address::from(2)];
+
+		assert_eq!(governance.params_at(&Header::default(), &ValidatorsClient(validators.clone())), GovernanceParams {
+			validators: Some(validators),
+			..Default::default()
+		});
+	}
+}
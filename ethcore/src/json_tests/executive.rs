@@ -34,6 +34,7 @@ use trie;
 use rlp::RlpStream;
 use hash::keccak;
 use machine::EthereumMachine as Machine;
+use pod_state::PodState;
 
 #[derive(Debug, PartialEq, Clone)]
 struct CallCreate {
@@ -98,6 +99,10 @@ impl<'a, T: 'a, V: 'a, B: 'a> Ext for TestExt<'a, T, V, B>
 		self.ext.storage_at(key)
 	}
 
+	fn initial_storage_at(&self, key: &H256) -> vm::Result<H256> {
+		self.ext.initial_storage_at(key)
+	}
+
 	fn set_storage(&mut self, key: H256, value: H256) -> vm::Result<()> {
 		self.ext.set_storage(key, value)
 	}
@@ -188,8 +193,28 @@ impl<'a, T: 'a, V: 'a, B: 'a> Ext for TestExt<'a, T, V, B>
 		false
 	}
 
-	fn inc_sstore_clears(&mut self) {
-		self.ext.inc_sstore_clears()
+	fn add_sstore_refund(&mut self, value: usize) {
+		self.ext.add_sstore_refund(value)
+	}
+
+	fn sub_sstore_refund(&mut self, value: usize) {
+		self.ext.sub_sstore_refund(value)
+	}
+
+	fn is_warm_address(&self, address: &Address) -> bool {
+		self.ext.is_warm_address(address)
+	}
+
+	fn warm_address(&mut self, address: &Address) {
+		self.ext.warm_address(address)
+	}
+
+	fn is_warm_storage_key(&self, key: &H256) -> bool {
+		self.ext.is_warm_storage_key(key)
+	}
+
+	fn warm_storage_key(&mut self, key: &H256) {
+		self.ext.warm_storage_key(key)
 	}
 }
 
@@ -229,7 +254,8 @@ fn do_json_test_for(vm_type: &VMType, json_data: &[u8]) -> Vec<String> {
 
 		let out_of_gas = vm.out_of_gas();
 		let mut state = get_temp_state();
-		state.populate_from(From::from(vm.pre_state.clone()));
+		let pre_state: PodState = vm.pre_state.clone().into();
+		state.populate_from(pre_state).expect("populating the pre state failed");
 		let info = From::from(vm.env);
 		let machine = {
 			let mut machine = ::ethereum::new_frontier_test_machine();
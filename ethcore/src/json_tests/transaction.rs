@@ -44,7 +44,7 @@ fn do_json_test(json_data: &[u8]) -> Vec<String> {
 			.as_val()
 			.map_err(::error::Error::from)
 			.and_then(|t: UnverifiedTransaction| {
-				t.validate(schedule, schedule.have_delegate_call, allow_chain_id_of_one, allow_unsigned).map_err(Into::into)
+				t.validate(schedule, schedule.require_low_s, allow_chain_id_of_one, allow_unsigned).map_err(Into::into)
 			});
 
 		fail_unless(test.transaction.is_none() == res.is_err(), "Validity different");
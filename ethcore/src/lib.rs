@@ -75,6 +75,8 @@ extern crate ethcore_transaction as transaction;
 extern crate ethereum_types;
 extern crate ethjson;
 extern crate ethkey;
+extern crate fetch;
+extern crate futures;
 extern crate hardware_wallet;
 extern crate hashdb;
 extern crate itertools;
@@ -149,6 +151,7 @@ pub mod error;
 pub mod ethereum;
 pub mod executed;
 pub mod executive;
+pub mod governance;
 pub mod header;
 pub mod machine;
 pub mod miner;
@@ -157,6 +160,7 @@ pub mod snapshot;
 pub mod spec;
 pub mod state;
 pub mod state_db;
+pub mod state_healing;
 // Test helpers made public for usage outside ethcore
 pub mod test_helpers;
 pub mod trace;
@@ -171,6 +175,7 @@ mod externalities;
 mod blockchain;
 mod factory;
 mod tx_filter;
+pub mod tx_certifier;
 
 #[cfg(test)]
 mod tests;
@@ -27,10 +27,12 @@ use error::Error;
 use executive::Executive;
 use header::{BlockNumber, Header, ExtendedHeader};
 use spec::CommonParams;
-use state::{CleanupMode, Substate};
+use state::{Backend as StateBackend, CleanupMode, State, Substate};
 use trace::{NoopTracer, NoopVMTracer, Tracer, ExecutiveTracer, RewardType, Tracing};
 use transaction::{self, SYSTEM_ADDRESS, UnverifiedTransaction, SignedTransaction};
+use tx_certifier::SenderCertifier;
 use tx_filter::TransactionFilter;
+use governance::{Governance, GovernanceParams};
 
 use ethereum_types::{U256, Address};
 use bytes::BytesRef;
@@ -38,9 +40,17 @@ use rlp::Rlp;
 use vm::{CallType, ActionParams, ActionValue, ParamsType};
 use vm::{EnvInfo, Schedule, CreateContractAddress};
 
+use_contract!(service_transaction, "ServiceTransaction", "res/contracts/service_transaction.json");
+
 /// Parity tries to round block.gas_limit to multiple of this constant
 pub const PARITY_GAS_LIMIT_DETERMINANT: U256 = U256([37, 0, 0, 0]);
 
+/// Gas available to the service transaction certifier when checking a sender.
+///
+/// This is a consensus parameter: the check runs while blocks are executed, a certifier
+/// running out of it rejects the transaction.
+const SERVICE_TRANSACTION_CHECK_GAS: u64 = 50_000;
+
 /// Ethash-specific extensions.
 #[derive(Debug, Clone)]
 pub struct EthashExtensions {
@@ -73,6 +83,8 @@ pub struct EthereumMachine {
 	params: CommonParams,
 	builtins: Arc<BTreeMap<Address, Builtin>>,
 	tx_filter: Option<Arc<TransactionFilter>>,
+	tx_certifier: Option<Arc<SenderCertifier>>,
+	governance: Option<Arc<Governance>>,
 	ethash_extensions: Option<EthashExtensions>,
 	schedule_rules: Option<Box<ScheduleCreationRules>>,
 }
@@ -81,10 +93,14 @@ impl EthereumMachine {
 	/// Regular ethereum machine.
 	pub fn regular(params: CommonParams, builtins: BTreeMap<Address, Builtin>) -> EthereumMachine {
 		let tx_filter = TransactionFilter::from_params(&params).map(Arc::new);
+		let tx_certifier = SenderCertifier::from_params(&params).map(Arc::new);
+		let governance = Governance::from_params(&params).map(Arc::new);
 		EthereumMachine {
 			params: params,
 			builtins: Arc::new(builtins),
 			tx_filter: tx_filter,
+			tx_certifier: tx_certifier,
+			governance: governance,
 			ethash_extensions: None,
 			schedule_rules: None,
 		}
@@ -107,6 +123,11 @@ impl EthereumMachine {
 	pub fn ethash_extensions(&self) -> Option<&EthashExtensions> {
 		self.ethash_extensions.as_ref()
 	}
+
+	/// Get a reference to the sender certificate registry, if certificates are required.
+	pub fn sender_certifier(&self) -> Option<&SenderCertifier> {
+		self.tx_certifier.as_ref().map(|c| &**c)
+	}
 }
 
 impl EthereumMachine {
@@ -155,6 +176,74 @@ impl EthereumMachine {
 		Ok(output)
 	}
 
+	/// Execute a static call as the system address against given state. Any changes
+	/// made by the call are reverted.
+	fn call_as_system<B: StateBackend>(
+		&self,
+		state: &mut State<B>,
+		env_info: &EnvInfo,
+		contract_address: Address,
+		gas: U256,
+		data: Vec<u8>,
+	) -> Result<Vec<u8>, Error> {
+		let params = ActionParams {
+			code_address: contract_address.clone(),
+			address: contract_address.clone(),
+			sender: SYSTEM_ADDRESS.clone(),
+			origin: SYSTEM_ADDRESS.clone(),
+			gas: gas,
+			gas_price: 0.into(),
+			value: ActionValue::Apparent(0.into()),
+			code: state.code(&contract_address)?,
+			code_hash: Some(state.code_hash(&contract_address)?),
+			data: Some(data),
+			call_type: CallType::StaticCall,
+			params_type: ParamsType::Separate,
+		};
+
+		state.checkpoint();
+		let mut output = Vec::new();
+		let result = {
+			let mut ex = Executive::new(state, env_info, self);
+			let mut substate = Substate::new();
+			ex.call(params, &mut substate, BytesRef::Flexible(&mut output), &mut NoopTracer, &mut NoopVMTracer)
+		};
+		state.revert_to_checkpoint();
+
+		match result {
+			Ok(ref res) if res.apply_state => Ok(output),
+			Ok(_) => Err(::engines::EngineError::FailedSystemCall("reverted".into()).into()),
+			Err(e) => Err(::engines::EngineError::FailedSystemCall(format!("{}", e)).into()),
+		}
+	}
+
+	/// Checks whether the transaction is a service transaction, i.e. it has zero gas price
+	/// and its sender is certified by the chain's service transaction certifier contract.
+	/// Always `false` on chains without a certifier.
+	///
+	/// The block author pays for the gas of service transactions at `service_transaction_gas_price`.
+	pub fn is_service_transaction<B: StateBackend>(
+		&self,
+		state: &mut State<B>,
+		env_info: &EnvInfo,
+		t: &SignedTransaction,
+	) -> Result<bool, Error> {
+		let params = self.params();
+		let certifier = match params.service_transaction_certifier {
+			Some(certifier) if env_info.number >= params.service_transaction_certifier_transition => certifier,
+			_ => return Ok(false),
+		};
+		if !t.gas_price.is_zero() || t.is_unsigned() {
+			return Ok(false);
+		}
+
+		let contract = service_transaction::ServiceTransaction::default();
+		let certified = contract.functions().certified();
+		let output = self.call_as_system(state, env_info, certifier, SERVICE_TRANSACTION_CHECK_GAS.into(), certified.input(t.sender()))?;
+
+		Ok(certified.output(&output).unwrap_or(false))
+	}
+
 	/// Push last known block hash to the state.
 	fn push_last_hash(&self, block: &mut ExecutedBlock) -> Result<(), Error> {
 		let params = self.params();
@@ -200,6 +289,8 @@ impl EthereumMachine {
 		header.set_difficulty(parent.difficulty().clone());
 		let gas_limit = parent.gas_limit().clone();
 		assert!(!gas_limit.is_zero(), "Gas limit should be > 0");
+		// the floor target wins if the targets were set inconsistently.
+		let gas_ceil_target = cmp::max(gas_floor_target, gas_ceil_target);
 
 		if let Some(ref ethash_params) = self.ethash_extensions {
 			let gas_limit = {
@@ -308,6 +399,27 @@ impl EthereumMachine {
 		}
 	}
 
+	/// Checks that the transaction is signed for this chain and carries replay protection
+	/// if the chain requires it at given block.
+	pub fn verify_replay_protection(&self, t: &UnverifiedTransaction, number: BlockNumber) -> Result<(), transaction::Error> {
+		if t.is_unsigned() {
+			return Ok(());
+		}
+
+		let params = self.params();
+		match t.chain_id() {
+			None if number >= params.replay_protection_transition => Err(transaction::Error::ReplayProtectionRequired),
+			None => Ok(()),
+			// chain ID was not validated before this block.
+			Some(_) if number < params.validate_chain_id_transition => Ok(()),
+			Some(got) if number >= params.eip155_transition && got == params.chain_id => Ok(()),
+			Some(got) => Err(transaction::Error::InvalidChainId {
+				expected: if number >= params.eip155_transition { Some(params.chain_id) } else { None },
+				got,
+			}),
+		}
+	}
+
 	/// Returns new contract address generation scheme at given block number.
 	pub fn create_address_scheme(&self, number: BlockNumber) -> CreateContractAddress {
 		if number >= self.params().eip86_transition {
@@ -324,19 +436,15 @@ impl EthereumMachine {
 
 	/// Does basic verification of the transaction.
 	pub fn verify_transaction_basic(&self, t: &UnverifiedTransaction, header: &Header) -> Result<(), transaction::Error> {
-		let check_low_s = match self.ethash_extensions {
-			Some(ref ext) => header.number() >= ext.homestead_transition,
-			None => true,
-		};
+		let schedule = self.schedule(header.number());
 
-		let chain_id = if header.number() < self.params().validate_chain_id_transition {
-			t.chain_id()
-		} else if header.number() >= self.params().eip155_transition {
-			Some(self.params().chain_id)
-		} else {
-			None
-		};
-		t.verify_basic(check_low_s, chain_id, false)?;
+		self.verify_replay_protection(t, header.number())?;
+		// chain ID has been checked above.
+		t.verify_basic(schedule.require_low_s, t.chain_id(), false)?;
+
+		if t.access_list().is_some() && !schedule.have_access_lists {
+			return Err(transaction::Error::TransactionTypeNotEnabled);
+		}
 
 		Ok(())
 	}
@@ -354,6 +462,27 @@ impl EthereumMachine {
 		Ok(())
 	}
 
+	/// Checks that the sender holds a certificate of the configured authority.
+	///
+	/// Certificates are held by each node separately, so this is a local policy: it's used
+	/// when admitting transactions to the queue and when authoring blocks, but never
+	/// while importing blocks.
+	pub fn verify_transaction_certificate(&self, t: &SignedTransaction) -> Result<(), transaction::Error> {
+		if let Some(ref certifier) = self.tx_certifier.as_ref() {
+			if !t.is_unsigned() && !certifier.is_certified(&t.sender()) {
+				return Err(transaction::Error::SenderNotCertified)
+			}
+		}
+
+		Ok(())
+	}
+
+	/// Runtime parameters read from the governance contract at the state of given block.
+	/// `None` if the chain has no governance contract.
+	pub fn governance_params(&self, header: &Header, client: &CallContract) -> Option<GovernanceParams> {
+		self.governance.as_ref().map(|governance| governance.params_at(header, client))
+	}
+
 	/// Additional params.
 	pub fn additional_params(&self) -> HashMap<String, String> {
 		hash_map![
@@ -536,5 +665,48 @@ mod tests {
 		parent.set_gas_limit(U256::from(150_000));
 		machine.populate_from_parent(&mut header, &parent, U256::from(150_000), U256::from(150_002));
 		assert_eq!(*header.gas_limit(), U256::from(150_002));
+
+		// when gas_ceil_target < gas_floor_target the floor is the target
+		parent.set_gas_limit(U256::from(250_000));
+		machine.populate_from_parent(&mut header, &parent, U256::from(200_000), U256::from(100_000));
+		assert_eq!(*header.gas_limit(), U256::from(249_787));
+		parent.set_gas_limit(U256::from(200_000));
+		machine.populate_from_parent(&mut header, &parent, U256::from(200_000), U256::from(100_000));
+		assert_eq!(*header.gas_limit(), U256::from(200_000));
+	}
+
+	#[test]
+	fn replay_protection_becomes_mandatory() {
+		use ethkey::{Generator, Random};
+		use transaction::{Action, Transaction};
+
+		let spec = ::ethereum::new_byzantium_test();
+		let mut params = spec.params().clone();
+		params.replay_protection_transition = 10;
+		let chain_id = params.chain_id;
+		let machine = EthereumMachine::regular(params, Default::default());
+
+		let keypair = Random.generate().unwrap();
+		let sign = |chain_id| Transaction {
+			action: Action::Create,
+			value: 0.into(),
+			data: vec![],
+			gas: 100_000.into(),
+			gas_price: 0.into(),
+			nonce: 0.into(),
+		}.sign(keypair.secret(), chain_id);
+
+		let legacy = sign(None);
+		assert_eq!(machine.verify_replay_protection(&legacy, 9), Ok(()));
+		assert_eq!(machine.verify_replay_protection(&legacy, 10), Err(transaction::Error::ReplayProtectionRequired));
+
+		let protected = sign(Some(chain_id));
+		assert_eq!(machine.verify_replay_protection(&protected, 10), Ok(()));
+
+		let other_chain = sign(Some(chain_id + 1));
+		assert_eq!(
+			machine.verify_replay_protection(&other_chain, 10),
+			Err(transaction::Error::InvalidChainId { expected: Some(chain_id), got: chain_id + 1 })
+		);
 	}
 }
@@ -14,6 +14,7 @@
 // You should have received a copy of the GNU General Public License
 // along with Parity.  If not, see <http://www.gnu.org/licenses/>.
 
+use std::cmp;
 use std::time::{Instant, Duration};
 use std::collections::{BTreeMap, HashSet, HashMap};
 use std::sync::Arc;
@@ -37,19 +38,21 @@ use transaction::{
 };
 use using_queue::{UsingQueue, GetAction};
 
-use account_provider::{AccountProvider, SignError as AccountError};
+use account_provider::{AccountProvider, KeyOperation, SignError as AccountError};
 use block::{ClosedBlock, IsBlock, Block, SealedBlock};
 use client::{
 	BlockChain, ChainInfo, CallContract, BlockProducer, SealedBlockImporter, Nonce
 };
 use client::BlockId;
-use executive::contract_address;
+use executive::{contract_address, intrinsic_gas};
+use governance::GovernanceParams;
 use header::{Header, BlockNumber};
 use miner;
 use miner::pool_client::{PoolClient, CachedNonceClient};
 use receipt::{Receipt, RichReceipt};
 use spec::Spec;
 use state::State;
+use vm::Schedule;
 
 /// Different possible definitions for pending transaction set.
 #[derive(Debug, PartialEq)]
@@ -124,6 +127,12 @@ pub struct MinerOptions {
 
 	/// Strategy to use for prioritizing transactions in the queue.
 	pub tx_queue_strategy: PrioritizationStrategy,
+	/// Gas price bump required to replace a queued transaction, in thousandths of its gas price.
+	pub tx_queue_gas_price_bump: u32,
+	/// Time after which non-local transactions are culled from the queue.
+	pub tx_queue_max_age: Option<Duration>,
+	/// Minimal gas price of non-local transactions propagated to peers.
+	pub tx_propagation_min_gas_price: Option<U256>,
 	/// Simple senders penalization.
 	pub tx_queue_penalization: Penalization,
 	/// Do we refuse to accept service transactions even if sender is certified.
@@ -148,6 +157,9 @@ impl Default for MinerOptions {
 			enable_resubmission: true,
 			infinite_pending_block: false,
 			tx_queue_strategy: PrioritizationStrategy::GasPriceOnly,
+			tx_queue_gas_price_bump: pool::DEFAULT_GAS_PRICE_BUMP,
+			tx_queue_max_age: None,
+			tx_propagation_min_gas_price: None,
 			tx_queue_penalization: Penalization::Disabled,
 			refuse_service_transactions: false,
 			pool_limits: pool::Options {
@@ -200,9 +212,11 @@ pub struct Miner {
 	listeners: RwLock<Vec<Box<NotifyWork>>>,
 	nonce_cache: RwLock<HashMap<Address, U256>>,
 	gas_pricer: Mutex<GasPricer>,
+	governance_params: RwLock<GovernanceParams>,
 	options: MinerOptions,
 	// TODO [ToDr] Arc is only required because of price updater
 	transaction_queue: Arc<TransactionQueue>,
+	propagation_filter: RwLock<Option<Box<pool::ShouldPropagate>>>,
 	engine: Arc<EthEngine>,
 	accounts: Option<Arc<AccountProvider>>,
 }
@@ -219,11 +233,37 @@ impl Miner {
 		self.transaction_queue.add_listener(f);
 	}
 
+	/// Set the filter of transactions propagated to peers, replacing the current one.
+	///
+	/// Filtered transactions are still accepted to the queue and included in blocks.
+	pub fn set_propagation_filter(&self, filter: Box<pool::ShouldPropagate>) {
+		*self.propagation_filter.write() = Some(filter);
+	}
+
+	/// Whether the transaction passes the propagation filter.
+	pub fn should_propagate(&self, tx: &VerifiedTransaction) -> bool {
+		self.propagation_filter.read().as_ref().map_or(true, |filter| filter.should_propagate(tx))
+	}
+
+	/// Set a callback to be notified about hashes of transactions evicted from the queue.
+	pub fn add_dropped_transactions_listener(&self, f: Box<Fn(&[H256]) + Send + Sync>) {
+		self.transaction_queue.add_dropped_listener(f);
+	}
+
+	/// Set a callback to be notified about hashes of transactions removed from the queue without being mined,
+	/// because they expired, were replaced or another transaction with the same nonce was mined.
+	pub fn add_removed_transactions_listener(&self, f: Box<Fn(&[(H256, pool::RemovalReason)]) + Send + Sync>) {
+		self.transaction_queue.add_removed_listener(f);
+	}
+
 	/// Creates new instance of miner Arc.
 	pub fn new(options: MinerOptions, gas_pricer: GasPricer, spec: &Spec, accounts: Option<Arc<AccountProvider>>) -> Self {
 		let limits = options.pool_limits.clone();
 		let verifier_options = options.pool_verification_options.clone();
 		let tx_queue_strategy = options.tx_queue_strategy;
+		let tx_queue_gas_price_bump = options.tx_queue_gas_price_bump;
+		let propagation_filter = options.tx_propagation_min_gas_price
+			.map(|gas_price| Box::new(pool::GasPriceFloor(gas_price)) as Box<pool::ShouldPropagate>);
 
 		Miner {
 			sealing: Mutex::new(SealingWork {
@@ -237,9 +277,11 @@ impl Miner {
 			params: RwLock::new(AuthoringParams::default()),
 			listeners: RwLock::new(vec![]),
 			gas_pricer: Mutex::new(gas_pricer),
+			governance_params: RwLock::new(GovernanceParams::default()),
 			nonce_cache: RwLock::new(HashMap::with_capacity(1024)),
 			options,
-			transaction_queue: Arc::new(TransactionQueue::new(limits, verifier_options, tx_queue_strategy)),
+			transaction_queue: Arc::new(TransactionQueue::with_gas_price_bump(limits, verifier_options, tx_queue_strategy, tx_queue_gas_price_bump)),
+			propagation_filter: RwLock::new(propagation_filter),
 			accounts,
 			engine: spec.engine.clone(),
 		}
@@ -269,11 +311,29 @@ impl Miner {
 	/// Updates transaction queue verification limits.
 	///
 	/// Limits consist of current block gas limit and minimal gas price.
-	pub fn update_transaction_queue_limits(&self, block_gas_limit: U256) {
-		trace!(target: "miner", "minimal_gas_price: recalibrating...");
+	/// Minimal gas price set by the governance contract takes precedence over the gas pricer.
+	pub fn update_transaction_queue_limits<C: BlockChain>(&self, chain: &C, block_gas_limit: U256) {
 		let txq = self.transaction_queue.clone();
 		let mut options = self.options.pool_verification_options.clone();
-		self.gas_pricer.lock().recalibrate(move |gas_price| {
+
+		if let Some(gas_price) = self.governance_params.read().min_gas_price {
+			trace!(target: "miner", "minimal_gas_price: using governance price {}", gas_price);
+			options.minimal_gas_price = gas_price;
+			options.block_gas_limit = block_gas_limit;
+			txq.set_verifier_options(options);
+			return;
+		}
+
+		trace!(target: "miner", "minimal_gas_price: recalibrating...");
+		self.gas_pricer.lock().recalibrate(|samples| {
+			samples.update(chain.chain_info().best_block_hash, |hash| {
+				chain.block(BlockId::Hash(*hash))
+					.and_then(|block| match block.number() {
+						0 => None,
+						_ => Some((block.parent_hash(), block.transaction_views().iter().map(|t| t.gas_price()).collect())),
+					})
+			})
+		}, move |gas_price| {
 			debug!(target: "miner", "minimal_gas_price: Got gas price! {}", gas_price);
 			options.minimal_gas_price = gas_price;
 			options.block_gas_limit = block_gas_limit;
@@ -338,9 +398,13 @@ impl Miner {
 					// block not found - create it.
 					trace!(target: "miner", "prepare_block: No existing work - making new block");
 					let params = self.params.read().clone();
+					let gas_range_target = match self.governance_params.read().gas_limit_target {
+						Some(target) => (target, ::std::cmp::max(target, params.gas_range_target.1)),
+						None => params.gas_range_target,
+					};
 					chain.prepare_open_block(
 						params.author,
-						params.gas_range_target,
+						gas_range_target,
 						params.extra_data,
 					)
 				}
@@ -595,6 +659,22 @@ impl Miner {
 						false
 					})
 			},
+			// Keep the candidate until its seal is submitted and let the external sealer know of it.
+			Seal::External => {
+				let (hash, difficulty, number) = (block.hash(), *block.header().difficulty(), block.header().number());
+				trace!(target: "miner", "Block {} awaits an external seal.", hash);
+				{
+					let mut sealing = self.sealing.lock();
+					sealing.next_mandatory_reseal = Instant::now() + self.options.reseal_max_period;
+					sealing.queue.push(block);
+					sealing.queue.use_last_ref();
+				}
+
+				for notifier in self.listeners.read().iter() {
+					notifier.notify(hash, difficulty, number)
+				}
+				false
+			},
 			Seal::None => false,
 		}
 	}
@@ -714,7 +794,7 @@ impl miner::MinerService for Miner {
 			if let Some(ref ap) = self.accounts {
 				let password = password.unwrap_or_default();
 				// Sign test message
-				ap.sign(address.clone(), Some(password.clone()), Default::default())?;
+				ap.sign(address.clone(), Some(password.clone()), Default::default(), KeyOperation::SignMessage, "Miner")?;
 				// Enable sealing
 				self.sealing.lock().enabled = true;
 				// --------------------------------------------------------------------------
@@ -805,6 +885,23 @@ impl miner::MinerService for Miner {
 		self.transaction_queue.all_transactions()
 	}
 
+	fn queued_transactions_by_sender(&self, address: &Address) -> Vec<Arc<VerifiedTransaction>> {
+		self.transaction_queue.pending_by_sender(address)
+	}
+
+	fn queued_transactions_within_limits<C>(&self, chain: &C, block_gas_limit: U256, min_gas_price: U256) -> Vec<Arc<VerifiedTransaction>> where
+		C: ChainInfo + Nonce + Sync,
+	{
+		let chain_info = chain.chain_info();
+		self.transaction_queue.pending_within_limits(
+			CachedNonceClient::new(chain, &self.nonce_cache),
+			chain_info.best_block_number,
+			chain_info.best_block_timestamp,
+			block_gas_limit,
+			min_gas_price,
+		)
+	}
+
 	fn ready_transactions<C>(&self, chain: &C) -> Vec<Arc<VerifiedTransaction>> where
 		C: ChainInfo + Nonce + Sync,
 	{
@@ -826,8 +923,10 @@ impl miner::MinerService for Miner {
 			self.map_existing_pending_block(|sealing| {
 				sealing.transactions()
 					.iter()
-					.map(|signed| pool::VerifiedTransaction::from_pending_block_transaction(signed.clone()))
-					.map(Arc::new)
+					// prefer the pooled transaction, which keeps the submission details.
+					.map(|signed| self.transaction_queue.find(&signed.hash()).unwrap_or_else(|| {
+						Arc::new(pool::VerifiedTransaction::from_pending_block_transaction(signed.clone()))
+					}))
 					.collect()
 			}, chain_info.best_block_number)
 		};
@@ -1013,9 +1112,14 @@ impl miner::MinerService for Miner {
 		// Clear nonce cache
 		self.nonce_cache.write().clear();
 
+		// Read runtime parameters set by the governance contract at the new best block.
+		let best_header = chain.best_block_header();
+		let best_hash = best_header.hash();
+		*self.governance_params.write() = self.engine.governance_params(&best_header, chain).unwrap_or_default();
+
 		// First update gas limit in transaction queue and minimal gas price.
-		let gas_limit = *chain.best_block_header().gas_limit();
-		self.update_transaction_queue_limits(gas_limit);
+		let gas_limit = *best_header.gas_limit();
+		self.update_transaction_queue_limits(chain, gas_limit);
 
 		// Then import all transactions...
 		let client = self.pool_client(chain);
@@ -1036,8 +1140,33 @@ impl miner::MinerService for Miner {
 				});
 		}
 
-		// ...and at the end remove the old ones
-		self.transaction_queue.cull(client);
+		// Transactions may no longer pay the intrinsic gas after a fork.
+		let best_number = chain.chain_info().best_block_number;
+		let previous_number = best_number.saturating_sub(cmp::max(enacted.len(), 1) as u64);
+		let schedule = self.engine.schedule(best_number);
+		if !same_intrinsic_gas(&self.engine.schedule(previous_number), &schedule) {
+			self.transaction_queue.request_revalidation();
+		}
+		// Remove the ones failing stricter verification in the background, it scans the whole queue...
+		if self.transaction_queue.needs_revalidation() {
+			let queue = self.transaction_queue.clone();
+			::rayon::spawn(move || {
+				queue.revalidate(|tx| intrinsic_gas(tx, &schedule));
+			});
+		}
+
+		// ...and at the end remove the old ones. Transactions of the enacted blocks are known to be mined.
+		let mined: HashSet<_> = enacted.iter()
+			.filter_map(|hash| chain.block(BlockId::Hash(*hash)))
+			.flat_map(|block| block.transaction_hashes())
+			.collect();
+		self.transaction_queue.cull_with_max_age(client, self.options.tx_queue_max_age, &mined);
+
+		// Sealing candidates of engines which seal on their own are only valid on top of the best block.
+		// Work packages handed to external PoW miners are kept, late solutions may still be imported.
+		if enacted.len() > 0 && self.engine.seals_internally().is_some() {
+			self.sealing.lock().queue.retain(|b| b.header().parent_hash() == &best_hash);
+		}
 
 		if enacted.len() > 0 || (imported.len() > 0 && self.options.reseal_on_uncle) {
 			// Reset `next_allowed_reseal` in case a block is imported.
@@ -1072,6 +1201,17 @@ impl miner::MinerService for Miner {
 	}
 }
 
+// whether transactions require the same intrinsic gas under both schedules.
+fn same_intrinsic_gas(a: &Schedule, b: &Schedule) -> bool {
+	a.tx_gas == b.tx_gas
+		&& a.tx_create_gas == b.tx_create_gas
+		&& a.tx_data_zero_gas == b.tx_data_zero_gas
+		&& a.tx_data_non_zero_gas == b.tx_data_non_zero_gas
+		&& a.have_access_lists == b.have_access_lists
+		&& a.tx_access_list_address_gas == b.tx_access_list_address_gas
+		&& a.tx_access_list_storage_key_gas == b.tx_access_list_storage_key_gas
+}
+
 #[cfg(test)]
 mod tests {
 	use super::*;
@@ -1133,6 +1273,9 @@ mod tests {
 				infinite_pending_block: false,
 				tx_queue_penalization: Penalization::Disabled,
 				tx_queue_strategy: PrioritizationStrategy::GasPriceOnly,
+				tx_queue_gas_price_bump: pool::DEFAULT_GAS_PRICE_BUMP,
+				tx_queue_max_age: None,
+				tx_propagation_min_gas_price: None,
 				refuse_service_transactions: false,
 				pool_limits: Default::default(),
 				pool_verification_options: pool::verifier::Options {
@@ -1182,6 +1325,25 @@ mod tests {
 		assert!(!miner.prepare_pending_block(&client));
 	}
 
+	#[test]
+	fn should_keep_local_only_flag_of_pending_block_transactions() {
+		// given
+		let client = TestBlockChainClient::default();
+		let miner = miner();
+		let mut pending = PendingTransaction::new(transaction(), None);
+		pending.local_only = true;
+
+		// when
+		let res = miner.import_own_transaction(&client, pending);
+
+		// then
+		assert_eq!(res.unwrap(), ());
+		assert_eq!(miner.pending_transactions(0).unwrap().len(), 1);
+		let ready = miner.ready_transactions(&client);
+		assert_eq!(ready.len(), 1);
+		assert!(ready[0].pending().local_only);
+	}
+
 	#[test]
 	fn should_not_use_pending_block_if_best_block_is_higher() {
 		// given
@@ -1258,6 +1420,88 @@ mod tests {
 		assert_eq!(client.chain_info().best_block_number, 4 as BlockNumber);
 	}
 
+	#[test]
+	fn notifies_external_sealer_of_candidates() {
+		use engines::Engine;
+		use machine::EthereumMachine;
+
+		// engine leaving the seals of its blocks to an external sealer.
+		struct ExternalSealer(EthereumMachine);
+
+		impl Engine<EthereumMachine> for ExternalSealer {
+			fn name(&self) -> &str { "ExternalSealer" }
+			fn machine(&self) -> &EthereumMachine { &self.0 }
+			fn seals_internally(&self) -> Option<bool> { Some(true) }
+			fn generate_seal(&self, _block: &::block::ExecutedBlock, _parent: &Header) -> Seal { Seal::External }
+			fn verify_local_seal(&self, _header: &Header) -> Result<(), Error> { Ok(()) }
+		}
+
+		struct Candidates(Arc<Mutex<Vec<H256>>>);
+
+		impl NotifyWork for Candidates {
+			fn notify(&self, hash: H256, _difficulty: U256, _number: u64) {
+				self.0.lock().push(hash);
+			}
+		}
+
+		let mut spec = Spec::new_test();
+		spec.engine = Arc::new(ExternalSealer(Spec::new_test_machine()));
+		let miner = Miner::new_for_tests(&spec, None);
+		let candidates = Arc::new(Mutex::new(Vec::new()));
+		miner.add_work_listener(Box::new(Candidates(candidates.clone())));
+		let client = generate_dummy_client(2);
+
+		assert!(miner.import_own_transaction(&*client, PendingTransaction::new(transaction_with_chain_id(spec.chain_id()).into(), None)).is_ok());
+		miner.update_sealing(&*client);
+
+		// the candidate is announced and not imported until its seal is submitted.
+		let candidate = candidates.lock().last().cloned().expect("the external sealer is notified of the candidate");
+		assert_eq!(client.chain_info().best_block_number, 2 as BlockNumber);
+		let sealed = miner.submit_seal(candidate, vec![]).unwrap();
+		assert_eq!(sealed.header().number(), 3);
+		assert_eq!(sealed.transactions().len(), 1);
+	}
+
+	#[test]
+	fn should_follow_gas_price_percentile_of_recent_blocks() {
+		// given
+		let client = TestBlockChainClient::default();
+		let mut miner = miner();
+		miner.gas_pricer = Mutex::new(GasPricer::new_percentile(50, 100));
+
+		// when there are no transactions to sample
+		miner.update_transaction_queue_limits(&client, 1_000_000.into());
+
+		// then
+		assert_eq!(miner.queue_status().options.minimal_gas_price, 0.into());
+
+		// when
+		client.add_blocks(3, EachBlockWith::Transaction);
+		miner.update_transaction_queue_limits(&client, 1_000_000.into());
+
+		// then
+		assert_eq!(miner.queue_status().options.minimal_gas_price, 200_000_000_000u64.into());
+	}
+
+	#[test]
+	fn should_detect_intrinsic_gas_change_across_schedules() {
+		// given
+		let mut params = ::spec::CommonParams::default();
+		params.eip2028_transition = 10;
+		let schedule = |block_number| {
+			let mut schedule = Schedule::new_byzantium();
+			params.update_schedule(block_number, &mut schedule);
+			schedule
+		};
+
+		// then
+		assert!(same_intrinsic_gas(&schedule(5), &schedule(9)));
+		assert!(!same_intrinsic_gas(&schedule(9), &schedule(10)));
+		assert!(!same_intrinsic_gas(&Schedule::new_frontier(), &Schedule::new_homestead()));
+		// Byzantium doesn't change the intrinsic gas.
+		assert!(same_intrinsic_gas(&Schedule::new_homestead(), &Schedule::new_byzantium()));
+	}
+
 	#[test]
 	fn should_fail_setting_engine_signer_without_account_provider() {
 		let spec = Spec::new_instant;
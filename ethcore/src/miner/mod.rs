@@ -167,6 +167,14 @@ pub trait MinerService : Send + Sync {
 	/// Get a list of all transactions in the pool (some of them might not be ready for inclusion yet).
 	fn queued_transactions(&self) -> Vec<Arc<VerifiedTransaction>>;
 
+	/// Get a list of all transactions of given sender in the pool, ordered by nonce.
+	fn queued_transactions_by_sender(&self, address: &Address) -> Vec<Arc<VerifiedTransaction>>;
+
+	/// Get a list of ready transactions in the pool with gas at most `block_gas_limit`
+	/// paying at least `min_gas_price`.
+	fn queued_transactions_within_limits<C>(&self, chain: &C, block_gas_limit: U256, min_gas_price: U256) -> Vec<Arc<VerifiedTransaction>>
+		where C: ChainInfo + Nonce + Sync;
+
 	/// Get a list of local transactions with statuses.
 	fn local_transactions(&self) -> BTreeMap<H256, local_transactions::Status>;
 
@@ -32,6 +32,7 @@ use parking_lot::RwLock;
 use account_provider::AccountProvider;
 use client::{TransactionId, BlockInfo, CallContract, Nonce};
 use engines::EthEngine;
+use executive::intrinsic_gas;
 use header::Header;
 use miner;
 use miner::service_transaction_checker::ServiceTransactionChecker;
@@ -94,6 +95,7 @@ C: BlockInfo + CallContract,
 	///
 	/// This should perform any verifications that rely on chain status.
 	pub fn verify_signed(&self, tx: &SignedTransaction) -> Result<(), transaction::Error> {
+		self.engine.machine().verify_transaction_certificate(&tx)?;
 		self.engine.machine().verify_transaction(&tx, &self.best_block_header, self.chain)
 	}
 }
@@ -128,8 +130,8 @@ impl<'a, C: 'a> pool::client::Client for PoolClient<'a, C> where
 		}
 	}
 
-	fn required_gas(&self, tx: &transaction::Transaction) -> U256 {
-		tx.gas_required(&self.chain.latest_schedule()).into()
+	fn required_gas(&self, tx: &UnverifiedTransaction) -> U256 {
+		intrinsic_gas(tx, &self.chain.latest_schedule())
 	}
 
 	fn transaction_type(&self, tx: &SignedTransaction) -> pool::client::TransactionType {
@@ -18,12 +18,14 @@
 
 use std::sync::{Arc, Weak};
 use std::net::{SocketAddr, AddrParseError};
-use std::fmt;
+use std::path::Path;
+use std::collections::VecDeque;
+use std::{cmp, fmt};
 
 use client::{Client, ImportSealedBlock};
 use ethereum_types::{H64, H256, clean_0x, U256};
 use ethereum::ethash::Ethash;
-use ethash::SeedHashCompute;
+use ethash::{EthashManager, SeedHashCompute, quick_get_difficulty};
 use ethcore_miner::work_notify::NotifyWork;
 use ethcore_stratum::{
 	JobDispatcher, PushWorkHandler,
@@ -33,6 +35,9 @@ use miner::{Miner, MinerService};
 use parking_lot::Mutex;
 use rlp::encode;
 
+/// Number of recent work packages shares are accepted for.
+const MAX_RECENT_WORK: usize = 20;
+
 /// Configures stratum server options.
 #[derive(Debug, PartialEq, Clone)]
 pub struct Options {
@@ -88,6 +93,31 @@ impl SubmitPayload {
 			mix_hash: mix_hash,
 		})
 	}
+
+	/// Difficulty claimed by the submitted seal, assuming its mix hash is correct.
+	fn claimed_difficulty(&self) -> U256 {
+		Ethash::boundary_to_difficulty(&H256(quick_get_difficulty(
+			&self.pow_hash.0,
+			self.nonce.low_u64(),
+			&self.mix_hash.0
+		)))
+	}
+
+	/// Difficulty achieved by the submitted seal, verified with the light cache.
+	/// `None` if the mix hash doesn't match.
+	fn verified_difficulty(&self, pow: &EthashManager, number: u64) -> Option<U256> {
+		let result = pow.compute_light(number, &self.pow_hash.0, self.nonce.low_u64());
+		if H256(result.mix_hash) != self.mix_hash {
+			return None;
+		}
+		Some(Ethash::boundary_to_difficulty(&H256(result.value)))
+	}
+}
+
+struct Work {
+	pow_hash: H256,
+	difficulty: U256,
+	number: u64,
 }
 
 #[derive(Debug)]
@@ -107,6 +137,8 @@ impl fmt::Display for PayloadError {
 /// Job dispatcher for stratum service
 pub struct StratumJobDispatcher {
 	seed_compute: Mutex<SeedHashCompute>,
+	recent_work: Mutex<VecDeque<Work>>,
+	pow: EthashManager,
 	client: Weak<Client>,
 	miner: Weak<Miner>,
 }
@@ -120,10 +152,18 @@ impl JobDispatcher for StratumJobDispatcher {
 
 	fn job(&self) -> Option<String> {
 		self.with_core(|client, miner| miner.work_package(&*client).map(|(pow_hash, number, _timestamp, difficulty)| {
+			self.note_work(pow_hash, difficulty, number);
 			self.payload(pow_hash, difficulty, number)
 		}))
 	}
 
+	fn share_job(&self, difficulty: U256) -> Option<String> {
+		let recent_work = self.recent_work.lock();
+		recent_work.back().map(|work| {
+			self.payload(work.pow_hash, cmp::min(difficulty, work.difficulty), work.number)
+		})
+	}
+
 	fn submit(&self, payload: Vec<String>) -> Result<(), StratumServiceError> {
 		let payload = SubmitPayload::from_args(payload).map_err(|e|
 			StratumServiceError::Dispatch(e.to_string())
@@ -137,6 +177,79 @@ impl JobDispatcher for StratumJobDispatcher {
 			payload.mix_hash,
 		);
 
+		self.submit_seal(payload)
+	}
+
+	fn submit_share(&self, payload: Vec<String>, difficulty: U256) -> Result<(), StratumServiceError> {
+		let payload = SubmitPayload::from_args(payload).map_err(|e|
+			StratumServiceError::Dispatch(e.to_string())
+		)?;
+
+		let (block_difficulty, number) = self.recent_work.lock().iter()
+			.find(|work| work.pow_hash == payload.pow_hash)
+			.map(|work| (work.difficulty, work.number))
+			.ok_or_else(|| StratumServiceError::Dispatch(format!("Unknown or stale work: {}", payload.pow_hash)))?;
+
+		// the claimed difficulty is cheap to compute and rejects low shares before the light verification.
+		let share_difficulty = cmp::min(difficulty, block_difficulty);
+		let claimed = payload.claimed_difficulty();
+		if claimed < share_difficulty {
+			return Err(StratumServiceError::Dispatch(format!("Share difficulty {} below {}", claimed, share_difficulty)));
+		}
+
+		let achieved = payload.verified_difficulty(&self.pow, number)
+			.ok_or_else(|| StratumServiceError::Dispatch(format!("Invalid mix hash of share: {}", payload.mix_hash)))?;
+		if achieved < share_difficulty {
+			return Err(StratumServiceError::Dispatch(format!("Share difficulty {} below {}", achieved, share_difficulty)));
+		}
+
+		trace!(
+			target: "stratum",
+			"submit_share: Valid share: nonce={}, pow_hash={}, difficulty={}",
+			payload.nonce,
+			payload.pow_hash,
+			achieved,
+		);
+
+		// only shares meeting the block difficulty are sealed, the others just account for the work done.
+		if achieved < block_difficulty {
+			return Ok(());
+		}
+
+		self.submit_seal(payload)
+	}
+}
+
+impl StratumJobDispatcher {
+	/// New stratum job dispatcher given the miner and client.
+	/// Shares are verified with the light ethash cache kept in `cache_dir`.
+	fn new(miner: Weak<Miner>, client: Weak<Client>, cache_dir: &Path) -> StratumJobDispatcher {
+		StratumJobDispatcher {
+			seed_compute: Mutex::new(SeedHashCompute::new()),
+			recent_work: Mutex::new(VecDeque::with_capacity(MAX_RECENT_WORK)),
+			pow: EthashManager::new(cache_dir, None),
+			client: client,
+			miner: miner,
+		}
+	}
+
+	/// Remembers the work package pushed to the workers to validate their shares.
+	fn note_work(&self, pow_hash: H256, difficulty: U256, number: u64) {
+		let mut recent_work = self.recent_work.lock();
+		if recent_work.iter().any(|work| work.pow_hash == pow_hash) {
+			return;
+		}
+		if recent_work.len() == MAX_RECENT_WORK {
+			recent_work.pop_front();
+		}
+		recent_work.push_back(Work {
+			pow_hash: pow_hash,
+			difficulty: difficulty,
+			number: number,
+		});
+	}
+
+	fn submit_seal(&self, payload: SubmitPayload) -> Result<(), StratumServiceError> {
 		self.with_core_result(|client, miner| {
 			let seal = vec![encode(&payload.mix_hash).into_vec(), encode(&payload.nonce).into_vec()];
 
@@ -151,17 +264,6 @@ impl JobDispatcher for StratumJobDispatcher {
 			}
 		})
 	}
-}
-
-impl StratumJobDispatcher {
-	/// New stratum job dispatcher given the miner and client
-	fn new(miner: Weak<Miner>, client: Weak<Client>) -> StratumJobDispatcher {
-		StratumJobDispatcher {
-			seed_compute: Mutex::new(SeedHashCompute::new()),
-			client: client,
-			miner: miner,
-		}
-	}
 
 	/// Serializes payload for stratum service
 	fn payload(&self, pow_hash: H256, difficulty: U256, number: u64) -> String {
@@ -214,6 +316,7 @@ impl NotifyWork for Stratum {
 	fn notify(&self, pow_hash: H256, difficulty: U256, number: u64) {
 		trace!(target: "stratum", "Notify work");
 
+		self.dispatcher.note_work(pow_hash, difficulty, number);
 		self.service.push_work_all(
 			self.dispatcher.payload(pow_hash, difficulty, number)
 		).unwrap_or_else(
@@ -228,7 +331,7 @@ impl Stratum {
 	pub fn start(options: &Options, miner: Weak<Miner>, client: Weak<Client>) -> Result<Stratum, Error> {
 		use std::net::IpAddr;
 
-		let dispatcher = Arc::new(StratumJobDispatcher::new(miner, client));
+		let dispatcher = Arc::new(StratumJobDispatcher::new(miner, client, Path::new(&options.io_path)));
 
 		let stratum_svc = StratumService::start(
 			&SocketAddr::new(options.listen_addr.parse::<IpAddr>()?, options.port),
@@ -17,7 +17,7 @@
 //! State of all accounts in the system expressed in Plain Old Data.
 
 use std::fmt;
-use std::collections::BTreeMap;
+use std::collections::{btree_map, BTreeMap};
 use itertools::Itertools;
 use ethereum_types::{H256, Address};
 use triehash::sec_trie_root;
@@ -48,6 +48,15 @@ impl PodState {
 	pub fn drain(self) -> BTreeMap<Address, PodAccount> { self.0 }
 }
 
+impl IntoIterator for PodState {
+	type Item = (Address, PodAccount);
+	type IntoIter = btree_map::IntoIter<Address, PodAccount>;
+
+	fn into_iter(self) -> Self::IntoIter {
+		self.0.into_iter()
+	}
+}
+
 impl From<ethjson::blockchain::State> for PodState {
 	fn from(s: ethjson::blockchain::State) -> PodState {
 		let state = s.into_iter().map(|(addr, acc)| (addr.into(), PodAccount::from(acc))).collect();
@@ -17,20 +17,23 @@
 //! Watcher for snapshot-related chain events.
 
 use parking_lot::Mutex;
-use client::{BlockInfo, Client, ChainNotify, ChainRoute, ClientIoMessage};
+use client::{BlockInfo, Client, ChainNotify, ChainRoute, ClientIoMessage, StatePin};
 use ids::BlockId;
 
 use io::IoChannel;
 use ethereum_types::H256;
 use bytes::Bytes;
 
-use std::{sync::Arc, time::Duration};
+use std::{collections::BTreeMap, sync::Arc, time::Duration};
 
-// helper trait for transforming hashes to numbers and checking if syncing.
+// helper trait for transforming hashes to numbers, checking if syncing
+// and keeping the state of a block to take a snapshot at.
 trait Oracle: Send + Sync {
 	fn to_number(&self, hash: H256) -> Option<u64>;
 
 	fn is_major_importing(&self) -> bool;
+
+	fn pin_state(&self, num: u64) -> StatePin;
 }
 
 struct StandardOracle<F> where F: 'static + Send + Sync + Fn() -> bool {
@@ -48,15 +51,19 @@ impl<F> Oracle for StandardOracle<F>
 	fn is_major_importing(&self) -> bool {
 		(self.sync_status)()
 	}
+
+	fn pin_state(&self, num: u64) -> StatePin {
+		self.client.pin_state(num)
+	}
 }
 
-// helper trait for broadcasting a block to take a snapshot at.
+// helper trait for broadcasting a block to take a snapshot at, along with the pin of its state.
 trait Broadcast: Send + Sync {
-	fn take_at(&self, num: Option<u64>);
+	fn take_at(&self, num: Option<u64>, pin: Option<StatePin>);
 }
 
 impl Broadcast for Mutex<IoChannel<ClientIoMessage>> {
-	fn take_at(&self, num: Option<u64>) {
+	fn take_at(&self, num: Option<u64>, pin: Option<StatePin>) {
 		let num = match num {
 			Some(n) => n,
 			None => return,
@@ -64,7 +71,7 @@ impl Broadcast for Mutex<IoChannel<ClientIoMessage>> {
 
 		trace!(target: "snapshot_watcher", "broadcast: {}", num);
 
-		if let Err(e) = self.lock().send(ClientIoMessage::TakeSnapshot(num)) {
+		if let Err(e) = self.lock().send(ClientIoMessage::TakeSnapshot(num, pin)) {
 			warn!("Snapshot watcher disconnected from IoService: {}", e);
 		}
 	}
@@ -72,11 +79,16 @@ impl Broadcast for Mutex<IoChannel<ClientIoMessage>> {
 
 /// A `ChainNotify` implementation which will trigger a snapshot event
 /// at certain block numbers.
+///
+/// The state of a block to be snapshotted is pinned as soon as the block is imported,
+/// so pruned nodes keep it around until the snapshot is taken.
 pub struct Watcher {
 	oracle: Box<Oracle>,
 	broadcast: Box<Broadcast>,
 	period: u64,
 	history: u64,
+	// pinned states of blocks not yet broadcast.
+	pins: Mutex<BTreeMap<u64, StatePin>>,
 }
 
 impl Watcher {
@@ -94,6 +106,7 @@ impl Watcher {
 			broadcast: Box::new(Mutex::new(channel)),
 			period: period,
 			history: history,
+			pins: Mutex::new(BTreeMap::new()),
 		}
 	}
 }
@@ -112,16 +125,35 @@ impl ChainNotify for Watcher {
 
 		trace!(target: "snapshot_watcher", "{} imported", imported.len());
 
-		let highest = imported.into_iter()
+		let numbers: Vec<_> = imported.into_iter()
 			.filter_map(|h| self.oracle.to_number(h))
+			.collect();
+
+		let to_pin = numbers.iter()
+			.cloned()
+			.filter(|&num| num >= self.period && num % self.period == 0)
+			.max();
+		let mut pins = self.pins.lock();
+		if let Some(num) = to_pin {
+			trace!(target: "snapshot_watcher", "pinning state at {}", num);
+			let pin = self.oracle.pin_state(num);
+			pins.insert(num, pin);
+		}
+
+		let highest = numbers.into_iter()
 			.filter(|&num| num >= self.period + self.history)
 			.map(|num| num - self.history)
 			.filter(|num| num % self.period == 0)
 			.fold(0, ::std::cmp::max);
 
 		match highest {
-			0 => self.broadcast.take_at(None),
-			_ => self.broadcast.take_at(Some(highest)),
+			0 => self.broadcast.take_at(None, None),
+			_ => {
+				// the pin moves to the snapshot, states of earlier blocks won't be snapshotted anymore.
+				let pin = pins.remove(&highest);
+				*pins = pins.split_off(&highest);
+				self.broadcast.take_at(Some(highest), pin)
+			},
 		}
 	}
 }
@@ -130,14 +162,15 @@ impl ChainNotify for Watcher {
 mod tests {
 	use super::{Broadcast, Oracle, Watcher};
 
-	use client::{ChainNotify, ChainRoute};
+	use client::{ChainNotify, ChainRoute, PinnedStates, StatePin};
 
 	use ethereum_types::{H256, U256};
 
-	use std::collections::HashMap;
+	use std::collections::{BTreeMap, HashMap};
 	use std::time::Duration;
+	use parking_lot::Mutex;
 
-	struct TestOracle(HashMap<H256, u64>);
+	struct TestOracle(HashMap<H256, u64>, PinnedStates);
 
 	impl Oracle for TestOracle {
 		fn to_number(&self, hash: H256) -> Option<u64> {
@@ -145,29 +178,40 @@ mod tests {
 		}
 
 		fn is_major_importing(&self) -> bool { false }
+
+		fn pin_state(&self, num: u64) -> StatePin {
+			self.1.pin(num)
+		}
 	}
 
-	struct TestBroadcast(Option<u64>);
+	// keeps the pins it receives, like a snapshot in progress.
+	struct TestBroadcast(Option<u64>, Mutex<Vec<StatePin>>);
 	impl Broadcast for TestBroadcast {
-		fn take_at(&self, num: Option<u64>) {
+		fn take_at(&self, num: Option<u64>, pin: Option<StatePin>) {
 			if num != self.0 {
 				panic!("Watcher broadcast wrong number. Expected {:?}, found {:?}", self.0, num);
 			}
+			if let Some(pin) = pin {
+				assert_eq!(Some(pin.number()), num);
+				self.1.lock().push(pin);
+			}
 		}
 	}
 
-	// helper harness for tests which expect a notification.
-	fn harness(numbers: Vec<u64>, period: u64, history: u64, expected: Option<u64>) {
+	// helper harness for tests which expect a notification. Returns the earliest pinned block, if any.
+	fn harness(numbers: Vec<u64>, period: u64, history: u64, expected: Option<u64>) -> Option<u64> {
 		const DURATION_ZERO: Duration = Duration::from_millis(0);
 
 		let hashes: Vec<_> = numbers.clone().into_iter().map(|x| H256::from(U256::from(x))).collect();
 		let map = hashes.clone().into_iter().zip(numbers).collect();
+		let pinned = PinnedStates::default();
 
 		let watcher = Watcher {
-			oracle: Box::new(TestOracle(map)),
-			broadcast: Box::new(TestBroadcast(expected)),
+			oracle: Box::new(TestOracle(map, pinned.clone())),
+			broadcast: Box::new(TestBroadcast(expected, Mutex::new(Vec::new()))),
 			period: period,
 			history: history,
+			pins: Mutex::new(BTreeMap::new()),
 		};
 
 		watcher.new_blocks(
@@ -178,6 +222,11 @@ mod tests {
 			vec![],
 			DURATION_ZERO,
 		);
+
+		let earliest = pinned.earliest();
+		drop(watcher);
+		assert_eq!(pinned.earliest(), None);
+		earliest
 	}
 
 	// helper
@@ -201,4 +250,11 @@ mod tests {
 	fn doesnt_fire_before_history() {
 		harness(vec![10, 11], 10, 5, None);
 	}
+
+	#[test]
+	fn pins_state_to_snapshot() {
+		assert_eq!(harness(vec![10, 11], 10, 5, None), Some(10));
+		assert_eq!(harness(vec![19, 20, 25], 10, 5, Some(20)), Some(20));
+		assert_eq!(harness(vec![0, 5], 10, 5, None), None);
+	}
 }
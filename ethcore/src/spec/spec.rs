@@ -33,7 +33,7 @@ use vm::{EnvInfo, CallType, ActionValue, ActionParams, ParamsType};
 
 use builtin::Builtin;
 use encoded;
-use engines::{EthEngine, NullEngine, InstantSeal, BasicAuthority, AuthorityRound, Tendermint, DEFAULT_BLOCKHASH_CONTRACT};
+use engines::{EthEngine, NullEngine, InstantSeal, BasicAuthority, AuthorityRound, Tendermint, Clique, DEFAULT_BLOCKHASH_CONTRACT};
 use error::Error;
 use executive::Executive;
 use factory::Factories;
@@ -50,6 +50,9 @@ pub use ethash::OptimizeFor;
 
 const MAX_TRANSACTION_SIZE: usize = 300 * 1024;
 
+/// Gas of `SSTORE` to a dirty slot under EIP-2200, independent of the `SLOAD` price.
+const EIP2200_SSTORE_DIRTY_GAS: usize = 800;
+
 // helper for formatting errors.
 fn fmt_err<F: ::std::fmt::Display>(f: F) -> String {
 	format!("Spec json is invalid: {}", f)
@@ -115,6 +118,25 @@ pub struct CommonParams {
 	pub eip214_transition: BlockNumber,
 	/// Number of first block where EIP-145 rules begin.
 	pub eip145_transition: BlockNumber,
+	/// Number of first block where EIP-1283 rules begin.
+	pub eip1283_transition: BlockNumber,
+	/// Number of first block where EIP-2200 rules begin.
+	pub eip2200_transition: BlockNumber,
+	/// Number of first block where EIP-2028 (cheaper non-zero calldata) rules begin.
+	pub eip2028_transition: BlockNumber,
+	/// Number of first block where EIP-2930 access-list transactions are accepted
+	/// and EIP-2929 warm/cold access gas costs apply.
+	pub eip2930_transition: BlockNumber,
+	/// Number of first block from which transactions must be replay-protected (EIP-155).
+	pub replay_protection_transition: BlockNumber,
+	/// Number of first block where the SUICIDE instruction is disabled.
+	pub selfdestruct_disable_transition: BlockNumber,
+	/// Number of first block where suicided accounts keep their remaining balance instead of burning it.
+	pub selfdestruct_keeps_balance_transition: BlockNumber,
+	/// Gas refunded for each suicided account, if different from the schedule default.
+	pub selfdestruct_refund: Option<usize>,
+	/// Number of first block where `selfdestruct_refund` applies.
+	pub selfdestruct_refund_transition: BlockNumber,
 	/// Number of first block where dust cleanup rules (EIP-168 and EIP169) begin.
 	pub dust_protection_transition: BlockNumber,
 	/// Nonce cap increase per block. Nonce cap is only checked if dust protection is enabled.
@@ -135,6 +157,19 @@ pub struct CommonParams {
 	pub max_code_size_transition: BlockNumber,
 	/// Transaction permission managing contract address.
 	pub transaction_permission_contract: Option<Address>,
+	/// Authority whose certificates senders must hold, if any.
+	pub transaction_certificate_authority: Option<Address>,
+	/// Contract certifying senders of zero gas price service transactions, if any.
+	///
+	/// Part of consensus: once activated, every zero gas price transaction in a block runs
+	/// a static call to the certifier with a fixed gas allowance before it's executed.
+	pub service_transaction_certifier: Option<Address>,
+	/// Block at which the service transaction certifier is activated.
+	pub service_transaction_certifier_transition: BlockNumber,
+	/// Gas price the block author pays for the gas of service transactions it includes.
+	pub service_transaction_gas_price: U256,
+	/// Contract providing runtime parameters read at block boundaries, if any.
+	pub governance_contract: Option<Address>,
 	/// Maximum size of transaction's RLP payload
 	pub max_transaction_size: usize,
 }
@@ -177,6 +212,37 @@ impl CommonParams {
 		if block_number >= self.eip210_transition {
 			schedule.blockhash_gas = 800;
 		}
+		if block_number >= self.eip1283_transition {
+			schedule.sstore_dirty_gas = Some(200);
+		}
+		if block_number >= self.eip2200_transition {
+			schedule.sstore_dirty_gas = Some(EIP2200_SSTORE_DIRTY_GAS);
+			schedule.eip1706 = true;
+		}
+		if block_number >= self.eip2028_transition {
+			schedule.tx_data_non_zero_gas = 16;
+		}
+		if block_number >= self.eip2930_transition {
+			schedule.have_access_lists = true;
+			// accounts and storage keys declared by access lists are cheaper to access (EIP-2929)
+			schedule.eip2929 = true;
+			schedule.sload_gas = schedule.warm_storage_read_gas;
+			schedule.balance_gas = schedule.warm_storage_read_gas;
+			schedule.extcodesize_gas = schedule.warm_storage_read_gas;
+			schedule.extcodecopy_base_gas = schedule.warm_storage_read_gas;
+			schedule.call_gas = schedule.warm_storage_read_gas;
+			schedule.sstore_reset_gas -= schedule.cold_sload_gas;
+			if schedule.sstore_dirty_gas.is_some() {
+				schedule.sstore_dirty_gas = Some(schedule.warm_storage_read_gas);
+			}
+		}
+		schedule.have_suicide = block_number < self.selfdestruct_disable_transition;
+		schedule.suicide_burns_balance = block_number < self.selfdestruct_keeps_balance_transition;
+		if let Some(refund) = self.selfdestruct_refund {
+			if block_number >= self.selfdestruct_refund_transition {
+				schedule.suicide_refund_gas = refund;
+			}
+		}
 		if block_number >= self.dust_protection_transition {
 			schedule.kill_dust = match self.remove_dust_contracts {
 				true => ::vm::CleanDustMode::WithCodeAndStorage,
@@ -217,18 +283,18 @@ impl From<ethjson::spec::Params> for CommonParams {
 				None
 			},
 			eip150_transition: p.eip150_transition.map_or(0, Into::into),
-			eip160_transition: p.eip160_transition.map_or(0, Into::into),
-			eip161abc_transition: p.eip161abc_transition.map_or(0, Into::into),
-			eip161d_transition: p.eip161d_transition.map_or(0, Into::into),
+			eip160_transition: p.eip160_transition.or(p.spurious_dragon_transition).map_or(0, Into::into),
+			eip161abc_transition: p.eip161abc_transition.or(p.spurious_dragon_transition).map_or(0, Into::into),
+			eip161d_transition: p.eip161d_transition.or(p.spurious_dragon_transition).map_or(0, Into::into),
 			eip98_transition: p.eip98_transition.map_or(0, Into::into),
-			eip155_transition: p.eip155_transition.map_or(0, Into::into),
+			eip155_transition: p.eip155_transition.or(p.spurious_dragon_transition).map_or(0, Into::into),
 			validate_receipts_transition: p.validate_receipts_transition.map_or(0, Into::into),
 			validate_chain_id_transition: p.validate_chain_id_transition.map_or(0, Into::into),
 			eip86_transition: p.eip86_transition.map_or_else(
 				BlockNumber::max_value,
 				Into::into,
 			),
-			eip140_transition: p.eip140_transition.map_or_else(
+			eip140_transition: p.eip140_transition.or(p.byzantium_transition).map_or_else(
 				BlockNumber::max_value,
 				Into::into,
 			),
@@ -246,19 +312,49 @@ impl From<ethjson::spec::Params> for CommonParams {
 				Into::into,
 			),
 			eip210_contract_gas: p.eip210_contract_gas.map_or(1000000.into(), Into::into),
-			eip211_transition: p.eip211_transition.map_or_else(
+			eip211_transition: p.eip211_transition.or(p.byzantium_transition).map_or_else(
+				BlockNumber::max_value,
+				Into::into,
+			),
+			eip145_transition: p.eip145_transition.or(p.constantinople_transition).map_or_else(
+				BlockNumber::max_value,
+				Into::into,
+			),
+			eip1283_transition: p.eip1283_transition.map_or_else(
+				BlockNumber::max_value,
+				Into::into,
+			),
+			eip2200_transition: p.eip2200_transition.map_or_else(
 				BlockNumber::max_value,
 				Into::into,
 			),
-			eip145_transition: p.eip145_transition.map_or_else(
+			eip2028_transition: p.eip2028_transition.map_or_else(
 				BlockNumber::max_value,
 				Into::into,
 			),
-			eip214_transition: p.eip214_transition.map_or_else(
+			eip2930_transition: p.eip2930_transition.map_or_else(
 				BlockNumber::max_value,
 				Into::into,
 			),
-			eip658_transition: p.eip658_transition.map_or_else(
+			replay_protection_transition: p.replay_protection_transition.map_or_else(
+				BlockNumber::max_value,
+				Into::into,
+			),
+			selfdestruct_disable_transition: p.selfdestruct_disable_transition.map_or_else(
+				BlockNumber::max_value,
+				Into::into,
+			),
+			selfdestruct_keeps_balance_transition: p.selfdestruct_keeps_balance_transition.map_or_else(
+				BlockNumber::max_value,
+				Into::into,
+			),
+			selfdestruct_refund: p.selfdestruct_refund.map(Into::into),
+			selfdestruct_refund_transition: p.selfdestruct_refund_transition.map_or(0, Into::into),
+			eip214_transition: p.eip214_transition.or(p.byzantium_transition).map_or_else(
+				BlockNumber::max_value,
+				Into::into,
+			),
+			eip658_transition: p.eip658_transition.or(p.byzantium_transition).map_or_else(
 				BlockNumber::max_value,
 				Into::into,
 			),
@@ -273,8 +369,13 @@ impl From<ethjson::spec::Params> for CommonParams {
 			node_permission_contract: p.node_permission_contract.map(Into::into),
 			max_code_size: p.max_code_size.map_or(u64::max_value(), Into::into),
 			max_transaction_size: p.max_transaction_size.map_or(MAX_TRANSACTION_SIZE, Into::into),
-			max_code_size_transition: p.max_code_size_transition.map_or(0, Into::into),
+			max_code_size_transition: p.max_code_size_transition.or(p.spurious_dragon_transition).map_or(0, Into::into),
 			transaction_permission_contract: p.transaction_permission_contract.map(Into::into),
+			transaction_certificate_authority: p.transaction_certificate_authority.map(Into::into),
+			service_transaction_certifier: p.service_transaction_certifier.map(Into::into),
+			service_transaction_certifier_transition: p.service_transaction_certifier_transition.map_or(0, Into::into),
+			service_transaction_gas_price: p.service_transaction_gas_price.map_or_else(U256::zero, Into::into),
+			governance_contract: p.governance_contract.map(Into::into),
 			wasm_activation_transition: p.wasm_activation_transition.map_or_else(
 				BlockNumber::max_value,
 				Into::into
@@ -433,7 +534,33 @@ impl From<SpecHardcodedSync> for ethjson::spec::HardcodedSync {
 	}
 }
 
-fn load_machine_from(s: ethjson::spec::Spec) -> EthereumMachine {
+// builtins activated by the Byzantium fork.
+const BYZANTIUM_BUILTINS: &'static [&'static str] = &["modexp", "alt_bn128_add", "alt_bn128_mul", "alt_bn128_pairing"];
+
+// fill engine transitions and builtin activations left unspecified with the named fork transitions.
+// transitions of common params are defaulted in `CommonParams::from`.
+fn apply_fork_transitions(s: &mut ethjson::spec::Spec) {
+	let homestead = s.params.homestead_transition;
+	let byzantium = s.params.byzantium_transition;
+
+	if let ethjson::spec::Engine::Ethash(ref mut ethash) = s.engine {
+		let params = &mut ethash.params;
+		params.homestead_transition = params.homestead_transition.or(homestead);
+		params.eip100b_transition = params.eip100b_transition.or(byzantium);
+		params.eip649_transition = params.eip649_transition.or(byzantium);
+	}
+
+	if byzantium.is_some() {
+		for builtin in s.accounts.builtins_mut() {
+			if builtin.activate_at.is_none() && BYZANTIUM_BUILTINS.contains(&builtin.name.as_str()) {
+				builtin.activate_at = byzantium;
+			}
+		}
+	}
+}
+
+fn load_machine_from(mut s: ethjson::spec::Spec) -> EthereumMachine {
+	apply_fork_transitions(&mut s);
 	let builtins = s.accounts.builtins().into_iter().map(|p| (p.0.into(), From::from(p.1))).collect();
 	let params = CommonParams::from(s.params);
 
@@ -441,7 +568,8 @@ fn load_machine_from(s: ethjson::spec::Spec) -> EthereumMachine {
 }
 
 /// Load from JSON object.
-fn load_from(spec_params: SpecParams, s: ethjson::spec::Spec) -> Result<Spec, Error> {
+fn load_from(spec_params: SpecParams, mut s: ethjson::spec::Spec) -> Result<Spec, Error> {
+	apply_fork_transitions(&mut s);
 	let builtins = s.accounts
 		.builtins()
 		.into_iter()
@@ -557,6 +685,7 @@ impl Spec {
 				.expect("Failed to start AuthorityRound consensus engine."),
 			ethjson::spec::Engine::Tendermint(tendermint) => Tendermint::new(tendermint.params.into(), machine)
 				.expect("Failed to start the Tendermint consensus engine."),
+			ethjson::spec::Engine::Clique(clique) => Arc::new(Clique::new(clique.params.into(), machine)),
 		}
 	}
 
@@ -866,7 +995,7 @@ impl Spec {
 	/// Create a new Spec with InstantSeal consensus which does internal sealing (not requiring
 	/// work).
 	pub fn new_instant() -> Spec {
-		load_bundled!("instant_seal")
+		Spec::load(&::std::env::temp_dir(), ::ethereum::bundled::INSTANT_SEAL).expect("Chain spec instant_seal is invalid.")
 	}
 
 	/// Create a new Spec with AuthorityRound consensus which does internal sealing (not
@@ -978,4 +1107,131 @@ mod tests {
 		assert_eq!(state.storage_at(&address, &H256::zero()).unwrap(), expected);
 		assert_eq!(state.balance(&address).unwrap(), 1.into());
 	}
+
+	#[test]
+	fn genesis_state_runs_constructors() {
+		let spec = Spec::new_test_constructor();
+		let state = State::from_genesis(&spec, get_temp_state_db(), Default::default()).unwrap();
+		let address = "0000000000000000000000000000000000001337".into();
+
+		assert_eq!(*state.root(), spec.state_root());
+		assert_eq!(state.storage_at(&address, &H256::zero()).unwrap(), H256::from(1));
+		assert_eq!(state.balance(&address).unwrap(), 1.into());
+	}
+
+	#[test]
+	fn fork_transitions_are_defaults() {
+		let spec = ethjson::spec::Spec::load(&br#"{
+			"name": "Forks",
+			"engine": { "null": { "params": {} } },
+			"params": {
+				"gasLimitBoundDivisor": "0x0400",
+				"maximumExtraDataSize": "0x20",
+				"minGasLimit": "0x1388",
+				"networkID" : "0x2",
+				"byzantiumTransition": "0x10",
+				"eip658Transition": "0x20"
+			},
+			"genesis": {
+				"seal": { "generic": "0x" },
+				"difficulty": "0x20000",
+				"gasLimit": "0x2fefd8"
+			},
+			"accounts": {}
+		}"#[..]).unwrap();
+		let params = CommonParams::from(spec.params);
+
+		assert_eq!(params.eip140_transition, 0x10);
+		assert_eq!(params.eip214_transition, 0x10);
+		assert_eq!(params.eip658_transition, 0x20);
+		assert_eq!(params.eip145_transition, BlockNumber::max_value());
+		assert!(!params.schedule(0xf).have_revert);
+		assert!(params.schedule(0x10).have_revert);
+	}
+
+	#[test]
+	fn constantinople_transition_leaves_net_sstore_metering_off() {
+		let spec = ethjson::spec::Spec::load(&br#"{
+			"name": "Forks",
+			"engine": { "null": { "params": {} } },
+			"params": {
+				"gasLimitBoundDivisor": "0x0400",
+				"maximumExtraDataSize": "0x20",
+				"minGasLimit": "0x1388",
+				"networkID" : "0x2",
+				"constantinopleTransition": "0x10",
+				"eip2200Transition": "0x20"
+			},
+			"genesis": {
+				"seal": { "generic": "0x" },
+				"difficulty": "0x20000",
+				"gasLimit": "0x2fefd8"
+			},
+			"accounts": {}
+		}"#[..]).unwrap();
+		let params = CommonParams::from(spec.params);
+
+		assert_eq!(params.eip145_transition, 0x10);
+		assert_eq!(params.eip1283_transition, BlockNumber::max_value());
+		assert_eq!(params.schedule(0x10).sstore_dirty_gas, None);
+		assert_eq!(params.schedule(0x20).sstore_dirty_gas, Some(800));
+	}
+
+	#[test]
+	fn fork_transitions_default_engine_and_builtins() {
+		use ethjson::uint::Uint;
+
+		let mut spec = ethjson::spec::Spec::load(&br#"{
+			"name": "Forks",
+			"engine": {
+				"Ethash": {
+					"params": {
+						"minimumDifficulty": "0x020000",
+						"difficultyBoundDivisor": "0x0800",
+						"durationLimit": "0x0d",
+						"blockReward": "0x4563918244F40000",
+						"eip649Transition": "0x30"
+					}
+				}
+			},
+			"params": {
+				"gasLimitBoundDivisor": "0x0400",
+				"maximumExtraDataSize": "0x20",
+				"minGasLimit": "0x1388",
+				"networkID" : "0x2",
+				"maxCodeSize": "0x6000",
+				"homesteadTransition": "0x5",
+				"spuriousDragonTransition": "0xa",
+				"byzantiumTransition": "0x10"
+			},
+			"genesis": {
+				"seal": { "generic": "0x" },
+				"difficulty": "0x20000",
+				"gasLimit": "0x2fefd8"
+			},
+			"accounts": {
+				"0000000000000000000000000000000000000001": { "builtin": { "name": "ecrecover", "pricing": { "linear": { "base": 3000, "word": 0 } } } },
+				"0000000000000000000000000000000000000005": { "builtin": { "name": "modexp", "pricing": { "modexp": { "divisor": 20 } } } },
+				"0000000000000000000000000000000000000006": { "builtin": { "name": "alt_bn128_add", "activate_at": "0x20", "pricing": { "linear": { "base": 500, "word": 0 } } } }
+			}
+		}"#[..]).unwrap();
+		apply_fork_transitions(&mut spec);
+
+		match spec.engine {
+			ethjson::spec::Engine::Ethash(ref ethash) => {
+				assert_eq!(ethash.params.homestead_transition, Some(Uint(U256::from(0x5))));
+				assert_eq!(ethash.params.eip100b_transition, Some(Uint(U256::from(0x10))));
+				assert_eq!(ethash.params.eip649_transition, Some(Uint(U256::from(0x30))));
+			},
+			_ => panic!("Ethash engine expected"),
+		}
+
+		let builtins = spec.accounts.builtins();
+		assert_eq!(builtins[&ethjson::hash::Address(Address::from(1))].activate_at, None);
+		assert_eq!(builtins[&ethjson::hash::Address(Address::from(5))].activate_at, Some(Uint(U256::from(0x10))));
+		assert_eq!(builtins[&ethjson::hash::Address(Address::from(6))].activate_at, Some(Uint(U256::from(0x20))));
+
+		let params = CommonParams::from(spec.params);
+		assert_eq!(params.max_code_size_transition, 0xa);
+	}
 }
@@ -272,6 +272,11 @@ impl Account {
 		}
 	}
 
+	/// Has the code been changed since the last `commit_code`?
+	pub fn is_code_dirty(&self) -> bool {
+		self.code_filth == Filth::Dirty
+	}
+
 	/// Is `code_cache` valid; such that code is going to return Some?
 	pub fn is_cached(&self) -> bool {
 		!self.code_cache.is_empty() || (self.code_cache.is_empty() && self.code_hash == KECCAK_EMPTY)
@@ -22,12 +22,16 @@
 //! merkle trie is strictly necessary.
 
 use std::collections::{HashSet, HashMap};
+use std::ops::Deref;
 use std::sync::Arc;
 
-use state::Account;
-use parking_lot::Mutex;
+use bytes::Bytes;
+use state::{Account, StateSizeDelta};
+use parking_lot::{Mutex, RwLock};
 use ethereum_types::{Address, H256};
 use memorydb::MemoryDB;
+use memory_cache::MemoryLruCache;
+use memory_cache::pressure::{self, Shed};
 use hashdb::{AsHashDB, HashDB, DBValue};
 
 /// State backend. See module docs for more details.
@@ -254,3 +258,276 @@ impl<H: AsHashDB + Send + Sync> Backend for Basic<H> {
 	fn note_non_null_account(&self, _: &Address) { }
 	fn is_known_null(&self, _: &Address) -> bool { false }
 }
+
+/// Writes trie nodes to a batch instead of the database it reads from.
+///
+/// Removals are kept in the batch as negative reference counts, so applying the batch later
+/// has the same effect as writing to the database directly.
+pub struct Batch<'a> {
+	base: &'a HashDB,
+	batch: &'a mut MemoryDB,
+}
+
+impl<'a> Batch<'a> {
+	/// Create a new `Batch` collecting the changes to `base` in `batch`.
+	pub fn new(base: &'a HashDB, batch: &'a mut MemoryDB) -> Self {
+		Batch {
+			base: base,
+			batch: batch,
+		}
+	}
+}
+
+impl<'a> HashDB for Batch<'a> {
+	fn keys(&self) -> HashMap<H256, i32> {
+		let mut keys = self.base.keys();
+		for (key, rc) in self.batch.keys() {
+			*keys.entry(key).or_insert(0) += rc;
+		}
+		keys
+	}
+
+	fn get(&self, key: &H256) -> Option<DBValue> {
+		self.batch.get(key).or_else(|| self.base.get(key))
+	}
+
+	fn contains(&self, key: &H256) -> bool {
+		self.get(key).is_some()
+	}
+
+	fn insert(&mut self, value: &[u8]) -> H256 {
+		self.batch.insert(value)
+	}
+
+	fn emplace(&mut self, key: H256, value: DBValue) {
+		self.batch.emplace(key, value)
+	}
+
+	fn remove(&mut self, key: &H256) {
+		self.batch.remove(key)
+	}
+}
+
+/// Writes trie nodes through to a database, measuring the entries written and freed.
+///
+/// Removals don't carry the value, so only their number is measured.
+pub struct Measured<'a> {
+	db: &'a mut HashDB,
+	delta: &'a mut StateSizeDelta,
+}
+
+impl<'a> Measured<'a> {
+	/// Create a new `Measured` database writing to `db` and adding the sizes to `delta`.
+	pub fn new(db: &'a mut HashDB, delta: &'a mut StateSizeDelta) -> Self {
+		Measured {
+			db: db,
+			delta: delta,
+		}
+	}
+
+	fn note_added(&mut self, len: usize) {
+		self.delta.nodes_added += 1;
+		self.delta.bytes_added += len as u64;
+	}
+}
+
+impl<'a> HashDB for Measured<'a> {
+	fn keys(&self) -> HashMap<H256, i32> {
+		self.db.keys()
+	}
+
+	fn get(&self, key: &H256) -> Option<DBValue> {
+		self.db.get(key)
+	}
+
+	fn contains(&self, key: &H256) -> bool {
+		self.db.contains(key)
+	}
+
+	fn insert(&mut self, value: &[u8]) -> H256 {
+		self.note_added(value.len());
+		self.db.insert(value)
+	}
+
+	fn emplace(&mut self, key: H256, value: DBValue) {
+		self.note_added(value.len());
+		self.db.emplace(key, value)
+	}
+
+	fn remove(&mut self, key: &H256) {
+		self.delta.nodes_freed += 1;
+		self.db.remove(key)
+	}
+}
+
+/// Overlay state backend.
+/// Reads from a base backend which is never written to; every change is kept in memory
+/// and dropped along with the overlay. The base may be borrowed (speculative execution,
+/// see `State::overlay`), owned or shared by clones of the overlay (copy-on-write states,
+/// see `State::into_copy_on_write`). Reads may also go through a `NodeCache` shared by
+/// many overlays, e.g. read-only views of historical states.
+///
+/// Accounts aren't cached: the caches of the base don't know about the changes in the overlay.
+/// Code is keyed by hash, so the code cache of the base is used. Accounts committed to the
+/// overlay are noted as non-null in the overlay only.
+pub struct Overlay<D> {
+	base: D,
+	nodes: Option<Arc<NodeCache>>,
+	changed: MemoryDB,
+	non_null: RwLock<HashSet<Address>>,
+}
+
+impl<D> Overlay<D> {
+	/// Create a new `Overlay` over a base backend.
+	pub fn new(base: D) -> Self {
+		Overlay {
+			base: base,
+			nodes: None,
+			changed: MemoryDB::new(),
+			non_null: RwLock::new(HashSet::new()),
+		}
+	}
+
+	/// Create a new `Overlay` over a base backend, sharing given node cache.
+	pub fn with_node_cache(base: D, nodes: Arc<NodeCache>) -> Self {
+		Overlay {
+			nodes: Some(nodes),
+			..Overlay::new(base)
+		}
+	}
+}
+
+impl<D: Clone> Clone for Overlay<D> {
+	fn clone(&self) -> Self {
+		Overlay {
+			base: self.base.clone(),
+			nodes: self.nodes.clone(),
+			changed: self.changed.clone(),
+			non_null: RwLock::new(self.non_null.read().clone()),
+		}
+	}
+}
+
+impl<D, B> HashDB for Overlay<D> where D: Deref<Target = B> + Send + Sync, B: Backend + ?Sized {
+	fn keys(&self) -> HashMap<H256, i32> {
+		let mut keys = self.base.as_hashdb().keys();
+		keys.extend(self.changed.keys());
+		keys
+	}
+
+	fn get(&self, key: &H256) -> Option<DBValue> {
+		if let Some(val) = self.changed.get(key) {
+			return Some(val);
+		}
+
+		let nodes = match self.nodes {
+			Some(ref nodes) => nodes,
+			None => return self.base.as_hashdb().get(key),
+		};
+
+		if let Some(val) = nodes.get(key) {
+			return Some(val);
+		}
+
+		let val = self.base.as_hashdb().get(key);
+		if let Some(ref val) = val {
+			nodes.insert(*key, val);
+		}
+		val
+	}
+
+	fn contains(&self, key: &H256) -> bool {
+		self.get(key).is_some()
+	}
+
+	fn insert(&mut self, value: &[u8]) -> H256 {
+		self.changed.insert(value)
+	}
+
+	fn emplace(&mut self, key: H256, value: DBValue) {
+		self.changed.emplace(key, value)
+	}
+
+	fn remove(&mut self, key: &H256) {
+		// only remove from `changed`
+		if self.changed.contains(key) {
+			self.changed.remove(key)
+		}
+	}
+}
+
+impl<D, B> Backend for Overlay<D> where D: Deref<Target = B> + Send + Sync, B: Backend + ?Sized {
+	fn as_hashdb(&self) -> &HashDB {
+		self
+	}
+
+	fn as_hashdb_mut(&mut self) -> &mut HashDB {
+		self
+	}
+
+	fn add_to_account_cache(&mut self, _: Address, _: Option<Account>, _: bool) { }
+
+	fn cache_code(&self, hash: H256, code: Arc<Vec<u8>>) {
+		self.base.cache_code(hash, code)
+	}
+
+	fn get_cached_account(&self, _: &Address) -> Option<Option<Account>> { None }
+
+	fn get_cached<F, U>(&self, _: &Address, _: F) -> Option<U>
+		where F: FnOnce(Option<&mut Account>) -> U
+	{
+		None
+	}
+
+	fn get_cached_code(&self, hash: &H256) -> Option<Arc<Vec<u8>>> {
+		self.base.get_cached_code(hash)
+	}
+
+	fn note_non_null_account(&self, address: &Address) {
+		self.non_null.write().insert(*address);
+	}
+
+	fn is_known_null(&self, address: &Address) -> bool {
+		!self.non_null.read().contains(address) && self.base.is_known_null(address)
+	}
+}
+
+// number of independently locked parts of a `NodeCache`.
+const NODE_CACHE_SHARDS: usize = 16;
+
+/// Cache of trie nodes shared by overlays, e.g. read-only views of the state.
+///
+/// Nodes are keyed by their hash, so they are valid for any state root and never need
+/// invalidating. The cache is independent of the canonical state caches, which are
+/// updated on block import. It is split into shards by node hash, so that concurrent
+/// views mostly lock different shards.
+pub struct NodeCache {
+	shards: Vec<Arc<Mutex<MemoryLruCache<H256, Bytes>>>>,
+}
+
+impl NodeCache {
+	/// Create a new cache holding at most `size` bytes of nodes.
+	pub fn new(size: usize) -> Self {
+		let shards = (0..NODE_CACHE_SHARDS).map(|_| {
+			let shard = Arc::new(Mutex::new(MemoryLruCache::new(size / NODE_CACHE_SHARDS)));
+			pressure::global().register(&(shard.clone() as Arc<Shed>));
+			shard
+		}).collect();
+
+		NodeCache {
+			shards: shards,
+		}
+	}
+
+	fn shard(&self, key: &H256) -> &Mutex<MemoryLruCache<H256, Bytes>> {
+		&self.shards[key[0] as usize % NODE_CACHE_SHARDS]
+	}
+
+	fn get(&self, key: &H256) -> Option<DBValue> {
+		self.shard(key).lock().get_mut(key).map(|node| DBValue::from_slice(node))
+	}
+
+	fn insert(&self, key: H256, value: &DBValue) {
+		self.shard(&key).lock().insert(key, value.to_vec());
+	}
+}
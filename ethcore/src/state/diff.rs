@@ -0,0 +1,260 @@
+// Copyright 2015-2018 Parity Technologies (UK) Ltd.
+// This file is part of Parity.
+
+// Parity is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// Parity is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with Parity.  If not, see <http://www.gnu.org/licenses/>.
+
+//! Diff of two committed states.
+//!
+//! Both tries are walked node by node from the root, skipping subtrees whose hashes are equal
+//! in both, so the cost depends on the size of the change rather than the size of the state.
+//! Storage tries are only walked for accounts whose storage root differs.
+//!
+//! Fat databases keep the preimages of the hashed keys of the tries, so their diffs are by
+//! address and storage key. Other databases only know the hashes.
+
+use std::collections::BTreeMap;
+
+use ethereum_types::{Address, H256};
+use factory::Factories;
+use hash::{keccak, KECCAK_EMPTY, KECCAK_NULL_RLP};
+use hashdb::HashDB;
+use kvdb::DBValue;
+use trie::{self, TrieError};
+use trie::node::Node;
+use types::account_diff::{AccountDiff, Diff};
+use types::basic_account::BasicAccount;
+use types::state_diff::StateDiff;
+
+use super::iter::decode_storage;
+
+/// Diff of two committed states, see `diff`.
+#[derive(Debug, PartialEq, Eq, Clone)]
+pub enum TrieDiff {
+	/// Diff by address and storage key, from a fat database.
+	Fat(StateDiff),
+	/// Diff by hash of address and hash of storage key, from a database which doesn't keep
+	/// their preimages.
+	Hashed(BTreeMap<H256, AccountDiff>),
+}
+
+// a trie node below some path; the path may end within the partial key of a leaf or extension.
+// children are kept as the RLP their parent refers to them with.
+enum Subtrie {
+	// node referred to by a hash or inlined in its parent, not yet decoded.
+	Ref(Vec<u8>),
+	Empty,
+	Leaf(Vec<u8>, Vec<u8>),
+	Extension(Vec<u8>, Vec<u8>),
+	Branch(Vec<Vec<u8>>, Option<Vec<u8>>),
+}
+
+impl Subtrie {
+	fn root(root: &H256) -> Self {
+		Subtrie::Ref(::rlp::encode(root).into_vec())
+	}
+
+	fn child(rlp: &[u8]) -> Self {
+		match ::rlp::Rlp::new(rlp).is_empty() {
+			true => Subtrie::Empty,
+			false => Subtrie::Ref(rlp.to_vec()),
+		}
+	}
+
+	fn resolve(self, db: &HashDB) -> trie::Result<Self> {
+		let rlp = match self {
+			Subtrie::Ref(rlp) => rlp,
+			other => return Ok(other),
+		};
+
+		let node = match Node::try_decode_hash(&rlp) {
+			Some(ref hash) if *hash == KECCAK_NULL_RLP => return Ok(Subtrie::Empty),
+			Some(hash) => db.get(&hash).ok_or_else(|| Box::new(TrieError::IncompleteDatabase(hash)))?,
+			None => DBValue::from_slice(&rlp),
+		};
+
+		Ok(match Node::decoded(&node).map_err(|e| Box::new(TrieError::DecoderError(e)))? {
+			Node::Empty => Subtrie::Empty,
+			Node::Leaf(key, value) => Subtrie::Leaf((0..key.len()).map(|i| key.at(i)).collect(), value.to_vec()),
+			Node::Extension(key, child) => Subtrie::Extension((0..key.len()).map(|i| key.at(i)).collect(), child.to_vec()),
+			Node::Branch(children, value) => Subtrie::Branch(
+				children.iter().map(|child| child.to_vec()).collect(),
+				value.map(|value| value.to_vec()),
+			),
+		})
+	}
+
+	// value at the path of this subtrie, if it's resolved.
+	fn value(&self) -> Option<&[u8]> {
+		match *self {
+			Subtrie::Leaf(ref key, ref value) if key.is_empty() => Some(value),
+			Subtrie::Branch(_, ref value) => value.as_ref().map(|value| &value[..]),
+			_ => None,
+		}
+	}
+
+	// subtrie below `nibble`, if this one is resolved.
+	fn below(&self, nibble: u8) -> Self {
+		match *self {
+			Subtrie::Leaf(ref key, ref value) if key.first() == Some(&nibble) =>
+				Subtrie::Leaf(key[1..].to_vec(), value.clone()),
+			Subtrie::Extension(ref key, ref child) if key.first() == Some(&nibble) => match key.len() {
+				1 => Subtrie::child(child),
+				_ => Subtrie::Extension(key[1..].to_vec(), child.clone()),
+			},
+			Subtrie::Branch(ref children, _) => Subtrie::child(&children[nibble as usize]),
+			_ => Subtrie::Empty,
+		}
+	}
+}
+
+// hashed key of the value at the end of `path` followed by `rest`.
+fn key_of(path: &[u8], rest: &[u8]) -> H256 {
+	let nibbles: Vec<u8> = path.iter().chain(rest).cloned().collect();
+	let bytes: Vec<u8> = nibbles.chunks(2).map(|pair| (pair[0] << 4) | pair.get(1).cloned().unwrap_or(0)).collect();
+	H256::from_slice(&bytes)
+}
+
+// walk two subtries at `path` together, calling `f` for every key whose value differs.
+fn diff_subtries<F>(db: &HashDB, pre: Subtrie, post: Subtrie, path: &mut Vec<u8>, f: &mut F) -> trie::Result<()>
+	where F: FnMut(H256, Option<&[u8]>, Option<&[u8]>) -> trie::Result<()>
+{
+	if let (&Subtrie::Ref(ref pre), &Subtrie::Ref(ref post)) = (&pre, &post) {
+		if pre == post {
+			return Ok(());
+		}
+	}
+
+	let pre = pre.resolve(db)?;
+	let post = post.resolve(db)?;
+	match (&pre, &post) {
+		(&Subtrie::Empty, &Subtrie::Empty) => return Ok(()),
+		(&Subtrie::Leaf(ref key, ref value), &Subtrie::Empty) => return f(key_of(path, key), Some(&value[..]), None),
+		(&Subtrie::Empty, &Subtrie::Leaf(ref key, ref value)) => return f(key_of(path, key), None, Some(&value[..])),
+		(&Subtrie::Leaf(ref pre_key, ref pre_value), &Subtrie::Leaf(ref post_key, ref post_value)) if pre_key == post_key => {
+			if pre_value != post_value {
+				f(key_of(path, pre_key), Some(&pre_value[..]), Some(&post_value[..]))?;
+			}
+			return Ok(());
+		},
+		_ => {},
+	}
+
+	if pre.value() != post.value() {
+		f(key_of(path, &[]), pre.value(), post.value())?;
+	}
+
+	for nibble in 0..16 {
+		path.push(nibble);
+		diff_subtries(db, pre.below(nibble), post.below(nibble), path, f)?;
+		path.pop();
+	}
+	Ok(())
+}
+
+// walk the tries at `pre` and `post` together, calling `f` for every hashed key whose value differs.
+fn diff_tries<F>(db: &HashDB, pre: &H256, post: &H256, mut f: F) -> trie::Result<()>
+	where F: FnMut(H256, Option<&[u8]>, Option<&[u8]>) -> trie::Result<()>
+{
+	diff_subtries(db, Subtrie::root(pre), Subtrie::root(post), &mut Vec::new(), &mut f)
+}
+
+// key a fat trie stored under `hash`.
+fn preimage(db: &HashDB, hash: &H256) -> trie::Result<DBValue> {
+	let aux = keccak(hash);
+	db.get(&aux).ok_or_else(|| Box::new(TrieError::IncompleteDatabase(aux)))
+}
+
+fn diff_of<T: Eq>(pre: Option<T>, post: Option<T>) -> Diff<T> {
+	match (pre, post) {
+		(None, Some(post)) => Diff::Born(post),
+		(Some(pre), None) => Diff::Died(pre),
+		(Some(pre), Some(post)) => Diff::new(pre, post),
+		(None, None) => Diff::Same,
+	}
+}
+
+fn account_diff(db: &HashDB, factories: &Factories, address_hash: H256, pre: Option<BasicAccount>, post: Option<BasicAccount>) -> trie::Result<Option<AccountDiff>> {
+	let account_db = factories.accountdb.readonly(db, address_hash);
+	let code = |account: &BasicAccount| -> trie::Result<Vec<u8>> {
+		if account.code_hash == KECCAK_EMPTY {
+			return Ok(Vec::new());
+		}
+		account_db.as_hashdb().get(&account.code_hash)
+			.map(|code| code.into_vec())
+			.ok_or_else(|| Box::new(TrieError::IncompleteDatabase(account.code_hash)))
+	};
+
+	let pre_root = pre.as_ref().map_or(KECCAK_NULL_RLP, |account| account.storage_root);
+	let post_root = post.as_ref().map_or(KECCAK_NULL_RLP, |account| account.storage_root);
+	// like `diff_pod`, slots of an account which lives on change from or to zero.
+	let lives = pre.is_some() && post.is_some();
+	let fat = factories.trie.is_fat();
+	let mut storage = BTreeMap::new();
+	diff_tries(account_db.as_hashdb(), &pre_root, &post_root, |key_hash, pre, post| {
+		let key = match fat {
+			true => H256::from_slice(&preimage(account_db.as_hashdb(), &key_hash)?),
+			false => key_hash,
+		};
+		let pre = pre.map(decode_storage);
+		let post = post.map(decode_storage);
+		let diff = match lives {
+			true => Diff::new(pre.unwrap_or_default(), post.unwrap_or_default()),
+			false => diff_of(pre, post),
+		};
+		storage.insert(key, diff);
+		Ok(())
+	})?;
+
+	let pre_code = match pre { Some(ref account) => Some(code(account)?), None => None };
+	let post_code = match post { Some(ref account) => Some(code(account)?), None => None };
+	let diff = AccountDiff {
+		balance: diff_of(pre.as_ref().map(|account| account.balance), post.as_ref().map(|account| account.balance)),
+		nonce: diff_of(pre.as_ref().map(|account| account.nonce), post.as_ref().map(|account| account.nonce)),
+		code: diff_of(pre_code, post_code),
+		storage: storage,
+	};
+
+	if diff.balance.is_same() && diff.nonce.is_same() && diff.code.is_same() && diff.storage.is_empty() {
+		Ok(None)
+	} else {
+		Ok(Some(diff))
+	}
+}
+
+/// Diff of the states at `pre` and `post`, reporting created, deleted and modified accounts
+/// and storage.
+///
+/// Addresses and storage keys are only known to fat databases, the diff is by their hashes
+/// otherwise.
+pub fn diff(pre: &H256, post: &H256, db: &HashDB, factories: &Factories) -> trie::Result<TrieDiff> {
+	let mut raw = BTreeMap::new();
+	diff_tries(db, pre, post, |address_hash, pre, post| {
+		let pre = pre.map(|value| ::rlp::decode(value).expect("decoding db value failed"));
+		let post = post.map(|value| ::rlp::decode(value).expect("decoding db value failed"));
+		if let Some(diff) = account_diff(db, factories, address_hash, pre, post)? {
+			raw.insert(address_hash, diff);
+		}
+		Ok(())
+	})?;
+
+	if !factories.trie.is_fat() {
+		return Ok(TrieDiff::Hashed(raw));
+	}
+
+	let mut by_address = BTreeMap::new();
+	for (address_hash, diff) in raw {
+		by_address.insert(Address::from_slice(&preimage(db, &address_hash)?), diff);
+	}
+	Ok(TrieDiff::Fat(StateDiff { raw: by_address }))
+}
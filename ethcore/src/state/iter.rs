@@ -0,0 +1,213 @@
+// Copyright 2015-2018 Parity Technologies (UK) Ltd.
+// This file is part of Parity.
+
+// Parity is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// Parity is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with Parity.  If not, see <http://www.gnu.org/licenses/>.
+
+//! Streaming iterators over the accounts and storage of a committed state.
+//!
+//! The tries are read in batches, re-opening the trie and seeking past the last
+//! key for every batch, so the iterators don't hold a borrow of the trie and
+//! only keep a single batch in memory.
+
+use std::collections::VecDeque;
+
+use ethereum_types::{H256, U256};
+use hashdb::HashDB;
+use kvdb::DBValue;
+use trie::{self, Trie, TrieDB};
+use types::basic_account::BasicAccount;
+
+use super::{Backend, State};
+
+// number of trie items read at once.
+const BATCH_SIZE: usize = 1024;
+
+// read up to `BATCH_SIZE` items of the trie at `root` following the key `after`.
+fn read_batch(db: &HashDB, root: &H256, after: Option<&[u8]>) -> trie::Result<VecDeque<(Vec<u8>, DBValue)>> {
+	let trie = TrieDB::new(db, root)?;
+	let mut iter = trie.iter()?;
+	if let Some(after) = after {
+		iter.seek(after)?;
+	}
+
+	let mut batch = VecDeque::with_capacity(BATCH_SIZE);
+	for item in iter {
+		let (key, value) = item?;
+		if after.map_or(false, |after| after == &key[..]) {
+			continue;
+		}
+
+		batch.push_back((key, value));
+		if batch.len() == BATCH_SIZE {
+			break;
+		}
+	}
+	Ok(batch)
+}
+
+// decode a value of the storage trie.
+pub(super) fn decode_storage(value: &[u8]) -> H256 {
+	let value: U256 = ::rlp::decode(value).expect("decoding db value failed");
+	value.into()
+}
+
+/// A page of an account's storage, as returned by `State::storage_range`.
+#[derive(Debug, PartialEq)]
+pub struct StorageRange {
+	/// `(keccak(key), value)` pairs in trie order.
+	pub storage: Vec<(H256, H256)>,
+	/// Hashed key the next page starts at, if there are more items.
+	pub next_key: Option<H256>,
+}
+
+// read up to `max` items of the storage trie at `root`, starting at the key `start`.
+pub(super) fn storage_range(db: &HashDB, root: &H256, start: &H256, max: usize) -> trie::Result<StorageRange> {
+	let trie = TrieDB::new(db, root)?;
+	let mut iter = trie.iter()?;
+	iter.seek(start)?;
+
+	let mut range = StorageRange {
+		storage: Vec::new(),
+		next_key: None,
+	};
+	for item in iter {
+		let (key, value) = item?;
+		let key = H256::from_slice(&key);
+		if range.storage.len() == max {
+			range.next_key = Some(key);
+			break;
+		}
+		range.storage.push((key, decode_storage(&value)));
+	}
+	Ok(range)
+}
+
+/// Position in a trie, fetching items batch by batch.
+struct Cursor {
+	root: H256,
+	last: Option<Vec<u8>>,
+	batch: VecDeque<(Vec<u8>, DBValue)>,
+	done: bool,
+}
+
+impl Cursor {
+	fn new(root: H256) -> Self {
+		Cursor {
+			root: root,
+			last: None,
+			batch: VecDeque::new(),
+			done: false,
+		}
+	}
+
+	fn next(&mut self, db: &HashDB) -> Option<trie::Result<(Vec<u8>, DBValue)>> {
+		if self.batch.is_empty() && !self.done {
+			match read_batch(db, &self.root, self.last.as_ref().map(|key| &key[..])) {
+				Ok(batch) => {
+					self.done = batch.len() < BATCH_SIZE;
+					self.batch = batch;
+				},
+				Err(e) => {
+					self.done = true;
+					return Some(Err(e));
+				},
+			}
+		}
+
+		let (key, value) = self.batch.pop_front()?;
+		self.last = Some(key.clone());
+		Some(Ok((key, value)))
+	}
+}
+
+/// Iterator over the accounts of a state, yielding `(keccak(address), account)` in trie order.
+pub struct AccountsIter<'a, B: 'a> {
+	state: &'a State<B>,
+	cursor: Cursor,
+}
+
+impl<'a, B: Backend> AccountsIter<'a, B> {
+	pub(super) fn new(state: &'a State<B>) -> Self {
+		AccountsIter {
+			state: state,
+			cursor: Cursor::new(state.root),
+		}
+	}
+}
+
+impl<'a, B: Backend> Iterator for AccountsIter<'a, B> {
+	type Item = trie::Result<(H256, BasicAccount)>;
+
+	fn next(&mut self) -> Option<Self::Item> {
+		self.cursor.next(self.state.db.as_hashdb()).map(|item| item.map(|(key, value)| {
+			let account = ::rlp::decode(&value).expect("decoding db value failed");
+			(H256::from_slice(&key), account)
+		}))
+	}
+}
+
+/// Iterator over the storage of an account, yielding `(keccak(key), value)` in trie order.
+pub struct StorageIter<'a, B: 'a> {
+	state: &'a State<B>,
+	account_key: H256,
+	cursor: Cursor,
+}
+
+impl<'a, B: Backend> StorageIter<'a, B> {
+	pub(super) fn new(state: &'a State<B>, account_key: H256, storage_root: H256) -> Self {
+		StorageIter {
+			state: state,
+			account_key: account_key,
+			cursor: Cursor::new(storage_root),
+		}
+	}
+}
+
+impl<'a, B: Backend> Iterator for StorageIter<'a, B> {
+	type Item = trie::Result<(H256, H256)>;
+
+	fn next(&mut self) -> Option<Self::Item> {
+		let account_db = self.state.factories.accountdb.readonly(self.state.db.as_hashdb(), self.account_key);
+		self.cursor.next(account_db.as_hashdb()).map(|item| item.map(|(key, value)| {
+			(H256::from_slice(&key), decode_storage(&value))
+		}))
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use ethereum_types::{Address, H256};
+	use hash::keccak;
+	use test_helpers::get_temp_state;
+	use super::BATCH_SIZE;
+
+	#[test]
+	fn iterates_storage_across_batches() {
+		let a: Address = 0xa.into();
+		let mut state = get_temp_state();
+		let count = BATCH_SIZE * 2 + 1;
+		for i in 0..count {
+			state.set_storage(&a, H256::from(i as u64), H256::from(i as u64 + 1)).unwrap();
+		}
+		state.commit().unwrap();
+
+		let storage: Vec<_> = state.storage_iter(&a).unwrap().map(|item| item.unwrap()).collect();
+		assert_eq!(storage.len(), count);
+		// in trie order, without repeating the items at the batch boundaries.
+		assert!(storage.windows(2).all(|pair| pair[0].0 < pair[1].0));
+		for i in 0..count {
+			assert!(storage.contains(&(keccak(&H256::from(i as u64)), H256::from(i as u64 + 1))));
+		}
+	}
+}
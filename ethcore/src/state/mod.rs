@@ -24,13 +24,13 @@ use std::collections::hash_map::Entry;
 use std::collections::{HashMap, BTreeMap, BTreeSet, HashSet};
 use std::fmt;
 use std::sync::Arc;
-use hash::{KECCAK_NULL_RLP, KECCAK_EMPTY};
+use hash::{keccak, KECCAK_NULL_RLP, KECCAK_EMPTY};
 
 use receipt::{Receipt, TransactionOutcome};
 use machine::EthereumMachine as Machine;
-use vm::EnvInfo;
+use vm::{self, EnvInfo};
 use error::Error;
-use executive::{Executive, TransactOptions};
+use executive::{BlockExecutor, TransactOptions};
 use factory::Factories;
 use trace::{self, FlatTrace, VMTrace};
 use pod_account::*;
@@ -41,9 +41,11 @@ use types::state_diff::StateDiff;
 use transaction::SignedTransaction;
 use state_db::StateDB;
 use factory::VmFactory;
+use spec::Spec;
 
 use ethereum_types::{H256, U256, Address};
 use hashdb::{HashDB, AsHashDB};
+use memorydb::MemoryDB;
 use kvdb::DBValue;
 use bytes::Bytes;
 
@@ -53,12 +55,16 @@ use trie::recorder::Recorder;
 
 
 mod account;
+mod diff;
+mod iter;
 mod substate;
 
 pub mod backend;
 
 pub use self::account::Account;
 pub use self::backend::Backend;
+pub use self::diff::{diff, TrieDiff};
+pub use self::iter::{AccountsIter, StorageIter, StorageRange};
 pub use self::substate::Substate;
 
 /// Used to return information about an `State::apply` operation.
@@ -70,7 +76,9 @@ pub struct ApplyOutcome<T, V> {
 	/// The trace for the applied transaction, empty if tracing was not produced.
 	pub trace: Vec<T>,
 	/// The VM trace for the applied transaction, None if tracing was not produced.
-	pub vm_trace: Option<V>
+	pub vm_trace: Option<V>,
+	/// The VM error which caused the transaction to fail, None if it succeeded.
+	pub exception: Option<vm::Error>,
 }
 
 /// Result type for the execution ("application") of a transaction.
@@ -314,8 +322,50 @@ pub struct State<B: Backend> {
 	checkpoints: RefCell<Vec<HashMap<Address, Option<AccountEntry>>>>,
 	account_start_nonce: U256,
 	factories: Factories,
+	// Accounts and storage keys accessed so far, if tracking is enabled.
+	accessed: RefCell<Option<AccessList>>,
+	// Size of the nodes written and freed by commits so far.
+	size_delta: StateSizeDelta,
 }
 
+/// Accessed accounts along with their accessed storage keys.
+pub type AccessList = BTreeMap<Address, BTreeSet<H256>>;
+
+/// Changes collected by `State::commit_into`, written to the backend by `State::apply_batch`.
+pub struct CommitBatch {
+	nodes: MemoryDB,
+	committed: Committed,
+}
+
+// code deployed and accounts made non-null by a commit.
+#[derive(Default)]
+struct Committed {
+	code: Vec<(H256, Arc<Bytes>)>,
+	non_null: Vec<Address>,
+}
+
+/// Trie nodes and code written to and freed from the state database by commits.
+///
+/// Entries are counted once per reference: an entry shared by several tries, or written again
+/// with the same value, is counted every time. The size of freed entries is only known once
+/// pruning deletes them, see `JournalDB::freed_bytes`.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub struct StateSizeDelta {
+	/// Number of entries written.
+	pub nodes_added: u64,
+	/// Bytes written.
+	pub bytes_added: u64,
+	/// Number of entries freed.
+	pub nodes_freed: u64,
+}
+
+/// State buffering every write in memory over the database of another state.
+/// See `State::overlay`.
+pub type OverlayState<'a, B> = State<backend::Overlay<&'a B>>;
+
+/// State whose database is shared by its clones, see `State::into_copy_on_write`.
+pub type CopyOnWriteState<B> = State<backend::Overlay<Arc<B>>>;
+
 #[derive(Copy, Clone)]
 enum RequireCache {
 	None,
@@ -356,6 +406,9 @@ impl<B: Backend> StateInfo for State<B> {
 	fn code(&self, address: &Address) -> trie::Result<Option<Arc<Bytes>>> { State::code(self, address) }
 }
 
+/// Number of accounts committed to the trie at once when populating the state.
+const ACCOUNTS_PER_COMMIT: usize = 10_000;
+
 const SEC_TRIE_DB_UNWRAP_STR: &'static str = "A state can only be created with valid root. Creating a SecTrieDB with a valid root will not fail. \
 			 Therefore creating a SecTrieDB with this state's root will not fail.";
 
@@ -376,6 +429,8 @@ impl<B: Backend> State<B> {
 			checkpoints: RefCell::new(Vec::new()),
 			account_start_nonce: account_start_nonce,
 			factories: factories,
+			accessed: RefCell::new(None),
+			size_delta: StateSizeDelta::default(),
 		}
 	}
 
@@ -391,17 +446,58 @@ impl<B: Backend> State<B> {
 			cache: RefCell::new(HashMap::new()),
 			checkpoints: RefCell::new(Vec::new()),
 			account_start_nonce: account_start_nonce,
-			factories: factories
+			factories: factories,
+			accessed: RefCell::new(None),
+			size_delta: StateSizeDelta::default(),
 		};
 
 		Ok(state)
 	}
 
+	/// Creates the genesis state of `spec` on top of `db`: the accounts of the spec are inserted
+	/// along with their code and storage, and the contract constructors of the spec are run.
+	pub fn from_genesis(spec: &Spec, db: B, factories: Factories) -> Result<State<B>, Error> {
+		let db = spec.ensure_db_good(db, &factories)?;
+		let state = State::from_existing(db, spec.state_root(), spec.engine.account_start_nonce(0), factories)?;
+		Ok(state)
+	}
+
 	/// Get a VM factory that can execute on this state.
 	pub fn vm_factory(&self) -> VmFactory {
 		self.factories.vm.clone()
 	}
 
+	/// Replace the VM factory executing on this state. Returns the previous one.
+	pub fn set_vm_factory(&mut self, factory: VmFactory) -> VmFactory {
+		::std::mem::replace(&mut self.factories.vm, factory)
+	}
+
+	/// Speculative copy of this state, including its uncommitted changes.
+	/// Changes made to the copy are kept in memory and never reach this state or its database.
+	pub fn overlay(&self) -> OverlayState<B> where B: Sync {
+		self.with_overlay(backend::Overlay::new(&self.db))
+	}
+
+	// copy of this state over given overlay backend, including its uncommitted changes.
+	fn with_overlay<D>(&self, db: backend::Overlay<D>) -> State<backend::Overlay<D>>
+		where backend::Overlay<D>: Backend
+	{
+		let cache = self.cache.borrow().iter()
+			.filter_map(|(address, entry)| entry.clone_if_dirty().map(|entry| (*address, entry)))
+			.collect();
+
+		State {
+			db: db,
+			root: self.root,
+			cache: RefCell::new(cache),
+			checkpoints: RefCell::new(Vec::new()),
+			account_start_nonce: self.account_start_nonce,
+			factories: self.factories.clone(),
+			accessed: RefCell::new(self.accessed.borrow().clone()),
+			size_delta: self.size_delta,
+		}
+	}
+
 	/// Create a recoverable checkpoint of this state.
 	pub fn checkpoint(&mut self) {
 		self.checkpoints.get_mut().push(HashMap::new());
@@ -542,6 +638,8 @@ impl<B: Backend> State<B> {
 
 	/// Mutate storage of account `address` so that it is `value` for `key`.
 	pub fn storage_at(&self, address: &Address, key: &H256) -> trie::Result<H256> {
+		self.note_access(address, Some(key));
+
 		// Storage key search and update works like this:
 		// 1. If there's an entry for the account in the local cache check for the key and return it if found.
 		// 2. If there's an entry for the account in the global cache check for the key or load it into that account.
@@ -659,6 +757,55 @@ impl<B: Backend> State<B> {
 		self.require(a, false).map(|mut x| x.inc_nonce())
 	}
 
+	/// Get the value of storage at `key` of account `address` as it was when the earliest
+	/// active checkpoint was created, i.e. at the start of the transaction being executed.
+	/// Falls back to the current value if there is no checkpoint.
+	pub fn original_storage_at(&self, address: &Address, key: &H256) -> trie::Result<H256> {
+		{
+			let checkpoints = self.checkpoints.borrow();
+			// the earliest checkpoint which noted the account holds its state before the first change.
+			match checkpoints.iter().filter_map(|checkpoint| checkpoint.get(address)).next() {
+				Some(&Some(ref entry)) => return match entry.account {
+					Some(ref account) => {
+						let account_db = self.factories.accountdb.readonly(self.db.as_hashdb(), account.address_hash(address));
+						account.storage_at(account_db.as_hashdb(), key)
+					},
+					None => Ok(H256::new()),
+				},
+				// account was not cached locally, so it is unchanged since it was loaded.
+				Some(&None) => return self.base_storage_at(address, key),
+				None => {},
+			}
+		}
+
+		self.storage_at(address, key)
+	}
+
+	// Get the value of storage at `key` of account `address`, bypassing the local cache.
+	fn base_storage_at(&self, address: &Address, key: &H256) -> trie::Result<H256> {
+		let trie_res = self.db.get_cached(address, |acc| match acc {
+			None => Ok(H256::new()),
+			Some(a) => {
+				let account_db = self.factories.accountdb.readonly(self.db.as_hashdb(), a.address_hash(address));
+				a.storage_at(account_db.as_hashdb(), key)
+			}
+		});
+
+		if let Some(res) = trie_res {
+			return res;
+		}
+
+		if self.db.is_known_null(address) { return Ok(H256::zero()) }
+
+		let db = self.factories.trie.readonly(self.db.as_hashdb(), &self.root).expect(SEC_TRIE_DB_UNWRAP_STR);
+		let from_rlp = |b: &[u8]| Account::from_rlp(b).expect("decoding db value failed");
+		let maybe_acc = db.get_with(address, from_rlp)?;
+		maybe_acc.as_ref().map_or(Ok(H256::new()), |a| {
+			let account_db = self.factories.accountdb.readonly(self.db.as_hashdb(), a.address_hash(address));
+			a.storage_at(account_db.as_hashdb(), key)
+		})
+	}
+
 	/// Mutate storage of account `a` so that it is `value` for `key`.
 	pub fn set_storage(&mut self, a: &Address, key: H256, value: H256) -> trie::Result<()> {
 		trace!(target: "state", "set_storage({}:{:x} to {:x})", a, key, value);
@@ -736,6 +883,7 @@ impl<B: Backend> State<B> {
 			output,
 			trace: e.trace,
 			vm_trace: e.vm_trace,
+			exception: e.exception,
 		})
 	}
 
@@ -746,11 +894,11 @@ impl<B: Backend> State<B> {
 	fn execute<T, V>(&mut self, env_info: &EnvInfo, machine: &Machine, t: &SignedTransaction, options: TransactOptions<T, V>, virt: bool)
 		-> Result<Executed<T::Output, V::Output>, ExecutionError> where T: trace::Tracer, V: trace::VMTracer,
 	{
-		let mut e = Executive::new(self, env_info, machine);
+		let mut executor = BlockExecutor::new(env_info.clone());
 
 		match virt {
-			true => e.transact_virtual(t, options),
-			false => e.transact(t, options),
+			true => executor.transact_virtual(self, machine, t, options),
+			false => executor.transact(self, machine, t, options),
 		}
 	}
 
@@ -761,24 +909,78 @@ impl<B: Backend> State<B> {
 
 	/// Commits our cached account changes into the trie.
 	pub fn commit(&mut self) -> Result<(), Error> {
+		let committed = {
+			let mut db = backend::Measured::new(self.db.as_hashdb_mut(), &mut self.size_delta);
+			Self::commit_accounts(&mut db, &mut self.cache.borrow_mut(), &self.factories, &mut self.root)?
+		};
+		self.note_committed(committed);
+		Ok(())
+	}
+
+	/// Commit accounts to a batch rather than to the backend, computing the new state root.
+	///
+	/// The trie nodes inserted and removed by the commit, along with the code it deployed and
+	/// the accounts it made non-null, only reach the backend with `apply_batch`, so the caller
+	/// can write them once the block has been verified. Until then the state must not be read
+	/// from the backend.
+	pub fn commit_into(&mut self) -> Result<CommitBatch, Error> {
+		let mut nodes = MemoryDB::new();
+		let committed = {
+			let mut db = backend::Batch::new(self.db.as_hashdb(), &mut nodes);
+			Self::commit_accounts(&mut db, &mut self.cache.borrow_mut(), &self.factories, &mut self.root)?
+		};
+
+		Ok(CommitBatch {
+			nodes: nodes,
+			committed: committed,
+		})
+	}
+
+	/// Write a batch collected by `commit_into` to the backend.
+	pub fn apply_batch(&mut self, batch: CommitBatch) {
+		let CommitBatch { mut nodes, committed } = batch;
+		{
+			let mut db = backend::Measured::new(self.db.as_hashdb_mut(), &mut self.size_delta);
+			for (key, (value, rc)) in nodes.drain() {
+				if rc > 0 {
+					for _ in 0..rc {
+						db.emplace(key, value.clone());
+					}
+				} else {
+					for _ in rc..0 {
+						db.remove(&key);
+					}
+				}
+			}
+		}
+		self.note_committed(committed);
+	}
+
+	// commits the dirty accounts to `db` and the new account trie root to `root`.
+	fn commit_accounts(db: &mut HashDB, accounts: &mut HashMap<Address, AccountEntry>, factories: &Factories, root: &mut H256) -> Result<Committed, Error> {
+		let mut committed = Committed::default();
+
 		// first, commit the sub trees.
-		let mut accounts = self.cache.borrow_mut();
 		for (address, ref mut a) in accounts.iter_mut().filter(|&(_, ref a)| a.is_dirty()) {
 			if let Some(ref mut account) = a.account {
 				let addr_hash = account.address_hash(address);
+				let new_code = if account.is_code_dirty() { account.code() } else { None };
 				{
-					let mut account_db = self.factories.accountdb.create(self.db.as_hashdb_mut(), addr_hash);
-					account.commit_storage(&self.factories.trie, account_db.as_hashdb_mut())?;
+					let mut account_db = factories.accountdb.create(&mut *db, addr_hash);
+					account.commit_storage(&factories.trie, account_db.as_hashdb_mut())?;
 					account.commit_code(account_db.as_hashdb_mut());
 				}
+				if let Some(code) = new_code {
+					committed.code.push((account.code_hash(), code));
+				}
 				if !account.is_empty() {
-					self.db.note_non_null_account(address);
+					committed.non_null.push(*address);
 				}
 			}
 		}
 
 		{
-			let mut trie = self.factories.trie.from_existing(self.db.as_hashdb_mut(), &mut self.root)?;
+			let mut trie = factories.trie.from_existing(db, root)?;
 			for (address, ref mut a) in accounts.iter_mut().filter(|&(_, ref a)| a.is_dirty()) {
 				a.state = AccountState::Committed;
 				match a.account {
@@ -792,7 +994,25 @@ impl<B: Backend> State<B> {
 			}
 		}
 
-		Ok(())
+		Ok(committed)
+	}
+
+	// tells the backend about the code and accounts committed to it.
+	fn note_committed(&self, committed: Committed) {
+		// pre-warm the shared code cache so newly deployed contracts are
+		// not read back from the database on their first call.
+		for (hash, code) in committed.code {
+			self.db.cache_code(hash, code);
+		}
+		for address in &committed.non_null {
+			self.db.note_non_null_account(address);
+		}
+	}
+
+	/// Size of the entries written to and freed from the database by the commits of this state
+	/// so far. For the state of a block, this is the state growth caused by the block.
+	pub fn size_delta(&self) -> StateSizeDelta {
+		self.size_delta
 	}
 
 	/// Propagate local cache into shared canonical state cache.
@@ -828,13 +1048,20 @@ impl<B: Backend> State<B> {
 		Ok(())
 	}
 
-	/// Populate the state from `accounts`.
-	/// Used for tests.
-	pub fn populate_from(&mut self, accounts: PodState) {
+	/// Populate the state from `accounts` and commit it.
+	/// Used for genesis allocations and tests.
+	pub fn populate_from<I>(&mut self, accounts: I) -> Result<(), Error> where I: IntoIterator<Item = (Address, PodAccount)> {
 		assert!(self.checkpoints.borrow().is_empty());
-		for (add, acc) in accounts.drain().into_iter() {
+		for (i, (add, acc)) in accounts.into_iter().enumerate() {
 			self.cache.borrow_mut().insert(add, AccountEntry::new_dirty(Some(Account::from_pod(acc))));
+
+			// commit full batches and drop them from the cache to keep memory bounded.
+			if (i + 1) % ACCOUNTS_PER_COMMIT == 0 {
+				self.commit()?;
+				self.cache.get_mut().clear();
+			}
 		}
+		self.commit()
 	}
 
 	/// Populate a PodAccount map from this state.
@@ -911,14 +1138,80 @@ impl<B: Backend> State<B> {
 		Ok(pod_state::diff_pod(&pod_state_pre, &pod_state_post))
 	}
 
-	// load required account data from the databases.
-	fn update_account_cache(require: RequireCache, account: &mut Account, state_db: &B, db: &HashDB) {
-		if let RequireCache::None = require {
-			return;
+	/// Returns a `StateDiff` of all accounts noted in the last checkpoint,
+	/// comparing their state at the time the checkpoint was created with the current one.
+	pub fn diff_since_checkpoint(&self) -> trie::Result<StateDiff> {
+		let originals = match self.checkpoints.borrow().last() {
+			Some(checkpoint) => checkpoint.iter()
+				.map(|(address, entry)| (*address, entry.as_ref().map(|e| e.account.as_ref().map(Account::clone_dirty))))
+				.collect::<Vec<_>>(),
+			None => return Ok(StateDiff { raw: BTreeMap::new() }),
+		};
+
+		let mut pre = BTreeMap::new();
+		let mut post = BTreeMap::new();
+		for (address, original) in originals {
+			let original = match original {
+				Some(account) => account,
+				// account was not cached when noted, so it is unchanged since the last commit.
+				None => self.base_account(&address)?,
+			};
+			let current = self.cache.borrow().get(&address).and_then(|e| e.account.as_ref().map(Account::clone_dirty));
+
+			let keys = original.iter().chain(current.iter())
+				.flat_map(|account| account.storage_changes().keys().cloned())
+				.collect::<BTreeSet<_>>();
+
+			if let Some(account) = original {
+				pre.insert(address, self.pod_account(&address, account, &keys)?);
+			}
+			if let Some(account) = current {
+				post.insert(address, self.pod_account(&address, account, &keys)?);
+			}
 		}
 
-		if account.is_cached() {
-			return;
+		Ok(pod_state::diff_pod(&PodState::from(pre), &PodState::from(post)))
+	}
+
+	// Convert given account to a `PodAccount` holding its code and the values of `keys`.
+	fn pod_account(&self, address: &Address, mut account: Account, keys: &BTreeSet<H256>) -> trie::Result<PodAccount> {
+		let accountdb = self.factories.accountdb.readonly(self.db.as_hashdb(), account.address_hash(address));
+		Self::update_account_cache(RequireCache::Code, &mut account, &self.db, accountdb.as_hashdb());
+
+		let mut storage = BTreeMap::new();
+		for key in keys {
+			storage.insert(*key, account.storage_at(accountdb.as_hashdb(), key)?);
+		}
+
+		Ok(PodAccount {
+			balance: *account.balance(),
+			nonce: *account.nonce(),
+			code: account.code().map(|code| code.to_vec()),
+			storage: storage,
+		})
+	}
+
+	// Load account `address` as of the last commit, bypassing the local cache.
+	fn base_account(&self, address: &Address) -> trie::Result<Option<Account>> {
+		if let Some(account) = self.db.get_cached_account(address) {
+			return Ok(account);
+		}
+		if self.db.is_known_null(address) {
+			return Ok(None);
+		}
+
+		let db = self.factories.trie.readonly(self.db.as_hashdb(), &self.root)?;
+		let from_rlp = |b: &[u8]| Account::from_rlp(b).expect("decoding db value failed");
+		db.get_with(address, from_rlp)
+	}
+
+	// load required account data from the databases.
+	fn update_account_cache(require: RequireCache, account: &mut Account, state_db: &B, db: &HashDB) {
+		match require {
+			RequireCache::None => return,
+			RequireCache::CodeSize if account.code_size().is_some() => return,
+			_ if account.is_cached() => return,
+			_ => {},
 		}
 
 		// if there's already code in the global cache, always cache it localy
@@ -934,9 +1227,39 @@ impl<B: Backend> State<B> {
 						state_db.cache_code(hash, code)
 					}
 				},
+				// only the size is kept, the code isn't cached for a size lookup.
 				RequireCache::CodeSize => {
 					account.cache_code_size(db);
-				}
+				},
+			}
+		}
+	}
+
+	/// Start recording accessed accounts and storage keys, discarding previous records.
+	pub fn track_accesses(&mut self) {
+		*self.accessed.borrow_mut() = Some(AccessList::new());
+	}
+
+	/// Accounts and storage keys accessed since `track_accesses` was called.
+	pub fn accessed(&self) -> Option<AccessList> {
+		self.accessed.borrow().clone()
+	}
+
+	/// Stop recording accesses, returning the records collected so far.
+	pub fn take_accesses(&mut self) -> Option<AccessList> {
+		self.accessed.borrow_mut().take()
+	}
+
+	/// Resume recording accesses on top of previously collected records.
+	pub fn resume_accesses(&mut self, accessed: AccessList) {
+		*self.accessed.borrow_mut() = Some(accessed);
+	}
+
+	fn note_access(&self, a: &Address, key: Option<&H256>) {
+		if let Some(ref mut accessed) = *self.accessed.borrow_mut() {
+			let keys = accessed.entry(*a).or_insert_with(BTreeSet::new);
+			if let Some(key) = key {
+				keys.insert(*key);
 			}
 		}
 	}
@@ -946,6 +1269,8 @@ impl<B: Backend> State<B> {
 	/// Populates local cache if nothing found.
 	fn ensure_cached<F, U>(&self, a: &Address, require: RequireCache, check_null: bool, f: F) -> trie::Result<U>
 		where F: Fn(Option<&Account>) -> U {
+		self.note_access(a, None);
+
 		// check local cache first
 		if let Some(ref mut maybe_acc) = self.cache.borrow_mut().get_mut(a) {
 			if let Some(ref mut account) = maybe_acc.account {
@@ -994,6 +1319,8 @@ impl<B: Backend> State<B> {
 	fn require_or_from<'a, F, G>(&'a self, a: &Address, require_code: bool, default: F, not_default: G) -> trie::Result<RefMut<'a, Account>>
 		where F: FnOnce() -> Account, G: FnOnce(&mut Account),
 	{
+		self.note_access(a, None);
+
 		let contains_key = self.cache.borrow().contains_key(a);
 		if !contains_key {
 			match self.db.get_cached_account(a) {
@@ -1043,6 +1370,38 @@ impl<B: Backend> State<B> {
 	}
 }
 
+// State iteration; useful for state dumps, audits and snapshots.
+impl<B: Backend> State<B> {
+	/// Iterate over all accounts in the committed state trie, in trie order.
+	/// Yields `(keccak(address), account)`; uncommitted changes are not seen.
+	pub fn accounts_iter(&self) -> AccountsIter<B> {
+		AccountsIter::new(self)
+	}
+
+	/// Iterate over the storage of an account in the committed state trie, in trie order.
+	/// Yields `(keccak(key), value)`; uncommitted changes are not seen.
+	pub fn storage_iter(&self, address: &Address) -> trie::Result<StorageIter<B>> {
+		let account_key = keccak(address);
+		let storage_root = self.committed_storage_root(&account_key)?;
+		Ok(StorageIter::new(self, account_key, storage_root))
+	}
+
+	/// Get up to `max` storage items of an account in the committed state trie, starting
+	/// at the hashed key `start`. The returned `next_key` is where the following page starts.
+	pub fn storage_range(&self, address: &Address, start: &H256, max: usize) -> trie::Result<StorageRange> {
+		let account_key = keccak(address);
+		let storage_root = self.committed_storage_root(&account_key)?;
+		let account_db = self.factories.accountdb.readonly(self.db.as_hashdb(), account_key);
+		iter::storage_range(account_db.as_hashdb(), &storage_root, start, max)
+	}
+
+	fn committed_storage_root(&self, account_key: &H256) -> trie::Result<H256> {
+		let trie = TrieDB::new(self.db.as_hashdb(), &self.root)?;
+		let from_rlp = |b: &[u8]| -> BasicAccount { ::rlp::decode(b).expect("decoding db value failed") };
+		Ok(trie.get_with(account_key, from_rlp)?.map_or(KECCAK_NULL_RLP, |account| account.storage_root))
+	}
+}
+
 // State proof implementations; useful for light client protocols.
 impl<B: Backend> State<B> {
 	/// Prove an account's existence or nonexistence in the state trie.
@@ -1117,10 +1476,35 @@ impl Clone for State<StateDB> {
 			checkpoints: RefCell::new(Vec::new()),
 			account_start_nonce: self.account_start_nonce.clone(),
 			factories: self.factories.clone(),
+			accessed: RefCell::new(self.accessed.borrow().clone()),
+			size_delta: self.size_delta,
 		}
 	}
 }
 
+impl<B: Backend + Sync> State<B> {
+	/// Turn this state into one which can be cloned cheaply, e.g. to run many variants of
+	/// a transaction against the same base state. Clones share the database of this state.
+	pub fn into_copy_on_write(self) -> CopyOnWriteState<B> {
+		State {
+			db: backend::Overlay::new(Arc::new(self.db)),
+			root: self.root,
+			cache: self.cache,
+			checkpoints: self.checkpoints,
+			account_start_nonce: self.account_start_nonce,
+			factories: self.factories,
+			accessed: self.accessed,
+			size_delta: self.size_delta,
+		}
+	}
+}
+
+impl<B: Backend + Sync> Clone for CopyOnWriteState<B> {
+	fn clone(&self) -> Self {
+		self.with_overlay(self.db.clone())
+	}
+}
+
 #[cfg(test)]
 mod tests {
 	use std::sync::Arc;
@@ -1189,6 +1573,55 @@ mod tests {
 		assert_eq!(result.trace, expected_trace);
 	}
 
+	#[test]
+	fn should_not_write_through_overlay() {
+		let a = Address::zero();
+		let b = Address::from(1);
+
+		let mut state = get_temp_state();
+		state.add_balance(&a, &100.into(), CleanupMode::NoEmpty).unwrap();
+		state.commit().unwrap();
+		// uncommitted changes are carried over.
+		state.add_balance(&b, &7.into(), CleanupMode::NoEmpty).unwrap();
+		let root = *state.root();
+
+		let overlay_root = {
+			let mut overlay = state.overlay();
+			assert_eq!(overlay.balance(&b).unwrap(), 7.into());
+			overlay.sub_balance(&a, &40.into(), &mut CleanupMode::NoEmpty).unwrap();
+			overlay.init_code(&b, vec![1, 2, 3]).unwrap();
+			overlay.commit().unwrap();
+			assert_eq!(overlay.balance(&a).unwrap(), 60.into());
+			*overlay.root()
+		};
+
+		assert!(overlay_root != root);
+		assert!(!state.db.as_hashdb().contains(&overlay_root));
+		assert_eq!(state.root(), &root);
+		assert_eq!(state.balance(&a).unwrap(), 100.into());
+		assert_eq!(state.code(&b).unwrap(), None);
+	}
+
+	#[test]
+	fn should_share_nodes_between_views() {
+		use self::backend::{NodeCache, Overlay, Basic};
+		use memorydb::MemoryDB;
+
+		let a = Address::zero();
+		let mut state = State::new(Basic(MemoryDB::new()), 0.into(), Default::default());
+		state.add_balance(&a, &69.into(), CleanupMode::NoEmpty).unwrap();
+		state.commit().unwrap();
+		let (root, Basic(db)) = state.drop();
+
+		let nodes = Arc::new(NodeCache::new(1024 * 1024));
+		let first = State::from_existing(Overlay::with_node_cache(Arc::new(Basic(db.clone())), nodes.clone()), root, 0.into(), Default::default()).unwrap();
+		assert_eq!(first.balance(&a).unwrap(), 69.into());
+
+		// the second view only has the nodes cached by the first one.
+		let second = State::from_existing(Overlay::with_node_cache(Arc::new(Basic(MemoryDB::new())), nodes), root, 0.into(), Default::default()).unwrap();
+		assert_eq!(second.balance(&a).unwrap(), 69.into());
+	}
+
 	#[test]
 	fn should_work_when_cloned() {
 		init_log();
@@ -2004,6 +2437,226 @@ mod tests {
 		assert_eq!(s.storage_at(&a, &H256::from(&U256::from(1u64))).unwrap(), H256::from(&U256::from(69u64)));
 	}
 
+	#[test]
+	fn iterates_committed_accounts_and_storage() {
+		let a: Address = 0xa.into();
+		let b: Address = 0xb.into();
+		let mut state = get_temp_state();
+		state.add_balance(&a, &U256::from(69u64), CleanupMode::NoEmpty).unwrap();
+		state.set_storage(&b, 0x1.into(), 0x2.into()).unwrap();
+		state.set_storage(&b, 0x3.into(), 0x4.into()).unwrap();
+		state.commit().unwrap();
+
+		let mut accounts: Vec<_> = state.accounts_iter().map(|item| item.unwrap()).collect();
+		accounts.sort_by_key(|&(ref key, _)| key.clone());
+		let mut expected = vec![(keccak(&a), 69u64), (keccak(&b), 0)];
+		expected.sort();
+		assert_eq!(accounts.iter().map(|&(ref key, ref account)| (key.clone(), account.balance.low_u64())).collect::<Vec<_>>(), expected);
+
+		let storage: Vec<_> = state.storage_iter(&b).unwrap().map(|item| item.unwrap()).collect();
+		assert_eq!(storage.len(), 2);
+		assert!(storage.contains(&(keccak(&H256::from(0x1)), 0x2.into())));
+		assert!(storage.contains(&(keccak(&H256::from(0x3)), 0x4.into())));
+		assert_eq!(state.storage_iter(&a).unwrap().count(), 0);
+	}
+
+	#[test]
+	fn copy_on_write_clones_are_independent() {
+		let a = Address::zero();
+		let mut state = get_temp_state();
+		state.add_balance(&a, &U256::from(69u64), CleanupMode::NoEmpty).unwrap();
+		state.commit().unwrap();
+		let base = state.into_copy_on_write();
+
+		let mut variant = base.clone();
+		variant.add_balance(&a, &U256::from(1u64), CleanupMode::NoEmpty).unwrap();
+		variant.commit().unwrap();
+
+		let copy = variant.clone();
+		assert_eq!(copy.balance(&a).unwrap(), U256::from(70u64));
+		assert_eq!(base.clone().balance(&a).unwrap(), U256::from(69u64));
+		assert_eq!(base.balance(&a).unwrap(), U256::from(69u64));
+		assert!(base.root() != copy.root());
+	}
+
+	#[test]
+	fn copy_on_write_clones_note_non_null_accounts() {
+		let a = Address::zero();
+		let b = Address::from(1u64);
+		let mut state = get_temp_state();
+		state.add_balance(&a, &U256::from(69u64), CleanupMode::NoEmpty).unwrap();
+		state.commit().unwrap();
+		let base = state.into_copy_on_write();
+		assert!(base.db.is_known_null(&b));
+
+		let mut variant = base.clone();
+		variant.add_balance(&b, &U256::from(1u64), CleanupMode::NoEmpty).unwrap();
+		variant.commit().unwrap();
+
+		assert!(!variant.clone().db.is_known_null(&b));
+
+		let other = base.clone();
+		assert!(other.db.is_known_null(&b));
+		assert_eq!(other.balance(&b).unwrap(), U256::zero());
+		assert_eq!(variant.clone().balance(&b).unwrap(), U256::from(1u64));
+	}
+
+	#[test]
+	fn overlays_do_not_note_accounts_in_base_bloom() {
+		let b = Address::from(1u64);
+		let state = get_temp_state();
+		{
+			let mut overlay = state.overlay();
+			overlay.add_balance(&b, &U256::from(1u64), CleanupMode::NoEmpty).unwrap();
+			overlay.commit().unwrap();
+			assert!(!overlay.db.is_known_null(&b));
+		}
+
+		// the copy shares the account bloom of `state`.
+		let mut copy = state.clone().into_copy_on_write();
+		copy.add_balance(&b, &U256::from(1u64), CleanupMode::NoEmpty).unwrap();
+		copy.commit().unwrap();
+		assert!(!copy.db.is_known_null(&b));
+
+		assert!(state.db.is_known_null(&b));
+	}
+
+	#[test]
+	fn commit_into_defers_writes_to_batch() {
+		let a = Address::zero();
+		let populate = |state: &mut State<StateDB>| {
+			state.add_balance(&a, &U256::from(69u64), CleanupMode::NoEmpty).unwrap();
+			state.set_storage(&a, H256::from(1u64), H256::from(2u64)).unwrap();
+			state.init_code(&a, vec![1, 2, 3]).unwrap();
+		};
+		let mut expected = get_temp_state();
+		populate(&mut expected);
+		expected.commit().unwrap();
+
+		let mut state = get_temp_state();
+		populate(&mut state);
+		let code_hash = state.code_hash(&a).unwrap();
+
+		let batch = state.commit_into().unwrap();
+		assert_eq!(state.root(), expected.root());
+		assert!(!state.db.as_hashdb().contains(state.root()));
+		assert!(state.db.get_cached_code(&code_hash).is_none());
+		assert!(state.db.is_known_null(&a));
+
+		state.apply_batch(batch);
+		assert!(state.db.as_hashdb().contains(state.root()));
+		assert!(state.db.get_cached_code(&code_hash).is_some());
+		assert!(!state.db.is_known_null(&a));
+		let (root, db) = state.drop();
+		let state = State::from_existing(db, root, U256::from(0u8), Default::default()).unwrap();
+		assert_eq!(state.balance(&a).unwrap(), U256::from(69u64));
+		assert_eq!(state.storage_at(&a, &H256::from(1u64)).unwrap(), H256::from(2u64));
+	}
+
+	#[test]
+	fn diffs_committed_states() {
+		use trie::{TrieFactory, TrieSpec};
+		use types::account_diff::{AccountDiff, Diff};
+
+		let a = Address::zero();
+		let b = Address::from(1u64);
+		let factories = Factories {
+			trie: TrieFactory::new(TrieSpec::Fat),
+			..Default::default()
+		};
+		let mut state = State::new(get_temp_state_db(), U256::from(0u8), factories.clone());
+		state.add_balance(&a, &U256::from(69u64), CleanupMode::NoEmpty).unwrap();
+		state.set_storage(&a, H256::from(1u64), H256::from(2u64)).unwrap();
+		state.commit().unwrap();
+		let pre = *state.root();
+
+		state.add_balance(&a, &U256::from(1u64), CleanupMode::NoEmpty).unwrap();
+		state.set_storage(&a, H256::from(1u64), H256::zero()).unwrap();
+		state.set_storage(&b, H256::from(3u64), H256::from(4u64)).unwrap();
+		state.commit().unwrap();
+		let (post, db) = state.drop();
+
+		let fat_diff = |pre: &H256, post: &H256| match super::diff(pre, post, db.as_hashdb(), &factories).unwrap() {
+			TrieDiff::Fat(diff) => diff,
+			TrieDiff::Hashed(_) => panic!("fat database diffs by address"),
+		};
+		let diff = fat_diff(&pre, &post);
+		assert_eq!(diff.raw.len(), 2);
+		assert_eq!(diff.raw[&a], AccountDiff {
+			balance: Diff::Changed(U256::from(69u64), U256::from(70u64)),
+			nonce: Diff::Same,
+			code: Diff::Same,
+			storage: vec![(H256::from(1u64), Diff::Changed(H256::from(2u64), H256::zero()))].into_iter().collect(),
+		});
+		assert_eq!(diff.raw[&b].balance, Diff::Born(U256::zero()));
+		assert_eq!(diff.raw[&b].storage[&H256::from(3u64)], Diff::Born(H256::from(4u64)));
+
+		assert!(fat_diff(&post, &post).raw.is_empty());
+
+		let hashed = match super::diff(&pre, &post, db.as_hashdb(), &Default::default()).unwrap() {
+			TrieDiff::Hashed(raw) => raw,
+			TrieDiff::Fat(_) => panic!("only fat databases diff by address"),
+		};
+		assert_eq!(hashed.len(), 2);
+		assert_eq!(hashed[&keccak(&a)].storage[&keccak(&H256::from(1u64))], Diff::Changed(H256::from(2u64), H256::zero()));
+		assert_eq!(hashed[&keccak(&b)].balance, Diff::Born(U256::zero()));
+	}
+
+	#[test]
+	fn tracks_size_delta_of_commits() {
+		let a = Address::zero();
+		let mut state = get_temp_state();
+		state.add_balance(&a, &U256::from(69u64), CleanupMode::NoEmpty).unwrap();
+		state.commit().unwrap();
+		let first = state.size_delta();
+		assert!(first.bytes_added > 0);
+		assert_eq!(first.nodes_freed, 0);
+
+		state.add_balance(&a, &U256::from(1u64), CleanupMode::NoEmpty).unwrap();
+		state.commit().unwrap();
+		let second = state.size_delta();
+		assert!(second.nodes_added > first.nodes_added);
+		assert!(second.bytes_added > first.bytes_added);
+		assert!(second.nodes_freed > 0);
+	}
+
+	#[test]
+	fn pages_through_storage() {
+		let a = Address::zero();
+		let mut state = get_temp_state();
+		for i in 0..5u64 {
+			state.set_storage(&a, H256::from(i), H256::from(i + 1)).unwrap();
+		}
+		state.commit().unwrap();
+
+		let all: Vec<_> = state.storage_iter(&a).unwrap().map(|item| item.unwrap()).collect();
+		let first = state.storage_range(&a, &H256::zero(), 3).unwrap();
+		assert_eq!(&first.storage[..], &all[..3]);
+		assert_eq!(first.next_key, Some(all[3].0));
+
+		let second = state.storage_range(&a, &first.next_key.unwrap(), 3).unwrap();
+		assert_eq!(&second.storage[..], &all[3..]);
+		assert_eq!(second.next_key, None);
+	}
+
+	#[test]
+	fn code_size_from_database_keeps_only_size() {
+		let a = Address::zero();
+		let (root, db) = {
+			let mut state = get_temp_state();
+			state.init_code(&a, vec![1, 2, 3]).unwrap();
+			state.commit().unwrap();
+			state.drop()
+		};
+
+		let s = State::from_existing(db, root, U256::from(0u8), Default::default()).unwrap();
+		assert_eq!(s.code_size(&a).unwrap(), Some(3));
+		assert!(s.db.get_cached_code(&keccak(&[1u8, 2, 3][..])).is_none());
+		assert_eq!(s.code_size(&a).unwrap(), Some(3));
+		assert_eq!(s.code(&a).unwrap().map(|code| (*code).clone()), Some(vec![1, 2, 3]));
+		assert_eq!(s.db.get_cached_code(&keccak(&[1u8, 2, 3][..])).map(|code| (*code).clone()), Some(vec![1, 2, 3]));
+	}
+
 	#[test]
 	fn get_from_database() {
 		let a = Address::zero();
@@ -2152,6 +2805,25 @@ mod tests {
 		assert_eq!(*state.root(), "0ce23f3c809de377b008a4a3ee94a0834aac8bec1f86e28ffe4fdb5a15b0c785".into());
 	}
 
+	#[test]
+	fn tracks_accesses() {
+		let mut state = get_temp_state();
+		let a = Address::from(1);
+		let b = Address::from(2);
+		state.balance(&a).unwrap();
+		assert_eq!(state.accessed(), None);
+
+		state.track_accesses();
+		state.storage_at(&a, &H256::from(3)).unwrap();
+		state.set_storage(&b, H256::from(4), H256::from(5)).unwrap();
+		state.nonce(&b).unwrap();
+
+		let accessed = state.accessed().unwrap();
+		assert_eq!(accessed.len(), 2);
+		assert_eq!(accessed[&a].iter().cloned().collect::<Vec<_>>(), vec![H256::from(3)]);
+		assert_eq!(accessed[&b].iter().cloned().collect::<Vec<_>>(), vec![H256::from(4)]);
+	}
+
 	#[test]
 	fn checkpoint_basic() {
 		let mut state = get_temp_state();
@@ -2312,4 +2984,44 @@ mod tests {
 						   .into_iter().collect(),
 				   })).as_ref());
 	}
+
+	#[test]
+	fn populate_from_builds_pod_state_root() {
+		let mut accounts: BTreeMap<Address, PodAccount> = (0..ACCOUNTS_PER_COMMIT as u64 + 1).map(|i| {
+			(Address::from(i + 1), PodAccount {
+				balance: U256::from(i),
+				nonce: U256::zero(),
+				code: Some(Vec::new()),
+				storage: BTreeMap::new(),
+			})
+		}).collect();
+		accounts.insert(Address::from(0), PodAccount {
+			balance: U256::from(7),
+			nonce: U256::from(3),
+			code: Some(vec![0x60, 0x00]),
+			storage: vec![(H256::from(1), H256::from(2))].into_iter().collect(),
+		});
+		let pod = PodState::from(accounts);
+
+		let mut state = get_temp_state();
+		state.populate_from(pod.clone()).unwrap();
+
+		assert_eq!(*state.root(), pod.root());
+		assert_eq!(state.balance(&Address::from(1)).unwrap(), U256::zero());
+		assert_eq!(state.nonce(&Address::from(0)).unwrap(), U256::from(3));
+		assert_eq!(state.code(&Address::from(0)).unwrap(), Some(Arc::new(vec![0x60, 0x00])));
+		assert_eq!(state.storage_at(&Address::from(0), &H256::from(1)).unwrap(), H256::from(2));
+	}
+
+	#[test]
+	fn commit_warms_code_cache() {
+		let a = Address::zero();
+		let code = vec![0x60, 0x00, 0x60, 0x00];
+		let mut state = get_temp_state();
+		state.init_code(&a, code.clone()).unwrap();
+		state.commit().unwrap();
+
+		let hash = state.code_hash(&a).unwrap();
+		assert_eq!(state.db.get_cached_code(&hash), Some(Arc::new(code)));
+	}
 }
@@ -16,7 +16,7 @@
 
 //! Execution environment substate.
 use std::collections::HashSet;
-use ethereum_types::{U256, Address};
+use ethereum_types::{Address, H256};
 use log_entry::LogEntry;
 use evm::{Schedule, CleanDustMode};
 use super::CleanupMode;
@@ -34,11 +34,17 @@ pub struct Substate {
 	/// Any logs.
 	pub logs: Vec<LogEntry>,
 
-	/// Refund counter of SSTORE nonzero -> zero.
-	pub sstore_clears_count: U256,
+	/// Refund counter of SSTORE. May be negative in a sub-call with net gas metering.
+	pub sstore_clears_refund: i128,
 
 	/// Created contracts.
 	pub contracts_created: Vec<Address>,
+
+	/// Accounts accessed so far in the transaction (EIP-2929).
+	pub warm_addresses: HashSet<Address>,
+
+	/// Storage keys accessed so far in the transaction, along with their accounts (EIP-2929).
+	pub warm_storage_keys: HashSet<(Address, H256)>,
 }
 
 impl Substate {
@@ -47,13 +53,24 @@ impl Substate {
 		Substate::default()
 	}
 
+	/// Creates substate for a nested call, aware of the accounts and storage keys accessed so far.
+	pub fn child(&self) -> Self {
+		Substate {
+			warm_addresses: self.warm_addresses.clone(),
+			warm_storage_keys: self.warm_storage_keys.clone(),
+			..Substate::default()
+		}
+	}
+
 	/// Merge secondary substate `s` into self, accruing each element correspondingly.
 	pub fn accrue(&mut self, s: Substate) {
 		self.suicides.extend(s.suicides);
 		self.touched.extend(s.touched);
 		self.logs.extend(s.logs);
-		self.sstore_clears_count = self.sstore_clears_count + s.sstore_clears_count;
+		self.sstore_clears_refund += s.sstore_clears_refund;
 		self.contracts_created.extend(s.contracts_created);
+		self.warm_addresses.extend(s.warm_addresses);
+		self.warm_storage_keys.extend(s.warm_storage_keys);
 	}
 
 	/// Get the cleanup mode object from this.
@@ -86,7 +103,7 @@ mod tests {
 			topics: vec![],
 			data: vec![]
 		});
-		sub_state.sstore_clears_count = 5.into();
+		sub_state.sstore_clears_refund = 15000 * 5;
 		sub_state.suicides.insert(10u64.into());
 
 		let mut sub_state_2 = Substate::new();
@@ -96,11 +113,30 @@ mod tests {
 			topics: vec![],
 			data: vec![]
 		});
-		sub_state_2.sstore_clears_count = 7.into();
+		sub_state_2.sstore_clears_refund = 15000 * 7;
 
 		sub_state.accrue(sub_state_2);
 		assert_eq!(sub_state.contracts_created.len(), 2);
-		assert_eq!(sub_state.sstore_clears_count, 12.into());
+		assert_eq!(sub_state.sstore_clears_refund, 15000 * 12);
 		assert_eq!(sub_state.suicides.len(), 1);
 	}
+
+	#[test]
+	fn child_sees_warm_accesses() {
+		let mut sub_state = Substate::new();
+		sub_state.warm_addresses.insert(1u64.into());
+		sub_state.logs.push(LogEntry {
+			address: 1u64.into(),
+			topics: vec![],
+			data: vec![]
+		});
+
+		let mut child = sub_state.child();
+		assert!(child.warm_addresses.contains(&1u64.into()));
+		assert!(child.logs.is_empty());
+
+		child.warm_storage_keys.insert((1u64.into(), 2u64.into()));
+		sub_state.accrue(child);
+		assert!(sub_state.warm_storage_keys.contains(&(1u64.into(), 2u64.into())));
+	}
 }
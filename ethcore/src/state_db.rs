@@ -17,9 +17,12 @@
 //! State database abstraction. For more info, see the doc for `StateDB`
 
 use std::collections::{VecDeque, HashSet};
+use std::cmp;
+use std::ops::Deref;
 use std::sync::Arc;
 use lru_cache::LruCache;
-use memory_cache::MemoryLruCache;
+use memory_cache::{MemoryLruCache, CacheStats};
+use memory_cache::pressure::{self, Shed};
 use journaldb::JournalDB;
 use kvdb::{KeyValueDB, DBTransaction};
 use ethereum_types::{H256, Address};
@@ -60,6 +63,40 @@ struct AccountCache {
 	/// Information on the modifications in recently committed blocks; specifically which addresses
 	/// changed in which block. Ordered by block number.
 	modifications: VecDeque<BlockChanges>,
+	/// Lookup statistics of `accounts`.
+	stats: CacheStats,
+}
+
+impl AccountCache {
+	fn note_lookup(&mut self, hit: bool) {
+		if hit {
+			self.stats.hits += 1;
+		} else {
+			self.stats.misses += 1;
+		}
+	}
+}
+
+// account cache shared by the clones of a `StateDB`, shedding accounts under memory pressure.
+struct SharedAccountCache(Mutex<AccountCache>);
+
+impl Deref for SharedAccountCache {
+	type Target = Mutex<AccountCache>;
+
+	fn deref(&self) -> &Mutex<AccountCache> {
+		&self.0
+	}
+}
+
+impl Shed for SharedAccountCache {
+	fn shed(&self, percent: usize) -> usize {
+		let mut cache = self.0.lock();
+		let count = cache.accounts.len() * cmp::min(percent, 100) / 100;
+		for _ in 0..count {
+			cache.accounts.remove_lru();
+		}
+		count * ::std::mem::size_of::<Option<Account>>()
+	}
 }
 
 /// Buffered account cache item.
@@ -106,7 +143,7 @@ pub struct StateDB {
 	/// Backing database.
 	db: Box<JournalDB>,
 	/// Shared canonical state cache.
-	account_cache: Arc<Mutex<AccountCache>>,
+	account_cache: Arc<SharedAccountCache>,
 	/// DB Code cache. Maps code hashes to shared bytes.
 	code_cache: Arc<Mutex<MemoryLruCache<H256, Arc<Vec<u8>>>>>,
 	/// Local dirty cache.
@@ -134,14 +171,19 @@ impl StateDB {
 		let acc_cache_size = cache_size * ACCOUNT_CACHE_RATIO / 100;
 		let code_cache_size = cache_size - acc_cache_size;
 		let cache_items = acc_cache_size / ::std::mem::size_of::<Option<Account>>();
+		let account_cache = Arc::new(SharedAccountCache(Mutex::new(AccountCache {
+			accounts: LruCache::new(cache_items),
+			modifications: VecDeque::new(),
+			stats: CacheStats::default(),
+		})));
+		let code_cache = Arc::new(Mutex::new(MemoryLruCache::new(code_cache_size)));
+		pressure::global().register(&(account_cache.clone() as Arc<Shed>));
+		pressure::global().register(&(code_cache.clone() as Arc<Shed>));
 
 		StateDB {
 			db: db,
-			account_cache: Arc::new(Mutex::new(AccountCache {
-				accounts: LruCache::new(cache_items),
-				modifications: VecDeque::new(),
-			})),
-			code_cache: Arc::new(Mutex::new(MemoryLruCache::new(code_cache_size))),
+			account_cache: account_cache,
+			code_cache: code_cache,
 			local_cache: Vec::new(),
 			account_bloom: Arc::new(Mutex::new(bloom)),
 			cache_size: cache_size,
@@ -374,6 +416,40 @@ impl StateDB {
 		self.cache_size
 	}
 
+	/// Resize the shared account and code caches, evicting least recently used entries if necessary.
+	pub fn set_cache_size(&mut self, cache_size: usize) {
+		let acc_cache_size = cache_size * ACCOUNT_CACHE_RATIO / 100;
+		let code_cache_size = cache_size - acc_cache_size;
+		let cache_items = acc_cache_size / ::std::mem::size_of::<Option<Account>>();
+
+		self.account_cache.lock().accounts.set_capacity(cache_items);
+		self.code_cache.lock().set_max_size(code_cache_size);
+		self.cache_size = cache_size;
+	}
+
+	/// Returns lookup statistics of the shared account and code caches since last called.
+	pub fn take_cache_stats(&self) -> CacheStats {
+		let accounts = ::std::mem::replace(&mut self.account_cache.lock().stats, CacheStats::default());
+		accounts + self.code_cache.lock().take_stats()
+	}
+
+	/// Make `account` share the code with the code cache, so accounts with the same code
+	/// don't keep separate copies of it in memory.
+	fn share_code(&self, account: &mut Account) {
+		let code = match account.code() {
+			Some(code) if !code.is_empty() => code,
+			_ => return,
+		};
+		let hash = account.code_hash();
+
+		let mut cache = self.code_cache.lock();
+		let shared = cache.peek_mut(&hash).map(|c| c.clone());
+		match shared {
+			Some(shared) => account.cache_given_code(shared),
+			None => cache.insert(hash, code),
+		}
+	}
+
 	/// Check if the account can be returned from cache by matching current block parent hash against canonical
 	/// state and filtering out account modified in later blocks.
 	fn is_allowed(addr: &Address, parent_hash: &Option<H256>, modifications: &VecDeque<BlockChanges>) -> bool {
@@ -418,7 +494,10 @@ impl state::Backend for StateDB {
 		self.db.as_hashdb_mut()
 	}
 
-	fn add_to_account_cache(&mut self, addr: Address, data: Option<Account>, modified: bool) {
+	fn add_to_account_cache(&mut self, addr: Address, mut data: Option<Account>, modified: bool) {
+		if let Some(ref mut account) = data {
+			self.share_code(account);
+		}
 		self.local_cache.push(CacheQueueItem {
 			address: addr,
 			account: SyncAccount(data),
@@ -429,7 +508,10 @@ impl state::Backend for StateDB {
 	fn cache_code(&self, hash: H256, code: Arc<Vec<u8>>) {
 		let mut cache = self.code_cache.lock();
 
-		cache.insert(hash, code);
+		// keep the cached copy, which may already be shared by cached accounts.
+		if cache.peek_mut(&hash).is_none() {
+			cache.insert(hash, code);
+		}
 	}
 
 	fn get_cached_account(&self, addr: &Address) -> Option<Option<Account>> {
@@ -437,7 +519,9 @@ impl state::Backend for StateDB {
 		if !Self::is_allowed(addr, &self.parent_hash, &cache.modifications) {
 			return None;
 		}
-		cache.accounts.get_mut(addr).map(|a| a.as_ref().map(|a| a.clone_basic()))
+		let account = cache.accounts.get_mut(addr).map(|a| a.as_ref().map(|a| a.clone_basic()));
+		cache.note_lookup(account.is_some());
+		account
 	}
 
 	fn get_cached<F, U>(&self, a: &Address, f: F) -> Option<U>
@@ -446,7 +530,9 @@ impl state::Backend for StateDB {
 		if !Self::is_allowed(a, &self.parent_hash, &cache.modifications) {
 			return None;
 		}
-		cache.accounts.get_mut(a).map(|c| f(c.as_mut()))
+		let value = cache.accounts.get_mut(a).map(|c| f(c.as_mut()));
+		cache.note_lookup(value.is_some());
+		value
 	}
 
 	fn get_cached_code(&self, hash: &H256) -> Option<Arc<Vec<u8>>> {
@@ -478,6 +564,7 @@ unsafe impl Sync for SyncAccount {}
 
 #[cfg(test)]
 mod tests {
+	use std::sync::Arc;
 	use ethereum_types::{H256, U256, Address};
 	use kvdb::DBTransaction;
 	use test_helpers::get_temp_state_db;
@@ -550,4 +637,24 @@ mod tests {
 		let s = state_db.boxed_clone_canon(&h3a);
 		assert!(s.get_cached_account(&address).is_none());
 	}
+
+	#[test]
+	fn accounts_share_cached_code() {
+		let state_db = get_temp_state_db();
+		let mut s = state_db.boxed_clone_canon(&H256::random());
+		let code = vec![0x60, 0x00, 0x60, 0x00];
+		let a = Account::new(0.into(), 0.into(), Default::default(), code.clone());
+		let b = Account::new(0.into(), 0.into(), Default::default(), code.clone());
+		let hash = a.code_hash();
+
+		s.add_to_account_cache(Address::from(1), Some(a), true);
+		s.add_to_account_cache(Address::from(2), Some(b), true);
+		s.cache_code(hash, Arc::new(code));
+
+		let cached = s.get_cached_code(&hash).unwrap();
+		for item in &s.local_cache {
+			let account = item.account.0.as_ref().unwrap();
+			assert!(Arc::ptr_eq(&account.code().unwrap(), &cached));
+		}
+	}
 }
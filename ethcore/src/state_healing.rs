@@ -0,0 +1,382 @@
+// Copyright 2015-2018 Parity Technologies (UK) Ltd.
+// This file is part of Parity.
+
+// Parity is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// Parity is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with Parity.  If not, see <http://www.gnu.org/licenses/>.
+
+//! Healing of state left incomplete by an interrupted fast or warp sync.
+//!
+//! The healer walks the account trie of a target state root, together with the storage
+//! tries and code of every account, and collects the nodes missing from the database.
+//! Those are requested from peers by the sync and imported back, after which their
+//! children are walked in turn.
+
+use std::collections::HashMap;
+
+use account_db::Factory as AccountDBFactory;
+use bytes::Bytes;
+use ethereum_types::H256;
+use hash::{keccak, KECCAK_EMPTY, KECCAK_NULL_RLP};
+use hashdb::{HashDB, DBValue};
+use rlp::Rlp;
+use trie::node::Node;
+
+/// Number of times a missing node is requested before giving up on it.
+const MAX_ATTEMPTS: usize = 5;
+
+/// Progress of state healing.
+#[derive(Debug, Clone, PartialEq)]
+pub struct StateHealingStatus {
+	/// State root being healed.
+	pub root: H256,
+	/// Number of nodes found in the database.
+	pub checked: u64,
+	/// Number of missing nodes retrieved from peers.
+	pub healed: u64,
+	/// Number of missing nodes which no peer provided.
+	pub failed: u64,
+	/// Number of nodes left to check or to retrieve.
+	pub pending: usize,
+}
+
+impl StateHealingStatus {
+	/// Whether the whole state has been walked.
+	pub fn is_done(&self) -> bool {
+		self.pending == 0
+	}
+}
+
+#[derive(Debug, Clone, PartialEq)]
+enum Location {
+	/// Node of the account trie, with the nibbles of its path.
+	State(Vec<u8>),
+	/// Node of the storage trie of the account with given address hash.
+	Storage(H256),
+	/// Code of the account with given address hash.
+	Code(H256),
+}
+
+struct Missing {
+	locations: Vec<Location>,
+	attempts: usize,
+}
+
+/// Walks the state trie, collecting missing nodes and importing them once retrieved.
+pub struct StateHealer {
+	root: H256,
+	factory: AccountDBFactory,
+	// walked depth-first, to keep the number of pending nodes low.
+	stack: Vec<(H256, Location)>,
+	missing: HashMap<H256, Missing>,
+	checked: u64,
+	healed: u64,
+	failed: u64,
+}
+
+impl StateHealer {
+	/// Create a healer of the state with given root.
+	pub fn new(root: H256, factory: AccountDBFactory) -> Self {
+		let mut stack = Vec::new();
+		if root != KECCAK_NULL_RLP {
+			stack.push((root, Location::State(Vec::new())));
+		}
+
+		StateHealer {
+			root,
+			factory,
+			stack,
+			missing: HashMap::new(),
+			checked: 0,
+			healed: 0,
+			failed: 0,
+		}
+	}
+
+	/// State root being healed.
+	pub fn root(&self) -> H256 {
+		self.root
+	}
+
+	/// Continue healing the state with given root instead, e.g. of a more recent block.
+	/// The walk starts over, nodes healed so far are found in the database and not requested again.
+	pub fn reanchor(&mut self, root: H256) {
+		self.root = root;
+		self.stack.clear();
+		self.missing.clear();
+		if root != KECCAK_NULL_RLP {
+			self.stack.push((root, Location::State(Vec::new())));
+		}
+	}
+
+	/// Progress of the healing.
+	pub fn status(&self) -> StateHealingStatus {
+		StateHealingStatus {
+			root: self.root,
+			checked: self.checked,
+			healed: self.healed,
+			failed: self.failed,
+			pending: self.stack.len() + self.missing.len(),
+		}
+	}
+
+	/// Walk at most `max_checks` nodes of `db` and return the hashes of at most `max` missing nodes
+	/// to request. Every returned hash counts as an attempt to retrieve the node.
+	pub fn request(&mut self, db: &HashDB, max: usize, max_checks: usize) -> Vec<H256> {
+		let mut checks = 0;
+		while self.missing.len() < max && checks < max_checks {
+			let (hash, location) = match self.stack.pop() {
+				Some(node) => node,
+				None => break,
+			};
+			checks += 1;
+
+			match self.lookup(db, &hash, &location) {
+				Some(data) => {
+					self.checked += 1;
+					self.walk(&data, location);
+				},
+				None => self.missing.entry(hash)
+					.or_insert_with(|| Missing { locations: Vec::new(), attempts: 0 })
+					.locations.push(location),
+			}
+		}
+
+		let mut request = Vec::new();
+		let mut given_up = Vec::new();
+		for (hash, missing) in self.missing.iter_mut().take(max) {
+			missing.attempts += 1;
+			if missing.attempts > MAX_ATTEMPTS {
+				given_up.push(*hash);
+			} else {
+				request.push(*hash);
+			}
+		}
+
+		for hash in given_up {
+			trace!(target: "healing", "Giving up on state node {}", hash);
+			self.missing.remove(&hash);
+			self.failed += 1;
+		}
+
+		request
+	}
+
+	/// Import nodes retrieved from peers into `db`. Returns the number of nodes which were missing.
+	pub fn import(&mut self, db: &mut HashDB, nodes: &[Bytes]) -> usize {
+		let mut imported = 0;
+		for node in nodes {
+			let hash = keccak(node);
+			let missing = match self.missing.remove(&hash) {
+				Some(missing) => missing,
+				None => continue,
+			};
+
+			imported += 1;
+			self.healed += 1;
+			for location in missing.locations {
+				match location {
+					Location::State(_) => db.emplace(hash, DBValue::from_slice(node)),
+					Location::Storage(address_hash) | Location::Code(address_hash) =>
+						self.factory.create(db, address_hash).emplace(hash, DBValue::from_slice(node)),
+				}
+				self.walk(node, location);
+			}
+		}
+
+		imported
+	}
+
+	fn lookup(&self, db: &HashDB, hash: &H256, location: &Location) -> Option<DBValue> {
+		match *location {
+			Location::State(_) => db.get(hash),
+			Location::Storage(address_hash) | Location::Code(address_hash) =>
+				self.factory.readonly(db, address_hash).get(hash),
+		}
+	}
+
+	fn walk(&mut self, data: &[u8], location: Location) {
+		match location {
+			Location::State(path) => self.walk_node(data, path, None),
+			Location::Storage(address_hash) => self.walk_node(data, Vec::new(), Some(address_hash)),
+			Location::Code(_) => {},
+		}
+	}
+
+	// queue the children of a trie node. `storage` is the address hash of the account
+	// whose storage trie the node belongs to; paths are tracked for the account trie only.
+	fn walk_node(&mut self, data: &[u8], path: Vec<u8>, storage: Option<H256>) {
+		let node = match Node::decoded(data) {
+			Ok(node) => node,
+			Err(e) => {
+				debug!(target: "healing", "Malformed trie node: {}", e);
+				return;
+			},
+		};
+
+		let extended = |path: &Vec<u8>, nibbles: &mut Iterator<Item = u8>| -> Vec<u8> {
+			match storage {
+				Some(_) => Vec::new(),
+				None => path.iter().cloned().chain(nibbles).collect(),
+			}
+		};
+
+		match node {
+			Node::Empty => {},
+			Node::Leaf(slice, value) => if storage.is_none() {
+				let key = extended(&path, &mut (0..slice.len()).map(|i| slice.at(i)));
+				self.walk_account(&key, value);
+			},
+			Node::Extension(slice, child) => {
+				let child_path = extended(&path, &mut (0..slice.len()).map(|i| slice.at(i)));
+				self.walk_child(child, child_path, storage);
+			},
+			Node::Branch(children, _) => {
+				for (i, child) in children.iter().enumerate() {
+					let child_path = extended(&path, &mut ::std::iter::once(i as u8));
+					self.walk_child(child, child_path, storage);
+				}
+			},
+		}
+	}
+
+	fn walk_child(&mut self, child: &[u8], path: Vec<u8>, storage: Option<H256>) {
+		let rlp = Rlp::new(child);
+		if rlp.is_list() {
+			// nodes shorter than a hash are inlined in their parent.
+			self.walk_node(child, path, storage);
+		} else if let Ok(hash) = rlp.as_val::<H256>() {
+			let location = match storage {
+				Some(address_hash) => Location::Storage(address_hash),
+				None => Location::State(path),
+			};
+			self.stack.push((hash, location));
+		}
+	}
+
+	fn walk_account(&mut self, key: &[u8], value: &[u8]) {
+		if key.len() != 64 {
+			debug!(target: "healing", "Account trie leaf with unexpected key length {}", key.len());
+			return;
+		}
+
+		let mut address_hash = H256::new();
+		for (byte, nibbles) in address_hash.iter_mut().zip(key.chunks(2)) {
+			*byte = nibbles[0] << 4 | nibbles[1];
+		}
+
+		let account = Rlp::new(value);
+		let (storage_root, code_hash): (H256, H256) = match (account.val_at(2), account.val_at(3)) {
+			(Ok(storage_root), Ok(code_hash)) => (storage_root, code_hash),
+			_ => {
+				debug!(target: "healing", "Malformed account {}", address_hash);
+				return;
+			},
+		};
+
+		if storage_root != KECCAK_NULL_RLP {
+			self.stack.push((storage_root, Location::Storage(address_hash)));
+		}
+		if code_hash != KECCAK_EMPTY {
+			self.stack.push((code_hash, Location::Code(address_hash)));
+		}
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use std::collections::HashMap;
+	use ethereum_types::{Address, H256, U256};
+	use hash::keccak;
+	use hashdb::HashDB;
+	use memorydb::MemoryDB;
+	use state::{State, CleanupMode};
+	use state::backend::Basic;
+	use super::StateHealer;
+
+	fn full_state() -> (H256, MemoryDB) {
+		let mut state = State::new(Basic(MemoryDB::new()), U256::zero(), Default::default());
+		for i in 1..40u64 {
+			let address = Address::from(i);
+			state.add_balance(&address, &i.into(), CleanupMode::NoEmpty).unwrap();
+			if i % 3 == 0 {
+				state.init_code(&address, vec![i as u8; 40]).unwrap();
+				for key in 0..20u64 {
+					state.set_storage(&address, H256::from(key), H256::from(i * key + 1)).unwrap();
+				}
+			}
+		}
+		state.commit().unwrap();
+		let (root, backend) = state.drop();
+		(root, backend.0)
+	}
+
+	#[test]
+	fn walks_complete_state() {
+		let (root, db) = full_state();
+		let mut healer = StateHealer::new(root, Default::default());
+
+		assert!(healer.request(&db, 16, usize::max_value()).is_empty());
+		let status = healer.status();
+		assert!(status.is_done());
+		assert_eq!(status.checked as usize, db.keys().len());
+		assert_eq!(status.healed, 0);
+	}
+
+	#[test]
+	fn heals_missing_nodes() {
+		let (root, full) = full_state();
+		// every node by its hash, as peers would serve them.
+		let peer: HashMap<H256, Vec<u8>> = full.keys().keys()
+			.map(|key| full.get(key).unwrap().to_vec())
+			.map(|value| (keccak(&value), value))
+			.collect();
+
+		let mut db = full.clone();
+		let removed: Vec<_> = full.keys().keys().cloned().enumerate()
+			.filter(|&(i, _)| i % 4 == 0)
+			.map(|(_, key)| key)
+			.collect();
+		for key in &removed {
+			db.remove_and_purge(key);
+		}
+
+		let mut healer = StateHealer::new(root, Default::default());
+		loop {
+			let request = healer.request(&db, 8, 64);
+			if request.is_empty() && healer.status().is_done() {
+				break;
+			}
+			let nodes: Vec<_> = request.iter().map(|hash| peer[hash].clone()).collect();
+			assert_eq!(healer.import(&mut db, &nodes), nodes.len());
+		}
+
+		assert_eq!(healer.status().failed, 0);
+		assert_eq!(healer.status().healed as usize, removed.len());
+		assert!(full.keys().keys().all(|key| db.contains(key)));
+	}
+
+	#[test]
+	fn reanchors_to_another_root() {
+		let (root, db) = full_state();
+		let mut healer = StateHealer::new(H256::from(1), Default::default());
+		assert_eq!(healer.request(&db, 16, usize::max_value()), vec![H256::from(1)]);
+
+		healer.reanchor(root);
+
+		assert!(healer.request(&db, 16, usize::max_value()).is_empty());
+		let status = healer.status();
+		assert_eq!(status.root, root);
+		assert!(status.is_done());
+		assert_eq!(status.checked as usize, db.keys().len());
+	}
+}
@@ -17,12 +17,13 @@
 //! Set of different helpers for client tests
 
 use account_provider::AccountProvider;
-use ethereum_types::{H256, U256, Address};
+use ethereum_types::{H256, H512, U256, Address};
 use block::{OpenBlock, Drain};
 use blockchain::{BlockChain, Config as BlockChainConfig, ExtrasInsert};
 use bytes::Bytes;
 use client::{Client, ClientConfig, ChainInfo, ImportBlock, ChainNotify, ChainMessageType, PrepareOpenBlock};
 use ethkey::KeyPair;
+use engines::LivenessAlert;
 use evm::Factory as EvmFactory;
 use factory::Factories;
 use hash::keccak;
@@ -398,6 +399,10 @@ pub fn get_bad_state_dummy_block() -> Bytes {
 pub struct TestNotify {
 	/// Messages store
 	pub messages: RwLock<Vec<Bytes>>,
+	/// Messages sent to a single peer, with the peer's node id
+	pub sent: RwLock<Vec<(Bytes, H512)>>,
+	/// Validator liveness alerts
+	pub liveness_alerts: RwLock<Vec<LivenessAlert>>,
 }
 
 impl ChainNotify for TestNotify {
@@ -409,4 +414,14 @@ impl ChainNotify for TestNotify {
 		};
 		self.messages.write().push(data);
 	}
+
+	fn send(&self, message: ChainMessageType, node_id: &H512) {
+		if let ChainMessageType::Consensus(data) = message {
+			self.sent.write().push((data, *node_id));
+		}
+	}
+
+	fn liveness_alert(&self, alert: &LivenessAlert) {
+		self.liveness_alerts.write().push(alert.clone());
+	}
 }
@@ -18,17 +18,17 @@ use std::str::FromStr;
 use std::sync::Arc;
 use hash::keccak;
 use io::IoChannel;
-use client::{BlockChainClient, Client, ClientConfig, BlockId, ChainInfo, BlockInfo, PrepareOpenBlock, ImportSealedBlock, ImportBlock};
+use client::{BlockChainClient, EngineClient, Client, ClientConfig, BlockId, ChainInfo, BlockInfo, PrepareOpenBlock, ImportSealedBlock, ImportBlock};
 use state::{self, State, CleanupMode};
 use executive::{Executive, TransactOptions};
 use ethereum;
 use block::IsBlock;
 use test_helpers::{
 	generate_dummy_client, push_blocks_to_client, get_test_client_with_blocks, get_good_dummy_block_seq,
-	generate_dummy_client_with_data, get_good_dummy_block, get_bad_state_dummy_block
+	generate_dummy_client_with_data, get_good_dummy_block, get_bad_state_dummy_block, TestNotify
 };
 use types::filter::Filter;
-use ethereum_types::{U256, Address};
+use ethereum_types::{H512, U256, Address};
 use kvdb_rocksdb::{Database, DatabaseConfig};
 use miner::Miner;
 use spec::Spec;
@@ -214,6 +214,23 @@ fn imports_block_sequence() {
 	assert!(!block.into_inner().is_empty());
 }
 
+#[test]
+fn iterates_canonical_blocks() {
+	use client::CanonItem;
+
+	let client = generate_dummy_client(6);
+	let mut iter = client.canon_iter(2, 4);
+	let numbers: Vec<_> = iter.by_ref().map(|item| match item {
+		CanonItem::Block { header, .. } => header.number(),
+		CanonItem::Reorg { .. } => panic!("no reorg happened"),
+	}).collect();
+	assert_eq!(numbers, vec![2, 3, 4]);
+
+	let cursor = iter.cursor().unwrap();
+	assert_eq!(cursor.hash, client.block_hash(BlockId::Number(4)).unwrap());
+	assert_eq!(client.canon_iter_after(cursor, 6).count(), 2);
+}
+
 #[test]
 fn can_collect_garbage() {
 	let client = generate_dummy_client(100);
@@ -391,3 +408,14 @@ fn transaction_proof() {
 	assert_eq!(state.balance(&Address::default()).unwrap(), 5.into());
 	assert_eq!(state.balance(&address).unwrap(), 95.into());
 }
+
+#[test]
+fn sends_consensus_message_to_single_peer() {
+	let client = generate_dummy_client(0);
+	let notify = Arc::new(TestNotify::default());
+	client.add_notify(notify.clone());
+
+	client.send_consensus_message(vec![1, 2, 3], H512::from(7));
+	assert!(notify.messages.read().is_empty());
+	assert_eq!(*notify.sent.read(), vec![(vec![1, 2, 3], H512::from(7))]);
+}
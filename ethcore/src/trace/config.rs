@@ -29,6 +29,10 @@ pub struct Config {
 	pub pref_cache_size: usize,
 	/// Max cache-size.
 	pub max_cache_size: usize,
+	/// Number of most recent blocks to keep traces of, `None` to keep traces of all blocks.
+	pub max_age: Option<u64>,
+	/// Maximal total size of stored traces in bytes, `None` for no limit.
+	pub max_size: Option<u64>,
 }
 
 impl Default for Config {
@@ -41,6 +45,8 @@ impl Default for Config {
 			},
 			pref_cache_size: 15 * 1024 * 1024,
 			max_cache_size: 20 * 1024 * 1024,
+			max_age: None,
+			max_size: None,
 		}
 	}
 }
@@ -24,6 +24,8 @@ use heapsize::HeapSizeOf;
 use ethereum_types::{H256, H264};
 use kvdb::{KeyValueDB, DBTransaction};
 use parking_lot::RwLock;
+use memory_cache::{AtomicCacheStats, CacheStats};
+use rlp::{self, Encodable, RlpStream, Decodable, DecoderError, Rlp};
 use header::BlockNumber;
 use trace::{LocalizedTrace, Config, Filter, Database as TraceDatabase, ImportRequest, DatabaseExtras};
 use db::{self, Key, Writable, Readable, CacheUpdatePolicy};
@@ -32,6 +34,39 @@ use super::flat::{FlatTrace, FlatBlockTraces, FlatTransactionTraces};
 use cache_manager::CacheManager;
 
 const TRACE_DB_VER: &'static [u8] = b"1.0";
+const USAGE_KEY: &'static [u8] = b"usage";
+
+/// Traces of this many most recent blocks are never pruned, so that reorganisations
+/// can always rebuild blooms of re-enacted blocks.
+const MIN_RETAINED_BLOCKS: BlockNumber = 128;
+/// Maximal number of blocks pruned at once.
+const MAX_PRUNED_BLOCKS: usize = 1024;
+
+/// Storage used by the traces database.
+#[derive(Debug, Default, Clone, Copy, PartialEq)]
+pub struct StorageUsage {
+	/// Number of the oldest block whose traces are kept.
+	pub oldest_block: BlockNumber,
+	/// Total size of stored block traces in bytes.
+	pub size: u64,
+}
+
+impl Encodable for StorageUsage {
+	fn rlp_append(&self, s: &mut RlpStream) {
+		s.begin_list(2);
+		s.append(&self.oldest_block);
+		s.append(&self.size);
+	}
+}
+
+impl Decodable for StorageUsage {
+	fn decode(rlp: &Rlp) -> Result<Self, DecoderError> {
+		Ok(StorageUsage {
+			oldest_block: rlp.val_at(0)?,
+			size: rlp.val_at(1)?,
+		})
+	}
+}
 
 #[derive(Debug, Copy, Clone)]
 enum TraceDBIndex {
@@ -112,12 +147,17 @@ pub struct TraceDB<T> where T: DatabaseExtras {
 	traces: RwLock<HashMap<H256, FlatBlockTraces>>,
 	blooms: RwLock<HashMap<TraceGroupPosition, blooms::BloomGroup>>,
 	cache_manager: RwLock<CacheManager<CacheId>>,
+	cache_stats: AtomicCacheStats,
 	// db
 	tracesdb: Arc<KeyValueDB>,
 	// config,
 	bloom_config: BloomConfig,
 	// tracing enabled
 	enabled: bool,
+	// retention
+	max_age: Option<u64>,
+	max_size: Option<u64>,
+	usage: RwLock<StorageUsage>,
 	// extras
 	extras: Arc<T>,
 }
@@ -125,7 +165,7 @@ pub struct TraceDB<T> where T: DatabaseExtras {
 impl<T> BloomGroupDatabase for TraceDB<T> where T: DatabaseExtras {
 	fn blooms_at(&self, position: &GroupPosition) -> Option<BloomGroup> {
 		let position = TraceGroupPosition::from(position.clone());
-		let result = self.tracesdb.read_with_cache(db::COL_TRACE, &self.blooms, &position).map(Into::into);
+		let result = self.tracesdb.read_with_cache_stats(db::COL_TRACE, &self.blooms, &position, &self.cache_stats).map(Into::into);
 		self.note_used(CacheId::Bloom(position));
 		result
 	}
@@ -141,17 +181,107 @@ impl<T> TraceDB<T> where T: DatabaseExtras {
 		batch.put(db::COL_TRACE, b"version", TRACE_DB_VER);
 		tracesdb.write(batch).expect("failed to update version");
 
+		let usage: Option<StorageUsage> = tracesdb.get(db::COL_TRACE, USAGE_KEY)
+			.expect("Low level database error. Some issue with disk?")
+			.map(|usage| rlp::decode(&usage).expect("Traces storage usage is corrupted"));
+
+		// the size is counted on import and prune and only measured here, at startup. It's missing
+		// in databases created before it was tracked, and it drifts when traces are re-imported,
+		// which matters when pruning by size.
+		let usage = match usage {
+			Some(usage) if config.max_size.is_none() => usage,
+			usage => {
+				let usage = StorageUsage {
+					size: measure_size(&*tracesdb),
+					..usage.unwrap_or_default()
+				};
+				let mut batch = DBTransaction::new();
+				batch.put(db::COL_TRACE, USAGE_KEY, &rlp::encode(&usage));
+				tracesdb.write(batch).expect("Low level database error. Some issue with disk?");
+				usage
+			},
+		};
+
 		TraceDB {
 			traces: RwLock::new(HashMap::new()),
 			blooms: RwLock::new(HashMap::new()),
 			cache_manager: RwLock::new(CacheManager::new(config.pref_cache_size, config.max_cache_size, 10 * 1024)),
+			cache_stats: AtomicCacheStats::default(),
 			tracesdb: tracesdb,
 			bloom_config: config.blooms,
 			enabled: config.enabled,
+			max_age: config.max_age,
+			max_size: config.max_size,
+			usage: RwLock::new(usage),
 			extras: extras,
 		}
 	}
 
+	/// Returns storage used by stored traces.
+	pub fn storage_usage(&self) -> StorageUsage {
+		*self.usage.read()
+	}
+
+	/// Removes traces of the oldest blocks until the configured retention is satisfied.
+	/// Traces of `MIN_RETAINED_BLOCKS` blocks before `best_block` are always kept.
+	/// Returns number of blocks whose traces were removed.
+	///
+	/// Imports are only locked out while the cache and the usage are updated.
+	pub fn prune(&self, best_block: BlockNumber) -> usize {
+		if self.max_age.is_none() && self.max_size.is_none() {
+			return 0;
+		}
+
+		let StorageUsage { mut oldest_block, mut size } = *self.usage.read();
+		let mut batch = DBTransaction::new();
+		let mut removed = Vec::new();
+		let mut pruned = 0;
+		let mut pruned_size = 0;
+
+		while pruned < MAX_PRUNED_BLOCKS && oldest_block + MIN_RETAINED_BLOCKS < best_block {
+			let too_old = self.max_age.map_or(false, |age| best_block - oldest_block > age);
+			let too_big = self.max_size.map_or(false, |max_size| size > max_size);
+			if !too_old && !too_big {
+				break;
+			}
+
+			// traces of retracted blocks are not pruned, they stay counted in the size.
+			if let Some(hash) = self.extras.block_hash(oldest_block) {
+				let key = Key::<FlatBlockTraces>::key(&hash);
+				let traces_size = self.tracesdb.get(db::COL_TRACE, &*key)
+					.expect("Low level database error. Some issue with disk?")
+					.map_or(0, |traces| traces.len() as u64);
+				batch.delete(db::COL_TRACE, &*key);
+				removed.push(hash);
+				size = size.saturating_sub(traces_size);
+				pruned_size += traces_size;
+			}
+			oldest_block += 1;
+			pruned += 1;
+		}
+
+		if pruned == 0 {
+			return 0;
+		}
+
+		{
+			let mut traces = self.traces.write();
+			for hash in &removed {
+				traces.remove(hash);
+			}
+		}
+
+		// traces imported meanwhile were counted on top of the size read above.
+		let mut usage = self.usage.write();
+		usage.oldest_block = oldest_block;
+		usage.size = usage.size.saturating_sub(pruned_size);
+		batch.put(db::COL_TRACE, USAGE_KEY, &rlp::encode(&*usage));
+		self.tracesdb.write(batch).expect("Low level database error. Some issue with disk?");
+		debug!(target: "trace", "Pruned traces of {} blocks, oldest kept block is #{}, {} bytes used", pruned, usage.oldest_block, usage.size);
+
+		pruned
+	}
+
 	fn cache_size(&self) -> usize {
 		let traces = self.traces.read().heap_size_of_children();
 		let blooms = self.blooms.read().heap_size_of_children();
@@ -164,6 +294,16 @@ impl<T> TraceDB<T> where T: DatabaseExtras {
 		cache_manager.note_used(id);
 	}
 
+	/// Change the preferred and maximal size of the cache, in bytes.
+	pub fn set_cache_size(&self, pref_cache_size: usize, max_cache_size: usize) {
+		self.cache_manager.write().set_cache_size(pref_cache_size, max_cache_size);
+	}
+
+	/// Cache lookups since the last call.
+	pub fn take_cache_stats(&self) -> CacheStats {
+		self.cache_stats.take()
+	}
+
 	/// Ticks our cache system and throws out any old data.
 	pub fn collect_garbage(&self) {
 		let current_size = self.cache_size();
@@ -188,7 +328,7 @@ impl<T> TraceDB<T> where T: DatabaseExtras {
 
 	/// Returns traces for block with hash.
 	fn traces(&self, block_hash: &H256) -> Option<FlatBlockTraces> {
-		let result = self.tracesdb.read_with_cache(db::COL_TRACE, &self.traces, block_hash);
+		let result = self.tracesdb.read_with_cache_stats(db::COL_TRACE, &self.traces, block_hash, &self.cache_stats);
 		self.note_used(CacheId::Trace(block_hash.clone()));
 		result
 	}
@@ -249,6 +389,14 @@ impl<T> TraceDB<T> where T: DatabaseExtras {
 	}
 }
 
+// total size of block traces stored in the database.
+fn measure_size(tracesdb: &KeyValueDB) -> u64 {
+	tracesdb.iter(db::COL_TRACE)
+		.filter(|&(ref key, _)| key.len() == 33 && key[0] == TraceDBIndex::BlockTraces as u8)
+		.map(|(_, value)| value.len() as u64)
+		.sum()
+}
+
 impl<T> TraceDatabase for TraceDB<T> where T: DatabaseExtras {
 	fn tracing_enabled(&self) -> bool {
 		self.enabled
@@ -303,6 +451,10 @@ impl<T> TraceDatabase for TraceDB<T> where T: DatabaseExtras {
 
 		// insert new block traces into the cache and the database
 		{
+			let mut usage = self.usage.write();
+			usage.size += rlp::encode(&request.traces).len() as u64;
+			batch.put(db::COL_TRACE, USAGE_KEY, &rlp::encode(&*usage));
+
 			let mut traces = self.traces.write();
 			// it's important to use overwrite here,
 			// cause this value might be queried by hash later
@@ -403,9 +555,10 @@ impl<T> TraceDatabase for TraceDB<T> where T: DatabaseExtras {
 				let number = n as BlockNumber;
 				let hash = self.extras.block_hash(number)
 					.expect("Expected to find block hash. Extras db is probably corrupted");
-				let traces = self.traces(&hash)
-					.expect("Expected to find a trace. Db is probably corrupted.");
-				self.matching_block_traces(filter, traces, hash, number)
+				// traces of the block may have been pruned.
+				self.traces(&hash)
+					.map(|traces| self.matching_block_traces(filter, traces, hash, number))
+					.unwrap_or_default()
 			})
 			.collect()
 	}
@@ -721,4 +874,37 @@ mod tests {
 
 		assert_eq!(traces.len(), 0);
 	}
+
+	#[test]
+	fn test_prune_by_age() {
+		let db = new_db();
+		let mut config = Config::default();
+		config.enabled = true;
+		config.max_age = Some(10);
+
+		let mut extras = Extras::default();
+		for number in 0..201 {
+			extras.block_hashes.insert(number, H256::from(0x1000 + number));
+			extras.transaction_hashes.insert(number, vec![H256::from(number)]);
+		}
+
+		let tracedb = TraceDB::new(config, db.clone(), Arc::new(extras));
+		for number in 1..201 {
+			let mut batch = DBTransaction::new();
+			tracedb.import(&mut batch, create_simple_import_request(number, H256::from(0x1000 + number)));
+			db.write(batch).unwrap();
+		}
+		let size = tracedb.storage_usage().size;
+
+		// the most recent `MIN_RETAINED_BLOCKS` are kept regardless of the configured age.
+		assert_eq!(tracedb.prune(200), 72);
+		assert_eq!(tracedb.storage_usage().oldest_block, 72);
+		assert!(tracedb.storage_usage().size < size);
+		assert_eq!(tracedb.storage_usage().size, measure_size(&*db));
+		assert!(tracedb.block_traces(71).is_none());
+		assert!(tracedb.block_traces(72).is_some());
+
+		// nothing left to prune until the chain advances.
+		assert_eq!(tracedb.prune(200), 0);
+	}
 }
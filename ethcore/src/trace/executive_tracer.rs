@@ -206,11 +206,12 @@ impl VMTracer for ExecutiveVMTracer {
 
 	fn trace_next_instruction(&mut self, _pc: usize, _instruction: u8, _current_gas: U256) -> bool { true }
 
-	fn trace_prepare_execute(&mut self, pc: usize, instruction: u8, gas_cost: U256) {
+	fn trace_prepare_execute(&mut self, pc: usize, instruction: u8, gas_cost: U256, dynamic_gas: U256) {
 		self.data.operations.push(VMOperation {
 			pc: pc,
 			instruction: instruction,
 			gas_cost: gas_cost,
+			dynamic_gas: dynamic_gas,
 			executed: None,
 		});
 	}
@@ -21,12 +21,14 @@ mod db;
 mod executive_tracer;
 mod import;
 mod noop_tracer;
+mod struct_log_tracer;
 mod types;
 
 pub use self::config::Config;
-pub use self::db::TraceDB;
+pub use self::db::{TraceDB, StorageUsage};
 pub use self::noop_tracer::{NoopTracer, NoopVMTracer};
 pub use self::executive_tracer::{ExecutiveTracer, ExecutiveVMTracer};
+pub use self::struct_log_tracer::{StructLog, StructLogTracer};
 pub use self::import::ImportRequest;
 pub use self::localized::LocalizedTrace;
 
@@ -107,7 +109,8 @@ pub trait VMTracer: Send {
 	fn trace_next_instruction(&mut self, _pc: usize, _instruction: u8, _current_gas: U256) -> bool { false }
 
 	/// Trace the preparation to execute a single valid instruction.
-	fn trace_prepare_execute(&mut self, _pc: usize, _instruction: u8, _gas_cost: U256) {}
+	/// `dynamic_gas` is the part of `gas_cost` depending on the size of the operands.
+	fn trace_prepare_execute(&mut self, _pc: usize, _instruction: u8, _gas_cost: U256, _dynamic_gas: U256) {}
 
 	/// Trace the finalised execution of a single valid instruction.
 	fn trace_executed(&mut self, _gas_used: U256, _stack_push: &[U256], _mem_diff: Option<(usize, &[u8])>, _store_diff: Option<(U256, U256)>) {}
@@ -0,0 +1,173 @@
+// Copyright 2015-2017 Parity Technologies (UK) Ltd.
+// This file is part of Parity.
+
+// Parity is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// Parity is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with Parity.  If not, see <http://www.gnu.org/licenses/>.
+
+//! Step-by-step VM tracer producing Geth-style `structLogs`.
+
+use std::collections::BTreeMap;
+use ethereum_types::{H256, U256};
+use evm::INSTRUCTIONS;
+use trace::VMTracer;
+
+/// Single executed instruction together with the VM state observed right before its execution.
+#[derive(Debug, Clone, PartialEq)]
+pub struct StructLog {
+	/// Program counter.
+	pub pc: usize,
+	/// Opcode.
+	pub op: u8,
+	/// Opcode name.
+	pub op_name: &'static str,
+	/// Gas remaining before execution.
+	pub gas: U256,
+	/// Gas cost of the instruction.
+	pub gas_cost: U256,
+	/// Call depth, starting from 1 for the outermost frame.
+	pub depth: usize,
+	/// Full stack, bottom first.
+	pub stack: Vec<U256>,
+	/// Memory of the current frame.
+	pub memory: Vec<u8>,
+	/// Storage slots of the current contract modified so far in this frame.
+	pub storage: BTreeMap<H256, H256>,
+}
+
+/// VM tracer which records every executed instruction as a `StructLog`.
+///
+/// Stack, memory and storage are reconstructed from the diffs reported by the interpreter,
+/// so memory only reflects regions which were written to.
+pub struct StructLogTracer {
+	depth: usize,
+	gas: U256,
+	instruction: u8,
+	stack: Vec<U256>,
+	memory: Vec<u8>,
+	storage: BTreeMap<H256, H256>,
+	logs: Vec<StructLog>,
+}
+
+impl StructLogTracer {
+	/// Create a new top-level instance.
+	pub fn toplevel() -> Self {
+		StructLogTracer::with_depth(0)
+	}
+
+	fn with_depth(depth: usize) -> Self {
+		StructLogTracer {
+			depth: depth,
+			gas: U256::zero(),
+			instruction: 0,
+			stack: Vec::new(),
+			memory: Vec::new(),
+			storage: BTreeMap::new(),
+			logs: Vec::new(),
+		}
+	}
+}
+
+impl VMTracer for StructLogTracer {
+	type Output = Vec<StructLog>;
+
+	fn trace_next_instruction(&mut self, _pc: usize, instruction: u8, current_gas: U256) -> bool {
+		self.instruction = instruction;
+		self.gas = current_gas;
+		true
+	}
+
+	fn trace_prepare_execute(&mut self, pc: usize, instruction: u8, gas_cost: U256, _dynamic_gas: U256) {
+		self.logs.push(StructLog {
+			pc: pc,
+			op: instruction,
+			op_name: INSTRUCTIONS[instruction as usize].name,
+			gas: self.gas,
+			gas_cost: gas_cost,
+			depth: self.depth,
+			stack: self.stack.clone(),
+			memory: self.memory.clone(),
+			storage: self.storage.clone(),
+		});
+	}
+
+	fn trace_executed(&mut self, _gas_used: U256, stack_push: &[U256], mem_diff: Option<(usize, &[u8])>, store_diff: Option<(U256, U256)>) {
+		let info = INSTRUCTIONS[self.instruction as usize];
+
+		let len = self.stack.len();
+		self.stack.truncate(len.saturating_sub(info.args));
+		self.stack.extend_from_slice(stack_push);
+
+		if let Some((offset, data)) = mem_diff {
+			let end = offset + data.len();
+			if self.memory.len() < end {
+				self.memory.resize(end, 0);
+			}
+			self.memory[offset..end].copy_from_slice(data);
+		}
+
+		if let Some((key, value)) = store_diff {
+			self.storage.insert(key.into(), value.into());
+		}
+	}
+
+	fn prepare_subtrace(&self, _code: &[u8]) -> Self {
+		StructLogTracer::with_depth(self.depth + 1)
+	}
+
+	fn done_subtrace(&mut self, sub: Self) {
+		self.logs.extend(sub.logs);
+	}
+
+	fn drain(self) -> Option<Vec<StructLog>> { Some(self.logs) }
+}
+
+#[cfg(test)]
+mod tests {
+	use ethereum_types::U256;
+	use trace::VMTracer;
+	use super::StructLogTracer;
+
+	#[test]
+	fn should_reconstruct_stack_and_memory() {
+		// PUSH1 0x2a, PUSH1 0x00, MSTORE
+		let mut tracer = StructLogTracer::toplevel();
+		let mut sub = tracer.prepare_subtrace(&[0x60, 0x2a, 0x60, 0x00, 0x52]);
+
+		sub.trace_next_instruction(0, 0x60, 100.into());
+		sub.trace_prepare_execute(0, 0x60, 3.into(), 0.into());
+		sub.trace_executed(97.into(), &[0x2a.into()], None, None);
+
+		sub.trace_next_instruction(2, 0x60, 97.into());
+		sub.trace_prepare_execute(2, 0x60, 3.into(), 0.into());
+		sub.trace_executed(94.into(), &[0.into()], None, None);
+
+		let mut word = [0u8; 32];
+		word[31] = 0x2a;
+		sub.trace_next_instruction(4, 0x52, 94.into());
+		sub.trace_prepare_execute(4, 0x52, 6.into(), 0.into());
+		sub.trace_executed(88.into(), &[], Some((0, &word)), None);
+
+		tracer.done_subtrace(sub);
+		let logs = tracer.drain().unwrap();
+
+		assert_eq!(logs.len(), 3);
+		assert_eq!(logs[0].depth, 1);
+		assert_eq!(logs[0].op_name, "PUSH1");
+		assert!(logs[0].stack.is_empty());
+		assert_eq!(logs[2].op_name, "MSTORE");
+		assert_eq!(logs[2].gas, U256::from(94));
+		assert_eq!(logs[2].gas_cost, U256::from(6));
+		assert_eq!(logs[2].stack, vec![U256::from(0x2a), U256::zero()]);
+		assert!(logs[2].memory.is_empty());
+	}
+}
@@ -47,6 +47,10 @@ pub enum Error {
 	OutOfBounds,
 	/// Execution has been reverted with REVERT instruction.
 	Reverted,
+	/// Contract creation targeted an address which already has code or a nonzero nonce.
+	ContractAddressCollision,
+	/// Execution exceeded the memory limit of the VM.
+	OutOfMemory,
 }
 
 impl<'a> From<&'a VmError> for Error {
@@ -63,6 +67,8 @@ impl<'a> From<&'a VmError> for Error {
 			VmError::MutableCallInStaticContext => Error::MutableCallInStaticContext,
 			VmError::OutOfBounds => Error::OutOfBounds,
 			VmError::Reverted => Error::Reverted,
+			VmError::ContractAddressCollision => Error::ContractAddressCollision,
+			VmError::OutOfMemory { .. } => Error::OutOfMemory,
 		}
 	}
 }
@@ -88,6 +94,8 @@ impl fmt::Display for Error {
 			MutableCallInStaticContext => "Mutable Call In Static Context",
 			OutOfBounds => "Out of bounds",
 			Reverted => "Reverted",
+			ContractAddressCollision => "Contract address collision",
+			OutOfMemory => "Out of memory",
 		};
 		message.fmt(f)
 	}
@@ -108,6 +116,8 @@ impl Encodable for Error {
 			Wasm => 8,
 			OutOfBounds => 9,
 			Reverted => 10,
+			ContractAddressCollision => 11,
+			OutOfMemory => 12,
 		};
 
 		s.append_internal(&value);
@@ -130,6 +140,8 @@ impl Decodable for Error {
 			8 => Ok(Wasm),
 			9 => Ok(OutOfBounds),
 			10 => Ok(Reverted),
+			11 => Ok(ContractAddressCollision),
+			12 => Ok(OutOfMemory),
 			_ => Err(DecoderError::Custom("Invalid error type")),
 		}
 	}
@@ -413,6 +413,8 @@ pub struct VMOperation {
 	pub instruction: u8,
 	/// The gas cost for this instruction.
 	pub gas_cost: U256,
+	/// The part of the gas cost depending on the size of the operands.
+	pub dynamic_gas: U256,
 	/// Information concerning the execution of the operation.
 	pub executed: Option<VMExecutedOperation>,
 }
@@ -0,0 +1,214 @@
+// Copyright 2015-2017 Parity Technologies (UK) Ltd.
+// This file is part of Parity.
+
+// Parity is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// Parity is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with Parity.  If not, see <http://www.gnu.org/licenses/>.
+
+//! Sender certificates issued by a configured authority.
+//!
+//! A certificate is the authority's signature over `keccak(CERTIFICATE_PREFIX ++ sender)`.
+//!
+//! This is a local policy of the node, not a consensus rule: certificates are handed
+//! to each node separately, so other nodes can't be expected to hold the same set.
+//! Transactions from senders without a certificate are rejected when admitted to the
+//! queue and again when the miner includes them in a block it authors, but blocks
+//! imported from the network are not checked. Certificates are persisted in the client
+//! database once it's attached, so they survive restarts.
+
+use std::collections::HashMap;
+use std::sync::Arc;
+use ethereum_types::{H256, H520, Address};
+use ethkey::{recover, public_to_address, Signature};
+use hash::keccak;
+use kvdb::{KeyValueDB, DBTransaction};
+use parking_lot::RwLock;
+use spec::CommonParams;
+use db::COL_NODE_INFO;
+
+const CERTIFICATE_PREFIX: &'static [u8] = b"parity-sender-certificate";
+// prefix of database keys holding certificates, followed by the sender address.
+const CERTIFICATE_KEY_PREFIX: &'static [u8] = b"sender-certificate";
+
+fn certificate_key(sender: &Address) -> Vec<u8> {
+	let mut key = CERTIFICATE_KEY_PREFIX.to_vec();
+	key.extend_from_slice(&*sender);
+	key
+}
+
+/// Returns the message an authority signs to certify given sender.
+pub fn certificate_message(sender: &Address) -> H256 {
+	let mut data = CERTIFICATE_PREFIX.to_vec();
+	data.extend_from_slice(&*sender);
+	keccak(data)
+}
+
+/// Node-side registry of sender certificates.
+pub struct SenderCertifier {
+	authority: Address,
+	certificates: RwLock<HashMap<Address, Signature>>,
+	db: RwLock<Option<Arc<KeyValueDB>>>,
+}
+
+impl SenderCertifier {
+	/// Create a new instance if certificate authority is specified in params.
+	pub fn from_params(params: &CommonParams) -> Option<SenderCertifier> {
+		params.transaction_certificate_authority.map(SenderCertifier::new)
+	}
+
+	/// Create a new certifier trusting certificates signed by `authority`.
+	pub fn new(authority: Address) -> Self {
+		SenderCertifier {
+			authority: authority,
+			certificates: RwLock::new(HashMap::new()),
+			db: RwLock::new(None),
+		}
+	}
+
+	/// Persist certificates in given database and load the ones stored there before.
+	/// Stored certificates not issued by the current authority are dropped.
+	pub fn attach_db(&self, db: Arc<KeyValueDB>) {
+		let mut batch = DBTransaction::new();
+		{
+			let mut certificates = self.certificates.write();
+			for (key, value) in db.iter_from_prefix(COL_NODE_INFO, CERTIFICATE_KEY_PREFIX) {
+				// iterator may continue beyond values beginning with this prefix.
+				if !key.starts_with(CERTIFICATE_KEY_PREFIX) {
+					break;
+				}
+
+				let sender = &key[CERTIFICATE_KEY_PREFIX.len()..];
+				if sender.len() != 20 || value.len() != 65 {
+					batch.delete(COL_NODE_INFO, &key);
+					continue;
+				}
+
+				let sender = Address::from_slice(sender);
+				let certificate: Signature = H520::from_slice(&value).into();
+				if self.is_issued_by_authority(&sender, &certificate) {
+					certificates.insert(sender, certificate);
+				} else {
+					batch.delete(COL_NODE_INFO, &key);
+				}
+			}
+
+			// certificates added before the database was attached.
+			for (sender, certificate) in certificates.iter() {
+				batch.put(COL_NODE_INFO, &certificate_key(sender), &**certificate);
+			}
+			if let Err(e) = db.write(batch) {
+				warn!(target: "certifier", "Failed to store sender certificates: {}", e);
+			}
+		}
+
+		*self.db.write() = Some(db);
+	}
+
+	/// Address of the certificate authority.
+	pub fn authority(&self) -> &Address {
+		&self.authority
+	}
+
+	/// Verifies and stores certificate of given sender.
+	/// Returns `false` if the certificate was not issued by the authority.
+	pub fn add_certificate(&self, sender: Address, certificate: Signature) -> bool {
+		if !self.is_issued_by_authority(&sender, &certificate) {
+			debug!(target: "certifier", "Rejected certificate of {} not issued by {}", sender, self.authority);
+			return false;
+		}
+
+		self.persist(|batch| batch.put(COL_NODE_INFO, &certificate_key(&sender), &*certificate));
+		self.certificates.write().insert(sender, certificate);
+		true
+	}
+
+	/// Revokes certificate of given sender.
+	pub fn revoke(&self, sender: &Address) {
+		self.persist(|batch| batch.delete(COL_NODE_INFO, &certificate_key(sender)));
+		self.certificates.write().remove(sender);
+	}
+
+	/// Checks whether given sender holds a valid certificate.
+	pub fn is_certified(&self, sender: &Address) -> bool {
+		self.certificates.read().contains_key(sender)
+	}
+
+	fn is_issued_by_authority(&self, sender: &Address, certificate: &Signature) -> bool {
+		recover(certificate, &certificate_message(sender))
+			.map(|public| public_to_address(&public) == self.authority)
+			.unwrap_or(false)
+	}
+
+	// write the change to the attached database, if any.
+	fn persist<F: FnOnce(&mut DBTransaction)>(&self, f: F) {
+		if let Some(ref db) = *self.db.read() {
+			let mut batch = DBTransaction::new();
+			f(&mut batch);
+			if let Err(e) = db.write(batch) {
+				warn!(target: "certifier", "Failed to store sender certificates: {}", e);
+			}
+		}
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use std::sync::Arc;
+	use ethkey::{Generator, Random, sign};
+	use kvdb_memorydb;
+	use super::{SenderCertifier, certificate_message};
+
+	#[test]
+	fn accepts_only_authority_certificates() {
+		let authority = Random.generate().unwrap();
+		let other = Random.generate().unwrap();
+		let sender = Random.generate().unwrap().address();
+		let certifier = SenderCertifier::new(authority.address());
+
+		assert!(!certifier.is_certified(&sender));
+
+		let forged = sign(other.secret(), &certificate_message(&sender)).unwrap();
+		assert!(!certifier.add_certificate(sender, forged));
+		assert!(!certifier.is_certified(&sender));
+
+		let certificate = sign(authority.secret(), &certificate_message(&sender)).unwrap();
+		assert!(certifier.add_certificate(sender, certificate));
+		assert!(certifier.is_certified(&sender));
+
+		certifier.revoke(&sender);
+		assert!(!certifier.is_certified(&sender));
+	}
+
+	#[test]
+	fn keeps_certificates_in_attached_db() {
+		let authority = Random.generate().unwrap();
+		let sender = Random.generate().unwrap().address();
+		let revoked = Random.generate().unwrap().address();
+		let db = Arc::new(kvdb_memorydb::create(::db::NUM_COLUMNS.unwrap_or(0)));
+
+		let certifier = SenderCertifier::new(authority.address());
+		certifier.attach_db(db.clone());
+		assert!(certifier.add_certificate(sender, sign(authority.secret(), &certificate_message(&sender)).unwrap()));
+		assert!(certifier.add_certificate(revoked, sign(authority.secret(), &certificate_message(&revoked)).unwrap()));
+		certifier.revoke(&revoked);
+
+		let restarted = SenderCertifier::new(authority.address());
+		restarted.attach_db(db.clone());
+		assert!(restarted.is_certified(&sender));
+		assert!(!restarted.is_certified(&revoked));
+
+		// certificates of a previous authority are dropped.
+		let other = SenderCertifier::new(Random.generate().unwrap().address());
+		other.attach_db(db);
+		assert!(!other.is_certified(&sender));
+	}
+}
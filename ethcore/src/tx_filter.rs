@@ -15,6 +15,10 @@
 // along with Parity.  If not, see <http://www.gnu.org/licenses/>.
 
 //! Smart contract based transaction filter.
+//!
+//! Version 1 contracts grant permissions based on the sender only. Contracts exposing
+//! `contractVersion()` returning 2 are also given the recipient, value and gas of the
+//! transaction, and tell whether their answer may be cached for the sender.
 
 use ethereum_types::{H256, Address};
 use lru_cache::LruCache;
@@ -26,8 +30,10 @@ use transaction::{Action, SignedTransaction};
 use hash::KECCAK_EMPTY;
 
 use_contract!(transact_acl, "TransactAcl", "res/contracts/tx_acl.json");
+use_contract!(transact_acl_v2, "TransactAclV2", "res/contracts/tx_acl_v2.json");
 
 const MAX_CACHE_SIZE: usize = 4096;
+const MAX_VERSION_CACHE_SIZE: usize = 256;
 
 mod tx_permissions {
 	pub const _ALL: u32 = 0xffffffff;
@@ -41,8 +47,10 @@ mod tx_permissions {
 /// Connection filter that uses a contract to manage permissions.
 pub struct TransactionFilter {
 	contract: transact_acl::TransactAcl,
+	contract_v2: transact_acl_v2::TransactAclV2,
 	contract_address: Address,
 	permission_cache: Mutex<LruCache<(H256, Address), u32>>,
+	version_cache: Mutex<LruCache<H256, u64>>,
 }
 
 impl TransactionFilter {
@@ -51,8 +59,10 @@ impl TransactionFilter {
 		params.transaction_permission_contract.map(|address|
 			TransactionFilter {
 				contract: transact_acl::TransactAcl::default(),
+				contract_v2: transact_acl_v2::TransactAclV2::default(),
 				contract_address: address,
 				permission_cache: Mutex::new(LruCache::new(MAX_CACHE_SIZE)),
+				version_cache: Mutex::new(LruCache::new(MAX_VERSION_CACHE_SIZE)),
 			}
 		)
 	}
@@ -78,20 +88,62 @@ impl TransactionFilter {
 		}
 
 		let contract_address = self.contract_address;
-		let permissions = self.contract.functions()
-			.allowed_tx_types()
-			.call(sender, &|data| client.call_contract(BlockId::Hash(*parent_hash), contract_address, data))
-			.map(|p| p.low_u32())
-			.unwrap_or_else(|e| {
-				debug!("Error callling tx permissions contract: {:?}", e);
-				tx_permissions::NONE
-			});
+		let call = |data| client.call_contract(BlockId::Hash(*parent_hash), contract_address, data);
+		let (permissions, cacheable) = match self.contract_version(parent_hash, &call) {
+			1 => {
+				let permissions = self.contract.functions()
+					.allowed_tx_types()
+					.call(sender, &call)
+					.map(|p| p.low_u32())
+					.unwrap_or_else(|e| {
+						debug!("Error callling tx permissions contract: {:?}", e);
+						tx_permissions::NONE
+					});
+				(permissions, true)
+			},
+			2 => {
+				let to = match transaction.action {
+					Action::Create => Address::new(),
+					Action::Call(address) => address,
+				};
+				self.contract_v2.functions()
+					.allowed_tx_types()
+					.call(sender, to, transaction.value, transaction.gas, &call)
+					.map(|(p, cacheable)| (p.low_u32(), cacheable))
+					.unwrap_or_else(|e| {
+						debug!("Error callling tx permissions contract: {:?}", e);
+						(tx_permissions::NONE, false)
+					})
+			},
+			version => {
+				debug!("Unsupported tx permissions contract version {}", version);
+				(tx_permissions::NONE, false)
+			},
+		};
 
-		cache.insert((*parent_hash, sender), permissions);
+		if cacheable {
+			cache.insert((*parent_hash, sender), permissions);
+		}
 
 		trace!("Permissions required: {}, got: {}", tx_type, permissions);
 		permissions & tx_type != 0
 	}
+
+	// version of the contract at given block; contracts without `contractVersion()` are version 1.
+	fn contract_version<F>(&self, parent_hash: &H256, call: &F) -> u64 where F: Fn(Vec<u8>) -> Result<Vec<u8>, String> {
+		let mut cache = self.version_cache.lock();
+		if let Some(version) = cache.get_mut(parent_hash) {
+			return *version;
+		}
+
+		let version = self.contract_v2.functions()
+			.contract_version()
+			.call(call)
+			.ok()
+			.map_or(1, |v| v.low_u64());
+		cache.insert(*parent_hash, version);
+		version
+	}
 }
 
 #[cfg(test)]
@@ -197,5 +249,92 @@ mod test {
 		assert!(!filter.transaction_allowed(&genesis, &create_tx.clone().sign(key4.secret(), None), &*client));
 		assert!(!filter.transaction_allowed(&genesis, &call_tx.clone().sign(key4.secret(), None), &*client));
 	}
-}
 
+	/// Version 2 contract: allows anything for 0x7e5f4552091a69125d5dfcb7b8c2659029395bdf
+	/// and caches it, allows only basic transactions and calls without value for others
+	/// and doesn't cache it.
+	#[test]
+	fn transaction_filter_v2() {
+		let spec_data = r#"
+		{
+			"name": "TestNodeFilterContract",
+			"engine": {
+				"authorityRound": {
+					"params": {
+						"stepDuration": 1,
+						"startStep": 2,
+						"validators": {
+							"contract": "0x0000000000000000000000000000000000000000"
+						}
+					}
+				}
+			},
+			"params": {
+				"accountStartNonce": "0x0",
+				"maximumExtraDataSize": "0x20",
+				"minGasLimit": "0x1388",
+				"networkID" : "0x69",
+				"gasLimitBoundDivisor": "0x0400",
+				"transactionPermissionContract": "0x0000000000000000000000000000000000000005"
+			},
+			"genesis": {
+				"seal": {
+					"generic": "0xc180"
+				},
+				"difficulty": "0x20000",
+				"author": "0x0000000000000000000000000000000000000000",
+				"timestamp": "0x00",
+				"parentHash": "0x0000000000000000000000000000000000000000000000000000000000000000",
+				"extraData": "0x",
+				"gasLimit": "0x222222"
+			},
+			"accounts": {
+				"0000000000000000000000000000000000000001": { "balance": "1", "builtin": { "name": "ecrecover", "pricing": { "linear": { "base": 3000, "word": 0 } } } },
+				"0000000000000000000000000000000000000002": { "balance": "1", "builtin": { "name": "sha256", "pricing": { "linear": { "base": 60, "word": 12 } } } },
+				"0000000000000000000000000000000000000003": { "balance": "1", "builtin": { "name": "ripemd160", "pricing": { "linear": { "base": 600, "word": 120 } } } },
+				"0000000000000000000000000000000000000004": { "balance": "1", "builtin": { "name": "identity", "pricing": { "linear": { "base": 15, "word": 3 } } } },
+				"0000000000000000000000000000000000000005": {
+					"balance": "1",
+					"code": "0x7c0100000000000000000000000000000000000000000000000000000000600035048063a0a8e460146100385763a78500e91461004357fe5b600260005260206000f35b600435737e5f4552091a69125d5dfcb7b8c2659029395bdf1461008757604435610077576003600052600060205260406000f35b6000600052600060205260406000f35b63ffffffff600052600160205260406000f3"
+				}
+			}
+		}
+		"#;
+
+		let tempdir = TempDir::new("").unwrap();
+		let spec = Spec::load(&tempdir.path(), spec_data.as_bytes()).unwrap();
+		let client_db = Arc::new(::kvdb_memorydb::create(::db::NUM_COLUMNS.unwrap_or(0)));
+
+		let client = Client::new(
+			ClientConfig::default(),
+			&spec,
+			client_db,
+			Arc::new(Miner::new_for_tests(&spec, None)),
+			IoChannel::disconnected(),
+		).unwrap();
+		let key1 = KeyPair::from_secret(Secret::from("0000000000000000000000000000000000000000000000000000000000000001")).unwrap();
+		let key2 = KeyPair::from_secret(Secret::from("0000000000000000000000000000000000000000000000000000000000000002")).unwrap();
+
+		let filter = TransactionFilter::from_params(spec.params()).unwrap();
+		let mut basic_tx = Transaction::default();
+		basic_tx.action = Action::Call(Address::from("000000000000000000000000000000000000032"));
+		let mut basic_tx_with_value = basic_tx.clone();
+		basic_tx_with_value.value = 1.into();
+		let create_tx = Transaction::default();
+		let mut call_tx = Transaction::default();
+		call_tx.action = Action::Call(Address::from("0000000000000000000000000000000000000005"));
+
+		let genesis = client.block_hash(BlockId::Latest).unwrap();
+
+		assert!(filter.transaction_allowed(&genesis, &basic_tx_with_value.clone().sign(key1.secret(), None), &*client));
+		assert!(filter.transaction_allowed(&genesis, &create_tx.clone().sign(key1.secret(), None), &*client));
+		assert!(filter.transaction_allowed(&genesis, &call_tx.clone().sign(key1.secret(), None), &*client));
+
+		// permissions of key2 depend on the value, so they aren't cached.
+		assert!(filter.transaction_allowed(&genesis, &basic_tx.clone().sign(key2.secret(), None), &*client));
+		assert!(!filter.transaction_allowed(&genesis, &basic_tx_with_value.clone().sign(key2.secret(), None), &*client));
+		assert!(filter.transaction_allowed(&genesis, &basic_tx.clone().sign(key2.secret(), None), &*client));
+		assert!(filter.transaction_allowed(&genesis, &call_tx.clone().sign(key2.secret(), None), &*client));
+		assert!(!filter.transaction_allowed(&genesis, &create_tx.clone().sign(key2.secret(), None), &*client));
+	}
+}
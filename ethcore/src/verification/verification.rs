@@ -284,6 +284,12 @@ pub fn verify_header_params(header: &Header, engine: &EthEngine, is_full: bool)
 	}
 
 	if is_full {
+		if let Some((fork_number, fork_hash)) = engine.params().fork_block {
+			if header.number() == fork_number && header.hash() != fork_hash {
+				return Err(From::from(BlockError::InvalidForkHash(Mismatch { expected: fork_hash, found: header.hash() })));
+			}
+		}
+
 		const ACCEPTABLE_DRIFT: Duration = Duration::from_secs(15);
 		let max_time = SystemTime::now() + ACCEPTABLE_DRIFT;
 		let invalid_threshold = max_time + ACCEPTABLE_DRIFT * 9;
@@ -757,4 +763,26 @@ mod tests {
 		check_fail(unordered_test(&create_test_block_with_data(&header, &bad_transactions, &[]), &engine), TooManyTransactions(keypair.address()));
 		unordered_test(&create_test_block_with_data(&header, &good_transactions, &[]), &engine).unwrap();
 	}
+
+	#[test]
+	fn fork_block_hash() {
+		use machine::EthereumMachine;
+		use engines::NullEngine;
+
+		let mut header = Header::default();
+		header.set_number(10);
+		let fork_hash = header.hash();
+		let mut other = header.clone();
+		other.set_timestamp(1);
+
+		let mut params = CommonParams::default();
+		params.fork_block = Some((10, fork_hash));
+		let machine = EthereumMachine::regular(params, BTreeMap::new());
+		let engine = NullEngine::new(Default::default(), machine);
+
+		check_ok(verify_header_params(&header, &engine, true));
+		check_fail(verify_header_params(&other, &engine, true), InvalidForkHash(Mismatch { expected: fork_hash, found: other.hash() }));
+		// uncles from the other side of the fork are fine.
+		check_ok(verify_header_params(&other, &engine, false));
+	}
 }
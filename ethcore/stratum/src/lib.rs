@@ -46,7 +46,7 @@ use std::sync::Arc;
 use std::net::SocketAddr;
 use std::collections::{HashSet, HashMap};
 use hash::keccak;
-use ethereum_types::H256;
+use ethereum_types::{H256, U256};
 use parking_lot::RwLock;
 
 type RpcResult = Result<jsonrpc_core::Value, jsonrpc_core::Error>;
@@ -81,6 +81,7 @@ impl Stratum {
 			job_que: RwLock::default(),
 			dispatcher,
 			workers: Arc::new(RwLock::default()),
+			difficulties: RwLock::default(),
 			secret,
 			notify_counter: RwLock::new(NOTIFY_COUNTER_INITIAL),
 		});
@@ -88,6 +89,7 @@ impl Stratum {
 		let mut delegate = IoDelegate::<StratumImpl, SocketMetadata>::new(implementation.clone());
 		delegate.add_method_with_meta("mining.subscribe", StratumImpl::subscribe);
 		delegate.add_method_with_meta("mining.authorize", StratumImpl::authorize);
+		delegate.add_method_with_meta("mining.suggest_difficulty", StratumImpl::suggest_difficulty);
 		delegate.add_method_with_meta("mining.submit", StratumImpl::submit);
 		let mut handler = MetaIoHandler::<SocketMetadata>::with_compatibility(Compatibility::Both);
 		handler.extend_with(delegate);
@@ -133,6 +135,8 @@ struct StratumImpl {
 	dispatcher: Arc<JobDispatcher>,
 	/// Authorized workers (socket - worker_id)
 	workers: Arc<RwLock<HashMap<SocketAddr, String>>>,
+	/// Share difficulties requested by workers (socket - difficulty)
+	difficulties: RwLock<HashMap<SocketAddr, U256>>,
 	/// Secret if any
 	secret: Option<H256>,
 	/// Dispatch notify couinter
@@ -175,26 +179,49 @@ impl StratumImpl {
 		}).map(|v| v.expect("Only true/false is returned and it's always serializable; qed"))
 	}
 
+	/// rpc method `mining.suggest_difficulty`
+	///
+	/// Sets the share difficulty of the connection, work pushed to it and shares submitted by it use that difficulty.
+	fn suggest_difficulty(&self, params: Params, meta: SocketMetadata) -> RpcResult {
+		params.parse::<(u64,)>().map(|(difficulty,)| {
+			if difficulty == 0 {
+				return to_value(&false);
+			}
+			trace!(target: "stratum", "Share difficulty of {} set to {}", meta.addr(), difficulty);
+			self.difficulties.write().insert(meta.addr().clone(), difficulty.into());
+			to_value(true)
+		}).map(|v| v.expect("Only true/false is returned and it's always serializable; qed"))
+	}
+
 	/// rpc method `mining.submit`
 	fn submit(&self, params: Params, meta: SocketMetadata) -> RpcResult {
 		Ok(match params {
 			Params::Array(vals) => {
 				// first two elements are service messages (worker_id & job_id)
-				match self.dispatcher.submit(vals.iter().skip(2)
+				let payload = vals.iter().skip(2)
 					.filter_map(|val| match *val {
 						Value::String(ref s) => Some(s.to_owned()),
 						_ => None
 					})
-					.collect::<Vec<String>>()) {
-						Ok(()) => {
+					.collect::<Vec<String>>();
+				let difficulty = self.difficulties.read().get(meta.addr()).cloned();
+				let result = match difficulty {
+					Some(difficulty) => self.dispatcher.submit_share(payload, difficulty),
+					None => self.dispatcher.submit(payload),
+				};
+				match result {
+					Ok(()) => {
+						// shares below the block difficulty don't change the work, sealed blocks are notified by the miner.
+						if difficulty.is_none() {
 							self.update_peers(&meta.tcp_dispatcher.expect("tcp_dispatcher is always initialized; qed"));
-							to_value(true)
-						},
-						Err(submit_err) => {
-							warn!("Error while submitting share: {:?}", submit_err);
-							to_value(false)
 						}
+						to_value(true)
+					},
+					Err(submit_err) => {
+						warn!("Error while submitting share: {:?}", submit_err);
+						to_value(false)
 					}
+				}
 			},
 			_ => {
 				trace!(target: "stratum", "Invalid submit work format {:?}", params);
@@ -225,9 +252,15 @@ impl StratumImpl {
 			let mut hup_peers = HashSet::with_capacity(0); // most of the cases won't be needed, hence avoid allocation
 			let workers_msg = format!("{{ \"id\": {}, \"method\": \"mining.notify\", \"params\": {} }}", next_request_id, payload);
 			trace!(target: "stratum", "pushing work for {} workers (payload: '{}')", workers.len(), &workers_msg);
+			let difficulties = self.difficulties.read();
 			for (ref addr, _) in workers.iter() {
 				trace!(target: "stratum", "pusing work to {}", addr);
-				match tcp_dispatcher.push_message(addr, workers_msg.clone()) {
+				// workers with their own share difficulty get work with their own target
+				let msg = match difficulties.get(*addr).and_then(|d| self.dispatcher.share_job(*d)) {
+					Some(payload) => format!("{{ \"id\": {}, \"method\": \"mining.notify\", \"params\": {} }}", next_request_id, payload),
+					None => workers_msg.clone(),
+				};
+				match tcp_dispatcher.push_message(addr, msg) {
 					Err(PushMessageError::NoSuchPeer) => {
 						trace!(target: "stratum", "Worker no longer connected: {}", &addr);
 						hup_peers.insert(*addr.clone());
@@ -243,7 +276,11 @@ impl StratumImpl {
 
 		if !hup_peers.is_empty() {
 			let mut workers = self.workers.write();
-			for hup_peer in hup_peers { workers.remove(&hup_peer); }
+			let mut difficulties = self.difficulties.write();
+			for hup_peer in hup_peers {
+				workers.remove(&hup_peer);
+				difficulties.remove(&hup_peer);
+			}
 		}
 
 		Ok(())
@@ -443,6 +480,25 @@ mod tests {
 		assert_eq!(1, stratum.implementation.workers.read().len());
 	}
 
+	#[test]
+	fn can_suggest_difficulty() {
+		let addr = "127.0.0.1:19965".parse().unwrap();
+		let stratum = Stratum::start(&addr, DummyManager::new(), None).expect("There should be no error starting stratum");
+
+		let request = r#"{"jsonrpc": "2.0", "method": "mining.suggest_difficulty", "params": [0], "id": 1}"#;
+		let response = String::from_utf8(dummy_request(&addr, request)).unwrap();
+		assert_eq!(terminated_str(r#"{"jsonrpc":"2.0","result":false,"id":1}"#), response);
+		assert!(stratum.implementation.difficulties.read().is_empty());
+
+		let request = r#"{"jsonrpc": "2.0", "method": "mining.suggest_difficulty", "params": [1000], "id": 2}"#;
+		let response = String::from_utf8(dummy_request(&addr, request)).unwrap();
+		assert_eq!(terminated_str(r#"{"jsonrpc":"2.0","result":true,"id":2}"#), response);
+		assert_eq!(
+			stratum.implementation.difficulties.read().values().cloned().collect::<Vec<_>>(),
+			vec![U256::from(1000)]
+		);
+	}
+
 	#[test]
 	fn can_push_work() {
 		init_log();
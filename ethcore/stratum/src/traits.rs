@@ -16,7 +16,7 @@
 
 use std;
 use std::error::Error as StdError;
-use ethereum_types::H256;
+use ethereum_types::{H256, U256};
 use jsonrpc_tcp_server::PushMessageError;
 
 #[derive(Debug, Clone)]
@@ -48,8 +48,12 @@ pub trait JobDispatcher: Send + Sync {
 	fn difficulty(&self) -> Option<String> { None }
 	// json for job update given worker_id (payload manager should split job!)
 	fn job(&self) -> Option<String> { None }
+	// json for job update of a worker with its own share difficulty
+	fn share_job(&self, _difficulty: U256) -> Option<String> { self.job() }
 	// miner job result
 	fn submit(&self, payload: Vec<String>) -> Result<(), Error>;
+	// miner share result of a worker with its own share difficulty
+	fn submit_share(&self, payload: Vec<String>, _difficulty: U256) -> Result<(), Error> { self.submit(payload) }
 }
 
 /// Interface that can handle requests to push job for workers
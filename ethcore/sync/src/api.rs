@@ -30,12 +30,13 @@ use ethcore::client::{BlockChainClient, ChainNotify, ChainRoute, ChainMessageTyp
 use ethcore::snapshot::SnapshotService;
 use ethcore::header::BlockNumber;
 use sync_io::NetSyncIo;
-use chain::{ChainSync, SyncStatus as EthSyncStatus};
+use chain::{ChainSync, SyncStatus as EthSyncStatus, DEFAULT_LOCAL_TRANSACTIONS_REBROADCAST_INTERVAL};
+use block_sync::{DEFAULT_DOWNLOAD_MEMORY_BUDGET, DEFAULT_MAX_DOWNLOAD_AHEAD_BLOCKS};
 use std::net::{SocketAddr, AddrParseError};
 use std::str::FromStr;
 use parking_lot::RwLock;
 use chain::{ETH_PROTOCOL_VERSION_63, ETH_PROTOCOL_VERSION_62,
-	PAR_PROTOCOL_VERSION_1, PAR_PROTOCOL_VERSION_2, PAR_PROTOCOL_VERSION_3};
+	PAR_PROTOCOL_VERSION_1, PAR_PROTOCOL_VERSION_2, PAR_PROTOCOL_VERSION_3, PAR_PROTOCOL_VERSION_4};
 use light::client::AsLightClient;
 use light::Provider;
 use light::net::{self as light_net, LightProtocol, Params as LightParams, Capabilities, Handler as LightHandler, EventContext};
@@ -90,6 +91,8 @@ impl WarpSync {
 pub struct SyncConfig {
 	/// Max blocks to download ahead
 	pub max_download_ahead_blocks: usize,
+	/// Max heap size of downloaded blocks, bodies and receipts waiting to be imported, in bytes.
+	pub download_memory_budget: usize,
 	/// Enable ancient block download.
 	pub download_old_blocks: bool,
 	/// Network ID
@@ -104,12 +107,17 @@ pub struct SyncConfig {
 	pub warp_sync: WarpSync,
 	/// Enable light client server.
 	pub serve_light: bool,
+	/// Heal the state left incomplete by warp sync.
+	pub state_healing: bool,
+	/// Local transactions are propagated again this often, until they are mined.
+	pub local_transactions_rebroadcast_interval: Duration,
 }
 
 impl Default for SyncConfig {
 	fn default() -> SyncConfig {
 		SyncConfig {
-			max_download_ahead_blocks: 20000,
+			max_download_ahead_blocks: DEFAULT_MAX_DOWNLOAD_AHEAD_BLOCKS,
+			download_memory_budget: DEFAULT_DOWNLOAD_MEMORY_BUDGET,
 			download_old_blocks: true,
 			network_id: 1,
 			subprotocol_name: ETH_PROTOCOL,
@@ -117,6 +125,8 @@ impl Default for SyncConfig {
 			fork_block: None,
 			warp_sync: WarpSync::Disabled,
 			serve_light: false,
+			state_healing: false,
+			local_transactions_rebroadcast_interval: DEFAULT_LOCAL_TRANSACTIONS_REBROADCAST_INTERVAL,
 		}
 	}
 }
@@ -460,7 +470,7 @@ impl ChainNotify for EthSync {
 		self.network.register_protocol(self.eth_handler.clone(), self.subprotocol_name, &[ETH_PROTOCOL_VERSION_62, ETH_PROTOCOL_VERSION_63])
 			.unwrap_or_else(|e| warn!("Error registering ethereum protocol: {:?}", e));
 		// register the warp sync subprotocol
-		self.network.register_protocol(self.eth_handler.clone(), WARP_SYNC_PROTOCOL_ID, &[PAR_PROTOCOL_VERSION_1, PAR_PROTOCOL_VERSION_2, PAR_PROTOCOL_VERSION_3])
+		self.network.register_protocol(self.eth_handler.clone(), WARP_SYNC_PROTOCOL_ID, &[PAR_PROTOCOL_VERSION_1, PAR_PROTOCOL_VERSION_2, PAR_PROTOCOL_VERSION_3, PAR_PROTOCOL_VERSION_4])
 			.unwrap_or_else(|e| warn!("Error registering snapshot sync protocol: {:?}", e));
 
 		// register the light protocol.
@@ -489,6 +499,16 @@ impl ChainNotify for EthSync {
 		});
 	}
 
+	fn send(&self, message_type: ChainMessageType, node_id: &H512) {
+		self.network.with_context(WARP_SYNC_PROTOCOL_ID, |context| {
+			let mut sync_io = NetSyncIo::new(context, &*self.eth_handler.chain, &*self.eth_handler.snapshot_service, &self.eth_handler.overlay);
+			match message_type {
+				ChainMessageType::Consensus(message) => self.eth_handler.sync.write().send_consensus_packet(&mut sync_io, message, node_id),
+				_ => debug!(target: "sync", "Only consensus messages can be sent to a single peer"),
+			}
+		});
+	}
+
 	fn transactions_received(&self, txs: &[UnverifiedTransaction], peer_id: PeerId) {
 		let mut sync = self.eth_handler.sync.write();
 		sync.transactions_received(txs, peer_id);
@@ -724,6 +744,8 @@ pub struct LightSyncParams<L> {
 	pub handlers: Vec<Arc<LightHandler>>,
 	/// Other subprotocols to run.
 	pub attached_protos: Vec<AttachedProtocol>,
+	/// Schedule deciding when to sync. Keep a reference to feed it host hints.
+	pub schedule: Arc<::light_sync::SyncSchedule>,
 }
 
 /// Service for light synchronization.
@@ -758,7 +780,7 @@ impl LightSync {
 			};
 
 			let mut light_proto = LightProtocol::new(params.client.clone(), light_params);
-			let sync_handler = Arc::new(SyncHandler::new(params.client.clone())?);
+			let sync_handler = Arc::new(SyncHandler::new(params.client.clone())?.with_schedule(params.schedule));
 			light_proto.add_handler(sync_handler.clone());
 
 			for handler in params.handlers {
@@ -37,6 +37,10 @@ const MAX_RECEPITS_TO_REQUEST: usize = 128;
 const SUBCHAIN_SIZE: u64 = 256;
 const MAX_ROUND_PARENTS: usize = 16;
 const MAX_PARALLEL_SUBCHAIN_DOWNLOAD: usize = 5;
+/// Default memory budget for downloaded blocks waiting to be imported.
+pub const DEFAULT_DOWNLOAD_MEMORY_BUDGET: usize = 256 * 1024 * 1024;
+/// Default number of downloaded blocks waiting to be imported.
+pub const DEFAULT_MAX_DOWNLOAD_AHEAD_BLOCKS: usize = 20000;
 
 #[derive(Copy, Clone, Eq, PartialEq, Debug)]
 /// Downloader state
@@ -110,6 +114,10 @@ pub struct BlockDownloader {
 	retract_step: u64,
 	/// Whether reorg should be limited.
 	limit_reorg: bool,
+	/// Max number of downloaded blocks held at once.
+	max_ahead_blocks: usize,
+	/// Max heap size of downloaded blocks held at once.
+	memory_budget: usize,
 }
 
 impl BlockDownloader {
@@ -130,6 +138,8 @@ impl BlockDownloader {
 			target_hash: None,
 			retract_step: 1,
 			limit_reorg: true,
+			max_ahead_blocks: DEFAULT_MAX_DOWNLOAD_AHEAD_BLOCKS,
+			memory_budget: DEFAULT_DOWNLOAD_MEMORY_BUDGET,
 		}
 	}
 
@@ -149,6 +159,8 @@ impl BlockDownloader {
 			target_hash: None,
 			retract_step: 1,
 			limit_reorg: false,
+			max_ahead_blocks: DEFAULT_MAX_DOWNLOAD_AHEAD_BLOCKS,
+			memory_budget: DEFAULT_DOWNLOAD_MEMORY_BUDGET,
 		}
 	}
 
@@ -184,6 +196,24 @@ impl BlockDownloader {
 		self.target_hash = Some(hash.clone());
 	}
 
+	/// Limit the number of downloaded blocks and their heap size held before import.
+	/// Requests shrink as the limits are approached, down to a single item once exceeded.
+	pub fn set_download_limits(&mut self, max_ahead_blocks: usize, memory_budget: usize) {
+		self.max_ahead_blocks = cmp::max(1, max_ahead_blocks);
+		self.memory_budget = cmp::max(1, memory_budget);
+	}
+
+	/// Scale `max` items to the share of download limits left unused.
+	fn request_count(&self, max: usize) -> usize {
+		let blocks_left = self.max_ahead_blocks.saturating_sub(self.blocks.len());
+		let memory_left = self.memory_budget.saturating_sub(self.blocks.heap_size());
+		let scaled = cmp::min(
+			blocks_left as u64 * max as u64 / self.max_ahead_blocks as u64,
+			memory_left as u64 * max as u64 / self.memory_budget as u64,
+		);
+		cmp::max(1, scaled as usize)
+	}
+
 	/// Unmark header as being downloaded.
 	pub fn clear_header_download(&mut self, hash: &H256) {
 		self.blocks.clear_header_download(hash)
@@ -438,7 +468,7 @@ impl BlockDownloader {
 			},
 			State::Blocks => {
 				// check to see if we need to download any block bodies first
-				let needed_bodies = self.blocks.needed_bodies(MAX_BODIES_TO_REQUEST, false);
+				let needed_bodies = self.blocks.needed_bodies(self.request_count(MAX_BODIES_TO_REQUEST), false);
 				if !needed_bodies.is_empty() {
 					return Some(BlockRequest::Bodies {
 						hashes: needed_bodies,
@@ -446,7 +476,7 @@ impl BlockDownloader {
 				}
 
 				if self.download_receipts {
-					let needed_receipts = self.blocks.needed_receipts(MAX_RECEPITS_TO_REQUEST, false);
+					let needed_receipts = self.blocks.needed_receipts(self.request_count(MAX_RECEPITS_TO_REQUEST), false);
 					if !needed_receipts.is_empty() {
 						return Some(BlockRequest::Receipts {
 							hashes: needed_receipts,
@@ -455,7 +485,7 @@ impl BlockDownloader {
 				}
 
 				// find subchain to download
-				if let Some((h, count)) = self.blocks.needed_headers(MAX_HEADERS_TO_REQUEST, false) {
+				if let Some((h, count)) = self.blocks.needed_headers(self.request_count(MAX_HEADERS_TO_REQUEST), false) {
 					return Some(BlockRequest::Headers {
 						start: h,
 						count: count as u64,
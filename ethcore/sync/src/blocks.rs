@@ -314,6 +314,11 @@ impl BlockCollection {
 		self.heads.len() == 0 || (self.heads.len() == 1 && self.head.map_or(false, |h| h == self.heads[0]))
 	}
 
+	/// Number of downloaded block headers held in the collection.
+	pub fn len(&self) -> usize {
+		self.blocks.len()
+	}
+
 	/// Check if collection contains a block header.
 	pub fn contains(&self, hash: &H256) -> bool {
 		self.blocks.contains_key(hash)
@@ -38,18 +38,22 @@ use super::{
 	PacketDecodeError,
 	PeerAsking,
 	PeerInfo,
+	SyncPropagator,
 	SyncRequester,
 	SyncState,
 	ETH_PROTOCOL_VERSION_62,
 	ETH_PROTOCOL_VERSION_63,
 	MAX_NEW_BLOCK_AGE,
 	MAX_NEW_HASHES,
+	MAX_POOLED_TRANSACTION_HASHES,
 	PAR_PROTOCOL_VERSION_1,
-	PAR_PROTOCOL_VERSION_3,
+	PAR_PROTOCOL_VERSION_4,
 	BLOCK_BODIES_PACKET,
 	BLOCK_HEADERS_PACKET,
 	NEW_BLOCK_HASHES_PACKET,
 	NEW_BLOCK_PACKET,
+	NEW_POOLED_TRANSACTION_HASHES_PACKET,
+	NODE_DATA_PACKET,
 	PRIVATE_TRANSACTION_PACKET,
 	RECEIPTS_PACKET,
 	SIGNED_PRIVATE_TRANSACTION_PACKET,
@@ -80,8 +84,10 @@ impl SyncHandler {
 			NEW_BLOCK_HASHES_PACKET => SyncHandler::on_peer_new_hashes(sync, io, peer, &rlp),
 			SNAPSHOT_MANIFEST_PACKET => SyncHandler::on_snapshot_manifest(sync, io, peer, &rlp),
 			SNAPSHOT_DATA_PACKET => SyncHandler::on_snapshot_data(sync, io, peer, &rlp),
+			NODE_DATA_PACKET => SyncHandler::on_peer_node_data(sync, io, peer, &rlp),
 			PRIVATE_TRANSACTION_PACKET => SyncHandler::on_private_transaction(sync, io, peer, &rlp),
 			SIGNED_PRIVATE_TRANSACTION_PACKET => SyncHandler::on_signed_private_transaction(sync, io, peer, &rlp),
+			NEW_POOLED_TRANSACTION_HASHES_PACKET => SyncHandler::on_peer_pooled_transaction_hashes(sync, io, peer, &rlp),
 			_ => {
 				debug!(target: "sync", "{}: Unknown packet {}", peer, packet_id);
 				Ok(())
@@ -95,7 +101,8 @@ impl SyncHandler {
 	/// Called when peer sends us new consensus packet
 	pub fn on_consensus_packet(io: &mut SyncIo, peer_id: PeerId, r: &Rlp) -> Result<(), PacketDecodeError> {
 		trace!(target: "sync", "Received consensus packet from {:?}", peer_id);
-		io.chain().queue_consensus_message(r.as_raw().to_vec());
+		let node_id = io.peer_session_info(peer_id).and_then(|info| info.id);
+		io.chain().queue_consensus_message(r.as_raw().to_vec(), node_id);
 		Ok(())
 	}
 
@@ -338,6 +345,7 @@ impl SyncHandler {
 			peer.confirmation = ForkConfirmation::Confirmed;
 		}
 		sync.sync_peer(io, peer_id, false);
+		SyncPropagator::announce_pooled_transactions(sync, io, peer_id);
 	}
 
 	fn on_peer_fork_header(sync: &mut ChainSync, io: &mut SyncIo, peer_id: PeerId, r: &Rlp) -> Result<(), PacketDecodeError> {
@@ -547,6 +555,25 @@ impl SyncHandler {
 		Ok(())
 	}
 
+	/// Called when state nodes requested for healing are downloaded from a peer.
+	fn on_peer_node_data(sync: &mut ChainSync, io: &mut SyncIo, peer_id: PeerId, r: &Rlp) -> Result<(), PacketDecodeError> {
+		if !sync.reset_peer_asking(peer_id, PeerAsking::NodeData) {
+			trace!(target: "sync", "{}: Ignored unexpected node data", peer_id);
+			return Ok(());
+		}
+		let nodes: Vec<Bytes> = r.as_list()?;
+		trace!(target: "sync", "{} -> NodeData ({} entries)", peer_id, nodes.len());
+		if nodes.is_empty() {
+			if let Some(peer) = sync.peers.get_mut(&peer_id) {
+				peer.empty_node_data += 1;
+			}
+		} else {
+			io.chain().import_state_nodes(nodes);
+		}
+		sync.maintain_state_healing(io);
+		Ok(())
+	}
+
 	/// Called when snapshot data is downloaded from a peer.
 	fn on_snapshot_data(sync: &mut ChainSync, io: &mut SyncIo, peer_id: PeerId, r: &Rlp) -> Result<(), PacketDecodeError> {
 		if !sync.peers.get(&peer_id).map_or(false, |p| p.can_sync()) {
@@ -637,6 +664,7 @@ impl SyncHandler {
 			snapshot_hash: if warp_protocol { Some(r.val_at(5)?) } else { None },
 			snapshot_number: if warp_protocol { Some(r.val_at(6)?) } else { None },
 			block_set: None,
+			empty_node_data: 0,
 		};
 
 		trace!(target: "sync", "New peer {} (protocol: {}, network: {:?}, difficulty: {:?}, latest:{}, genesis:{}, snapshot:{:?})",
@@ -663,7 +691,7 @@ impl SyncHandler {
 		}
 
 		if false
-			|| (warp_protocol && (peer.protocol_version < PAR_PROTOCOL_VERSION_1.0 || peer.protocol_version > PAR_PROTOCOL_VERSION_3.0))
+			|| (warp_protocol && (peer.protocol_version < PAR_PROTOCOL_VERSION_1.0 || peer.protocol_version > PAR_PROTOCOL_VERSION_4.0))
 			|| (!warp_protocol && (peer.protocol_version < ETH_PROTOCOL_VERSION_62.0 || peer.protocol_version > ETH_PROTOCOL_VERSION_63.0))
 		{
 			io.disable_peer(peer_id);
@@ -717,6 +745,31 @@ impl SyncHandler {
 		Ok(())
 	}
 
+	/// Called when peer announces hashes of its pooled transactions
+	fn on_peer_pooled_transaction_hashes(sync: &mut ChainSync, io: &mut SyncIo, peer_id: PeerId, r: &Rlp) -> Result<(), PacketDecodeError> {
+		// Accept transactions only when fully synced
+		if !io.is_chain_queue_empty() || (sync.state != SyncState::Idle && sync.state != SyncState::NewBlocks) {
+			trace!(target: "sync", "{} Ignoring pooled transaction hashes while syncing", peer_id);
+			return Ok(());
+		}
+		if !sync.peers.get(&peer_id).map_or(false, |p| p.can_sync()) {
+			trace!(target: "sync", "{} Ignoring pooled transaction hashes from unconfirmed/unknown peer", peer_id);
+			return Ok(());
+		}
+
+		let item_count = r.item_count()?;
+		trace!(target: "sync", "{:02} -> NewPooledTransactionHashes ({} entries)", peer_id, item_count);
+		let mut unknown = Vec::new();
+		for i in 0 .. cmp::min(item_count, MAX_POOLED_TRANSACTION_HASHES) {
+			let hash: H256 = r.val_at(i)?;
+			if io.chain().queued_transaction(&hash).is_none() {
+				unknown.push(hash);
+			}
+		}
+		SyncRequester::request_pooled_transactions(io, peer_id, &unknown);
+		Ok(())
+	}
+
 	/// Called when peer sends us signed private transaction packet
 	fn on_signed_private_transaction(sync: &ChainSync, _io: &mut SyncIo, peer_id: PeerId, r: &Rlp) -> Result<(), PacketDecodeError> {
 		if !sync.peers.get(&peer_id).map_or(false, |p| p.can_sync()) {
@@ -98,7 +98,7 @@ use std::cmp;
 use std::time::{Duration, Instant};
 use hash::keccak;
 use heapsize::HeapSizeOf;
-use ethereum_types::{H256, U256};
+use ethereum_types::{H256, H512, U256};
 use plain_hasher::H256FastMap;
 use parking_lot::RwLock;
 use bytes::Bytes;
@@ -136,10 +136,13 @@ pub const PAR_PROTOCOL_VERSION_1: (u8, u8) = (1, 0x15);
 pub const PAR_PROTOCOL_VERSION_2: (u8, u8) = (2, 0x16);
 /// 3 version of Parity protocol (private transactions messages added).
 pub const PAR_PROTOCOL_VERSION_3: (u8, u8) = (3, 0x18);
+/// 4 version of Parity protocol (pooled transactions exchange added).
+pub const PAR_PROTOCOL_VERSION_4: (u8, u8) = (4, 0x1a);
 
 pub const MAX_BODIES_TO_SEND: usize = 256;
 pub const MAX_HEADERS_TO_SEND: usize = 512;
 pub const MAX_NODE_DATA_TO_SEND: usize = 1024;
+const MAX_NODE_DATA_TO_REQUEST: usize = 384;
 pub const MAX_RECEIPTS_TO_SEND: usize = 1024;
 pub const MAX_RECEIPTS_HEADERS_TO_SEND: usize = 256;
 const MIN_PEERS_PROPAGATION: usize = 4;
@@ -151,6 +154,12 @@ const MAX_NEW_BLOCK_AGE: BlockNumber = 20;
 const MAX_TRANSACTION_PACKET_SIZE: usize = 8 * 1024 * 1024;
 // Maximal number of transactions in sent in single packet.
 const MAX_TRANSACTIONS_TO_PROPAGATE: usize = 64;
+/// Default interval of local transactions rebroadcast, see `SyncConfig`.
+pub const DEFAULT_LOCAL_TRANSACTIONS_REBROADCAST_INTERVAL: Duration = Duration::from_secs(10 * 60);
+// Maximal number of pooled transaction hashes announced to a newly connected peer.
+const MAX_POOLED_TRANSACTION_HASHES: usize = 4096;
+// Maximal number of pooled transactions requested in single packet.
+const MAX_POOLED_TRANSACTIONS_TO_REQUEST: usize = 256;
 // Min number of blocks to be behind for a snapshot sync
 const SNAPSHOT_RESTORE_THRESHOLD: BlockNumber = 30000;
 const SNAPSHOT_MIN_PEERS: usize = 3;
@@ -176,6 +185,8 @@ pub const SNAPSHOT_DATA_PACKET: u8 = 0x14;
 pub const CONSENSUS_DATA_PACKET: u8 = 0x15;
 const PRIVATE_TRANSACTION_PACKET: u8 = 0x16;
 const SIGNED_PRIVATE_TRANSACTION_PACKET: u8 = 0x17;
+const NEW_POOLED_TRANSACTION_HASHES_PACKET: u8 = 0x18;
+pub const GET_POOLED_TRANSACTIONS_PACKET: u8 = 0x19;
 
 const MAX_SNAPSHOT_CHUNKS_DOWNLOAD_AHEAD: usize = 3;
 
@@ -187,6 +198,7 @@ const RECEIPTS_TIMEOUT: Duration = Duration::from_secs(10);
 const FORK_HEADER_TIMEOUT: Duration = Duration::from_secs(3);
 const SNAPSHOT_MANIFEST_TIMEOUT: Duration = Duration::from_secs(5);
 const SNAPSHOT_DATA_TIMEOUT: Duration = Duration::from_secs(120);
+const NODE_DATA_TIMEOUT: Duration = Duration::from_secs(10);
 
 #[derive(Copy, Clone, Eq, PartialEq, Debug)]
 /// Sync state
@@ -280,6 +292,7 @@ pub enum PeerAsking {
 	BlockReceipts,
 	SnapshotManifest,
 	SnapshotData,
+	NodeData,
 }
 
 #[derive(PartialEq, Eq, Debug, Clone, Copy)]
@@ -333,6 +346,8 @@ pub struct PeerInfo {
 	snapshot_number: Option<BlockNumber>,
 	/// Block set requested
 	block_set: Option<BlockSet>,
+	/// Number of empty responses to state healing requests
+	empty_node_data: usize,
 }
 
 impl PeerInfo {
@@ -400,12 +415,24 @@ pub struct ChainSync {
 	sync_start_time: Option<Instant>,
 	/// Transactions propagation statistics
 	transactions_stats: TransactionsStats,
+	/// Last time local transactions were rebroadcast
+	last_local_rebroadcast: Instant,
+	/// Local transactions are propagated again this often, until they are mined.
+	local_rebroadcast_interval: Duration,
 	/// Enable ancient block downloading
 	download_old_blocks: bool,
+	/// Max blocks to download ahead.
+	max_download_ahead_blocks: usize,
+	/// Memory budget for downloaded blocks.
+	download_memory_budget: usize,
 	/// Shared private tx service.
 	private_tx_handler: Arc<PrivateTxHandler>,
 	/// Enable warp sync.
 	warp_sync: WarpSync,
+	/// Heal the state after warp sync.
+	state_healing: bool,
+	/// Peer asked for state nodes most recently.
+	last_node_data_peer: Option<PeerId>,
 }
 
 impl ChainSync {
@@ -428,12 +455,19 @@ impl ChainSync {
 			network_id: config.network_id,
 			fork_block: config.fork_block,
 			download_old_blocks: config.download_old_blocks,
+			max_download_ahead_blocks: config.max_download_ahead_blocks,
+			download_memory_budget: config.download_memory_budget,
 			snapshot: Snapshot::new(),
 			sync_start_time: None,
 			transactions_stats: TransactionsStats::default(),
+			last_local_rebroadcast: Instant::now(),
+			local_rebroadcast_interval: config.local_transactions_rebroadcast_interval,
 			private_tx_handler,
 			warp_sync: config.warp_sync,
+			state_healing: config.state_healing,
+			last_node_data_peer: None,
 		};
+		sync.new_blocks.set_download_limits(sync.max_download_ahead_blocks, sync.download_memory_budget);
 		sync.update_targets(chain);
 		sync
 	}
@@ -631,12 +665,14 @@ impl ChainSync {
 		// Do not assume that the block queue/chain still has our last_imported_block
 		let chain = chain.chain_info();
 		self.new_blocks = BlockDownloader::new(false, &chain.best_block_hash, chain.best_block_number);
+		self.new_blocks.set_download_limits(self.max_download_ahead_blocks, self.download_memory_budget);
 		self.old_blocks = None;
 		if self.download_old_blocks {
 			if let (Some(ancient_block_hash), Some(ancient_block_number)) = (chain.ancient_block_hash, chain.ancient_block_number) {
 
 				trace!(target: "sync", "Downloading old blocks from {:?} (#{}) till {:?} (#{:?})", ancient_block_hash, ancient_block_number, chain.first_block_hash, chain.first_block_number);
 				let mut downloader = BlockDownloader::with_unlimited_reorg(true, &ancient_block_hash, ancient_block_number);
+				downloader.set_download_limits(self.max_download_ahead_blocks, self.download_memory_budget);
 				if let Some(hash) = chain.first_block_hash {
 					trace!(target: "sync", "Downloader target set to {:?}", hash);
 					downloader.set_target(&hash);
@@ -905,6 +941,7 @@ impl ChainSync {
 				PeerAsking::ForkHeader => elapsed > FORK_HEADER_TIMEOUT,
 				PeerAsking::SnapshotManifest => elapsed > SNAPSHOT_MANIFEST_TIMEOUT,
 				PeerAsking::SnapshotData => elapsed > SNAPSHOT_DATA_TIMEOUT,
+				PeerAsking::NodeData => elapsed > NODE_DATA_TIMEOUT,
 			};
 			if timeout {
 				debug!(target:"sync", "Timeout {}", peer_id);
@@ -936,6 +973,9 @@ impl ChainSync {
 				match io.snapshot_service().status() {
 					RestorationStatus::Inactive => {
 						trace!(target:"sync", "Snapshot restoration is complete");
+						if self.state_healing {
+							io.chain().start_state_healing();
+						}
 						self.restart(io);
 					},
 					RestorationStatus::Initializing { .. } => {
@@ -1052,6 +1092,39 @@ impl ChainSync {
 	pub fn maintain_sync(&mut self, io: &mut SyncIo) {
 		self.maybe_start_snapshot_sync(io);
 		self.check_resume(io);
+		self.maintain_state_healing(io);
+	}
+
+	/// Request missing state nodes from an idle peer, one request at a time.
+	///
+	/// Requests rotate over the peers, so that nodes one peer doesn't serve are retried with
+	/// others. Peers which returned empty responses are asked last.
+	fn maintain_state_healing(&mut self, io: &mut SyncIo) {
+		match self.state {
+			SyncState::SnapshotManifest | SyncState::SnapshotData | SyncState::SnapshotWaiting => return,
+			_ => {},
+		}
+		if self.peers.values().any(|p| p.asking == PeerAsking::NodeData) {
+			return;
+		}
+
+		// eth/62 peers don't serve node data.
+		let mut peers: Vec<_> = self.peers.iter()
+			.filter(|&(_, p)| p.can_sync() && p.asking == PeerAsking::Nothing && p.protocol_version != ETH_PROTOCOL_VERSION_62.0)
+			.map(|(id, p)| (*id, p.empty_node_data))
+			.collect();
+		// shuffled first, so that the stable sort picks randomly among equally good peers.
+		random::new().shuffle(&mut peers);
+		let last_peer = self.last_node_data_peer;
+		peers.sort_by_key(|&(id, empty)| (Some(id) == last_peer, empty));
+
+		if let Some(&(peer_id, _)) = peers.first() {
+			let hashes = io.chain().state_healing_request(MAX_NODE_DATA_TO_REQUEST);
+			if !hashes.is_empty() {
+				self.last_node_data_peer = Some(peer_id);
+				SyncRequester::request_node_data(self, io, peer_id, &hashes);
+			}
+		}
 	}
 
 	/// called when block is imported to chain - propagates the blocks and updates transactions sent to peers
@@ -1114,6 +1187,11 @@ impl ChainSync {
 		SyncPropagator::propagate_consensus_packet(self, io, packet);
 	}
 
+	/// Send consensus message to the peer with given node id.
+	pub fn send_consensus_packet(&mut self, io: &mut SyncIo, packet: Bytes, node_id: &H512) {
+		SyncPropagator::send_consensus_packet(self, io, packet, node_id);
+	}
+
 	/// Broadcast private transaction message to peers.
 	pub fn propagate_private_transaction(&mut self, io: &mut SyncIo, packet: Bytes) {
 		SyncPropagator::propagate_private_transaction(self, io, packet);
@@ -1250,6 +1328,7 @@ pub mod tests {
 				snapshot_hash: None,
 				asking_snapshot_data: None,
 				block_set: None,
+				empty_node_data: 0,
 			});
 
 	}
@@ -15,7 +15,7 @@
 // along with Parity.  If not, see <http://www.gnu.org/licenses/>.
 
 use bytes::Bytes;
-use ethereum_types::H256;
+use ethereum_types::{H256, H512};
 use ethcore::client::BlockChainInfo;
 use ethcore::header::BlockNumber;
 use network::{PeerId, PacketId};
@@ -24,19 +24,24 @@ use rlp::{Encodable, RlpStream};
 use sync_io::SyncIo;
 use std::cmp;
 use std::collections::HashSet;
+use std::time::Instant;
 use transaction::SignedTransaction;
 
+use api::WARP_SYNC_PROTOCOL_ID;
 use super::{
 	random,
 	ChainSync,
 	MAX_PEER_LAG_PROPAGATION,
 	MAX_PEERS_PROPAGATION,
+	MAX_POOLED_TRANSACTION_HASHES,
 	MAX_TRANSACTION_PACKET_SIZE,
 	MAX_TRANSACTIONS_TO_PROPAGATE,
 	MIN_PEERS_PROPAGATION,
 	CONSENSUS_DATA_PACKET,
 	NEW_BLOCK_HASHES_PACKET,
 	NEW_BLOCK_PACKET,
+	NEW_POOLED_TRANSACTION_HASHES_PACKET,
+	PAR_PROTOCOL_VERSION_4,
 	PRIVATE_TRANSACTION_PACKET,
 	SIGNED_PRIVATE_TRANSACTION_PACKET,
 	TRANSACTIONS_PACKET,
@@ -114,11 +119,26 @@ impl SyncPropagator {
 			return 0;
 		}
 
-		let transactions = io.chain().ready_transactions();
+		let transactions = io.chain().transactions_to_propagate();
 		if transactions.is_empty() {
 			return 0;
 		}
 
+		// forget which peers local transactions were sent to, so they are sent again.
+		if sync.last_local_rebroadcast.elapsed() >= sync.local_rebroadcast_interval {
+			sync.last_local_rebroadcast = Instant::now();
+			let local = transactions.iter()
+				.filter(|tx| tx.is_local())
+				.map(|tx| tx.signed().hash())
+				.collect::<HashSet<_>>();
+			if !local.is_empty() {
+				trace!(target: "sync", "Re-broadcasting {} local transactions.", local.len());
+				for peer_info in sync.peers.values_mut() {
+					peer_info.last_sent_transactions.retain(|hash| !local.contains(hash));
+				}
+			}
+		}
+
 		let (transactions, service_transactions): (Vec<_>, Vec<_>) = transactions.iter()
 			.map(|tx| tx.signed())
 			.partition(|tx| !tx.gas_price.is_zero());
@@ -141,6 +161,40 @@ impl SyncPropagator {
 		affected_peers.len()
 	}
 
+	/// Announces hashes of pooled transactions to a newly connected peer,
+	/// so it can request the ones it is missing.
+	pub fn announce_pooled_transactions(sync: &mut ChainSync, io: &mut SyncIo, peer_id: PeerId) {
+		if io.protocol_version(&WARP_SYNC_PROTOCOL_ID, peer_id) < PAR_PROTOCOL_VERSION_4.0 {
+			return;
+		}
+
+		let accepts_service = accepts_service_transaction(&io.peer_info(peer_id));
+		let hashes = io.chain().transactions_to_propagate().iter()
+			.map(|tx| tx.signed())
+			.filter(|tx| accepts_service || !tx.gas_price.is_zero())
+			.map(|tx| tx.hash())
+			.take(MAX_POOLED_TRANSACTION_HASHES)
+			.collect::<Vec<_>>();
+		if hashes.is_empty() {
+			return;
+		}
+
+		if let Some(peer_info) = sync.peers.get_mut(&peer_id) {
+			// the peer requests what it misses, no need to propagate these again.
+			peer_info.last_sent_transactions.extend(hashes.iter().cloned());
+		}
+
+		let mut packet = RlpStream::new_list(hashes.len());
+		for hash in &hashes {
+			packet.append(hash);
+		}
+		trace!(target: "sync", "{:02} <- NewPooledTransactionHashes ({} entries)", peer_id, hashes.len());
+		if let Err(e) = io.send_protocol(WARP_SYNC_PROTOCOL_ID, peer_id, NEW_POOLED_TRANSACTION_HASHES_PACKET, packet.out()) {
+			debug!(target:"sync", "Error sending packet: {:?}", e);
+			io.disconnect_peer(peer_id);
+		}
+	}
+
 	fn propagate_transactions_to_peers(sync: &mut ChainSync, io: &mut SyncIo, peers: Vec<PeerId>, transactions: Vec<&SignedTransaction>) -> HashSet<PeerId> {
 		let all_transactions_hashes = transactions.iter()
 			.map(|tx| tx.hash())
@@ -287,6 +341,19 @@ impl SyncPropagator {
 		}
 	}
 
+	/// Send consensus message to the peer with given node id.
+	pub fn send_consensus_packet(sync: &mut ChainSync, io: &mut SyncIo, packet: Bytes, node_id: &H512) {
+		let peer = sync.get_consensus_peers().into_iter()
+			.find(|peer_id| io.peer_session_info(*peer_id).and_then(|info| info.id).as_ref() == Some(node_id));
+		match peer {
+			Some(peer_id) => {
+				trace!(target: "sync", "Sending consensus packet to {}", peer_id);
+				SyncPropagator::send_packet(io, peer_id, CONSENSUS_DATA_PACKET, packet);
+			},
+			None => trace!(target: "sync", "No consensus peer with node id {}", node_id),
+		}
+	}
+
 	/// Broadcast private transaction message to peers.
 	pub fn propagate_private_transaction(sync: &mut ChainSync, io: &mut SyncIo, packet: Bytes) {
 		let lucky_peers = ChainSync::select_random_peers(&sync.get_private_transaction_peers());
@@ -429,6 +496,7 @@ mod tests {
 				snapshot_hash: None,
 				asking_snapshot_data: None,
 				block_set: None,
+				empty_node_data: 0,
 			});
 		let ss = TestSnapshotService::new();
 		let mut io = TestIo::new(&mut client, &ss, &queue, None);
@@ -467,6 +535,30 @@ mod tests {
 		assert_eq!(0x02, io.packets[0].packet_id);
 	}
 
+	#[test]
+	fn rebroadcasts_local_transactions() {
+		let mut client = TestBlockChainClient::new();
+		client.add_blocks(100, EachBlockWith::Uncle);
+		client.insert_transaction_to_queue();
+		client.insert_local_transaction_to_queue();
+		let mut sync = dummy_sync_with_peer(client.block_hash_delta_minus(1), &client);
+		let queue = RwLock::new(VecDeque::new());
+		let ss = TestSnapshotService::new();
+		let mut io = TestIo::new(&mut client, &ss, &queue, None);
+		let peer_count = SyncPropagator::propagate_new_transactions(&mut sync, &mut io);
+		let peer_count2 = SyncPropagator::propagate_new_transactions(&mut sync, &mut io);
+		// once the interval passed, only the local transaction is sent again
+		sync.last_local_rebroadcast = Instant::now() - sync.local_rebroadcast_interval;
+		let peer_count3 = SyncPropagator::propagate_new_transactions(&mut sync, &mut io);
+
+		assert_eq!(1, peer_count);
+		assert_eq!(0, peer_count2);
+		assert_eq!(1, peer_count3);
+		assert_eq!(2, io.packets.len());
+		assert_eq!(2, Rlp::new(&io.packets[0].data).item_count().unwrap());
+		assert_eq!(1, Rlp::new(&io.packets[1].data).item_count().unwrap());
+	}
+
 	#[test]
 	fn does_not_propagate_new_transactions_after_new_block() {
 		let mut client = TestBlockChainClient::new();
@@ -633,4 +725,26 @@ mod tests {
 		assert!(sent_transactions.iter().any(|tx| tx.hash() == tx1_hash));
 		assert!(sent_transactions.iter().any(|tx| tx.hash() == tx2_hash));
 	}
+
+	#[test]
+	fn announces_pooled_transactions_to_new_peer() {
+		let mut client = TestBlockChainClient::new();
+		client.add_blocks(100, EachBlockWith::Uncle);
+		let tx_hash = client.insert_transaction_to_queue();
+		let mut sync = dummy_sync_with_peer(client.block_hash_delta_minus(1), &client);
+		let queue = RwLock::new(VecDeque::new());
+		let ss = TestSnapshotService::new();
+		let mut io = TestIo::new(&mut client, &ss, &queue, None);
+
+		SyncPropagator::announce_pooled_transactions(&mut sync, &mut io, 0);
+		// announced transactions are not propagated again
+		let peer_count = SyncPropagator::propagate_new_transactions(&mut sync, &mut io);
+
+		assert_eq!(0, peer_count);
+		assert_eq!(1, io.packets.len());
+		// NEW_POOLED_TRANSACTION_HASHES_PACKET
+		assert_eq!(0x18, io.packets[0].packet_id);
+		let hashes: Vec<H256> = Rlp::new(&io.packets[0].data).as_list().unwrap();
+		assert_eq!(hashes, vec![tx_hash]);
+	}
 }
@@ -31,9 +31,12 @@ use super::{
 	ETH_PROTOCOL_VERSION_63,
 	GET_BLOCK_BODIES_PACKET,
 	GET_BLOCK_HEADERS_PACKET,
+	GET_NODE_DATA_PACKET,
+	GET_POOLED_TRANSACTIONS_PACKET,
 	GET_RECEIPTS_PACKET,
 	GET_SNAPSHOT_DATA_PACKET,
 	GET_SNAPSHOT_MANIFEST_PACKET,
+	MAX_POOLED_TRANSACTIONS_TO_REQUEST,
 };
 
 /// The Chain Sync Requester: requesting data to other peers
@@ -124,6 +127,33 @@ impl SyncRequester {
 		peer.block_set = Some(set);
 	}
 
+	/// Request pooled transactions with given hashes from a peer, in batches.
+	/// Responses arrive as regular `Transactions` packets.
+	pub fn request_pooled_transactions(io: &mut SyncIo, peer_id: PeerId, hashes: &[H256]) {
+		for batch in hashes.chunks(MAX_POOLED_TRANSACTIONS_TO_REQUEST) {
+			trace!(target: "sync", "{} <- GetPooledTransactions: {} entries", peer_id, batch.len());
+			let mut rlp = RlpStream::new_list(batch.len());
+			for h in batch {
+				rlp.append(h);
+			}
+			if let Err(e) = io.send_protocol(WARP_SYNC_PROTOCOL_ID, peer_id, GET_POOLED_TRANSACTIONS_PACKET, rlp.out()) {
+				debug!(target:"sync", "Error sending request: {:?}", e);
+				io.disconnect_peer(peer_id);
+				return;
+			}
+		}
+	}
+
+	/// Request state nodes with given hashes from a peer.
+	pub fn request_node_data(sync: &mut ChainSync, io: &mut SyncIo, peer_id: PeerId, hashes: &[H256]) {
+		trace!(target: "sync", "{} <- GetNodeData: {} entries", peer_id, hashes.len());
+		let mut rlp = RlpStream::new_list(hashes.len());
+		for h in hashes {
+			rlp.append(h);
+		}
+		SyncRequester::send_request(sync, io, peer_id, PeerAsking::NodeData, GET_NODE_DATA_PACKET, rlp.out());
+	}
+
 	/// Request snapshot chunk from a peer.
 	fn request_snapshot_chunk(sync: &mut ChainSync, io: &mut SyncIo, peer_id: PeerId, chunk: &H256) {
 		trace!(target: "sync", "{} <- GetSnapshotData {:?}", peer_id, chunk);
@@ -34,18 +34,22 @@ use super::{
 	GET_BLOCK_BODIES_PACKET,
 	GET_BLOCK_HEADERS_PACKET,
 	GET_NODE_DATA_PACKET,
+	GET_POOLED_TRANSACTIONS_PACKET,
 	GET_RECEIPTS_PACKET,
 	GET_SNAPSHOT_DATA_PACKET,
 	GET_SNAPSHOT_MANIFEST_PACKET,
 	MAX_BODIES_TO_SEND,
 	MAX_HEADERS_TO_SEND,
 	MAX_NODE_DATA_TO_SEND,
+	MAX_POOLED_TRANSACTIONS_TO_REQUEST,
 	MAX_RECEIPTS_HEADERS_TO_SEND,
 	MAX_RECEIPTS_TO_SEND,
+	MAX_TRANSACTION_PACKET_SIZE,
 	NODE_DATA_PACKET,
 	RECEIPTS_PACKET,
 	SNAPSHOT_DATA_PACKET,
 	SNAPSHOT_MANIFEST_PACKET,
+	TRANSACTIONS_PACKET,
 };
 
 /// The Chain Sync Supplier: answers requests from peers with available data
@@ -79,6 +83,10 @@ impl SyncSupplier {
 			GET_SNAPSHOT_DATA_PACKET => SyncSupplier::return_rlp(io, &rlp, peer,
 				SyncSupplier::return_snapshot_data,
 				|e| format!("Error sending snapshot data: {:?}", e)),
+
+			GET_POOLED_TRANSACTIONS_PACKET => SyncSupplier::return_rlp(io, &rlp, peer,
+				SyncSupplier::return_pooled_transactions,
+				|e| format!("Error sending pooled transactions: {:?}", e)),
 			CONSENSUS_DATA_PACKET => ChainSync::on_consensus_packet(io, peer, &rlp),
 			_ => {
 				sync.write().on_packet(io, peer, packet_id, data);
@@ -90,6 +98,28 @@ impl SyncSupplier {
 		})
 	}
 
+	/// Respond to GetPooledTransactions request
+	fn return_pooled_transactions(io: &SyncIo, r: &Rlp, peer_id: PeerId) -> RlpResponseResult {
+		let count = cmp::min(r.item_count()?, MAX_POOLED_TRANSACTIONS_TO_REQUEST);
+		trace!(target: "sync", "{} -> GetPooledTransactions: {} entries", peer_id, count);
+		let mut added = 0;
+		let mut rlp = RlpStream::new();
+		rlp.begin_unbounded_list();
+		for i in 0..count {
+			let tx = match io.chain().transaction_to_propagate(&r.val_at(i)?) {
+				Some(tx) => tx,
+				None => continue,
+			};
+			if !rlp.append_raw_checked(&::rlp::encode(tx.signed()), 1, MAX_TRANSACTION_PACKET_SIZE) {
+				break;
+			}
+			added += 1;
+		}
+		rlp.complete_unbounded_list();
+		trace!(target: "sync", "{} <- Transactions: {} entries", peer_id, added);
+		Ok(Some((TRANSACTIONS_PACKET, rlp)))
+	}
+
 	/// Respond to GetBlockHeaders request
 	fn return_block_headers(io: &SyncIo, r: &Rlp, peer_id: PeerId) -> RlpResponseResult {
 		// Packet layout:
@@ -374,6 +404,27 @@ mod test {
 		assert_eq!(to_header_vec(result), vec![headers[50].clone(), headers[44].clone(), headers[38].clone()]);
 	}
 
+	#[test]
+	fn return_pooled_transactions() {
+		let mut client = TestBlockChainClient::new();
+		let tx_hash = client.insert_transaction_to_queue();
+		let queue = RwLock::new(VecDeque::new());
+		let ss = TestSnapshotService::new();
+		let io = TestIo::new(&mut client, &ss, &queue, None);
+
+		let mut request = RlpStream::new_list(2);
+		request.append(&tx_hash);
+		request.append(&H256::from(1));
+
+		let result = SyncSupplier::return_pooled_transactions(&io, &Rlp::new(&request.out()), 0).unwrap().unwrap();
+		assert_eq!(TRANSACTIONS_PACKET, result.0);
+
+		let rlp = result.1.out();
+		let transactions: Vec<::transaction::UnverifiedTransaction> = Rlp::new(&rlp).as_list().unwrap();
+		assert_eq!(transactions.len(), 1);
+		assert_eq!(transactions[0].hash(), tx_hash);
+	}
+
 	#[test]
 	fn return_nodes() {
 		let mut client = TestBlockChainClient::new();
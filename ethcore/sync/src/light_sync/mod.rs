@@ -31,6 +31,8 @@
 //! - When within a certain distance of the head of the chain, aggressively download all
 //!   announced blocks.
 //! - On bad block/response, punish peer and reset.
+//!
+//! When sync runs is controlled by a `SyncSchedule` shared with the embedder.
 
 use std::collections::{HashMap, HashSet};
 use std::mem;
@@ -52,7 +54,10 @@ use rand::{Rng, OsRng};
 
 use self::sync_round::{AbortReason, SyncRound, ResponseContext};
 
+pub use self::schedule::{SyncSchedule, SchedulingPolicy, NetworkHint, PowerHint};
+
 mod response;
+mod schedule;
 mod sync_round;
 
 #[cfg(test)]
@@ -236,6 +241,7 @@ pub struct LightSync<L: AsLightClient> {
 	client: Arc<L>,
 	rng: Mutex<OsRng>,
 	state: Mutex<SyncState>,
+	schedule: Arc<SyncSchedule>,
 }
 
 #[derive(Debug, Clone)]
@@ -453,6 +459,7 @@ impl<L: AsLightClient> LightSync<L> {
 
 				if sink.is_empty() { break }
 				trace!(target: "sync", "Drained {} headers to import", sink.len());
+				let burst_done = self.schedule.note_imported(sink.len() as u64);
 
 				for header in sink.drain(..) {
 					match client.queue_header(header) {
@@ -471,6 +478,12 @@ impl<L: AsLightClient> LightSync<L> {
 						}
 					}
 				}
+
+				if burst_done {
+					debug!(target: "sync", "Catch-up limit reached. Going idle until next scheduled run");
+					*state = SyncState::Idle;
+					return;
+				}
 			}
 		}
 
@@ -521,7 +534,9 @@ impl<L: AsLightClient> LightSync<L> {
 					let g_hash = chain_info.genesis_hash;
 					*state = SyncState::Rounds(SyncRound::begin((0, g_hash), sync_target));
 				}
-				SyncState::Idle => self.begin_search(&mut state),
+				SyncState::Idle => if self.schedule.start_run(Instant::now()) {
+					self.begin_search(&mut state)
+				},
 				other => *state = other, // restore displaced state.
 			}
 		}
@@ -553,7 +568,7 @@ impl<L: AsLightClient> LightSync<L> {
 		}
 
 		// allow dispatching of requests.
-		{
+		if !self.schedule.is_suspended() {
 			let peers = self.peers.read();
 			let mut peer_ids: Vec<_> = peers.iter().filter_map(|(id, p)| {
 				if p.lock().status.head_td > chain_info.pending_total_difficulty {
@@ -631,8 +646,15 @@ impl<L: AsLightClient> LightSync<L> {
 			client: client,
 			rng: Mutex::new(OsRng::new()?),
 			state: Mutex::new(SyncState::Idle),
+			schedule: Arc::new(SyncSchedule::default()),
 		})
 	}
+
+	/// Use given schedule to decide when to sync.
+	pub fn with_schedule(mut self, schedule: Arc<SyncSchedule>) -> Self {
+		self.schedule = schedule;
+		self
+	}
 }
 
 /// Trait for erasing the type of a light sync object and exposing read-only methods.
@@ -0,0 +1,225 @@
+// Copyright 2015-2018 Parity Technologies (UK) Ltd.
+// This file is part of Parity.
+
+// Parity is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// Parity is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with Parity.  If not, see <http://www.gnu.org/licenses/>.
+
+//! Scheduling of light client synchronization.
+//!
+//! Embedders (e.g. mobile wallets) share a `SyncSchedule` with the sync and feed it
+//! hints about the host's network and power state. The schedule decides how often
+//! sync starts and how many headers are caught up with in a single burst.
+
+use std::time::{Duration, Instant};
+
+use parking_lot::Mutex;
+
+/// Network connectivity of the host, as reported by the embedder.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum NetworkHint {
+	/// Unmetered connection, e.g. Wi-Fi.
+	Unmetered,
+	/// Metered connection, e.g. cellular data.
+	Metered,
+	/// No connectivity.
+	Offline,
+}
+
+impl Default for NetworkHint {
+	fn default() -> Self {
+		NetworkHint::Unmetered
+	}
+}
+
+/// Power source of the host, as reported by the embedder.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PowerHint {
+	/// External power source.
+	External,
+	/// Running on battery.
+	Battery,
+	/// Running on battery which is nearly depleted.
+	LowBattery,
+}
+
+impl Default for PowerHint {
+	fn default() -> Self {
+		PowerHint::External
+	}
+}
+
+/// Policy controlling when the light client syncs.
+#[derive(Debug, Clone, PartialEq)]
+pub struct SchedulingPolicy {
+	/// Minimal interval between starting two sync runs.
+	pub poll_interval: Duration,
+	/// Minimal interval between starting two sync runs on a metered connection or on battery.
+	pub constrained_poll_interval: Duration,
+	/// Maximal number of headers imported in a single sync run. The rest is caught up with
+	/// in the following runs. `None` for no limit.
+	pub max_catch_up: Option<u64>,
+	/// Whether to sync on a metered connection.
+	pub sync_on_metered: bool,
+	/// Whether to sync when battery is nearly depleted.
+	pub sync_on_low_battery: bool,
+}
+
+impl Default for SchedulingPolicy {
+	fn default() -> Self {
+		SchedulingPolicy {
+			poll_interval: Duration::from_secs(0),
+			constrained_poll_interval: Duration::from_secs(60),
+			max_catch_up: None,
+			sync_on_metered: true,
+			sync_on_low_battery: false,
+		}
+	}
+}
+
+#[derive(Default)]
+struct State {
+	policy: SchedulingPolicy,
+	network: NetworkHint,
+	power: PowerHint,
+	last_run: Option<Instant>,
+	imported: u64,
+}
+
+/// Sync schedule shared between the light sync and its embedder.
+#[derive(Default)]
+pub struct SyncSchedule {
+	state: Mutex<State>,
+}
+
+impl SyncSchedule {
+	/// Create a new schedule following given policy.
+	pub fn new(policy: SchedulingPolicy) -> Self {
+		SyncSchedule {
+			state: Mutex::new(State {
+				policy: policy,
+				..Default::default()
+			}),
+		}
+	}
+
+	/// Current scheduling policy.
+	pub fn policy(&self) -> SchedulingPolicy {
+		self.state.lock().policy.clone()
+	}
+
+	/// Replace the scheduling policy.
+	pub fn set_policy(&self, policy: SchedulingPolicy) {
+		self.state.lock().policy = policy;
+	}
+
+	/// Note a change of the host's network connectivity.
+	pub fn set_network_hint(&self, hint: NetworkHint) {
+		debug!(target: "sync", "Network hint: {:?}", hint);
+		self.state.lock().network = hint;
+	}
+
+	/// Note a change of the host's power source.
+	pub fn set_power_hint(&self, hint: PowerHint) {
+		debug!(target: "sync", "Power hint: {:?}", hint);
+		self.state.lock().power = hint;
+	}
+
+	/// Whether sync is suspended given the current hints. No requests are made while suspended.
+	pub fn is_suspended(&self) -> bool {
+		let state = self.state.lock();
+		state.network == NetworkHint::Offline
+			|| (state.network == NetworkHint::Metered && !state.policy.sync_on_metered)
+			|| (state.power == PowerHint::LowBattery && !state.policy.sync_on_low_battery)
+	}
+
+	/// Called by the sync when idle. Returns whether a new sync run should start now,
+	/// in which case the run is assumed to have started.
+	pub fn start_run(&self, now: Instant) -> bool {
+		if self.is_suspended() {
+			return false;
+		}
+
+		let mut state = self.state.lock();
+		let interval = if state.network == NetworkHint::Metered || state.power != PowerHint::External {
+			state.policy.constrained_poll_interval
+		} else {
+			state.policy.poll_interval
+		};
+
+		match state.last_run {
+			Some(last) if now < last + interval => false,
+			_ => {
+				state.last_run = Some(now);
+				state.imported = 0;
+				true
+			}
+		}
+	}
+
+	/// Called by the sync after queueing headers for import. Returns whether the current
+	/// run reached its catch-up limit and should be stopped.
+	pub fn note_imported(&self, count: u64) -> bool {
+		let mut state = self.state.lock();
+		state.imported += count;
+		state.policy.max_catch_up.map_or(false, |max| state.imported >= max)
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use std::time::{Duration, Instant};
+	use super::{SyncSchedule, SchedulingPolicy, NetworkHint, PowerHint};
+
+	#[test]
+	fn default_schedule_always_runs() {
+		let schedule = SyncSchedule::default();
+		let now = Instant::now();
+		assert!(schedule.start_run(now));
+		assert!(schedule.start_run(now));
+		assert!(!schedule.note_imported(1_000_000));
+	}
+
+	#[test]
+	fn respects_hints() {
+		let schedule = SyncSchedule::new(SchedulingPolicy {
+			sync_on_metered: false,
+			..Default::default()
+		});
+
+		schedule.set_network_hint(NetworkHint::Metered);
+		assert!(schedule.is_suspended());
+		schedule.set_network_hint(NetworkHint::Unmetered);
+		assert!(!schedule.is_suspended());
+		schedule.set_power_hint(PowerHint::LowBattery);
+		assert!(schedule.is_suspended());
+		assert!(!schedule.start_run(Instant::now()));
+	}
+
+	#[test]
+	fn throttles_runs_on_battery() {
+		let schedule = SyncSchedule::new(SchedulingPolicy {
+			constrained_poll_interval: Duration::from_secs(30),
+			max_catch_up: Some(100),
+			..Default::default()
+		});
+		schedule.set_power_hint(PowerHint::Battery);
+
+		let now = Instant::now();
+		assert!(schedule.start_run(now));
+		assert!(!schedule.note_imported(64));
+		assert!(schedule.note_imported(64));
+		assert!(!schedule.start_run(now + Duration::from_secs(10)));
+		assert!(schedule.start_run(now + Duration::from_secs(30)));
+		assert!(!schedule.note_imported(64));
+	}
+}
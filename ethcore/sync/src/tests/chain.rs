@@ -52,6 +52,19 @@ fn status_after_sync() {
 	assert_eq!(status.state, SyncState::Idle);
 }
 
+#[test]
+fn limited_download_window() {
+	::env_logger::init().ok();
+	let mut config = SyncConfig::default();
+	config.max_download_ahead_blocks = 64;
+	config.download_memory_budget = 16 * 1024;
+	let mut net = TestNet::new_with_config(2, config);
+	net.peer(1).chain.add_blocks(1000, EachBlockWith::Uncle);
+	net.sync();
+	assert!(net.peer(0).chain.block(BlockId::Number(1000)).is_some());
+	assert_eq!(*net.peer(0).chain.blocks.read(), *net.peer(1).chain.blocks.read());
+}
+
 #[test]
 fn takes_few_steps() {
 	let mut net = TestNet::new(3);
@@ -70,11 +70,26 @@ pub enum Error {
 	/// Contract creation code is banned.
 	CodeBanned,
 	/// Invalid chain ID given.
-	InvalidChainId,
+	InvalidChainId {
+		/// Chain ID accepted at this block, `None` if only unprotected transactions are.
+		expected: Option<u64>,
+		/// Chain ID the transaction was signed for.
+		got: u64,
+	},
+	/// Transaction is not replay-protected while the chain requires it.
+	ReplayProtectionRequired,
 	/// Not enough permissions given by permission contract.
 	NotAllowed,
+	/// Sender does not hold a certificate of the configured authority.
+	SenderNotCertified,
+	/// Transaction type is not accepted yet.
+	TransactionTypeNotEnabled,
 	/// Signature error
 	InvalidSignature(String),
+	/// Signature `r` or `s` value is out of range, or `v` is invalid.
+	SignatureOutOfRange,
+	/// Signature has a high `s` value, which is rejected since EIP-2.
+	MalleableSignature,
 	/// Transaction too big
 	TooBig,
 	/// Invalid RLP encoding
@@ -114,9 +129,17 @@ impl fmt::Display for Error {
 			SenderBanned => "Sender is temporarily banned.".into(),
 			RecipientBanned => "Recipient is temporarily banned.".into(),
 			CodeBanned => "Contract code is temporarily banned.".into(),
-			InvalidChainId => "Transaction of this chain ID is not allowed on this chain.".into(),
+			InvalidChainId { expected: Some(expected), got } =>
+				format!("Transaction of chain ID {} is not allowed on this chain. Expected={}", got, expected),
+			InvalidChainId { expected: None, got } =>
+				format!("Transaction of chain ID {} is not allowed on this chain. Only unprotected transactions are accepted", got),
+			ReplayProtectionRequired => "Transaction must be signed with the chain ID of this chain".into(),
 			InvalidSignature(ref err) => format!("Transaction has invalid signature: {}.", err),
+			SignatureOutOfRange => "Transaction signature values are out of range".into(),
+			MalleableSignature => "Transaction signature has a high s value".into(),
 			NotAllowed => "Sender does not have permissions to execute this type of transction".into(),
+			SenderNotCertified => "Sender is not certified by the certificate authority".into(),
+			TransactionTypeNotEnabled => "Transaction type is not enabled on this chain".into(),
 			TooBig => "Transaction too big".into(),
 			InvalidRlp(ref err) => format!("Transaction has invalid RLP structure: {}.", err),
 		};
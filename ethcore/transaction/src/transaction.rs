@@ -35,6 +35,30 @@ pub const UNSIGNED_SENDER: Address = H160([0xff; 20]);
 /// System sender address for internal state updates.
 pub const SYSTEM_ADDRESS: Address = H160([0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff,0xff, 0xff, 0xff, 0xff,0xff, 0xff, 0xff, 0xff,0xff, 0xff, 0xff, 0xfe]);
 
+/// Type byte of EIP-2930 access-list transactions.
+pub const ACCESS_LIST_TX_TYPE: u8 = 0x01;
+
+/// Addresses and storage keys declared up front by an EIP-2930 transaction.
+pub type AccessList = Vec<(Address, Vec<H256>)>;
+
+fn append_access_list(s: &mut RlpStream, access_list: &AccessList) {
+	s.begin_list(access_list.len());
+	for &(ref address, ref keys) in access_list {
+		s.begin_list(2);
+		s.append(address);
+		s.append_list::<H256, _>(keys);
+	}
+}
+
+fn decode_access_list(rlp: &Rlp) -> Result<AccessList, DecoderError> {
+	rlp.iter().map(|item| {
+		if item.item_count()? != 2 {
+			return Err(DecoderError::RlpIncorrectListLen);
+		}
+		Ok((item.val_at(0)?, item.list_at(1)?))
+	}).collect()
+}
+
 /// Transaction action type.
 #[derive(Debug, Clone, PartialEq, Eq)]
 pub enum Action {
@@ -178,6 +202,7 @@ impl From<ethjson::transaction::Transaction> for UnverifiedTransaction {
 			r: t.r.into(),
 			s: t.s.into(),
 			v: t.v.into(),
+			access_list: None,
 			hash: 0.into(),
 		}.compute_hash()
 	}
@@ -191,6 +216,26 @@ impl Transaction {
 		keccak(stream.as_raw())
 	}
 
+	/// The message hash of the transaction wrapped in an EIP-2930 envelope.
+	pub fn access_list_hash(&self, chain_id: u64, access_list: &AccessList) -> H256 {
+		let mut stream = RlpStream::new_list(8);
+		stream.append(&chain_id);
+		self.rlp_append_fields(&mut stream);
+		append_access_list(&mut stream, access_list);
+		let mut payload = vec![ACCESS_LIST_TX_TYPE];
+		payload.extend_from_slice(stream.as_raw());
+		keccak(payload)
+	}
+
+	fn rlp_append_fields(&self, s: &mut RlpStream) {
+		s.append(&self.nonce);
+		s.append(&self.gas_price);
+		s.append(&self.gas);
+		s.append(&self.action);
+		s.append(&self.value);
+		s.append(&self.data);
+	}
+
 	/// Signs the transaction as coming from `sender`.
 	pub fn sign(self, secret: &Secret, chain_id: Option<u64>) -> SignedTransaction {
 		let sig = ::ethkey::sign(secret, &self.hash(chain_id))
@@ -206,6 +251,27 @@ impl Transaction {
 			r: sig.r().into(),
 			s: sig.s().into(),
 			v: signature::add_chain_replay_protection(sig.v() as u64, chain_id),
+			access_list: None,
+			hash: 0.into(),
+		}.compute_hash()
+	}
+
+	/// Signs the transaction as an EIP-2930 access-list transaction.
+	pub fn sign_with_access_list(self, secret: &Secret, chain_id: u64, access_list: AccessList) -> SignedTransaction {
+		let sig = ::ethkey::sign(secret, &self.access_list_hash(chain_id, &access_list))
+			.expect("data is valid and context has signing capabilities; qed");
+		SignedTransaction::new(self.with_access_list_signature(sig, chain_id, access_list))
+			.expect("secret is valid so it's recoverable")
+	}
+
+	/// Signs the EIP-2930 access-list transaction with signature.
+	pub fn with_access_list_signature(self, sig: Signature, chain_id: u64, access_list: AccessList) -> UnverifiedTransaction {
+		UnverifiedTransaction {
+			unsigned: self,
+			r: sig.r().into(),
+			s: sig.s().into(),
+			v: signature::add_chain_replay_protection(sig.v() as u64, Some(chain_id)),
+			access_list: Some(access_list),
 			hash: 0.into(),
 		}.compute_hash()
 	}
@@ -218,6 +284,7 @@ impl Transaction {
 			r: U256::one(),
 			s: U256::one(),
 			v: 0,
+			access_list: None,
 			hash: 0.into(),
 		}.compute_hash()
 	}
@@ -230,6 +297,24 @@ impl Transaction {
 				r: U256::one(),
 				s: U256::one(),
 				v: 0,
+				access_list: None,
+				hash: 0.into(),
+			}.compute_hash(),
+			sender: from,
+			public: None,
+		}
+	}
+
+	/// Specify the sender of an access-list transaction; like `fake_sign` it won't survive the
+	/// serialize/deserialize process.
+	pub fn fake_sign_with_access_list(self, from: Address, chain_id: u64, access_list: AccessList) -> SignedTransaction {
+		SignedTransaction {
+			transaction: UnverifiedTransaction {
+				unsigned: self,
+				r: U256::one(),
+				s: U256::one(),
+				v: signature::add_chain_replay_protection(0, Some(chain_id)),
+				access_list: Some(access_list),
 				hash: 0.into(),
 			}.compute_hash(),
 			sender: from,
@@ -245,6 +330,7 @@ impl Transaction {
 				r: U256::zero(),
 				s: U256::zero(),
 				v: chain_id,
+				access_list: None,
 				hash: 0.into(),
 			}.compute_hash(),
 			sender: UNSIGNED_SENDER,
@@ -278,6 +364,8 @@ pub struct UnverifiedTransaction {
 	r: U256,
 	/// The S field of the signature; helps describe the point on the curve.
 	s: U256,
+	/// Access list of EIP-2930 transactions, `None` for legacy transactions.
+	access_list: Option<AccessList>,
 	/// Hash of the transaction
 	hash: H256,
 }
@@ -292,6 +380,9 @@ impl Deref for UnverifiedTransaction {
 
 impl rlp::Decodable for UnverifiedTransaction {
 	fn decode(d: &Rlp) -> Result<Self, DecoderError> {
+		if !d.is_list() {
+			return UnverifiedTransaction::decode_envelope(d.data()?);
+		}
 		if d.item_count()? != 9 {
 			return Err(DecoderError::RlpIncorrectListLen);
 		}
@@ -308,6 +399,7 @@ impl rlp::Decodable for UnverifiedTransaction {
 			v: d.val_at(6)?,
 			r: d.val_at(7)?,
 			s: d.val_at(8)?,
+			access_list: None,
 			hash: hash,
 		})
 	}
@@ -320,11 +412,75 @@ impl rlp::Encodable for UnverifiedTransaction {
 impl UnverifiedTransaction {
 	/// Used to compute hash of created transactions
 	fn compute_hash(mut self) -> UnverifiedTransaction {
-		let hash = keccak(&*self.rlp_bytes());
+		let hash = match self.access_list {
+			Some(_) => keccak(self.envelope()),
+			None => keccak(&*self.rlp_bytes()),
+		};
 		self.hash = hash;
 		self
 	}
 
+	/// Decodes a raw transaction as submitted by users: either a legacy RLP list
+	/// or a typed transaction envelope.
+	pub fn decode_raw(bytes: &[u8]) -> Result<Self, DecoderError> {
+		match bytes.first() {
+			Some(&first) if first < 0xc0 => UnverifiedTransaction::decode_envelope(bytes),
+			_ => rlp::decode(bytes),
+		}
+	}
+
+	/// Decodes typed transaction envelope: `type || rlp(payload)`.
+	fn decode_envelope(bytes: &[u8]) -> Result<Self, DecoderError> {
+		match bytes.first() {
+			Some(&ACCESS_LIST_TX_TYPE) => {},
+			Some(_) => return Err(DecoderError::Custom("Unknown transaction type")),
+			None => return Err(DecoderError::RlpIsTooShort),
+		}
+
+		let d = Rlp::new(&bytes[1..]);
+		if d.item_count()? != 11 {
+			return Err(DecoderError::RlpIncorrectListLen);
+		}
+		let chain_id: u64 = d.val_at(0)?;
+		let parity: u64 = d.val_at(8)?;
+		if parity > 1 {
+			return Err(DecoderError::Custom("Invalid signature parity"));
+		}
+		let v = chain_id.checked_mul(2).and_then(|n| n.checked_add(35 + parity))
+			.ok_or(DecoderError::Custom("Chain id out of range"))?;
+		Ok(UnverifiedTransaction {
+			unsigned: Transaction {
+				nonce: d.val_at(1)?,
+				gas_price: d.val_at(2)?,
+				gas: d.val_at(3)?,
+				action: d.val_at(4)?,
+				value: d.val_at(5)?,
+				data: d.val_at(6)?,
+			},
+			v: v,
+			r: d.val_at(9)?,
+			s: d.val_at(10)?,
+			access_list: Some(decode_access_list(&d.at(7)?)?),
+			hash: keccak(bytes),
+		})
+	}
+
+	/// Typed transaction envelope of EIP-2930 transactions.
+	fn envelope(&self) -> Bytes {
+		let access_list = self.access_list.as_ref().expect("envelope is only built for access-list transactions; qed");
+		let mut s = RlpStream::new_list(11);
+		s.append(&self.chain_id().expect("access-list transactions always carry chain id; qed"));
+		self.unsigned.rlp_append_fields(&mut s);
+		append_access_list(&mut s, access_list);
+		s.append(&self.standard_v());
+		s.append(&self.r);
+		s.append(&self.s);
+
+		let mut envelope = vec![ACCESS_LIST_TX_TYPE];
+		envelope.extend_from_slice(s.as_raw());
+		envelope
+	}
+
 	/// Checks is signature is empty.
 	pub fn is_unsigned(&self) -> bool {
 		self.r.is_zero() && self.s.is_zero()
@@ -332,6 +488,10 @@ impl UnverifiedTransaction {
 
 	/// Append object with a signature into RLP stream
 	fn rlp_append_sealed_transaction(&self, s: &mut RlpStream) {
+		if self.access_list.is_some() {
+			s.append(&self.envelope());
+			return;
+		}
 		s.begin_list(9);
 		s.append(&self.nonce);
 		s.append(&self.gas_price);
@@ -349,7 +509,12 @@ impl UnverifiedTransaction {
 		&self.unsigned
 	}
 
-	pub fn standard_v(&self) -> u8 { signature::check_replay_protection(self.v) }
+	pub fn standard_v(&self) -> u8 {
+		match self.access_list {
+			Some(_) => ((self.v - 35) % 2) as u8,
+			None => signature::check_replay_protection(self.v),
+		}
+	}
 
 	/// The `v` value that appears in the RLP.
 	pub fn original_v(&self) -> u64 { self.v }
@@ -358,7 +523,7 @@ impl UnverifiedTransaction {
 	pub fn chain_id(&self) -> Option<u64> {
 		match self.v {
 			v if self.is_unsigned() => Some(v),
-			v if v > 36 => Some((v - 35) / 2),
+			v if v > 36 || self.access_list.is_some() => Some((v - 35) / 2),
 			_ => None,
 		}
 	}
@@ -382,9 +547,33 @@ impl UnverifiedTransaction {
 		self.hash
 	}
 
+	/// Access list of EIP-2930 transactions, `None` for legacy transactions.
+	pub fn access_list(&self) -> Option<&AccessList> {
+		self.access_list.as_ref()
+	}
+
+	/// The message hash signed by the sender.
+	pub fn signature_hash(&self) -> H256 {
+		match self.access_list {
+			Some(ref access_list) => {
+				let chain_id = self.chain_id().expect("access-list transactions always carry chain id; qed");
+				self.unsigned.access_list_hash(chain_id, access_list)
+			},
+			None => self.unsigned.hash(self.chain_id()),
+		}
+	}
+
+	/// Get the transaction cost in gas, including the declared access list.
+	pub fn gas_required(&self, schedule: &Schedule) -> u64 {
+		let base = self.unsigned.gas_required(schedule);
+		self.access_list.as_ref().map_or(base, |access_list| access_list.iter().fold(base, |g, &(_, ref keys)| {
+			g + schedule.tx_access_list_address_gas as u64 + keys.len() as u64 * schedule.tx_access_list_storage_key_gas as u64
+		}))
+	}
+
 	/// Recovers the public key of the sender.
 	pub fn recover_public(&self) -> Result<Public, ethkey::Error> {
-		Ok(recover(&self.signature(), &self.unsigned.hash(self.chain_id()))?)
+		Ok(recover(&self.signature(), &self.signature_hash())?)
 	}
 
 	/// Do basic validation, checking for valid signature and minimum gas,
@@ -406,8 +595,15 @@ impl UnverifiedTransaction {
 
 	/// Verify basic signature params. Does not attempt sender recovery.
 	pub fn verify_basic(&self, check_low_s: bool, chain_id: Option<u64>, allow_empty_signature: bool) -> Result<(), error::Error> {
-		if check_low_s && !(allow_empty_signature && self.is_unsigned()) {
-			self.check_low_s()?;
+		if !self.is_unsigned() {
+			let signature = self.signature();
+			if !signature.is_valid() {
+				return Err(error::Error::SignatureOutOfRange);
+			}
+			// EIP-2: signatures with `s` in the upper half of the curve order are malleable.
+			if check_low_s && !signature.is_low_s() {
+				return Err(error::Error::MalleableSignature);
+			}
 		}
 		// EIP-86: Transactions of this form MUST have gasprice = 0, nonce = 0, value = 0, and do NOT increment the nonce of account 0.
 		if allow_empty_signature && self.is_unsigned() && !(self.gas_price.is_zero() && self.value.is_zero() && self.nonce.is_zero()) {
@@ -416,7 +612,7 @@ impl UnverifiedTransaction {
 		match (self.chain_id(), chain_id) {
 			(None, _) => {},
 			(Some(n), Some(m)) if n == m => {},
-			_ => return Err(error::Error::InvalidChainId),
+			(Some(got), expected) => return Err(error::Error::InvalidChainId { expected, got }),
 		};
 		Ok(())
 	}
@@ -432,7 +628,7 @@ pub struct SignedTransaction {
 
 impl HeapSizeOf for SignedTransaction {
 	fn heap_size_of_children(&self) -> usize {
-		self.transaction.unsigned.heap_size_of_children()
+		self.transaction.unsigned.heap_size_of_children() + self.transaction.access_list.heap_size_of_children()
 	}
 }
 
@@ -541,6 +737,8 @@ pub struct PendingTransaction {
 	pub transaction: SignedTransaction,
 	/// To be activated at this condition. `None` for immediately.
 	pub condition: Option<Condition>,
+	/// Whether the transaction should be kept by this node and never propagated to peers.
+	pub local_only: bool,
 }
 
 impl PendingTransaction {
@@ -549,6 +747,7 @@ impl PendingTransaction {
 		PendingTransaction {
 			transaction: signed,
 			condition: condition,
+			local_only: false,
 		}
 	}
 }
@@ -564,6 +763,7 @@ impl From<SignedTransaction> for PendingTransaction {
 		PendingTransaction {
 			transaction: t,
 			condition: None,
+			local_only: false,
 		}
 	}
 }
@@ -641,6 +841,80 @@ mod tests {
 		assert_eq!(t.chain_id(), Some(69));
 	}
 
+	#[test]
+	fn should_encode_and_recover_access_list_transaction() {
+		use ethkey::{Random, Generator};
+
+		let key = Random.generate().unwrap();
+		let access_list = vec![(Address::from(0x69), vec![H256::from(1), H256::from(2)])];
+		let t = Transaction {
+			action: Action::Call(Address::from(0x69)),
+			nonce: U256::from(42),
+			gas_price: U256::from(3000),
+			gas: U256::from(50_000),
+			value: U256::from(1),
+			data: b"Hello!".to_vec()
+		}.sign_with_access_list(&key.secret(), 69, access_list.clone());
+		assert_eq!(Address::from(keccak(key.public())), t.sender());
+		assert_eq!(t.chain_id(), Some(69));
+		assert_eq!(t.access_list(), Some(&access_list));
+
+		let encoded = rlp::encode(&t);
+		let decoded: UnverifiedTransaction = rlp::decode(&encoded).expect("decoding access-list transaction failed");
+		assert_eq!(decoded.hash(), t.hash());
+		let raw = UnverifiedTransaction::decode_raw(&decoded.envelope()).expect("decoding raw envelope failed");
+		assert_eq!(raw.hash(), t.hash());
+		assert_eq!(decoded.access_list(), Some(&access_list));
+		assert_eq!(SignedTransaction::new(decoded).unwrap().sender(), t.sender());
+
+		let schedule = Schedule::new_byzantium();
+		assert_eq!(t.gas_required(&schedule), t.as_unsigned().gas_required(&schedule) + 2400 + 2 * 1900);
+	}
+
+	#[test]
+	fn should_reject_access_list_transaction_with_chain_id_out_of_range() {
+		let mut s = RlpStream::new_list(11);
+		s.append(&u64::max_value());
+		Transaction::default().rlp_append_fields(&mut s);
+		s.begin_list(0);
+		s.append(&0u8);
+		s.append(&U256::one());
+		s.append(&U256::one());
+
+		let mut envelope = vec![ACCESS_LIST_TX_TYPE];
+		envelope.extend_from_slice(s.as_raw());
+		assert_eq!(UnverifiedTransaction::decode_raw(&envelope), Err(DecoderError::Custom("Chain id out of range")));
+	}
+
+	#[test]
+	fn should_reject_malleable_signature() {
+		use ethkey::{Random, Generator};
+
+		let key = Random.generate().unwrap();
+		let t = Transaction {
+			action: Action::Create,
+			nonce: U256::from(42),
+			gas_price: U256::from(3000),
+			gas: U256::from(50_000),
+			value: U256::from(1),
+			data: b"Hello!".to_vec()
+		}.sign(&key.secret(), None).transaction;
+		assert_eq!(t.verify_basic(true, None, false), Ok(()));
+
+		// the same signature with `s` mirrored to the upper half of the curve order.
+		let order: U256 = "fffffffffffffffffffffffffffffffebaaedce6af48a03bbfd25e8cd0364141".into();
+		let malleable = UnverifiedTransaction {
+			s: order - t.s,
+			v: if t.v == 27 { 28 } else { 27 },
+			..t.clone()
+		};
+		assert_eq!(malleable.verify_basic(true, None, false), Err(error::Error::MalleableSignature));
+		assert_eq!(malleable.verify_basic(false, None, false), Ok(()));
+
+		let out_of_range = UnverifiedTransaction { s: order, ..t };
+		assert_eq!(out_of_range.verify_basic(false, None, false), Err(error::Error::SignatureOutOfRange));
+	}
+
 	#[test]
 	fn should_agree_with_vitalik() {
 		use rustc_hex::FromHex;
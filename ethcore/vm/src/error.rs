@@ -69,6 +69,15 @@ pub enum Error {
 	OutOfBounds,
 	/// Execution has been reverted with REVERT.
 	Reverted,
+	/// Contract creation targeted an address which already has code or a nonzero nonce.
+	ContractAddressCollision,
+	/// Execution tried to expand memory past the configured limit.
+	OutOfMemory {
+		/// Memory size in bytes the instruction required
+		required: usize,
+		/// Memory limit in bytes
+		limit: usize,
+	},
 }
 
 
@@ -99,6 +108,8 @@ impl fmt::Display for Error {
 			Wasm(ref msg) => write!(f, "Internal error: {}", msg),
 			OutOfBounds => write!(f, "Out of bounds"),
 			Reverted => write!(f, "Reverted"),
+			ContractAddressCollision => write!(f, "Contract address collision"),
+			OutOfMemory { required, limit } => write!(f, "Out of memory {}/{}", required, limit),
 		}
 	}
 }
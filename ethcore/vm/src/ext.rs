@@ -66,6 +66,9 @@ pub trait Ext {
 	/// Returns a value for given key.
 	fn storage_at(&self, key: &H256) -> Result<H256>;
 
+	/// Returns the value for given key as it was at the start of the transaction.
+	fn initial_storage_at(&self, key: &H256) -> Result<H256>;
+
 	/// Stores a value for given key.
 	fn set_storage(&mut self, key: H256, value: H256) -> Result<()>;
 
@@ -134,14 +137,30 @@ pub trait Ext {
 	/// then A depth is 0, B is 1, C is 2 and so on.
 	fn depth(&self) -> usize;
 
-	/// Increments sstore refunds count by 1.
-	fn inc_sstore_clears(&mut self);
+	/// Increments sstore refund by given amount.
+	fn add_sstore_refund(&mut self, value: usize);
+
+	/// Decrements sstore refund by given amount. The refund may become temporarily negative.
+	fn sub_sstore_refund(&mut self, value: usize);
+
+	/// Check if the account was accessed before in the transaction (EIP-2929).
+	fn is_warm_address(&self, address: &Address) -> bool;
+
+	/// Mark the account as accessed in the transaction.
+	fn warm_address(&mut self, address: &Address);
+
+	/// Check if the storage key of the current contract was accessed before in the transaction (EIP-2929).
+	fn is_warm_storage_key(&self, key: &H256) -> bool;
+
+	/// Mark the storage key of the current contract as accessed in the transaction.
+	fn warm_storage_key(&mut self, key: &H256);
 
 	/// Decide if any more operations should be traced. Passthrough for the VM trace.
 	fn trace_next_instruction(&mut self, _pc: usize, _instruction: u8, _current_gas: U256) -> bool { false }
 
 	/// Prepare to trace an operation. Passthrough for the VM trace.
-	fn trace_prepare_execute(&mut self, _pc: usize, _instruction: u8, _gas_cost: U256) {}
+	/// `dynamic_gas` is the part of `gas_cost` depending on the size of the operands.
+	fn trace_prepare_execute(&mut self, _pc: usize, _instruction: u8, _gas_cost: U256, _dynamic_gas: U256) {}
 
 	/// Trace the finalised execution of a single instruction.
 	fn trace_executed(&mut self, _gas_used: U256, _stack_push: &[U256], _mem_diff: Option<(usize, &[u8])>, _store_diff: Option<(U256, U256)>) {}
@@ -48,6 +48,11 @@ pub struct Schedule {
 	pub sstore_reset_gas: usize,
 	/// Gas refund for `SSTORE` clearing (when `storage!=0`, `new==0`)
 	pub sstore_refund_gas: usize,
+	/// If Some(x): net gas metering for `SSTORE` (EIP-1283), with `x` charged for dirty slots.
+	/// If None: `SSTORE` is charged based on current and new value only.
+	pub sstore_dirty_gas: Option<usize>,
+	/// Fail `SSTORE` if gas left is not above the call stipend (EIP-1706).
+	pub eip1706: bool,
 	/// Gas price for `JUMPDEST` opcode
 	pub jumpdest_gas: usize,
 	/// Gas price for `LOG*`
@@ -68,6 +73,11 @@ pub struct Schedule {
 	pub call_new_account_gas: usize,
 	/// Refund for SUICIDE
 	pub suicide_refund_gas: usize,
+	/// Does it have a SUICIDE instruction
+	pub have_suicide: bool,
+	/// Whether the balance left in a suicided account (e.g. after SUICIDE to itself) is burnt.
+	/// If not, the account loses its code and storage but keeps the balance.
+	pub suicide_burns_balance: bool,
 	/// Gas for used memory
 	pub memory_gas: usize,
 	/// Coefficient used to convert memory size to gas price for memory
@@ -84,6 +94,22 @@ pub struct Schedule {
 	pub tx_data_zero_gas: usize,
 	/// Aditional cost for non-empty data transaction
 	pub tx_data_non_zero_gas: usize,
+	/// Does it accept access-list transactions (EIP-2930)
+	pub have_access_lists: bool,
+	/// Does it reject transaction signatures with high `s` value (EIP-2)
+	pub require_low_s: bool,
+	/// Cost of each address declared in a transaction access list
+	pub tx_access_list_address_gas: usize,
+	/// Cost of each storage key declared in a transaction access list
+	pub tx_access_list_storage_key_gas: usize,
+	/// Does it charge more for the first access of an account or storage key in a transaction (EIP-2929)
+	pub eip2929: bool,
+	/// Gas price for the first access of a storage key in a transaction
+	pub cold_sload_gas: usize,
+	/// Gas price for the first access of an account in a transaction
+	pub cold_account_access_gas: usize,
+	/// Gas price for accessing an account or storage key accessed before in the transaction
+	pub warm_storage_read_gas: usize,
 	/// Gas price for copying memory
 	pub copy_gas: usize,
 	/// Price of EXTCODESIZE
@@ -185,7 +211,9 @@ impl Schedule {
 
 	/// Schedule for the Homestead-era of the Ethereum main net.
 	pub fn new_homestead() -> Schedule {
-		Self::new(true, true, 53000)
+		let mut schedule = Self::new(true, true, 53000);
+		schedule.require_low_s = true;
+		schedule
 	}
 
 	/// Schedule for the post-EIP-150-era of the Ethereum main net.
@@ -208,6 +236,8 @@ impl Schedule {
 			sstore_set_gas: 20000,
 			sstore_reset_gas: 5000,
 			sstore_refund_gas: 15000,
+			sstore_dirty_gas: None,
+			eip1706: false,
 			jumpdest_gas: 1,
 			log_gas: 375,
 			log_data_gas: 8,
@@ -218,6 +248,8 @@ impl Schedule {
 			call_value_transfer_gas: 9000,
 			call_new_account_gas: 25000,
 			suicide_refund_gas: 24000,
+			have_suicide: true,
+			suicide_burns_balance: true,
 			memory_gas: 3,
 			quad_coeff_div: 512,
 			create_data_gas: 200,
@@ -226,6 +258,14 @@ impl Schedule {
 			tx_create_gas: 53000,
 			tx_data_zero_gas: 4,
 			tx_data_non_zero_gas: 68,
+			have_access_lists: false,
+			require_low_s: true,
+			tx_access_list_address_gas: 2400,
+			tx_access_list_storage_key_gas: 1900,
+			eip2929: false,
+			cold_sload_gas: 2100,
+			cold_account_access_gas: 2600,
+			warm_storage_read_gas: 100,
 			copy_gas: 3,
 			extcodesize_gas: 700,
 			extcodecopy_base_gas: 700,
@@ -279,6 +319,8 @@ impl Schedule {
 			sstore_set_gas: 20000,
 			sstore_reset_gas: 5000,
 			sstore_refund_gas: 15000,
+			sstore_dirty_gas: None,
+			eip1706: false,
 			jumpdest_gas: 1,
 			log_gas: 375,
 			log_data_gas: 8,
@@ -289,6 +331,8 @@ impl Schedule {
 			call_value_transfer_gas: 9000,
 			call_new_account_gas: 25000,
 			suicide_refund_gas: 24000,
+			have_suicide: true,
+			suicide_burns_balance: true,
 			memory_gas: 3,
 			quad_coeff_div: 512,
 			create_data_gas: 200,
@@ -297,6 +341,14 @@ impl Schedule {
 			tx_create_gas: tcg,
 			tx_data_zero_gas: 4,
 			tx_data_non_zero_gas: 68,
+			have_access_lists: false,
+			require_low_s: false,
+			tx_access_list_address_gas: 2400,
+			tx_access_list_storage_key_gas: 1900,
+			eip2929: false,
+			cold_sload_gas: 2100,
+			cold_account_access_gas: 2600,
+			warm_storage_read_gas: 100,
 			copy_gas: 3,
 			extcodesize_gas: 20,
 			extcodecopy_base_gas: 20,
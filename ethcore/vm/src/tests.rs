@@ -54,7 +54,7 @@ pub struct FakeExt {
 	pub store: HashMap<H256, H256>,
 	pub suicides: HashSet<Address>,
 	pub calls: HashSet<FakeCall>,
-	pub sstore_clears: usize,
+	pub sstore_clears: i128,
 	pub depth: usize,
 	pub blockhashes: HashMap<U256, H256>,
 	pub codes: HashMap<Address, Arc<Bytes>>,
@@ -64,6 +64,8 @@ pub struct FakeExt {
 	pub balances: HashMap<Address, U256>,
 	pub tracing: bool,
 	pub is_static: bool,
+	pub warm_addresses: HashSet<Address>,
+	pub warm_storage_keys: HashSet<H256>,
 }
 
 // similar to the normal `finalize` function, but ignoring NeedsReturn.
@@ -107,6 +109,10 @@ impl Ext for FakeExt {
 		Ok(self.store.get(key).unwrap_or(&H256::new()).clone())
 	}
 
+	fn initial_storage_at(&self, _key: &H256) -> Result<H256> {
+		Ok(H256::new())
+	}
+
 	fn set_storage(&mut self, key: H256, value: H256) -> Result<()> {
 		self.store.insert(key, value);
 		Ok(())
@@ -209,8 +215,28 @@ impl Ext for FakeExt {
 		self.is_static
 	}
 
-	fn inc_sstore_clears(&mut self) {
-		self.sstore_clears += 1;
+	fn add_sstore_refund(&mut self, value: usize) {
+		self.sstore_clears += value as i128;
+	}
+
+	fn sub_sstore_refund(&mut self, value: usize) {
+		self.sstore_clears -= value as i128;
+	}
+
+	fn is_warm_address(&self, address: &Address) -> bool {
+		self.warm_addresses.contains(address)
+	}
+
+	fn warm_address(&mut self, address: &Address) {
+		self.warm_addresses.insert(*address);
+	}
+
+	fn is_warm_storage_key(&self, key: &H256) -> bool {
+		self.warm_storage_keys.contains(key)
+	}
+
+	fn warm_storage_key(&mut self, key: &H256) {
+		self.warm_storage_keys.insert(*key);
 	}
 
 	fn trace_next_instruction(&mut self, _pc: usize, _instruction: u8, _gas: U256) -> bool {
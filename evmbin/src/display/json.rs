@@ -114,7 +114,7 @@ impl trace::VMTracer for Informant {
 		true
 	}
 
-	fn trace_prepare_execute(&mut self, pc: usize, instruction: u8, gas_cost: U256) {
+	fn trace_prepare_execute(&mut self, pc: usize, instruction: u8, gas_cost: U256, _dynamic_gas: U256) {
 		self.pc = pc;
 		self.instruction = instruction;
 		self.gas_cost = gas_cost;
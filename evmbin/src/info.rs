@@ -81,6 +81,8 @@ pub fn run_action<T: Informant>(
 }
 
 /// Execute given Transaction and verify resulting state root.
+///
+/// Returns the state root reached, `None` if the spec is not supported or execution failed.
 pub fn run_transaction<T: Informant>(
 	name: &str,
 	idx: usize,
@@ -90,7 +92,7 @@ pub fn run_transaction<T: Informant>(
 	env_info: &client::EnvInfo,
 	transaction: transaction::SignedTransaction,
 	mut informant: T,
-) {
+) -> Option<H256> {
 	let spec_name = format!("{:?}", spec).to_lowercase();
 	let spec = match EvmTestClient::spec_from_json(spec) {
 		Some(spec) => {
@@ -99,16 +101,18 @@ pub fn run_transaction<T: Informant>(
 		},
 		None => {
 			informant.before_test(&format!("{}:{}:{}", name, spec_name, idx), "skipping because of missing spec");
-			return;
+			return None;
 		},
 	};
 
 	informant.set_gas(env_info.gas_limit);
 
+	let mut reached_root = None;
 	let result = run(spec, env_info.gas_limit, pre_state, |mut client| {
 		let result = client.transact(env_info, transaction, trace::NoopTracer, informant);
 		match result {
 			TransactResult::Ok { state_root, .. } if state_root != post_root => {
+				reached_root = Some(state_root);
 				(Err(EvmTestError::PostCondition(format!(
 					"State root mismatch (got: {}, expected: {})",
 					state_root,
@@ -116,6 +120,7 @@ pub fn run_transaction<T: Informant>(
 				))), None)
 			},
 			TransactResult::Ok { state_root, gas_left, output, vm_trace, .. } => {
+				reached_root = Some(state_root);
 				(Ok((state_root, gas_left, output)), vm_trace)
 			},
 			TransactResult::Err { error, .. } => {
@@ -126,7 +131,8 @@ pub fn run_transaction<T: Informant>(
 		}
 	});
 
-	T::finish(result)
+	T::finish(result);
+	reached_root
 }
 
 /// Execute VM with given `ActionParams`
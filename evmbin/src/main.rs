@@ -51,6 +51,7 @@ use vm::{ActionParams, CallType};
 
 mod info;
 mod display;
+mod statetest;
 
 use info::Informant;
 
@@ -64,6 +65,10 @@ Usage:
     parity-evm [options]
     parity-evm [-h | --help]
 
+Exit status:
+    state-test exits with 1 when any post state doesn't match the expected state
+    root, and with 0 otherwise.
+
 Transaction options:
     --code CODE        Contract code as hex (without 0x).
     --to ADDRESS       Recipient address (without 0x).
@@ -101,50 +106,29 @@ fn main() {
 }
 
 fn run_state_test(args: Args) {
-	use ethjson::state::test::Test;
-
 	let file = args.arg_file.expect("FILE is required");
-	let mut file = match fs::File::open(&file) {
+	let file = match fs::File::open(&file) {
 		Err(err) => die(format!("Unable to open: {:?}: {}", file, err)),
 		Ok(file) => file,
 	};
-	let state_test = match Test::load(&mut file) {
-		Err(err) => die(format!("Unable to load the test file: {}", err)),
-		Ok(test) => test,
+	let filter = statetest::Filter {
+		only_test: args.flag_only,
+		only_chain: args.flag_chain,
 	};
-	let only_test = args.flag_only.map(|s| s.to_lowercase());
-	let only_chain = args.flag_chain.map(|s| s.to_lowercase());
 
-	for (name, test) in state_test {
-		if let Some(false) = only_test.as_ref().map(|only_test| &name.to_lowercase() == only_test) {
-			continue;
-		}
+	let results = if args.flag_json {
+		statetest::run_state_test(file, &filter, display::json::Informant::default)
+	} else if args.flag_std_json {
+		statetest::run_state_test(file, &filter, display::std_json::Informant::default)
+	} else {
+		statetest::run_state_test(file, &filter, display::simple::Informant::default)
+	};
 
-		let multitransaction = test.transaction;
-		let env_info = test.env.into();
-		let pre = test.pre_state.into();
-
-		for (spec, states) in test.post_states {
-			if let Some(false) = only_chain.as_ref().map(|only_chain| &format!("{:?}", spec).to_lowercase() == only_chain) {
-				continue;
-			}
-
-			for (idx, state) in states.into_iter().enumerate() {
-				let post_root = state.hash.into();
-				let transaction = multitransaction.select(&state.indexes).into();
-
-				if args.flag_json {
-					let i = display::json::Informant::default();
-					info::run_transaction(&name, idx, &spec, &pre, post_root, &env_info, transaction, i)
-				} else if args.flag_std_json {
-					let i = display::std_json::Informant::default();
-					info::run_transaction(&name, idx, &spec, &pre, post_root, &env_info, transaction, i)
-				} else {
-					let i = display::simple::Informant::default();
-					info::run_transaction(&name, idx, &spec, &pre, post_root, &env_info, transaction, i)
-				}
-			}
-		}
+	// mismatching post states fail the process, so that test runners can rely on the exit code.
+	match results {
+		Err(err) => die(err),
+		Ok(ref results) if results.iter().any(|r| !r.is_ok()) => ::std::process::exit(1),
+		Ok(_) => {},
 	}
 }
 
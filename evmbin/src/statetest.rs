@@ -0,0 +1,173 @@
+// Copyright 2015-2018 Parity Technologies (UK) Ltd.
+// This file is part of Parity.
+
+// Parity is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// Parity is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with Parity.  If not, see <http://www.gnu.org/licenses/>.
+
+//! General state tests runner.
+
+use std::io::Read;
+use ethereum_types::H256;
+use ethjson::state::test::Test;
+use info::{self, Informant};
+
+/// Outcome of a single post state of a state test.
+#[derive(Debug, Clone, PartialEq)]
+pub struct PostState {
+	/// Name of the test.
+	pub name: String,
+	/// Fork the transaction was executed with, e.g. `byzantium`.
+	pub spec: String,
+	/// Index of the post state within the fork.
+	pub index: usize,
+	/// Expected state root.
+	pub expected_root: H256,
+	/// State root after executing the transaction.
+	/// `None` if the fork is not supported or the transaction could not be executed.
+	pub state_root: Option<H256>,
+}
+
+impl PostState {
+	/// Whether the expected state root was reached.
+	pub fn is_ok(&self) -> bool {
+		self.state_root == Some(self.expected_root)
+	}
+}
+
+/// Selection of tests to run. Names are matched case-insensitively.
+#[derive(Debug, Default)]
+pub struct Filter {
+	/// Run only the test with given name.
+	pub only_test: Option<String>,
+	/// Run only post states of given fork.
+	pub only_chain: Option<String>,
+}
+
+/// Execute all post states of the state tests read from `json`.
+///
+/// Every post state is traced by a fresh informant created with `informant`.
+pub fn run_state_test<R, T, F>(json: R, filter: &Filter, informant: F) -> Result<Vec<PostState>, String> where
+	R: Read,
+	T: Informant,
+	F: Fn() -> T,
+{
+	let state_test = Test::load(json).map_err(|err| format!("Unable to load the test file: {}", err))?;
+	let only_test = filter.only_test.as_ref().map(|s| s.to_lowercase());
+	let only_chain = filter.only_chain.as_ref().map(|s| s.to_lowercase());
+	let mut results = Vec::new();
+
+	for (name, test) in state_test {
+		if let Some(false) = only_test.as_ref().map(|only_test| &name.to_lowercase() == only_test) {
+			continue;
+		}
+
+		let multitransaction = test.transaction;
+		let env_info = test.env.into();
+		let pre = test.pre_state.into();
+
+		for (spec, states) in test.post_states {
+			let spec_name = format!("{:?}", spec).to_lowercase();
+			if let Some(false) = only_chain.as_ref().map(|only_chain| &spec_name == only_chain) {
+				continue;
+			}
+
+			for (idx, state) in states.into_iter().enumerate() {
+				let post_root = state.hash.into();
+				let transaction = multitransaction.select(&state.indexes).into();
+				let state_root = info::run_transaction(&name, idx, &spec, &pre, post_root, &env_info, transaction, informant());
+
+				results.push(PostState {
+					name: name.clone(),
+					spec: spec_name.clone(),
+					index: idx,
+					expected_root: post_root,
+					state_root: state_root,
+				});
+			}
+		}
+	}
+
+	Ok(results)
+}
+
+#[cfg(test)]
+mod tests {
+	use ethereum_types::H256;
+	use display;
+	use super::{run_state_test, Filter};
+
+	const TEST: &'static str = r#"{
+		"transfer": {
+			"env": {
+				"currentCoinbase": "2adc25665018aa1fe0e6bc666dac8fc2697ff9ba",
+				"currentDifficulty": "0x0100",
+				"currentGasLimit": "0x01c9c380",
+				"currentNumber": "0x00",
+				"currentTimestamp": "0x01",
+				"previousHash": "5e20a0453cecd065ea59c37ac63e079ee08998b6045136a8ce6635c7912ec0b6"
+			},
+			"post": {
+				"Byzantium": [
+					{
+						"hash": "0000000000000000000000000000000000000000000000000000000000000000",
+						"indexes": { "data": 0, "gas": 0, "value": 0 }
+					}
+				],
+				"EIP150": [
+					{
+						"hash": "0000000000000000000000000000000000000000000000000000000000000000",
+						"indexes": { "data": 0, "gas": 0, "value": 0 }
+					}
+				]
+			},
+			"pre": {
+				"a94f5374fce5edbc8e2a8697c15331677e6ebf0b": {
+					"balance": "0x0de0b6b3a7640000",
+					"code": "0x",
+					"nonce": "0x00",
+					"storage": {}
+				}
+			},
+			"transaction": {
+				"data": [ "" ],
+				"gasLimit": [ "0x5208" ],
+				"gasPrice": "0x01",
+				"nonce": "0x00",
+				"secretKey": "45a915e4d060149eb4365960e6a7a45f334393093061116b197e3240065ff2d8",
+				"to": "095e7baea6a6c7c4c2dfeb977efac326af552d87",
+				"value": [ "0x01" ]
+			}
+		}
+	}"#;
+
+	#[test]
+	fn reports_post_state_mismatch() {
+		let filter = Filter {
+			only_chain: Some("Byzantium".into()),
+			..Default::default()
+		};
+		let results = run_state_test(TEST.as_bytes(), &filter, display::simple::Informant::default).unwrap();
+
+		assert_eq!(results.len(), 1);
+		assert_eq!(results[0].name, "transfer");
+		assert_eq!(results[0].spec, "byzantium");
+		assert_eq!(results[0].expected_root, H256::zero());
+		assert!(results[0].state_root.is_some());
+		assert!(!results[0].is_ok());
+	}
+
+	#[test]
+	fn rejects_malformed_test() {
+		assert!(run_state_test(&b"{"[..], &Filter::default(), display::simple::Informant::default).is_err());
+	}
+}
@@ -63,6 +63,9 @@ pub struct AuthorityRoundParams {
 	/// Maximum number of accepted empty steps.
 	#[serde(rename="maximumEmptySteps")]
 	pub maximum_empty_steps: Option<Uint>,
+	/// Number of consecutive steps a validator may miss before an alert is raised.
+	#[serde(rename="missedStepsAlertThreshold")]
+	pub missed_steps_alert_threshold: Option<Uint>,
 }
 
 /// Authority engine deserialization.
@@ -57,6 +57,15 @@ pub enum Pricing {
 	AltBn128Pairing(AltBn128Pairing),
 }
 
+/// Pricing which replaces the builtin pricing from given block on.
+#[derive(Debug, PartialEq, Deserialize, Clone)]
+pub struct Repricing {
+	/// Activation block.
+	pub activate_at: Uint,
+	/// New pricing.
+	pub pricing: Pricing,
+}
+
 /// Spec builtin.
 #[derive(Debug, PartialEq, Deserialize, Clone)]
 pub struct Builtin {
@@ -66,12 +75,14 @@ pub struct Builtin {
 	pub pricing: Pricing,
 	/// Activation block.
 	pub activate_at: Option<Uint>,
+	/// Pricing changes at later forks.
+	pub repricing: Option<Vec<Repricing>>,
 }
 
 #[cfg(test)]
 mod tests {
 	use serde_json;
-	use spec::builtin::{Builtin, Pricing, Linear, Modexp};
+	use spec::builtin::{Builtin, Pricing, Linear, Modexp, AltBn128Pairing, Repricing};
 	use uint::Uint;
 
 	#[test]
@@ -99,4 +110,23 @@ mod tests {
 		assert_eq!(deserialized.pricing, Pricing::Modexp(Modexp { divisor: 5 }));
 		assert_eq!(deserialized.activate_at, Some(Uint(100000.into())));
 	}
+
+	#[test]
+	fn repricing() {
+		let s = r#"{
+			"name": "alt_bn128_pairing",
+			"activate_at": 4370000,
+			"pricing": { "alt_bn128_pairing": { "base": 100000, "pair": 80000 } },
+			"repricing": [{
+				"activate_at": 7280000,
+				"pricing": { "alt_bn128_pairing": { "base": 45000, "pair": 34000 } }
+			}]
+		}"#;
+
+		let deserialized: Builtin = serde_json::from_str(s).unwrap();
+		assert_eq!(deserialized.repricing, Some(vec![Repricing {
+			activate_at: Uint(7280000.into()),
+			pricing: Pricing::AltBn128Pairing(AltBn128Pairing { base: 45000, pair: 34000 }),
+		}]));
+	}
 }
@@ -16,7 +16,7 @@
 
 //! Engine deserialization.
 
-use super::{Ethash, BasicAuthority, AuthorityRound, Tendermint, NullEngine};
+use super::{Ethash, BasicAuthority, AuthorityRound, Tendermint, NullEngine, Clique};
 
 /// Engine deserialization.
 #[derive(Debug, PartialEq, Deserialize)]
@@ -37,7 +37,10 @@ pub enum Engine {
 	AuthorityRound(AuthorityRound),
 	/// Tendermint engine.
 	#[serde(rename="tendermint")]
-	Tendermint(Tendermint)
+	Tendermint(Tendermint),
+	/// Clique engine.
+	#[serde(rename="clique")]
+	Clique(Clique),
 }
 
 #[cfg(test)]
@@ -140,6 +143,20 @@ mod tests {
 			Engine::Tendermint(_) => {}, // Tendermint is unit tested in its own file.
 			_ => panic!(),
 		};
+
+		let s = r#"{
+			"clique": {
+				"params": {
+					"period": 15,
+					"epoch": 30000
+				}
+			}
+		}"#;
+		let deserialized: Engine = serde_json::from_str(s).unwrap();
+		match deserialized {
+			Engine::Clique(_) => {}, // Clique is unit tested in its own file.
+			_ => panic!(),
+		};
 	}
 }
 
@@ -16,6 +16,7 @@
 
 //! Ethash params deserialization.
 
+use std::collections::BTreeMap;
 use uint::{self, Uint};
 use hash::Address;
 
@@ -115,6 +116,10 @@ pub struct EthashParams {
 	#[serde(rename="eip649Reward")]
 	pub eip649_reward: Option<Uint>,
 
+	/// Further difficulty bomb delays, by transition block.
+	#[serde(rename="difficultyBombDelays")]
+	pub difficulty_bomb_delays: Option<BTreeMap<Uint, Uint>>,
+
 	/// EXPIP-2 block height
 	#[serde(rename="expip2Transition")]
 	pub expip2_transition: Option<Uint>,
@@ -122,6 +127,15 @@ pub struct EthashParams {
 	/// EXPIP-2 duration limit
 	#[serde(rename="expip2DurationLimit")]
 	pub expip2_duration_limit: Option<Uint>,
+
+	/// Maximum number of uncles a block may include.
+	#[serde(rename="maximumUncleCount")]
+	pub maximum_uncle_count: Option<Uint>,
+
+	/// Maximum depth of an uncle relative to the including block.
+	#[serde(rename="maximumUncleAge")]
+	#[serde(default, deserialize_with="uint::validate_optional_non_zero")]
+	pub maximum_uncle_age: Option<Uint>,
 }
 
 /// Ethash engine deserialization.
@@ -230,8 +244,11 @@ mod tests {
 				eip649_transition: None,
 				eip649_delay: None,
 				eip649_reward: None,
+				difficulty_bomb_delays: None,
 				expip2_transition: None,
 				expip2_duration_limit: None,
+				maximum_uncle_count: None,
+				maximum_uncle_age: None,
 			}
 		});
 	}
@@ -274,8 +291,11 @@ mod tests {
 				eip649_transition: None,
 				eip649_delay: None,
 				eip649_reward: None,
+				difficulty_bomb_delays: None,
 				expip2_transition: None,
 				expip2_duration_limit: None,
+				maximum_uncle_count: None,
+				maximum_uncle_age: None,
 			}
 		});
 	}
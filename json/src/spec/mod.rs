@@ -29,11 +29,12 @@ pub mod validator_set;
 pub mod basic_authority;
 pub mod authority_round;
 pub mod tendermint;
+pub mod clique;
 pub mod null_engine;
 pub mod hardcoded_sync;
 
 pub use self::account::Account;
-pub use self::builtin::{Builtin, Pricing, Linear};
+pub use self::builtin::{Builtin, Pricing, Linear, Repricing};
 pub use self::genesis::Genesis;
 pub use self::params::Params;
 pub use self::spec::Spec;
@@ -45,5 +46,6 @@ pub use self::validator_set::ValidatorSet;
 pub use self::basic_authority::{BasicAuthority, BasicAuthorityParams};
 pub use self::authority_round::{AuthorityRound, AuthorityRoundParams};
 pub use self::tendermint::{Tendermint, TendermintParams};
+pub use self::clique::{Clique, CliqueParams};
 pub use self::null_engine::{NullEngine, NullEngineParams};
 pub use self::hardcoded_sync::HardcodedSync;
@@ -55,6 +55,21 @@ pub struct Params {
 	#[serde(rename="eip150Transition")]
 	pub eip150_transition: Option<Uint>,
 
+	/// Homestead fork block. Default for the Ethash homestead transition.
+	#[serde(rename="homesteadTransition")]
+	pub homestead_transition: Option<Uint>,
+	/// Spurious Dragon fork block. Default for EIP-155, EIP-160, EIP-161 and EIP-170 transitions.
+	#[serde(rename="spuriousDragonTransition")]
+	pub spurious_dragon_transition: Option<Uint>,
+	/// Byzantium fork block. Default for EIP-140, EIP-211, EIP-214 and EIP-658 transitions,
+	/// the Ethash EIP-100b and EIP-649 transitions and activation of the Byzantium builtins.
+	#[serde(rename="byzantiumTransition")]
+	pub byzantium_transition: Option<Uint>,
+	/// Constantinople fork block. Default for EIP-145 transition. EIP-1283 was removed
+	/// by Petersburg and has to be enabled explicitly.
+	#[serde(rename="constantinopleTransition")]
+	pub constantinople_transition: Option<Uint>,
+
 	/// See main EthashParams docs.
 	#[serde(rename="eip160Transition")]
 	pub eip160_transition: Option<Uint>,
@@ -103,6 +118,33 @@ pub struct Params {
 	#[serde(rename="eip145Transition")]
 	pub eip145_transition: Option<Uint>,
 	/// See `CommonParams` docs.
+	#[serde(rename="eip1283Transition")]
+	pub eip1283_transition: Option<Uint>,
+	/// See `CommonParams` docs.
+	#[serde(rename="eip2200Transition")]
+	pub eip2200_transition: Option<Uint>,
+	/// See `CommonParams` docs.
+	#[serde(rename="eip2028Transition")]
+	pub eip2028_transition: Option<Uint>,
+	/// See `CommonParams` docs.
+	#[serde(rename="eip2930Transition")]
+	pub eip2930_transition: Option<Uint>,
+	/// See `CommonParams` docs.
+	#[serde(rename="replayProtectionTransition")]
+	pub replay_protection_transition: Option<Uint>,
+	/// See `CommonParams` docs.
+	#[serde(rename="selfdestructDisableTransition")]
+	pub selfdestruct_disable_transition: Option<Uint>,
+	/// See `CommonParams` docs.
+	#[serde(rename="selfdestructKeepsBalanceTransition")]
+	pub selfdestruct_keeps_balance_transition: Option<Uint>,
+	/// See `CommonParams` docs.
+	#[serde(rename="selfdestructRefund")]
+	pub selfdestruct_refund: Option<Uint>,
+	/// See `CommonParams` docs.
+	#[serde(rename="selfdestructRefundTransition")]
+	pub selfdestruct_refund_transition: Option<Uint>,
+	/// See `CommonParams` docs.
 	#[serde(rename="eip214Transition")]
 	pub eip214_transition: Option<Uint>,
 	/// See `CommonParams` docs.
@@ -140,6 +182,21 @@ pub struct Params {
 	/// Transaction permission contract address.
 	#[serde(rename="transactionPermissionContract")]
 	pub transaction_permission_contract: Option<Address>,
+	/// Sender certificate authority address.
+	#[serde(rename="transactionCertificateAuthority")]
+	pub transaction_certificate_authority: Option<Address>,
+	/// Service transaction certifier contract address.
+	#[serde(rename="serviceTransactionCertifier")]
+	pub service_transaction_certifier: Option<Address>,
+	/// Block at which the service transaction certifier is activated.
+	#[serde(rename="serviceTransactionCertifierTransition")]
+	pub service_transaction_certifier_transition: Option<Uint>,
+	/// Gas price the block author pays for service transactions.
+	#[serde(rename="serviceTransactionGasPrice")]
+	pub service_transaction_gas_price: Option<Uint>,
+	/// Governance contract address.
+	#[serde(rename="governanceContract")]
+	pub governance_contract: Option<Address>,
 	/// Wasm activation block height, if not activated from start
 	#[serde(rename="wasmActivationTransition")]
 	pub wasm_activation_transition: Option<Uint>,
@@ -163,7 +220,8 @@ mod tests {
 			"accountStartNonce": "0x01",
 			"gasLimitBoundDivisor": "0x20",
 			"maxCodeSize": "0x1000",
-			"wasmActivationTransition": "0x1010"
+			"wasmActivationTransition": "0x1010",
+			"byzantiumTransition": "0x2a"
 		}"#;
 
 		let deserialized: Params = serde_json::from_str(s).unwrap();
@@ -176,6 +234,7 @@ mod tests {
 		assert_eq!(deserialized.gas_limit_bound_divisor, Uint(U256::from(0x20)));
 		assert_eq!(deserialized.max_code_size, Some(Uint(U256::from(0x1000))));
 		assert_eq!(deserialized.wasm_activation_transition, Some(Uint(U256::from(0x1010))));
+		assert_eq!(deserialized.byzantium_transition, Some(Uint(U256::from(0x2a))));
 	}
 
 	#[test]
@@ -34,6 +34,14 @@ impl State {
 			.collect()
 	}
 
+	/// Returns mutable references to all builtins.
+	pub fn builtins_mut(&mut self) -> Vec<&mut Builtin> {
+		self.0
+			.values_mut()
+			.filter_map(|acc| acc.builtin.as_mut())
+			.collect()
+	}
+
 	/// Returns all constructors.
 	pub fn constructors(&self) -> BTreeMap<Address, Bytes> {
 		self.0
@@ -99,6 +99,8 @@ impl Into<TransactionCondition> for Condition {
 struct TransactionEntry {
 	rlp_bytes: Vec<u8>,
 	condition: Option<Condition>,
+	#[serde(default)]
+	local_only: bool,
 }
 
 impl TransactionEntry {
@@ -113,7 +115,11 @@ impl TransactionEntry {
 
 		let hash = tx.hash();
 		match SignedTransaction::new(tx) {
-			Ok(tx) => Some(PendingTransaction::new(tx, self.condition.map(Into::into))),
+			Ok(tx) => {
+				let mut pending = PendingTransaction::new(tx, self.condition.map(Into::into));
+				pending.local_only = self.local_only;
+				Some(pending)
+			},
 			Err(_) => {
 				warn!(target: "local_store", "Bad signature on persistent transaction: {}", hash);
 				return None
@@ -127,6 +133,7 @@ impl From<PendingTransaction> for TransactionEntry {
 		TransactionEntry {
 			rlp_bytes: ::rlp::encode(&pending.transaction).into_vec(),
 			condition: pending.condition.map(Into::into),
+			local_only: pending.local_only,
 		}
 	}
 }
@@ -272,7 +279,9 @@ mod tests {
 				_ => None,
 			};
 
-			PendingTransaction::new(signed, condition)
+			let mut pending = PendingTransaction::new(signed, condition);
+			pending.local_only = nonce == 7;
+			pending
 		}).collect();
 
 		let db = Arc::new(::kvdb_memorydb::create(0));
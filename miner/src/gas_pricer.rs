@@ -16,14 +16,18 @@
 
 //! Auto-updates minimal gas price requirement.
 
+use std::collections::VecDeque;
 use std::time::{Instant, Duration};
 
 use ansi_term::Colour;
-use ethereum_types::U256;
+use ethereum_types::{H256, U256};
 use futures_cpupool::CpuPool;
 use price_info::{Client as PriceInfoClient, PriceInfo};
 use price_info::fetch::Client as FetchClient;
 
+/// Number of recent blocks whose transactions are sampled by default, the same as `eth_gasPrice` uses.
+pub const DEFAULT_SAMPLE_BLOCKS: usize = 100;
+
 /// Options for the dynamic gas price recalibrator.
 #[derive(Debug, PartialEq)]
 pub struct GasPriceCalibratorOptions {
@@ -63,6 +67,78 @@ impl GasPriceCalibrator {
 	}
 }
 
+/// Gas prices of transactions in the recent blocks of the canonical chain.
+///
+/// Kept up to date as the chain moves, so only newly imported blocks are read.
+#[derive(Debug, PartialEq)]
+pub struct GasPriceSamples {
+	percentile: usize,
+	max_blocks: usize,
+	// hashes of sampled blocks along with the gas prices of their transactions, oldest first.
+	blocks: VecDeque<(H256, Vec<U256>)>,
+}
+
+impl GasPriceSamples {
+	/// Create samples reporting the `percentile` of the gas prices in the last `max_blocks` blocks.
+	pub fn new(percentile: usize, max_blocks: usize) -> Self {
+		assert!(percentile <= 100, "Percentile must be at most 100");
+		GasPriceSamples {
+			percentile: percentile,
+			max_blocks: max_blocks,
+			blocks: VecDeque::with_capacity(max_blocks),
+		}
+	}
+
+	/// Move the samples to the chain ending with `best_block`.
+	///
+	/// `block` returns the parent hash and the transaction gas prices of a block, `None` for the
+	/// genesis or an unknown block. Only blocks not sampled yet are looked up, blocks no longer
+	/// in the chain are dropped.
+	pub fn update<F>(&mut self, best_block: H256, mut block: F) where
+		F: FnMut(&H256) -> Option<(H256, Vec<U256>)>,
+	{
+		let mut new_blocks = Vec::new();
+		let mut hash = best_block;
+		let ancestor = loop {
+			if new_blocks.len() == self.max_blocks {
+				break None;
+			}
+			if let Some(position) = self.blocks.iter().position(|&(ref sampled, _)| *sampled == hash) {
+				break Some(position);
+			}
+			match block(&hash) {
+				Some((parent, gas_prices)) => {
+					new_blocks.push((hash, gas_prices));
+					hash = parent;
+				},
+				None => break None,
+			}
+		};
+
+		match ancestor {
+			Some(position) => self.blocks.truncate(position + 1),
+			None => self.blocks.clear(),
+		}
+		self.blocks.extend(new_blocks.into_iter().rev());
+		while self.blocks.len() > self.max_blocks {
+			self.blocks.pop_front();
+		}
+	}
+
+	/// Current gas price, `None` if there are no transactions sampled.
+	pub fn gas_price(&self) -> Option<U256> {
+		let mut prices = self.blocks.iter().flat_map(|&(_, ref prices)| prices.iter().cloned()).collect::<Vec<_>>();
+		prices.sort();
+
+		// same approximation as `stats::Corpus::percentile`.
+		let index = self.percentile * prices.len() / 100;
+		match index {
+			0 => prices.first().cloned(),
+			index => prices.get(index - 1).cloned(),
+		}
+	}
+}
+
 /// Struct to look after updating the acceptable gas price of a miner.
 #[derive(Debug, PartialEq)]
 pub enum GasPricer {
@@ -70,6 +146,8 @@ pub enum GasPricer {
 	Fixed(U256),
 	/// Gas price is calibrated according to a fixed amount of USD.
 	Calibrated(GasPriceCalibrator),
+	/// Gas price is a percentile of the gas prices of transactions in the last blocks.
+	Percentile(GasPriceSamples),
 }
 
 impl GasPricer {
@@ -87,11 +165,70 @@ impl GasPricer {
 		GasPricer::Fixed(gas_price)
 	}
 
+	/// Create a new `GasPricer` following the `percentile` of gas prices in the last `blocks` blocks.
+	pub fn new_percentile(percentile: usize, blocks: usize) -> GasPricer {
+		GasPricer::Percentile(GasPriceSamples::new(percentile, blocks))
+	}
+
 	/// Recalibrate current gas price.
-	pub fn recalibrate<F: FnOnce(U256) + Sync + Send + 'static>(&mut self, set_price: F) {
+	///
+	/// `update_samples` brings the samples of recent blocks up to date,
+	/// it's only called by the `Percentile` variant.
+	pub fn recalibrate<F, G>(&mut self, update_samples: G, set_price: F) where
+		F: FnOnce(U256) + Sync + Send + 'static,
+		G: FnOnce(&mut GasPriceSamples),
+	{
 		match *self {
 			GasPricer::Fixed(ref max) => set_price(max.clone()),
 			GasPricer::Calibrated(ref mut cal) => cal.recalibrate(set_price),
+			GasPricer::Percentile(ref mut samples) => {
+				update_samples(samples);
+				// keep the current price until there are transactions to sample.
+				if let Some(gas_price) = samples.gas_price() {
+					set_price(gas_price)
+				}
+			},
 		}
 	}
 }
+
+#[cfg(test)]
+mod tests {
+	use ethereum_types::H256;
+	use super::GasPriceSamples;
+
+	#[test]
+	fn should_sample_only_new_blocks() {
+		// blocks 1..4 with parents 0..3, block `n` has a single transaction priced `n`.
+		let block = |hash: &H256| match hash.low_u64() {
+			0 => None,
+			n => Some(((n - 1).into(), vec![n.into()])),
+		};
+		let mut samples = GasPriceSamples::new(50, 2);
+		assert_eq!(samples.gas_price(), None);
+
+		samples.update(2.into(), &block);
+		assert_eq!(samples.gas_price(), Some(1.into()));
+
+		let mut looked_up = Vec::new();
+		samples.update(4.into(), |hash| { looked_up.push(*hash); block(hash) });
+		assert_eq!(looked_up, vec![4.into(), 3.into()]);
+		assert_eq!(samples.gas_price(), Some(3.into()));
+	}
+
+	#[test]
+	fn should_drop_retracted_blocks() {
+		// block 12 is a sibling of block 2, both children of block 1.
+		let block = |hash: &H256| match hash.low_u64() {
+			0 => None,
+			12 => Some((1.into(), vec![100.into()])),
+			n => Some(((n - 1).into(), vec![n.into()])),
+		};
+		let mut samples = GasPriceSamples::new(100, 10);
+		samples.update(2.into(), &block);
+		assert_eq!(samples.gas_price(), Some(2.into()));
+
+		samples.update(12.into(), &block);
+		assert_eq!(samples.gas_price(), Some(100.into()));
+	}
+}
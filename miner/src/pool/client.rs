@@ -54,8 +54,8 @@ pub trait Client: fmt::Debug + Sync {
 	fn verify_transaction(&self, tx: transaction::UnverifiedTransaction)
 		-> Result<transaction::SignedTransaction, transaction::Error>;
 
-	/// Estimate minimal gas requirurement for given transaction.
-	fn required_gas(&self, tx: &transaction::Transaction) -> U256;
+	/// Estimate minimal gas requirurement for given transaction, including its access list.
+	fn required_gas(&self, tx: &transaction::UnverifiedTransaction) -> U256;
 
 	/// Fetch account details for given sender.
 	fn account_details(&self, address: &Address) -> AccountDetails;
@@ -73,3 +73,9 @@ pub trait NonceClient: fmt::Debug + Sync {
 	/// Fetch only account nonce for given sender.
 	fn account_nonce(&self, address: &Address) -> U256;
 }
+
+impl<'a, C: NonceClient> NonceClient for &'a C {
+	fn account_nonce(&self, address: &Address) -> U256 {
+		(**self).account_nonce(address)
+	}
+}
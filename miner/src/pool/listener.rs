@@ -14,7 +14,7 @@
 // You should have received a copy of the GNU General Public License
 // along with Parity.  If not, see <http://www.gnu.org/licenses/>.
 
-//! Notifier for new transaction hashes.
+//! Notifier for new, dropped and removed transaction hashes.
 
 use std::fmt;
 use std::sync::Arc;
@@ -25,12 +25,29 @@ use txpool::{self, VerifiedTransaction};
 use pool::VerifiedTransaction as Transaction;
 
 type Listener = Box<Fn(&[H256]) + Send + Sync>;
+type RemovedListener = Box<Fn(&[(H256, RemovalReason)]) + Send + Sync>;
 
-/// Manages notifications to pending transaction listeners.
+/// Reason of removing a transaction from the pool without it being mined.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum RemovalReason {
+	/// The transaction was waiting in the pool for too long.
+	Expired,
+	/// Another transaction with the same sender and nonce was mined.
+	Superseded,
+	/// The transaction was replaced in the pool by one with a higher gas price.
+	Replaced,
+}
+
+/// Manages notifications to pending, dropped and removed transaction listeners.
 #[derive(Default)]
 pub struct Notifier {
 	listeners: Vec<Listener>,
 	pending: Vec<H256>,
+	dropped_listeners: Vec<Listener>,
+	dropped: Vec<H256>,
+	removed_listeners: Vec<RemovedListener>,
+	removed: Vec<(H256, RemovalReason)>,
+	culled: Vec<Arc<Transaction>>,
 }
 
 impl fmt::Debug for Notifier {
@@ -38,6 +55,11 @@ impl fmt::Debug for Notifier {
 		fmt.debug_struct("Notifier")
 			.field("listeners", &self.listeners.len())
 			.field("pending", &self.pending)
+			.field("dropped_listeners", &self.dropped_listeners.len())
+			.field("dropped", &self.dropped)
+			.field("removed_listeners", &self.removed_listeners.len())
+			.field("removed", &self.removed)
+			.field("culled", &self.culled.len())
 			.finish()
 	}
 }
@@ -48,19 +70,67 @@ impl Notifier {
 		self.listeners.push(f)
 	}
 
-	/// Notify listeners about all currently pending transactions.
+	/// Add new listener to receive notifications about transactions pushed out of the pool.
+	pub fn add_dropped(&mut self, f: Listener) {
+		self.dropped_listeners.push(f)
+	}
+
+	/// Add new listener to receive notifications about transactions removed from the pool
+	/// without being mined, together with the reason of removal.
+	pub fn add_removed(&mut self, f: RemovedListener) {
+		self.removed_listeners.push(f)
+	}
+
+	/// Note a transaction removed from the pool without being mined.
+	pub fn removed(&mut self, hash: H256, reason: RemovalReason) {
+		self.removed.push((hash, reason));
+	}
+
+	/// Take transactions culled from the pool since the last call.
+	///
+	/// Culled transactions are either mined or stalled, it's up to the caller to tell them apart.
+	pub fn take_culled(&mut self) -> Vec<Arc<Transaction>> {
+		::std::mem::replace(&mut self.culled, Vec::new())
+	}
+
+	/// Notify listeners about all currently pending, dropped and removed transactions.
 	pub fn notify(&mut self) {
 		for l in &self.listeners {
 			(l)(&self.pending);
 		}
 
+		if !self.dropped.is_empty() {
+			for l in &self.dropped_listeners {
+				(l)(&self.dropped);
+			}
+		}
+
+		if !self.removed.is_empty() {
+			for l in &self.removed_listeners {
+				(l)(&self.removed);
+			}
+		}
+
 		self.pending.clear();
+		self.dropped.clear();
+		self.removed.clear();
 	}
 }
 
 impl txpool::Listener<Transaction> for Notifier {
-	fn added(&mut self, tx: &Arc<Transaction>, _old: Option<&Arc<Transaction>>) {
+	fn added(&mut self, tx: &Arc<Transaction>, old: Option<&Arc<Transaction>>) {
 		self.pending.push(*tx.hash());
+		if let Some(old) = old {
+			self.removed.push((*old.hash(), RemovalReason::Replaced));
+		}
+	}
+
+	fn dropped(&mut self, tx: &Arc<Transaction>, _new: Option<&Transaction>) {
+		self.dropped.push(*tx.hash());
+	}
+
+	fn mined(&mut self, tx: &Arc<Transaction>) {
+		self.culled.push(tx.clone());
 	}
 }
 
@@ -146,6 +216,59 @@ mod tests {
 		);
 	}
 
+	#[test]
+	fn should_notify_dropped_listeners() {
+		// given
+		let received = Arc::new(Mutex::new(vec![]));
+		let r = received.clone();
+		let listener = Box::new(move |hashes: &[H256]| {
+			r.lock().extend_from_slice(hashes);
+		});
+
+		let mut tx_listener = Notifier::default();
+		tx_listener.add_dropped(listener);
+
+		// when
+		let tx = new_tx();
+		tx_listener.added(&tx, None);
+		tx_listener.notify();
+		assert_eq!(*received.lock(), vec![]);
+		tx_listener.dropped(&tx, None);
+
+		// then
+		tx_listener.notify();
+		assert_eq!(*received.lock(), vec![*tx.hash()]);
+		tx_listener.notify();
+		assert_eq!(received.lock().len(), 1);
+	}
+
+	#[test]
+	fn should_notify_removed_listeners() {
+		// given
+		let received = Arc::new(Mutex::new(vec![]));
+		let r = received.clone();
+		let listener = Box::new(move |removed: &[(H256, RemovalReason)]| {
+			r.lock().extend_from_slice(removed);
+		});
+
+		let mut tx_listener = Notifier::default();
+		tx_listener.add_removed(listener);
+
+		// when
+		let tx = new_tx();
+		let tx2 = new_tx();
+		tx_listener.dropped(&tx, None);
+		tx_listener.mined(&tx);
+		tx_listener.added(&tx2, Some(&tx));
+		tx_listener.removed(*tx2.hash(), RemovalReason::Expired);
+
+		// then
+		tx_listener.notify();
+		assert_eq!(*received.lock(), vec![(*tx.hash(), RemovalReason::Replaced), (*tx2.hash(), RemovalReason::Expired)]);
+		assert_eq!(tx_listener.take_culled().len(), 1);
+		assert!(tx_listener.take_culled().is_empty());
+	}
+
 	fn new_tx() -> Arc<Transaction> {
 		let signed = transaction::Transaction {
 			action: transaction::Action::Create,
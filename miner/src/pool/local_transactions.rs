@@ -32,8 +32,8 @@ pub enum Status {
 	Pending(Arc<Transaction>),
 	/// Transaction is already mined.
 	Mined(Arc<Transaction>),
-	/// Transaction is dropped because of limit
-	Dropped(Arc<Transaction>),
+	/// Transaction is dropped because of limit, with the reason.
+	Dropped(Arc<Transaction>, String),
 	/// Replaced because of higher gas price of another transaction.
 	Replaced {
 		/// Replaced transaction
@@ -162,11 +162,17 @@ impl txpool::Listener<Transaction> for LocalTransactionsList {
 			return;
 		}
 
-		match new {
-			Some(new) => warn!(target: "own_tx", "Transaction pushed out because of limit (hash {:?}, replacement: {:?})", tx.hash(), new.hash()),
-			None => warn!(target: "own_tx", "Transaction dropped because of limit (hash: {:?})", tx.hash()),
-		}
-		self.insert(*tx.hash(), Status::Dropped(tx.clone()));
+		let reason = match new {
+			Some(new) => {
+				warn!(target: "own_tx", "Transaction pushed out because of limit (hash {:?}, replacement: {:?})", tx.hash(), new.hash());
+				format!("Pushed out of the queue by {:?}", new.hash())
+			},
+			None => {
+				warn!(target: "own_tx", "Transaction dropped because of limit (hash: {:?})", tx.hash());
+				"Dropped because of the queue limit".into()
+			},
+		};
+		self.insert(*tx.hash(), Status::Dropped(tx.clone(), reason));
 		self.clear_old();
 	}
 
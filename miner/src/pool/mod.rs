@@ -16,12 +16,15 @@
 
 //! Transaction Pool
 
+use std::time::Instant;
+
 use ethereum_types::{H256, Address};
 use heapsize::HeapSizeOf;
 use transaction;
 use txpool;
 
 mod listener;
+mod propagation;
 mod queue;
 mod ready;
 mod scoring;
@@ -33,7 +36,10 @@ pub mod verifier;
 #[cfg(test)]
 mod tests;
 
+pub use self::listener::RemovalReason;
+pub use self::propagation::{ShouldPropagate, GasPriceFloor};
 pub use self::queue::{TransactionQueue, Status as QueueStatus};
+pub use self::scoring::DEFAULT_GAS_PRICE_BUMP;
 pub use self::txpool::{VerifiedTransaction as PoolVerifiedTransaction, Options};
 
 /// How to prioritize transactions in the pool
@@ -80,6 +86,7 @@ pub struct VerifiedTransaction {
 	sender: Address,
 	priority: Priority,
 	insertion_id: usize,
+	insertion_time: Instant,
 }
 
 impl VerifiedTransaction {
@@ -97,6 +104,7 @@ impl VerifiedTransaction {
 			sender,
 			priority: Priority::Retracted,
 			insertion_id: 0,
+			insertion_time: Instant::now(),
 		}
 	}
 
@@ -105,11 +113,21 @@ impl VerifiedTransaction {
 		self.priority
 	}
 
+	/// Whether the transaction was submitted locally.
+	pub fn is_local(&self) -> bool {
+		self.priority.is_local()
+	}
+
 	/// Gets transaction insertion id.
 	pub(crate) fn insertion_id(&self) -> usize {
 		self.insertion_id
 	}
 
+	/// Gets the time the transaction was verified for insertion.
+	pub(crate) fn insertion_time(&self) -> Instant {
+		self.insertion_time
+	}
+
 	/// Gets wrapped `SignedTransaction`
 	pub fn signed(&self) -> &transaction::SignedTransaction {
 		&self.transaction
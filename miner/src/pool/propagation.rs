@@ -0,0 +1,56 @@
+// Copyright 2015-2018 Parity Technologies (UK) Ltd.
+// This file is part of Parity.
+
+// Parity is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// Parity is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with Parity.  If not, see <http://www.gnu.org/licenses/>.
+
+//! Filters of the transactions gossiped to peers.
+//!
+//! Filtered transactions are still accepted and kept in the pool, they are only
+//! left out of propagation.
+
+use ethereum_types::U256;
+
+use super::VerifiedTransaction;
+
+/// Decides whether a pooled transaction is propagated to peers.
+pub trait ShouldPropagate: Send + Sync {
+	/// Whether to propagate the transaction.
+	fn should_propagate(&self, tx: &VerifiedTransaction) -> bool;
+}
+
+/// Propagates only transactions paying at least the given gas price, local transactions are always propagated.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct GasPriceFloor(pub U256);
+
+impl ShouldPropagate for GasPriceFloor {
+	fn should_propagate(&self, tx: &VerifiedTransaction) -> bool {
+		tx.is_local() || tx.signed().gas_price >= self.0
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use pool::tests::tx::{Tx, TxExt};
+
+	#[test]
+	fn should_filter_by_gas_price_floor() {
+		let cheap = Tx::gas_price(1).signed().verified();
+		let expensive = Tx::gas_price(3).signed().verified();
+		let floor = GasPriceFloor(2.into());
+
+		assert!(!floor.should_propagate(&cheap));
+		assert!(floor.should_propagate(&expensive));
+	}
+}
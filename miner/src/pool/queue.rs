@@ -18,8 +18,9 @@
 
 use std::{cmp, fmt};
 use std::sync::Arc;
-use std::sync::atomic::{self, AtomicUsize};
-use std::collections::BTreeMap;
+use std::sync::atomic::{self, AtomicBool, AtomicUsize};
+use std::collections::{BTreeMap, HashSet};
+use std::time::{Duration, Instant};
 
 use ethereum_types::{H256, U256, Address};
 use parking_lot::RwLock;
@@ -41,6 +42,12 @@ type Pool = txpool::Pool<pool::VerifiedTransaction, scoring::NonceAndGasPrice, L
 /// since it only affects transaction Condition.
 const TIMESTAMP_CACHE: u64 = 1000;
 
+/// Relative change of the verification options which requires re-verification of queued transactions.
+///
+/// Queued transactions are re-verified only if the minimal gas price rises or the gas limits drop
+/// by more than `1/REVALIDATION_TOLERANCE` since the last re-verification.
+const REVALIDATION_TOLERANCE: u64 = 8;
+
 /// Transaction queue status.
 #[derive(Debug, Clone, PartialEq)]
 pub struct Status {
@@ -140,6 +147,8 @@ pub struct TransactionQueue {
 	pool: RwLock<Pool>,
 	options: RwLock<verifier::Options>,
 	cached_pending: RwLock<CachedPending>,
+	needs_revalidation: AtomicBool,
+	revalidated_options: RwLock<verifier::Options>,
 }
 
 impl TransactionQueue {
@@ -148,20 +157,85 @@ impl TransactionQueue {
 		limits: txpool::Options,
 		verification_options: verifier::Options,
 		strategy: PrioritizationStrategy,
+	) -> Self {
+		Self::with_gas_price_bump(limits, verification_options, strategy, scoring::DEFAULT_GAS_PRICE_BUMP)
+	}
+
+	/// Create new queue replacing transactions only if the gas price is bumped by at least
+	/// `gas_price_bump` thousandths of the replaced transaction's gas price.
+	pub fn with_gas_price_bump(
+		limits: txpool::Options,
+		verification_options: verifier::Options,
+		strategy: PrioritizationStrategy,
+		gas_price_bump: u32,
 	) -> Self {
 		TransactionQueue {
 			insertion_id: Default::default(),
-			pool: RwLock::new(txpool::Pool::new(Default::default(), scoring::NonceAndGasPrice(strategy), limits)),
-			options: RwLock::new(verification_options),
+			pool: RwLock::new(txpool::Pool::new(Default::default(), scoring::NonceAndGasPrice::new(strategy, gas_price_bump), limits)),
+			options: RwLock::new(verification_options.clone()),
 			cached_pending: RwLock::new(CachedPending::none()),
+			needs_revalidation: AtomicBool::new(false),
+			revalidated_options: RwLock::new(verification_options),
 		}
 	}
 
 	/// Update verification options
 	///
 	/// Some parameters of verification may vary in time (like block gas limit or minimal gas price).
+	/// If the new options are stricter than the ones queued transactions were last re-verified with
+	/// by more than `1/REVALIDATION_TOLERANCE`, they are re-verified on the next `revalidate`.
+	/// Small drifts of the block gas limit or the minimal gas price don't cause re-verification.
 	pub fn set_verifier_options(&self, options: verifier::Options) {
-		*self.options.write() = options;
+		let mut current = self.options.write();
+		if is_stricter_beyond_tolerance(&self.revalidated_options.read(), &options) {
+			self.request_revalidation();
+		}
+		*current = options;
+	}
+
+	/// Re-verify queued transactions on the next `revalidate`, e.g. because the intrinsic gas changed at a fork.
+	pub fn request_revalidation(&self) {
+		self.needs_revalidation.store(true, atomic::Ordering::Release);
+	}
+
+	/// Returns true if queued transactions should be re-verified.
+	pub fn needs_revalidation(&self) -> bool {
+		self.needs_revalidation.load(atomic::Ordering::Acquire)
+	}
+
+	/// Removes non-local transactions which no longer pass the gas checks of the verifier,
+	/// if re-verification was requested. Returns the number of removed transactions.
+	///
+	/// Scans the whole pool, so it's meant to be run in the background.
+	/// `required_gas` estimates the intrinsic gas of a transaction.
+	pub fn revalidate<F>(&self, required_gas: F) -> usize where
+		F: Fn(&transaction::UnverifiedTransaction) -> U256,
+	{
+		if !self.needs_revalidation.swap(false, atomic::Ordering::AcqRel) {
+			return 0;
+		}
+
+		let options = self.options.read().clone();
+		*self.revalidated_options.write() = options.clone();
+		let gas_limit = cmp::min(options.tx_gas_limit, options.block_gas_limit);
+		// transactions paying slightly less than the raised floor are kept, they are likely to be mined soon anyway.
+		let minimal_gas_price = options.minimal_gas_price - options.minimal_gas_price / U256::from(REVALIDATION_TOLERANCE);
+		let invalid = self.all_transactions()
+			.into_iter()
+			.filter(|tx| !tx.is_local())
+			.filter(|tx| {
+				let signed = tx.signed();
+				// zero gas price service transactions are exempt from the minimal gas price, like in the verifier.
+				signed.gas > gas_limit
+					|| signed.gas < required_gas(signed)
+					|| (signed.gas_price < minimal_gas_price && !signed.gas_price.is_zero())
+			})
+			.map(|tx| tx.signed().hash())
+			.collect::<Vec<_>>();
+
+		self.remove(invalid.iter(), true);
+		debug!(target: "txqueue", "Removed {} transactions failing re-verification. {}", invalid.len(), self.status());
+		invalid.len()
 	}
 
 	/// Import a set of transactions to the pool.
@@ -188,7 +262,7 @@ impl TransactionQueue {
 			}))
 			.collect::<Vec<_>>();
 
-		// Notify about imported transactions.
+		// Notify about imported and dropped transactions.
 		(self.pool.write().listener_mut().1).0.notify();
 
 		if results.iter().any(|r| r.is_ok()) {
@@ -204,6 +278,44 @@ impl TransactionQueue {
 		self.pool.read().pending(ready).collect()
 	}
 
+	/// Returns all transactions of given sender in the queue, ordered by nonce.
+	pub fn pending_by_sender(&self, address: &Address) -> Vec<Arc<pool::VerifiedTransaction>> {
+		let ready = |_tx: &pool::VerifiedTransaction| txpool::Readiness::Ready;
+		self.pool.read().pending_from_sender(ready, address).collect()
+	}
+
+	/// Returns pending transactions with gas at most `block_gas_limit` paying at least `min_gas_price`.
+	///
+	/// Once a transaction of a sender is left out, the following ones of that sender are as well,
+	/// since they can't be included before it.
+	pub fn pending_within_limits<C>(
+		&self,
+		client: C,
+		block_number: u64,
+		current_timestamp: u64,
+		block_gas_limit: U256,
+		min_gas_price: U256,
+	) -> Vec<Arc<pool::VerifiedTransaction>> where
+		C: client::NonceClient,
+	{
+		let mut skipped = HashSet::new();
+		self.pending(client, block_number, current_timestamp, None)
+			.into_iter()
+			.filter(|tx| {
+				let sender = tx.signed().sender();
+				if skipped.contains(&sender) {
+					return false;
+				}
+
+				let fits = tx.signed().gas <= block_gas_limit && tx.signed().gas_price >= min_gas_price;
+				if !fits {
+					skipped.insert(sender);
+				}
+				fits
+			})
+			.collect()
+	}
+
 	/// Returns current pneding transactions.
 	///
 	/// NOTE: This may return a cached version of pending transaction set.
@@ -273,10 +385,38 @@ impl TransactionQueue {
 	}
 
 	/// Culls all stalled transactions from the pool.
-	pub fn cull<C: client::NonceClient>(
+	pub fn cull<C: client::NonceClient + client::Client>(
 		&self,
 		client: C,
 	) {
+		self.cull_with_max_age(client, None, &HashSet::new())
+	}
+
+	/// Culls all stalled transactions from the pool, as well as non-local transactions
+	/// waiting in the pool for longer than `max_age`.
+	///
+	/// Transactions removed without being mined are reported to the removed transactions listeners.
+	/// `mined` are hashes of recently mined transactions, e.g. of the enacted blocks, which are
+	/// known not to be reported without looking them up in the chain.
+	pub fn cull_with_max_age<C: client::NonceClient + client::Client>(
+		&self,
+		client: C,
+		max_age: Option<Duration>,
+		mined: &HashSet<H256>,
+	) {
+		let expired = match max_age {
+			Some(max_age) => {
+				let now = Instant::now();
+				self.all_transactions()
+					.into_iter()
+					// Local transactions are kept, they are rebroadcast until mined.
+					.filter(|tx| !tx.is_local() && now.duration_since(tx.insertion_time()) > max_age)
+					.map(|tx| *tx.hash())
+					.collect()
+			},
+			None => Vec::new(),
+		};
+
 		// We don't care about future transactions, so nonce_cap is not important.
 		let nonce_cap = None;
 		// We want to clear stale transactions from the queue as well.
@@ -291,10 +431,45 @@ impl TransactionQueue {
 			current_id.checked_sub(gap)
 		};
 
-		let state_readiness = ready::State::new(client, stale_id, nonce_cap);
+		let state_readiness = ready::State::new(&client, stale_id, nonce_cap);
+
+		let (removed, culled) = {
+			let mut pool = self.pool.write();
+			for hash in &expired {
+				pool.remove(hash, false);
+			}
+			let removed = pool.cull(None, state_readiness);
+			let culled = (pool.listener_mut().1).0.take_culled();
+			(removed, culled)
+		};
+
+		// Mined transactions are not reported, only the ones that will never make it to the chain.
+		// Classified without holding the pool lock, it may require chain lookups.
+		let reasons = culled.into_iter()
+			.filter_map(|tx| {
+				if tx.signed().nonce >= client.account_nonce(tx.sender()) {
+					// a future transaction waiting for the nonce gap to be filled for too long
+					Some((*tx.hash(), listener::RemovalReason::Expired))
+				} else if !mined.contains(tx.hash()) && !client.transaction_already_included(tx.hash()) {
+					Some((*tx.hash(), listener::RemovalReason::Superseded))
+				} else {
+					None
+				}
+			})
+			.collect::<Vec<_>>();
 
-		let removed = self.pool.write().cull(None, state_readiness);
-		debug!(target: "txqueue", "Removed {} stalled transactions. {}", removed, self.status());
+		{
+			let mut pool = self.pool.write();
+			let notifier = &mut (pool.listener_mut().1).0;
+			for hash in &expired {
+				notifier.removed(*hash, listener::RemovalReason::Expired);
+			}
+			for (hash, reason) in reasons {
+				notifier.removed(hash, reason);
+			}
+			notifier.notify();
+		}
+		debug!(target: "txqueue", "Removed {} expired and {} stalled transactions. {}", expired.len(), removed, self.status());
 	}
 
 	/// Returns next valid nonce for given sender
@@ -410,6 +585,18 @@ impl TransactionQueue {
 		let mut pool = self.pool.write();
 		(pool.listener_mut().1).0.add(f);
 	}
+
+	/// Add a callback to be notified about transactions pushed out of the pool by the limits.
+	pub fn add_dropped_listener(&self, f: Box<Fn(&[H256]) + Send + Sync>) {
+		let mut pool = self.pool.write();
+		(pool.listener_mut().1).0.add_dropped(f);
+	}
+
+	/// Add a callback to be notified about transactions removed from the pool without being mined.
+	pub fn add_removed_listener(&self, f: Box<Fn(&[(H256, pool::RemovalReason)]) + Send + Sync>) {
+		let mut pool = self.pool.write();
+		(pool.listener_mut().1).0.add_removed(f);
+	}
 }
 
 
@@ -427,6 +614,15 @@ fn convert_error(err: txpool::Error) -> transaction::Error {
 	}
 }
 
+// whether `new` options are stricter than `old` by more than `1/REVALIDATION_TOLERANCE`.
+fn is_stricter_beyond_tolerance(old: &verifier::Options, new: &verifier::Options) -> bool {
+	let exceeds = |from: U256, to: U256| to > from && to - from > from / U256::from(REVALIDATION_TOLERANCE);
+
+	exceeds(old.minimal_gas_price, new.minimal_gas_price)
+		|| exceeds(new.block_gas_limit, old.block_gas_limit)
+		|| exceeds(new.tx_gas_limit, old.tx_gas_limit)
+}
+
 #[cfg(test)]
 mod tests {
 	use super::*;
@@ -33,16 +33,31 @@ use ethereum_types::U256;
 use txpool;
 use super::{PrioritizationStrategy, VerifiedTransaction};
 
-/// Transaction with the same (sender, nonce) can be replaced only if
-/// `new_gas_price > old_gas_price + old_gas_price >> SHIFT`
-const GAS_PRICE_BUMP_SHIFT: usize = 3; // 2 = 25%, 3 = 12.5%, 4 = 6.25%
+/// Default gas price bump required to replace a transaction, in thousandths: 12.5%.
+pub const DEFAULT_GAS_PRICE_BUMP: u32 = 125;
 
 /// Simple, gas-price based scoring for transactions.
 ///
 /// NOTE: Currently penalization does not apply to new transactions that enter the pool.
 /// We might want to store penalization status in some persistent state.
 #[derive(Debug)]
-pub struct NonceAndGasPrice(pub PrioritizationStrategy);
+pub struct NonceAndGasPrice {
+	/// Prioritization strategy.
+	pub strategy: PrioritizationStrategy,
+	/// Gas price bump required to replace a transaction, in thousandths.
+	pub gas_price_bump: u32,
+}
+
+impl NonceAndGasPrice {
+	/// Create new scoring. Transaction with the same (sender, nonce) can be replaced only if
+	/// `new_gas_price >= old_gas_price + old_gas_price * gas_price_bump / 1000`
+	pub fn new(strategy: PrioritizationStrategy, gas_price_bump: u32) -> Self {
+		NonceAndGasPrice {
+			strategy,
+			gas_price_bump,
+		}
+	}
+}
 
 impl txpool::Scoring<VerifiedTransaction> for NonceAndGasPrice {
 	type Score = U256;
@@ -60,7 +75,12 @@ impl txpool::Scoring<VerifiedTransaction> for NonceAndGasPrice {
 		let old_gp = old.transaction.gas_price;
 		let new_gp = new.transaction.gas_price;
 
-		let min_required_gp = old_gp + (old_gp >> GAS_PRICE_BUMP_SHIFT);
+		// `old_gp + old_gp * bump / 1000`, without overflowing.
+		let bump = U256::from(self.gas_price_bump);
+		let thousand = U256::from(1000);
+		let min_required_gp = old_gp
+			.saturating_add((old_gp / thousand).saturating_mul(bump))
+			.saturating_add(old_gp % thousand * bump / thousand);
 
 		match min_required_gp.cmp(&new_gp) {
 			cmp::Ordering::Greater => txpool::scoring::Choice::RejectNew,
@@ -122,7 +142,7 @@ mod tests {
 	#[test]
 	fn should_calculate_score_correctly() {
 		// given
-		let scoring = NonceAndGasPrice(PrioritizationStrategy::GasPriceOnly);
+		let scoring = NonceAndGasPrice::new(PrioritizationStrategy::GasPriceOnly, DEFAULT_GAS_PRICE_BUMP);
 		let (tx1, tx2, tx3) = Tx::default().signed_triple();
 		let transactions = vec![tx1, tx2, tx3].into_iter().enumerate().map(|(i, tx)| {
 			let mut verified = tx.verified();
@@ -171,4 +191,29 @@ mod tests {
 		scoring.update_scores(&transactions, &mut *scores, txpool::scoring::Change::Event(()));
 		assert_eq!(scores, vec![32768.into(), 128.into(), 0.into()]);
 	}
+
+	#[test]
+	fn should_require_configured_gas_price_bump() {
+		use ethkey::{Random, Generator};
+		use txpool::scoring::Choice;
+
+		let replacement = |old: u64, new: u64| {
+			let keypair = Random.generate().unwrap();
+			let old = Tx::gas_price(old).unsigned().sign(keypair.secret(), None).verified();
+			let new = Tx::gas_price(new).unsigned().sign(keypair.secret(), None).verified();
+			(old, new)
+		};
+
+		let scoring = NonceAndGasPrice::new(PrioritizationStrategy::GasPriceOnly, DEFAULT_GAS_PRICE_BUMP);
+		let (old, new) = replacement(100, 112);
+		assert_eq!(scoring.choose(&old, &new), Choice::ReplaceOld);
+		let (old, new) = replacement(100, 111);
+		assert_eq!(scoring.choose(&old, &new), Choice::RejectNew);
+
+		let scoring = NonceAndGasPrice::new(PrioritizationStrategy::GasPriceOnly, 500);
+		let (old, new) = replacement(100, 149);
+		assert_eq!(scoring.choose(&old, &new), Choice::RejectNew);
+		let (old, new) = replacement(100, 150);
+		assert_eq!(scoring.choose(&old, &new), Choice::ReplaceOld);
+	}
 }
@@ -14,9 +14,11 @@
 // You should have received a copy of the GNU General Public License
 // along with Parity.  If not, see <http://www.gnu.org/licenses/>.
 
+use std::time::Instant;
+
 use ethereum_types::{U256, H256, Address};
 use rlp::Rlp;
-use transaction::{self, Transaction, SignedTransaction, UnverifiedTransaction};
+use transaction::{self, SignedTransaction, UnverifiedTransaction};
 
 use pool;
 use pool::client::AccountDetails;
@@ -30,6 +32,7 @@ pub struct TestClient {
 	is_service_transaction: bool,
 	local_address: Address,
 	max_transaction_size: usize,
+	included: Vec<H256>,
 }
 
 impl Default for TestClient {
@@ -44,6 +47,7 @@ impl Default for TestClient {
 			is_service_transaction: false,
 			local_address: Default::default(),
 			max_transaction_size: MAX_TRANSACTION_SIZE,
+			included: Vec::new(),
 		}
 	}
 }
@@ -73,6 +77,11 @@ impl TestClient {
 		self
 	}
 
+	pub fn with_included(mut self, hash: H256) -> Self {
+		self.included.push(hash);
+		self
+	}
+
 	pub fn with_service_transaction(mut self) -> Self {
 		self.is_service_transaction = true;
 		self
@@ -86,13 +95,14 @@ impl TestClient {
 			priority: pool::Priority::Regular,
 			transaction: tx,
 			insertion_id: 1,
+			insertion_time: Instant::now(),
 		}
 	}
 }
 
 impl pool::client::Client for TestClient {
-	fn transaction_already_included(&self, _hash: &H256) -> bool {
-		false
+	fn transaction_already_included(&self, hash: &H256) -> bool {
+		self.included.contains(hash)
 	}
 
 	fn verify_transaction(&self, tx: UnverifiedTransaction)
@@ -110,7 +120,7 @@ impl pool::client::Client for TestClient {
 		details
 	}
 
-	fn required_gas(&self, _tx: &Transaction) -> U256 {
+	fn required_gas(&self, _tx: &UnverifiedTransaction) -> U256 {
 		self.gas_required
 	}
 
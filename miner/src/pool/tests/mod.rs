@@ -14,11 +14,15 @@
 // You should have received a copy of the GNU General Public License
 // along with Parity.  If not, see <http://www.gnu.org/licenses/>.
 
+use std::sync::Arc;
+use std::time::Duration;
+
 use ethereum_types::U256;
+use parking_lot::Mutex;
 use transaction::{self, PendingTransaction};
 use txpool;
 
-use pool::{verifier, TransactionQueue, PrioritizationStrategy};
+use pool::{verifier, TransactionQueue, PrioritizationStrategy, RemovalReason};
 
 pub mod tx;
 pub mod client;
@@ -332,6 +336,93 @@ fn should_correctly_update_futures_when_removing() {
 	assert_eq!(txq.status().status.transaction_count, 0);
 }
 
+#[test]
+fn should_query_transactions_by_sender_and_price() {
+	// given
+	let txq = new_queue();
+	let (tx1, tx2) = Tx::gas_price(1).signed_pair();
+	let tx3 = Tx::gas_price(5).signed();
+	let sender = tx1.sender();
+
+	let res = txq.import(TestClient::new(), vec![tx2, tx1, tx3].unverified());
+	assert_eq!(res, vec![Ok(()), Ok(()), Ok(())]);
+
+	// when
+	let by_sender = txq.pending_by_sender(&sender);
+	let ready = txq.pending_within_limits(TestClient::new(), 0, 0, 1_000_000.into(), 2.into());
+
+	// then
+	assert_eq!(by_sender.iter().map(|tx| tx.signed().nonce).collect::<Vec<_>>(), vec![123.into(), 124.into()]);
+	assert_eq!(ready.len(), 1);
+	assert_eq!(ready[0].signed().gas_price, 5.into());
+}
+
+#[test]
+fn should_drop_transactions_below_raised_gas_price_floor() {
+	// given
+	let txq = new_queue();
+	let cheap = Tx::gas_price(2).signed();
+	let cheap_hash = cheap.hash();
+	let expensive = Tx::gas_price(5).signed();
+	let local = Tx::gas_price(2).signed();
+
+	let res = txq.import(TestClient::new(), vec![cheap.unverified(), expensive.unverified(), local.local()]);
+	assert_eq!(res, vec![Ok(()), Ok(()), Ok(())]);
+	assert_eq!(txq.revalidate(|_| 21_000.into()), 0);
+
+	// when
+	txq.set_verifier_options(verifier::Options {
+		minimal_gas_price: 3.into(),
+		block_gas_limit: 1_000_000.into(),
+		tx_gas_limit: 1_000_000.into(),
+	});
+	let removed = txq.revalidate(|_| 21_000.into());
+
+	// then
+	assert_eq!(removed, 1);
+	assert_eq!(txq.status().status.transaction_count, 2);
+	assert!(txq.find(&cheap_hash).is_none());
+}
+
+#[test]
+fn should_not_revalidate_on_small_changes_of_verifier_options() {
+	// given
+	let txq = new_queue();
+	txq.set_verifier_options(verifier::Options {
+		minimal_gas_price: 80.into(),
+		block_gas_limit: 1_000_000.into(),
+		tx_gas_limit: 1_000_000.into(),
+	});
+	let tx = Tx::gas_price(80).signed();
+	let res = txq.import(TestClient::new(), vec![tx.unverified()]);
+	assert_eq!(res, vec![Ok(())]);
+	assert_eq!(txq.revalidate(|_| 21_000.into()), 0);
+
+	// when
+	txq.set_verifier_options(verifier::Options {
+		minimal_gas_price: 85.into(),
+		block_gas_limit: 999_000.into(),
+		tx_gas_limit: 1_000_000.into(),
+	});
+
+	// then
+	assert!(!txq.needs_revalidation());
+	assert_eq!(txq.status().status.transaction_count, 1);
+
+	// when
+	txq.set_verifier_options(verifier::Options {
+		minimal_gas_price: 91.into(),
+		block_gas_limit: 999_000.into(),
+		tx_gas_limit: 1_000_000.into(),
+	});
+
+	// then
+	assert!(txq.needs_revalidation());
+	// the transaction pays within the tolerance of the raised floor
+	assert_eq!(txq.revalidate(|_| 21_000.into()), 0);
+	assert_eq!(txq.status().status.transaction_count, 1);
+}
+
 #[test]
 fn should_move_transactions_if_gap_filled() {
 	// given
@@ -352,6 +443,95 @@ fn should_move_transactions_if_gap_filled() {
 	assert_eq!(txq.pending(TestClient::new(), 0, 0, None).len(), 3);
 }
 
+#[test]
+fn should_cull_expired_transactions_and_notify() {
+	// given
+	let txq = new_queue();
+	let removed = Arc::new(Mutex::new(vec![]));
+	let r = removed.clone();
+	txq.add_removed_listener(Box::new(move |removed| r.lock().extend_from_slice(removed)));
+
+	let (tx, tx2) = Tx::default().signed_pair();
+	let hash = tx.hash();
+	let res = txq.import(TestClient::new(), vec![tx.unverified(), tx2.local()]);
+	assert_eq!(res, vec![Ok(()), Ok(())]);
+	::std::thread::sleep(Duration::from_millis(10));
+
+	// when
+	txq.cull_with_max_age(TestClient::new(), Some(Duration::from_secs(60)), &Default::default());
+	assert_eq!(txq.status().status.transaction_count, 2);
+	txq.cull_with_max_age(TestClient::new(), Some(Duration::from_millis(5)), &Default::default());
+
+	// then
+	assert_eq!(txq.status().status.transaction_count, 1);
+	assert_eq!(*removed.lock(), vec![(hash, RemovalReason::Expired)]);
+}
+
+#[test]
+fn should_notify_about_superseded_but_not_mined_transactions() {
+	// given
+	let txq = new_queue();
+	let removed = Arc::new(Mutex::new(vec![]));
+	let r = removed.clone();
+	txq.add_removed_listener(Box::new(move |removed| r.lock().extend_from_slice(removed)));
+
+	let (tx, tx2) = Tx::default().signed_pair();
+	let (hash, hash2) = (tx.hash(), tx2.hash());
+	let res = txq.import(TestClient::new(), vec![tx, tx2].local());
+	assert_eq!(res, vec![Ok(()), Ok(())]);
+
+	// when
+	txq.cull(TestClient::new().with_nonce(125).with_included(hash));
+
+	// then
+	assert_eq!(txq.status().status.transaction_count, 0);
+	assert_eq!(*removed.lock(), vec![(hash2, RemovalReason::Superseded)]);
+}
+
+#[test]
+fn should_not_look_up_transactions_known_to_be_mined() {
+	// given
+	let txq = new_queue();
+	let removed = Arc::new(Mutex::new(vec![]));
+	let r = removed.clone();
+	txq.add_removed_listener(Box::new(move |removed| r.lock().extend_from_slice(removed)));
+
+	let (tx, tx2) = Tx::default().signed_pair();
+	let (hash, hash2) = (tx.hash(), tx2.hash());
+	let res = txq.import(TestClient::new(), vec![tx, tx2].local());
+	assert_eq!(res, vec![Ok(()), Ok(())]);
+
+	// when
+	let mined = vec![hash].into_iter().collect();
+	txq.cull_with_max_age(TestClient::new().with_nonce(125), None, &mined);
+
+	// then
+	assert_eq!(txq.status().status.transaction_count, 0);
+	assert_eq!(*removed.lock(), vec![(hash2, RemovalReason::Superseded)]);
+}
+
+#[test]
+fn should_notify_about_replaced_transactions() {
+	// given
+	let txq = new_queue();
+	let removed = Arc::new(Mutex::new(vec![]));
+	let r = removed.clone();
+	txq.add_removed_listener(Box::new(move |removed| r.lock().extend_from_slice(removed)));
+
+	let (tx, tx2) = Tx::gas_price(1).signed_replacement();
+	let hash = tx.hash();
+	let client = TestClient::new().with_balance(1_000_000);
+	let res = txq.import(client.clone(), vec![tx, tx2].unverified());
+	assert_eq!(res, vec![Ok(()), Ok(())]);
+
+	// when
+	txq.cull(client);
+
+	// then
+	assert_eq!(txq.status().status.transaction_count, 1);
+	assert_eq!(*removed.lock(), vec![(hash, RemovalReason::Replaced)]);
+}
+
 #[test]
 fn should_remove_transaction() {
 	// given
@@ -25,6 +25,7 @@
 use std::cmp;
 use std::sync::Arc;
 use std::sync::atomic::{self, AtomicUsize};
+use std::time::Instant;
 
 use ethereum_types::{U256, H256};
 use rlp::Encodable;
@@ -108,6 +109,14 @@ impl Transaction {
 		}
 	}
 
+	fn unverified(&self) -> &transaction::UnverifiedTransaction {
+		match *self {
+			Transaction::Unverified(ref tx) => tx,
+			Transaction::Retracted(ref tx) => tx,
+			Transaction::Local(ref tx) => &*tx,
+		}
+	}
+
 	fn is_local(&self) -> bool {
 		match *self {
 			Transaction::Local(..) => true,
@@ -175,7 +184,7 @@ impl<C: Client> txpool::Verifier<Transaction> for Verifier<C> {
 			});
 		}
 
-		let minimal_gas = self.client.required_gas(tx.transaction());
+		let minimal_gas = self.client.required_gas(tx.unverified());
 		if tx.gas() < &minimal_gas {
 			trace!(target: "txqueue",
 				"[{:?}] Dropping transaction with insufficient gas: {} < {}",
@@ -290,6 +299,7 @@ impl<C: Client> txpool::Verifier<Transaction> for Verifier<C> {
 			hash,
 			sender,
 			insertion_id: self.id.fetch_add(1, atomic::Ordering::AcqRel),
+			insertion_time: Instant::now(),
 		})
 	}
 }
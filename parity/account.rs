@@ -14,12 +14,12 @@
 // You should have received a copy of the GNU General Public License
 // along with Parity.  If not, see <http://www.gnu.org/licenses/>.
 
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
 use ethcore::ethstore::{EthStore, SecretStore, import_account, import_accounts, read_geth_accounts};
 use ethcore::ethstore::accounts_dir::RootDiskDirectory;
 use ethcore::ethstore::SecretVaultRef;
 use ethcore::account_provider::{AccountProvider, AccountProviderSettings};
-use helpers::{password_prompt, password_from_file};
+use helpers::{password_prompt, password_from_file, keys_iterations};
 use params::SpecType;
 
 #[derive(Debug, PartialEq)]
@@ -38,7 +38,8 @@ pub struct ListAccounts {
 
 #[derive(Debug, PartialEq)]
 pub struct NewAccount {
-	pub iterations: u32,
+	pub iterations: Option<u32>,
+	pub base_path: String,
 	pub path: String,
 	pub spec: SpecType,
 	pub password_file: Option<String>,
@@ -91,7 +92,8 @@ fn new(n: NewAccount) -> Result<String, String> {
 	};
 
 	let dir = Box::new(keys_dir(n.path, n.spec)?);
-	let secret_store = Box::new(secret_store(dir, Some(n.iterations))?);
+	let iterations = keys_iterations(n.iterations, Path::new(&n.base_path))?;
+	let secret_store = Box::new(secret_store(dir, Some(iterations))?);
 	let acc_provider = AccountProvider::new(secret_store, AccountProviderSettings::default());
 	let new_account = acc_provider.new_account(&password).map_err(|e| format!("Could not create new account: {}", e))?;
 	Ok(format!("0x{:x}", new_account))
@@ -16,7 +16,8 @@
 
 use std::str::{FromStr, from_utf8};
 use std::{io, fs};
-use std::io::{BufReader, BufRead};
+use std::path::Path;
+use std::io::{BufReader, BufRead, Write};
 use std::time::{Instant, Duration};
 use std::thread::sleep;
 use std::sync::Arc;
@@ -25,8 +26,9 @@ use hash::{keccak, KECCAK_NULL_RLP};
 use ethereum_types::{U256, H256, Address};
 use bytes::ToPretty;
 use rlp::PayloadInfo;
+use serde_json::{self, Map, Value};
 use ethcore::account_provider::AccountProvider;
-use ethcore::client::{Mode, DatabaseCompactionProfile, VMType, BlockImportError, Nonce, Balance, BlockChainClient, BlockId, BlockInfo, ImportBlock};
+use ethcore::client::{Mode, DatabaseCompactionProfile, VMType, BlockImportError, Nonce, Balance, BlockChainClient, BlockId, BlockInfo, ImportBlock, Inconsistency, audit_block};
 use ethcore::error::{ImportErrorKind, BlockImportErrorKind};
 use ethcore::miner::Miner;
 use ethcore::verification::queue::VerifierSettings;
@@ -70,6 +72,8 @@ pub enum BlockchainCmd {
 	Import(ImportBlockchain),
 	Export(ExportBlockchain),
 	ExportState(ExportState),
+	ExportGenesisSpec(ExportGenesisSpec),
+	Audit(AuditBlockchain),
 }
 
 #[derive(Debug, PartialEq)]
@@ -79,6 +83,23 @@ pub struct KillBlockchain {
 	pub pruning: Pruning,
 }
 
+#[derive(Debug, PartialEq)]
+pub struct AuditBlockchain {
+	pub spec: SpecType,
+	pub cache_config: CacheConfig,
+	pub dirs: Directories,
+	pub pruning: Pruning,
+	pub pruning_history: u64,
+	pub pruning_memory: usize,
+	pub compaction: DatabaseCompactionProfile,
+	pub wal: bool,
+	pub tracing: Switch,
+	pub fat_db: Switch,
+	pub from_block: BlockId,
+	pub to_block: BlockId,
+	pub quarantine_path: Option<String>,
+}
+
 #[derive(Debug, PartialEq)]
 pub struct ImportBlockchain {
 	pub spec: SpecType,
@@ -140,6 +161,22 @@ pub struct ExportState {
 	pub max_balance: Option<U256>,
 }
 
+#[derive(Debug, PartialEq)]
+pub struct ExportGenesisSpec {
+	pub spec: SpecType,
+	pub cache_config: CacheConfig,
+	pub dirs: Directories,
+	pub file_path: Option<String>,
+	pub pruning: Pruning,
+	pub pruning_history: u64,
+	pub pruning_memory: usize,
+	pub compaction: DatabaseCompactionProfile,
+	pub wal: bool,
+	pub fat_db: Switch,
+	pub tracing: Switch,
+	pub at: BlockId,
+}
+
 pub fn execute(cmd: BlockchainCmd) -> Result<(), String> {
 	match cmd {
 		BlockchainCmd::Kill(kill_cmd) => kill_db(kill_cmd),
@@ -152,6 +189,8 @@ pub fn execute(cmd: BlockchainCmd) -> Result<(), String> {
 		}
 		BlockchainCmd::Export(export_cmd) => execute_export(export_cmd),
 		BlockchainCmd::ExportState(export_cmd) => execute_export_state(export_cmd),
+		BlockchainCmd::ExportGenesisSpec(export_cmd) => execute_export_genesis_spec(export_cmd),
+		BlockchainCmd::Audit(audit_cmd) => execute_audit(audit_cmd),
 	}
 }
 
@@ -706,6 +745,330 @@ fn execute_export_state(cmd: ExportState) -> Result<(), String> {
 	Ok(())
 }
 
+// block number in a chain spec: a JSON number, or a decimal or hex string.
+fn spec_block_number(value: &Value) -> Option<u64> {
+	match *value {
+		Value::Number(ref n) => n.as_u64(),
+		Value::String(ref s) if s.starts_with("0x") => u64::from_str_radix(&s[2..], 16).ok(),
+		Value::String(ref s) => s.parse().ok(),
+		_ => None,
+	}
+}
+
+// move the keys of a map from block numbers to values `at` blocks back. The value in force
+// at block `at` moves to block 0: the last one of them, or their sum if `cumulative`.
+fn rebase_block_map(map: &mut Map<String, Value>, at: u64, cumulative: bool) {
+	let entries = ::std::mem::replace(map, Map::new());
+	let mut genesis: Option<(u64, Value)> = None;
+	for (key, value) in entries {
+		match spec_block_number(&Value::String(key.clone())) {
+			Some(number) if number > at => {
+				map.insert(format!("{}", number - at), value);
+			},
+			Some(number) => {
+				genesis = Some(match genesis.take() {
+					None => (number, value),
+					Some((previous_number, previous)) => if cumulative {
+						let sum = spec_block_number(&previous).unwrap_or(0) + spec_block_number(&value).unwrap_or(0);
+						(number, Value::String(format!("0x{:x}", sum)))
+					} else if previous_number > number {
+						(previous_number, previous)
+					} else {
+						(number, value)
+					},
+				});
+			},
+			None => {
+				map.insert(key, value);
+			},
+		}
+	}
+	if let Some((_, value)) = genesis {
+		map.insert("0".into(), value);
+	}
+}
+
+// rebase the forks of the chain spec params onto a new genesis at block `at`: transitions up
+// to `at` are in force from the genesis on, later ones happen `at` blocks earlier.
+// The difficulty bomb counts from the new genesis.
+fn rebase_spec_params(params: &mut Map<String, Value>, at: u64) {
+	let transitions: Vec<(String, u64)> = params.iter()
+		.filter(|&(key, _)| key.ends_with("Transition"))
+		.filter_map(|(key, value)| spec_block_number(value).map(|number| (key.clone(), number)))
+		.collect();
+	for (key, number) in transitions {
+		// the state already contains the effects of the DAO hard fork.
+		if key == "daoHardforkTransition" && number <= at {
+			params.remove(&key);
+			continue;
+		}
+		params.insert(key, Value::String(format!("0x{:x}", number.saturating_sub(at))));
+	}
+
+	for &(key, cumulative) in &[("blockReward", false), ("blockRewardContractTransitions", false), ("difficultyBombDelays", true)] {
+		if let Some(&mut Value::Object(ref mut map)) = params.get_mut(key) {
+			rebase_block_map(map, at, cumulative);
+		}
+	}
+
+	let multi = params.get_mut("validators")
+		.and_then(Value::as_object_mut)
+		.and_then(|validators| validators.get_mut("multi"))
+		.and_then(Value::as_object_mut);
+	if let Some(multi) = multi {
+		rebase_block_map(multi, at, false);
+	}
+}
+
+// rebase the activation of a builtin onto a new genesis at block `at`.
+fn rebase_builtin(builtin: &mut Value, at: u64) {
+	fn rebase_activation(value: &mut Value, at: u64) {
+		if let Some(activation) = value.get_mut("activate_at") {
+			if let Some(number) = spec_block_number(activation) {
+				*activation = Value::from(number.saturating_sub(at));
+			}
+		}
+	}
+
+	rebase_activation(builtin, at);
+	if let Some(repricings) = builtin.get_mut("repricing").and_then(Value::as_array_mut) {
+		for repricing in repricings {
+			rebase_activation(repricing, at);
+		}
+	}
+}
+
+// write an entry of the accounts object of a chain spec.
+fn write_spec_account(out: &mut io::Write, first: bool, address: &str, account: &Value) -> Result<(), String> {
+	let account = serde_json::to_string(account).map_err(|e| format!("Couldn't write chain spec: {}", e))?;
+	let separator = if first { "" } else { "," };
+	out.write_all(format!("{}\n    \"{}\": {}", separator, address, account).as_bytes()).map_err(|e| format!("Couldn't write chain spec: {}", e))
+}
+
+fn execute_export_genesis_spec(cmd: ExportGenesisSpec) -> Result<(), String> {
+	let mut spec: Value = serde_json::from_slice(&cmd.spec.json()?)
+		.map_err(|e| format!("Invalid chain spec: {}", e))?;
+
+	let service = start_client(
+		cmd.dirs,
+		cmd.spec,
+		cmd.pruning,
+		cmd.pruning_history,
+		cmd.pruning_memory,
+		cmd.tracing,
+		cmd.fat_db,
+		cmd.compaction,
+		cmd.wal,
+		cmd.cache_config,
+		true
+	)?;
+
+	let client = service.client();
+	let at = cmd.at;
+	let header = client.block_header(at).ok_or("Specified block not found")?;
+	let number = header.number();
+
+	// builtins keep their definitions, their state is taken from the chain.
+	let mut builtins = Map::new();
+	if let Some(original) = spec.get("accounts").and_then(Value::as_object) {
+		for (address, account) in original {
+			let address: Address = address.trim_left_matches("0x").parse()
+				.map_err(|_| format!("Invalid account address in chain spec: {}", address))?;
+			if let Some(builtin) = account.get("builtin") {
+				let mut builtin = builtin.clone();
+				rebase_builtin(&mut builtin, number);
+				builtins.insert(format!("0x{:x}", address), builtin);
+			}
+		}
+	}
+
+	{
+		let genesis = spec.get_mut("genesis").and_then(Value::as_object_mut).ok_or("Chain spec has no genesis")?;
+		genesis.insert("parentHash".into(), Value::String(format!("0x{:x}", H256::zero())));
+		genesis.insert("timestamp".into(), Value::String(format!("0x{:x}", header.timestamp())));
+		genesis.insert("gasLimit".into(), Value::String(format!("0x{:x}", header.gas_limit())));
+		// recomputed from the accounts.
+		genesis.remove("stateRoot");
+	}
+
+	{
+		let root = spec.as_object_mut().ok_or("Invalid chain spec")?;
+		let name = format!("{} fork at #{}", root.get("name").and_then(Value::as_str).unwrap_or("Chain"), number);
+		let data_dir = root.get("dataDir").and_then(Value::as_str).map(|dir| format!("{}-fork-{}", dir, number));
+		root.insert("name".into(), Value::String(name));
+		if let Some(data_dir) = data_dir {
+			root.insert("dataDir".into(), Value::String(data_dir));
+		}
+		// the fork is a new network: it must not connect to the nodes of the original one.
+		root.remove("nodes");
+		root.remove("hardcodedSync");
+		// streamed below.
+		root.remove("accounts");
+
+		if let Some(params) = root.get_mut("params").and_then(Value::as_object_mut) {
+			rebase_spec_params(params, number);
+		}
+		if let Some(engine) = root.get_mut("engine").and_then(Value::as_object_mut) {
+			for (_, engine_spec) in engine.iter_mut() {
+				if let Some(params) = engine_spec.get_mut("params").and_then(Value::as_object_mut) {
+					rebase_spec_params(params, number);
+				}
+			}
+		}
+	}
+
+	let mut out: Box<io::Write> = match cmd.file_path {
+		Some(f) => Box::new(fs::File::create(&f).map_err(|_| format!("Cannot write to file given: {}", f))?),
+		None => Box::new(io::stdout()),
+	};
+
+	// the accounts are written one by one as the last entry of the spec, so that the state
+	// is never held in memory as a whole.
+	let head = serde_json::to_string_pretty(&spec).map_err(|e| format!("Couldn't write chain spec: {}", e))?;
+	let head = head[..head.rfind('}').ok_or("Invalid chain spec")?].trim_right();
+	out.write_all(format!("{},\n  \"accounts\": {{", head).as_bytes()).map_err(|e| format!("Couldn't write chain spec: {}", e))?;
+
+	let mut last: Option<Address> = None;
+	let mut i = 0usize;
+	loop {
+		let list = client.list_accounts(at, last.as_ref(), 1000).ok_or("Specified block not found")?;
+		if list.is_empty() {
+			break;
+		}
+
+		for account in list.into_iter() {
+			let mut entry = Map::new();
+			entry.insert("balance".into(), Value::String(format!("0x{:x}", client.balance(&account, at.into()).unwrap_or_else(U256::zero))));
+			entry.insert("nonce".into(), Value::String(format!("0x{:x}", client.nonce(&account, at).unwrap_or_else(U256::zero))));
+			let code = client.code(&account, at.into()).unwrap_or(None).unwrap_or_else(Vec::new);
+			if !code.is_empty() {
+				entry.insert("code".into(), Value::String(format!("0x{}", code.to_hex())));
+			}
+
+			if client.storage_root(&account, at).unwrap_or(KECCAK_NULL_RLP) != KECCAK_NULL_RLP {
+				let mut storage = Map::new();
+				let mut last_storage: Option<H256> = None;
+				loop {
+					let keys = client.list_storage(at, &account, last_storage.as_ref(), 1000).ok_or("Specified block not found")?;
+					if keys.is_empty() {
+						break;
+					}
+
+					for key in keys.into_iter() {
+						let value = client.storage_at(&account, &key, at.into()).unwrap_or_else(Default::default);
+						storage.insert(format!("0x{:x}", key), Value::String(format!("0x{:x}", value)));
+						last_storage = Some(key);
+					}
+				}
+				entry.insert("storage".into(), Value::Object(storage));
+			}
+
+			let key = format!("0x{:x}", account);
+			if let Some(builtin) = builtins.remove(&key) {
+				entry.insert("builtin".into(), builtin);
+			}
+			write_spec_account(&mut *out, i == 0, &key, &Value::Object(entry))?;
+
+			i += 1;
+			if i % 10000 == 0 {
+				info!("Account #{}", i);
+			}
+			last = Some(account);
+		}
+	}
+
+	// builtins without any state.
+	for (key, builtin) in builtins {
+		let mut entry = Map::new();
+		entry.insert("builtin".into(), builtin);
+		write_spec_account(&mut *out, i == 0, &key, &Value::Object(entry))?;
+		i += 1;
+	}
+
+	out.write_all(b"\n  }\n}\n").map_err(|e| format!("Couldn't write chain spec: {}", e))?;
+	info!("Export completed.");
+	Ok(())
+}
+
+fn execute_audit(cmd: AuditBlockchain) -> Result<(), String> {
+	let service = start_client(
+		cmd.dirs,
+		cmd.spec,
+		cmd.pruning,
+		cmd.pruning_history,
+		cmd.pruning_memory,
+		cmd.tracing,
+		cmd.fat_db,
+		cmd.compaction,
+		cmd.wal,
+		cmd.cache_config,
+		false,
+	)?;
+
+	let client = service.client();
+
+	let from = client.block_number(cmd.from_block).ok_or("From block could not be found")?;
+	let to = client.block_number(cmd.to_block).ok_or("To block could not be found")?;
+	if from > to {
+		return Err(format!("Invalid block range: from block #{} is after to block #{}", from, to));
+	}
+
+	if let Some(ref dir) = cmd.quarantine_path {
+		fs::create_dir_all(dir).map_err(|e| format!("Cannot create quarantine directory {}: {}", dir, e))?;
+	}
+
+	let mut inconsistent = 0;
+	for i in from..(to + 1) {
+		if i % 10000 == 0 {
+			info!("#{}", i);
+		}
+
+		let audit = audit_block(&*client, i);
+		if audit.is_ok() {
+			continue;
+		}
+
+		inconsistent += 1;
+		for inconsistency in &audit.inconsistencies {
+			warn!("Block #{} ({}): {}", i, audit.hash.map_or_else(|| "unknown".into(), |h| format!("{:#x}", h)), inconsistency);
+		}
+
+		if let (Some(dir), Some(hash)) = (cmd.quarantine_path.as_ref(), audit.hash) {
+			quarantine_block(&*client, dir, i, &hash, &audit.inconsistencies)?;
+		}
+	}
+
+	if inconsistent == 0 {
+		info!("Audit of blocks #{}..#{} completed. No inconsistencies found.", from, to);
+		Ok(())
+	} else {
+		Err(format!("Audit of blocks #{}..#{} found {} inconsistent blocks.", from, to, inconsistent))
+	}
+}
+
+/// Copy whatever raw data of an inconsistent block is still present into the quarantine directory.
+fn quarantine_block(client: &BlockChainClient, dir: &str, number: u64, hash: &H256, inconsistencies: &[Inconsistency]) -> Result<(), String> {
+	let path = Path::new(dir).join(format!("{}-{:x}", number, hash));
+	let mut out = fs::File::create(&path).map_err(|e| format!("Cannot write to quarantine file {}: {}", path.display(), e))?;
+
+	let header = client.block_header(BlockId::Hash(*hash)).map(|h| h.into_inner());
+	let body = client.block_body(BlockId::Hash(*hash)).map(|b| b.into_inner());
+	let receipts = client.block_receipts(hash);
+
+	let mut report = String::new();
+	for inconsistency in inconsistencies {
+		report.push_str(&format!("inconsistency: {}\n", inconsistency));
+	}
+	for (name, data) in vec![("header", header), ("body", body), ("receipts", receipts)] {
+		match data {
+			Some(data) => report.push_str(&format!("{}: 0x{}\n", name, data.to_hex())),
+			None => report.push_str(&format!("{}: missing\n", name)),
+		}
+	}
+
+	out.write_all(report.as_bytes()).map_err(|e| format!("Couldn't write to stream. Cause: {}", e))
+}
+
 pub fn kill_db(cmd: KillBlockchain) -> Result<(), String> {
 	let spec = cmd.spec.spec(&cmd.dirs.cache)?;
 	let genesis_hash = spec.genesis_header().hash();
@@ -723,7 +1086,8 @@ pub fn kill_db(cmd: KillBlockchain) -> Result<(), String> {
 
 #[cfg(test)]
 mod test {
-	use super::DataFormat;
+	use serde_json::{self, Value};
+	use super::{DataFormat, rebase_spec_params};
 
 	#[test]
 	fn test_data_format_parsing() {
@@ -731,4 +1095,28 @@ mod test {
 		assert_eq!(DataFormat::Binary, "bin".parse().unwrap());
 		assert_eq!(DataFormat::Hex, "hex".parse().unwrap());
 	}
+
+	#[test]
+	fn rebases_forks_onto_new_genesis() {
+		let mut params: Value = serde_json::from_str(r#"{
+			"homesteadTransition": "0x10",
+			"daoHardforkTransition": 100,
+			"eip150Transition": "200",
+			"minimumDifficulty": "0x20000",
+			"blockReward": { "0": "0x5", "150": "0x3", "300": "0x2" },
+			"difficultyBombDelays": { "100": "0x10", "150": "0x20", "250": "0x30" },
+			"validators": { "multi": { "0": { "list": [] }, "120": { "contract": "0x01" } } }
+		}"#).unwrap();
+		rebase_spec_params(params.as_object_mut().unwrap(), 150);
+
+		let expected: Value = serde_json::from_str(r#"{
+			"homesteadTransition": "0x0",
+			"eip150Transition": "0x32",
+			"minimumDifficulty": "0x20000",
+			"blockReward": { "0": "0x3", "150": "0x2" },
+			"difficultyBombDelays": { "0": "0x30", "100": "0x30" },
+			"validators": { "multi": { "0": { "contract": "0x01" } } }
+		}"#).unwrap();
+		assert_eq!(params, expected);
+	}
 }
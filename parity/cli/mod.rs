@@ -154,6 +154,19 @@ usage! {
 				"[FILE]",
 				"Path to the exported file",
 			}
+
+			CMD cmd_export_genesis_spec
+			{
+				"Export a chain spec whose genesis state is the state at a given block",
+
+				ARG arg_export_genesis_spec_at: (String) = "latest",
+				"--at=[BLOCK]",
+				"Take the state at the given block, which may be an index, hash, or latest. Requires --fat-db on. Note that taking the state at non-recent blocks will only work with --pruning archive",
+
+				ARG arg_export_genesis_spec_file: (Option<String>) = None,
+				"[FILE]",
+				"Path to the exported file",
+			}
 		}
 
 		CMD cmd_signer
@@ -230,6 +243,22 @@ usage! {
 			CMD cmd_db_kill {
 				"Clean the database",
 			}
+
+			CMD cmd_db_audit {
+				"Check the integrity of the chain database",
+
+				ARG arg_db_audit_from: (String) = "0",
+				"--from=[BLOCK]",
+				"Audit from block BLOCK, which may be an index or hash.",
+
+				ARG arg_db_audit_to: (String) = "latest",
+				"--to=[BLOCK]",
+				"Audit to (including) block BLOCK, which may be an index, hash or latest.",
+
+				ARG arg_db_audit_quarantine: (Option<String>) = None,
+				"--quarantine=[DIR]",
+				"Copy the raw data of every inconsistent block into DIR for later inspection.",
+			}
 		}
 
 		CMD cmd_export_hardcoded_sync
@@ -264,6 +293,14 @@ usage! {
 			"--force-direct",
 			"Run the originally installed version of Parity, ignoring any updates that have since been installed.",
 
+			FLAG flag_supervise: (bool) = false, or |_| None,
+			"--supervise",
+			"Run the node in a child process and restart it, with an increasing delay, whenever it exits abnormally. A crash report is written on every abnormal exit.",
+
+			ARG arg_crash_reports_path: (String) = "$BASE/crash-reports", or |_| None,
+			"--crash-reports-path=[PATH]",
+			"Specify the directory crash reports are written to in supervisor mode.",
+
 			ARG arg_mode: (String) = "last", or |c: &Config| c.parity.as_ref()?.mode.clone(),
 			"--mode=[MODE]",
 			"Set the operating mode. MODE can be one of: last - Uses the last-used mode, active if none; active - Parity continuously syncs the chain; passive - Parity syncs initially, then sleeps and wakes regularly to resync; dark - Parity syncs only when the RPC is active; offline - Parity doesn't sync.",
@@ -334,9 +371,9 @@ usage! {
 			"--fast-unlock",
 			"Use drasticly faster unlocking mode. This setting causes raw secrets to be stored unprotected in memory, so use with care.",
 
-			ARG arg_keys_iterations: (u32) = 10240u32, or |c: &Config| c.account.as_ref()?.keys_iterations.clone(),
+			ARG arg_keys_iterations: (Option<u32>) = None, or |c: &Config| c.account.as_ref()?.keys_iterations.clone(),
 			"--keys-iterations=[NUM]",
-			"Specify the number of iterations to use when deriving key from the password (bigger is more secure)",
+			"Specify the number of iterations to use when deriving key from the password (bigger is more secure). By default it is calibrated to this machine on first use.",
 
 			ARG arg_accounts_refresh: (u64) = 5u64, or |c: &Config| c.account.as_ref()?.refresh_time.clone(),
 			"--accounts-refresh=[TIME]",
@@ -430,6 +467,10 @@ usage! {
 			"--no-serve-light",
 			"Disable serving of light peers.",
 
+			FLAG flag_heal_state: (bool) = false, or |c: &Config| c.network.as_ref()?.heal_state.clone(),
+			"--heal-state",
+			"After warp sync, check the restored state and retrieve any missing part of it from peers.",
+
 			ARG arg_warp_barrier: (Option<u64>) = None, or |c: &Config| c.network.as_ref()?.warp_barrier.clone(),
 			"--warp-barrier=[NUM]",
 			"When warp enabled never attempt regular sync before warping to block NUM.",
@@ -741,6 +782,22 @@ usage! {
 			"--tx-queue-strategy=[S]",
 			"Prioritization strategy used to order transactions in the queue. S may be: gas_price - Prioritize txs with high gas price",
 
+			ARG arg_tx_queue_gas_price_bump: (String) = "12.5", or |c: &Config| c.mining.as_ref()?.tx_queue_gas_price_bump.clone(),
+			"--tx-queue-gas-price-bump=[PERCENT]",
+			"Minimal gas price increase, in percent, for a transaction to replace a queued transaction with the same sender and nonce. Must be above 0 and at most 1000.",
+
+			ARG arg_tx_propagation_min_gas_price: (Option<String>) = None, or |c: &Config| c.mining.as_ref()?.tx_propagation_min_gas_price.clone(),
+			"--tx-propagation-min-gas-price=[WEI]",
+			"Minimum gas price, in Wei, of transactions received from the network to propagate to peers. Cheaper transactions are still accepted to the queue.",
+
+			ARG arg_tx_queue_max_age: (Option<u64>) = None, or |c: &Config| c.mining.as_ref()?.tx_queue_max_age.clone(),
+			"--tx-queue-max-age=[SECS]",
+			"Maximum time, in seconds, a non-local transaction may wait in the queue before it's dropped. By default transactions don't expire.",
+
+			ARG arg_tx_rebroadcast_interval: (u64) = 600u64, or |c: &Config| c.mining.as_ref()?.tx_rebroadcast_interval.clone(),
+			"--tx-rebroadcast-interval=[SECS]",
+			"Interval, in seconds, at which local transactions still in the queue are propagated to peers again.",
+
 			ARG arg_stratum_interface: (String) = "local", or |c: &Config| c.stratum.as_ref()?.interface.clone(),
 			"--stratum-interface=[IP]",
 			"Interface address for Stratum server.",
@@ -757,6 +814,10 @@ usage! {
 			"--gas-price-percentile=[PCT]",
 			"Set PCT percentile gas price value from last 100 blocks as default gas price when sending transactions.",
 
+			ARG arg_min_gas_price_percentile: (Option<usize>) = None, or |c: &Config| c.mining.as_ref()?.min_gas_price_percentile.clone(),
+			"--min-gas-price-percentile=[PCT]",
+			"Set the minimum gas price for a transaction to be accepted for mining to the PCT percentile gas price value from last 100 blocks. Overrides --usd-per-tx.",
+
 			ARG arg_author: (Option<String>) = None, or |c: &Config| c.mining.as_ref()?.author.clone(),
 			"--author=[ADDRESS]",
 			"Specify the block author (aka \"coinbase\") address for sending block rewards from sealed blocks. NOTE: MINING WILL NOT WORK WITHOUT THIS OPTION.", // Sealing/Mining Option
@@ -765,6 +826,14 @@ usage! {
 			"--engine-signer=[ADDRESS]",
 			"Specify the address which should be used to sign consensus messages and issue blocks. Relevant only to non-PoW chains.",
 
+			ARG arg_engine_signer_timeout: (Option<u64>) = None, or |c: &Config| c.mining.as_ref()?.engine_signer_timeout.clone(),
+			"--engine-signer-timeout=[MS]",
+			"Wait at most MS milliseconds for the consensus signer to sign a block. The step is skipped if the signature is late. By default sealing waits for the signer. Relevant only to non-PoW chains.",
+
+			FLAG flag_engine_signer_retry: (bool) = false, or |c: &Config| c.mining.as_ref()?.engine_signer_retry.clone(),
+			"--engine-signer-retry",
+			"Keep waiting for a late block signature and use it on the next attempt to seal the same block, instead of asking the signer again. Relevant only with --engine-signer-timeout.",
+
 			ARG arg_tx_gas_limit: (Option<String>) = None, or |c: &Config| c.mining.as_ref()?.tx_gas_limit.clone(),
 			"--tx-gas-limit=[GAS]",
 			"Apply a limit of GAS as the maximum amount of gas a single transaction may have for it to be mined.",
@@ -828,6 +897,14 @@ usage! {
 			"--tracing=[BOOL]",
 			"Indicates if full transaction tracing should be enabled. Works only if client had been fully synced with tracing enabled. BOOL may be one of auto, on, off. auto uses last used value of this option (off if it does not exist).", // footprint option
 
+			ARG arg_tracing_max_age: (Option<u64>) = None, or |c: &Config| c.footprint.as_ref()?.tracing_max_age.clone(),
+			"--tracing-max-age=[BLOCKS]",
+			"Remove traces of blocks older than BLOCKS. Keeps traces of all blocks if not specified.",
+
+			ARG arg_tracing_max_size: (Option<u64>) = None, or |c: &Config| c.footprint.as_ref()?.tracing_max_size.clone(),
+			"--tracing-max-size=[MB]",
+			"Remove traces of the oldest blocks once stored traces exceed MB. No limit if not specified.",
+
 			ARG arg_pruning: (String) = "auto", or |c: &Config| c.footprint.as_ref()?.pruning.clone(),
 			"--pruning=[METHOD]",
 			"Configure pruning of the state/storage trie. METHOD may be one of auto, archive, fast: archive - keep all state trie data. No pruning. fast - maintain journal overlay. Fast but 50MB used. auto - use the method most recently synced or default to fast if none synced.",
@@ -856,6 +933,14 @@ usage! {
 			"--cache-size-state=[MB]",
 			"Specify the maximum size of memory to use for the state cache.",
 
+			ARG arg_sync_download_memory: (u32) = 256u32, or |c: &Config| c.footprint.as_ref()?.sync_download_memory.clone(),
+			"--sync-download-memory=[MB]",
+			"Specify the maximum size of memory to use for blocks downloaded ahead of the import queue.",
+
+			ARG arg_vm_memory_limit: (u32) = 256u32, or |c: &Config| c.footprint.as_ref()?.vm_memory_limit.clone(),
+			"--vm-memory-limit=[MB]",
+			"Specify the maximum size of memory a single EVM call frame of eth_call, gas estimation and tracing may use. Calls expanding memory past it fail with an out of memory error. Imported blocks are never limited.",
+
 			ARG arg_db_compaction: (String) = "auto", or |c: &Config| c.footprint.as_ref()?.db_compaction.clone(),
 			"--db-compaction=[TYPE]",
 			"Database compaction type. TYPE may be one of: ssd - suitable for SSDs and fast HDDs; hdd - suitable for slow HDDs; auto - determine automatically.",
@@ -868,6 +953,14 @@ usage! {
 			"--cache-size=[MB]",
 			"Set total amount of discretionary memory to use for the entire system, overrides other cache and queue options.",
 
+			FLAG flag_cache_balancing: (bool) = false, or |c: &Config| c.footprint.as_ref()?.cache_balancing.clone(),
+			"--cache-balancing",
+			"Periodically redistribute memory among the state, blockchain, traces and jump-tables caches based on their hit rates and on whether the node is syncing.",
+
+			ARG arg_memory_limit: (Option<u32>) = None, or |c: &Config| c.footprint.as_ref()?.memory_limit.clone(),
+			"--memory-limit=[MB]",
+			"Drop part of the contents of the caches once the resident memory of the process exceeds MB, until it is back below 90% of MB.",
+
 			ARG arg_num_verifiers: (Option<usize>) = None, or |c: &Config| c.footprint.as_ref()?.num_verifiers.clone(),
 			"--num-verifiers=[INT]",
 			"Amount of verifier threads to use or to begin with, if verifier auto-scaling is enabled.",
@@ -882,6 +975,10 @@ usage! {
 			"--no-periodic-snapshot",
 			"Disable automated snapshots which usually occur once every 10000 blocks.",
 
+			ARG arg_snapshot_period: (u64) = 5000u64, or |c: &Config| c.snapshots.as_ref()?.period.clone(),
+			"--snapshot-period=[BLOCKS]",
+			"Take automated snapshots of every block whose number is a multiple of BLOCKS. On pruned nodes the state of such a block is kept until its snapshot is taken.",
+
 		["Whisper options"]
 			FLAG flag_whisper: (bool) = false, or |c: &Config| c.whisper.as_ref()?.enabled,
 			"--whisper",
@@ -1132,6 +1229,7 @@ struct Network {
 	reserved_peers: Option<String>,
 	reserved_only: Option<bool>,
 	no_serve_light: Option<bool>,
+	heal_state: Option<bool>,
 }
 
 #[derive(Default, Debug, PartialEq, Deserialize)]
@@ -1217,6 +1315,8 @@ struct Ipfs {
 struct Mining {
 	author: Option<String>,
 	engine_signer: Option<String>,
+	engine_signer_timeout: Option<u64>,
+	engine_signer_retry: Option<bool>,
 	force_sealing: Option<bool>,
 	reseal_on_uncle: Option<bool>,
 	reseal_on_txs: Option<String>,
@@ -1228,6 +1328,7 @@ struct Mining {
 	relay_set: Option<String>,
 	min_gas_price: Option<u64>,
 	gas_price_percentile: Option<usize>,
+	min_gas_price_percentile: Option<usize>,
 	usd_per_tx: Option<String>,
 	usd_per_eth: Option<String>,
 	price_update_period: Option<String>,
@@ -1239,6 +1340,10 @@ struct Mining {
 	tx_queue_mem_limit: Option<u32>,
 	tx_queue_gas: Option<String>,
 	tx_queue_strategy: Option<String>,
+	tx_queue_gas_price_bump: Option<String>,
+	tx_queue_max_age: Option<u64>,
+	tx_rebroadcast_interval: Option<u64>,
+	tx_propagation_min_gas_price: Option<String>,
 	tx_queue_ban_count: Option<u16>,
 	tx_queue_ban_time: Option<u16>,
 	remove_solved: Option<bool>,
@@ -1259,6 +1364,8 @@ struct Stratum {
 #[serde(deny_unknown_fields)]
 struct Footprint {
 	tracing: Option<String>,
+	tracing_max_age: Option<u64>,
+	tracing_max_size: Option<u64>,
 	pruning: Option<String>,
 	pruning_history: Option<u64>,
 	pruning_memory: Option<usize>,
@@ -1268,6 +1375,10 @@ struct Footprint {
 	cache_size_blocks: Option<u32>,
 	cache_size_queue: Option<u32>,
 	cache_size_state: Option<u32>,
+	cache_balancing: Option<bool>,
+	memory_limit: Option<u32>,
+	sync_download_memory: Option<u32>,
+	vm_memory_limit: Option<u32>,
 	db_compaction: Option<String>,
 	fat_db: Option<String>,
 	scale_verifiers: Option<bool>,
@@ -1278,6 +1389,7 @@ struct Footprint {
 #[serde(deny_unknown_fields)]
 struct Snapshots {
 	disable_periodic: Option<bool>,
+	period: Option<u64>,
 }
 
 #[derive(Default, Debug, PartialEq, Deserialize)]
@@ -1482,6 +1594,7 @@ mod tests {
 			cmd_export: false,
 			cmd_export_blocks: false,
 			cmd_export_state: false,
+			cmd_export_genesis_spec: false,
 			cmd_signer: false,
 			cmd_signer_list: false,
 			cmd_signer_sign: false,
@@ -1493,6 +1606,7 @@ mod tests {
 			cmd_tools_hash: false,
 			cmd_db: false,
 			cmd_db_kill: false,
+			cmd_db_audit: false,
 			cmd_export_hardcoded_sync: false,
 
 			// Arguments
@@ -1503,9 +1617,11 @@ mod tests {
 			arg_export_blocks_format: None,
 			arg_export_state_file: None,
 			arg_export_state_format: None,
+			arg_export_genesis_spec_file: None,
 			arg_snapshot_file: None,
 			arg_restore_file: None,
 			arg_tools_hash_file: None,
+			arg_db_audit_quarantine: None,
 
 			arg_signer_sign_id: None,
 			arg_signer_reject_id: None,
@@ -1533,6 +1649,8 @@ mod tests {
 			flag_no_hardcoded_sync: false,
 			flag_no_persistent_txqueue: false,
 			flag_force_direct: false,
+			flag_supervise: false,
+			arg_crash_reports_path: "$BASE/crash-reports".into(),
 
 			// -- Convenience Options
 			arg_config: "$BASE/config.toml".into(),
@@ -1542,7 +1660,7 @@ mod tests {
 			// -- Account Options
 			arg_unlock: Some("0xdeadbeefcafe0000000000000000000000000000".into()),
 			arg_password: vec!["~/.safe/password.file".into()],
-			arg_keys_iterations: 10240u32,
+			arg_keys_iterations: Some(10240u32),
 			arg_accounts_refresh: 5u64,
 			flag_no_hardware_wallets: false,
 			flag_fast_unlock: false,
@@ -1581,6 +1699,7 @@ mod tests {
 			flag_reserved_only: false,
 			flag_no_ancient_blocks: false,
 			flag_no_serve_light: false,
+			flag_heal_state: false,
 
 			// -- API and Console Options
 			// RPC
@@ -1640,6 +1759,8 @@ mod tests {
 			// -- Sealing/Mining Options
 			arg_author: Some("0xdeadbeefcafe0000000000000000000000000001".into()),
 			arg_engine_signer: Some("0xdeadbeefcafe0000000000000000000000000001".into()),
+			arg_engine_signer_timeout: None,
+			flag_engine_signer_retry: false,
 			flag_force_sealing: true,
 			arg_reseal_on_txs: "all".into(),
 			arg_reseal_min_period: 4000u64,
@@ -1652,6 +1773,7 @@ mod tests {
 			arg_min_gas_price: Some(0u64),
 			arg_usd_per_tx: "0.0001".into(),
 			arg_gas_price_percentile: 50usize,
+			arg_min_gas_price_percentile: None,
 			arg_usd_per_eth: "auto".into(),
 			arg_price_update_period: "hourly".into(),
 			arg_gas_floor_target: "4700000".into(),
@@ -1662,6 +1784,10 @@ mod tests {
 			arg_tx_queue_mem_limit: 4u32,
 			arg_tx_queue_gas: "off".into(),
 			arg_tx_queue_strategy: "gas_factor".into(),
+			arg_tx_queue_gas_price_bump: "12.5".into(),
+			arg_tx_queue_max_age: None,
+			arg_tx_rebroadcast_interval: 600u64,
+			arg_tx_propagation_min_gas_price: None,
 			arg_tx_queue_ban_count: 1u16,
 			arg_tx_queue_ban_time: 180u16,
 			flag_remove_solved: false,
@@ -1676,6 +1802,8 @@ mod tests {
 
 			// -- Footprint Options
 			arg_tracing: "auto".into(),
+			arg_tracing_max_age: None,
+			arg_tracing_max_size: None,
 			arg_pruning: "auto".into(),
 			arg_pruning_history: 64u64,
 			arg_pruning_memory: 500usize,
@@ -1683,7 +1811,11 @@ mod tests {
 			arg_cache_size_blocks: 8u32,
 			arg_cache_size_queue: 50u32,
 			arg_cache_size_state: 25u32,
+			arg_sync_download_memory: 128u32,
+			arg_vm_memory_limit: 512u32,
 			arg_cache_size: Some(128),
+			flag_cache_balancing: false,
+			arg_memory_limit: None,
 			flag_fast_and_loose: false,
 			arg_db_compaction: "ssd".into(),
 			arg_fat_db: "auto".into(),
@@ -1698,11 +1830,15 @@ mod tests {
 			flag_export_state_no_storage: false,
 			arg_export_state_min_balance: None,
 			arg_export_state_max_balance: None,
+			arg_db_audit_from: "0".into(),
+			arg_db_audit_to: "latest".into(),
 
 			// -- Snapshot Optons
 			arg_export_state_at: "latest".into(),
+			arg_export_genesis_spec_at: "latest".into(),
 			arg_snapshot_at: "latest".into(),
 			flag_no_periodic_snapshot: false,
+			arg_snapshot_period: 5000u64,
 
 			// -- Whisper options.
 			flag_whisper: false,
@@ -1836,6 +1972,7 @@ mod tests {
 				reserved_peers: Some("./path/to/reserved_peers".into()),
 				reserved_only: Some(true),
 				no_serve_light: None,
+				heal_state: None,
 			}),
 			websockets: Some(Ws {
 				disable: Some(true),
@@ -1901,6 +2038,8 @@ mod tests {
 			mining: Some(Mining {
 				author: Some("0xdeadbeefcafe0000000000000000000000000001".into()),
 				engine_signer: Some("0xdeadbeefcafe0000000000000000000000000001".into()),
+				engine_signer_timeout: None,
+				engine_signer_retry: None,
 				force_sealing: Some(true),
 				reseal_on_txs: Some("all".into()),
 				reseal_on_uncle: None,
@@ -1910,6 +2049,7 @@ mod tests {
 				relay_set: None,
 				min_gas_price: None,
 				gas_price_percentile: None,
+				min_gas_price_percentile: None,
 				usd_per_tx: None,
 				usd_per_eth: None,
 				price_update_period: Some("hourly".into()),
@@ -1920,6 +2060,10 @@ mod tests {
 				tx_queue_mem_limit: None,
 				tx_queue_gas: Some("off".into()),
 				tx_queue_strategy: None,
+				tx_queue_gas_price_bump: None,
+				tx_queue_max_age: None,
+				tx_rebroadcast_interval: None,
+				tx_propagation_min_gas_price: None,
 				tx_queue_ban_count: None,
 				tx_queue_ban_time: None,
 				tx_gas_limit: None,
@@ -1932,6 +2076,8 @@ mod tests {
 			}),
 			footprint: Some(Footprint {
 				tracing: Some("on".into()),
+				tracing_max_age: None,
+				tracing_max_size: None,
 				pruning: Some("fast".into()),
 				pruning_history: Some(64),
 				pruning_memory: None,
@@ -1941,6 +2087,10 @@ mod tests {
 				cache_size_blocks: Some(16),
 				cache_size_queue: Some(100),
 				cache_size_state: Some(25),
+				cache_balancing: None,
+				memory_limit: None,
+				sync_download_memory: None,
+				vm_memory_limit: None,
 				db_compaction: Some("ssd".into()),
 				fat_db: Some("off".into()),
 				scale_verifiers: Some(false),
@@ -1948,6 +2098,7 @@ mod tests {
 			}),
 			snapshots: Some(Snapshots {
 				disable_periodic: Some(true),
+				period: None,
 			}),
 			misc: Some(Misc {
 				ntp_servers: Some(vec!["0.parity.pool.ntp.org:123".into()]),
@@ -30,15 +30,17 @@ use ansi_term::Colour;
 use sync::{NetworkConfiguration, validate_node_url, self};
 use ethcore::ethstore::ethkey::{Secret, Public};
 use ethcore::client::{VMType};
+use ethcore::engines::SignerTimeoutPolicy;
 use ethcore::miner::{stratum, MinerOptions};
 use ethcore::verification::queue::VerifierSettings;
+use miner::gas_pricer::DEFAULT_SAMPLE_BLOCKS;
 use miner::pool;
 
 use rpc::{IpcConfiguration, HttpConfiguration, WsConfiguration, UiConfiguration};
 use rpc_apis::ApiSet;
 use parity_rpc::NetworkSettings;
 use cache::CacheConfig;
-use helpers::{to_duration, to_mode, to_block_id, to_u256, to_pending_set, to_price, geth_ipc_path, parity_ipc_path, to_bootnodes, to_addresses, to_address, to_queue_strategy, to_queue_penalization, passwords_from_files};
+use helpers::{to_duration, to_mode, to_block_id, to_u256, to_pending_set, to_price, geth_ipc_path, parity_ipc_path, to_bootnodes, to_addresses, to_address, to_queue_strategy, to_gas_price_bump, to_queue_penalization, passwords_from_files};
 use dir::helpers::{replace_home, replace_home_and_local};
 use params::{ResealPolicy, AccountsConfig, GasPricerConfig, MinerExtras, SpecType};
 use ethcore_logger::Config as LogConfig;
@@ -49,7 +51,8 @@ use ethcore_private_tx::{ProviderConfig, EncryptorConfig};
 use secretstore::{NodeSecretKey, Configuration as SecretStoreConfiguration, ContractAddress as SecretStoreContractAddress};
 use updater::{UpdatePolicy, UpdateFilter, ReleaseTrack};
 use run::RunCmd;
-use blockchain::{BlockchainCmd, ImportBlockchain, ExportBlockchain, KillBlockchain, ExportState, DataFormat};
+use blockchain::{BlockchainCmd, ImportBlockchain, ExportBlockchain, KillBlockchain, AuditBlockchain, ExportState, ExportGenesisSpec, DataFormat};
+use supervisor::Supervisor;
 use export_hardcoded_sync::ExportHsyncCmd;
 use presale::ImportWallet;
 use account::{AccountCmd, NewAccount, ListAccounts, ImportAccounts, ImportFromGethAccounts};
@@ -115,6 +118,20 @@ impl Configuration {
 		Ok(config)
 	}
 
+	/// Supervisor of the node started with `args`, if running in supervisor mode.
+	pub fn supervisor(&self, args: Vec<String>) -> Option<Supervisor> {
+		if !self.args.flag_supervise {
+			return None;
+		}
+
+		let base = self.directories().base;
+		Some(Supervisor {
+			args: args,
+			reports_path: replace_home(&base, &self.args.arg_crash_reports_path).into(),
+			config_path: replace_home(&base, &self.args.arg_config).into(),
+		})
+	}
+
 	pub(crate) fn into_command(self) -> Result<Execute, String> {
 		let dirs = self.directories();
 		let pruning = self.args.arg_pruning.parse()?;
@@ -185,10 +202,27 @@ impl Configuration {
 				dirs: dirs,
 				pruning: pruning,
 			}))
+		} else if self.args.cmd_db && self.args.cmd_db_audit {
+			Cmd::Blockchain(BlockchainCmd::Audit(AuditBlockchain {
+				spec: spec,
+				cache_config: cache_config,
+				dirs: dirs,
+				pruning: pruning,
+				pruning_history: pruning_history,
+				pruning_memory: self.args.arg_pruning_memory,
+				compaction: compaction,
+				wal: wal,
+				tracing: tracing,
+				fat_db: fat_db,
+				from_block: to_block_id(&self.args.arg_db_audit_from)?,
+				to_block: to_block_id(&self.args.arg_db_audit_to)?,
+				quarantine_path: self.args.arg_db_audit_quarantine.clone(),
+			}))
 		} else if self.args.cmd_account {
 			let account_cmd = if self.args.cmd_account_new {
 				let new_acc = NewAccount {
 					iterations: self.args.arg_keys_iterations,
+					base_path: dirs.base,
 					path: dirs.keys,
 					spec: spec,
 					password_file: self.accounts_config()?.password_files.first().map(|x| x.to_owned()),
@@ -223,6 +257,7 @@ impl Configuration {
 		} else if self.args.cmd_wallet {
 			let presale_cmd = ImportWallet {
 				iterations: self.args.arg_keys_iterations,
+				base_path: dirs.base,
 				path: dirs.keys,
 				spec: spec,
 				wallet_path: self.args.arg_wallet_import_path.clone().unwrap(),
@@ -291,6 +326,22 @@ impl Configuration {
 					max_balance: self.args.arg_export_state_max_balance.and_then(|s| to_u256(&s).ok()),
 				};
 				Cmd::Blockchain(BlockchainCmd::ExportState(export_cmd))
+			} else if self.args.cmd_export_genesis_spec {
+				let export_cmd = ExportGenesisSpec {
+					spec: spec,
+					cache_config: cache_config,
+					dirs: dirs,
+					file_path: self.args.arg_export_genesis_spec_file.clone(),
+					pruning: pruning,
+					pruning_history: pruning_history,
+					pruning_memory: self.args.arg_pruning_memory,
+					compaction: compaction,
+					wal: wal,
+					tracing: tracing,
+					fat_db: fat_db,
+					at: to_block_id(&self.args.arg_export_genesis_spec_at)?,
+				};
+				Cmd::Blockchain(BlockchainCmd::ExportGenesisSpec(export_cmd))
 			} else {
 				unreachable!();
 			}
@@ -348,6 +399,9 @@ impl Configuration {
 			let verifier_settings = self.verifier_settings();
 			let whisper_config = self.whisper_config();
 			let (private_provider_conf, private_enc_conf, private_tx_enabled) = self.private_provider_config()?;
+			if self.args.arg_snapshot_period == 0 {
+				return Err("--snapshot-period must be greater than 0".into());
+			}
 
 			let run_cmd = RunCmd {
 				cache_config: cache_config,
@@ -394,10 +448,19 @@ impl Configuration {
 				name: self.args.arg_identity,
 				custom_bootnodes: self.args.arg_bootnodes.is_some(),
 				no_periodic_snapshot: self.args.flag_no_periodic_snapshot,
+				snapshot_period: self.args.arg_snapshot_period,
 				check_seal: !self.args.flag_no_seal_check,
 				download_old_blocks: !self.args.flag_no_ancient_blocks,
+				sync_download_memory: self.args.arg_sync_download_memory as usize * 1024 * 1024,
+				vm_memory_limit: self.args.arg_vm_memory_limit as usize * 1024 * 1024,
+				cache_balancing: self.args.flag_cache_balancing,
+				memory_limit: self.args.arg_memory_limit.map(|mb| mb as usize * 1024 * 1024),
+				tracing_max_age: self.args.arg_tracing_max_age,
+				tracing_max_size: self.args.arg_tracing_max_size.map(|mb| mb * 1024 * 1024),
 				verifier_settings: verifier_settings,
 				serve_light: !self.args.flag_no_serve_light,
+				state_healing: self.args.flag_heal_state,
+				tx_rebroadcast_interval: Duration::from_secs(self.args.arg_tx_rebroadcast_interval),
 				light: self.args.flag_light,
 				no_persistent_txqueue: self.args.flag_no_persistent_txqueue,
 				whisper: whisper_config,
@@ -419,11 +482,19 @@ impl Configuration {
 	fn miner_extras(&self) -> Result<MinerExtras, String> {
 		let floor = to_u256(&self.args.arg_gas_floor_target)?;
 		let ceil = to_u256(&self.args.arg_gas_cap)?;
+		if floor > ceil {
+			return Err("--gas-floor-target must not be greater than --gas-cap".into());
+		}
 		let extras = MinerExtras {
 			author: self.author()?,
 			extra_data: self.extra_data()?,
 			gas_range_target: (floor, ceil),
 			engine_signer: self.engine_signer()?,
+			engine_signer_timeout: self.args.arg_engine_signer_timeout.map(Duration::from_millis),
+			engine_signer_timeout_policy: match self.args.flag_engine_signer_retry {
+				true => SignerTimeoutPolicy::Retry,
+				false => SignerTimeoutPolicy::Skip,
+			},
 			work_notify: self.work_notify(),
 		};
 
@@ -556,6 +627,12 @@ impl Configuration {
 
 			tx_queue_penalization: to_queue_penalization(self.args.arg_tx_time_limit)?,
 			tx_queue_strategy: to_queue_strategy(&self.args.arg_tx_queue_strategy)?,
+			tx_queue_gas_price_bump: to_gas_price_bump(&self.args.arg_tx_queue_gas_price_bump)?,
+			tx_queue_max_age: self.args.arg_tx_queue_max_age.map(Duration::from_secs),
+			tx_propagation_min_gas_price: match self.args.arg_tx_propagation_min_gas_price {
+				Some(ref gas_price) => Some(to_u256(gas_price)?),
+				None => None,
+			},
 			refuse_service_transactions: self.args.flag_refuse_service_transactions,
 
 			pool_limits: self.pool_limits()?,
@@ -707,6 +784,14 @@ impl Configuration {
 			return Ok(GasPricerConfig::Fixed(to_u256(dec)?));
 		} else if let Some(dec) = self.args.arg_min_gas_price {
 			return Ok(GasPricerConfig::Fixed(U256::from(dec)));
+		} else if let Some(percentile) = self.args.arg_min_gas_price_percentile {
+			if percentile > 100 {
+				return Err(format!("Invalid minimum gas price percentile: {}, must be at most 100", percentile));
+			}
+			return Ok(GasPricerConfig::Percentile {
+				percentile: percentile,
+				blocks: DEFAULT_SAMPLE_BLOCKS,
+			});
 		} else if self.chain()? != SpecType::Foundation {
 			return Ok(GasPricerConfig::Fixed(U256::zero()));
 		}
@@ -1256,7 +1341,7 @@ mod tests {
 	use updater::{UpdatePolicy, UpdateFilter, ReleaseTrack};
 
 	use account::{AccountCmd, NewAccount, ImportAccounts, ListAccounts};
-	use blockchain::{BlockchainCmd, ImportBlockchain, ExportBlockchain, DataFormat, ExportState};
+	use blockchain::{BlockchainCmd, ImportBlockchain, ExportBlockchain, DataFormat, ExportState, ExportGenesisSpec};
 	use cli::Args;
 	use dir::{Directories, default_hypervisor_path};
 	use helpers::{default_network_config};
@@ -1293,7 +1378,8 @@ mod tests {
 		let args = vec!["parity", "account", "new"];
 		let conf = parse(&args);
 		assert_eq!(conf.into_command().unwrap().cmd, Cmd::Account(AccountCmd::New(NewAccount {
-			iterations: 10240,
+			iterations: None,
+			base_path: Directories::default().base,
 			path: Directories::default().keys,
 			password_file: None,
 			spec: SpecType::default(),
@@ -1328,7 +1414,8 @@ mod tests {
 		let args = vec!["parity", "wallet", "import", "my_wallet.json", "--password", "pwd"];
 		let conf = parse(&args);
 		assert_eq!(conf.into_command().unwrap().cmd, Cmd::ImportPresaleWallet(ImportWallet {
-			iterations: 10240,
+			iterations: None,
+			base_path: Directories::default().base,
 			path: Directories::default().keys,
 			wallet_path: "my_wallet.json".into(),
 			password_file: Some("pwd".into()),
@@ -1384,6 +1471,27 @@ mod tests {
 		})));
 	}
 
+	#[test]
+	fn test_command_db_audit() {
+		let args = vec!["parity", "db", "audit", "--from", "100", "--quarantine", "quarantine"];
+		let conf = parse(&args);
+		assert_eq!(conf.into_command().unwrap().cmd, Cmd::Blockchain(BlockchainCmd::Audit(AuditBlockchain {
+			spec: Default::default(),
+			cache_config: Default::default(),
+			dirs: Default::default(),
+			pruning: Default::default(),
+			pruning_history: 64,
+			pruning_memory: 32,
+			compaction: Default::default(),
+			wal: true,
+			tracing: Default::default(),
+			fat_db: Default::default(),
+			from_block: BlockId::Number(100),
+			to_block: BlockId::Latest,
+			quarantine_path: Some("quarantine".into()),
+		})));
+	}
+
 	#[test]
 	fn test_command_state_export() {
 		let args = vec!["parity", "export", "state", "state.json"];
@@ -1409,6 +1517,37 @@ mod tests {
 		})));
 	}
 
+	#[test]
+	fn test_supervisor() {
+		assert_eq!(parse(&["parity"]).supervisor(vec!["parity".into()]), None);
+
+		let args = vec!["parity", "--supervise", "--base-path", "/tmp/supervised"];
+		let supervisor = parse(&args).supervisor(args.iter().map(|a| a.to_string()).collect()).unwrap();
+		assert_eq!(supervisor.reports_path, PathBuf::from("/tmp/supervised/crash-reports"));
+		assert_eq!(supervisor.config_path, PathBuf::from("/tmp/supervised/config.toml"));
+		assert_eq!(supervisor.args.len(), 4);
+	}
+
+	#[test]
+	fn test_command_genesis_spec_export() {
+		let args = vec!["parity", "export", "genesis-spec", "--at", "100", "fork.json"];
+		let conf = parse(&args);
+		assert_eq!(conf.into_command().unwrap().cmd, Cmd::Blockchain(BlockchainCmd::ExportGenesisSpec(ExportGenesisSpec {
+			spec: Default::default(),
+			cache_config: Default::default(),
+			dirs: Default::default(),
+			file_path: Some("fork.json".into()),
+			pruning: Default::default(),
+			pruning_history: 64,
+			pruning_memory: 32,
+			compaction: Default::default(),
+			wal: true,
+			tracing: Default::default(),
+			fat_db: Default::default(),
+			at: BlockId::Number(100),
+		})));
+	}
+
 	#[test]
 	fn test_command_blockchain_export_with_custom_format() {
 		let args = vec!["parity", "export", "blocks", "--format", "hex", "blockchain.json"];
@@ -1535,11 +1674,20 @@ mod tests {
 			custom_bootnodes: false,
 			fat_db: Default::default(),
 			no_periodic_snapshot: false,
+			snapshot_period: 5000,
 			stratum: None,
 			check_seal: true,
 			download_old_blocks: true,
+			sync_download_memory: 256 * 1024 * 1024,
+			vm_memory_limit: 256 * 1024 * 1024,
+			cache_balancing: false,
+			memory_limit: None,
+			tracing_max_age: None,
+			tracing_max_size: None,
 			verifier_settings: Default::default(),
 			serve_light: true,
+			state_healing: false,
+			tx_rebroadcast_interval: Duration::from_secs(600),
 			light: false,
 			no_hardcoded_sync: false,
 			no_persistent_txqueue: false,
@@ -1572,6 +1720,13 @@ mod tests {
 		assert!(conf.miner_options().is_err());
 	}
 
+	#[test]
+	fn should_fail_on_gas_floor_target_above_gas_cap() {
+		let conf = parse(&["parity", "--gas-floor-target", "8000000", "--gas-cap", "7000000"]);
+
+		assert!(conf.miner_extras().is_err());
+	}
+
 	#[test]
 	fn should_parse_updater_options() {
 		// when
@@ -1866,6 +2021,18 @@ mod tests {
 		assert_eq!(reserved_nodes.unwrap().len(), 1);
 	}
 
+	#[test]
+	fn should_use_gas_price_percentile_for_minimal_gas_price() {
+		let conf = parse(&["parity", "--min-gas-price-percentile", "30"]);
+		assert_eq!(conf.gas_pricer_config(), Ok(GasPricerConfig::Percentile {
+			percentile: 30,
+			blocks: 100,
+		}));
+
+		let conf = parse(&["parity", "--min-gas-price-percentile", "101"]);
+		assert!(conf.gas_pricer_config().is_err());
+	}
+
 	#[test]
 	fn test_dev_preset() {
 		let args = vec!["parity", "--config", "dev"];
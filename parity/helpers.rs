@@ -17,7 +17,9 @@
 use std::io;
 use std::io::{Write, BufReader, BufRead};
 use std::time::Duration;
-use std::fs::File;
+use std::fs::{self, File};
+use std::path::Path;
+use crypto::passwd;
 use ethereum_types::{U256, clean_0x, Address};
 use journaldb::Algorithm;
 use ethcore::client::{Mode, BlockId, VMType, DatabaseCompactionProfile, ClientConfig, VerifierType};
@@ -103,6 +105,22 @@ pub fn to_queue_strategy(s: &str) -> Result<PrioritizationStrategy, String> {
 	}
 }
 
+// largest gas price bump accepted, in percent.
+const MAX_GAS_PRICE_BUMP_PERCENT: f32 = 1000.0;
+
+// parses a gas price bump given in percent into thousandths.
+pub fn to_gas_price_bump(s: &str) -> Result<u32, String> {
+	let thousandths = match s.parse::<f32>() {
+		Ok(percent) if percent.is_finite() && percent <= MAX_GAS_PRICE_BUMP_PERCENT => (percent * 10.0).round(),
+		_ => 0.0,
+	};
+
+	match thousandths >= 1.0 {
+		true => Ok(thousandths as u32),
+		false => Err(format!("Invalid gas price bump '{}' given. Must be a percentage above 0 and at most {}.", s, MAX_GAS_PRICE_BUMP_PERCENT)),
+	}
+}
+
 pub fn to_queue_penalization(time: Option<u64>) -> Result<Penalization, String> {
 	Ok(match time {
 		Some(threshold_ms) => Penalization::Enabled {
@@ -177,6 +195,19 @@ pub fn to_bootnodes(bootnodes: &Option<String>) -> Result<Vec<String>, String> {
 	}
 }
 
+/// Number of iterations to derive keys with. Unless given, it's calibrated to this machine
+/// on first use and persisted in the `base_path` data directory, outside of any keystore.
+pub fn keys_iterations(iterations: Option<u32>, base_path: &Path) -> Result<u32, String> {
+	if let Some(iterations) = iterations {
+		return Ok(iterations);
+	}
+
+	fs::create_dir_all(base_path).map_err(|e| format!("Could not create {}: {}", base_path.display(), e))?;
+	let path = base_path.join("key_iterations");
+	passwd::persisted_iterations(&path, Duration::from_millis(passwd::DEFAULT_TARGET_MS))
+		.map_err(|e| format!("Could not calibrate the number of key iterations in {}: {}", path.display(), e))
+}
+
 #[cfg(test)]
 pub fn default_network_config() -> ::sync::NetworkConfiguration {
 	use sync::{NetworkConfiguration};
@@ -330,7 +361,7 @@ mod tests {
 	use ethereum_types::U256;
 	use ethcore::client::{Mode, BlockId};
 	use ethcore::miner::PendingSet;
-	use super::{to_duration, to_mode, to_block_id, to_u256, to_pending_set, to_address, to_addresses, to_price, geth_ipc_path, to_bootnodes, password_from_file};
+	use super::{to_duration, to_mode, to_block_id, to_u256, to_pending_set, to_address, to_addresses, to_price, to_gas_price_bump, geth_ipc_path, to_bootnodes, password_from_file};
 
 	#[test]
 	fn test_to_duration() {
@@ -445,6 +476,21 @@ but the first password is trimmed
 		assert_eq!(to_price("2.33").unwrap(), 2.33);
 	}
 
+	#[test]
+	fn test_to_gas_price_bump() {
+		assert_eq!(to_gas_price_bump("12.5").unwrap(), 125);
+		assert_eq!(to_gas_price_bump("10").unwrap(), 100);
+		assert_eq!(to_gas_price_bump("0.1").unwrap(), 1);
+		assert_eq!(to_gas_price_bump("1000").unwrap(), 10000);
+		assert!(to_gas_price_bump("0").is_err());
+		assert!(to_gas_price_bump("0.01").is_err());
+		assert!(to_gas_price_bump("-1").is_err());
+		assert!(to_gas_price_bump("1000.1").is_err());
+		assert!(to_gas_price_bump("inf").is_err());
+		assert!(to_gas_price_bump("NaN").is_err());
+		assert!(to_gas_price_bump("ten").is_err());
+	}
+
 	#[test]
 	#[cfg(windows)]
 	fn test_geth_ipc_path() {
@@ -27,6 +27,7 @@ use ethcore::client::{
 	BlockId, BlockChainClient, ChainInfo, BlockInfo, BlockChainInfo,
 	BlockQueueInfo, ChainNotify, ChainRoute, ClientReport, Client, ClientIoMessage
 };
+use ethcore::engines::LivenessAlert;
 use ethcore::header::BlockNumber;
 use ethcore::snapshot::{RestorationStatus, SnapshotService as SS};
 use ethcore::snapshot::service::Service as SnapshotService;
@@ -402,6 +403,14 @@ impl ChainNotify for Informant<FullNodeInformantData> {
 			self.skipped_txs.fetch_add(txs_imported, AtomicOrdering::Relaxed);
 		}
 	}
+
+	fn liveness_alert(&self, alert: &LivenessAlert) {
+		warn!(target: "engine", "Validator {} missed {} consecutive steps before block {}",
+			Colour::White.bold().paint(format!("{}", alert.validator)),
+			Colour::Red.bold().paint(format!("{}", alert.consecutive_missed)),
+			Colour::White.bold().paint(format!("#{}", alert.block)),
+		);
+	}
 }
 
 impl LightChainNotify for Informant<LightNodeInformantData> {
@@ -44,6 +44,7 @@ extern crate toml;
 
 extern crate ethcore;
 extern crate ethcore_bytes as bytes;
+extern crate ethcore_crypto as crypto;
 extern crate ethcore_io as io;
 extern crate ethcore_light as light;
 extern crate ethcore_logger;
@@ -56,6 +57,7 @@ extern crate ethcore_transaction as transaction;
 extern crate ethereum_types;
 extern crate ethkey;
 extern crate kvdb;
+extern crate memory_cache;
 extern crate node_health;
 extern crate panic_hook;
 extern crate parity_hash_fetch as hash_fetch;
@@ -104,6 +106,7 @@ mod helpers;
 mod informant;
 mod light_helpers;
 mod modules;
+mod node;
 mod params;
 mod presale;
 mod rpc;
@@ -112,6 +115,7 @@ mod run;
 mod secretstore;
 mod signer;
 mod snapshot;
+mod supervisor;
 mod upgrade;
 mod url;
 mod user_defaults;
@@ -129,7 +133,9 @@ use deprecated::find_deprecated;
 use ethcore_logger::{Config as LogConfig, setup_log};
 
 pub use self::configuration::Configuration;
+pub use self::node::{EthereumNode, EthereumNodeBuilder, BlockWatcher, TransactionSubmitter, StateReader};
 pub use self::run::RunningClient;
+pub use self::supervisor::Supervisor;
 
 fn print_hash_of(maybe_file: Option<String>) -> Result<String, String> {
 	if let Some(file) = maybe_file {
@@ -35,6 +35,7 @@ use std::io::{self as stdio, Read, Write};
 use std::fs::{remove_file, metadata, File, create_dir_all};
 use std::path::PathBuf;
 use std::sync::Arc;
+use std::sync::atomic::{AtomicBool, Ordering};
 use ctrlc::CtrlC;
 use dir::default_hypervisor_path;
 use fdlimit::raise_fd_limit;
@@ -197,6 +198,17 @@ macro_rules! trace_main {
 fn main() {
 	panic_hook::set();
 
+	// in supervisor mode, the node runs in a child process and is restarted if it crashes.
+	let args = std::env::args().collect::<Vec<_>>();
+	if let Some(supervisor) = parity::Configuration::parse_cli(&args).ok().and_then(|conf| conf.supervisor(args.clone())) {
+		let stopping = Arc::new(AtomicBool::new(false));
+		CtrlC::set_handler({
+			let stopping = stopping.clone();
+			move || stopping.store(true, Ordering::SeqCst)
+		});
+		process::exit(supervisor.run(stopping));
+	}
+
 	// assuming the user is not running with `--force-direct`, then:
 	// if argv[0] == "parity" and this executable != ~/.parity-updates/parity, run that instead.
 	let force_direct = std::env::args().any(|arg| arg == "--force-direct");
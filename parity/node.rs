@@ -0,0 +1,279 @@
+// Copyright 2015-2018 Parity Technologies (UK) Ltd.
+// This file is part of Parity.
+
+// Parity is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// Parity is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with Parity.  If not, see <http://www.gnu.org/licenses/>.
+
+//! Embeddable node.
+//!
+//! `EthereumNode::builder()` assembles the client, sync, transaction pool and RPC servers the
+//! same way the `parity` binary does, and hands out typed handles for the common integrations:
+//! watching new blocks, submitting transactions and reading the latest state.
+
+use std::sync::{mpsc, Arc, Weak};
+use std::time::Duration;
+
+use bytes::Bytes;
+use ethcore::client::{Client, BlockChainClient, BlockId, ChainInfo, ChainNotify, ChainRoute, Nonce, Balance};
+use ethcore::miner::MinerService;
+use ethereum_types::{Address, H256, U256};
+use parking_lot::Mutex;
+use transaction::{SignedTransaction, UnverifiedTransaction};
+
+use configuration::Configuration;
+use run::RunningClient;
+use {start, ExecutionAction};
+
+/// Builder of an `EthereumNode`.
+///
+/// Every option maps to the command line flag of the same meaning, anything not set uses the
+/// defaults of the `parity` binary.
+#[derive(Debug, Clone)]
+pub struct EthereumNodeBuilder {
+	args: Vec<String>,
+}
+
+impl EthereumNodeBuilder {
+	/// Chain to run: a built-in chain name or the path to a chain specification file.
+	pub fn chain<S: Into<String>>(self, chain: S) -> Self {
+		self.arg("--chain", chain.into())
+	}
+
+	/// Directory the node keeps its databases, keys and configuration in.
+	pub fn data_dir<S: Into<String>>(self, path: S) -> Self {
+		self.arg("--base-path", path.into())
+	}
+
+	/// Serve JSON-RPC over HTTP on the given interface and port, or not at all if `None`.
+	pub fn rpc(self, endpoint: Option<(&str, u16)>) -> Self {
+		match endpoint {
+			Some((interface, port)) => self
+				.arg("--jsonrpc-interface", interface.into())
+				.arg("--jsonrpc-port", port.to_string()),
+			None => self.flag("--no-jsonrpc"),
+		}
+	}
+
+	/// Any other command line flag, e.g. `("--port", "30304")`.
+	pub fn arg<S: Into<String>>(mut self, name: &str, value: S) -> Self {
+		self.args.push(name.into());
+		self.args.push(value.into());
+		self
+	}
+
+	/// Any other command line switch, e.g. `"--no-ws"`.
+	pub fn flag(mut self, name: &str) -> Self {
+		self.args.push(name.into());
+		self
+	}
+
+	fn configuration(&self) -> Result<Configuration, String> {
+		Configuration::parse_cli(&self.args).map_err(|e| format!("Invalid configuration: {:?}", e))
+	}
+
+	/// Start the node.
+	pub fn build(self) -> Result<EthereumNode, String> {
+		let conf = self.configuration()?;
+		match start(conf, |_| {}, || {})? {
+			ExecutionAction::Running(client) => Ok(EthereumNode { client: client }),
+			ExecutionAction::Instant(_) => Err("The configuration doesn't start a node.".into()),
+		}
+	}
+}
+
+/// A running node, stopped with `shutdown`.
+pub struct EthereumNode {
+	client: RunningClient,
+}
+
+impl EthereumNode {
+	/// Builder of a node with the default configuration.
+	pub fn builder() -> EthereumNodeBuilder {
+		EthereumNodeBuilder {
+			args: vec!["parity".into()],
+		}
+	}
+
+	// full client the handles are built on, light nodes don't provide them.
+	fn full_client(&self) -> Result<Arc<Client>, String> {
+		self.client.full_client().ok_or_else(|| "Not available for a light client.".into())
+	}
+
+	/// Handle receiving the hashes of blocks added to the canonical chain.
+	/// Blocks are forwarded to it until it's dropped.
+	pub fn block_watcher(&self) -> Result<BlockWatcher, String> {
+		let client = self.full_client()?;
+		let (tx, rx) = mpsc::channel();
+		let forwarder: Arc<ChainNotify> = Arc::new(BlockForwarder { sender: Mutex::new(tx) });
+		client.add_notify(forwarder.clone());
+		Ok(BlockWatcher {
+			receiver: rx,
+			forwarder: forwarder,
+			client: Arc::downgrade(&client),
+		})
+	}
+
+	/// Handle submitting transactions to the local transaction pool.
+	pub fn transaction_submitter(&self) -> Result<TransactionSubmitter, String> {
+		self.full_client().map(|client| TransactionSubmitter { client: client })
+	}
+
+	/// Handle reading the state at the best block.
+	pub fn state_reader(&self) -> Result<StateReader, String> {
+		self.full_client().map(|client| StateReader { client: client })
+	}
+
+	/// Performs a synchronous RPC query.
+	pub fn rpc_query_sync(&self, request: &str) -> Option<String> {
+		self.client.rpc_query_sync(request)
+	}
+
+	/// Shuts down the node, blocking until the client is dropped.
+	pub fn shutdown(self) {
+		self.client.shutdown()
+	}
+}
+
+// forwards enacted blocks to a `BlockWatcher`.
+struct BlockForwarder {
+	sender: Mutex<mpsc::Sender<H256>>,
+}
+
+impl ChainNotify for BlockForwarder {
+	fn new_blocks(&self, _imported: Vec<H256>, _invalid: Vec<H256>, route: ChainRoute, _sealed: Vec<H256>, _proposed: Vec<Bytes>, _duration: Duration) {
+		let sender = self.sender.lock();
+		for hash in route.enacted() {
+			// the watcher may have been dropped.
+			let _ = sender.send(*hash);
+		}
+	}
+}
+
+/// Hashes of blocks added to the canonical chain, in order.
+/// Stops watching the client when dropped.
+pub struct BlockWatcher {
+	receiver: mpsc::Receiver<H256>,
+	// the client only keeps a weak reference to its notify targets.
+	forwarder: Arc<ChainNotify>,
+	// doesn't keep the client alive, so that the node can shut down.
+	client: Weak<Client>,
+}
+
+impl Drop for BlockWatcher {
+	fn drop(&mut self) {
+		if let Some(client) = self.client.upgrade() {
+			client.remove_notify(&self.forwarder);
+		}
+	}
+}
+
+impl BlockWatcher {
+	/// Next block hash, if one was added since the last call.
+	pub fn try_next(&self) -> Option<H256> {
+		self.receiver.try_recv().ok()
+	}
+
+	/// Next block hash, waiting at most `timeout` for it.
+	pub fn next_timeout(&self, timeout: Duration) -> Option<H256> {
+		self.receiver.recv_timeout(timeout).ok()
+	}
+}
+
+impl Iterator for BlockWatcher {
+	type Item = H256;
+
+	/// Next block hash, blocking until it's added.
+	fn next(&mut self) -> Option<H256> {
+		self.receiver.recv().ok()
+	}
+}
+
+/// Submits signed transactions to the local transaction pool.
+#[derive(Clone)]
+pub struct TransactionSubmitter {
+	client: Arc<Client>,
+}
+
+impl TransactionSubmitter {
+	/// Submit an RLP-encoded signed transaction, returning its hash.
+	pub fn submit_raw(&self, raw: &[u8]) -> Result<H256, String> {
+		let transaction: UnverifiedTransaction = ::rlp::decode(raw).map_err(|e| format!("Invalid transaction RLP: {}", e))?;
+		let signed = SignedTransaction::new(transaction).map_err(|e| format!("Invalid transaction signature: {}", e))?;
+		self.submit(signed)
+	}
+
+	/// Submit a signed transaction, returning its hash.
+	pub fn submit(&self, transaction: SignedTransaction) -> Result<H256, String> {
+		let hash = transaction.hash();
+		self.client.miner().import_own_transaction(&*self.client, transaction.into())
+			.map(|_| hash)
+			.map_err(|e| format!("Transaction rejected: {}", e))
+	}
+}
+
+/// Reads accounts at the best block.
+#[derive(Clone)]
+pub struct StateReader {
+	client: Arc<Client>,
+}
+
+impl StateReader {
+	/// Hash of the best block.
+	pub fn best_block_hash(&self) -> H256 {
+		self.client.chain_info().best_block_hash
+	}
+
+	/// Balance of an account.
+	pub fn balance(&self, address: &Address) -> U256 {
+		self.client.latest_balance(address)
+	}
+
+	/// Nonce of an account.
+	pub fn nonce(&self, address: &Address) -> U256 {
+		self.client.latest_nonce(address)
+	}
+
+	/// Code of an account, `None` if it has none.
+	pub fn code(&self, address: &Address) -> Option<Bytes> {
+		self.client.code(address, BlockId::Latest.into()).and_then(|code| code)
+	}
+
+	/// Value of a storage slot of an account.
+	pub fn storage_at(&self, address: &Address, key: &H256) -> H256 {
+		self.client.storage_at(address, key, BlockId::Latest.into()).unwrap_or_default()
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::EthereumNode;
+
+	#[test]
+	fn builder_produces_cli_configuration() {
+		let builder = EthereumNode::builder()
+			.chain("dev")
+			.data_dir("/tmp/embedded")
+			.rpc(Some(("127.0.0.1", 8555)))
+			.flag("--no-ws");
+		let conf = builder.configuration().unwrap();
+
+		assert_eq!(conf.args.arg_chain, "dev");
+		assert_eq!(conf.args.arg_base_path, Some("/tmp/embedded".into()));
+		assert_eq!(conf.args.arg_jsonrpc_interface, "127.0.0.1");
+		assert_eq!(conf.args.arg_jsonrpc_port, 8555);
+		assert!(conf.args.flag_no_ws);
+
+		let conf = EthereumNode::builder().rpc(None).configuration().unwrap();
+		assert!(conf.args.flag_no_jsonrpc);
+	}
+}
@@ -15,9 +15,11 @@
 // along with Parity.  If not, see <http://www.gnu.org/licenses/>.
 
 use std::{str, fs, fmt};
+use std::io::Read;
 use std::time::Duration;
 
 use ethcore::client::Mode;
+use ethcore::engines::SignerTimeoutPolicy;
 use ethcore::ethereum;
 use ethcore::spec::{Spec, SpecParams};
 use ethereum_types::{U256, Address};
@@ -117,6 +119,32 @@ impl SpecType {
 		}
 	}
 
+	/// JSON the spec is loaded from.
+	pub fn json(&self) -> Result<Vec<u8>, String> {
+		let bundled = match *self {
+			SpecType::Foundation => ethereum::bundled::FOUNDATION,
+			SpecType::Morden => ethereum::bundled::MORDEN,
+			SpecType::Ropsten => ethereum::bundled::ROPSTEN,
+			SpecType::Olympic => ethereum::bundled::OLYMPIC,
+			SpecType::Classic => ethereum::bundled::CLASSIC,
+			SpecType::Expanse => ethereum::bundled::EXPANSE,
+			SpecType::Musicoin => ethereum::bundled::MUSICOIN,
+			SpecType::Ellaism => ethereum::bundled::ELLAISM,
+			SpecType::Easthub => ethereum::bundled::EASTHUB,
+			SpecType::Social => ethereum::bundled::SOCIAL,
+			SpecType::Kovan => ethereum::bundled::KOVAN,
+			SpecType::Dev => ethereum::bundled::INSTANT_SEAL,
+			SpecType::Custom(ref filename) => {
+				let mut json = Vec::new();
+				fs::File::open(filename)
+					.and_then(|mut file| file.read_to_end(&mut json))
+					.map_err(|e| format!("Could not load specification file at {}: {}", filename, e))?;
+				return Ok(json);
+			}
+		};
+		Ok(bundled.to_vec())
+	}
+
 	pub fn legacy_fork_name(&self) -> Option<String> {
 		match *self {
 			SpecType::Classic => Some("classic".to_owned()),
@@ -197,7 +225,7 @@ impl str::FromStr for ResealPolicy {
 
 #[derive(Debug, PartialEq)]
 pub struct AccountsConfig {
-	pub iterations: u32,
+	pub iterations: Option<u32>,
 	pub refresh_time: u64,
 	pub testnet: bool,
 	pub password_files: Vec<String>,
@@ -209,7 +237,7 @@ pub struct AccountsConfig {
 impl Default for AccountsConfig {
 	fn default() -> Self {
 		AccountsConfig {
-			iterations: 10240,
+			iterations: None,
 			refresh_time: 5,
 			testnet: false,
 			password_files: Vec::new(),
@@ -226,7 +254,11 @@ pub enum GasPricerConfig {
 	Calibrated {
 		usd_per_tx: f32,
 		recalibration_period: Duration,
-	}
+	},
+	Percentile {
+		percentile: usize,
+		blocks: usize,
+	},
 }
 
 impl Default for GasPricerConfig {
@@ -251,7 +283,8 @@ impl GasPricerConfig {
 					fetch,
 					p,
 				)
-			}
+			},
+			GasPricerConfig::Percentile { percentile, blocks } => GasPricer::new_percentile(percentile, blocks),
 		}
 	}
 }
@@ -260,6 +293,8 @@ impl GasPricerConfig {
 pub struct MinerExtras {
 	pub author: Address,
 	pub engine_signer: Address,
+	pub engine_signer_timeout: Option<Duration>,
+	pub engine_signer_timeout_policy: SignerTimeoutPolicy,
 	pub extra_data: Vec<u8>,
 	pub gas_range_target: (U256, U256),
 	pub work_notify: Vec<String>,
@@ -270,6 +305,8 @@ impl Default for MinerExtras {
 		MinerExtras {
 			author: Default::default(),
 			engine_signer: Default::default(),
+			engine_signer_timeout: None,
+			engine_signer_timeout_policy: SignerTimeoutPolicy::Skip,
 			extra_data: version_data(),
 			gas_range_target: (4_700_000.into(), 6_283_184.into()),
 			work_notify: Default::default(),
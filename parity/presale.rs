@@ -14,15 +14,17 @@
 // You should have received a copy of the GNU General Public License
 // along with Parity.  If not, see <http://www.gnu.org/licenses/>.
 
+use std::path::Path;
 use ethcore::ethstore::{PresaleWallet, EthStore};
 use ethcore::ethstore::accounts_dir::RootDiskDirectory;
 use ethcore::account_provider::{AccountProvider, AccountProviderSettings};
-use helpers::{password_prompt, password_from_file};
+use helpers::{password_prompt, password_from_file, keys_iterations};
 use params::SpecType;
 
 #[derive(Debug, PartialEq)]
 pub struct ImportWallet {
-	pub iterations: u32,
+	pub iterations: Option<u32>,
+	pub base_path: String,
 	pub path: String,
 	pub spec: SpecType,
 	pub wallet_path: String,
@@ -36,7 +38,8 @@ pub fn execute(cmd: ImportWallet) -> Result<String, String> {
 	};
 
 	let dir = Box::new(RootDiskDirectory::create(cmd.path).unwrap());
-	let secret_store = Box::new(EthStore::open_with_iterations(dir, cmd.iterations).unwrap());
+	let iterations = keys_iterations(cmd.iterations, Path::new(&cmd.base_path))?;
+	let secret_store = Box::new(EthStore::open_with_iterations(dir, iterations).unwrap());
 	let acc_provider = AccountProvider::new(secret_store, AccountProviderSettings::default());
 	let wallet = PresaleWallet::open(cmd.wallet_path).map_err(|_| "Unable to open presale wallet.")?;
 	let kp = wallet.decrypt(&password).map_err(|_| "Invalid password.")?;
@@ -308,6 +308,14 @@ impl FullDependencies {
 						self.miner.add_transactions_listener(Box::new(move |hashes| if let Some(h) = h.upgrade() {
 							h.new_transactions(hashes);
 						}));
+						let h = client.handler();
+						self.miner.add_dropped_transactions_listener(Box::new(move |hashes| if let Some(h) = h.upgrade() {
+							h.dropped_transactions(hashes);
+						}));
+						let h = client.handler();
+						self.miner.add_removed_transactions_listener(Box::new(move |hashes| if let Some(h) = h.upgrade() {
+							h.removed_transactions(hashes);
+						}));
 
 						if let Some(h) = client.handler().upgrade() {
 							self.client.add_notify(h);
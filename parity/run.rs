@@ -16,6 +16,7 @@
 
 use std::any::Any;
 use std::fmt;
+use std::path::Path;
 use std::sync::{Arc, Weak};
 use std::time::{Duration, Instant};
 use std::thread;
@@ -49,7 +50,7 @@ use params::{
 	SpecType, Pruning, AccountsConfig, GasPricerConfig, MinerExtras, Switch,
 	tracing_switch_to_bool, fatdb_switch_to_bool, mode_switch_to_bool
 };
-use helpers::{to_client_config, execute_upgrades, passwords_from_files};
+use helpers::{to_client_config, execute_upgrades, passwords_from_files, keys_iterations};
 use upgrade::upgrade_key_location;
 use dir::{Directories, DatabaseDirectories};
 use cache::CacheConfig;
@@ -64,9 +65,6 @@ use secretstore;
 use signer;
 use db;
 
-// how often to take periodic snapshots.
-const SNAPSHOT_PERIOD: u64 = 5000;
-
 // how many blocks to wait before starting a periodic snapshot.
 const SNAPSHOT_HISTORY: u64 = 100;
 
@@ -74,6 +72,15 @@ const SNAPSHOT_HISTORY: u64 = 100;
 // Light client only.
 const GAS_CORPUS_EXPIRATION_MINUTES: u64 = 60 * 6;
 
+// How often the memory usage is checked against `--memory-limit`.
+const MEMORY_WATCHDOG_INTERVAL: Duration = Duration::from_secs(5);
+
+// Share of the caches dropped on each check while the memory usage is too high.
+const MEMORY_WATCHDOG_SHED_PERCENT: usize = 25;
+
+// Once above `--memory-limit`, caches are shed until the memory usage drops below this share of it.
+const MEMORY_WATCHDOG_LOW_WATER_PERCENT: usize = 90;
+
 // Pops along with error messages when a password is missing or invalid.
 const VERIFY_PASSWORD_HINT: &'static str = "Make sure valid password is present in files passed using `--password` or in the configuration file.";
 
@@ -124,10 +131,19 @@ pub struct RunCmd {
 	pub custom_bootnodes: bool,
 	pub stratum: Option<stratum::Options>,
 	pub no_periodic_snapshot: bool,
+	pub snapshot_period: u64,
 	pub check_seal: bool,
 	pub download_old_blocks: bool,
+	pub sync_download_memory: usize,
+	pub vm_memory_limit: usize,
+	pub cache_balancing: bool,
+	pub memory_limit: Option<usize>,
+	pub tracing_max_age: Option<u64>,
+	pub tracing_max_size: Option<u64>,
 	pub verifier_settings: VerifierSettings,
 	pub serve_light: bool,
+	pub state_healing: bool,
+	pub tx_rebroadcast_interval: Duration,
 	pub light: bool,
 	pub no_persistent_txqueue: bool,
 	pub whisper: ::whisper::Config,
@@ -255,6 +271,7 @@ fn execute_light_impl(cmd: RunCmd, logger: Arc<RotatingLogger>) -> Result<Runnin
 		subprotocol_name: sync::LIGHT_PROTOCOL,
 		handlers: vec![on_demand.clone()],
 		attached_protos: attached_protos,
+		schedule: Default::default(),
 	};
 	let light_sync = LightSync::new(sync_params).map_err(|e| format!("Error starting network: {}", e))?;
 	let light_sync = Arc::new(light_sync);
@@ -507,7 +524,10 @@ fn execute_impl<Cr, Rr>(cmd: RunCmd, logger: Arc<RotatingLogger>, on_client_rq:
 		_ => sync::WarpSync::Disabled,
 	};
 	sync_config.download_old_blocks = cmd.download_old_blocks;
+	sync_config.download_memory_budget = cmd.sync_download_memory;
 	sync_config.serve_light = cmd.serve_light;
+	sync_config.state_healing = cmd.state_healing;
+	sync_config.local_transactions_rebroadcast_interval = cmd.tx_rebroadcast_interval;
 
 	let passwords = passwords_from_files(&cmd.acc_conf.password_files)?;
 
@@ -537,6 +557,7 @@ fn execute_impl<Cr, Rr>(cmd: RunCmd, logger: Arc<RotatingLogger>, on_client_rq:
 			WorkPoster::new(&cmd.miner_extras.work_notify, fetch.clone(), event_loop.remote())
 		));
 	}
+	spec.engine.set_signer_timeout(cmd.miner_extras.engine_signer_timeout, cmd.miner_extras.engine_signer_timeout_policy);
 	let engine_signer = cmd.miner_extras.engine_signer;
 	if engine_signer != Default::default() {
 		// Check if engine signer exists
@@ -578,6 +599,10 @@ fn execute_impl<Cr, Rr>(cmd: RunCmd, logger: Arc<RotatingLogger>, on_client_rq:
 	);
 
 	client_config.queue.verifier_settings = cmd.verifier_settings;
+	client_config.tracing.max_age = cmd.tracing_max_age;
+	client_config.tracing.max_size = cmd.tracing_max_size;
+	client_config.vm_memory_limit = Some(cmd.vm_memory_limit);
+	client_config.balance_caches = cmd.cache_balancing;
 
 	// set up bootnodes
 	let mut net_conf = cmd.net_conf;
@@ -612,7 +637,7 @@ fn execute_impl<Cr, Rr>(cmd: RunCmd, logger: Arc<RotatingLogger>, on_client_rq:
 	// take handle to client
 	let client = service.client();
 	// Update miners block gas limit
-	miner.update_transaction_queue_limits(*client.best_block_header().gas_limit());
+	miner.update_transaction_queue_limits(&*client, *client.best_block_header().gas_limit());
 
 	// take handle to private transactions service
 	let private_tx_service = service.private_tx_service();
@@ -868,7 +893,7 @@ fn execute_impl<Cr, Rr>(cmd: RunCmd, logger: Arc<RotatingLogger>, on_client_rq:
 				service.client(),
 				move || is_major_importing(Some(sync.status().state), client.queue_info()),
 				service.io().channel(),
-				SNAPSHOT_PERIOD,
+				cmd.snapshot_period,
 				SNAPSHOT_HISTORY,
 			));
 
@@ -877,6 +902,13 @@ fn execute_impl<Cr, Rr>(cmd: RunCmd, logger: Arc<RotatingLogger>, on_client_rq:
 		},
 	};
 
+	// the memory watchdog stops when dropped.
+	let memory_watchdog = match cmd.memory_limit {
+		Some(limit) => Some(memory_cache::pressure::Watchdog::start(limit, limit / 100 * MEMORY_WATCHDOG_LOW_WATER_PERCENT, MEMORY_WATCHDOG_INTERVAL, MEMORY_WATCHDOG_SHED_PERCENT)
+			.map_err(|e| format!("Failed to start memory watchdog: {}", e))?),
+		None => None,
+	};
+
 	client.set_exit_handler(on_client_rq);
 	updater.set_exit_handler(on_updater_rq);
 
@@ -886,7 +918,7 @@ fn execute_impl<Cr, Rr>(cmd: RunCmd, logger: Arc<RotatingLogger>, on_client_rq:
 			informant,
 			client,
 			client_service: Arc::new(service),
-			keep_alive: Box::new((watcher, updater, ws_server, http_server, ipc_server, ui_server, secretstore_key_server, ipfs_server, event_loop)),
+			keep_alive: Box::new((watcher, updater, ws_server, http_server, ipc_server, ui_server, secretstore_key_server, ipfs_server, event_loop, memory_watchdog)),
 		}
 	})
 }
@@ -934,6 +966,14 @@ impl RunningClient {
 		}
 	}
 
+	/// The full client, if not running as a light client.
+	pub fn full_client(&self) -> Option<Arc<Client>> {
+		match self.inner {
+			RunningClientInner::Light { .. } => None,
+			RunningClientInner::Full { ref client, .. } => Some(client.clone()),
+		}
+	}
+
 	/// Shuts down the client.
 	pub fn shutdown(self) {
 		match self.inner {
@@ -1033,7 +1073,8 @@ fn prepare_account_provider(spec: &SpecType, dirs: &Directories, data_dir: &str,
 		},
 	};
 
-	let ethstore = EthStore::open_with_iterations(dir, cfg.iterations).map_err(|e| format!("Could not open keys directory: {}", e))?;
+	let iterations = keys_iterations(cfg.iterations, Path::new(&dirs.base))?;
+	let ethstore = EthStore::open_with_iterations(dir, iterations).map_err(|e| format!("Could not open keys directory: {}", e))?;
 	if cfg.refresh_time > 0 {
 		ethstore.set_refresh_time(::std::time::Duration::from_secs(cfg.refresh_time));
 	}
@@ -153,9 +153,9 @@ mod server {
 						return Err(format!("No password found for the secret store node account {}", account));
 					}
 
-					// Attempt to sign in the engine signer.
+					// Find the password of the account.
 					let password = deps.accounts_passwords.iter()
-						.find(|p| deps.account_provider.sign(account.clone(), Some((*p).clone()), Default::default()).is_ok())
+						.find(|p| deps.account_provider.test_password(&account, p).unwrap_or(false))
 						.ok_or_else(|| format!("No valid password for the secret store node account {}", account))?;
 					Arc::new(ethcore_secretstore::KeyStoreNodeKeyPair::new(deps.account_provider, account, password.clone())
 						.map_err(|e| format!("{}", e))?)
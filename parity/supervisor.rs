@@ -0,0 +1,254 @@
+// Copyright 2015-2018 Parity Technologies (UK) Ltd.
+// This file is part of Parity.
+
+// Parity is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// Parity is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with Parity.  If not, see <http://www.gnu.org/licenses/>.
+
+//! Supervisor mode: runs the node in a child process and restarts it when it exits abnormally.
+//!
+//! Every abnormal exit leaves a crash report bundle behind, holding the last lines logged by
+//! the node (with the panic message and backtrace, if any), its exit status, the command line
+//! and the configuration file it was started with. Secrets are redacted from the latter two.
+
+use std::collections::VecDeque;
+use std::{cmp, env, fs, thread};
+use std::io::{self, BufRead, BufReader, Read, Write};
+use std::path::PathBuf;
+use std::process::{Command, ExitStatus, Stdio};
+use std::sync::Arc;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::time::{Duration, Instant, SystemTime, UNIX_EPOCH};
+use parking_lot::Mutex;
+
+// flag turning the supervisor on, not passed on to the node.
+const SUPERVISE_FLAG: &'static str = "--supervise";
+
+// options whose values are secrets, redacted from crash reports.
+const SECRET_OPTIONS: &'static [&'static str] = &["--node-key", "--nodekey", "--secretstore-secret", "--stratum-secret", "--dapps-pass"];
+// configuration file keys whose values are secrets, redacted from crash reports.
+const SECRET_CONFIG_KEYS: &'static [&'static str] = &["node_key", "self_secret", "secret", "pass"];
+const REDACTED: &'static str = "<redacted>";
+
+// number of log lines kept for crash reports.
+const LOG_TAIL_LINES: usize = 1000;
+// delay before the first restart, doubled after every crash.
+const MIN_BACKOFF_SECS: u64 = 1;
+const MAX_BACKOFF_SECS: u64 = 300;
+// the backoff is reset once the node ran for this long.
+const STABLE_RUN_SECS: u64 = 600;
+
+/// Last lines written by the node.
+struct LogTail {
+	lines: VecDeque<String>,
+	max: usize,
+}
+
+impl LogTail {
+	fn new(max: usize) -> Self {
+		LogTail {
+			lines: VecDeque::with_capacity(max),
+			max: max,
+		}
+	}
+
+	fn push(&mut self, line: String) {
+		if self.lines.len() == self.max {
+			self.lines.pop_front();
+		}
+		self.lines.push_back(line);
+	}
+}
+
+/// Supervisor of a node process.
+#[derive(Debug, PartialEq)]
+pub struct Supervisor {
+	/// Command line the supervisor was started with.
+	pub args: Vec<String>,
+	/// Directory crash reports are written to.
+	pub reports_path: PathBuf,
+	/// Configuration file of the node.
+	pub config_path: PathBuf,
+}
+
+impl Supervisor {
+	/// Run the node until it exits successfully or `stopping` is set. Returns the exit code.
+	pub fn run(self, stopping: Arc<AtomicBool>) -> i32 {
+		let mut backoff = MIN_BACKOFF_SECS;
+		let mut restarts = 0usize;
+
+		loop {
+			let started = Instant::now();
+			let (status, tail) = match self.run_node() {
+				Ok(result) => result,
+				Err(e) => {
+					let _ = writeln!(io::stderr(), "Supervisor: couldn't start the node: {}", e);
+					return 1;
+				},
+			};
+			let uptime = started.elapsed();
+
+			if status.success() || stopping.load(Ordering::SeqCst) {
+				return status.code().unwrap_or(0);
+			}
+
+			match self.write_report(&status, &tail, uptime, restarts) {
+				Ok(path) => { let _ = writeln!(io::stderr(), "Supervisor: node exited abnormally ({}), crash report written to {}", status, path.display()); },
+				Err(e) => { let _ = writeln!(io::stderr(), "Supervisor: node exited abnormally ({}), couldn't write crash report: {}", status, e); },
+			}
+
+			if uptime >= Duration::from_secs(STABLE_RUN_SECS) {
+				backoff = MIN_BACKOFF_SECS;
+			}
+
+			let _ = writeln!(io::stderr(), "Supervisor: restarting the node in {}s", backoff);
+			for _ in 0..backoff {
+				if stopping.load(Ordering::SeqCst) {
+					return 1;
+				}
+				thread::sleep(Duration::from_secs(1));
+			}
+
+			backoff = cmp::min(backoff * 2, MAX_BACKOFF_SECS);
+			restarts += 1;
+		}
+	}
+
+	// run the node once, passing its standard error through.
+	fn run_node(&self) -> io::Result<(ExitStatus, Vec<String>)> {
+		let mut child = Command::new(env::current_exe()?)
+			.args(node_args(&self.args))
+			.stderr(Stdio::piped())
+			.spawn()?;
+
+		let tail = Arc::new(Mutex::new(LogTail::new(LOG_TAIL_LINES)));
+		let stderr = child.stderr.take().expect("stderr is piped; qed");
+		let reader = {
+			let tail = tail.clone();
+			thread::Builder::new().name("supervisor-log".into()).spawn(move || {
+				let mut stderr = BufReader::new(stderr);
+				let mut line = Vec::new();
+				while stderr.read_until(b'\n', &mut line).map(|read| read > 0).unwrap_or(false) {
+					let _ = io::stderr().write_all(&line);
+					tail.lock().push(String::from_utf8_lossy(&line).trim_right().to_owned());
+					line.clear();
+				}
+			})?
+		};
+
+		let status = child.wait()?;
+		let _ = reader.join();
+		let lines = tail.lock().lines.drain(..).collect();
+		Ok((status, lines))
+	}
+
+	fn write_report(&self, status: &ExitStatus, tail: &[String], uptime: Duration, restarts: usize) -> io::Result<PathBuf> {
+		let now = SystemTime::now().duration_since(UNIX_EPOCH).map(|d| d.as_secs()).unwrap_or(0);
+		let mut path = self.reports_path.clone();
+		path.push(format!("crash-{}", now));
+		fs::create_dir_all(&path)?;
+
+		let mut log = fs::File::create(path.join("log.txt"))?;
+		for line in tail {
+			writeln!(log, "{}", line)?;
+		}
+
+		let mut report = fs::File::create(path.join("status.txt"))?;
+		writeln!(report, "exit status: {}", status)?;
+		writeln!(report, "uptime: {}s", uptime.as_secs())?;
+		writeln!(report, "previous restarts: {}", restarts)?;
+		writeln!(report, "version: {}", ::parity_version::version())?;
+
+		let mut command = fs::File::create(path.join("command.txt"))?;
+		writeln!(command, "{}", redact_args(&self.args).join(" "))?;
+
+		if self.config_path.exists() {
+			let mut config = String::new();
+			fs::File::open(&self.config_path)?.read_to_string(&mut config)?;
+			fs::File::create(path.join("config.toml"))?.write_all(redact_config(&config).as_bytes())?;
+		}
+
+		Ok(path)
+	}
+}
+
+// arguments the node is started with.
+fn node_args(args: &[String]) -> Vec<String> {
+	args.iter().skip(1).filter(|arg| *arg != SUPERVISE_FLAG).cloned().collect()
+}
+
+// command line with the values of secret options replaced.
+fn redact_args(args: &[String]) -> Vec<String> {
+	let mut redacted = Vec::with_capacity(args.len());
+	let mut secret_next = false;
+	for arg in args {
+		if secret_next {
+			redacted.push(REDACTED.to_owned());
+			secret_next = false;
+			continue;
+		}
+
+		let option = arg.split('=').next().unwrap_or("");
+		if SECRET_OPTIONS.contains(&option) {
+			if arg.contains('=') {
+				redacted.push(format!("{}={}", option, REDACTED));
+			} else {
+				redacted.push(arg.clone());
+				secret_next = true;
+			}
+		} else {
+			redacted.push(arg.clone());
+		}
+	}
+	redacted
+}
+
+// configuration file with the values of secret keys replaced.
+fn redact_config(config: &str) -> String {
+	config.lines().map(|line| {
+		let key = line.split('=').next().unwrap_or("").trim();
+		if line.contains('=') && SECRET_CONFIG_KEYS.contains(&key) {
+			format!("{} = \"{}\"", key, REDACTED)
+		} else {
+			line.to_owned()
+		}
+	}).collect::<Vec<_>>().join("\n")
+}
+
+#[cfg(test)]
+mod tests {
+	use super::{node_args, redact_args, redact_config, LogTail};
+
+	#[test]
+	fn strips_supervise_flag() {
+		let args: Vec<String> = vec!["parity", "--supervise", "--chain", "kovan"].into_iter().map(Into::into).collect();
+		assert_eq!(node_args(&args), vec!["--chain".to_owned(), "kovan".to_owned()]);
+	}
+
+	#[test]
+	fn keeps_last_lines() {
+		let mut tail = LogTail::new(2);
+		for line in &["a", "b", "c"] {
+			tail.push(line.to_string());
+		}
+		assert_eq!(tail.lines, vec!["b".to_owned(), "c".to_owned()]);
+	}
+
+	#[test]
+	fn redacts_secrets() {
+		let args: Vec<String> = vec!["parity", "--node-key", "0xdead", "--secretstore-secret=0xbeef", "--chain", "kovan"].into_iter().map(Into::into).collect();
+		assert_eq!(redact_args(&args).join(" "), "parity --node-key <redacted> --secretstore-secret=<redacted> --chain kovan");
+
+		let config = "[network]\nnode_key = \"0xdead\"\nport = 30303\n[secretstore]\n  self_secret=\"0xbeef\"";
+		assert_eq!(redact_config(config), "[network]\nnode_key = \"<redacted>\"\nport = 30303\n[secretstore]\nself_secret = \"<redacted>\"");
+	}
+}
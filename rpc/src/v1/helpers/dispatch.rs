@@ -36,7 +36,8 @@ use sync::LightSync;
 use ethcore::ids::BlockId;
 use ethcore::client::BlockChainClient;
 use ethcore::miner::{self, MinerService};
-use ethcore::account_provider::AccountProvider;
+use miner::gas_pricer::DEFAULT_SAMPLE_BLOCKS;
+use ethcore::account_provider::{AccountProvider, KeyOperation};
 use crypto::DEFAULT_MAC;
 use transaction::{Action, SignedTransaction, PendingTransaction, Transaction};
 
@@ -51,6 +52,7 @@ use v1::types::{
 	ConfirmationResponse,
 	SignRequest as RpcSignRequest,
 	DecryptRequest as RpcDecryptRequest,
+	Origin,
 };
 
 pub use self::nonce::Reservations;
@@ -68,7 +70,9 @@ pub trait Dispatcher: Send + Sync + Clone {
 		-> BoxFuture<FilledTransactionRequest>;
 
 	/// Sign the given transaction request without dispatching, fetching appropriate nonce.
-	fn sign(&self, accounts: Arc<AccountProvider>, filled: FilledTransactionRequest, password: SignWith)
+	///
+	/// The signing is recorded in the key usage log of the sender's account, along with the `origin` of the request.
+	fn sign(&self, accounts: Arc<AccountProvider>, filled: FilledTransactionRequest, password: SignWith, origin: Origin)
 		-> BoxFuture<WithToken<SignedTransaction>>;
 
 	/// Converts a `SignedTransaction` into `RichRawTransaction`
@@ -159,19 +163,19 @@ impl<C: miner::BlockChainClient + BlockChainClient, M: MinerService> Dispatcher
 		}))
 	}
 
-	fn sign(&self, accounts: Arc<AccountProvider>, filled: FilledTransactionRequest, password: SignWith)
+	fn sign(&self, accounts: Arc<AccountProvider>, filled: FilledTransactionRequest, password: SignWith, origin: Origin)
 		-> BoxFuture<WithToken<SignedTransaction>>
 	{
 		let chain_id = self.client.signing_chain_id();
 
 		if let Some(nonce) = filled.nonce {
-			return Box::new(future::done(sign_transaction(&*accounts, filled, chain_id, nonce, password)));
+			return Box::new(future::done(sign_transaction(&*accounts, filled, chain_id, nonce, password, &origin)));
 		}
 
 		let state = self.state_nonce(&filled.from);
 		let reserved = self.nonces.lock().reserve(filled.from, state);
 
-		Box::new(ProspectiveSigner::new(accounts, filled, chain_id, reserved, password))
+		Box::new(ProspectiveSigner::new(accounts, filled, chain_id, reserved, password, origin))
 	}
 
 	fn enrich(&self, signed_transaction: SignedTransaction) -> RpcRichRawTransaction {
@@ -381,14 +385,14 @@ impl Dispatcher for LightDispatcher {
 		}
 	}
 
-	fn sign(&self, accounts: Arc<AccountProvider>, filled: FilledTransactionRequest, password: SignWith)
+	fn sign(&self, accounts: Arc<AccountProvider>, filled: FilledTransactionRequest, password: SignWith, origin: Origin)
 		-> BoxFuture<WithToken<SignedTransaction>>
 	{
 		let chain_id = self.client.signing_chain_id();
 
 		// fast path for pre-filled nonce.
 		if let Some(nonce) = filled.nonce {
-			return Box::new(future::done(sign_transaction(&*accounts, filled, chain_id, nonce, password)))
+			return Box::new(future::done(sign_transaction(&*accounts, filled, chain_id, nonce, password, &origin)))
 		}
 
 		let nonces = self.nonces.clone();
@@ -397,7 +401,7 @@ impl Dispatcher for LightDispatcher {
 			.and_then(move |nonce| {
 				let reserved = nonces.lock().reserve(filled.from, nonce);
 
-				ProspectiveSigner::new(accounts, filled, chain_id, reserved, password)
+				ProspectiveSigner::new(accounts, filled, chain_id, reserved, password, origin)
 			}))
 	}
 
@@ -421,6 +425,7 @@ fn sign_transaction(
 	chain_id: Option<u64>,
 	nonce: U256,
 	password: SignWith,
+	origin: &Origin,
 ) -> Result<WithToken<SignedTransaction>> {
 	let t = Transaction {
 		nonce: nonce,
@@ -432,11 +437,11 @@ fn sign_transaction(
 	};
 
 	if accounts.is_hardware_address(&filled.from) {
-		return hardware_signature(accounts, filled.from, t, chain_id).map(WithToken::No)
+		return hardware_signature(accounts, filled.from, t, chain_id, origin).map(WithToken::No)
 	}
 
 	let hash = t.hash(chain_id);
-	let signature = signature(accounts, filled.from, hash, password)?;
+	let signature = signature(accounts, filled.from, hash, password, KeyOperation::SignTransaction, origin)?;
 
 	Ok(signature.map(|sig| {
 		SignedTransaction::new(t.with_signature(sig, chain_id))
@@ -457,6 +462,7 @@ struct ProspectiveSigner {
 	chain_id: Option<u64>,
 	reserved: nonce::Reserved,
 	password: SignWith,
+	origin: Origin,
 	state: ProspectiveSignerState,
 	prospective: Option<Result<WithToken<SignedTransaction>>>,
 	ready: Option<nonce::Ready>,
@@ -469,6 +475,7 @@ impl ProspectiveSigner {
 		chain_id: Option<u64>,
 		reserved: nonce::Reserved,
 		password: SignWith,
+		origin: Origin,
 	) -> Self {
 		// If the account is permanently unlocked we can try to sign
 		// using prospective nonce. This should speed up sending
@@ -482,6 +489,7 @@ impl ProspectiveSigner {
 			chain_id,
 			reserved,
 			password,
+			origin,
 			state: if is_unlocked_permanently || has_password {
 				ProspectiveSignerState::TryProspectiveSign
 			} else {
@@ -498,7 +506,8 @@ impl ProspectiveSigner {
 			self.filled.clone(),
 			self.chain_id,
 			*nonce,
-			self.password.clone()
+			self.password.clone(),
+			&self.origin,
 		)
 	}
 
@@ -645,16 +654,19 @@ impl<T: Debug> From<(T, Option<AccountToken>)> for WithToken<T> {
 }
 
 /// Execute a confirmation payload.
+///
+/// The operation is recorded in the key usage log of the sender's account, along with the `origin` of the request.
 pub fn execute<D: Dispatcher + 'static>(
 	dispatcher: D,
 	accounts: Arc<AccountProvider>,
 	payload: ConfirmationPayload,
-	pass: SignWith
+	pass: SignWith,
+	origin: Origin,
 ) -> BoxFuture<WithToken<ConfirmationResponse>> {
 	match payload {
 		ConfirmationPayload::SendTransaction(request) => {
 			let condition = request.condition.clone().map(Into::into);
-			Box::new(dispatcher.sign(accounts, request, pass)
+			Box::new(dispatcher.sign(accounts, request, pass, origin)
 				.map(move |v| v.map(move |tx| PendingTransaction::new(tx, condition)))
 				.map(WithToken::into_tuple)
 				.map(|(tx, token)| (tx, token, dispatcher))
@@ -666,7 +678,7 @@ pub fn execute<D: Dispatcher + 'static>(
 				}))
 		},
 		ConfirmationPayload::SignTransaction(request) => {
-			Box::new(dispatcher.sign(accounts, request, pass)
+			Box::new(dispatcher.sign(accounts, request, pass, origin)
 				.map(move |result| result
 					.map(move |tx| dispatcher.enrich(tx))
 					.map(ConfirmationResponse::SignTransaction)
@@ -678,7 +690,7 @@ pub fn execute<D: Dispatcher + 'static>(
 			}
 
 			let hash = eth_data_hash(data);
-			let res = signature(&accounts, address, hash, pass)
+			let res = signature(&accounts, address, hash, pass, KeyOperation::SignMessage, &origin)
 				.map(|result| result
 					.map(|rsv| H520(rsv.into_electrum()))
 					.map(RpcH520::from)
@@ -690,8 +702,11 @@ pub fn execute<D: Dispatcher + 'static>(
 			if accounts.is_hardware_address(&address) {
 				return Box::new(future::err(errors::unsupported("Decrypting via hardware wallets is not supported.", None)));
 			}
+			if accounts.is_remote_address(&address) {
+				return Box::new(future::err(errors::unsupported("Decrypting via remote signers is not supported.", None)));
+			}
 
-			let res = decrypt(&accounts, address, data, pass)
+			let res = decrypt(&accounts, address, data, pass, &origin)
 				.map(|result| result
 					.map(RpcBytes)
 					.map(ConfirmationResponse::Decrypt)
@@ -701,11 +716,22 @@ pub fn execute<D: Dispatcher + 'static>(
 	}
 }
 
-fn signature(accounts: &AccountProvider, address: Address, hash: H256, password: SignWith) -> Result<WithToken<Signature>> {
+fn signature(accounts: &AccountProvider, address: Address, hash: H256, password: SignWith, operation: KeyOperation, origin: &Origin)
+	-> Result<WithToken<Signature>>
+{
+	let origin = origin.to_string();
+
+	// keys held by the remote signer are never locked, the password and token don't apply.
+	if accounts.is_remote_address(&address) {
+		return accounts.sign(address, None, hash, operation, &origin)
+			.map(WithToken::No)
+			.map_err(|e| errors::account("Error signing with remote signer", e));
+	}
+
 	match password.clone() {
-		SignWith::Nothing => accounts.sign(address, None, hash).map(WithToken::No),
-		SignWith::Password(pass) => accounts.sign(address, Some(pass), hash).map(WithToken::No),
-		SignWith::Token(token) => accounts.sign_with_token(address, token, hash).map(Into::into),
+		SignWith::Nothing => accounts.sign(address, None, hash, operation, &origin).map(WithToken::No),
+		SignWith::Password(pass) => accounts.sign(address, Some(pass), hash, operation, &origin).map(WithToken::No),
+		SignWith::Token(token) => accounts.sign_with_token(address, token, hash, operation, &origin).map(Into::into),
 	}.map_err(|e| match password {
 		SignWith::Nothing => errors::signing(e),
 		_ => errors::password(e),
@@ -713,14 +739,14 @@ fn signature(accounts: &AccountProvider, address: Address, hash: H256, password:
 }
 
 // obtain a hardware signature from the given account.
-fn hardware_signature(accounts: &AccountProvider, address: Address, t: Transaction, chain_id: Option<u64>)
+fn hardware_signature(accounts: &AccountProvider, address: Address, t: Transaction, chain_id: Option<u64>, origin: &Origin)
 	-> Result<SignedTransaction>
 {
 	debug_assert!(accounts.is_hardware_address(&address));
 
 	let mut stream = rlp::RlpStream::new();
 	t.rlp_append_unsigned_transaction(&mut stream, chain_id);
-	let signature = accounts.sign_with_hardware(address, &t, chain_id, &stream.as_raw())
+	let signature = accounts.sign_with_hardware(address, &t, chain_id, &stream.as_raw(), &origin.to_string())
 		.map_err(|e| {
 			debug!(target: "miner", "Error signing transaction with hardware wallet: {}", e);
 			errors::account("Error signing transaction with hardware wallet", e)
@@ -733,11 +759,12 @@ fn hardware_signature(accounts: &AccountProvider, address: Address, t: Transacti
 		})
 }
 
-fn decrypt(accounts: &AccountProvider, address: Address, msg: Bytes, password: SignWith) -> Result<WithToken<Bytes>> {
+fn decrypt(accounts: &AccountProvider, address: Address, msg: Bytes, password: SignWith, origin: &Origin) -> Result<WithToken<Bytes>> {
+	let origin = origin.to_string();
 	match password.clone() {
-		SignWith::Nothing => accounts.decrypt(address, None, &DEFAULT_MAC, &msg).map(WithToken::No),
-		SignWith::Password(pass) => accounts.decrypt(address, Some(pass), &DEFAULT_MAC, &msg).map(WithToken::No),
-		SignWith::Token(token) => accounts.decrypt_with_token(address, token, &DEFAULT_MAC, &msg).map(Into::into),
+		SignWith::Nothing => accounts.decrypt(address, None, &DEFAULT_MAC, &msg, &origin).map(WithToken::No),
+		SignWith::Password(pass) => accounts.decrypt(address, Some(pass), &DEFAULT_MAC, &msg, &origin).map(WithToken::No),
+		SignWith::Token(token) => accounts.decrypt_with_token(address, token, &DEFAULT_MAC, &msg, &origin).map(Into::into),
 	}.map_err(|e| match password {
 		SignWith::Nothing => errors::signing(e),
 		_ => errors::password(e),
@@ -749,7 +776,7 @@ pub fn default_gas_price<C, M>(client: &C, miner: &M, percentile: usize) -> U256
 	C: BlockChainClient,
 	M: MinerService,
 {
-	client.gas_price_corpus(100).percentile(percentile).cloned().unwrap_or_else(|| miner.sensible_gas_price())
+	client.gas_price_corpus(DEFAULT_SAMPLE_BLOCKS).percentile(percentile).cloned().unwrap_or_else(|| miner.sensible_gas_price())
 }
 
 /// Convert RPC confirmation payload to signer confirmation payload.
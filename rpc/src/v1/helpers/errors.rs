@@ -33,11 +33,16 @@ mod codes {
 	pub const NO_AUTHOR: i64 = -32002;
 	pub const NO_NEW_WORK: i64 = -32003;
 	pub const NO_WORK_REQUIRED: i64 = -32004;
+	pub const RESOURCE_LIMIT: i64 = -32005;
 	pub const UNKNOWN_ERROR: i64 = -32009;
 	pub const TRANSACTION_ERROR: i64 = -32010;
 	pub const EXECUTION_ERROR: i64 = -32015;
 	pub const EXCEPTION_ERROR: i64 = -32016;
 	pub const DATABASE_ERROR: i64 = -32017;
+	pub const STATE_UNAVAILABLE: i64 = -32018;
+	// reverted calls used to be reported with `EXECUTION_ERROR` and the return data appended to the
+	// error message. Clients matching on -32015 to detect reverts have to match on this code instead.
+	pub const EXECUTION_REVERTED: i64 = -32019;
 	pub const ACCOUNT_LOCKED: i64 = -32020;
 	pub const PASSWORD_INVALID: i64 = -32021;
 	pub const ACCOUNT_ERROR: i64 = -32023;
@@ -143,9 +148,27 @@ pub fn execution<T: fmt::Debug>(data: T) -> Error {
 	}
 }
 
+pub fn reverted(output: &[u8]) -> Error {
+	use rustc_hex::ToHex;
+
+	Error {
+		code: ErrorCode::ServerError(codes::EXECUTION_REVERTED),
+		message: "Execution reverted.".into(),
+		data: Some(Value::String(format!("0x{}", output.to_hex()))),
+	}
+}
+
+pub fn resource_limit<T: fmt::Display>(resource: &str, details: T) -> Error {
+	Error {
+		code: ErrorCode::ServerError(codes::RESOURCE_LIMIT),
+		message: format!("Request exceeds the limit of {}.", resource),
+		data: Some(Value::String(details.to_string())),
+	}
+}
+
 pub fn state_pruned() -> Error {
 	Error {
-		code: ErrorCode::ServerError(codes::UNSUPPORTED_REQUEST),
+		code: ErrorCode::ServerError(codes::STATE_UNAVAILABLE),
 		message: "This request is not supported because your node is running with state pruning. Run with --pruning=archive.".into(),
 		data: None,
 	}
@@ -332,12 +355,18 @@ pub fn transaction_message(error: &TransactionError) -> String {
 			format!("Transaction cost exceeds current gas limit. Limit: {}, got: {}. Try decreasing supplied gas.", limit, got)
 		},
 		InvalidSignature(ref sig) => format!("Invalid signature: {}", sig),
-		InvalidChainId => "Invalid chain id.".into(),
+		SignatureOutOfRange => "Invalid signature: values are out of range.".into(),
+		MalleableSignature => "Invalid signature: s value must be in the lower half of the curve order (EIP-2).".into(),
+		InvalidChainId { expected: Some(expected), got } => format!("Invalid chain id. Expected {}, got {}.", expected, got),
+		InvalidChainId { expected: None, got } => format!("Invalid chain id {}. Only transactions without chain id are accepted.", got),
+		ReplayProtectionRequired => "Transaction must be signed with chain id (EIP-155).".into(),
 		InvalidGasLimit(_) => "Supplied gas is beyond limit.".into(),
 		SenderBanned => "Sender is banned in local queue.".into(),
 		RecipientBanned => "Recipient is banned in local queue.".into(),
 		CodeBanned => "Code is banned in local queue.".into(),
 		NotAllowed => "Transaction is not permitted.".into(),
+		SenderNotCertified => "Transaction sender is not certified.".into(),
+		TransactionTypeNotEnabled => "Transaction type is not supported yet.".into(),
 		TooBig => "Transaction is too big, see chain specification for the limit.".into(),
 		InvalidRlp(ref descr) => format!("Invalid RLP data: {}", descr),
 	}
@@ -392,17 +421,15 @@ pub fn call(error: CallError) -> Error {
 }
 
 pub fn vm(error: &VMError, output: &[u8]) -> Error {
-	use rustc_hex::ToHex;
-
-	let data = match error {
-		&VMError::Reverted => format!("{} 0x{}", VMError::Reverted, output.to_hex()),
-		error => format!("{}", error),
-	};
-
-	Error {
-		code: ErrorCode::ServerError(codes::EXECUTION_ERROR),
-		message: "VM execution error.".into(),
-		data: Some(Value::String(data)),
+	match error {
+		&VMError::Reverted => reverted(output),
+		&VMError::OutOfMemory { required, limit } =>
+			resource_limit("VM memory", format!("Execution required {} bytes of memory, limit is {} bytes.", required, limit)),
+		error => Error {
+			code: ErrorCode::ServerError(codes::EXECUTION_ERROR),
+			message: "VM execution error.".into(),
+			data: Some(Value::String(format!("{}", error))),
+		},
 	}
 }
 
@@ -20,7 +20,7 @@ use std::thread;
 use std::time::{Instant, Duration, SystemTime, UNIX_EPOCH};
 use std::sync::Arc;
 
-use rlp::{self, Rlp};
+use rlp;
 use ethereum_types::{U256, H64, H160, H256, Address};
 use parking_lot::Mutex;
 
@@ -36,7 +36,7 @@ use ethcore::snapshot::SnapshotService;
 use ethcore::encoded;
 use sync::{SyncProvider};
 use miner::external::ExternalMinerService;
-use transaction::{SignedTransaction, LocalizedTransaction};
+use transaction::{SignedTransaction, UnverifiedTransaction, LocalizedTransaction};
 
 use jsonrpc_core::{BoxFuture, Result};
 use jsonrpc_core::futures::future;
@@ -49,7 +49,7 @@ use v1::helpers::accounts::unwrap_provider;
 use v1::traits::Eth;
 use v1::types::{
 	RichBlock, Block, BlockTransactions, BlockNumber, Bytes, SyncStatus, SyncInfo,
-	Transaction, CallRequest, Index, Filter, Log, Receipt, Work,
+	Transaction, CallRequest, Index, Filter, Log, Receipt, Work, AccessListWithGasUsed,
 	H64 as RpcH64, H256 as RpcH256, H160 as RpcH160, U256 as RpcU256, block_number_to_id,
 };
 use v1::metadata::Metadata;
@@ -176,7 +176,7 @@ impl<C, SN: ?Sized, S: ?Sized, M, EM, T: StateInfo + 'static> EthClient<C, SN, S
 		unwrap_provider(&self.accounts)
 	}
 
-	fn rich_block(&self, id: BlockNumberOrId, include_txs: bool) -> Result<Option<RichBlock>> {
+	fn rich_block(&self, id: BlockNumberOrId, include_txs: bool, include_raw: bool) -> Result<Option<RichBlock>> {
 		let client = &self.client;
 
 		let client_query = |id| (client.block(id), client.block_total_difficulty(id), client.block_extra_info(id), false);
@@ -252,6 +252,10 @@ impl<C, SN: ?Sized, S: ?Sized, M, EM, T: StateInfo + 'static> EthClient<C, SN, S
 							false => BlockTransactions::Hashes(block.transaction_hashes().into_iter().map(Into::into).collect()),
 						},
 						extra_data: Bytes::new(view.extra_data()),
+						raw: match include_raw {
+							true => Some(block.rlp().as_raw().to_vec().into()),
+							false => None,
+						},
 					},
 					extra_info: extra.expect(EXTRA_INFO_PROOF),
 				}))
@@ -388,6 +392,7 @@ impl<C, SN: ?Sized, S: ?Sized, M, EM, T: StateInfo + 'static> EthClient<C, SN, S
 				seal_fields: uncle.seal().into_iter().cloned().map(Into::into).collect(),
 				uncles: vec![],
 				transactions: BlockTransactions::Hashes(vec![]),
+				raw: None,
 			},
 			extra_info: extra,
 		};
@@ -646,12 +651,12 @@ impl<C, SN: ?Sized, S: ?Sized, M, EM, T: StateInfo + 'static> Eth for EthClient<
 		Box::new(future::done(res))
 	}
 
-	fn block_by_hash(&self, hash: RpcH256, include_txs: bool) -> BoxFuture<Option<RichBlock>> {
-		Box::new(future::done(self.rich_block(BlockId::Hash(hash.into()).into(), include_txs)))
+	fn block_by_hash(&self, hash: RpcH256, include_txs: bool, include_raw: Trailing<bool>) -> BoxFuture<Option<RichBlock>> {
+		Box::new(future::done(self.rich_block(BlockId::Hash(hash.into()).into(), include_txs, include_raw.unwrap_or_default())))
 	}
 
-	fn block_by_number(&self, num: BlockNumber, include_txs: bool) -> BoxFuture<Option<RichBlock>> {
-		Box::new(future::done(self.rich_block(num.into(), include_txs)))
+	fn block_by_number(&self, num: BlockNumber, include_txs: bool, include_raw: Trailing<bool>) -> BoxFuture<Option<RichBlock>> {
+		Box::new(future::done(self.rich_block(num.into(), include_txs, include_raw.unwrap_or_default())))
 	}
 
 	fn transaction_by_hash(&self, hash: RpcH256) -> BoxFuture<Option<Transaction>> {
@@ -682,6 +687,20 @@ impl<C, SN: ?Sized, S: ?Sized, M, EM, T: StateInfo + 'static> Eth for EthClient<
 		Box::new(future::done(self.transaction(transaction_id)))
 	}
 
+	fn transaction_by_sender_and_nonce(&self, sender: RpcH160, nonce: RpcU256) -> BoxFuture<Option<Transaction>> {
+		let (sender, nonce): (Address, U256) = (sender.into(), nonce.into());
+		let block_number = self.client.chain_info().best_block_number;
+		let tx = self.client.transaction_by_sender_and_nonce(&sender, &nonce)
+			.map(|t| Transaction::from_localized(t, self.eip86_transition))
+			.or_else(|| {
+				self.miner.queued_transactions().into_iter()
+					.find(|t| t.signed().sender() == sender && t.signed().nonce == nonce)
+					.map(|t| Transaction::from_pending(t.pending().clone(), block_number + 1, self.eip86_transition))
+			});
+
+		Box::new(future::ok(tx))
+	}
+
 	fn transaction_receipt(&self, hash: RpcH256) -> BoxFuture<Option<Receipt>> {
 		let best_block = self.client.chain_info().best_block_number;
 		let hash: H256 = hash.into();
@@ -816,7 +835,7 @@ impl<C, SN: ?Sized, S: ?Sized, M, EM, T: StateInfo + 'static> Eth for EthClient<
 	}
 
 	fn send_raw_transaction(&self, raw: Bytes) -> Result<RpcH256> {
-		Rlp::new(&raw.into_vec()).as_val()
+		UnverifiedTransaction::decode_raw(&raw.into_vec())
 			.map_err(errors::rlp)
 			.and_then(|tx| SignedTransaction::new(tx).map_err(errors::transaction))
 			.and_then(|signed_transaction| {
@@ -839,12 +858,12 @@ impl<C, SN: ?Sized, S: ?Sized, M, EM, T: StateInfo + 'static> Eth for EthClient<
 
 		let num = num.unwrap_or_default();
 
-		let (mut state, header) = if num == BlockNumber::Pending {
+		let result = if num == BlockNumber::Pending {
 			let info = self.client.chain_info();
-			let state = try_bf!(self.miner.pending_state(info.best_block_number).ok_or(errors::state_pruned()));
+			let mut state = try_bf!(self.miner.pending_state(info.best_block_number).ok_or(errors::state_pruned()));
 			let header = try_bf!(self.miner.pending_block_header(info.best_block_number).ok_or(errors::state_pruned()));
 
-			(state, header)
+			self.client.call(&signed, Default::default(), &mut state, &header)
 		} else {
 			let id = match num {
 				BlockNumber::Num(num) => BlockId::Number(num),
@@ -853,14 +872,9 @@ impl<C, SN: ?Sized, S: ?Sized, M, EM, T: StateInfo + 'static> Eth for EthClient<
 				BlockNumber::Pending => unreachable!(), // Already covered
 			};
 
-			let state = try_bf!(self.client.state_at(id).ok_or(errors::state_pruned()));
-			let header = try_bf!(self.client.block_header(id).ok_or(errors::state_pruned()).and_then(|h| h.decode().map_err(errors::decode)));
-
-			(state, header)
+			self.client.call_at(&signed, Default::default(), id)
 		};
 
-		let result = self.client.call(&signed, Default::default(), &mut state, &header);
-
 		Box::new(future::done(result
 			.map_err(errors::call)
 			.and_then(|executed| {
@@ -904,6 +918,37 @@ impl<C, SN: ?Sized, S: ?Sized, M, EM, T: StateInfo + 'static> Eth for EthClient<
 		))
 	}
 
+	fn create_access_list(&self, meta: Self::Metadata, request: CallRequest, num: Trailing<BlockNumber>) -> BoxFuture<AccessListWithGasUsed> {
+		let request = CallRequest::into(request);
+		let signed = try_bf!(fake_sign::sign_call(request, meta.is_dapp()));
+		let num = num.unwrap_or_default();
+
+		let (state, header) = if num == BlockNumber::Pending {
+			let info = self.client.chain_info();
+			let state = try_bf!(self.miner.pending_state(info.best_block_number).ok_or(errors::state_pruned()));
+			let header = try_bf!(self.miner.pending_block_header(info.best_block_number).ok_or(errors::state_pruned()));
+
+			(state, header)
+		} else {
+			let id = match num {
+				BlockNumber::Num(num) => BlockId::Number(num),
+				BlockNumber::Earliest => BlockId::Earliest,
+				BlockNumber::Latest => BlockId::Latest,
+				BlockNumber::Pending => unreachable!(), // Already covered
+			};
+
+			let state = try_bf!(self.client.state_at(id).ok_or(errors::state_pruned()));
+			let header = try_bf!(self.client.block_header(id).ok_or(errors::state_pruned()).and_then(|h| h.decode().map_err(errors::decode)));
+
+			(state, header)
+		};
+
+		Box::new(future::done(self.client.create_access_list(&signed, &state, &header)
+			.map(Into::into)
+			.map_err(errors::call)
+		))
+	}
+
 	fn compile_lll(&self, _: String) -> Result<Bytes> {
 		Err(errors::deprecated("Compilation of LLL via RPC is deprecated".to_string()))
 	}
@@ -35,6 +35,7 @@ use v1::types::{pubsub, RichHeader, Log};
 use ethcore::encoded;
 use ethcore::filter::Filter as EthFilter;
 use ethcore::client::{BlockChainClient, ChainNotify, ChainRoute, ChainRouteType, BlockId};
+use miner::pool::RemovalReason;
 use sync::LightSync;
 use light::cache::Cache;
 use light::on_demand::OnDemand;
@@ -52,6 +53,8 @@ pub struct EthPubSubClient<C> {
 	heads_subscribers: Arc<RwLock<Subscribers<Client>>>,
 	logs_subscribers: Arc<RwLock<Subscribers<(Client, EthFilter)>>>,
 	transactions_subscribers: Arc<RwLock<Subscribers<Client>>>,
+	dropped_subscribers: Arc<RwLock<Subscribers<Client>>>,
+	removed_subscribers: Arc<RwLock<Subscribers<Client>>>,
 }
 
 impl<C> EthPubSubClient<C> {
@@ -60,6 +63,8 @@ impl<C> EthPubSubClient<C> {
 		let heads_subscribers = Arc::new(RwLock::new(Subscribers::default()));
 		let logs_subscribers = Arc::new(RwLock::new(Subscribers::default()));
 		let transactions_subscribers = Arc::new(RwLock::new(Subscribers::default()));
+		let dropped_subscribers = Arc::new(RwLock::new(Subscribers::default()));
+		let removed_subscribers = Arc::new(RwLock::new(Subscribers::default()));
 
 		EthPubSubClient {
 			handler: Arc::new(ChainNotificationHandler {
@@ -68,10 +73,14 @@ impl<C> EthPubSubClient<C> {
 				heads_subscribers: heads_subscribers.clone(),
 				logs_subscribers: logs_subscribers.clone(),
 				transactions_subscribers: transactions_subscribers.clone(),
+				dropped_subscribers: dropped_subscribers.clone(),
+				removed_subscribers: removed_subscribers.clone(),
 			}),
 			heads_subscribers,
 			logs_subscribers,
 			transactions_subscribers,
+			dropped_subscribers,
+			removed_subscribers,
 		}
 	}
 
@@ -82,6 +91,8 @@ impl<C> EthPubSubClient<C> {
 		*client.heads_subscribers.write() = Subscribers::new_test();
 		*client.logs_subscribers.write() = Subscribers::new_test();
 		*client.transactions_subscribers.write() = Subscribers::new_test();
+		*client.dropped_subscribers.write() = Subscribers::new_test();
+		*client.removed_subscribers.write() = Subscribers::new_test();
 		client
 	}
 
@@ -119,6 +130,8 @@ pub struct ChainNotificationHandler<C> {
 	heads_subscribers: Arc<RwLock<Subscribers<Client>>>,
 	logs_subscribers: Arc<RwLock<Subscribers<(Client, EthFilter)>>>,
 	transactions_subscribers: Arc<RwLock<Subscribers<Client>>>,
+	dropped_subscribers: Arc<RwLock<Subscribers<Client>>>,
+	removed_subscribers: Arc<RwLock<Subscribers<Client>>>,
 }
 
 impl<C> ChainNotificationHandler<C> {
@@ -182,6 +195,27 @@ impl<C> ChainNotificationHandler<C> {
 			}
 		}
 	}
+
+	/// Notify all subscribers about hashes of transactions dropped from the pool.
+	pub fn dropped_transactions(&self, hashes: &[H256]) {
+		for subscriber in self.dropped_subscribers.read().values() {
+			for hash in hashes {
+				Self::notify(&self.remote, subscriber, pubsub::Result::TransactionHash((*hash).into()));
+			}
+		}
+	}
+
+	/// Notify all subscribers about transactions removed from the pool without being mined.
+	pub fn removed_transactions(&self, removed: &[(H256, RemovalReason)]) {
+		for subscriber in self.removed_subscribers.read().values() {
+			for &(hash, reason) in removed {
+				Self::notify(&self.remote, subscriber, pubsub::Result::RemovedTransaction(pubsub::RemovedTransaction {
+					hash: hash.into(),
+					reason: reason.into(),
+				}));
+			}
+		}
+	}
 }
 
 /// A light client wrapper struct.
@@ -295,6 +329,20 @@ impl<C: Send + Sync + 'static> EthPubSub for EthPubSubClient<C> {
 			(pubsub::Kind::NewPendingTransactions, _) => {
 				errors::invalid_params("newPendingTransactions", "Expected no parameters.")
 			},
+			(pubsub::Kind::DroppedTransactions, None) => {
+				self.dropped_subscribers.write().push(subscriber);
+				return;
+			},
+			(pubsub::Kind::DroppedTransactions, _) => {
+				errors::invalid_params("droppedTransactions", "Expected no parameters.")
+			},
+			(pubsub::Kind::RemovedTransactions, None) => {
+				self.removed_subscribers.write().push(subscriber);
+				return;
+			},
+			(pubsub::Kind::RemovedTransactions, _) => {
+				errors::invalid_params("removedTransactions", "Expected no parameters.")
+			},
 			_ => {
 				errors::unimplemented(None)
 			},
@@ -307,7 +355,9 @@ impl<C: Send + Sync + 'static> EthPubSub for EthPubSubClient<C> {
 		let res = self.heads_subscribers.write().remove(&id).is_some();
 		let res2 = self.logs_subscribers.write().remove(&id).is_some();
 		let res3 = self.transactions_subscribers.write().remove(&id).is_some();
+		let res4 = self.dropped_subscribers.write().remove(&id).is_some();
+		let res5 = self.removed_subscribers.write().remove(&id).is_some();
 
-		Ok(res || res2 || res3)
+		Ok(res || res2 || res3 || res4 || res5)
 	}
 }
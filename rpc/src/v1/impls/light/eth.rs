@@ -36,8 +36,7 @@ use sync::LightSync;
 use hash::{KECCAK_NULL_RLP, KECCAK_EMPTY_LIST_RLP};
 use ethereum_types::U256;
 use parking_lot::{RwLock, Mutex};
-use rlp::Rlp;
-use transaction::SignedTransaction;
+use transaction::{SignedTransaction, UnverifiedTransaction};
 
 use v1::impls::eth_filter::Filterable;
 use v1::helpers::{errors, limit_logs};
@@ -46,7 +45,7 @@ use v1::helpers::light_fetch::{self, LightFetch};
 use v1::traits::Eth;
 use v1::types::{
 	RichBlock, Block, BlockTransactions, BlockNumber, Bytes, SyncStatus, SyncInfo,
-	Transaction, CallRequest, Index, Filter, Log, Receipt, Work,
+	Transaction, CallRequest, Index, Filter, Log, Receipt, Work, AccessListWithGasUsed,
 	H64 as RpcH64, H256 as RpcH256, H160 as RpcH160, U256 as RpcU256,
 };
 use v1::metadata::Metadata;
@@ -134,7 +133,7 @@ impl<T: LightChainClient + 'static> EthClient<T> {
 	}
 
 	// get a "rich" block structure. Fails on unknown block.
-	fn rich_block(&self, id: BlockId, include_txs: bool) -> BoxFuture<RichBlock> {
+	fn rich_block(&self, id: BlockId, include_txs: bool, include_raw: bool) -> BoxFuture<RichBlock> {
 		let (on_demand, sync) = (self.on_demand.clone(), self.sync.clone());
 		let (client, engine) = (self.client.clone(), self.client.engine().clone());
 		let eip86_transition = self.client.eip86_transition();
@@ -168,6 +167,10 @@ impl<T: LightChainClient + 'static> EthClient<T> {
 						_ => BlockTransactions::Hashes(block.transaction_hashes().into_iter().map(Into::into).collect()),
 					},
 					extra_data: Bytes::new(header.extra_data().clone()),
+					raw: match include_raw {
+						true => Some(block.rlp().as_raw().to_vec().into()),
+						_ => None,
+					},
 				},
 				extra_info: extra_info
 			}
@@ -289,12 +292,12 @@ impl<T: LightChainClient + 'static> Eth for EthClient<T> {
 		Box::new(future::err(errors::unimplemented(None)))
 	}
 
-	fn block_by_hash(&self, hash: RpcH256, include_txs: bool) -> BoxFuture<Option<RichBlock>> {
-		Box::new(self.rich_block(BlockId::Hash(hash.into()), include_txs).map(Some))
+	fn block_by_hash(&self, hash: RpcH256, include_txs: bool, include_raw: Trailing<bool>) -> BoxFuture<Option<RichBlock>> {
+		Box::new(self.rich_block(BlockId::Hash(hash.into()), include_txs, include_raw.unwrap_or_default()).map(Some))
 	}
 
-	fn block_by_number(&self, num: BlockNumber, include_txs: bool) -> BoxFuture<Option<RichBlock>> {
-		Box::new(self.rich_block(Self::num_to_id(num), include_txs).map(Some))
+	fn block_by_number(&self, num: BlockNumber, include_txs: bool, include_raw: Trailing<bool>) -> BoxFuture<Option<RichBlock>> {
+		Box::new(self.rich_block(Self::num_to_id(num), include_txs, include_raw.unwrap_or_default()).map(Some))
 	}
 
 	fn transaction_count(&self, address: RpcH160, num: Trailing<BlockNumber>) -> BoxFuture<RpcU256> {
@@ -373,7 +376,7 @@ impl<T: LightChainClient + 'static> Eth for EthClient<T> {
 	fn send_raw_transaction(&self, raw: Bytes) -> Result<RpcH256> {
 		let best_header = self.client.best_block_header().decode().map_err(errors::decode)?;
 
-		Rlp::new(&raw.into_vec()).as_val()
+		UnverifiedTransaction::decode_raw(&raw.into_vec())
 			.map_err(errors::rlp)
 			.and_then(|tx| {
 				self.client.engine().verify_transaction_basic(&tx, &best_header)
@@ -396,7 +399,10 @@ impl<T: LightChainClient + 'static> Eth for EthClient<T> {
 	fn call(&self, _meta: Self::Metadata, req: CallRequest, num: Trailing<BlockNumber>) -> BoxFuture<Bytes> {
 		Box::new(self.fetcher().proved_execution(req, num).and_then(|res| {
 			match res {
-				Ok(exec) => Ok(exec.output.into()),
+				Ok(exec) => match exec.exception {
+					Some(ref exception) => Err(errors::vm(exception, &exec.output)),
+					None => Ok(exec.output.into()),
+				},
 				Err(e) => Err(errors::execution(e)),
 			}
 		}))
@@ -412,6 +418,10 @@ impl<T: LightChainClient + 'static> Eth for EthClient<T> {
 		}))
 	}
 
+	fn create_access_list(&self, _meta: Self::Metadata, _req: CallRequest, _num: Trailing<BlockNumber>) -> BoxFuture<AccessListWithGasUsed> {
+		Box::new(future::err(errors::light_unimplemented(None)))
+	}
+
 	fn transaction_by_hash(&self, hash: RpcH256) -> BoxFuture<Option<Transaction>> {
 		let hash = hash.into();
 		let eip86 = self.client.eip86_transition();
@@ -444,6 +454,10 @@ impl<T: LightChainClient + 'static> Eth for EthClient<T> {
 		}))
 	}
 
+	fn transaction_by_sender_and_nonce(&self, _sender: RpcH160, _nonce: RpcU256) -> BoxFuture<Option<Transaction>> {
+		Box::new(future::err(errors::light_unimplemented(None)))
+	}
+
 	fn transaction_receipt(&self, hash: RpcH256) -> BoxFuture<Option<Receipt>> {
 		let eip86 = self.client.eip86_transition();
 		let fetcher = self.fetcher();
@@ -579,6 +593,7 @@ fn extract_uncle_at_index<T: LightChainClient>(block: encoded::Block, index: Ind
 				seal_fields: uncle.seal().into_iter().cloned().map(Into::into).collect(),
 				uncles: vec![],
 				transactions: BlockTransactions::Hashes(vec![]),
+				raw: None,
 			},
 			extra_info: extra_info,
 		})
@@ -45,7 +45,7 @@ use v1::types::{
 	TransactionStats, LocalTransactionStatus,
 	BlockNumber, ConsensusCapability, VersionInfo,
 	OperationsInfo, DappId, ChainStatus,
-	AccountInfo, HwAccountInfo, Header, RichHeader,
+	AccountInfo, HwAccountInfo, Header, RichHeader, BalanceHistoryEntry, ValidatorLiveness,
 };
 use Host;
 
@@ -290,6 +290,31 @@ impl Parity for ParityClient {
 		)
 	}
 
+	fn queued_transactions_by_sender(&self, address: H160) -> Result<Vec<Transaction>> {
+		let txq = self.light_dispatch.transaction_queue.read();
+		let chain_info = self.light_dispatch.client.chain_info();
+
+		let current = txq.ready_transactions(chain_info.best_block_number, chain_info.best_block_timestamp);
+		let future = txq.future_transactions(chain_info.best_block_number, chain_info.best_block_timestamp);
+		let mut transactions = current
+			.into_iter()
+			.chain(future.into_iter())
+			.filter(|tx| H160::from(tx.sender()) == address)
+			.collect::<Vec<_>>();
+		transactions.sort_by_key(|tx| tx.nonce);
+
+		Ok(
+			transactions
+				.into_iter()
+				.map(|tx| Transaction::from_pending(tx, chain_info.best_block_number, self.eip86_transition))
+				.collect::<Vec<_>>()
+		)
+	}
+
+	fn queued_transactions_within_limits(&self, _gas_limit: U256, _min_gas_price: U256) -> Result<Vec<Transaction>> {
+		Err(errors::light_unimplemented(None))
+	}
+
 	fn future_transactions(&self) -> Result<Vec<Transaction>> {
 		let txq = self.light_dispatch.transaction_queue.read();
 		let chain_info = self.light_dispatch.client.chain_info();
@@ -432,6 +457,17 @@ impl Parity for ParityClient {
 		Box::new(self.fetcher().header(id).and_then(from_encoded))
 	}
 
+	fn raw_block(&self, number: Trailing<BlockNumber>) -> BoxFuture<Option<Bytes>> {
+		// Note: light clients don't produce pending blocks, `Pending` is treated as `Latest`.
+		let id = match number.unwrap_or_default() {
+			BlockNumber::Num(n) => BlockId::Number(n),
+			BlockNumber::Earliest => BlockId::Earliest,
+			BlockNumber::Latest | BlockNumber::Pending => BlockId::Latest,
+		};
+
+		Box::new(self.fetcher().block(id).map(|block| Some(block.into_inner().into())))
+	}
+
 	fn ipfs_cid(&self, content: Bytes) -> Result<String> {
 		ipfs::cid(content)
 	}
@@ -440,6 +476,16 @@ impl Parity for ParityClient {
 		Err(errors::light_unimplemented(None))
 	}
 
+	fn balance_history(&self, _address: H160, _from: BlockNumber, _to: BlockNumber, _step: Trailing<U64>) -> Result<Vec<BalanceHistoryEntry>> {
+		Err(errors::light_unimplemented(None))
+	}
+
+	fn validator_liveness(&self) -> Result<BTreeMap<H160, ValidatorLiveness>> {
+		Ok(self.client.engine().validator_liveness().into_iter()
+			.map(|(address, liveness)| (address.into(), liveness.into()))
+			.collect())
+	}
+
 	fn node_health(&self) -> BoxFuture<Health> {
 		Box::new(self.health.health()
 			.map_err(|err| errors::internal("Health API failure.", err)))
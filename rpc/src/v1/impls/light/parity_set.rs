@@ -159,4 +159,8 @@ impl<F: Fetch> ParitySet for ParitySetClient<F> {
 	fn remove_transaction(&self, _hash: H256) -> Result<Option<Transaction>> {
 		Err(errors::light_unimplemented(None))
 	}
+
+	fn send_raw_transaction_local_only(&self, _raw: Bytes) -> Result<H256> {
+		Err(errors::light_unimplemented(None))
+	}
 }
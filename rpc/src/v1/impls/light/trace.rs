@@ -21,7 +21,7 @@ use jsonrpc_macros::Trailing;
 use v1::Metadata;
 use v1::traits::Traces;
 use v1::helpers::errors;
-use v1::types::{TraceFilter, LocalizedTrace, BlockNumber, Index, CallRequest, Bytes, TraceResults, TraceOptions, H256};
+use v1::types::{TraceFilter, LocalizedTrace, BlockNumber, Index, CallRequest, Bytes, TraceResults, TraceStorageUsage, TraceOptions, H256};
 
 /// Traces api implementation.
 // TODO: all calling APIs should be possible w. proved remote TX execution.
@@ -42,6 +42,10 @@ impl Traces for TracesClient {
 		Err(errors::light_unimplemented(None))
 	}
 
+	fn storage_usage(&self) -> Result<Option<TraceStorageUsage>> {
+		Err(errors::light_unimplemented(None))
+	}
+
 	fn trace(&self, _transaction_hash: H256, _address: Vec<Index>) -> Result<Option<LocalizedTrace>> {
 		Err(errors::light_unimplemented(None))
 	}
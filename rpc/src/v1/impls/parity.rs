@@ -17,9 +17,9 @@
 //! Parity-specific rpc implementation.
 use std::sync::Arc;
 use std::str::FromStr;
-use std::collections::{BTreeMap, HashSet};
+use std::collections::{BTreeMap, HashMap, HashSet};
 
-use ethereum_types::Address;
+use ethereum_types::{Address, U256 as EthU256};
 use version::version_data;
 
 use crypto::DEFAULT_MAC;
@@ -27,9 +27,11 @@ use ethkey::{crypto::ecies, Brain, Generator};
 use ethstore::random_phrase;
 use sync::{SyncProvider, ManageNetwork};
 use ethcore::account_provider::AccountProvider;
-use ethcore::client::{BlockChainClient, StateClient, Call};
-use ethcore::ids::BlockId;
+use ethcore::client::{BlockChainClient, StateClient, Call, EngineInfo, Nonce};
+use ethcore::ids::{BlockId, TransactionId};
 use ethcore::miner::{self, MinerService};
+use miner::gas_pricer::DEFAULT_SAMPLE_BLOCKS;
+use miner::pool::local_transactions::Status;
 use ethcore::mode::Mode;
 use ethcore::state::StateInfo;
 use ethcore_logger::RotatingLogger;
@@ -49,7 +51,7 @@ use v1::types::{
 	TransactionStats, LocalTransactionStatus,
 	BlockNumber, ConsensusCapability, VersionInfo,
 	OperationsInfo, DappId, ChainStatus,
-	AccountInfo, HwAccountInfo, RichHeader,
+	AccountInfo, HwAccountInfo, RichHeader, BalanceHistoryEntry, ValidatorLiveness,
 	block_number_to_id
 };
 use Host;
@@ -116,7 +118,7 @@ impl<C, M, U> ParityClient<C, M, U> where
 
 impl<C, M, U, S> Parity for ParityClient<C, M, U> where
 	S: StateInfo + 'static,
-	C: miner::BlockChainClient + BlockChainClient + StateClient<State=S> + Call<State=S> + 'static,
+	C: miner::BlockChainClient + BlockChainClient + StateClient<State=S> + Call<State=S> + EngineInfo + 'static,
 	M: MinerService<State=S> + 'static,
 	U: UpdateService + 'static,
 {
@@ -255,7 +257,7 @@ impl<C, M, U, S> Parity for ParityClient<C, M, U> where
 
 	fn gas_price_histogram(&self) -> BoxFuture<Histogram> {
 		Box::new(future::done(self.client
-			.gas_price_corpus(100)
+			.gas_price_corpus(DEFAULT_SAMPLE_BLOCKS)
 			.histogram(10)
 			.ok_or_else(errors::not_enough_data)
 			.map(Into::into)
@@ -335,6 +337,28 @@ impl<C, M, U, S> Parity for ParityClient<C, M, U> where
 		)
 	}
 
+	fn queued_transactions_by_sender(&self, address: H160) -> Result<Vec<Transaction>> {
+		let block_number = self.client.chain_info().best_block_number;
+		let transactions = self.miner.queued_transactions_by_sender(&address.into());
+
+		Ok(transactions
+		   .into_iter()
+		   .map(|t| Transaction::from_pending(t.pending().clone(), block_number, self.eip86_transition))
+		   .collect()
+		)
+	}
+
+	fn queued_transactions_within_limits(&self, gas_limit: U256, min_gas_price: U256) -> Result<Vec<Transaction>> {
+		let block_number = self.client.chain_info().best_block_number;
+		let transactions = self.miner.queued_transactions_within_limits(&*self.client, gas_limit.into(), min_gas_price.into());
+
+		Ok(transactions
+		   .into_iter()
+		   .map(|t| Transaction::from_pending(t.pending().clone(), block_number, self.eip86_transition))
+		   .collect()
+		)
+	}
+
 	fn future_transactions(&self) -> Result<Vec<Transaction>> {
 		Err(errors::deprecated("Use `parity_allTransaction` instead."))
 	}
@@ -355,9 +379,34 @@ impl<C, M, U, S> Parity for ParityClient<C, M, U> where
 
 		let transactions = self.miner.local_transactions();
 		let block_number = self.client.chain_info().best_block_number;
+		// pending transactions beyond a gap in the nonces of their sender are future ones.
+		let mut pending_nonces: HashMap<Address, HashSet<EthU256>> = HashMap::new();
+		for status in transactions.values() {
+			if let Status::Pending(ref tx) = *status {
+				pending_nonces.entry(tx.signed().sender()).or_insert_with(HashSet::new).insert(tx.signed().nonce);
+			}
+		}
+		let next_nonces: HashMap<Address, EthU256> = pending_nonces.into_iter().map(|(sender, nonces)| {
+			let mut next_nonce = self.client.latest_nonce(&sender);
+			while nonces.contains(&next_nonce) {
+				next_nonce = next_nonce + EthU256::one();
+			}
+			(sender, next_nonce)
+		}).collect();
+
 		Ok(transactions
 		   .into_iter()
-		   .map(|(hash, status)| (hash.into(), LocalTransactionStatus::from(status, block_number, self.eip86_transition)))
+		   .map(|(hash, status)| {
+				let resolved = match status {
+					Status::Pending(ref tx) if next_nonces.get(&tx.signed().sender()).map_or(false, |next_nonce| tx.signed().nonce > *next_nonce) =>
+						Some(LocalTransactionStatus::Future),
+					Status::Mined(ref tx) => self.client.transaction(TransactionId::Hash(*tx.hash()))
+						.map(|tx| LocalTransactionStatus::Mined(Transaction::from_localized(tx, self.eip86_transition))),
+					_ => None,
+				};
+				let status = resolved.unwrap_or_else(|| LocalTransactionStatus::from(status, block_number, self.eip86_transition));
+				(hash.into(), status)
+		   })
 		   .collect()
 		)
 	}
@@ -457,6 +506,20 @@ impl<C, M, U, S> Parity for ParityClient<C, M, U> where
 		}))
 	}
 
+	fn raw_block(&self, number: Trailing<BlockNumber>) -> BoxFuture<Option<Bytes>> {
+		let block = match number.unwrap_or_default() {
+			BlockNumber::Pending => {
+				let info = self.client.chain_info();
+				self.miner.pending_block(info.best_block_number).map(|b| b.rlp_bytes())
+			},
+			BlockNumber::Num(num) => self.client.block(BlockId::Number(num)).map(|b| b.into_inner()),
+			BlockNumber::Earliest => self.client.block(BlockId::Earliest).map(|b| b.into_inner()),
+			BlockNumber::Latest => self.client.block(BlockId::Latest).map(|b| b.into_inner()),
+		};
+
+		Box::new(future::ok(block.map(Into::into)))
+	}
+
 	fn ipfs_cid(&self, content: Bytes) -> Result<String> {
 		ipfs::cid(content)
 	}
@@ -497,6 +560,57 @@ impl<C, M, U, S> Parity for ParityClient<C, M, U> where
 				.map_err(errors::call)
 	}
 
+	fn balance_history(&self, address: H160, from: BlockNumber, to: BlockNumber, step: Trailing<U64>) -> Result<Vec<BalanceHistoryEntry>> {
+		const MAX_SAMPLES: u64 = 10_000;
+
+		let address: Address = address.into();
+		let step: u64 = step.unwrap_or_else(|| 1.into()).into();
+		if step == 0 {
+			return Err(errors::invalid_params("step", "Step must be greater than zero."));
+		}
+
+		let block_number = |number| {
+			let id = match number {
+				BlockNumber::Pending => BlockId::Latest,
+				other => block_number_to_id(other),
+			};
+			self.client.block_number(id).ok_or_else(errors::unknown_block)
+		};
+		let (from, to) = (block_number(from)?, block_number(to)?);
+		if from > to {
+			return Err(errors::invalid_params("to", "Range must not end before it starts."));
+		}
+		if (to - from) / step >= MAX_SAMPLES {
+			return Err(errors::resource_limit("samples", format!("Range would yield more than {} samples.", MAX_SAMPLES)));
+		}
+
+		// states share the client's state database and its caches, so consecutive samples
+		// mostly resolve the same trie nodes from memory.
+		let mut history = Vec::with_capacity(((to - from) / step + 1) as usize);
+		let mut number = from;
+		while number <= to {
+			let state = self.client.state_at(BlockId::Number(number)).ok_or_else(errors::state_pruned)?;
+			history.push(BalanceHistoryEntry {
+				block_number: number.into(),
+				balance: state.balance(&address).map_err(errors::database)?.into(),
+				nonce: state.nonce(&address).map_err(errors::database)?.into(),
+			});
+
+			number = match number.checked_add(step) {
+				Some(next) => next,
+				None => break,
+			};
+		}
+
+		Ok(history)
+	}
+
+	fn validator_liveness(&self) -> Result<BTreeMap<H160, ValidatorLiveness>> {
+		Ok(self.client.engine().validator_liveness().into_iter()
+			.map(|(address, liveness)| (address.into(), liveness.into()))
+			.collect())
+	}
+
 	fn node_health(&self) -> BoxFuture<Health> {
 		Box::new(self.health.health()
 			.map_err(|err| errors::internal("Health API failure.", err)))
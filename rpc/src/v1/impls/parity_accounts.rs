@@ -21,13 +21,13 @@ use ethereum_types::Address;
 
 use ethkey::{Brain, Generator, Secret};
 use ethstore::KeyFile;
-use ethcore::account_provider::AccountProvider;
+use ethcore::account_provider::{AccountProvider, KeyOperation};
 
 use jsonrpc_core::Result;
 use v1::helpers::errors;
 use v1::helpers::accounts::unwrap_provider;
 use v1::traits::ParityAccounts;
-use v1::types::{H160 as RpcH160, H256 as RpcH256, H520 as RpcH520, DappId, Derive, DeriveHierarchical, DeriveHash, ExtAccountInfo};
+use v1::types::{H160 as RpcH160, H256 as RpcH256, H520 as RpcH520, DappId, Derive, DeriveHierarchical, DeriveHash, ExtAccountInfo, KeyUsage, Origin};
 
 /// Account management (personal) rpc implementation.
 pub struct ParityAccountsClient {
@@ -350,12 +350,23 @@ impl ParityAccounts for ParityAccountsClient {
 			.sign(
 				addr.into(),
 				Some(password),
-				message.into()
+				message.into(),
+				KeyOperation::SignMessage,
+				&Origin::Unknown.to_string(),
 			)
 			.map(Into::into)
 			.map_err(|e| errors::account("Could not sign message.", e))
 	}
 
+	fn key_usage(&self, addr: Option<RpcH160>) -> Result<Vec<KeyUsage>> {
+		let addr = addr.map(Into::into);
+		Ok(self.account_provider()?
+			.key_usage(addr.as_ref())
+			.into_iter()
+			.map(Into::into)
+			.collect())
+	}
+
 	fn hardware_pin_matrix_ack(&self, path: String, pin: String) -> Result<bool> {
 		let store = self.account_provider()?;
 		Ok(store.hardware_pin_matrix_ack(&path, &pin).map_err(|e| errors::account("Error communicating with hardware wallet.", e))?)
@@ -19,17 +19,19 @@ use std::io;
 use std::sync::Arc;
 
 use ethcore::client::BlockChainClient;
-use ethcore::miner::MinerService;
+use ethcore::miner::{self, MinerService};
 use ethcore::mode::Mode;
 use sync::ManageNetwork;
 use fetch::{self, Fetch};
 use futures_cpupool::CpuPool;
 use hash::keccak_buffer;
+use transaction::{SignedTransaction, UnverifiedTransaction, PendingTransaction};
 use updater::{Service as UpdateService};
 
 use jsonrpc_core::{BoxFuture, Result};
 use jsonrpc_core::futures::Future;
 use v1::helpers::dapps::DappsService;
+use v1::helpers::dispatch::FullDispatcher;
 use v1::helpers::errors;
 use v1::traits::ParitySet;
 use v1::types::{Bytes, H160, H256, U256, ReleaseInfo, Transaction, LocalDapp};
@@ -73,7 +75,7 @@ impl<C, M, U, F> ParitySetClient<C, M, U, F>
 }
 
 impl<C, M, U, F> ParitySet for ParitySetClient<C, M, U, F> where
-	C: BlockChainClient + 'static,
+	C: BlockChainClient + miner::BlockChainClient + 'static,
 	M: MinerService + 'static,
 	U: UpdateService + 'static,
 	F: Fetch + 'static,
@@ -210,4 +212,14 @@ impl<C, M, U, F> ParitySet for ParitySetClient<C, M, U, F> where
 		   .map(|t| Transaction::from_pending(t.pending().clone(), block_number + 1, self.eip86_transition))
 		)
 	}
+
+	fn send_raw_transaction_local_only(&self, raw: Bytes) -> Result<H256> {
+		let signed = UnverifiedTransaction::decode_raw(&raw.into_vec())
+			.map_err(errors::rlp)
+			.and_then(|tx| SignedTransaction::new(tx).map_err(errors::transaction))?;
+
+		let mut pending = PendingTransaction::from(signed);
+		pending.local_only = true;
+		FullDispatcher::dispatch_transaction(&*self.client, &*self.miner, pending).map(Into::into)
+	}
 }
@@ -79,10 +79,11 @@ impl<D: Dispatcher + 'static> PersonalClient<D> {
 			Err(e) => return Box::new(future::err(e)),
 		};
 
+		let origin = meta.origin;
 		Box::new(dispatcher.fill_optional_fields(request.into(), default, false)
 			.and_then(move |filled| {
 				let condition = filled.condition.clone().map(Into::into);
-				dispatcher.sign(accounts, filled, SignWith::Password(password))
+				dispatcher.sign(accounts, filled, SignWith::Password(password), origin)
 					.map(|tx| tx.into_value())
 					.map(move |tx| PendingTransaction::new(tx, condition))
 					.map(move |tx| (tx, dispatcher))
@@ -140,7 +141,7 @@ impl<D: Dispatcher + 'static> Personal for PersonalClient<D> {
 		}
 	}
 
-	fn sign(&self, data: RpcBytes, account: RpcH160, password: String) -> BoxFuture<RpcH520> {
+	fn sign(&self, meta: Metadata, data: RpcBytes, account: RpcH160, password: String) -> BoxFuture<RpcH520> {
 		let dispatcher = self.dispatcher.clone();
 		let accounts = try_bf!(self.account_provider());
 
@@ -148,7 +149,7 @@ impl<D: Dispatcher + 'static> Personal for PersonalClient<D> {
 
 		Box::new(dispatch::from_rpc(payload, account.into(), &dispatcher)
 				 .and_then(|payload| {
-					 dispatch::execute(dispatcher, accounts, payload, dispatch::SignWith::Password(password))
+					 dispatch::execute(dispatcher, accounts, payload, dispatch::SignWith::Password(password), meta.origin)
 				 })
 				 .map(|v| v.into_value())
 				 .then(|res| match res {
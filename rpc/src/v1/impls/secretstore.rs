@@ -21,7 +21,7 @@ use std::sync::Arc;
 
 use crypto::DEFAULT_MAC;
 use ethkey::Secret;
-use ethcore::account_provider::AccountProvider;
+use ethcore::account_provider::{AccountProvider, KeyOperation};
 
 use jsonrpc_core::Result;
 use v1::helpers::errors;
@@ -29,7 +29,7 @@ use v1::helpers::accounts::unwrap_provider;
 use v1::helpers::secretstore::{generate_document_key, encrypt_document,
 	decrypt_document, decrypt_document_with_shadow, ordered_servers_keccak};
 use v1::traits::SecretStore;
-use v1::types::{H160, H256, H512, Bytes, EncryptedDocumentKey};
+use v1::types::{H160, H256, H512, Bytes, EncryptedDocumentKey, Origin};
 
 /// Parity implementation.
 pub struct SecretStoreClient {
@@ -53,7 +53,7 @@ impl SecretStoreClient {
 	/// Decrypt public key using account' private key
 	fn decrypt_key(&self, address: H160, password: String, key: Bytes) -> Result<Vec<u8>> {
 		let store = self.account_provider()?;
-		store.decrypt(address.into(), Some(password), &DEFAULT_MAC, &key.0)
+		store.decrypt(address.into(), Some(password), &DEFAULT_MAC, &key.0, &Origin::Unknown.to_string())
 			.map_err(|e| errors::account("Could not decrypt key.", e))
 	}
 
@@ -99,7 +99,7 @@ impl SecretStore for SecretStoreClient {
 	fn sign_raw_hash(&self, address: H160, password: String, raw_hash: H256) -> Result<Bytes> {
 		let store = self.account_provider()?;
 		store
-			.sign(address.into(), Some(password), raw_hash.into())
+			.sign(address.into(), Some(password), raw_hash.into(), KeyOperation::SignMessage, &Origin::Unknown.to_string())
 			.map(|s| Bytes::new((*s).to_vec()))
 			.map_err(|e| errors::account("Could not sign raw hash.", e))
 	}
@@ -35,7 +35,7 @@ use v1::helpers::dispatch::{self, Dispatcher, WithToken, eth_data_hash};
 use v1::helpers::{errors, SignerService, SigningQueue, ConfirmationPayload, FilledTransactionRequest, Subscribers};
 use v1::metadata::Metadata;
 use v1::traits::Signer;
-use v1::types::{TransactionModification, ConfirmationRequest, ConfirmationResponse, ConfirmationResponseWithToken, U256, Bytes};
+use v1::types::{TransactionModification, ConfirmationRequest, ConfirmationResponse, ConfirmationResponseWithToken, Origin, U256, Bytes};
 
 /// Transactions confirmation (personal) rpc implementation.
 pub struct SignerClient<D: Dispatcher> {
@@ -83,7 +83,7 @@ impl<D: Dispatcher + 'static> SignerClient<D> {
 	}
 
 	fn confirm_internal<F, T>(&self, id: U256, modification: TransactionModification, f: F) -> BoxFuture<WithToken<ConfirmationResponse>> where
-		F: FnOnce(D, Arc<AccountProvider>, ConfirmationPayload) -> T,
+		F: FnOnce(D, Arc<AccountProvider>, ConfirmationPayload, Origin) -> T,
 		T: IntoFuture<Item=WithToken<ConfirmationResponse>, Error=Error>,
 		T::Future: Send + 'static
 	{
@@ -111,7 +111,7 @@ impl<D: Dispatcher + 'static> SignerClient<D> {
 					request.condition = condition.clone().map(Into::into);
 				}
 			}
-			let fut = f(dispatcher, accounts, payload);
+			let fut = f(dispatcher, accounts, payload, confirmation.origin.clone());
 			Either::A(fut.into_future().then(move |result| {
 				// Execute
 				if let Ok(ref response) = result {
@@ -172,16 +172,16 @@ impl<D: Dispatcher + 'static> Signer for SignerClient<D> {
 	fn confirm_request(&self, id: U256, modification: TransactionModification, pass: String)
 		-> BoxFuture<ConfirmationResponse>
 	{
-		Box::new(self.confirm_internal(id, modification, move |dis, accounts, payload| {
-			dispatch::execute(dis, accounts, payload, dispatch::SignWith::Password(pass))
+		Box::new(self.confirm_internal(id, modification, move |dis, accounts, payload, origin| {
+			dispatch::execute(dis, accounts, payload, dispatch::SignWith::Password(pass), origin)
 		}).map(|v| v.into_value()))
 	}
 
 	fn confirm_request_with_token(&self, id: U256, modification: TransactionModification, token: String)
 		-> BoxFuture<ConfirmationResponseWithToken>
 	{
-		Box::new(self.confirm_internal(id, modification, move |dis, accounts, payload| {
-			dispatch::execute(dis, accounts, payload, dispatch::SignWith::Token(token))
+		Box::new(self.confirm_internal(id, modification, move |dis, accounts, payload, origin| {
+			dispatch::execute(dis, accounts, payload, dispatch::SignWith::Token(token), origin)
 		}).and_then(|v| match v {
 			WithToken::No(_) => Err(errors::internal("Unexpected response without token.", "")),
 			WithToken::Yes(response, token) => Ok(ConfirmationResponseWithToken {
@@ -126,7 +126,7 @@ impl<D: Dispatcher + 'static> SigningQueueClient<D> {
 			.and_then(move |payload| {
 				let sender = payload.sender();
 				if accounts.is_unlocked(&sender) {
-					Either::A(dispatch::execute(dispatcher, accounts, payload, dispatch::SignWith::Nothing)
+					Either::A(dispatch::execute(dispatcher, accounts, payload, dispatch::SignWith::Nothing, origin)
 						.map(|v| v.into_value())
 						.map(DispatchResult::Value))
 				} else {
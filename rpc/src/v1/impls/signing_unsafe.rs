@@ -35,6 +35,7 @@ use v1::types::{
 	TransactionRequest as RpcTransactionRequest,
 	ConfirmationPayload as RpcConfirmationPayload,
 	ConfirmationResponse as RpcConfirmationResponse,
+	Origin,
 };
 
 /// Implementation of functions that require signing when no trusted signer is used.
@@ -56,7 +57,7 @@ impl<D: Dispatcher + 'static> SigningUnsafeClient<D> {
 		unwrap_provider(&self.accounts)
 	}
 
-	fn handle(&self, payload: RpcConfirmationPayload, account: DefaultAccount) -> BoxFuture<RpcConfirmationResponse> {
+	fn handle(&self, payload: RpcConfirmationPayload, account: DefaultAccount, origin: Origin) -> BoxFuture<RpcConfirmationResponse> {
 		let accounts = try_bf!(self.account_provider());
 		let default = match account {
 			DefaultAccount::Provided(acc) => acc,
@@ -66,7 +67,7 @@ impl<D: Dispatcher + 'static> SigningUnsafeClient<D> {
 		let dis = self.dispatcher.clone();
 		Box::new(dispatch::from_rpc(payload, default, &dis)
 			.and_then(move |payload| {
-				dispatch::execute(dis, accounts, payload, dispatch::SignWith::Nothing)
+				dispatch::execute(dis, accounts, payload, dispatch::SignWith::Nothing, origin)
 			})
 			.map(|v| v.into_value()))
 	}
@@ -76,8 +77,8 @@ impl<D: Dispatcher + 'static> EthSigning for SigningUnsafeClient<D>
 {
 	type Metadata = Metadata;
 
-	fn sign(&self, _: Metadata, address: RpcH160, data: RpcBytes) -> BoxFuture<RpcH520> {
-		Box::new(self.handle(RpcConfirmationPayload::EthSignMessage((address.clone(), data).into()), address.into())
+	fn sign(&self, meta: Metadata, address: RpcH160, data: RpcBytes) -> BoxFuture<RpcH520> {
+		Box::new(self.handle(RpcConfirmationPayload::EthSignMessage((address.clone(), data).into()), address.into(), meta.origin)
 			.then(|res| match res {
 				Ok(RpcConfirmationResponse::Signature(signature)) => Ok(signature),
 				Err(e) => Err(e),
@@ -86,7 +87,7 @@ impl<D: Dispatcher + 'static> EthSigning for SigningUnsafeClient<D>
 	}
 
 	fn send_transaction(&self, meta: Metadata, request: RpcTransactionRequest) -> BoxFuture<RpcH256> {
-		Box::new(self.handle(RpcConfirmationPayload::SendTransaction(request), meta.dapp_id().into())
+		Box::new(self.handle(RpcConfirmationPayload::SendTransaction(request), meta.dapp_id().into(), meta.origin)
 			.then(|res| match res {
 				Ok(RpcConfirmationResponse::SendTransaction(hash)) => Ok(hash),
 				Err(e) => Err(e),
@@ -95,7 +96,7 @@ impl<D: Dispatcher + 'static> EthSigning for SigningUnsafeClient<D>
 	}
 
 	fn sign_transaction(&self, meta: Metadata, request: RpcTransactionRequest) -> BoxFuture<RpcRichRawTransaction> {
-		Box::new(self.handle(RpcConfirmationPayload::SignTransaction(request), meta.dapp_id().into())
+		Box::new(self.handle(RpcConfirmationPayload::SignTransaction(request), meta.dapp_id().into(), meta.origin)
 			.then(|res| match res {
 				Ok(RpcConfirmationResponse::SignTransaction(tx)) => Ok(tx),
 				Err(e) => Err(e),
@@ -113,8 +114,8 @@ impl<D: Dispatcher + 'static> ParitySigning for SigningUnsafeClient<D> {
 		Box::new(self.dispatcher.fill_optional_fields(transaction.into(), default_account, true).map(Into::into))
 	}
 
-	fn decrypt_message(&self, _: Metadata, address: RpcH160, data: RpcBytes) -> BoxFuture<RpcBytes> {
-		Box::new(self.handle(RpcConfirmationPayload::Decrypt((address.clone(), data).into()), address.into())
+	fn decrypt_message(&self, meta: Metadata, address: RpcH160, data: RpcBytes) -> BoxFuture<RpcBytes> {
+		Box::new(self.handle(RpcConfirmationPayload::Decrypt((address.clone(), data).into()), address.into(), meta.origin)
 			.then(|res| match res {
 				Ok(RpcConfirmationResponse::Decrypt(data)) => Ok(data),
 				Err(e) => Err(e),
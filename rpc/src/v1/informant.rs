@@ -221,16 +221,20 @@ impl<M: rpc::Metadata, T: ActivityNotifier> rpc::Middleware<M> for Middleware<T>
 		self.notifier.active();
 		self.stats.count_request();
 
-		let id = match request {
-			rpc::Request::Single(rpc::Call::MethodCall(ref call)) => Some(call.id.clone()),
-			_ => None,
+		let (id, method) = match request {
+			rpc::Request::Single(rpc::Call::MethodCall(ref call)) => (Some(call.id.clone()), Some(call.method.clone())),
+			_ => (None, None),
 		};
+		trace!(target: "rpc", "[{:?}] Request {:?}", id, method);
 		let stats = self.stats.clone();
 		let future = process(request, meta).map(move |res| {
 			let time = Self::as_micro(start.elapsed());
 			if time > 10_000 {
 				debug!(target: "rpc", "[{:?}] Took {}ms", id, time / 1_000);
 			}
+			if let Some(rpc::Response::Single(rpc::Output::Failure(ref failure))) = res {
+				debug!(target: "rpc", "[{:?}] {:?} failed with {}: {}", id, method, failure.error.code.code(), failure.error.message);
+			}
 			stats.add_roundtrip(time);
 			res
 		});
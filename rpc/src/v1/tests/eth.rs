@@ -216,6 +216,25 @@ fn eth_get_block_by_hash() {
 	assert_eq!(tester.handler.handle_request_sync(req_block).unwrap(), res_block);
 }
 
+#[test]
+fn eth_get_block_with_raw() {
+	use rustc_hex::ToHex;
+	use serde_json;
+
+	let chain = extract_chain!("BlockchainTests/bcGasPricerTest/RPC_API_Test");
+	let tester = EthTester::from_chain(&chain);
+	let raw = tester.client.block(BlockId::Number(4)).unwrap().into_inner();
+
+	let req_block = r#"{"method":"eth_getBlockByNumber","params":["0x4",false,true],"id":1,"jsonrpc":"2.0"}"#;
+	let res: serde_json::Value = serde_json::from_str(&tester.handler.handle_request_sync(req_block).unwrap()).unwrap();
+	assert_eq!(res["result"]["raw"], format!("0x{}", raw.to_hex()));
+
+	// raw encoding is omitted unless requested
+	let req_block = r#"{"method":"eth_getBlockByNumber","params":["0x4",false],"id":1,"jsonrpc":"2.0"}"#;
+	let res: serde_json::Value = serde_json::from_str(&tester.handler.handle_request_sync(req_block).unwrap()).unwrap();
+	assert!(res["result"].get("raw").is_none());
+}
+
 // a frontier-like test with an expanded gas limit and balance on known account.
 const TRANSACTION_COUNT_SPEC: &'static [u8] = br#"{
 	"name": "Frontier (Test)",
@@ -218,6 +218,16 @@ impl MinerService for TestMinerService {
 		}).collect()
 	}
 
+	fn queued_transactions_by_sender(&self, address: &Address) -> Vec<Arc<VerifiedTransaction>> {
+		self.queued_transactions().into_iter().filter(|tx| tx.sender() == address).collect()
+	}
+
+	fn queued_transactions_within_limits<C>(&self, _chain: &C, block_gas_limit: U256, min_gas_price: U256) -> Vec<Arc<VerifiedTransaction>> {
+		self.queued_transactions().into_iter().filter(|tx| {
+			tx.signed().gas <= block_gas_limit && tx.signed().gas_price >= min_gas_price
+		}).collect()
+	}
+
 	fn pending_receipt(&self, _best_block: BlockNumber, hash: &H256) -> Option<RichReceipt> {
 		// Not much point implementing this since the logic is complex and the only thing it relies on is pending_receipts, which is already tested.
 		self.pending_receipts(0).unwrap().get(hash).map(|r|
@@ -21,7 +21,7 @@ use std::time::{Instant, Duration, SystemTime, UNIX_EPOCH};
 
 use ethereum_types::{H256, U256, Address};
 use parking_lot::Mutex;
-use ethcore::account_provider::AccountProvider;
+use ethcore::account_provider::{AccountProvider, KeyOperation};
 use ethcore::client::{BlockChainClient, BlockId, EachBlockWith, Executed, TestBlockChainClient, TransactionId};
 use ethcore::log_entry::{LocalizedLogEntry, LogEntry};
 use ethcore::miner::MinerService;
@@ -582,6 +582,39 @@ fn rpc_eth_pending_transaction_by_hash() {
 	assert_eq!(tester.io.handle_request_sync(request), Some(response.to_owned()));
 }
 
+#[test]
+fn rpc_eth_pending_transaction_by_sender_and_nonce() {
+	use ethereum_types::H256;
+	use rlp;
+	use transaction::SignedTransaction;
+
+	let tester = EthTester::default();
+	{
+		let bytes = FromHex::from_hex("f85f800182520894095e7baea6a6c7c4c2dfeb977efac326af552d870a801ba048b55bfa915ac795c431978d8a6a992b628d557da5ff759b307d495a36649353a0efffd310ac743f371de3b9f7f9cb56c0b28ad43601b4ab949f53faa07bd2c804").unwrap();
+		let tx = rlp::decode(&bytes).expect("decoding failure");
+		let tx = SignedTransaction::new(tx).unwrap();
+		tester.miner.pending_transactions.lock().insert(H256::zero(), tx);
+	}
+
+	let response = r#"{"jsonrpc":"2.0","result":{"blockHash":null,"blockNumber":null,"chainId":null,"condition":null,"creates":null,"from":"0x0f65fe9276bc9a24ae7083ae28e2660ef72df99e","gas":"0x5208","gasPrice":"0x1","hash":"0x41df922fd0d4766fcc02e161f8295ec28522f329ae487f14d811e4b64c8d6e31","input":"0x","nonce":"0x0","publicKey":"0x7ae46da747962c2ee46825839c1ef9298e3bd2e70ca2938495c3693a485ec3eaa8f196327881090ff64cf4fbb0a48485d4f83098e189ed3b7a87d5941b59f789","r":"0x48b55bfa915ac795c431978d8a6a992b628d557da5ff759b307d495a36649353","raw":"0xf85f800182520894095e7baea6a6c7c4c2dfeb977efac326af552d870a801ba048b55bfa915ac795c431978d8a6a992b628d557da5ff759b307d495a36649353a0efffd310ac743f371de3b9f7f9cb56c0b28ad43601b4ab949f53faa07bd2c804","s":"0xefffd310ac743f371de3b9f7f9cb56c0b28ad43601b4ab949f53faa07bd2c804","standardV":"0x0","to":"0x095e7baea6a6c7c4c2dfeb977efac326af552d87","transactionIndex":null,"v":"0x1b","value":"0xa"},"id":1}"#;
+	let request = r#"{
+		"jsonrpc": "2.0",
+		"method": "eth_getTransactionBySenderAndNonce",
+		"params": ["0x0f65fe9276bc9a24ae7083ae28e2660ef72df99e", "0x0"],
+		"id": 1
+	}"#;
+	assert_eq!(tester.io.handle_request_sync(request), Some(response.to_owned()));
+
+	let response = r#"{"jsonrpc":"2.0","result":null,"id":1}"#;
+	let request = r#"{
+		"jsonrpc": "2.0",
+		"method": "eth_getTransactionBySenderAndNonce",
+		"params": ["0x0f65fe9276bc9a24ae7083ae28e2660ef72df99e", "0x1"],
+		"id": 1
+	}"#;
+	assert_eq!(tester.io.handle_request_sync(request), Some(response.to_owned()));
+}
+
 
 #[test]
 fn rpc_eth_uncle_count_by_block_hash() {
@@ -640,6 +673,7 @@ fn rpc_eth_call_latest() {
 		trace: vec![],
 		vm_trace: None,
 		state_diff: None,
+		access_counts: Default::default(),
 	}));
 
 	let request = r#"{
@@ -676,6 +710,7 @@ fn rpc_eth_call() {
 		trace: vec![],
 		vm_trace: None,
 		state_diff: None,
+		access_counts: Default::default(),
 	}));
 
 	let request = r#"{
@@ -697,6 +732,40 @@ fn rpc_eth_call() {
 	assert_eq!(tester.io.handle_request_sync(request), Some(response.to_owned()));
 }
 
+#[test]
+fn rpc_eth_call_reverted() {
+	let tester = EthTester::default();
+	tester.client.set_execution_result(Ok(Executed {
+		exception: Some(::vm::Error::Reverted),
+		gas: U256::zero(),
+		gas_used: U256::from(0xff30),
+		refunded: U256::from(0x5),
+		cumulative_gas_used: U256::zero(),
+		logs: vec![],
+		contracts_created: vec![],
+		output: vec![0x12, 0x34, 0xff],
+		trace: vec![],
+		vm_trace: None,
+		state_diff: None,
+		access_counts: Default::default(),
+	}));
+
+	let request = r#"{
+		"jsonrpc": "2.0",
+		"method": "eth_call",
+		"params": [{
+			"from": "0xb60e8dd61c5d32be8058bb8eb970870f07233155",
+			"to": "0xd46e8dd67c5d32be8058bb8eb970870f07244567",
+			"data": "0xd46e8dd6"
+		},
+		"latest"],
+		"id": 1
+	}"#;
+	let response = r#"{"jsonrpc":"2.0","error":{"code":-32019,"message":"Execution reverted.","data":"0x1234ff"},"id":1}"#;
+
+	assert_eq!(tester.io.handle_request_sync(request), Some(response.to_owned()));
+}
+
 #[test]
 fn rpc_eth_call_default_block() {
 	let tester = EthTester::default();
@@ -712,6 +781,7 @@ fn rpc_eth_call_default_block() {
 		trace: vec![],
 		vm_trace: None,
 		state_diff: None,
+		access_counts: Default::default(),
 	}));
 
 	let request = r#"{
@@ -747,6 +817,7 @@ fn rpc_eth_estimate_gas() {
 		trace: vec![],
 		vm_trace: None,
 		state_diff: None,
+		access_counts: Default::default(),
 	}));
 
 	let request = r#"{
@@ -783,6 +854,7 @@ fn rpc_eth_estimate_gas_default_block() {
 		trace: vec![],
 		vm_trace: None,
 		state_diff: None,
+		access_counts: Default::default(),
 	}));
 
 	let request = r#"{
@@ -803,6 +875,26 @@ fn rpc_eth_estimate_gas_default_block() {
 	assert_eq!(tester.io.handle_request_sync(request), Some(response.to_owned()));
 }
 
+#[test]
+fn rpc_eth_create_access_list() {
+	let tester = EthTester::default();
+
+	let request = r#"{
+		"jsonrpc": "2.0",
+		"method": "eth_createAccessList",
+		"params": [{
+			"from": "0xb60e8dd61c5d32be8058bb8eb970870f07233155",
+			"to": "0xd46e8dd67c5d32be8058bb8eb970870f07244567",
+			"data": "0xd46e8dd67c5d32be8d46e8dd67c5d32be8058bb8eb970870f072445675058bb8eb970870f072445675"
+		},
+		"latest"],
+		"id": 1
+	}"#;
+	let response = r#"{"jsonrpc":"2.0","result":{"accessList":[],"gasUsed":"0x5208"},"id":1}"#;
+
+	assert_eq!(tester.io.handle_request_sync(request), Some(response.to_owned()));
+}
+
 #[test]
 fn rpc_eth_send_transaction() {
 	let tester = EthTester::default();
@@ -829,7 +921,7 @@ fn rpc_eth_send_transaction() {
 		value: U256::from(0x9184e72au64),
 		data: vec![]
 	};
-	let signature = tester.accounts_provider.sign(address, None, t.hash(None)).unwrap();
+	let signature = tester.accounts_provider.sign(address, None, t.hash(None), KeyOperation::SignMessage, "test").unwrap();
 	let t = t.with_signature(signature, None);
 
 	let response = r#"{"jsonrpc":"2.0","result":""#.to_owned() + format!("0x{:x}", t.hash()).as_ref() + r#"","id":1}"#;
@@ -846,7 +938,7 @@ fn rpc_eth_send_transaction() {
 		value: U256::from(0x9184e72au64),
 		data: vec![]
 	};
-	let signature = tester.accounts_provider.sign(address, None, t.hash(None)).unwrap();
+	let signature = tester.accounts_provider.sign(address, None, t.hash(None), KeyOperation::SignMessage, "test").unwrap();
 	let t = t.with_signature(signature, None);
 
 	let response = r#"{"jsonrpc":"2.0","result":""#.to_owned() + format!("0x{:x}", t.hash()).as_ref() + r#"","id":1}"#;
@@ -880,7 +972,7 @@ fn rpc_eth_sign_transaction() {
 		value: U256::from(0x9184e72au64),
 		data: vec![]
 	};
-	let signature = tester.accounts_provider.sign(address, None, t.hash(None)).unwrap();
+	let signature = tester.accounts_provider.sign(address, None, t.hash(None), KeyOperation::SignMessage, "test").unwrap();
 	let t = t.with_signature(signature, None);
 	let signature = t.signature();
 	let rlp = rlp::encode(&t);
@@ -986,7 +1078,7 @@ fn rpc_eth_send_raw_transaction() {
 		value: U256::from(0x9184e72au64),
 		data: vec![]
 	};
-	let signature = tester.accounts_provider.sign(address, None, t.hash(None)).unwrap();
+	let signature = tester.accounts_provider.sign(address, None, t.hash(None), KeyOperation::SignMessage, "test").unwrap();
 	let t = t.with_signature(signature, None);
 
 	let rlp = rlp::encode(&t).into_vec().to_hex();
@@ -403,6 +403,28 @@ fn rpc_parity_pending_transactions() {
 	assert_eq!(io.handle_request_sync(request), Some(response.to_owned()));
 }
 
+#[test]
+fn rpc_parity_queued_transactions_by_sender() {
+	let deps = Dependencies::new();
+	let io = deps.default_client();
+
+	let request = r#"{"jsonrpc": "2.0", "method": "parity_queuedTransactionsBySender", "params":["0x0000000000000000000000000000000000000005"], "id": 1}"#;
+	let response = r#"{"jsonrpc":"2.0","result":[],"id":1}"#;
+
+	assert_eq!(io.handle_request_sync(request), Some(response.to_owned()));
+}
+
+#[test]
+fn rpc_parity_queued_transactions_within_limits() {
+	let deps = Dependencies::new();
+	let io = deps.default_client();
+
+	let request = r#"{"jsonrpc": "2.0", "method": "parity_queuedTransactionsWithinLimits", "params":["0x5208", "0x1"], "id": 1}"#;
+	let response = r#"{"jsonrpc":"2.0","result":[],"id":1}"#;
+
+	assert_eq!(io.handle_request_sync(request), Some(response.to_owned()));
+}
+
 #[test]
 fn rpc_parity_encrypt() {
 	let deps = Dependencies::new();
@@ -488,20 +510,22 @@ fn rpc_parity_transactions_stats() {
 fn rpc_parity_local_transactions() {
 	let deps = Dependencies::new();
 	let io = deps.default_client();
-	let tx = ::transaction::Transaction {
+	let new_tx = |nonce: u64| ::transaction::Transaction {
 		value: 5.into(),
 		gas: 3.into(),
 		gas_price: 2.into(),
 		action: ::transaction::Action::Create,
 		data: vec![1, 2, 3],
-		nonce: 0.into(),
+		nonce: nonce.into(),
 	}.fake_sign(3.into());
-	let tx = Arc::new(::miner::pool::VerifiedTransaction::from_pending_block_transaction(tx));
-	deps.miner.local_transactions.lock().insert(10.into(), LocalTransactionStatus::Pending(tx.clone()));
-	deps.miner.local_transactions.lock().insert(15.into(), LocalTransactionStatus::Pending(tx.clone()));
+	let ready = Arc::new(::miner::pool::VerifiedTransaction::from_pending_block_transaction(new_tx(0)));
+	let future = Arc::new(::miner::pool::VerifiedTransaction::from_pending_block_transaction(new_tx(2)));
+	deps.miner.local_transactions.lock().insert(10.into(), LocalTransactionStatus::Pending(ready.clone()));
+	deps.miner.local_transactions.lock().insert(15.into(), LocalTransactionStatus::Pending(ready.clone()));
+	deps.miner.local_transactions.lock().insert(20.into(), LocalTransactionStatus::Pending(future));
 
 	let request = r#"{"jsonrpc": "2.0", "method": "parity_localTransactions", "params":[], "id": 1}"#;
-	let response = r#"{"jsonrpc":"2.0","result":{"0x000000000000000000000000000000000000000000000000000000000000000a":{"status":"pending"},"0x000000000000000000000000000000000000000000000000000000000000000f":{"status":"pending"}},"id":1}"#;
+	let response = r#"{"jsonrpc":"2.0","result":{"0x000000000000000000000000000000000000000000000000000000000000000a":{"status":"pending"},"0x000000000000000000000000000000000000000000000000000000000000000f":{"status":"pending"},"0x0000000000000000000000000000000000000000000000000000000000000014":{"status":"future"}},"id":1}"#;
 
 	assert_eq!(io.handle_request_sync(request), Some(response.to_owned()));
 }
@@ -542,6 +566,28 @@ fn rpc_parity_cid() {
 	assert_eq!(io.handle_request_sync(request), Some(response.to_owned()));
 }
 
+#[test]
+fn rpc_parity_balance_history_rejects_zero_step() {
+	let deps = Dependencies::new();
+	let io = deps.default_client();
+
+	let request = r#"{"jsonrpc": "2.0", "method": "parity_getBalanceHistory", "params":["0x0000000000000000000000000000000000000001", "earliest", "latest", "0x0"], "id": 1}"#;
+	let response = r#"{"jsonrpc":"2.0","error":{"code":-32602,"message":"Couldn't parse parameters: step","data":"\"Step must be greater than zero.\""},"id":1}"#;
+
+	assert_eq!(io.handle_request_sync(request), Some(response.to_owned()));
+}
+
+#[test]
+fn rpc_parity_validator_liveness() {
+	let deps = Dependencies::new();
+	let io = deps.default_client();
+
+	let request = r#"{"jsonrpc": "2.0", "method": "parity_validatorLiveness", "params":[], "id": 1}"#;
+	let response = r#"{"jsonrpc":"2.0","result":{},"id":1}"#;
+
+	assert_eq!(io.handle_request_sync(request), Some(response.to_owned()));
+}
+
 #[test]
 fn rpc_parity_call() {
 	let deps = Dependencies::new();
@@ -557,6 +603,7 @@ fn rpc_parity_call() {
 		trace: vec![],
 		vm_trace: None,
 		state_diff: None,
+		access_counts: Default::default(),
 	}));
 	let io = deps.default_client();
 
@@ -589,3 +636,21 @@ fn rpc_parity_node_health() {
 
 	assert_eq!(io.handle_request_sync(request), Some(response.to_owned()));
 }
+
+#[test]
+fn rpc_parity_get_raw_block_by_number() {
+	use ethcore::client::{BlockChainClient, BlockId};
+	use rustc_hex::ToHex;
+
+	let deps = Dependencies::new();
+	let io = deps.default_client();
+	let raw = deps.client.block(BlockId::Number(0)).unwrap().into_inner();
+
+	let request = r#"{"jsonrpc": "2.0", "method": "parity_getRawBlockByNumber", "params":["0x0"], "id": 1}"#;
+	let response = format!(r#"{{"jsonrpc":"2.0","result":"0x{}","id":1}}"#, raw.to_hex());
+	assert_eq!(io.handle_request_sync(request), Some(response));
+
+	let request = r#"{"jsonrpc": "2.0", "method": "parity_getRawBlockByNumber", "params":["0x10"], "id": 1}"#;
+	let response = r#"{"jsonrpc":"2.0","result":null,"id":1}"#;
+	assert_eq!(io.handle_request_sync(request), Some(response.to_owned()));
+}
@@ -537,3 +537,24 @@ fn should_sign_message() {
 	let res = tester.io.handle_request_sync(&request);
 	assert_eq!(res, Some(response.into()));
 }
+
+#[test]
+fn should_record_key_usage() {
+	let tester = setup();
+	tester.accounts.insert_account("0a0a0a0a0a0a0a0a0a0a0a0a0a0a0a0a0a0a0a0a0a0a0a0a0a0a0a0a0a0a0a0a".parse().unwrap(), "password1").unwrap();
+
+	let request = r#"{"jsonrpc": "2.0", "method": "parity_signMessage", "params": ["0xc171033d5cbff7175f29dfd3a63dda3d6f8f385e", "password1", "0xbc36789e7a1e281436464229828f817d6612f7b477d66591ff96a9e064bcc98a"], "id": 1}"#;
+	tester.io.handle_request_sync(&request).unwrap();
+	let request = r#"{"jsonrpc": "2.0", "method": "parity_signMessage", "params": ["0xc171033d5cbff7175f29dfd3a63dda3d6f8f385e", "wrong", "0xbc36789e7a1e281436464229828f817d6612f7b477d66591ff96a9e064bcc98a"], "id": 2}"#;
+	tester.io.handle_request_sync(&request).unwrap();
+
+	let usage = tester.accounts.key_usage(None);
+	assert_eq!(usage.len(), 2);
+	assert!(usage[0].success);
+	assert!(!usage[1].success);
+
+	let request = r#"{"jsonrpc": "2.0", "method": "parity_keyUsage", "params": ["0xc171033d5cbff7175f29dfd3a63dda3d6f8f385e"], "id": 3}"#;
+	let res = tester.io.handle_request_sync(&request).unwrap();
+	let expected = format!(r#"{{"address":"0xc171033d5cbff7175f29dfd3a63dda3d6f8f385e","operation":"signMessage","origin":"unknown origin","success":true,"timestamp":{}}}"#, usage[0].timestamp);
+	assert!(res.contains(&expected), "unexpected response: {}", res);
+}
@@ -16,7 +16,7 @@
 
 use std::sync::Arc;
 use std::str::FromStr;
-use rustc_hex::FromHex;
+use rustc_hex::{FromHex, ToHex};
 use ethereum_types::{U256, Address};
 
 use ethcore::miner::MinerService;
@@ -241,6 +241,37 @@ fn rpc_parity_remove_transaction() {
 	assert_eq!(io.handle_request_sync(&request), Some(response.to_owned()));
 }
 
+#[test]
+fn rpc_parity_send_raw_transaction_local_only() {
+	use ethkey::{Generator, Random};
+	use rlp;
+	use transaction::{Transaction, Action};
+
+	let miner = miner_service();
+	let client = client_service();
+	let network = network_service();
+	let updater = updater_service();
+	let mut io = IoHandler::new();
+	io.extend_with(parity_set_client(&client, &miner, &updater, &network).to_delegate());
+
+	let tx = Transaction {
+		nonce: 0.into(),
+		gas_price: 0x9184e72a000u64.into(),
+		gas: 0x76c0.into(),
+		action: Action::Call(5.into()),
+		value: 0x9184e72au64.into(),
+		data: vec![]
+	};
+	let signed = tx.sign(Random.generate().unwrap().secret(), None);
+	let hash = signed.hash();
+
+	let request = r#"{"jsonrpc": "2.0", "method": "parity_sendRawTransactionLocalOnly", "params":["0x"#.to_owned() + &rlp::encode(&signed).into_vec().to_hex() + r#""], "id": 1}"#;
+	let response = r#"{"jsonrpc":"2.0","result":""#.to_owned() + &format!("0x{:x}", hash) + r#"","id":1}"#;
+
+	assert_eq!(io.handle_request_sync(&request), Some(response));
+	assert_eq!(miner.imported_transactions.lock().len(), 1);
+}
+
 #[test]
 fn rpc_parity_set_dapps_list() {
 	let miner = miner_service();
@@ -19,7 +19,7 @@ use std::str::FromStr;
 
 use bytes::ToPretty;
 use ethereum_types::{U256, Address};
-use ethcore::account_provider::AccountProvider;
+use ethcore::account_provider::{AccountProvider, KeyOperation};
 use ethcore::client::TestBlockChainClient;
 use jsonrpc_core::IoHandler;
 use parking_lot::Mutex;
@@ -138,7 +138,7 @@ fn sign() {
 	}"#;
 
 	let hash = eth_data_hash(data);
-	let signature = H520(tester.accounts.sign(address, Some("password123".into()), hash).unwrap().into_electrum());
+	let signature = H520(tester.accounts.sign(address, Some("password123".into()), hash, KeyOperation::SignMessage, "test").unwrap().into_electrum());
 	let signature = format!("0x{:?}", signature);
 
 	let response = r#"{"jsonrpc":"2.0","result":""#.to_owned() + &signature + r#"","id":1}"#;
@@ -213,7 +213,7 @@ fn sign_and_send_test(method: &str) {
 		data: vec![]
 	};
 	tester.accounts.unlock_account_temporarily(address, "password123".into()).unwrap();
-	let signature = tester.accounts.sign(address, None, t.hash(None)).unwrap();
+	let signature = tester.accounts.sign(address, None, t.hash(None), KeyOperation::SignMessage, "test").unwrap();
 	let t = t.with_signature(signature, None);
 
 	let response = r#"{"jsonrpc":"2.0","result":""#.to_owned() + format!("0x{:x}", t.hash()).as_ref() + r#"","id":1}"#;
@@ -231,7 +231,7 @@ fn sign_and_send_test(method: &str) {
 		data: vec![]
 	};
 	tester.accounts.unlock_account_temporarily(address, "password123".into()).unwrap();
-	let signature = tester.accounts.sign(address, None, t.hash(None)).unwrap();
+	let signature = tester.accounts.sign(address, None, t.hash(None), KeyOperation::SignMessage, "test").unwrap();
 	let t = t.with_signature(signature, None);
 
 	let response = r#"{"jsonrpc":"2.0","result":""#.to_owned() + format!("0x{:x}", t.hash()).as_ref() + r#"","id":1}"#;
@@ -246,7 +246,7 @@ fn ec_recover() {
 	let data = vec![5u8];
 
 	let hash = eth_data_hash(data.clone());
-	let signature = H520(tester.accounts.sign(address, Some("password123".into()), hash).unwrap().into_electrum());
+	let signature = H520(tester.accounts.sign(address, Some("password123".into()), hash, KeyOperation::SignMessage, "test").unwrap().into_electrum());
 	let signature = format!("0x{:?}", signature);
 
 	let request = r#"{
@@ -303,7 +303,7 @@ fn should_unlock_not_account_temporarily_if_allow_perm_is_disabled() {
 	let response = r#"{"jsonrpc":"2.0","error":{"code":-32000,"message":"Time-unlocking is only supported in --geth compatibility mode.","data":"Restart your client with --geth flag or use personal_sendTransaction instead."},"id":1}"#;
 	assert_eq!(tester.io.handle_request_sync(&request), Some(response.into()));
 
-	assert!(tester.accounts.sign(address, None, Default::default()).is_err(), "Should not unlock account.");
+	assert!(tester.accounts.sign(address, None, Default::default(), KeyOperation::SignMessage, "test").is_err(), "Should not unlock account.");
 }
 
 #[test]
@@ -323,5 +323,5 @@ fn should_unlock_account_permanently() {
 	}"#;
 	let response = r#"{"jsonrpc":"2.0","result":true,"id":1}"#;
 	assert_eq!(tester.io.handle_request_sync(&request), Some(response.into()));
-	assert!(tester.accounts.sign(address, None, Default::default()).is_ok(), "Should unlock account.");
+	assert!(tester.accounts.sign(address, None, Default::default(), KeyOperation::SignMessage, "test").is_ok(), "Should unlock account.");
 }
@@ -171,5 +171,6 @@ fn rpc_secretstore_generate_document_key() {
 		"00dfE63B22312ab4329aD0d28CaD8Af987A01932".parse().unwrap(),
 		Some("password".into()),
 		&DEFAULT_MAC,
-		&generation_response.encrypted_key.0).is_ok());
+		&generation_response.encrypted_key.0,
+		"test").is_ok());
 }
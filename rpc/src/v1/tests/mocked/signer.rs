@@ -19,7 +19,7 @@ use std::str::FromStr;
 use ethereum_types::{U256, Address};
 use bytes::ToPretty;
 
-use ethcore::account_provider::AccountProvider;
+use ethcore::account_provider::{AccountProvider, KeyOperation};
 use ethcore::client::TestBlockChainClient;
 use parity_reactor::EventLoop;
 use parking_lot::Mutex;
@@ -204,7 +204,7 @@ fn should_confirm_transaction_and_dispatch() {
 		data: vec![]
 	};
 	tester.accounts.unlock_account_temporarily(address, "test".into()).unwrap();
-	let signature = tester.accounts.sign(address, None, t.hash(None)).unwrap();
+	let signature = tester.accounts.sign(address, None, t.hash(None), KeyOperation::SignMessage, "test").unwrap();
 	let t = t.with_signature(signature, None);
 
 	assert_eq!(tester.signer.requests().len(), 1);
@@ -251,7 +251,7 @@ fn should_alter_the_sender_and_nonce() {
 	};
 
 	let address = tester.accounts.new_account("test").unwrap();
-	let signature = tester.accounts.sign(address, Some("test".into()), t.hash(None)).unwrap();
+	let signature = tester.accounts.sign(address, Some("test".into()), t.hash(None), KeyOperation::SignMessage, "test").unwrap();
 	let t = t.with_signature(signature, None);
 
 	assert_eq!(tester.signer.requests().len(), 1);
@@ -299,7 +299,7 @@ fn should_confirm_transaction_with_token() {
 		value: U256::from(0x1),
 		data: vec![]
 	};
-	let (signature, token) = tester.accounts.sign_with_token(address, "test".into(), t.hash(None)).unwrap();
+	let (signature, token) = tester.accounts.sign_with_token(address, "test".into(), t.hash(None), KeyOperation::SignMessage, "test").unwrap();
 	let t = t.with_signature(signature, None);
 
 	assert_eq!(tester.signer.requests().len(), 1);
@@ -348,7 +348,7 @@ fn should_confirm_transaction_with_rlp() {
 		value: U256::from(0x1),
 		data: vec![]
 	};
-	let signature = tester.accounts.sign(address, Some("test".into()), t.hash(None)).unwrap();
+	let signature = tester.accounts.sign(address, Some("test".into()), t.hash(None), KeyOperation::SignMessage, "test").unwrap();
 	let t = t.with_signature(signature, None);
 	let rlp = encode(&t);
 
@@ -396,7 +396,7 @@ fn should_return_error_when_sender_does_not_match() {
 		data: vec![]
 	};
 	tester.accounts.unlock_account_temporarily(address, "test".into()).unwrap();
-	let signature = tester.accounts.sign(address, None, t.hash(None)).unwrap();
+	let signature = tester.accounts.sign(address, None, t.hash(None), KeyOperation::SignMessage, "test").unwrap();
 	let t = t.with_signature(signature, None);
 	let rlp = encode(&t);
 
@@ -443,7 +443,7 @@ fn should_confirm_sign_transaction_with_rlp() {
 		value: U256::from(0x1),
 		data: vec![]
 	};
-	let signature = tester.accounts.sign(address, Some("test".into()), t.hash(None)).unwrap();
+	let signature = tester.accounts.sign(address, Some("test".into()), t.hash(None), KeyOperation::SignMessage, "test").unwrap();
 	let t = SignedTransaction::new(t.with_signature(signature.clone(), None)).unwrap();
 	let rlp = encode(&t);
 
@@ -493,7 +493,7 @@ fn should_confirm_data_sign_with_signature() {
 	assert_eq!(tester.signer.requests().len(), 1);
 
 	let data_hash = eth_data_hash(vec![1, 2, 3, 4].into());
-	let signature = H520(tester.accounts.sign(address, Some("test".into()), data_hash).unwrap().into_electrum());
+	let signature = H520(tester.accounts.sign(address, Some("test".into()), data_hash, KeyOperation::SignMessage, "test").unwrap().into_electrum());
 	let signature = format!("0x{:?}", signature);
 
 	// when
@@ -32,7 +32,7 @@ use v1::tests::mocked::parity;
 
 use ethereum_types::{U256, Address};
 use bytes::ToPretty;
-use ethcore::account_provider::AccountProvider;
+use ethcore::account_provider::{AccountProvider, KeyOperation};
 use ethcore::client::TestBlockChainClient;
 use ethkey::Secret;
 use ethstore::ethkey::{Generator, Random};
@@ -299,7 +299,7 @@ fn should_add_sign_transaction_to_the_queue() {
 		value: U256::from(0x9184e72au64),
 		data: vec![]
 	};
-	let signature = tester.accounts.sign(address, Some("test".into()), t.hash(None)).unwrap();
+	let signature = tester.accounts.sign(address, Some("test".into()), t.hash(None), KeyOperation::SignMessage, "test").unwrap();
 	let t = t.with_signature(signature, None);
 	let t = SignedTransaction::new(t).unwrap();
 	let signature = t.signature();
@@ -362,7 +362,7 @@ fn should_dispatch_transaction_if_account_is_unlock() {
 		value: U256::from(0x9184e72au64),
 		data: vec![]
 	};
-	let signature = tester.accounts.sign(acc, None, t.hash(None)).unwrap();
+	let signature = tester.accounts.sign(acc, None, t.hash(None), KeyOperation::SignMessage, "test").unwrap();
 	let t = t.with_signature(signature, None);
 
 	// when
@@ -19,7 +19,7 @@ use jsonrpc_core::{Result, BoxFuture};
 use jsonrpc_macros::Trailing;
 
 use v1::types::{RichBlock, BlockNumber, Bytes, CallRequest, Filter, FilterChanges, Index};
-use v1::types::{Log, Receipt, SyncStatus, Transaction, Work};
+use v1::types::{Log, Receipt, SyncStatus, Transaction, Work, AccessListWithGasUsed};
 use v1::types::{H64, H160, H256, U256};
 
 build_rpc_trait! {
@@ -67,13 +67,13 @@ build_rpc_trait! {
 		#[rpc(name = "eth_getStorageAt")]
 		fn storage_at(&self, H160, U256, Trailing<BlockNumber>) -> BoxFuture<H256>;
 
-		/// Returns block with given hash.
+		/// Returns block with given hash, optionally including its RLP encoding.
 		#[rpc(name = "eth_getBlockByHash")]
-		fn block_by_hash(&self, H256, bool) -> BoxFuture<Option<RichBlock>>;
+		fn block_by_hash(&self, H256, bool, Trailing<bool>) -> BoxFuture<Option<RichBlock>>;
 
-		/// Returns block with given number.
+		/// Returns block with given number, optionally including its RLP encoding.
 		#[rpc(name = "eth_getBlockByNumber")]
-		fn block_by_number(&self, BlockNumber, bool) -> BoxFuture<Option<RichBlock>>;
+		fn block_by_number(&self, BlockNumber, bool, Trailing<bool>) -> BoxFuture<Option<RichBlock>>;
 
 		/// Returns the number of transactions sent from given address at given time (block number).
 		#[rpc(name = "eth_getTransactionCount")]
@@ -115,6 +115,10 @@ build_rpc_trait! {
 		#[rpc(meta, name = "eth_estimateGas")]
 		fn estimate_gas(&self, Self::Metadata, CallRequest, Trailing<BlockNumber>) -> BoxFuture<U256>;
 
+		/// Generate access list of accounts and storage keys touched by given call, along with its gas estimate.
+		#[rpc(meta, name = "eth_createAccessList")]
+		fn create_access_list(&self, Self::Metadata, CallRequest, Trailing<BlockNumber>) -> BoxFuture<AccessListWithGasUsed>;
+
 		/// Get transaction by its hash.
 		#[rpc(name = "eth_getTransactionByHash")]
 		fn transaction_by_hash(&self, H256) -> BoxFuture<Option<Transaction>>;
@@ -127,6 +131,10 @@ build_rpc_trait! {
 		#[rpc(name = "eth_getTransactionByBlockNumberAndIndex")]
 		fn transaction_by_block_number_and_index(&self, BlockNumber, Index) -> BoxFuture<Option<Transaction>>;
 
+		/// Returns the canonical or pending transaction with given sender and nonce.
+		#[rpc(name = "eth_getTransactionBySenderAndNonce")]
+		fn transaction_by_sender_and_nonce(&self, H160, U256) -> BoxFuture<Option<Transaction>>;
+
 		/// Returns transaction receipt by transaction hash.
 		#[rpc(name = "eth_getTransactionReceipt")]
 		fn transaction_receipt(&self, H256) -> BoxFuture<Option<Receipt>>;
@@ -28,7 +28,8 @@ use v1::types::{
 	TransactionStats, LocalTransactionStatus,
 	BlockNumber, ConsensusCapability, VersionInfo,
 	OperationsInfo, DappId, ChainStatus,
-	AccountInfo, HwAccountInfo, RichHeader,
+	AccountInfo, HwAccountInfo, RichHeader, BalanceHistoryEntry,
+	ValidatorLiveness,
 };
 
 build_rpc_trait! {
@@ -149,6 +150,15 @@ build_rpc_trait! {
 		#[rpc(name = "parity_allTransactions")]
 		fn all_transactions(&self) -> Result<Vec<Transaction>>;
 
+		/// Returns all transactions of given sender from transaction queue, ordered by nonce.
+		#[rpc(name = "parity_queuedTransactionsBySender")]
+		fn queued_transactions_by_sender(&self, H160) -> Result<Vec<Transaction>>;
+
+		/// Returns ready transactions from transaction queue with gas at most the given gas limit
+		/// and paying at least the given gas price.
+		#[rpc(name = "parity_queuedTransactionsWithinLimits")]
+		fn queued_transactions_within_limits(&self, U256, U256) -> Result<Vec<Transaction>>;
+
 		/// Returns all future transactions from transaction queue (deprecated)
 		#[rpc(name = "parity_futureTransactions")]
 		fn future_transactions(&self) -> Result<Vec<Transaction>>;
@@ -216,6 +226,10 @@ build_rpc_trait! {
 		#[rpc(name = "parity_getBlockHeaderByNumber")]
 		fn block_header(&self, Trailing<BlockNumber>) -> BoxFuture<RichHeader>;
 
+		/// Get RLP encoded block.
+		#[rpc(name = "parity_getRawBlockByNumber")]
+		fn raw_block(&self, Trailing<BlockNumber>) -> BoxFuture<Option<Bytes>>;
+
 		/// Get IPFS CIDv0 given protobuf encoded bytes.
 		#[rpc(name = "parity_cidV0")]
 		fn ipfs_cid(&self, Bytes) -> Result<String>;
@@ -224,6 +238,16 @@ build_rpc_trait! {
 		#[rpc(meta, name = "parity_call")]
 		fn call(&self, Self::Metadata, Vec<CallRequest>, Trailing<BlockNumber>) -> Result<Vec<Bytes>>;
 
+		/// Returns balance and nonce of an account at every `step`-th block (1 by default)
+		/// between the two given blocks, inclusive. Requires state of all sampled blocks.
+		#[rpc(name = "parity_getBalanceHistory")]
+		fn balance_history(&self, H160, BlockNumber, BlockNumber, Trailing<U64>) -> Result<Vec<BalanceHistoryEntry>>;
+
+		/// Returns sealing performance of the validators seen since startup.
+		/// Empty unless the chain is driven by an authority engine.
+		#[rpc(name = "parity_validatorLiveness")]
+		fn validator_liveness(&self) -> Result<BTreeMap<H160, ValidatorLiveness>>;
+
 		/// Returns node's health report.
 		#[rpc(name = "parity_nodeHealth")]
 		fn node_health(&self) -> BoxFuture<Health>;
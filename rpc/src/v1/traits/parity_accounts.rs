@@ -19,7 +19,7 @@ use std::collections::BTreeMap;
 
 use jsonrpc_core::Result;
 use ethstore::KeyFile;
-use v1::types::{H160, H256, H520, DappId, DeriveHash, DeriveHierarchical, ExtAccountInfo};
+use v1::types::{H160, H256, H520, DappId, DeriveHash, DeriveHierarchical, ExtAccountInfo, KeyUsage};
 
 build_rpc_trait! {
 	/// Personal Parity rpc interface.
@@ -185,6 +185,11 @@ build_rpc_trait! {
 		#[rpc(name = "parity_signMessage")]
 		fn sign_message(&self, H160, String, H256) -> Result<H520>;
 
+		/// Returns the log of signing and decryption operations performed with the key of
+		/// the given account, or of all accounts if no address is provided.
+		#[rpc(name = "parity_keyUsage")]
+		fn key_usage(&self, Option<H160>) -> Result<Vec<KeyUsage>>;
+
 		/// Send a PinMatrixAck to a hardware wallet, unlocking it
 		#[rpc(name = "parity_hardwarePinMatrixAck")]
 		fn hardware_pin_matrix_ack(&self, String, String) -> Result<bool>;
@@ -119,5 +119,11 @@ build_rpc_trait! {
 		/// Returns `true` when transaction was removed, `false` if it was not found.
 		#[rpc(name = "parity_removeTransaction")]
 		fn remove_transaction(&self, H256) -> Result<Option<Transaction>>;
+
+		/// Imports signed transaction to be included in blocks sealed by this node only.
+		/// The transaction is never propagated to other peers.
+		/// Returns the transaction hash.
+		#[rpc(name = "parity_sendRawTransactionLocalOnly")]
+		fn send_raw_transaction_local_only(&self, Bytes) -> Result<H256>;
 	}
 }
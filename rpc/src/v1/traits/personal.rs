@@ -39,8 +39,8 @@ build_rpc_trait! {
 
 		/// Signs the hash of data with given account signature using the given password to unlock the account during
 		/// the request.
-		#[rpc(name = "personal_sign")]
-		fn sign(&self, Bytes, H160, String) -> BoxFuture<H520>;
+		#[rpc(meta, name = "personal_sign")]
+		fn sign(&self, Self::Metadata, Bytes, H160, String) -> BoxFuture<H520>;
 
 		/// Returns the account associated with the private key that was used to calculate the signature in
 		/// `personal_sign`.
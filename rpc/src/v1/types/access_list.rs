@@ -0,0 +1,71 @@
+// Copyright 2015-2017 Parity Technologies (UK) Ltd.
+// This file is part of Parity.
+
+// Parity is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// Parity is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with Parity. If not, see <http://www.gnu.org/licenses/>.
+
+//! Access list returned by `eth_createAccessList`.
+
+use ethcore::state::AccessList as EthAccessList;
+use ethereum_types::U256 as EthU256;
+use v1::types::{H160, H256, U256};
+
+/// Account accessed during execution along with its accessed storage keys.
+#[derive(Debug, Serialize, PartialEq)]
+pub struct AccessListItem {
+	/// Account address
+	pub address: H160,
+	/// Accessed storage keys
+	#[serde(rename="storageKeys")]
+	pub storage_keys: Vec<H256>,
+}
+
+/// Access list together with the gas needed by the call.
+#[derive(Debug, Serialize, PartialEq)]
+pub struct AccessListWithGasUsed {
+	/// Accessed accounts and storage keys
+	#[serde(rename="accessList")]
+	pub access_list: Vec<AccessListItem>,
+	/// Estimated gas
+	#[serde(rename="gasUsed")]
+	pub gas_used: U256,
+}
+
+impl From<(EthAccessList, EthU256)> for AccessListWithGasUsed {
+	fn from((list, gas): (EthAccessList, EthU256)) -> Self {
+		AccessListWithGasUsed {
+			access_list: list.into_iter().map(|(address, keys)| AccessListItem {
+				address: address.into(),
+				storage_keys: keys.into_iter().map(Into::into).collect(),
+			}).collect(),
+			gas_used: gas.into(),
+		}
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use serde_json;
+	use ethcore::state::AccessList;
+	use super::AccessListWithGasUsed;
+
+	#[test]
+	fn access_list_serialization() {
+		let mut list = AccessList::new();
+		list.entry(1.into()).or_insert_with(Default::default).insert(2.into());
+		let result: AccessListWithGasUsed = (list, 21_000.into()).into();
+
+		let serialized = serde_json::to_string(&result).unwrap();
+		assert_eq!(serialized, r#"{"accessList":[{"address":"0x0000000000000000000000000000000000000001","storageKeys":["0x0000000000000000000000000000000000000000000000000000000000000002"]}],"gasUsed":"0x5208"}"#);
+	}
+}
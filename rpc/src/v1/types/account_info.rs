@@ -14,6 +14,9 @@
 // You should have received a copy of the GNU General Public License
 // along with Parity.  If not, see <http://www.gnu.org/licenses/>.
 
+use ethcore::account_provider::{KeyUsage as EthKeyUsage, KeyOperation};
+use v1::types::H160;
+
 /// Account information.
 #[derive(Debug, Default, Clone, PartialEq, Serialize)]
 pub struct AccountInfo {
@@ -42,3 +45,34 @@ pub struct HwAccountInfo {
 	pub manufacturer: String,
 }
 
+/// Single recorded use of an account key (used by `parity_keyUsage`).
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct KeyUsage {
+	/// Unix timestamp (in seconds) of the operation.
+	pub timestamp: u64,
+	/// Account whose key was used.
+	pub address: H160,
+	/// Operation type: `signTransaction`, `signMessage` or `decrypt`.
+	pub operation: String,
+	/// Whether the operation succeeded.
+	pub success: bool,
+	/// Origin of the request.
+	pub origin: String,
+}
+
+impl From<EthKeyUsage> for KeyUsage {
+	fn from(usage: EthKeyUsage) -> Self {
+		KeyUsage {
+			timestamp: usage.timestamp,
+			address: usage.address.into(),
+			operation: match usage.operation {
+				KeyOperation::SignTransaction => "signTransaction",
+				KeyOperation::SignMessage => "signMessage",
+				KeyOperation::Decrypt => "decrypt",
+				KeyOperation::Agree => "agree",
+			}.into(),
+			success: usage.success,
+			origin: usage.origin,
+		}
+	}
+}
@@ -0,0 +1,29 @@
+// Copyright 2015-2018 Parity Technologies (UK) Ltd.
+// This file is part of Parity.
+
+// Parity is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// Parity is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with Parity.  If not, see <http://www.gnu.org/licenses/>.
+
+use v1::types::U256;
+
+/// Balance and nonce of an account at a block (used by `parity_getBalanceHistory`).
+#[derive(Debug, Clone, PartialEq, Serialize)]
+#[serde(rename_all="camelCase")]
+pub struct BalanceHistoryEntry {
+	/// Block number.
+	pub block_number: U256,
+	/// Account balance after the block.
+	pub balance: U256,
+	/// Account nonce after the block.
+	pub nonce: U256,
+}
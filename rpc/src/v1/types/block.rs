@@ -87,6 +87,9 @@ pub struct Block {
 	pub transactions: BlockTransactions,
 	/// Size in bytes
 	pub size: Option<U256>,
+	/// RLP encoded block, if requested
+	#[serde(skip_serializing_if = "Option::is_none")]
+	pub raw: Option<Bytes>,
 }
 
 /// Block header representation.
@@ -243,6 +246,7 @@ mod tests {
 			uncles: vec![],
 			transactions: BlockTransactions::Hashes(vec![].into()),
 			size: Some(69.into()),
+			raw: None,
 		};
 		let serialized_block = serde_json::to_string(&block).unwrap();
 		let rich_block = RichBlock {
@@ -281,6 +285,7 @@ mod tests {
 			uncles: vec![],
 			transactions: BlockTransactions::Hashes(vec![].into()),
 			size: None,
+			raw: None,
 		};
 		let serialized_block = serde_json::to_string(&block).unwrap();
 		let rich_block = RichBlock {
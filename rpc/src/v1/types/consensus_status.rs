@@ -17,6 +17,7 @@
 use semver;
 use v1::types::{H160, H256};
 use updater::{self, CapState};
+use ethcore::engines;
 
 /// Capability info
 #[derive(Debug, PartialEq, Serialize)]
@@ -167,3 +168,28 @@ impl Into<OperationsInfo> for updater::OperationsInfo {
 		}
 	}
 }
+
+/// Sealing performance of a validator.
+#[derive(Debug, PartialEq, Serialize)]
+#[serde(rename_all="camelCase")]
+pub struct ValidatorLiveness {
+	/// Number of imported blocks sealed by the validator.
+	pub sealed: u64,
+	/// Number of steps missed by the validator.
+	pub missed: u64,
+	/// Number of steps missed since the validator was last seen.
+	pub consecutive_missed: u64,
+	/// Number of the last block sealed by the validator.
+	pub last_seen: Option<u64>,
+}
+
+impl From<engines::ValidatorLiveness> for ValidatorLiveness {
+	fn from(l: engines::ValidatorLiveness) -> Self {
+		ValidatorLiveness {
+			sealed: l.sealed,
+			missed: l.missed,
+			consecutive_missed: l.consecutive_missed,
+			last_seen: l.last_seen,
+		}
+	}
+}
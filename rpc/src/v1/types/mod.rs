@@ -17,7 +17,9 @@
 
 //! RPC types
 
+mod access_list;
 mod account_info;
+mod balance_history;
 mod block;
 mod block_number;
 mod bytes;
@@ -48,7 +50,9 @@ mod private_receipt;
 
 pub mod pubsub;
 
-pub use self::account_info::{AccountInfo, ExtAccountInfo, HwAccountInfo};
+pub use self::access_list::{AccessListItem, AccessListWithGasUsed};
+pub use self::account_info::{AccountInfo, ExtAccountInfo, HwAccountInfo, KeyUsage};
+pub use self::balance_history::BalanceHistoryEntry;
 pub use self::bytes::Bytes;
 pub use self::block::{RichBlock, Block, BlockTransactions, Header, RichHeader, Rich};
 pub use self::block_number::{BlockNumber, block_number_to_id};
@@ -74,7 +78,7 @@ pub use self::sync::{
 	SyncStatus, SyncInfo, Peers, PeerInfo, PeerNetworkInfo, PeerProtocolsInfo,
 	TransactionStats, ChainStatus, EthProtocolInfo, PipProtocolInfo,
 };
-pub use self::trace::{LocalizedTrace, TraceResults};
+pub use self::trace::{LocalizedTrace, TraceResults, TraceStorageUsage};
 pub use self::trace_filter::TraceFilter;
 pub use self::transaction::{Transaction, RichRawTransaction, LocalTransactionStatus};
 pub use self::transaction_request::TransactionRequest;
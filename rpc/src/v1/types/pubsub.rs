@@ -19,6 +19,7 @@
 use serde::{Deserialize, Deserializer, Serialize, Serializer};
 use serde::de::Error;
 use serde_json::{Value, from_value};
+use miner::pool;
 use v1::types::{RichHeader, Filter, Log, H256};
 
 /// Subscription result.
@@ -30,6 +31,39 @@ pub enum Result {
 	Log(Log),
 	/// Transaction hash
 	TransactionHash(H256),
+	/// Transaction removed from the pool
+	RemovedTransaction(RemovedTransaction),
+}
+
+/// Transaction removed from the pool without being mined.
+#[derive(Debug, Serialize, Clone, PartialEq, Eq)]
+pub struct RemovedTransaction {
+	/// Transaction hash
+	pub hash: H256,
+	/// Reason of removal
+	pub reason: RemovalReason,
+}
+
+/// Reason of removing a transaction from the pool.
+#[derive(Debug, Serialize, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all="lowercase")]
+pub enum RemovalReason {
+	/// Waited in the pool for too long
+	Expired,
+	/// Another transaction with the same nonce was mined
+	Superseded,
+	/// Replaced by a transaction with a higher gas price
+	Replaced,
+}
+
+impl From<pool::RemovalReason> for RemovalReason {
+	fn from(reason: pool::RemovalReason) -> Self {
+		match reason {
+			pool::RemovalReason::Expired => RemovalReason::Expired,
+			pool::RemovalReason::Superseded => RemovalReason::Superseded,
+			pool::RemovalReason::Replaced => RemovalReason::Replaced,
+		}
+	}
 }
 
 impl Serialize for Result {
@@ -40,6 +74,7 @@ impl Serialize for Result {
 			Result::Header(ref header) => header.serialize(serializer),
 			Result::Log(ref log) => log.serialize(serializer),
 			Result::TransactionHash(ref hash) => hash.serialize(serializer),
+			Result::RemovedTransaction(ref removed) => removed.serialize(serializer),
 		}
 	}
 }
@@ -57,6 +92,12 @@ pub enum Kind {
 	/// New Pending Transactions subscription.
 	#[serde(rename="newPendingTransactions")]
 	NewPendingTransactions,
+	/// Transactions dropped from the pool subscription.
+	#[serde(rename="droppedTransactions")]
+	DroppedTransactions,
+	/// Transactions culled from the pool subscription.
+	#[serde(rename="removedTransactions")]
+	RemovedTransactions,
 	/// Node syncing status subscription.
 	#[serde(rename="syncing")]
 	Syncing,
@@ -94,7 +135,7 @@ impl<'a> Deserialize<'a> for Params {
 #[cfg(test)]
 mod tests {
 	use serde_json;
-	use super::{Result, Kind, Params};
+	use super::{Result, Kind, Params, RemovedTransaction, RemovalReason};
 	use v1::types::{RichHeader, Header, Filter};
 	use v1::types::filter::VariadicValue;
 
@@ -103,6 +144,8 @@ mod tests {
 		assert_eq!(serde_json::from_str::<Kind>(r#""newHeads""#).unwrap(), Kind::NewHeads);
 		assert_eq!(serde_json::from_str::<Kind>(r#""logs""#).unwrap(), Kind::Logs);
 		assert_eq!(serde_json::from_str::<Kind>(r#""newPendingTransactions""#).unwrap(), Kind::NewPendingTransactions);
+		assert_eq!(serde_json::from_str::<Kind>(r#""droppedTransactions""#).unwrap(), Kind::DroppedTransactions);
+		assert_eq!(serde_json::from_str::<Kind>(r#""removedTransactions""#).unwrap(), Kind::RemovedTransactions);
 		assert_eq!(serde_json::from_str::<Kind>(r#""syncing""#).unwrap(), Kind::Syncing);
 	}
 
@@ -168,4 +211,14 @@ mod tests {
 		let expected = r#"{"author":"0x0000000000000000000000000000000000000000","difficulty":"0x0","extraData":"0x","gasLimit":"0x0","gasUsed":"0x0","hash":"0x0000000000000000000000000000000000000000000000000000000000000000","logsBloom":"0x00000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000","miner":"0x0000000000000000000000000000000000000000","number":"0x0","parentHash":"0x0000000000000000000000000000000000000000000000000000000000000000","receiptsRoot":"0x0000000000000000000000000000000000000000000000000000000000000000","sealFields":["0x","0x"],"sha3Uncles":"0x0000000000000000000000000000000000000000000000000000000000000000","size":"0x45","stateRoot":"0x0000000000000000000000000000000000000000000000000000000000000000","timestamp":"0x0","transactionsRoot":"0x0000000000000000000000000000000000000000000000000000000000000000"}"#;
 		assert_eq!(serde_json::to_string(&header).unwrap(), expected);
 	}
+
+	#[test]
+	fn should_serialize_removed_transaction() {
+		let removed = Result::RemovedTransaction(RemovedTransaction {
+			hash: 5.into(),
+			reason: RemovalReason::Superseded,
+		});
+		let expected = r#"{"hash":"0x0000000000000000000000000000000000000000000000000000000000000005","reason":"superseded"}"#;
+		assert_eq!(serde_json::to_string(&removed).unwrap(), expected);
+	}
 }
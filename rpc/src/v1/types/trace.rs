@@ -95,6 +95,9 @@ pub struct VMOperation {
 	pub pc: usize,
 	/// The gas cost for this instruction.
 	pub cost: u64,
+	/// The part of the gas cost depending on the size of the operands.
+	#[serde(rename="dynamicCost")]
+	pub dynamic_cost: u64,
 	/// Information concerning the execution of the operation.
 	pub ex: Option<VMExecutedOperation>,
 	/// Subordinate trace of the CALL/CREATE if applicable.
@@ -107,6 +110,7 @@ impl From<(et::VMOperation, Option<et::VMTrace>)> for VMOperation {
 		VMOperation {
 			pc: c.0.pc,
 			cost: c.0.gas_cost.low_u64(),
+			dynamic_cost: c.0.dynamic_gas.low_u64(),
 			ex: c.0.executed.map(Into::into),
 			sub: c.1.map(Into::into),
 		}
@@ -619,6 +623,9 @@ pub struct TraceResults {
 	/// The transaction trace.
 	#[serde(rename="stateDiff")]
 	pub state_diff: Option<StateDiff>,
+	/// The reason the transaction failed, if it did.
+	#[serde(skip_serializing_if = "Option::is_none")]
+	pub error: Option<String>,
 }
 
 impl From<Executed> for TraceResults {
@@ -628,6 +635,26 @@ impl From<Executed> for TraceResults {
 			trace: t.trace.into_iter().map(Into::into).collect(),
 			vm_trace: t.vm_trace.map(Into::into),
 			state_diff: t.state_diff.map(Into::into),
+			error: t.exception.map(|e| e.to_string()),
+		}
+	}
+}
+
+/// Storage used by the traces database.
+#[derive(Debug, Serialize)]
+pub struct TraceStorageUsage {
+	/// Number of the oldest block whose traces are kept.
+	#[serde(rename="oldestBlock")]
+	pub oldest_block: U256,
+	/// Total size of stored traces in bytes.
+	pub size: U256,
+}
+
+impl From<et::StorageUsage> for TraceStorageUsage {
+	fn from(usage: et::StorageUsage) -> Self {
+		TraceStorageUsage {
+			oldest_block: usage.oldest_block.into(),
+			size: usage.size.into(),
 		}
 	}
 }
@@ -647,11 +674,35 @@ mod tests {
 			trace: vec![],
 			vm_trace: None,
 			state_diff: None,
+			error: None,
 		};
 		let serialized = serde_json::to_string(&r).unwrap();
 		assert_eq!(serialized, r#"{"output":"0x60","trace":[],"vmTrace":null,"stateDiff":null}"#);
 	}
 
+	#[test]
+	fn should_serialize_trace_storage_usage() {
+		let usage = TraceStorageUsage {
+			oldest_block: 100.into(),
+			size: 2048.into(),
+		};
+		let serialized = serde_json::to_string(&usage).unwrap();
+		assert_eq!(serialized, r#"{"oldestBlock":"0x64","size":"0x800"}"#);
+	}
+
+	#[test]
+	fn should_serialize_failed_trace_results() {
+		let r = TraceResults {
+			output: vec![].into(),
+			trace: vec![],
+			vm_trace: None,
+			state_diff: None,
+			error: Some("Bad jump destination 3".into()),
+		};
+		let serialized = serde_json::to_string(&r).unwrap();
+		assert_eq!(serialized, r#"{"output":"0x","trace":[],"vmTrace":null,"stateDiff":null,"error":"Bad jump destination 3"}"#);
+	}
+
 	#[test]
 	fn test_trace_call_serialize() {
 		let t = LocalizedTrace {
@@ -795,12 +846,14 @@ mod tests {
 				VMOperation {
 					pc: 0,
 					cost: 10,
+					dynamic_cost: 0,
 					ex: None,
 					sub: None,
 				},
 				VMOperation {
 					pc: 1,
 					cost: 11,
+					dynamic_cost: 6,
 					ex: Some(VMExecutedOperation {
 						used: 10,
 						push: vec![69.into()],
@@ -813,6 +866,7 @@ mod tests {
 							VMOperation {
 								pc: 0,
 								cost: 0,
+								dynamic_cost: 0,
 								ex: Some(VMExecutedOperation {
 									used: 10,
 									push: vec![42.into()].into(),
@@ -827,7 +881,7 @@ mod tests {
 			]
 		};
 		let serialized = serde_json::to_string(&t).unwrap();
-		assert_eq!(serialized, r#"{"code":"0x00010203","ops":[{"pc":0,"cost":10,"ex":null,"sub":null},{"pc":1,"cost":11,"ex":{"used":10,"push":["0x45"],"mem":null,"store":null},"sub":{"code":"0x00","ops":[{"pc":0,"cost":0,"ex":{"used":10,"push":["0x2a"],"mem":{"off":42,"data":"0x010203"},"store":{"key":"0x45","val":"0x2a"}},"sub":null}]}}]}"#);
+		assert_eq!(serialized, r#"{"code":"0x00010203","ops":[{"pc":0,"cost":10,"dynamicCost":0,"ex":null,"sub":null},{"pc":1,"cost":11,"dynamicCost":6,"ex":{"used":10,"push":["0x45"],"mem":null,"store":null},"sub":{"code":"0x00","ops":[{"pc":0,"cost":0,"dynamicCost":0,"ex":{"used":10,"push":["0x2a"],"mem":{"off":42,"data":"0x010203"},"store":{"key":"0x45","val":"0x2a"}},"sub":null}]}}]}"#);
 	}
 
 	#[test]
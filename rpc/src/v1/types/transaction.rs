@@ -84,8 +84,8 @@ pub enum LocalTransactionStatus {
 	Future,
 	/// Transaction is already mined.
 	Mined(Transaction),
-	/// Transaction was dropped because of limit.
-	Dropped(Transaction),
+	/// Transaction was dropped because of limit, with the reason.
+	Dropped(Transaction, String),
 	/// Transaction was replaced by transaction with higher gas price.
 	Replaced(Transaction, U256, H256),
 	/// Transaction never got into the queue.
@@ -104,8 +104,8 @@ impl Serialize for LocalTransactionStatus {
 
 		let elems = match *self {
 			Pending | Future => 1,
-			Mined(..) | Dropped(..) | Invalid(..) | Canceled(..) => 2,
-			Rejected(..) => 3,
+			Mined(..) | Invalid(..) | Canceled(..) => 2,
+			Dropped(..) | Rejected(..) => 3,
 			Replaced(..) => 4,
 		};
 
@@ -120,9 +120,10 @@ impl Serialize for LocalTransactionStatus {
 				struc.serialize_field(status, "mined")?;
 				struc.serialize_field(transaction, tx)?;
 			},
-			Dropped(ref tx) => {
+			Dropped(ref tx, ref reason) => {
 				struc.serialize_field(status, "dropped")?;
 				struc.serialize_field(transaction, tx)?;
+				struc.serialize_field("reason", reason)?;
 			},
 			Canceled(ref tx) => {
 				struc.serialize_field(status, "canceled")?;
@@ -257,7 +258,7 @@ impl LocalTransactionStatus {
 		match s {
 			Pending(_) => LocalTransactionStatus::Pending,
 			Mined(tx) => LocalTransactionStatus::Mined(convert(tx)),
-			Dropped(tx) => LocalTransactionStatus::Dropped(convert(tx)),
+			Dropped(tx, reason) => LocalTransactionStatus::Dropped(convert(tx), reason),
 			Rejected(tx, reason) => LocalTransactionStatus::Rejected(convert(tx), reason),
 			Invalid(tx) => LocalTransactionStatus::Invalid(convert(tx)),
 			Canceled(tx) => LocalTransactionStatus::Canceled(convert(tx)),
@@ -288,7 +289,7 @@ mod tests {
 		let status1 = LocalTransactionStatus::Pending;
 		let status2 = LocalTransactionStatus::Future;
 		let status3 = LocalTransactionStatus::Mined(Transaction::default());
-		let status4 = LocalTransactionStatus::Dropped(Transaction::default());
+		let status4 = LocalTransactionStatus::Dropped(Transaction::default(), "Queue full".into());
 		let status5 = LocalTransactionStatus::Invalid(Transaction::default());
 		let status6 = LocalTransactionStatus::Rejected(Transaction::default(), "Just because".into());
 		let status7 = LocalTransactionStatus::Replaced(Transaction::default(), 5.into(), 10.into());
@@ -307,7 +308,9 @@ mod tests {
 		);
 		assert_eq!(
 			serde_json::to_string(&status4).unwrap(),
-			r#"{"status":"dropped","transaction":"#.to_owned() + &format!("{}", tx_ser) + r#"}"#
+			r#"{"status":"dropped","transaction":"#.to_owned() +
+			&format!("{}", tx_ser) +
+			r#","reason":"Queue full"}"#
 		);
 		assert_eq!(
 			serde_json::to_string(&status5).unwrap(),
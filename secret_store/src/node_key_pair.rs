@@ -17,10 +17,12 @@
 use std::sync::Arc;
 use ethkey::crypto::ecdh::agree;
 use ethkey::{KeyPair, Public, Signature, Error as EthKeyError, sign, public_to_address};
-use ethcore::account_provider::AccountProvider;
+use ethcore::account_provider::{AccountProvider, KeyOperation};
 use ethereum_types::{H256, Address};
 use traits::NodeKeyPair;
 
+const USAGE_ORIGIN: &'static str = "Secret Store";
+
 pub struct PlainNodeKeyPair {
 	key_pair: KeyPair,
 }
@@ -82,12 +84,12 @@ impl NodeKeyPair for KeyStoreNodeKeyPair {
 	}
 
 	fn sign(&self, data: &H256) -> Result<Signature, EthKeyError> {
-		self.account_provider.sign(self.address.clone(), Some(self.password.clone()), data.clone())
+		self.account_provider.sign(self.address.clone(), Some(self.password.clone()), data.clone(), KeyOperation::SignMessage, USAGE_ORIGIN)
 			.map_err(|e| EthKeyError::Custom(format!("{}", e)))
 	}
 
 	fn compute_shared_key(&self, peer_public: &Public) -> Result<KeyPair, EthKeyError> {
-		KeyPair::from_secret(self.account_provider.agree(self.address.clone(), Some(self.password.clone()), peer_public)
+		KeyPair::from_secret(self.account_provider.agree(self.address.clone(), Some(self.password.clone()), peer_public, USAGE_ORIGIN)
 			.map_err(|e| EthKeyError::Custom(format!("{}", e)))?)
 	}
 }
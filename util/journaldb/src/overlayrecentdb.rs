@@ -125,6 +125,7 @@ struct JournalOverlay {
 	latest_era: Option<u64>,
 	earliest_era: Option<u64>,
 	cumulative_size: usize, // cumulative size of all entries.
+	freed_bytes: u64, // total size of the entries deleted by canonicalisation so far.
 }
 
 #[derive(PartialEq)]
@@ -230,6 +231,7 @@ impl OverlayRecentDB {
 			latest_era: latest_era,
 			earliest_era: earliest_era,
 			cumulative_size: cumulative_size,
+			freed_bytes: 0,
 		}
 	}
 }
@@ -262,6 +264,10 @@ impl JournalDB for OverlayRecentDB {
 
 	}
 
+	fn freed_bytes(&self) -> u64 {
+		self.journal_overlay.read().freed_bytes
+	}
+
 	fn is_empty(&self) -> bool {
 		self.backing.get(self.column, &LATEST_ERA_KEY).expect("Low level database error").is_none()
 	}
@@ -392,6 +398,12 @@ impl JournalDB for OverlayRecentDB {
 			// apply canon deletions
 			for k in canon_deletions {
 				if !journal_overlay.backing_overlay.contains(&to_short_key(&k)) {
+					// the entry is read once it's actually deleted, rather than on every removal.
+					let len = match journal_overlay.pending_overlay.get(&to_short_key(&k)) {
+						Some(value) => value.len(),
+						None => self.backing.get(self.column, &k).expect("Low-level database error.").map_or(0, |value| value.len()),
+					};
+					journal_overlay.freed_bytes += len as u64;
 					batch.delete(self.column, &k);
 				}
 			}
@@ -554,6 +566,21 @@ mod tests {
 		assert!(!jdb.contains(&h));
 	}
 
+	#[test]
+	fn tracks_freed_bytes() {
+		let mut jdb = new_db();
+		let h = jdb.insert(b"foo");
+		jdb.commit_batch(0, &keccak(b"0"), None).unwrap();
+		jdb.remove(&h);
+		jdb.commit_batch(1, &keccak(b"1"), None).unwrap();
+		jdb.commit_batch(2, &keccak(b"2"), Some((0, keccak(b"0")))).unwrap();
+		assert_eq!(jdb.freed_bytes(), 0);
+
+		jdb.commit_batch(3, &keccak(b"3"), Some((1, keccak(b"1")))).unwrap();
+		assert!(!jdb.contains(&h));
+		assert_eq!(jdb.freed_bytes(), 3);
+	}
+
 	#[test]
 	fn complex() {
 		// history is 1
@@ -40,6 +40,10 @@ pub trait JournalDB: HashDB {
 	/// Check if this database has any commits
 	fn is_empty(&self) -> bool;
 
+	/// Total size in bytes of the entries deleted from the backing database by `mark_canonical`
+	/// since the database was opened. Zero for databases which don't track it.
+	fn freed_bytes(&self) -> u64 { 0 }
+
 	/// Get the earliest era in the DB. None if there isn't yet any data in there.
 	fn earliest_era(&self) -> Option<u64> { None }
 
@@ -14,25 +14,90 @@
 // You should have received a copy of the GNU General Public License
 // along with Parity.  If not, see <http://www.gnu.org/licenses/>.
 
-//! Lru-cache related utilities as quick-and-dirty wrappers around the lru-cache
+//! Lru-cache related utilities as quick-and-dirty wrappers around the linked-hash-map
 //! crate.
 // TODO: push changes upstream in a clean way.
 
 extern crate heapsize;
-extern crate lru_cache;
+extern crate linked_hash_map;
+extern crate parking_lot;
+#[macro_use]
+extern crate lazy_static;
+#[macro_use]
+extern crate log;
+
+pub mod pressure;
 
 use heapsize::HeapSizeOf;
-use lru_cache::LruCache;
+use linked_hash_map::LinkedHashMap;
 
 use std::hash::Hash;
+use std::sync::atomic::{AtomicUsize, Ordering as AtomicOrdering};
+
+/// Lookup statistics of a cache.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub struct CacheStats {
+	/// Number of lookups which found an item.
+	pub hits: u64,
+	/// Number of lookups which found nothing.
+	pub misses: u64,
+}
+
+impl CacheStats {
+	/// Share of lookups which found an item, `None` if there were no lookups.
+	pub fn hit_rate(&self) -> Option<f64> {
+		match self.hits + self.misses {
+			0 => None,
+			total => Some(self.hits as f64 / total as f64),
+		}
+	}
+}
+
+impl ::std::ops::Add for CacheStats {
+	type Output = CacheStats;
+
+	fn add(self, other: CacheStats) -> CacheStats {
+		CacheStats {
+			hits: self.hits + other.hits,
+			misses: self.misses + other.misses,
+		}
+	}
+}
+
+/// Lookup statistics of a cache shared between threads. Noting a lookup doesn't take a lock.
+#[derive(Debug, Default)]
+pub struct AtomicCacheStats {
+	hits: AtomicUsize,
+	misses: AtomicUsize,
+}
+
+impl AtomicCacheStats {
+	/// Notes a lookup which found an item.
+	pub fn note_hit(&self) {
+		self.hits.fetch_add(1, AtomicOrdering::Relaxed);
+	}
+
+	/// Notes a lookup which found nothing.
+	pub fn note_miss(&self) {
+		self.misses.fetch_add(1, AtomicOrdering::Relaxed);
+	}
 
-const INITIAL_CAPACITY: usize = 4;
+	/// Returns statistics gathered since the last call and resets them.
+	pub fn take(&self) -> CacheStats {
+		CacheStats {
+			hits: self.hits.swap(0, AtomicOrdering::Relaxed) as u64,
+			misses: self.misses.swap(0, AtomicOrdering::Relaxed) as u64,
+		}
+	}
+}
 
 /// An LRU-cache which operates on memory used.
 pub struct MemoryLruCache<K: Eq + Hash, V: HeapSizeOf> {
-	inner: LruCache<K, V>,
+	// items from the least to the most recently used.
+	inner: LinkedHashMap<K, V>,
 	cur_size: usize,
 	max_size: usize,
+	stats: CacheStats,
 }
 
 // amount of memory used when the item will be put on the heap.
@@ -44,41 +109,39 @@ impl<K: Eq + Hash, V: HeapSizeOf> MemoryLruCache<K, V> {
 	/// Create a new cache with a maximum size in bytes.
 	pub fn new(max_size: usize) -> Self {
 		MemoryLruCache {
-			inner: LruCache::new(INITIAL_CAPACITY),
+			inner: LinkedHashMap::new(),
 			max_size: max_size,
 			cur_size: 0,
+			stats: CacheStats::default(),
 		}
 	}
 
 	/// Insert an item.
 	pub fn insert(&mut self, key: K, val: V) {
-		let cap = self.inner.capacity();
-
-		// grow the cache as necessary; it operates on amount of items
-		// but we're working based on memory usage.
-		if self.inner.len() == cap && self.cur_size < self.max_size {
-			self.inner.set_capacity(cap * 2);
-		}
-
 		self.cur_size += heap_size_of(&val);
 
-		// account for any element displaced from the cache.
-		if let Some(lru) = self.inner.insert(key, val) {
-			self.cur_size -= heap_size_of(&lru);
+		// account for any element replaced in the cache.
+		if let Some(old) = self.inner.insert(key, val) {
+			self.cur_size -= heap_size_of(&old);
 		}
 
-		// remove elements until we are below the memory target.
-		while self.cur_size > self.max_size {
-			match self.inner.remove_lru() {
-				Some((_, v)) => self.cur_size -= heap_size_of(&v),
-				_ => break,
-			}
-		}
+		self.readjust_down();
 	}
 
 	/// Get a reference to an item in the cache. It is a logic error for its
 	/// heap size to be altered while borrowed.
 	pub fn get_mut(&mut self, key: &K) -> Option<&mut V> {
+		let val = self.inner.get_refresh(key);
+		match val {
+			Some(_) => self.stats.hits += 1,
+			None => self.stats.misses += 1,
+		}
+		val
+	}
+
+	/// Like `get_mut`, but the lookup neither marks the item as recently used
+	/// nor is counted in the statistics.
+	pub fn peek_mut(&mut self, key: &K) -> Option<&mut V> {
 		self.inner.get_mut(key)
 	}
 
@@ -86,6 +149,50 @@ impl<K: Eq + Hash, V: HeapSizeOf> MemoryLruCache<K, V> {
 	pub fn current_size(&self) -> usize {
 		self.cur_size
 	}
+
+	/// Maximum size of values in bytes.
+	pub fn max_size(&self) -> usize {
+		self.max_size
+	}
+
+	/// Change the maximum size in bytes, evicting least recently used items if necessary.
+	pub fn set_max_size(&mut self, max_size: usize) {
+		self.max_size = max_size;
+		self.readjust_down();
+	}
+
+	/// Evict the least recently used `percent`% of the contents. Returns the number of bytes freed.
+	pub fn shed(&mut self, percent: usize) -> usize {
+		let before = self.cur_size;
+		let target = before - before * ::std::cmp::min(percent, 100) / 100;
+		while self.cur_size > target {
+			match self.inner.pop_front() {
+				Some((_, v)) => self.cur_size -= heap_size_of(&v),
+				_ => break,
+			}
+		}
+		before - self.cur_size
+	}
+
+	/// Lookup statistics since the cache was created or the statistics were last taken.
+	pub fn stats(&self) -> CacheStats {
+		self.stats
+	}
+
+	/// Returns lookup statistics and resets them.
+	pub fn take_stats(&mut self) -> CacheStats {
+		::std::mem::replace(&mut self.stats, CacheStats::default())
+	}
+
+	// remove elements until we are below the memory target.
+	fn readjust_down(&mut self) {
+		while self.cur_size > self.max_size {
+			match self.inner.pop_front() {
+				Some((_, v)) => self.cur_size -= heap_size_of(&v),
+				_ => break,
+			}
+		}
+	}
 }
 
 #[cfg(test)]
@@ -110,4 +217,49 @@ mod tests {
 
 		assert_eq!(cache.current_size(), size2);
 	}
+
+	#[test]
+	fn shrinks_and_counts_lookups() {
+		let mut cache = MemoryLruCache::new(1024);
+		cache.insert("hello", vec![0u8; 100]);
+		cache.insert("world", vec![0u8; 100]);
+
+		assert!(cache.get_mut(&"hello").is_some());
+		assert!(cache.get_mut(&"foo").is_none());
+		assert_eq!(cache.stats(), CacheStats { hits: 1, misses: 1 });
+		assert_eq!(cache.take_stats().hit_rate(), Some(0.5));
+		assert_eq!(cache.stats().hit_rate(), None);
+
+		// "world" is the least recently used item.
+		cache.set_max_size(200);
+		assert!(cache.get_mut(&"world").is_none());
+		assert!(cache.get_mut(&"hello").is_some());
+		assert_eq!(cache.current_size(), heap_size_of(&vec![0u8; 100]));
+	}
+
+	#[test]
+	fn peeking_does_not_refresh() {
+		let mut cache = MemoryLruCache::new(1024);
+		cache.insert("hello", vec![0u8; 100]);
+		cache.insert("world", vec![0u8; 100]);
+
+		assert!(cache.peek_mut(&"hello").is_some());
+		assert_eq!(cache.stats(), CacheStats::default());
+
+		// "hello" is still the least recently used item.
+		cache.set_max_size(200);
+		assert!(cache.peek_mut(&"hello").is_none());
+		assert!(cache.peek_mut(&"world").is_some());
+	}
+
+	#[test]
+	fn atomic_stats_are_reset_when_taken() {
+		let stats = AtomicCacheStats::default();
+		stats.note_hit();
+		stats.note_hit();
+		stats.note_miss();
+
+		assert_eq!(stats.take(), CacheStats { hits: 2, misses: 1 });
+		assert_eq!(stats.take(), CacheStats::default());
+	}
 }
@@ -0,0 +1,216 @@
+// Copyright 2015-2018 Parity Technologies (UK) Ltd.
+// This file is part of Parity.
+
+// Parity is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// Parity is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with Parity.  If not, see <http://www.gnu.org/licenses/>.
+
+//! Shedding of cache contents when the process runs short of memory.
+//!
+//! Caches register themselves with a `Registry`, usually the global one. A `Watchdog`
+//! polls the resident set size of the process and asks the registered caches to
+//! shed part of their contents once it exceeds a limit, until it drops below a lower mark.
+
+use std::fs::File;
+use std::hash::Hash;
+use std::io::{self, Read};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, Weak};
+use std::thread;
+use std::time::Duration;
+
+use heapsize::HeapSizeOf;
+use parking_lot::{Mutex, RwLock};
+
+use MemoryLruCache;
+
+/// A cache which can give up part of its contents.
+pub trait Shed: Send + Sync {
+	/// Drop the least recently used `percent`% of the contents. Returns the number of bytes freed.
+	fn shed(&self, percent: usize) -> usize;
+}
+
+impl<K, V> Shed for Mutex<MemoryLruCache<K, V>> where K: Eq + Hash + Send, V: HeapSizeOf + Send {
+	fn shed(&self, percent: usize) -> usize {
+		self.lock().shed(percent)
+	}
+}
+
+impl<K, V> Shed for RwLock<MemoryLruCache<K, V>> where K: Eq + Hash + Send + Sync, V: HeapSizeOf + Send + Sync {
+	fn shed(&self, percent: usize) -> usize {
+		self.write().shed(percent)
+	}
+}
+
+/// Caches to shed contents from. Caches are held weakly and forgotten once dropped.
+#[derive(Default)]
+pub struct Registry {
+	caches: Mutex<Vec<Weak<Shed>>>,
+}
+
+impl Registry {
+	/// Register a cache.
+	pub fn register(&self, cache: &Arc<Shed>) {
+		self.caches.lock().push(Arc::downgrade(cache));
+	}
+
+	/// Number of registered caches still alive.
+	pub fn len(&self) -> usize {
+		self.caches.lock().iter().filter(|cache| cache.upgrade().is_some()).count()
+	}
+
+	/// Ask every registered cache to drop `percent`% of its contents. Returns the number of bytes freed.
+	pub fn shed(&self, percent: usize) -> usize {
+		let mut caches = self.caches.lock();
+		let mut freed = 0;
+		caches.retain(|cache| match cache.upgrade() {
+			Some(cache) => {
+				freed += cache.shed(percent);
+				true
+			},
+			None => false,
+		});
+		freed
+	}
+}
+
+lazy_static! {
+	static ref GLOBAL: Registry = Registry::default();
+}
+
+/// The registry polled by a `Watchdog`.
+pub fn global() -> &'static Registry {
+	&*GLOBAL
+}
+
+/// Resident set size of the process in bytes, if it can be determined on this platform.
+pub fn resident_set_size() -> Option<usize> {
+	let mut status = String::new();
+	File::open("/proc/self/status").and_then(|mut f| f.read_to_string(&mut status)).ok()?;
+	status.lines()
+		.find(|line| line.starts_with("VmRSS:"))
+		.and_then(|line| line.split_whitespace().nth(1))
+		.and_then(|kb| kb.parse::<usize>().ok())
+		.map(|kb| kb * 1024)
+}
+
+// decides when to shed: from the moment the memory usage exceeds the high mark
+// until it drops below the low mark, so that the caches aren't refilled and shed
+// again while the usage hovers around the limit.
+struct Hysteresis {
+	high: usize,
+	low: usize,
+	shedding: bool,
+}
+
+impl Hysteresis {
+	fn new(high: usize, low: usize) -> Self {
+		Hysteresis {
+			high: high,
+			low: ::std::cmp::min(low, high),
+			shedding: false,
+		}
+	}
+
+	fn should_shed(&mut self, usage: usize) -> bool {
+		if usage > self.high {
+			self.shedding = true;
+		} else if usage < self.low {
+			self.shedding = false;
+		}
+		self.shedding
+	}
+}
+
+/// Background thread shedding the contents of the global registry's caches
+/// while the resident set size of the process is too high. Stops when dropped.
+pub struct Watchdog {
+	stop: Arc<AtomicBool>,
+}
+
+impl Watchdog {
+	/// Check the resident set size every `interval`. Once it exceeds `limit` bytes,
+	/// shed `percent`% of the caches on every check until it drops below `low_water` bytes.
+	pub fn start(limit: usize, low_water: usize, interval: Duration, percent: usize) -> io::Result<Watchdog> {
+		let stop = Arc::new(AtomicBool::new(false));
+		let thread_stop = stop.clone();
+		let mut hysteresis = Hysteresis::new(limit, low_water);
+		thread::Builder::new().name("memory-watchdog".into()).spawn(move || {
+			while !thread_stop.load(Ordering::SeqCst) {
+				match resident_set_size() {
+					Some(rss) if hysteresis.should_shed(rss) => {
+						let freed = global().shed(percent);
+						warn!(target: "memory", "Memory usage {} MB above {} MB, freed {} KB of caches",
+							rss / 1024 / 1024, low_water / 1024 / 1024, freed / 1024);
+					},
+					Some(_) => {},
+					None => {
+						warn!(target: "memory", "Memory usage is unavailable on this platform, stopping the watchdog");
+						return;
+					},
+				}
+				thread::sleep(interval);
+			}
+		})?;
+
+		Ok(Watchdog { stop })
+	}
+}
+
+impl Drop for Watchdog {
+	fn drop(&mut self) {
+		self.stop.store(true, Ordering::SeqCst);
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use std::sync::Arc;
+	use parking_lot::Mutex;
+	use MemoryLruCache;
+	use super::{Hysteresis, Registry, Shed};
+
+	#[test]
+	fn sheds_registered_caches() {
+		let registry = Registry::default();
+		let cache = Arc::new(Mutex::new(MemoryLruCache::new(4096)));
+		for i in 0..10u8 {
+			cache.lock().insert(i, vec![i; 100]);
+		}
+		let size = cache.lock().current_size();
+
+		registry.register(&(cache.clone() as Arc<Shed>));
+		assert_eq!(registry.len(), 1);
+
+		let freed = registry.shed(50);
+		assert_eq!(cache.lock().current_size(), size - freed);
+		assert!(cache.lock().current_size() <= size / 2);
+		// the most recently inserted items are kept.
+		assert!(cache.lock().get_mut(&9).is_some());
+		assert!(cache.lock().get_mut(&0).is_none());
+
+		drop(cache);
+		assert_eq!(registry.shed(50), 0);
+		assert_eq!(registry.len(), 0);
+	}
+
+	#[test]
+	fn sheds_until_below_low_water() {
+		let mut hysteresis = Hysteresis::new(100, 80);
+		assert!(!hysteresis.should_shed(90));
+		assert!(hysteresis.should_shed(101));
+		assert!(hysteresis.should_shed(90));
+		assert!(hysteresis.should_shed(80));
+		assert!(!hysteresis.should_shed(79));
+		assert!(!hysteresis.should_shed(90));
+	}
+}
@@ -29,7 +29,7 @@ use ethereum_types::H256;
 use keccak_hash::keccak;
 use memorydb::MemoryDB;
 use test::{Bencher, black_box};
-use trie::{TrieDBMut, TrieDB, TrieMut, Trie};
+use trie::{TrieDBMut, TrieDB, TrieMut, Trie, SecTrieDBMut};
 use trie_standardmap::{Alphabet, ValueMode, StandardMap};
 
 fn random_word(alphabet: &[u8], min_count: usize, diff_count: usize, seed: &mut H256) -> Vec<u8> {
@@ -126,6 +126,37 @@ fn trie_insertions_32_ran_1k(b: &mut Bencher) {
 	});
 }
 
+#[bench]
+fn sec_trie_insertions_into_existing_1k(b: &mut Bencher) {
+	let st = StandardMap {
+		alphabet: Alphabet::All,
+		min_key: 32,
+		journal_key: 0,
+		value_mode: ValueMode::Random,
+		count: 11000,
+	};
+	let mut d = st.make();
+	let inserted = d.split_off(10000);
+	let mut memdb = MemoryDB::new();
+	let mut root = H256::new();
+	{
+		let mut t = SecTrieDBMut::new(&mut memdb, &mut root);
+		for i in d.iter() {
+			t.insert(&i.0, &i.1).unwrap();
+		}
+	}
+
+	b.iter(&mut ||{
+		let mut memdb = memdb.clone();
+		let mut root = root.clone();
+		let mut t = SecTrieDBMut::from_existing(&mut memdb, &mut root).unwrap();
+		for i in inserted.iter() {
+			t.insert(&i.0, &i.1).unwrap();
+		}
+		black_box(t.root().clone());
+	});
+}
+
 #[bench]
 fn trie_insertions_six_high(b: &mut Bencher) {
 	let mut d: Vec<(Bytes, Bytes)> = Vec::new();
@@ -80,6 +80,14 @@ impl<T> UsingQueue<T> where T: Clone {
 		self.in_use.clear();
 	}
 
+	/// Keeps only the items, used or not, for which `predicate` returns `true`.
+	pub fn retain<P>(&mut self, predicate: P) where P: Fn(&T) -> bool {
+		if self.pending.as_ref().map_or(false, |x| !predicate(x)) {
+			self.pending = None;
+		}
+		self.in_use.retain(|x| predicate(x));
+	}
+
 	/// Returns `Some` item which is the first that `f` returns `true` with a reference to it
 	/// as a parameter or `None` if no such item exists in the queue.
 	fn take_used_if<P>(&mut self, predicate: P) -> Option<T> where P: Fn(&T) -> bool {
@@ -275,3 +283,17 @@ fn should_not_remove_used_popped() {
 	assert_eq!(q.pop_if(|i| i == &1), Some(1));
 	assert_eq!(q.pop_if(|i| i == &1), Some(1));
 }
+
+#[test]
+fn should_retain_matching_items() {
+	let mut q = UsingQueue::new(3);
+	q.push(1);
+	q.use_last_ref();
+	q.push(2);
+	q.use_last_ref();
+	q.push(3);
+	q.retain(|i| i != &1 && i != &3);
+	assert_eq!(q.get_used_if(GetAction::Clone, |i| i == &1), None);
+	assert_eq!(q.get_used_if(GetAction::Clone, |i| i == &2), Some(2));
+	assert_eq!(q.peek_last_ref(), Some(&2));
+}